@@ -0,0 +1,93 @@
+//! Benchmarks for reading and writing client configs with many servers
+//!
+//! This suite measures read/modify/write cycles against configs of 10 to 200
+//! servers:
+//! - Parsing a config from scratch
+//! - Listing servers, which reuses the parsed config while the file is unchanged
+//! - Re-adding a server with the same config, which doesn't rewrite the file
+//! - Changing a server's config, which does
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use mcp_helper::client::{ClaudeDesktopClient, CursorClient, McpClient, ServerConfig};
+use mcp_helper::utils::json_validator::parse_json_safe;
+use std::collections::HashMap;
+use std::hint::black_box;
+use tempfile::TempDir;
+
+const SERVER_COUNTS: [usize; 3] = [10, 50, 200];
+
+fn server(index: usize, version: &str) -> ServerConfig {
+    ServerConfig {
+        command: "npx".to_string(),
+        args: vec![
+            "--yes".to_string(),
+            format!("@example/server-{index}@{version}"),
+        ],
+        env: HashMap::from([
+            ("LOG_LEVEL".to_string(), "info".to_string()),
+            (format!("SERVER_{index}_TOKEN"), "${TOKEN}".to_string()),
+        ]),
+        cwd: None,
+    }
+}
+
+/// The installed clients to benchmark, each with `count` servers configured
+///
+/// Cursor's config is found through `HOME`, which is pointed at `temp_dir`.
+fn clients(temp_dir: &TempDir, count: usize) -> Vec<Box<dyn McpClient>> {
+    let home = temp_dir.path();
+    std::env::set_var("HOME", home);
+    std::fs::create_dir_all(home.join(".cursor")).unwrap();
+    let clients: Vec<Box<dyn McpClient>> = vec![
+        Box::new(ClaudeDesktopClient::with_config_path(
+            home.join("Claude").join("claude_desktop_config.json"),
+        )),
+        Box::new(CursorClient::new()),
+    ];
+    for client in &clients {
+        for index in 0..count {
+            client
+                .add_server(&format!("server-{index}"), server(index, "1.0.0"))
+                .unwrap();
+        }
+    }
+    clients
+}
+
+fn bench_config_io(c: &mut Criterion) {
+    let mut group = c.benchmark_group("config_io");
+
+    for count in SERVER_COUNTS {
+        let temp_dir = TempDir::new().unwrap();
+        for client in clients(&temp_dir, count) {
+            let id =
+                |operation: &str| BenchmarkId::new(format!("{}/{operation}", client.name()), count);
+
+            let content = std::fs::read_to_string(client.config_path()).unwrap();
+            group.bench_function(id("parse"), |b| {
+                b.iter(|| black_box(parse_json_safe(black_box(&content)).unwrap()));
+            });
+
+            group.bench_function(id("list_servers"), |b| {
+                b.iter(|| black_box(client.list_servers().unwrap()));
+            });
+
+            group.bench_function(id("add_unchanged"), |b| {
+                b.iter(|| client.add_server("server-0", server(0, "1.0.0")).unwrap());
+            });
+
+            let mut versions = ["1.0.1", "1.0.0"].iter().cycle();
+            group.bench_function(id("add_changed"), |b| {
+                b.iter(|| {
+                    let version = versions.next().unwrap();
+                    client.add_server("server-0", server(0, version)).unwrap()
+                });
+            });
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_config_io);
+criterion_main!(benches);
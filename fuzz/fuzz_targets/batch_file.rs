@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcp_helper::utils::batch_file::parse_batch_file;
+
+fuzz_target!(|content: &str| {
+    if let Err(e) = parse_batch_file("fuzz.txt", content) {
+        // Errors must point inside the input
+        let line = content.lines().nth(e.line - 1).unwrap_or_default();
+        assert!(e.column >= 1 && e.column <= line.chars().count() + 1);
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcp_helper::server::install_manifest::InstallManifest;
+use mcp_helper::server::ServerManifest;
+
+fuzz_target!(|content: &str| {
+    let _ = ServerManifest::from_json(content);
+    let _ = InstallManifest::from_json(content);
+});
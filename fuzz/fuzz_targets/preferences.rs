@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use mcp_helper::config::Preferences;
+use mcp_helper::utils::parse_error::parse_json;
+
+fuzz_target!(|content: &str| {
+    let _ = parse_json::<Preferences>("preferences.json", content);
+});
@@ -6,24 +6,214 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use dialoguer::{Confirm, Input, MultiSelect};
+use dialoguer::{Input, Password};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use crate::client::{detect_clients, McpClient, ServerConfig};
-use crate::deps::{DependencyChecker, NodeChecker};
+use crate::audit_log;
+use crate::cache::CacheManager;
+use crate::client::capabilities::{
+    literal_secrets, offer_input_prompts, place_secrets, prompt_for_secrets,
+};
+use crate::client::running::detect_default_selection;
+use crate::client::working_dir::normalize_cwd;
+use crate::client::{
+    clients_with_name_taken, detect_clients, ClientRegistry, ClientTarget, McpClient, ServerConfig,
+};
+use crate::config::env_overlap::find_env_overlaps;
+use crate::config::{
+    expand_server_config, HookContext, HookEvent, HookRunner, ManagedServers, MetaMembership,
+    MetaServerDefinition, Preferences,
+};
+use crate::core::validation::validate_instance_name;
+use crate::deps::{
+    DependencyCheck, DependencyCheckCache, DependencyStatus, JavaChecker, NodeChecker,
+};
 use crate::error::McpError;
-use crate::server::{detect_server_type, ServerType};
+use crate::install::auto_install_dependency;
+use crate::logging::progress::{self, step_percent, Progress};
+use crate::remote::RemoteServerConfig;
+use crate::security::{
+    SecurityFailureClass, SecurityValidation, SecurityValidator, SecurityViolation,
+};
+use crate::server::docker::{resolve_digest, with_digest};
+use crate::server::docker_requirements::{
+    apply_requirement, image_from_run_args, DockerRequirementInspector, RequirementHint,
+};
+use crate::server::folder_picker::{normalize_selection, FolderPicker};
+use crate::server::next_steps::{npm_examples, NextSteps};
+use crate::server::npm::npx_preference_flags;
+use crate::server::npm_registry::{scope_wildcard, search_scope};
+use crate::server::platform::enforce_platform;
+use crate::server::publisher::{check_publisher, verification_required, verify_npm_package};
+use crate::server::scope::resolve_directory;
+use crate::server::{
+    configured_directories, detect_server_type, is_filesystem_server, BinaryServer, CargoServer,
+    DenoServer, DotnetToolServer, FilesystemScope, JarServer, ManifestSource, McpServer,
+    ServerType,
+};
+use crate::test_server::{builtin_config, is_builtin};
+use crate::update::find_npm_package_spec;
+use crate::utils::headless::HeadlessEnvironment;
+use crate::utils::parse_error::parse_json;
+use crate::utils::prompt::{Confirm, MultiSelect};
+use crate::utils::secrets::is_secret_name;
 
 /// Add command for configuring MCP servers
 pub struct AddCommand {
     verbose: bool,
+    allowed_dirs: Vec<PathBuf>,
+    read_only: bool,
+    strict: bool,
+    /// Directory the server is started in
+    working_dir: Option<PathBuf>,
+    /// URL and `Name=Value` headers of a remote server
+    remote: Option<(String, Vec<String>)>,
+    /// Dependency checks shared by every server added in this execution
+    dependency_checks: DependencyCheckCache,
+    /// Write the client entry to this file instead of into installed clients
+    manifest_out: Option<PathBuf>,
+    /// Install every package a scope wildcard matches without asking
+    select_all: bool,
+    /// Clients chosen once for every server of a scope wildcard
+    shared_clients: Option<Vec<String>>,
+    /// Skip the usage examples and restart reminder after adding
+    quiet: bool,
+    /// Name to add the server under instead of its default one (`--as`)
+    instance_name: Option<String>,
+    /// Whether servers declaring other platforms are added anyway (`--ignore-platform`)
+    ignore_platform: bool,
+    /// Whether Docker images are pinned to the digest their tag points at (`--pin-digest`)
+    pin_digest: bool,
+    /// Clients given with `--client` or `--all-clients` instead of prompting
+    client_target: ClientTarget,
+    /// Install missing runtimes with the system package manager
+    auto_install_deps: bool,
+    /// Show what would be installed and written without changing anything
+    dry_run: bool,
+    /// Run dependency install commands without asking
+    yes: bool,
 }
 
 impl AddCommand {
     pub fn new(verbose: bool) -> Self {
-        Self { verbose }
+        Self {
+            verbose,
+            allowed_dirs: Vec::new(),
+            read_only: false,
+            strict: false,
+            working_dir: None,
+            remote: None,
+            dependency_checks: DependencyCheckCache::new(),
+            manifest_out: None,
+            select_all: false,
+            shared_clients: None,
+            quiet: false,
+            instance_name: None,
+            ignore_platform: false,
+            pin_digest: false,
+            client_target: ClientTarget::Prompt,
+            auto_install_deps: false,
+            dry_run: false,
+            yes: false,
+        }
+    }
+
+    /// Restrict filesystem servers to these directories instead of prompting
+    pub fn with_allowed_dirs(mut self, allowed_dirs: Vec<PathBuf>) -> Self {
+        self.allowed_dirs = allowed_dirs;
+        self
+    }
+
+    /// Give filesystem servers read-only access to their directories
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Treat every security warning as an error instead of prompting
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Start the server in this directory
+    pub fn with_working_dir(mut self, working_dir: Option<PathBuf>) -> Self {
+        self.working_dir = working_dir;
+        self
+    }
+
+    /// Add a remote (streamable HTTP) server at `url` instead of a local command
+    pub fn with_remote(mut self, url: Option<String>, headers: Vec<String>) -> Self {
+        self.remote = url.map(|url| (url, headers));
+        self
+    }
+
+    /// Write the server entry to `path` (in `mcpServers` format) instead of adding it
+    /// to clients, for containers and CI where no client runs
+    pub fn with_manifest_out(mut self, path: Option<PathBuf>) -> Self {
+        self.manifest_out = path;
+        self
+    }
+
+    /// With a scope wildcard (`@myorg/*`), take every matching package
+    pub fn with_select_all(mut self, select_all: bool) -> Self {
+        self.select_all = select_all;
+        self
+    }
+
+    /// Don't print usage examples and the restart reminder after adding
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Add the server as `name`, another instance next to any already configured
+    pub fn with_instance_name(mut self, name: Option<String>) -> Self {
+        self.instance_name = name;
+        self
+    }
+
+    /// Add servers that declare they don't support this OS or architecture, with a warning
+    pub fn with_ignore_platform(mut self, ignore_platform: bool) -> Self {
+        self.ignore_platform = ignore_platform;
+        self
+    }
+
+    /// Replace a Docker image's tag with the digest it points at now, so the
+    /// server keeps running the same image when the tag moves
+    pub fn with_pin_digest(mut self, pin_digest: bool) -> Self {
+        self.pin_digest = pin_digest;
+        self
+    }
+
+    /// Add to these clients instead of asking which ones
+    pub fn with_client_target(mut self, target: ClientTarget) -> Self {
+        self.client_target = target;
+        self
+    }
+
+    /// Install a missing Node.js, Java, Rust or .NET with the system package manager
+    /// instead of failing, prerequisites first and re-checked on a refreshed PATH
+    pub fn with_auto_install_deps(mut self, auto_install: bool) -> Self {
+        self.auto_install_deps = auto_install;
+        self
+    }
+
+    /// Show the dependencies, downloads and builds that would be installed and the
+    /// client configs that would be written, without installing or writing any of them
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Run dependency install commands without asking; each is still explained first
+    pub fn with_yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
     }
 
+    #[tracing::instrument(name = "add", skip(self, env), fields(env_vars = env.len()))]
     pub fn execute(
         &mut self,
         server: &str,
@@ -31,54 +221,188 @@ impl AddCommand {
         args: Vec<String>,
         env: HashMap<String, String>,
         non_interactive: bool,
+    ) -> Result<(), McpError> {
+        if let Some(name) = &self.instance_name {
+            validate_instance_name(name).map_err(|e| McpError::Other(anyhow::anyhow!(e)))?;
+        }
+        if let Some(scope) = scope_wildcard(server).filter(|_| command.is_none()) {
+            if self.instance_name.is_some() {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "--as names a single server; it can't be used with a scope wildcard"
+                )));
+            }
+            return self.execute_scope(scope, env, non_interactive);
+        }
+
+        let progress = Progress::new("install", server);
+        let result = self.add(server, command, args, env, non_interactive, &progress);
+        progress.finish(&result, "Added");
+        result
+    }
+
+    fn add(
+        &mut self,
+        server: &str,
+        command: Option<String>,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+        non_interactive: bool,
+        progress: &Progress,
     ) -> Result<(), McpError> {
         println!("{} Adding MCP server: {}", "→".green(), server.cyan());
         println!();
 
+        if let Some((url, headers)) = &self.remote {
+            return self.execute_remote(server, url, headers, non_interactive);
+        }
+
+        let cwd = self
+            .working_dir
+            .as_deref()
+            .map(|dir| normalize_cwd(&dir.to_string_lossy()))
+            .transpose()
+            .map_err(McpError::Other)?;
+
+        // Strict mode checks the source before touching any client configuration
+        progress.step("validate", 10, "Validating server source");
+        if command.is_none() && self.strict && !is_builtin(server) {
+            let validation = SecurityValidator::new()
+                .validate_server_spec(server)
+                .map_err(McpError::Other)?;
+            self.enforce_strict(server, &validation)?;
+        }
+
         // Detect installed clients
-        let clients = detect_clients();
-        let installed_clients: Vec<&dyn McpClient> = clients
-            .iter()
-            .filter(|c| c.is_installed())
-            .map(|c| c.as_ref())
-            .collect();
+        let registry = ClientRegistry {
+            clients: detect_clients(),
+        };
+        let installed_clients = registry.detect_installed();
 
-        if installed_clients.is_empty() {
-            return Err(McpError::Other(anyhow::anyhow!(
-                "No MCP clients found. Please install Claude Desktop, VS Code, or another supported client."
-            )));
+        if installed_clients.is_empty() && self.manifest_out.is_none() {
+            return Err(no_clients_error());
+        }
+
+        if command.is_none() {
+            if let Some(definition) = self.find_meta_server(server) {
+                if self.instance_name.is_some() {
+                    return Err(McpError::Other(anyhow::anyhow!(
+                        "--as does not support meta-servers; name their members in the definition"
+                    )));
+                }
+                if self.manifest_out.is_some() {
+                    return Err(McpError::Other(anyhow::anyhow!(
+                        "--manifest-out does not support meta-servers; add their members one by one"
+                    )));
+                }
+                return self.execute_meta(server, &definition, &registry, &env, non_interactive);
+            }
         }
 
-        // Try to detect server type if command not specified
-        let (final_command, final_args, server_name) = if let Some(cmd) = command {
-            // Manual configuration
-            let platform_cmd = self.get_platform_command(&cmd);
-            (platform_cmd, args, server.to_string())
+        let manifest_source = if command.is_none() {
+            ManifestSource::parse(server)
         } else {
-            self.detect_server_config(server, args)?
+            None
         };
 
-        // Check dependencies based on command type
-        self.check_dependencies(&final_command)?;
+        let (config, server_name) = if let Some(source) = manifest_source {
+            // Manifest-driven configuration: the manifest declares args and variables
+            let (mut config, server_name) =
+                self.configure_from_manifest(&source, &env, non_interactive)?;
+            config.command = self.get_platform_command(&config.command);
+            config.args.extend(args);
+            (config, server_name)
+        } else {
+            // Try to detect server type if command not specified
+            let (final_command, final_args, server_name) = if let Some(cmd) = command {
+                // Manual configuration
+                let platform_cmd = self.get_platform_command(&cmd);
+                (platform_cmd, args, server.to_string())
+            } else {
+                self.detect_server_config(server, args)?
+            };
+
+            // Build the configuration
+            let mut config = ServerConfig {
+                command: final_command,
+                args: final_args,
+                env: env.clone(),
+                cwd: None,
+            };
 
-        // Build the configuration
-        let mut config = ServerConfig {
-            command: final_command.clone(),
-            args: final_args.clone(),
-            env: env.clone(),
+            // Add any additional configuration if interactive
+            if !non_interactive {
+                config = self.configure_interactively(config)?;
+            }
+
+            (config, server_name)
         };
 
-        // Add any additional configuration if interactive
-        if !non_interactive {
-            config = self.configure_interactively(config)?;
+        let config = ServerConfig { cwd, ..config };
+        let default_name = server_name;
+        let server_name = self
+            .instance_name
+            .clone()
+            .unwrap_or_else(|| default_name.clone());
+
+        // A manifest may be mounted anywhere, so only local servers must run here
+        if let Some(spec) = find_npm_package_spec(&config).filter(|_| self.manifest_out.is_none()) {
+            let server_type = ServerType::Npm {
+                package: spec.package,
+                version: spec.version,
+            };
+            enforce_platform(&server_type, self.ignore_platform)
+                .map_err(|e| McpError::Other(e.into()))?;
+        }
+
+        // Registry signatures and provenance of the npm package the config runs
+        let publisher = match find_npm_package_spec(&config) {
+            Some(spec) => check_publisher(
+                &spec.package,
+                spec.version.as_deref(),
+                verification_required(),
+            )
+            .map_err(McpError::Other)?,
+            None => None,
+        };
+
+        // Narrow the directories filesystem servers can reach
+        let config = self.scope_filesystem_access(config, non_interactive)?;
+
+        // Docker servers may silently need host networking or the Docker socket
+        let config = self.check_docker_requirements(config, non_interactive)?;
+
+        let config = if self.pin_digest {
+            self.pin_image_digest(config)?
+        } else {
+            config
+        };
+
+        // The server runs wherever the manifest is mounted, so local dependencies don't matter
+        if let Some(path) = &self.manifest_out {
+            if self.dry_run {
+                println!(
+                    "{} Dry run: would write {} to {}",
+                    "ℹ".blue(),
+                    server_name.cyan(),
+                    path.display()
+                );
+                return Ok(());
+            }
+            return write_manifest_out(path, &server_name, &config);
         }
 
+        // Check dependencies based on command type
+        progress.step("dependencies", 40, "Checking dependencies");
+        self.check_dependencies(&config.command)?;
+
         // Select which clients to add to
-        let selected_clients = if non_interactive {
-            // Add to all clients in non-interactive mode
+        let selected_clients = if let Some(names) = &self.shared_clients {
             installed_clients
+                .into_iter()
+                .filter(|client| names.iter().any(|name| name == client.name()))
+                .collect()
         } else {
-            self.select_clients(&installed_clients)?
+            self.select_clients(&registry, non_interactive)?
         };
 
         if selected_clients.is_empty() {
@@ -86,8 +410,16 @@ impl AddCommand {
             return Ok(());
         }
 
+        self.check_name_available(&server_name, &config, &selected_clients)?;
+
         // Show preview
         self.show_preview(&server_name, &config, &selected_clients);
+        warn_env_overlaps(&server_name, &config, &selected_clients);
+
+        if self.dry_run {
+            show_dry_run(&[server_name.as_str()], &selected_clients);
+            return Ok(());
+        }
 
         // Confirm if interactive
         if !non_interactive {
@@ -105,12 +437,35 @@ impl AddCommand {
 
         // Add to selected clients
         let mut success_count = 0;
+        let mut added_to = Vec::new();
         let mut errors = Vec::new();
 
-        for client in selected_clients {
-            match client.add_server(&server_name, config.clone()) {
+        let mut hooks = HookRunner::from_preferences(!non_interactive, self.verbose);
+
+        let client_count = selected_clients.len();
+        for (index, client) in selected_clients.into_iter().enumerate() {
+            progress.step(
+                "client",
+                step_percent(70, 30, index, client_count),
+                format!("Adding to {}", client.name()),
+            );
+            let context = HookContext::new(&server_name, client.name());
+            if let Err(e) = hooks.run(HookEvent::PreInstall, &context) {
+                errors.push((client.name(), e));
+                continue;
+            }
+
+            let client_config = expand_server_config(
+                self.config_for_client(client, &server_name, &config, non_interactive),
+                &server_name,
+            );
+            let result = client.add_server(&server_name, client_config.clone());
+            let succeeded = result.is_ok();
+            match result {
                 Ok(_) => {
                     success_count += 1;
+                    added_to.push(client.name().to_string());
+                    self.record_managed(&server_name, &client_config, client.name());
                     if self.verbose {
                         println!("  {} Added to {}", "✓".green(), client.name().cyan());
                     }
@@ -119,6 +474,24 @@ impl AddCommand {
                     errors.push((client.name(), e));
                 }
             }
+
+            self.run_post_install_hooks(&mut hooks, context.with_success(succeeded));
+        }
+
+        if let Some(publisher) = publisher.filter(|_| success_count > 0) {
+            let recorded = ManagedServers::new()
+                .and_then(|managed| managed.set_publisher(&server_name, publisher));
+            if let Err(e) = recorded {
+                tracing::warn!("Failed to record publisher of {}: {}", server_name, e);
+            }
+        }
+
+        if success_count > 0 && server_name != default_name {
+            let recorded = ManagedServers::new()
+                .and_then(|managed| managed.set_instance_of(&server_name, &default_name));
+            if let Err(e) = recorded {
+                tracing::warn!("Failed to record {} as an instance: {}", server_name, e);
+            }
         }
 
         // Report results
@@ -132,147 +505,1309 @@ impl AddCommand {
             );
         }
 
-        if !errors.is_empty() {
-            println!();
-            println!("{} Failed to add to some clients:", "⚠".yellow());
-            for (client, error) in errors {
-                println!("  • {}: {}", client, error.to_string().dimmed());
+        if !errors.is_empty() {
+            println!();
+            println!("{} Failed to add to some clients:", "⚠".yellow());
+            for (client, error) in errors {
+                println!("  • {}: {}", client, error.to_string().dimmed());
+            }
+        }
+
+        if !added_to.is_empty() {
+            self.show_next_steps(&server_name, &config, added_to, progress);
+        }
+
+        Ok(())
+    }
+
+    /// Refuse to replace another server under an instance name; without `--as`,
+    /// point out that an existing entry of the same name gets replaced
+    fn check_name_available(
+        &self,
+        server_name: &str,
+        config: &ServerConfig,
+        clients: &[&dyn McpClient],
+    ) -> Result<(), McpError> {
+        if self.instance_name.is_some() {
+            let taken = clients_with_name_taken(clients.iter().copied(), server_name, config);
+            if !taken.is_empty() {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "'{}' is already a different server in {}; choose another name with --as",
+                    server_name,
+                    taken.join(", ")
+                )));
+            }
+            return Ok(());
+        }
+
+        for client in clients {
+            let existing = client
+                .list_servers()
+                .ok()
+                .and_then(|mut servers| servers.remove(server_name));
+            if existing.is_some_and(|existing| existing != *config) {
+                println!(
+                    "{} {} already has '{}'; it will be replaced. Use --as <NAME> to add another instance",
+                    "ℹ".blue(),
+                    client.name(),
+                    server_name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Print the clients to restart and usage examples, and send them to the
+    /// progress stream
+    fn show_next_steps(
+        &self,
+        server_name: &str,
+        config: &ServerConfig,
+        restart: Vec<String>,
+        progress: &Progress,
+    ) {
+        if self.quiet && !progress::is_enabled() {
+            return;
+        }
+        let examples = find_npm_package_spec(config)
+            .map(|spec| npm_examples(&spec.package, spec.version.as_deref()))
+            .unwrap_or_default();
+        let next_steps = NextSteps::new(server_name, restart, examples);
+        progress.next_steps(&next_steps);
+        if !self.quiet {
+            println!();
+            print!("{next_steps}");
+        }
+    }
+
+    /// Add the packages of an npm scope that carry an `mcp` keyword, choosing
+    /// clients once for all of them
+    fn execute_scope(
+        &mut self,
+        scope: &str,
+        env: HashMap<String, String>,
+        non_interactive: bool,
+    ) -> Result<(), McpError> {
+        println!(
+            "{} Searching npm for MCP servers in {}",
+            "→".green(),
+            scope.cyan()
+        );
+        let packages = search_scope(scope).map_err(McpError::Other)?;
+        if packages.is_empty() {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "No packages in {} carry an 'mcp' keyword",
+                scope
+            )));
+        }
+
+        let packages: Vec<String> = if self.select_all || non_interactive {
+            packages.into_iter().map(|package| package.name).collect()
+        } else {
+            // Checked in parallel; a package whose lookup fails just gets no badge
+            let badges: Vec<String> = std::thread::scope(|scope| {
+                let handles: Vec<_> = packages
+                    .iter()
+                    .map(|package| {
+                        scope.spawn(move || {
+                            let version = Some(package.version.as_str()).filter(|v| !v.is_empty());
+                            verify_npm_package(&package.name, version)
+                                .map(|verification| format!(" {}", verification.badge()))
+                                .unwrap_or_default()
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|handle| handle.join().unwrap_or_default())
+                    .collect()
+            });
+            let items: Vec<String> = packages
+                .iter()
+                .zip(&badges)
+                .map(|(package, badge)| match &package.description {
+                    Some(description) => {
+                        format!("{}{badge}  {}", package.name, description.dimmed())
+                    }
+                    None => format!("{}{badge}", package.name),
+                })
+                .collect();
+            let defaults = vec![true; items.len()];
+            MultiSelect::new()
+                .with_prompt(format!("Select servers from {scope} to add"))
+                .items(&items)
+                .defaults(&defaults)
+                .interact()
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Selection failed: {}", e)))?
+                .into_iter()
+                .map(|i| packages[i].name.clone())
+                .collect()
+        };
+        if packages.is_empty() {
+            println!("{} No servers selected", "❌".red());
+            return Ok(());
+        }
+
+        if self.manifest_out.is_none() {
+            let registry = ClientRegistry {
+                clients: detect_clients(),
+            };
+            let installed_clients = registry.detect_installed();
+            if installed_clients.is_empty() {
+                return Err(no_clients_error());
+            }
+            let selected = self.select_clients(&registry, non_interactive)?;
+            if selected.is_empty() {
+                println!("{} No clients selected", "❌".red());
+                return Ok(());
+            }
+            self.shared_clients = Some(selected.iter().map(|c| c.name().to_string()).collect());
+        }
+
+        let mut failures = Vec::new();
+        for package in &packages {
+            println!();
+            if let Err(e) = self.execute(package, None, Vec::new(), env.clone(), non_interactive) {
+                println!("{} {}: {}", "❌".red(), package, e);
+                failures.push(package.as_str());
+            }
+        }
+        self.shared_clients = None;
+
+        println!();
+        println!(
+            "{} Added {} of {} server(s) from {}",
+            if failures.is_empty() {
+                "✅".green()
+            } else {
+                "⚠".yellow()
+            },
+            packages.len() - failures.len(),
+            packages.len(),
+            scope.cyan()
+        );
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(McpError::Other(anyhow::anyhow!(
+                "Failed to add {}",
+                failures.join(", ")
+            )))
+        }
+    }
+
+    /// Add a remote server, natively where the client supports it and through
+    /// `mcp bridge` elsewhere
+    fn execute_remote(
+        &self,
+        server_name: &str,
+        url: &str,
+        headers: &[String],
+        non_interactive: bool,
+    ) -> Result<(), McpError> {
+        let remote = RemoteServerConfig::new(url)
+            .and_then(|remote| remote.with_header_specs(headers))
+            .map_err(McpError::Other)?;
+
+        let literal_secrets = remote.literal_secret_headers();
+        if !literal_secrets.is_empty() {
+            let message = format!(
+                "Header(s) {} look like credentials and would be stored in plain text; use ${{keychain:NAME}} to keep them in the OS keychain",
+                literal_secrets.join(", ")
+            );
+            if self.strict {
+                return Err(McpError::Other(anyhow::anyhow!(message)));
+            }
+            println!("{} {}", "⚠".yellow(), message);
+        }
+
+        let registry = ClientRegistry {
+            clients: detect_clients(),
+        };
+        let installed_clients = registry.detect_installed();
+
+        if installed_clients.is_empty() {
+            return Err(no_clients_error());
+        }
+
+        let selected_clients = self.select_clients(&registry, non_interactive)?;
+
+        if selected_clients.is_empty() {
+            println!("{} No clients selected", "❌".red());
+            return Ok(());
+        }
+
+        self.show_remote_preview(server_name, &remote, &selected_clients);
+
+        if self.dry_run {
+            show_dry_run(&[server_name], &selected_clients);
+            return Ok(());
+        }
+
+        if !non_interactive {
+            let confirm = Confirm::new()
+                .with_prompt("Add this server configuration?")
+                .default(true)
+                .interact()
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Confirmation failed: {}", e)))?;
+
+            if !confirm {
+                println!("{} Configuration cancelled", "❌".red());
+                return Ok(());
+            }
+        }
+
+        let mut success_count = 0;
+        let mut errors = Vec::new();
+        let mut hooks = HookRunner::from_preferences(!non_interactive, self.verbose);
+
+        for client in selected_clients {
+            let context = HookContext::new(server_name, client.name());
+            if let Err(e) = hooks.run(HookEvent::PreInstall, &context) {
+                errors.push((client.name(), e));
+                continue;
+            }
+
+            let result = client.add_remote_server(server_name, &remote);
+            let succeeded = result.is_ok();
+            match result {
+                Ok(_) => {
+                    success_count += 1;
+                    if self.verbose {
+                        println!("  {} Added to {}", "✓".green(), client.name().cyan());
+                    }
+                }
+                Err(e) => errors.push((client.name(), e)),
+            }
+
+            self.run_post_install_hooks(&mut hooks, context.with_success(succeeded));
+        }
+
+        println!();
+        if success_count > 0 {
+            println!(
+                "{} Remote server '{}' added to {} client(s)",
+                "✅".green(),
+                server_name.cyan(),
+                success_count
+            );
+        }
+
+        if !errors.is_empty() {
+            println!();
+            println!("{} Failed to add to some clients:", "⚠".yellow());
+            for (client, error) in errors {
+                println!("  • {}: {}", client, error.to_string().dimmed());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Track the server as managed; a failure here never undoes the install
+    /// Place secrets in `config` the way `client` handles them best
+    fn config_for_client(
+        &self,
+        client: &dyn McpClient,
+        server_name: &str,
+        config: &ServerConfig,
+        non_interactive: bool,
+    ) -> ServerConfig {
+        let capabilities = client.capabilities();
+        let (mut placed, mut placements) = place_secrets(config.clone(), capabilities, &|name| {
+            std::env::var(name).ok()
+        });
+
+        if capabilities.input_prompts && !non_interactive {
+            let secrets = literal_secrets(&placed);
+            if !secrets.is_empty() && offer_input_prompts(client.name(), &secrets) {
+                let (prompted, input_placements) = prompt_for_secrets(placed, server_name);
+                placed = prompted;
+                placements.extend(input_placements);
+            }
+        }
+
+        for (name, placement) in placements {
+            if let Some(note) = placement.describe(&name) {
+                println!("  {} {}: {}", "ℹ".blue(), client.name(), note);
+            }
+        }
+        placed
+    }
+
+    fn record_managed(&self, server_name: &str, config: &ServerConfig, client: &str) {
+        let recorded = ManagedServers::new()
+            .and_then(|managed| managed.record_added(server_name, config, client));
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record managed server {}: {}", server_name, e);
+        }
+    }
+
+    /// Run `post_install` hooks; their failures never undo the install
+    fn run_post_install_hooks(&self, hooks: &mut HookRunner, context: HookContext) {
+        if let Err(e) = hooks.run(HookEvent::PostInstall, &context) {
+            println!("  {} post_install hooks: {}", "⚠".yellow(), e);
+        }
+    }
+
+    fn find_meta_server(&self, name: &str) -> Option<MetaServerDefinition> {
+        match Preferences::load() {
+            Ok(prefs) => prefs.meta_server(name).cloned(),
+            Err(e) => {
+                if self.verbose {
+                    println!("{} Could not read preferences: {}", "⚠".yellow(), e);
+                }
+                None
+            }
+        }
+    }
+
+    /// Install every member of a meta-server with a single prompt flow.
+    fn execute_meta(
+        &self,
+        meta_name: &str,
+        definition: &MetaServerDefinition,
+        registry: &ClientRegistry,
+        provided_env: &HashMap<String, String>,
+        non_interactive: bool,
+    ) -> Result<(), McpError> {
+        println!(
+            "{} {} is a meta-server with {} member(s)",
+            "ℹ".blue(),
+            meta_name.cyan(),
+            definition.members.len()
+        );
+        if let Some(description) = &definition.description {
+            println!("  {}", description.dimmed());
+        }
+
+        if definition.members.is_empty() {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Meta-server '{}' does not define any members",
+                meta_name
+            )));
+        }
+
+        // Ask once for every variable left blank, even if several members share it
+        let mut shared = HashMap::new();
+        for key in definition.prompted_variables() {
+            if let Some(value) = provided_env.get(&key) {
+                shared.insert(key, value.clone());
+                continue;
+            }
+
+            if non_interactive {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Missing required variable {} (pass it with --env {}=<value>)",
+                    key,
+                    key
+                )));
+            }
+
+            let value: String = if is_secret_name(&key) {
+                Password::new().with_prompt(&key).interact()
+            } else {
+                Input::new().with_prompt(&key).interact()
+            }
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))?;
+            shared.insert(key, value);
+        }
+
+        let mut members: Vec<(String, ServerConfig)> = Vec::new();
+        for member in &definition.members {
+            let (command, args, default_name) = match &member.command {
+                Some(command) => (
+                    self.get_platform_command(command),
+                    member.args.clone(),
+                    member.server.clone(),
+                ),
+                None => self.detect_server_config(&member.server, member.args.clone())?,
+            };
+
+            let env = member
+                .env
+                .iter()
+                .map(|(key, value)| {
+                    let value = if value.is_empty() {
+                        shared.get(key).cloned().unwrap_or_default()
+                    } else {
+                        value.clone()
+                    };
+                    (key.clone(), value)
+                })
+                .filter(|(_, value)| !value.is_empty())
+                .collect();
+
+            let config = ServerConfig {
+                command,
+                args,
+                env,
+                cwd: None,
+            };
+            self.check_dependencies(&config.command)?;
+
+            let name = member.name.clone().unwrap_or(default_name);
+            if members.iter().any(|(existing, _)| *existing == name) {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Meta-server '{}' defines '{}' more than once; give members distinct names",
+                    meta_name,
+                    name
+                )));
+            }
+            members.push((name, config));
+        }
+
+        let selected_clients = self.select_clients(registry, non_interactive)?;
+
+        if selected_clients.is_empty() {
+            println!("{} No clients selected", "❌".red());
+            return Ok(());
+        }
+
+        println!();
+        println!("{}", "Meta-server preview:".blue());
+        println!("  Meta-server: {}", meta_name.cyan());
+        for (name, config) in &members {
+            println!(
+                "  • {}: {} {}",
+                name.cyan(),
+                config.command.green(),
+                config.args.join(" ").dimmed()
+            );
+            if !config.env.is_empty() {
+                let mut keys: Vec<_> = config.env.keys().map(String::as_str).collect();
+                keys.sort_unstable();
+                println!("      env: {}", keys.join(", "));
+            }
+        }
+        println!(
+            "  Clients: {}",
+            selected_clients
+                .iter()
+                .map(|c| c.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+                .yellow()
+        );
+        println!();
+
+        if self.dry_run {
+            let names: Vec<&str> = members.iter().map(|(name, _)| name.as_str()).collect();
+            show_dry_run(&names, &selected_clients);
+            return Ok(());
+        }
+
+        if !non_interactive {
+            let confirm = Confirm::new()
+                .with_prompt(format!("Add {} servers from {}?", members.len(), meta_name))
+                .default(true)
+                .interact()
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Confirmation failed: {}", e)))?;
+
+            if !confirm {
+                println!("{} Configuration cancelled", "❌".red());
+                return Ok(());
+            }
+        }
+
+        let mut added: Vec<String> = Vec::new();
+        let mut clients_used: Vec<String> = Vec::new();
+        let mut errors = Vec::new();
+
+        let mut hooks = HookRunner::from_preferences(!non_interactive, self.verbose);
+
+        for client in &selected_clients {
+            for (name, config) in &members {
+                let context = HookContext::new(name, client.name());
+                if let Err(e) = hooks.run(HookEvent::PreInstall, &context) {
+                    errors.push((client.name(), name.clone(), e));
+                    continue;
+                }
+
+                let result = client.add_server(
+                    name,
+                    expand_server_config(
+                        self.config_for_client(*client, name, config, non_interactive),
+                        name,
+                    ),
+                );
+                let succeeded = result.is_ok();
+                match result {
+                    Ok(_) => {
+                        if !added.contains(name) {
+                            added.push(name.clone());
+                        }
+                        if !clients_used.iter().any(|c| c == client.name()) {
+                            clients_used.push(client.name().to_string());
+                        }
+                        if self.verbose {
+                            println!(
+                                "  {} Added {} to {}",
+                                "✓".green(),
+                                name,
+                                client.name().cyan()
+                            );
+                        }
+                    }
+                    Err(e) => errors.push((client.name(), name.clone(), e)),
+                }
+
+                self.run_post_install_hooks(&mut hooks, context.with_success(succeeded));
+            }
+        }
+
+        if !added.is_empty() {
+            let recorded =
+                MetaMembership::new().and_then(|m| m.record(meta_name, &added, &clients_used));
+            if let Err(e) = recorded {
+                println!(
+                    "{} Could not record meta-server membership: {}",
+                    "⚠".yellow(),
+                    e
+                );
+            }
+        }
+
+        println!();
+        if !added.is_empty() {
+            println!(
+                "{} Meta-server '{}' added {} server(s) to {} client(s)",
+                "✅".green(),
+                meta_name.cyan(),
+                added.len(),
+                clients_used.len()
+            );
+            println!("  Remove them all with: mcp remove {meta_name}");
+        }
+
+        if !errors.is_empty() {
+            println!();
+            println!("{} Failed to add some servers:", "⚠".yellow());
+            for (client, name, error) in errors {
+                println!("  • {} ({}): {}", name, client, error.to_string().dimmed());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn detect_server_config(
+        &self,
+        server: &str,
+        mut args: Vec<String>,
+    ) -> Result<(String, Vec<String>, String), McpError> {
+        if is_builtin(server) {
+            let (command, mut builtin_args, name) = builtin_config(server)?;
+            builtin_args.extend(args);
+            return Ok((command, builtin_args, name));
+        }
+
+        // Try to detect server type
+        let server_type = detect_server_type(server);
+
+        if self.verbose {
+            println!("Detected server type: {server_type:?}");
+        }
+
+        match server_type {
+            ServerType::Npm { package, version } => {
+                // For NPM packages, use npx (or npx.cmd on Windows)
+                let command = self.get_platform_command("npx");
+
+                // Build args: package name with version if specified
+                let package_arg = if let Some(v) = version {
+                    format!("{package}@{v}")
+                } else {
+                    package.clone()
+                };
+
+                // If no args provided, just use the package
+                if args.is_empty() {
+                    args = vec![package_arg];
+                } else {
+                    // Prepend package to existing args
+                    args.insert(0, package_arg);
+                }
+
+                // npm options from preferences go before the package
+                args.splice(0..0, npx_preference_flags());
+
+                // Extract clean server name for configuration
+                let server_name = package
+                    .split('/')
+                    .next_back()
+                    .unwrap_or(&package)
+                    .to_string();
+
+                Ok((command, args, server_name))
+            }
+            ServerType::Binary { url, checksum } => {
+                if self.strict && checksum.is_none() {
+                    return Err(McpError::Other(anyhow::Error::new(SecurityViolation::new(
+                        SecurityFailureClass::UnsignedBinary,
+                        server,
+                        "Binary downloads must have a checksum in strict mode",
+                    ))));
+                }
+
+                // Download into the managed bin directory and run the installed binary
+                let mut binary_server =
+                    BinaryServer::from_url(&url, checksum).map_err(McpError::Other)?;
+                let server_name = binary_server.metadata().name.clone();
+                if self.dry_run {
+                    let target = binary_server.install_target().map_err(McpError::Other)?;
+                    println!(
+                        "{} Would download {} to {}",
+                        "ℹ".blue(),
+                        url,
+                        target.display()
+                    );
+                    return Ok((target.to_string_lossy().to_string(), args, server_name));
+                }
+
+                let cache_manager = CacheManager::new().unwrap_or_default();
+                binary_server
+                    .download_and_install(Some(&cache_manager))
+                    .map_err(McpError::Other)?;
+
+                let (command, _) = binary_server.generate_command().map_err(McpError::Other)?;
+                Ok((command, args, server_name))
+            }
+            ServerType::Docker { image, tag } => {
+                // For Docker images, use docker run
+                let command = "docker".to_string();
+                let mut docker_args = vec!["run".to_string(), "--rm".to_string(), "-i".to_string()];
+
+                let full_image = if let Some(t) = tag {
+                    format!("{image}:{t}")
+                } else {
+                    image.clone()
+                };
+
+                docker_args.push(full_image);
+                docker_args.extend(args);
+
+                let server_name = image.split('/').next_back().unwrap_or(&image).to_string();
+
+                Ok((command, docker_args, server_name))
+            }
+            ServerType::Python { package, version } => {
+                // For Python packages, use python -m
+                let command = "python".to_string();
+                let mut python_args = vec!["-m".to_string()];
+
+                // Add package with version if specified
+                if let Some(v) = version {
+                    python_args.push(format!("{package}=={v}"));
+                } else {
+                    python_args.push(package.clone());
+                }
+
+                python_args.extend(args);
+
+                let server_name = package
+                    .split('/')
+                    .next_back()
+                    .unwrap_or(&package)
+                    .to_string();
+
+                Ok((command, python_args, server_name))
+            }
+            ServerType::Cargo {
+                crate_name,
+                version,
+            } => {
+                // For Rust crates, build into the managed bin directory and run the binary
+                let cargo_server = CargoServer::from_crate(crate_name.clone(), version);
+                let binary_path = cargo_server.binary_path().map_err(McpError::Other)?;
+
+                if !binary_path.exists() {
+                    let check = cargo_server.dependency().check().map_err(McpError::Other)?;
+                    if let DependencyStatus::Missing = check.status {
+                        self.require_dependency(
+                            &check,
+                            "Rust (cargo) is required for crate-based servers. Please install from https://rustup.rs",
+                        )?;
+                    }
+                    if self.dry_run {
+                        let install_args = cargo_server.install_args().map_err(McpError::Other)?;
+                        println!("{} Would run: cargo {}", "ℹ".blue(), install_args.join(" "));
+                    } else {
+                        cargo_server.install().map_err(McpError::Other)?;
+                    }
+                }
+
+                let (command, _) = cargo_server.generate_command().map_err(McpError::Other)?;
+                Ok((command, args, crate_name))
+            }
+            ServerType::Dotnet { package, version } => {
+                // .NET tools are installed into the managed tool path and run directly
+                let dotnet_server =
+                    DotnetToolServer::from_package(package.clone(), version.clone());
+                let installed = dotnet_server.installed().ok().flatten();
+                let outdated = matches!(
+                    (&installed, &version),
+                    (Some(tool), Some(version)) if &tool.version != version
+                );
+
+                if installed.is_none() {
+                    let check = dotnet_server
+                        .dependency()
+                        .check()
+                        .map_err(McpError::Other)?;
+                    if !matches!(check.status, DependencyStatus::Installed { .. }) {
+                        self.require_dependency(
+                            &check,
+                            "The .NET SDK is required for dotnet tool servers. Please install from https://dotnet.microsoft.com/download",
+                        )?;
+                    }
+                    if self.dry_run {
+                        let install_args = dotnet_server.install_args().map_err(McpError::Other)?;
+                        println!(
+                            "{} Would run: dotnet {}",
+                            "ℹ".blue(),
+                            install_args.join(" ")
+                        );
+                        // The tool's command is only known once it is installed
+                        let tool_path = dotnet_server.tool_path().map_err(McpError::Other)?;
+                        let command = tool_path.join(&package).to_string_lossy().to_string();
+                        return Ok((command, args, package));
+                    }
+                    dotnet_server.install().map_err(McpError::Other)?;
+                } else if outdated {
+                    if self.dry_run {
+                        let update_args = dotnet_server.update_args().map_err(McpError::Other)?;
+                        println!("{} Would run: dotnet {}", "ℹ".blue(), update_args.join(" "));
+                    } else {
+                        dotnet_server.update().map_err(McpError::Other)?;
+                    }
+                }
+
+                let (command, _) = dotnet_server.generate_command().map_err(McpError::Other)?;
+                let server_name = Path::new(&command)
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or(package);
+                Ok((command, args, server_name))
+            }
+            ServerType::Deno { specifier } => {
+                // Permission flags belong to `deno run`, everything else to the server
+                let (permissions, server_args): (Vec<String>, Vec<String>) = args
+                    .into_iter()
+                    .partition(|arg| DenoServer::is_permission_flag(arg));
+
+                let validation = SecurityValidator::new().validate_deno_permissions(&permissions);
+                self.enforce_strict(server, &validation)?;
+                self.print_scope_warnings(&validation);
+
+                let mut deno_args = vec!["run".to_string(), "--no-prompt".to_string()];
+                deno_args.extend(permissions);
+                deno_args.push(specifier.clone());
+                deno_args.extend(server_args);
+
+                Ok((
+                    "deno".to_string(),
+                    deno_args,
+                    DenoServer::server_name(&specifier),
+                ))
+            }
+            ServerType::Jar { path } => {
+                // JVM options go before `-jar`, everything else to the server
+                let jar = JarServer::resolve(&path).map_err(McpError::Other)?;
+                let (mut java_args, server_args): (Vec<String>, Vec<String>) = args
+                    .into_iter()
+                    .partition(|arg| JarServer::is_jvm_option(arg));
+                java_args.push("-jar".to_string());
+                java_args.push(jar.display().to_string());
+                java_args.extend(server_args);
+
+                Ok(("java".to_string(), java_args, JarServer::server_name(&path)))
+            }
+        }
+    }
+
+    fn get_platform_command(&self, command: &str) -> String {
+        // Handle platform-specific command variations
+        if command == "npx" && cfg!(target_os = "windows") {
+            // On Windows, prefer npx.cmd if available
+            if self.dependency_checks.resolve_command("npx.cmd").is_some() {
+                "npx.cmd".to_string()
+            } else {
+                "npx".to_string()
+            }
+        } else {
+            command.to_string()
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn check_dependencies(&self, command: &str) -> Result<(), McpError> {
+        // Check dependencies based on command type
+        if command == "npx" || command == "npx.cmd" || command == "npm" {
+            let checker = NodeChecker::new();
+            match self.dependency_checks.check(&checker) {
+                Ok(check) => {
+                    if let DependencyStatus::Missing = check.status {
+                        self.require_dependency(
+                            &check,
+                            "Node.js is required for NPM-based servers. Please install from https://nodejs.org",
+                        )?;
+                    }
+                }
+                Err(e) => {
+                    if self.verbose {
+                        println!("{} Warning: Could not verify Node.js: {}", "⚠".yellow(), e);
+                    }
+                }
+            }
+        }
+
+        if command == "java" {
+            match self.dependency_checks.check(&JavaChecker::new()) {
+                Ok(check) => {
+                    if let DependencyStatus::Missing = check.status {
+                        self.require_dependency(
+                            &check,
+                            "Java is required for JAR-based servers. Please install a runtime such as Temurin from https://adoptium.net",
+                        )?;
+                    }
+                }
+                Err(e) => {
+                    if self.verbose {
+                        println!("{} Warning: Could not verify Java: {}", "⚠".yellow(), e);
+                    }
+                }
+            }
+        }
+
+        // Docker, Python, etc. checks could be added here
+
+        Ok(())
+    }
+
+    /// `check` found a dependency missing: install it with `--auto-install-deps`, or
+    /// fail with `message`
+    fn require_dependency(&self, check: &DependencyCheck, message: &str) -> Result<(), McpError> {
+        let installable = matches!(
+            check.status,
+            DependencyStatus::Missing | DependencyStatus::VersionMismatch { .. }
+        );
+        if self.auto_install_deps
+            && installable
+            && auto_install_dependency(check, self.dry_run, self.yes)?
+        {
+            return Ok(());
+        }
+        Err(McpError::Other(anyhow::anyhow!("{message}")))
+    }
+
+    fn configure_from_manifest(
+        &self,
+        source: &ManifestSource,
+        provided_env: &HashMap<String, String>,
+        non_interactive: bool,
+    ) -> Result<(ServerConfig, String), McpError> {
+        let manifest = source.load().map_err(McpError::Other)?;
+
+        println!(
+            "{} Loaded manifest for {}{}",
+            "ℹ".blue(),
+            manifest.name.cyan(),
+            manifest
+                .version
+                .as_ref()
+                .map(|v| format!(" v{v}"))
+                .unwrap_or_default()
+        );
+        if let Some(description) = &manifest.description {
+            println!("  {}", description.dimmed());
+        }
+
+        let package = manifest.select_package().ok_or_else(|| {
+            let available = manifest
+                .packages
+                .iter()
+                .map(|p| format!("{} ({})", p.identifier, p.registry_type))
+                .collect::<Vec<_>>()
+                .join(", ");
+            McpError::Other(anyhow::anyhow!(
+                "No installable package in the manifest can run on this machine. Available: {}",
+                if available.is_empty() {
+                    "none".to_string()
+                } else {
+                    available
+                }
+            ))
+        })?;
+
+        if self.verbose {
+            println!(
+                "  Using {} package {}",
+                package.registry_type,
+                package.identifier.cyan()
+            );
+        }
+
+        let mut values = provided_env.clone();
+        for variable in &package.environment_variables {
+            if values.contains_key(&variable.name) {
+                continue;
+            }
+
+            if non_interactive {
+                match &variable.default {
+                    Some(default) => {
+                        values.insert(variable.name.clone(), default.clone());
+                    }
+                    None if variable.is_required => {
+                        return Err(McpError::Other(anyhow::anyhow!(
+                            "Missing required variable {} (pass it with --env {}=<value>)",
+                            variable.name,
+                            variable.name
+                        )));
+                    }
+                    None => {}
+                }
+                continue;
+            }
+
+            let prompt = match &variable.description {
+                Some(description) => format!("{} ({})", variable.name, description),
+                None => variable.name.clone(),
+            };
+
+            let value: String = if variable.is_secret {
+                Password::new()
+                    .with_prompt(prompt)
+                    .allow_empty_password(!variable.is_required)
+                    .interact()
+            } else {
+                let mut input = Input::new()
+                    .with_prompt(prompt)
+                    .allow_empty(!variable.is_required);
+                if let Some(default) = &variable.default {
+                    input = input.default(default.clone());
+                }
+                input.interact()
+            }
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))?;
+
+            if !value.is_empty() {
+                values.insert(variable.name.clone(), value);
+            }
+        }
+
+        let mut config = package.to_server_config(&values).map_err(McpError::Other)?;
+
+        // Keep any extra --env values the manifest doesn't declare
+        for (key, value) in provided_env {
+            config
+                .env
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+
+        Ok((config, manifest.short_name().to_string()))
+    }
+
+    fn scope_filesystem_access(
+        &self,
+        config: ServerConfig,
+        non_interactive: bool,
+    ) -> Result<ServerConfig, McpError> {
+        if !is_filesystem_server(&config) {
+            if !self.allowed_dirs.is_empty() || self.read_only {
+                println!(
+                    "{} --allow-dir and --read-only only apply to filesystem servers; ignoring",
+                    "⚠".yellow()
+                );
+            }
+            return Ok(config);
+        }
+
+        let validator = SecurityValidator::new();
+        let mut scope = FilesystemScope::new().with_read_only(self.read_only);
+
+        for dir in &self.allowed_dirs {
+            let dir = resolve_directory(dir).map_err(McpError::Other)?;
+            let validation = validator
+                .validate_filesystem_path(&dir)
+                .map_err(McpError::Other)?;
+            self.print_scope_warnings(&validation);
+            self.enforce_strict(&dir.display().to_string(), &validation)?;
+            if validation.should_block() {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Refusing to allow access to {}",
+                    dir.display()
+                )));
+            }
+            scope.add_directory(&dir).map_err(McpError::Other)?;
+        }
+
+        if scope.is_empty() && !non_interactive {
+            scope = self.prompt_filesystem_scope(&config, &validator, scope)?;
+        }
+
+        if scope.is_empty() {
+            // Keep the configuration as given, but still flag overly broad directories
+            for dir in configured_directories(&config) {
+                let validation = validator
+                    .validate_filesystem_path(&dir)
+                    .map_err(McpError::Other)?;
+                self.print_scope_warnings(&validation);
+                self.enforce_strict(&dir.display().to_string(), &validation)?;
+            }
+            return Ok(config);
+        }
+
+        scope.apply(&config).map_err(McpError::Other)
+    }
+
+    fn prompt_filesystem_scope(
+        &self,
+        config: &ServerConfig,
+        validator: &SecurityValidator,
+        mut scope: FilesystemScope,
+    ) -> Result<FilesystemScope, McpError> {
+        println!(
+            "{} This is a filesystem server. Choose the directories it may access.",
+            "ℹ".blue()
+        );
+
+        let current = configured_directories(config);
+        if !current.is_empty() {
+            let keep = Confirm::new()
+                .with_prompt(format!(
+                    "Keep the current directories ({})?",
+                    current
+                        .iter()
+                        .map(|d| d.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+                .default(false)
+                .interact()
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))?;
+            if keep {
+                return Ok(scope);
+            }
+        }
+
+        if let Some(picker) = FolderPicker::detect() {
+            self.pick_directories(picker, validator, &mut scope)?;
+        }
+
+        loop {
+            let input: String = Input::new()
+                .with_prompt(if scope.is_empty() {
+                    "Allowed directory"
+                } else {
+                    "Another directory (or press Enter to finish)"
+                })
+                .allow_empty(!scope.is_empty())
+                .validate_with(|input: &String| -> Result<(), String> {
+                    if input.is_empty() {
+                        return Ok(());
+                    }
+                    resolve_directory(&expand_home(input))
+                        .map(|_| ())
+                        .map_err(|e| e.to_string())
+                })
+                .interact()
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            let dir = resolve_directory(&expand_home(&input)).map_err(McpError::Other)?;
+            if self.admit_directory(validator, &dir)? {
+                scope.add_directory(&dir).map_err(McpError::Other)?;
+            }
+        }
+
+        if config.command == "docker" && !scope.is_read_only() {
+            let read_only = Confirm::new()
+                .with_prompt("Mount these directories read-only?")
+                .default(true)
+                .interact()
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))?;
+            scope = scope.with_read_only(read_only);
+        }
+
+        Ok(scope)
+    }
+
+    /// Add directories chosen in a native folder dialog; manual entry follows for
+    /// anything the dialog could not provide
+    fn pick_directories(
+        &self,
+        picker: FolderPicker,
+        validator: &SecurityValidator,
+        scope: &mut FilesystemScope,
+    ) -> Result<(), McpError> {
+        let use_picker = Confirm::new()
+            .with_prompt("Choose the directories in a folder picker?")
+            .default(true)
+            .interact()
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))?;
+        if !use_picker {
+            return Ok(());
+        }
+
+        let picked = picker.pick("Directories the MCP server may access");
+        let (dirs, rejected) = normalize_selection(&picked);
+        for (path, reason) in rejected {
+            println!("  {} Skipping {}: {}", "⚠".yellow(), path.display(), reason);
+        }
+        for dir in dirs {
+            if self.admit_directory(validator, &dir)? {
+                println!("  {} {}", "✓".green(), dir.display());
+                scope.add_directory(&dir).map_err(McpError::Other)?;
             }
         }
-
         Ok(())
     }
 
-    fn detect_server_config(
-        &self,
-        server: &str,
-        mut args: Vec<String>,
-    ) -> Result<(String, Vec<String>, String), McpError> {
-        // Try to detect server type
-        let server_type = detect_server_type(server);
+    /// Whether `dir` may be added to the scope, asking about security warnings
+    fn admit_directory(&self, validator: &SecurityValidator, dir: &Path) -> Result<bool, McpError> {
+        let validation = validator
+            .validate_filesystem_path(dir)
+            .map_err(McpError::Other)?;
+        if validation.warnings.is_empty() {
+            return Ok(true);
+        }
 
-        if self.verbose {
-            println!("Detected server type: {server_type:?}");
+        self.print_scope_warnings(&validation);
+        if validation.should_block() || self.strict {
+            return Ok(false);
         }
+        Confirm::new()
+            .with_prompt("Allow this directory anyway?")
+            .default(false)
+            .interact()
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))
+    }
 
-        match server_type {
-            ServerType::Npm { package, version } => {
-                // For NPM packages, use npx (or npx.cmd on Windows)
-                let command = self.get_platform_command("npx");
+    /// Point the Docker image `config` runs at the digest its tag resolves to
+    fn pin_image_digest(&self, mut config: ServerConfig) -> Result<ServerConfig, McpError> {
+        let position = (config.command == "docker")
+            .then(|| image_from_run_args(&config.args))
+            .flatten()
+            .and_then(|image| config.args.iter().position(|arg| arg == image));
+        let Some(position) = position else {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "--pin-digest only applies to Docker images"
+            )));
+        };
 
-                // Build args: package name with version if specified
-                let package_arg = if let Some(v) = version {
-                    format!("{package}@{v}")
-                } else {
-                    package.clone()
-                };
+        let image = config.args[position].clone();
+        println!("{} Resolving the digest of {}", "→".green(), image.cyan());
+        let digest = resolve_digest(&image).map_err(McpError::Other)?;
+        let pinned = with_digest(&image, &digest);
+        println!("  {} Pinned to {}", "✓".green(), pinned);
+        config.args[position] = pinned;
+        Ok(config)
+    }
 
-                // If no args provided, just use the package
-                if args.is_empty() {
-                    args = vec![package_arg];
-                } else {
-                    // Prepend package to existing args
-                    args.insert(0, package_arg);
-                }
+    fn check_docker_requirements(
+        &self,
+        config: ServerConfig,
+        non_interactive: bool,
+    ) -> Result<ServerConfig, McpError> {
+        if config.command != "docker" {
+            return Ok(config);
+        }
+        let Some(image) = image_from_run_args(&config.args).map(str::to_string) else {
+            return Ok(config);
+        };
 
-                // Extract clean server name for configuration
-                let server_name = package
-                    .split('/')
-                    .next_back()
-                    .unwrap_or(&package)
-                    .to_string();
+        let hints: Vec<RequirementHint> = DockerRequirementInspector::new()
+            .inspect(&image)
+            .into_iter()
+            .filter(|hint| !hint.requirement.is_satisfied_by(&config.args))
+            .collect();
+        if hints.is_empty() {
+            return Ok(config);
+        }
 
-                Ok((command, args, server_name))
-            }
-            ServerType::Binary { url, .. } => {
-                // For binary servers, download and use the binary
-                // For now, just use the URL as-is (future: download logic)
-                println!("{} Binary server support coming soon", "⚠".yellow());
-                println!("Using URL as command: {url}");
-                Ok((url.clone(), args, server.to_string()))
+        let mut config = config;
+        for hint in hints {
+            let requirement = hint.requirement;
+            println!(
+                "{} This server appears to need {} (found in {})",
+                "⚠".yellow(),
+                requirement,
+                hint.evidence
+            );
+            println!(
+                "  Without it the server may start but fail silently. Add: {}",
+                requirement.flags().join(" ").cyan()
+            );
+            if requirement.needs_confirmation() {
+                println!("  {} {}", "⚠".yellow(), requirement.security_warning());
+            } else if self.verbose {
+                println!("  {} {}", "ℹ".blue(), requirement.security_warning());
             }
-            ServerType::Docker { image, tag } => {
-                // For Docker images, use docker run
-                let command = "docker".to_string();
-                let mut docker_args = vec!["run".to_string(), "--rm".to_string(), "-i".to_string()];
-
-                let full_image = if let Some(t) = tag {
-                    format!("{image}:{t}")
-                } else {
-                    image.clone()
-                };
-
-                docker_args.push(full_image);
-                docker_args.extend(args);
-
-                let server_name = image.split('/').next_back().unwrap_or(&image).to_string();
 
-                Ok((command, docker_args, server_name))
+            if non_interactive {
+                println!(
+                    "  {} Not added automatically in non-interactive mode",
+                    "ℹ".blue()
+                );
+                continue;
             }
-            ServerType::Python { package, version } => {
-                // For Python packages, use python -m
-                let command = "python".to_string();
-                let mut python_args = vec!["-m".to_string()];
-
-                // Add package with version if specified
-                if let Some(v) = version {
-                    python_args.push(format!("{package}=={v}"));
-                } else {
-                    python_args.push(package.clone());
-                }
-
-                python_args.extend(args);
-
-                let server_name = package
-                    .split('/')
-                    .next_back()
-                    .unwrap_or(&package)
-                    .to_string();
-
-                Ok((command, python_args, server_name))
+            if self.strict && requirement.needs_confirmation() {
+                println!("  {} Not granted in strict mode", "ℹ".blue());
+                continue;
             }
-        }
-    }
 
-    fn get_platform_command(&self, command: &str) -> String {
-        // Handle platform-specific command variations
-        if command == "npx" && cfg!(target_os = "windows") {
-            // On Windows, prefer npx.cmd if available
-            if which::which("npx.cmd").is_ok() {
-                "npx.cmd".to_string()
+            let prompt = if requirement.needs_confirmation() {
+                format!("Grant {requirement}? This weakens container isolation")
             } else {
-                "npx".to_string()
-            }
-        } else {
-            command.to_string()
-        }
-    }
+                format!("Enable {requirement}?")
+            };
+            let accept = Confirm::new()
+                .with_prompt(prompt)
+                .default(!requirement.needs_confirmation())
+                .interact()
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))?;
 
-    fn check_dependencies(&self, command: &str) -> Result<(), McpError> {
-        // Check dependencies based on command type
-        if command == "npx" || command == "npx.cmd" || command == "npm" {
-            let checker = NodeChecker::new();
-            match checker.check() {
-                Ok(check) => {
-                    if let crate::deps::DependencyStatus::Missing = check.status {
-                        return Err(McpError::Other(anyhow::anyhow!(
-                            "Node.js is required for NPM-based servers. Please install from https://nodejs.org"
-                        )));
-                    }
-                }
-                Err(e) => {
-                    if self.verbose {
-                        println!("{} Warning: Could not verify Node.js: {}", "⚠".yellow(), e);
-                    }
+            if accept {
+                if requirement.needs_confirmation() {
+                    audit_log::record_override(
+                        &format!("{requirement} for {image}"),
+                        &[requirement.security_warning().to_string()],
+                    );
                 }
+                config.args = apply_requirement(&config.args, requirement);
             }
         }
+        println!();
 
-        // Docker, Python, etc. checks could be added here
+        Ok(config)
+    }
 
+    /// In strict mode, fail on any warning from the security validator
+    fn enforce_strict(
+        &self,
+        subject: &str,
+        validation: &SecurityValidation,
+    ) -> Result<(), McpError> {
+        if self.strict && !validation.warnings.is_empty() {
+            return Err(McpError::Other(anyhow::Error::new(
+                SecurityViolation::from_validation(subject, validation),
+            )));
+        }
         Ok(())
     }
 
+    fn print_scope_warnings(&self, validation: &SecurityValidation) {
+        for warning in &validation.warnings {
+            println!("  {} {}", "⚠".yellow(), warning);
+        }
+    }
+
     fn configure_interactively(&self, mut config: ServerConfig) -> Result<ServerConfig, McpError> {
         // Ask if user wants to add environment variables
         let add_env = Confirm::new()
@@ -305,7 +1840,25 @@ impl AddCommand {
         Ok(config)
     }
 
+    /// The clients named with `--client`, every installed client with `--all-clients`
+    /// or in non-interactive mode, or the ones the user picks
     fn select_clients<'a>(
+        &self,
+        registry: &'a ClientRegistry,
+        non_interactive: bool,
+    ) -> Result<Vec<&'a dyn McpClient>, McpError> {
+        let installed_clients = registry.detect_installed();
+        match &self.client_target {
+            ClientTarget::Named(names) => registry
+                .get_installed_by_names(names)
+                .map_err(McpError::Other),
+            ClientTarget::All => Ok(installed_clients),
+            ClientTarget::Prompt if non_interactive => Ok(installed_clients),
+            ClientTarget::Prompt => self.prompt_clients(&installed_clients),
+        }
+    }
+
+    fn prompt_clients<'a>(
         &self,
         installed_clients: &[&'a dyn McpClient],
     ) -> Result<Vec<&'a dyn McpClient>, McpError> {
@@ -319,6 +1872,7 @@ impl AddCommand {
             let selections = MultiSelect::new()
                 .with_prompt("Select MCP clients to configure")
                 .items(&client_names)
+                .defaults(&detect_default_selection(&client_names))
                 .interact()
                 .map_err(|e| McpError::Other(anyhow::anyhow!("Selection failed: {}", e)))?;
 
@@ -338,6 +1892,9 @@ impl AddCommand {
             config.command.green(),
             config.args.join(" ").dimmed()
         );
+        if let Some(cwd) = &config.cwd {
+            println!("  Working directory: {}", cwd.cyan());
+        }
 
         if !config.env.is_empty() {
             println!("  Environment:");
@@ -357,6 +1914,187 @@ impl AddCommand {
         );
         println!();
     }
+
+    fn show_remote_preview(
+        &self,
+        server_name: &str,
+        remote: &RemoteServerConfig,
+        clients: &[&dyn McpClient],
+    ) {
+        let literal_secrets = remote.literal_secret_headers();
+
+        println!();
+        println!("{}", "Configuration preview:".blue());
+        println!("  Server: {}", server_name.cyan());
+        println!("  URL: {}", remote.url.green());
+
+        if !remote.headers.is_empty() {
+            println!("  Headers:");
+            let mut headers: Vec<_> = remote.headers.iter().collect();
+            headers.sort();
+            for (name, value) in headers {
+                let shown = if literal_secrets.contains(&name.as_str()) {
+                    "********"
+                } else {
+                    value.as_str()
+                };
+                println!("    {}: {}", name.cyan(), shown);
+            }
+        }
+        if remote.uses_secret_references() {
+            println!(
+                "  {}",
+                "Secrets are resolved by 'mcp bridge' at startup, so every client runs the bridge"
+                    .dimmed()
+            );
+        }
+
+        println!(
+            "  Clients: {}",
+            clients
+                .iter()
+                .map(|c| c.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+                .yellow()
+        );
+        println!();
+    }
+}
+
+/// In a dry run, name the client configs the servers would be added to
+fn show_dry_run(server_names: &[&str], clients: &[&dyn McpClient]) {
+    println!("{} Dry run: nothing was installed or written", "ℹ".blue());
+    for client in clients {
+        println!(
+            "  Would add {} to {} ({})",
+            server_names.join(", ").cyan(),
+            client.name(),
+            client.config_path().display()
+        );
+    }
+}
+
+/// Expand a leading `~` to the user's home directory
+fn expand_home(input: &str) -> PathBuf {
+    let trimmed = input.trim();
+    if let Some(rest) = trimmed
+        .strip_prefix("~/")
+        .or_else(|| (trimmed == "~").then_some(""))
+    {
+        if let Some(dirs) = directories::BaseDirs::new() {
+            return dirs.home_dir().join(rest);
+        }
+    }
+    PathBuf::from(trimmed)
+}
+
+/// The error for a machine without MCP clients, with advice for containers and CI
+pub(crate) fn no_clients_error() -> McpError {
+    let mut message = "No MCP clients found. Please install Claude Desktop, VS Code, or another supported client.".to_string();
+    if let Some(environment) = HeadlessEnvironment::detect() {
+        message = format!(
+            "No MCP clients found ({environment}). {}",
+            environment.guidance()
+        );
+    }
+    McpError::Other(anyhow::anyhow!(message))
+}
+
+/// Add `config` under `mcpServers` in the JSON file at `path`, keeping other entries
+fn write_manifest_out(
+    path: &Path,
+    server_name: &str,
+    config: &ServerConfig,
+) -> Result<(), McpError> {
+    let display = path.display().to_string();
+    let mut manifest = if path.exists() {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| McpError::io_error("read manifest", Some(display.clone()), e))?;
+        parse_json::<serde_json::Value>(&display, &content)
+            .map_err(|e| McpError::Other(e.into()))?
+    } else {
+        serde_json::json!({})
+    };
+
+    let Some(servers) = manifest
+        .as_object_mut()
+        .map(|root| {
+            root.entry("mcpServers")
+                .or_insert_with(|| serde_json::json!({}))
+        })
+        .and_then(|servers| servers.as_object_mut())
+    else {
+        return Err(McpError::Other(anyhow::anyhow!(
+            "{display} is not a client config with an 'mcpServers' object"
+        )));
+    };
+    let entry = serde_json::to_value(config).map_err(|e| McpError::Other(e.into()))?;
+    servers.insert(server_name.to_string(), entry);
+
+    let content = serde_json::to_string_pretty(&manifest).map_err(|e| McpError::Other(e.into()))?;
+    std::fs::write(path, content + "\n")
+        .map_err(|e| McpError::io_error("write manifest", Some(display.clone()), e))?;
+
+    println!(
+        "{} Wrote '{}' to {}",
+        "✅".green(),
+        server_name.cyan(),
+        display
+    );
+    println!(
+        "  Merge its {} entry into your client's config on the host, or mount the file there.",
+        "mcpServers".cyan()
+    );
+    Ok(())
+}
+
+/// Point out environment variables the server shares with others in the clients
+fn warn_env_overlaps(server_name: &str, config: &ServerConfig, clients: &[&dyn McpClient]) {
+    let existing: Vec<(String, HashMap<String, ServerConfig>)> = clients
+        .iter()
+        .filter_map(|client| {
+            let servers = client.list_servers().ok()?;
+            Some((client.name().to_string(), servers))
+        })
+        .collect();
+    let overlaps = find_env_overlaps(server_name, config, &existing);
+    if overlaps.is_empty() {
+        return;
+    }
+
+    println!(
+        "{} Environment variables also set by other servers:",
+        "⚠".yellow()
+    );
+    for overlap in &overlaps {
+        let others: Vec<String> = overlap
+            .servers
+            .iter()
+            .map(|(client, server)| format!("{server} in {client}"))
+            .collect();
+        let note = if overlap.is_conflict() {
+            " with the same value; both servers will use it".yellow()
+        } else {
+            "".normal()
+        };
+        println!(
+            "  • {} ({}){}",
+            overlap.name.cyan(),
+            others.join(", "),
+            note
+        );
+    }
+    let example = &overlaps[0];
+    println!(
+        "  {} If the server supports a prefixed name, use it (e.g. {}), or set the value in a \
+         wrapper so it can't leak between entries (e.g. command \"env\" with args \"{}=...\" \
+         followed by the server command)",
+        "💡".blue(),
+        example.prefixed_name(server_name),
+        example.name
+    );
+    println!();
 }
 
 #[cfg(test)]
@@ -372,6 +2110,135 @@ mod tests {
         assert!(cmd.verbose);
     }
 
+    #[test]
+    fn test_add_command_scope_options() {
+        let cmd = AddCommand::new(false)
+            .with_allowed_dirs(vec![PathBuf::from("/tmp")])
+            .with_read_only(true);
+        assert_eq!(cmd.allowed_dirs, vec![PathBuf::from("/tmp")]);
+        assert!(cmd.read_only);
+    }
+
+    #[test]
+    fn test_non_filesystem_server_is_not_scoped() {
+        let cmd = AddCommand::new(false).with_read_only(true);
+        let config = ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["@modelcontextprotocol/server-github".to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        let result = cmd.scope_filesystem_access(config.clone(), true).unwrap();
+        assert_eq!(result, config);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_strict_mode_rejects_broad_filesystem_access() {
+        let config = ServerConfig {
+            command: "npx".to_string(),
+            args: vec![
+                "@modelcontextprotocol/server-filesystem".to_string(),
+                "/".to_string(),
+            ],
+            env: HashMap::new(),
+            cwd: None,
+        };
+
+        let lenient = AddCommand::new(false);
+        assert!(lenient
+            .scope_filesystem_access(config.clone(), true)
+            .is_ok());
+
+        let strict = AddCommand::new(false).with_strict(true);
+        let Err(McpError::Other(error)) = strict.scope_filesystem_access(config, true) else {
+            panic!("expected a security violation");
+        };
+        assert!(error.downcast_ref::<SecurityViolation>().is_some());
+    }
+
+    #[test]
+    fn test_deno_server_config() {
+        let cmd = AddCommand::new(false);
+        let args = vec![
+            "--allow-net=api.example.com".to_string(),
+            "--port".to_string(),
+            "8080".to_string(),
+        ];
+        let (command, args, name) = cmd
+            .detect_server_config("jsr:@scope/notes@1.0.0", args)
+            .unwrap();
+        assert_eq!(command, "deno");
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--no-prompt",
+                "--allow-net=api.example.com",
+                "jsr:@scope/notes@1.0.0",
+                "--port",
+                "8080"
+            ]
+        );
+        assert_eq!(name, "notes");
+
+        let strict = AddCommand::new(false).with_strict(true);
+        let Err(McpError::Other(error)) =
+            strict.detect_server_config("./server.ts", vec!["--allow-all".to_string()])
+        else {
+            panic!("expected strict mode to reject --allow-all");
+        };
+        assert!(error.downcast_ref::<SecurityViolation>().is_some());
+    }
+
+    #[test]
+    fn test_jar_server_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let jar = temp_dir.path().join("jira-mcp-1.0.0.jar");
+        std::fs::write(&jar, b"PK").unwrap();
+
+        let cmd = AddCommand::new(false);
+        let args = vec![
+            "-Xmx512m".to_string(),
+            "--project".to_string(),
+            "OPS".to_string(),
+        ];
+        let (command, args, name) = cmd
+            .detect_server_config(&jar.display().to_string(), args)
+            .unwrap();
+        assert_eq!(command, "java");
+        assert_eq!(args[..2], ["-Xmx512m", "-jar"]);
+        assert!(Path::new(&args[2]).is_absolute());
+        assert_eq!(args[3..], ["--project", "OPS"]);
+        assert_eq!(name, "jira-mcp");
+
+        assert!(cmd
+            .detect_server_config("./missing-server.jar", vec![])
+            .is_err());
+    }
+
+    #[test]
+    fn test_remote_server_validation() {
+        let cmd = AddCommand::new(false);
+        assert!(cmd
+            .execute_remote("remote", "http://api.example.com/mcp", &[], true)
+            .is_err());
+
+        let bad_header = ["Authorization".to_string()];
+        assert!(cmd
+            .execute_remote("remote", "https://api.example.com/mcp", &bad_header, true)
+            .is_err());
+
+        let literal = ["Authorization=Bearer abc".to_string()];
+        let strict = AddCommand::new(false).with_strict(true);
+        let Err(McpError::Other(error)) =
+            strict.execute_remote("remote", "https://api.example.com/mcp", &literal, true)
+        else {
+            panic!("expected strict mode to reject a plain-text credential");
+        };
+        assert!(error.to_string().contains("keychain"));
+    }
+
     #[test]
     fn test_platform_command_detection() {
         let cmd = AddCommand::new(false);
@@ -391,4 +2258,85 @@ mod tests {
             assert!(result == "npx" || result == "npx.cmd");
         }
     }
+
+    #[test]
+    fn test_write_manifest_out_merges_entries() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("mcp.json");
+        std::fs::write(
+            &path,
+            r#"{"mcpServers": {"existing": {"command": "node", "args": []}}, "other": 1}"#,
+        )
+        .unwrap();
+
+        let config = ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["server-x".to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        write_manifest_out(&path, "server-x", &config).unwrap();
+
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["other"], 1);
+        assert_eq!(written["mcpServers"]["existing"]["command"], "node");
+        assert_eq!(written["mcpServers"]["server-x"]["args"][0], "server-x");
+
+        std::fs::write(&path, "[]").unwrap();
+        assert!(write_manifest_out(&path, "server-x", &config).is_err());
+    }
+
+    #[test]
+    fn test_dry_run_does_not_download_binaries() {
+        let url = "https://github.com/org/repo/releases/download/v1.0.0/dry-run-server";
+        let (command, _, name) = AddCommand::new(false)
+            .with_dry_run(true)
+            .detect_server_config(url, Vec::new())
+            .unwrap();
+
+        assert!(command.ends_with("dry-run-server"), "{command}");
+        assert!(!Path::new(&command).exists());
+        assert_eq!(name, "repo");
+    }
+
+    #[test]
+    fn test_require_dependency() {
+        let node = crate::deps::Dependency::NodeJs { min_version: None };
+        let missing = DependencyCheck {
+            dependency: node.clone(),
+            status: DependencyStatus::Missing,
+            install_instructions: None,
+        };
+
+        // Without --auto-install-deps the message is all there is
+        let err = AddCommand::new(false)
+            .require_dependency(&missing, "Node.js is required")
+            .unwrap_err();
+        assert!(err.to_string().contains("Node.js is required"));
+
+        // Nothing to install it with
+        let cmd = AddCommand::new(false)
+            .with_auto_install_deps(true)
+            .with_dry_run(true);
+        assert!(cmd
+            .require_dependency(&missing, "Node.js is required")
+            .is_err());
+
+        let installable = DependencyCheck {
+            install_instructions: Some(crate::deps::get_install_instructions(&node)),
+            ..missing
+        };
+        if !installable
+            .install_instructions
+            .as_ref()
+            .unwrap()
+            .for_platform()
+            .is_empty()
+        {
+            assert!(cmd
+                .require_dependency(&installable, "Node.js is required")
+                .is_ok());
+        }
+    }
 }
@@ -0,0 +1,546 @@
+//! `mcp apply`: converge client configs on a declared desired state.
+//!
+//! A state file lists, per client, the complete set of stdio servers it should
+//! have:
+//!
+//! ```text
+//! clients:
+//!   Claude Desktop:
+//!     servers:
+//!       filesystem:
+//!         package: "@modelcontextprotocol/server-filesystem@0.6.2"
+//!         args: [/Users/me/projects]
+//!       fetch:
+//!         command: uvx
+//!         args: [mcp-server-fetch]
+//!         env:
+//!           LOG_LEVEL: info
+//! ```
+//!
+//! Each server is either an npm `package` (optionally pinned with `@version`),
+//! configured the way `mcp add` would, or an explicit `command` with `args`.
+//! Servers missing from a listed client are removed; clients not in the file and
+//! remote servers are left alone. `.json` files with the same layout work too.
+
+use colored::Colorize;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::client::{detect_clients, McpClient, ServerConfig};
+use crate::config::{expand_server_config, ConfigManager};
+use crate::error::McpError;
+use crate::server::npm::npx_preference_flags;
+use crate::server::{detect_server_type, ServerType};
+use crate::utils::confirm::Confirmer;
+use crate::utils::parse_error::{parse_json, ParseError};
+use crate::utils::yaml::parse_yaml;
+
+/// The servers every listed client should end up with
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredState {
+    #[serde(default)]
+    pub clients: BTreeMap<String, DesiredClient>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredClient {
+    /// `None` when the key is present but empty, which removes every server
+    #[serde(default)]
+    pub servers: Option<BTreeMap<String, DesiredServer>>,
+}
+
+/// One server in the state file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DesiredServer {
+    /// npm package spec, e.g. `@modelcontextprotocol/server-github@2025.4.8`
+    pub package: Option<String>,
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub cwd: Option<String>,
+}
+
+impl DesiredServer {
+    /// The client entry this server should have
+    pub fn to_config(&self) -> Result<ServerConfig, String> {
+        let (command, args) = match (&self.package, &self.command) {
+            (Some(_), Some(_)) => return Err("set either `package` or `command`, not both".into()),
+            (None, None) => return Err("set `package` or `command`".into()),
+            (None, Some(command)) => (command.clone(), self.args.clone()),
+            (Some(spec), None) => {
+                let ServerType::Npm { package, version } = detect_server_type(spec) else {
+                    return Err(format!(
+                        "`{spec}` is not an npm package; use `command` and `args` instead"
+                    ));
+                };
+                let package = match version {
+                    Some(version) => format!("{package}@{version}"),
+                    None => package,
+                };
+                let mut args = npx_preference_flags();
+                args.push(package);
+                args.extend(self.args.iter().cloned());
+                (npx_command(), args)
+            }
+        };
+        Ok(ServerConfig {
+            command,
+            args,
+            env: self.env.clone(),
+            cwd: self.cwd.clone(),
+        })
+    }
+}
+
+/// `npx`, or `npx.cmd` on Windows when available, matching `mcp add`
+fn npx_command() -> String {
+    if cfg!(target_os = "windows") && which::which("npx.cmd").is_ok() {
+        "npx.cmd".to_string()
+    } else {
+        "npx".to_string()
+    }
+}
+
+/// Read a state file, YAML unless it ends in `.json`
+pub fn parse_state(file: &str, content: &str) -> Result<DesiredState, ParseError> {
+    let state: Option<DesiredState> = if file.ends_with(".json") {
+        parse_json(file, content)?
+    } else {
+        parse_yaml(file, content)?
+    };
+    Ok(state.unwrap_or_default())
+}
+
+/// What applying the state does to one server of one client
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApplyAction {
+    Add(ServerConfig),
+    Update {
+        current: ServerConfig,
+        desired: ServerConfig,
+    },
+    Remove,
+    Unchanged,
+}
+
+impl ApplyAction {
+    fn changes(&self) -> bool {
+        !matches!(self, Self::Unchanged)
+    }
+}
+
+/// Compare one client's servers with their desired state, sorted by server name.
+pub fn plan_client(
+    desired: &BTreeMap<String, ServerConfig>,
+    current: &HashMap<String, ServerConfig>,
+) -> Vec<(String, ApplyAction)> {
+    let mut plan: Vec<(String, ApplyAction)> = desired
+        .iter()
+        .map(|(name, config)| {
+            let action = match current.get(name) {
+                None => ApplyAction::Add(config.clone()),
+                Some(existing) if existing == config => ApplyAction::Unchanged,
+                Some(existing) => ApplyAction::Update {
+                    current: existing.clone(),
+                    desired: config.clone(),
+                },
+            };
+            (name.clone(), action)
+        })
+        .collect();
+    plan.extend(
+        current
+            .keys()
+            .filter(|name| !desired.contains_key(*name))
+            .map(|name| (name.clone(), ApplyAction::Remove)),
+    );
+    plan.sort_by(|a, b| a.0.cmp(&b.0));
+    plan
+}
+
+/// What differs between two configs of the same server, one line per field
+pub fn describe_update(current: &ServerConfig, desired: &ServerConfig) -> Vec<String> {
+    let mut changes = Vec::new();
+    if current.command != desired.command {
+        changes.push(format!(
+            "command: {} → {}",
+            current.command, desired.command
+        ));
+    }
+    if current.args != desired.args {
+        changes.push(format!(
+            "args: {} → {}",
+            current.args.join(" "),
+            desired.args.join(" ")
+        ));
+    }
+    let mut keys: Vec<&String> = current.env.keys().chain(desired.env.keys()).collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        match (current.env.get(key), desired.env.get(key)) {
+            (Some(_), None) => changes.push(format!("env {key}: removed")),
+            (None, Some(_)) => changes.push(format!("env {key}: added")),
+            (Some(a), Some(b)) if a != b => changes.push(format!("env {key}: changed")),
+            _ => {}
+        }
+    }
+    if current.cwd != desired.cwd {
+        changes.push(format!(
+            "cwd: {} → {}",
+            current.cwd.as_deref().unwrap_or("(none)"),
+            desired.cwd.as_deref().unwrap_or("(none)")
+        ));
+    }
+    changes
+}
+
+/// Converge client configurations on a state file
+pub struct ApplyCommand {
+    verbose: bool,
+    plan_only: bool,
+    yes: bool,
+}
+
+impl ApplyCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            plan_only: false,
+            yes: false,
+        }
+    }
+
+    /// Only show the changes
+    pub fn with_plan_only(mut self, plan_only: bool) -> Self {
+        self.plan_only = plan_only;
+        self
+    }
+
+    /// Apply without asking for confirmation
+    pub fn with_yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
+
+    pub fn execute(&self, path: &Path) -> Result<(), McpError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            McpError::io_error("read state file", Some(path.display().to_string()), e)
+        })?;
+        let state = parse_state(&path.display().to_string(), &content)
+            .map_err(|e| McpError::Other(anyhow::Error::new(e)))?;
+
+        let clients = detect_clients();
+        let mut plans = Vec::new();
+        for (client_name, desired) in &state.clients {
+            let client = clients
+                .iter()
+                .find(|c| c.name().eq_ignore_ascii_case(client_name))
+                .ok_or_else(|| {
+                    McpError::client_not_found(
+                        client_name,
+                        clients.iter().map(|c| c.name().to_string()).collect(),
+                        "Use one of the supported client names as a key under `clients`",
+                    )
+                })?;
+            let desired = desired_configs(client_name, desired)?;
+            let current = client.list_servers().map_err(|e| {
+                McpError::Other(
+                    e.context(format!("Failed to read {} configuration", client.name())),
+                )
+            })?;
+            plans.push((client.as_ref(), plan_client(&desired, &current)));
+        }
+
+        let title = if self.plan_only {
+            "📋 Plan (no changes made)"
+        } else {
+            "📋 Applying desired state"
+        };
+        println!("{}", title.blue().bold());
+        println!();
+        for (client, plan) in &plans {
+            self.print_plan(*client, plan);
+        }
+
+        let count = |wanted: fn(&ApplyAction) -> bool| {
+            plans
+                .iter()
+                .flat_map(|(_, plan)| plan)
+                .filter(|(_, action)| wanted(action))
+                .count()
+        };
+        let adds = count(|a| matches!(a, ApplyAction::Add(_)));
+        let updates = count(|a| matches!(a, ApplyAction::Update { .. }));
+        let removes = count(|a| matches!(a, ApplyAction::Remove));
+        println!(
+            "Plan: {} to add, {} to change, {} to remove.",
+            adds.to_string().green(),
+            updates.to_string().yellow(),
+            removes.to_string().red()
+        );
+
+        if adds + updates + removes == 0 {
+            println!("{} Already in the desired state", "✓".green());
+            return Ok(());
+        }
+        if self.plan_only {
+            return Ok(());
+        }
+        if !Confirmer::new(self.yes).confirm("Apply these changes?")? {
+            println!("{} Nothing changed", "❌".red());
+            return Ok(());
+        }
+
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+        let mut failed = 0;
+        for (client, plan) in &plans {
+            for (name, action) in plan {
+                let result = match action {
+                    ApplyAction::Add(config)
+                    | ApplyAction::Update {
+                        desired: config, ..
+                    } => manager
+                        .apply_config(*client, name, config.clone())
+                        .map(|_| ()),
+                    ApplyAction::Remove => manager.remove_config(*client, name).map(|_| ()),
+                    ApplyAction::Unchanged => continue,
+                };
+                if let Err(e) = result {
+                    failed += 1;
+                    println!(
+                        "  {} {} in {} - {}",
+                        "✗".red(),
+                        name,
+                        client.name(),
+                        e.to_string().dimmed()
+                    );
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Failed to apply {} change{}; run the command again to retry",
+                failed,
+                if failed == 1 { "" } else { "s" }
+            )));
+        }
+        println!("{} Applied the desired state", "✓".green());
+        Ok(())
+    }
+
+    fn print_plan(&self, client: &dyn McpClient, plan: &[(String, ApplyAction)]) {
+        println!("{}", client.name().cyan().bold());
+        if !client.is_installed() {
+            println!(
+                "  {} {} does not look installed",
+                "⚠".yellow(),
+                client.name()
+            );
+        }
+        if !plan.iter().any(|(_, action)| action.changes()) && !self.verbose {
+            println!("  {}", "(no changes)".dimmed());
+        }
+        for (name, action) in plan {
+            match action {
+                ApplyAction::Add(config) => println!(
+                    "  {} {} {}",
+                    "+".green(),
+                    name,
+                    format!("({} {})", config.command, config.args.join(" ")).dimmed()
+                ),
+                ApplyAction::Update { current, desired } => {
+                    println!("  {} {}", "~".yellow(), name);
+                    for change in describe_update(current, desired) {
+                        println!("      {}", change.dimmed());
+                    }
+                }
+                ApplyAction::Remove => println!("  {} {}", "-".red(), name),
+                ApplyAction::Unchanged => {
+                    if self.verbose {
+                        println!("  {} {}", "=".dimmed(), name);
+                    }
+                }
+            }
+        }
+        println!();
+    }
+}
+
+fn desired_configs(
+    client_name: &str,
+    desired: &DesiredClient,
+) -> Result<BTreeMap<String, ServerConfig>, McpError> {
+    let mut configs = BTreeMap::new();
+    for (name, server) in desired.servers.iter().flatten() {
+        let config = server.to_config().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Server '{name}' for {client_name}: {e}"))
+        })?;
+        configs.insert(name.clone(), expand_server_config(config, name));
+    }
+    Ok(configs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_state_yaml_and_json() {
+        let yaml = "\
+clients:
+  Cursor:
+    servers:
+      fetch:
+        command: uvx
+        args: [mcp-server-fetch]
+        env:
+          LOG_LEVEL: info
+  VS Code:
+    servers:
+";
+        let state = parse_state("state.yaml", yaml).unwrap();
+        let cursor = &state.clients["Cursor"].servers.as_ref().unwrap()["fetch"];
+        assert_eq!(cursor.command.as_deref(), Some("uvx"));
+        assert_eq!(cursor.args, vec!["mcp-server-fetch"]);
+        assert_eq!(cursor.env["LOG_LEVEL"], "info");
+        assert_eq!(state.clients["VS Code"].servers, None);
+
+        let json = r#"{"clients": {"Cursor": {"servers": {"fetch": {"command": "uvx"}}}}}"#;
+        let state = parse_state("state.json", json).unwrap();
+        assert!(state.clients["Cursor"].servers.as_ref().unwrap()["fetch"]
+            .args
+            .is_empty());
+
+        assert_eq!(
+            parse_state("state.yaml", "").unwrap(),
+            DesiredState::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_state_rejects_unknown_fields() {
+        let error = parse_state(
+            "state.yaml",
+            "clients:\n  Cursor:\n    servers:\n      x:\n        comand: uvx\n",
+        )
+        .unwrap_err();
+        assert!(error.message.contains("comand"), "{}", error.message);
+        assert_eq!(error.line, 5);
+    }
+
+    #[test]
+    fn test_to_config() {
+        let desired = DesiredServer {
+            command: Some("uvx".to_string()),
+            args: vec!["mcp-server-fetch".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            desired.to_config().unwrap(),
+            server("uvx", &["mcp-server-fetch"])
+        );
+
+        let desired = DesiredServer {
+            package: Some("@modelcontextprotocol/server-github@1.2.3".to_string()),
+            ..Default::default()
+        };
+        let config = desired.to_config().unwrap();
+        assert!(config.command.starts_with("npx"));
+        assert_eq!(
+            config.args.last().map(String::as_str),
+            Some("@modelcontextprotocol/server-github@1.2.3")
+        );
+
+        assert!(DesiredServer::default().to_config().is_err());
+        let both = DesiredServer {
+            package: Some("a".to_string()),
+            command: Some("b".to_string()),
+            ..Default::default()
+        };
+        assert!(both.to_config().is_err());
+        let docker = DesiredServer {
+            package: Some("docker:mcp/fetch".to_string()),
+            ..Default::default()
+        };
+        assert!(docker
+            .to_config()
+            .unwrap_err()
+            .contains("not an npm package"));
+    }
+
+    #[test]
+    fn test_plan_client() {
+        let desired = BTreeMap::from([
+            ("fetch".to_string(), server("uvx", &["mcp-server-fetch"])),
+            ("git".to_string(), server("uvx", &["mcp-server-git"])),
+            ("time".to_string(), server("uvx", &["mcp-server-time"])),
+        ]);
+        let current = HashMap::from([
+            ("fetch".to_string(), server("uvx", &["mcp-server-fetch"])),
+            ("git".to_string(), server("uvx", &["mcp-server-git==0.5"])),
+            ("old".to_string(), server("node", &["old.js"])),
+        ]);
+
+        let plan = plan_client(&desired, &current);
+        let summary: Vec<(&str, &str)> = plan
+            .iter()
+            .map(|(name, action)| {
+                let kind = match action {
+                    ApplyAction::Add(_) => "add",
+                    ApplyAction::Update { .. } => "update",
+                    ApplyAction::Remove => "remove",
+                    ApplyAction::Unchanged => "unchanged",
+                };
+                (name.as_str(), kind)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("fetch", "unchanged"),
+                ("git", "update"),
+                ("old", "remove"),
+                ("time", "add"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_describe_update() {
+        let mut current = server("uvx", &["mcp-server-git"]);
+        current.env.insert("TOKEN".to_string(), "a".to_string());
+        current.env.insert("OLD".to_string(), "x".to_string());
+        let mut desired = server("uvx", &["mcp-server-git==0.6"]);
+        desired.env.insert("TOKEN".to_string(), "b".to_string());
+        desired.cwd = Some("/srv".to_string());
+
+        assert_eq!(
+            describe_update(&current, &desired),
+            vec![
+                "args: mcp-server-git → mcp-server-git==0.6".to_string(),
+                "env OLD: removed".to_string(),
+                "env TOKEN: changed".to_string(),
+                "cwd: (none) → /srv".to_string(),
+            ]
+        );
+    }
+}
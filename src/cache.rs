@@ -1,16 +1,62 @@
 //! Caching functionality for MCP Helper.
 //!
-//! This module provides caching for dependency checks, server metadata, and download artifacts
-//! to improve performance and reduce redundant operations.
+//! This module provides caching for dependency checks, server metadata, npm registry
+//! lookups and download artifacts to improve performance and reduce redundant operations.
+//!
+//! npm registry responses are shared by every command that looks packages up. They
+//! are reused for [`RegistryCache::DEFAULT_TTL`] and then revalidated with their
+//! ETag, so an unchanged package costs a `304 Not Modified` instead of a download.
+//! `--no-cache` bypasses them for one invocation. GitHub API responses are kept the
+//! same way in a cache of their own.
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::deps::{Dependency, DependencyStatus};
+use crate::utils::clock::{Clock, SystemClock};
+use crate::utils::filesystem::{FileSystem, RealFileSystem};
+use crate::utils::state_file::{read_state_in, write_state_in};
+
+/// Set by `--no-cache`
+static REGISTRY_CACHE_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Skip the npm registry cache for the rest of this process
+pub fn disable_registry_cache() {
+    REGISTRY_CACHE_DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether npm registry lookups may use the cache
+pub fn registry_cache_enabled() -> bool {
+    !REGISTRY_CACHE_DISABLED.load(Ordering::Relaxed)
+}
+
+/// What a registry request returned
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryResponse {
+    /// `304 Not Modified`: the cached body is still current
+    NotModified,
+    Fetched {
+        body: serde_json::Value,
+        etag: Option<String>,
+    },
+}
+
+impl RegistryResponse {
+    /// The fetched body; a `304` is an error here since there is no cached copy to use
+    pub fn into_body(self) -> Result<serde_json::Value> {
+        match self {
+            Self::Fetched { body, .. } => Ok(body),
+            Self::NotModified => {
+                anyhow::bail!("Server answered 304 Not Modified to an uncached request")
+            }
+        }
+    }
+}
 
 /// Cache manager for MCP Helper operations.
 #[derive(Debug)]
@@ -18,34 +64,73 @@ pub struct CacheManager {
     cache_dir: PathBuf,
     dependency_cache: DependencyCache,
     metadata_cache: MetadataCache,
+    registry_cache: RegistryCache,
+    github_cache: RegistryCache,
+    clock: Arc<dyn Clock>,
+    fs: Arc<dyn FileSystem>,
 }
 
 impl CacheManager {
     /// Create a new cache manager with the default cache directory.
     pub fn new() -> Result<Self> {
-        let cache_dir = Self::default_cache_dir()?;
-        fs::create_dir_all(&cache_dir)?;
+        Self::open(
+            Self::default_cache_dir()?,
+            Arc::new(SystemClock),
+            Arc::new(RealFileSystem),
+        )
+    }
+
+    /// Cache in `cache_dir`, reading the time from `clock` and files through `fs`
+    pub fn open(
+        cache_dir: PathBuf,
+        clock: Arc<dyn Clock>,
+        fs: Arc<dyn FileSystem>,
+    ) -> Result<Self> {
+        fs.create_dir_all(&cache_dir)?;
 
-        let dependency_cache = DependencyCache::load(&cache_dir)?;
-        let metadata_cache = MetadataCache::load(&cache_dir)?;
+        let dependency_cache = DependencyCache::load(&*fs, &cache_dir)?;
+        let metadata_cache = MetadataCache::load(&*fs, &cache_dir)?;
+        let registry_cache = RegistryCache::load(&*fs, &cache_dir, RegistryCache::CACHE_FILE)?;
+        let github_cache = RegistryCache::load(&*fs, &cache_dir, RegistryCache::GITHUB_CACHE_FILE)?;
 
         Ok(Self {
             cache_dir,
             dependency_cache,
             metadata_cache,
+            registry_cache,
+            github_cache,
+            clock,
+            fs,
         })
     }
 
+    /// Cache files checked for corruption by `mcp doctor`
+    pub fn state_files() -> Result<Vec<PathBuf>> {
+        let cache_dir = Self::default_cache_dir()?;
+        Ok(vec![
+            cache_dir.join(DependencyCache::CACHE_FILE),
+            cache_dir.join(MetadataCache::CACHE_FILE),
+            cache_dir.join(RegistryCache::CACHE_FILE),
+            cache_dir.join(RegistryCache::GITHUB_CACHE_FILE),
+        ])
+    }
+
     /// Get the default cache directory for the current platform.
-    fn default_cache_dir() -> Result<PathBuf> {
+    pub fn default_cache_dir() -> Result<PathBuf> {
         let base = directories::ProjectDirs::from("com", "mcp-helper", "mcp-helper")
             .ok_or_else(|| anyhow::anyhow!("Failed to determine cache directory"))?;
         Ok(base.cache_dir().to_path_buf())
     }
 
+    /// npm cache used by generated `npx` commands when `npm.shared_cache` is set
+    pub fn npm_cache_dir() -> Result<PathBuf> {
+        Ok(Self::default_cache_dir()?.join("npm"))
+    }
+
     /// Get cached dependency status if available and not expired.
     pub fn get_dependency_status(&self, dependency: &Dependency) -> Option<&DependencyStatus> {
-        self.dependency_cache.get(dependency)
+        self.dependency_cache
+            .get(dependency, self.clock.unix_secs())
     }
 
     /// Cache a dependency status result.
@@ -54,14 +139,15 @@ impl CacheManager {
         dependency: Dependency,
         status: DependencyStatus,
     ) -> Result<()> {
-        self.dependency_cache.insert(dependency, status);
-        self.dependency_cache.save(&self.cache_dir)?;
+        self.dependency_cache
+            .insert(dependency, status, self.clock.unix_secs());
+        self.dependency_cache.save(&*self.fs, &self.cache_dir)?;
         Ok(())
     }
 
     /// Get cached server metadata if available and not expired.
     pub fn get_server_metadata(&self, server_name: &str) -> Option<&CachedMetadata> {
-        self.metadata_cache.get(server_name)
+        self.metadata_cache.get(server_name, self.clock.unix_secs())
     }
 
     /// Cache server metadata.
@@ -70,25 +156,83 @@ impl CacheManager {
         server_name: String,
         metadata: ServerMetadataInfo,
     ) -> Result<()> {
-        self.metadata_cache.insert(server_name, metadata);
-        self.metadata_cache.save(&self.cache_dir)?;
+        self.metadata_cache
+            .insert(server_name, metadata, self.clock.unix_secs());
+        self.metadata_cache.save(&*self.fs, &self.cache_dir)?;
         Ok(())
     }
 
+    /// npm registry metadata for `package`, from the cache while it is fresh.
+    ///
+    /// `fetch` performs the request, sending the given ETag as `If-None-Match` when
+    /// there is a stale entry to revalidate.
+    pub fn npm_metadata(
+        &mut self,
+        package: &str,
+        fetch: impl FnOnce(Option<&str>) -> Result<RegistryResponse>,
+    ) -> Result<serde_json::Value> {
+        let now = self.clock.unix_secs();
+        let body = self.registry_cache.lookup(package, now, fetch)?;
+        self.save_cache(&self.registry_cache, RegistryCache::CACHE_FILE);
+        Ok(body)
+    }
+
+    /// A GitHub API response cached under `key`, like [`Self::npm_metadata`] but kept
+    /// apart from the npm registry entries.
+    pub fn github_metadata(
+        &mut self,
+        key: &str,
+        fetch: impl FnOnce(Option<&str>) -> Result<RegistryResponse>,
+    ) -> Result<serde_json::Value> {
+        let now = self.clock.unix_secs();
+        let body = self.github_cache.lookup(key, now, fetch)?;
+        self.save_cache(&self.github_cache, RegistryCache::GITHUB_CACHE_FILE);
+        Ok(body)
+    }
+
+    /// Cache writes are best effort: a read-only cache must not fail a lookup
+    fn save_cache(&self, cache: &RegistryCache, file: &str) {
+        if let Err(e) = cache.save(&*self.fs, &self.cache_dir, file) {
+            tracing::debug!("Failed to save {file}: {e}");
+        }
+    }
+
+    /// Entry counts and registry hit statistics, for `mcp cache stats`
+    pub fn stats(&self) -> CacheStats {
+        let now = self.clock.unix_secs();
+        CacheStats {
+            cache_dir: self.cache_dir.clone(),
+            dependency_entries: self.dependency_cache.entries.len(),
+            metadata_entries: self.metadata_cache.entries.len(),
+            registry_entries: self.registry_cache.entries.len(),
+            registry_fresh: self
+                .registry_cache
+                .entries
+                .keys()
+                .filter(|package| self.registry_cache.fresh(package, now).is_some())
+                .count(),
+            registry: self.registry_cache.stats.clone(),
+        }
+    }
+
     /// Clear all caches.
     pub fn clear_all(&mut self) -> Result<()> {
         self.dependency_cache.clear();
         self.metadata_cache.clear();
+        self.registry_cache.clear();
+        self.github_cache.clear();
 
         // Remove cache files
-        let dep_cache_path = self.cache_dir.join("dependency_cache.json");
-        let meta_cache_path = self.cache_dir.join("metadata_cache.json");
-
-        if dep_cache_path.exists() {
-            fs::remove_file(dep_cache_path)?;
-        }
-        if meta_cache_path.exists() {
-            fs::remove_file(meta_cache_path)?;
+        for file in [
+            DependencyCache::CACHE_FILE,
+            MetadataCache::CACHE_FILE,
+            RegistryCache::CACHE_FILE,
+            RegistryCache::GITHUB_CACHE_FILE,
+        ] {
+            let path = self.cache_dir.join(file);
+            if self.fs.exists(&path) {
+                self.fs.remove_file(&path)?;
+            }
         }
 
         Ok(())
@@ -103,7 +247,7 @@ impl CacheManager {
     pub fn get_cached_download(&self, url: &str) -> Option<PathBuf> {
         let filename = Self::url_to_filename(url);
         let path = self.downloads_dir().join(filename);
-        if path.exists() {
+        if self.fs.exists(&path) {
             Some(path)
         } else {
             None
@@ -161,17 +305,8 @@ impl DependencyCache {
     const CACHE_FILE: &'static str = "dependency_cache.json";
     const DEFAULT_TTL: Duration = Duration::from_secs(3600); // 1 hour
 
-    fn load(cache_dir: &Path) -> Result<Self> {
-        let path = cache_dir.join(Self::CACHE_FILE);
-        if path.exists() {
-            let content = fs::read_to_string(&path)?;
-            Ok(
-                crate::utils::json_validator::deserialize_json_safe(&content)
-                    .unwrap_or_else(|_| Self::new()),
-            )
-        } else {
-            Ok(Self::new())
-        }
+    fn load(fs: &dyn FileSystem, cache_dir: &Path) -> Result<Self> {
+        read_state_in(fs, &cache_dir.join(Self::CACHE_FILE), Self::new)
     }
 
     fn new() -> Self {
@@ -181,10 +316,10 @@ impl DependencyCache {
         }
     }
 
-    fn get(&self, dependency: &Dependency) -> Option<&DependencyStatus> {
+    fn get(&self, dependency: &Dependency, now: u64) -> Option<&DependencyStatus> {
         let key = self.dependency_key(dependency);
         self.entries.get(&key).and_then(|entry| {
-            if self.is_expired(entry.cached_at) {
+            if self.is_expired(entry.cached_at, now) {
                 None
             } else {
                 Some(&entry.status)
@@ -192,13 +327,8 @@ impl DependencyCache {
         })
     }
 
-    fn insert(&mut self, dependency: Dependency, status: DependencyStatus) {
+    fn insert(&mut self, dependency: Dependency, status: DependencyStatus, cached_at: u64) {
         let key = self.dependency_key(&dependency);
-        let cached_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
         self.entries.insert(
             key,
             CachedDependency {
@@ -209,11 +339,8 @@ impl DependencyCache {
         );
     }
 
-    fn save(&self, cache_dir: &Path) -> Result<()> {
-        let path = cache_dir.join(Self::CACHE_FILE);
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(path, content)?;
-        Ok(())
+    fn save(&self, fs: &dyn FileSystem, cache_dir: &Path) -> Result<()> {
+        write_state_in(fs, &cache_dir.join(Self::CACHE_FILE), self)
     }
 
     fn clear(&mut self) {
@@ -239,15 +366,23 @@ impl DependencyCache {
                 format!("python:{}", min_version.as_deref().unwrap_or("any"))
             }
             Dependency::Git => "git:any".to_string(),
+            Dependency::Rust { min_version } => {
+                format!("rust:{}", min_version.as_deref().unwrap_or("any"))
+            }
+            Dependency::Deno { min_version } => {
+                format!("deno:{}", min_version.as_deref().unwrap_or("any"))
+            }
+            Dependency::Java { min_version } => {
+                format!("java:{}", min_version.as_deref().unwrap_or("any"))
+            }
+            Dependency::Dotnet { min_version } => {
+                format!("dotnet:{}", min_version.as_deref().unwrap_or("any"))
+            }
         }
     }
 
-    fn is_expired(&self, cached_at: u64) -> bool {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        now - cached_at > self.ttl.as_secs()
+    fn is_expired(&self, cached_at: u64, now: u64) -> bool {
+        now.saturating_sub(cached_at) > self.ttl.as_secs()
     }
 }
 
@@ -277,17 +412,8 @@ impl MetadataCache {
     const CACHE_FILE: &'static str = "metadata_cache.json";
     const DEFAULT_TTL: Duration = Duration::from_secs(86400); // 24 hours
 
-    fn load(cache_dir: &Path) -> Result<Self> {
-        let path = cache_dir.join(Self::CACHE_FILE);
-        if path.exists() {
-            let content = fs::read_to_string(&path)?;
-            Ok(
-                crate::utils::json_validator::deserialize_json_safe(&content)
-                    .unwrap_or_else(|_| Self::new()),
-            )
-        } else {
-            Ok(Self::new())
-        }
+    fn load(fs: &dyn FileSystem, cache_dir: &Path) -> Result<Self> {
+        read_state_in(fs, &cache_dir.join(Self::CACHE_FILE), Self::new)
     }
 
     fn new() -> Self {
@@ -297,9 +423,9 @@ impl MetadataCache {
         }
     }
 
-    fn get(&self, server_name: &str) -> Option<&CachedMetadata> {
+    fn get(&self, server_name: &str, now: u64) -> Option<&CachedMetadata> {
         self.entries.get(server_name).and_then(|entry| {
-            if self.is_expired(entry.cached_at) {
+            if self.is_expired(entry.cached_at, now) {
                 None
             } else {
                 Some(entry)
@@ -307,12 +433,7 @@ impl MetadataCache {
         })
     }
 
-    fn insert(&mut self, server_name: String, metadata: ServerMetadataInfo) {
-        let cached_at = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-
+    fn insert(&mut self, server_name: String, metadata: ServerMetadataInfo, cached_at: u64) {
         self.entries.insert(
             server_name,
             CachedMetadata {
@@ -322,23 +443,155 @@ impl MetadataCache {
         );
     }
 
-    fn save(&self, cache_dir: &Path) -> Result<()> {
-        let path = cache_dir.join(Self::CACHE_FILE);
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(path, content)?;
-        Ok(())
+    fn save(&self, fs: &dyn FileSystem, cache_dir: &Path) -> Result<()> {
+        write_state_in(fs, &cache_dir.join(Self::CACHE_FILE), self)
     }
 
     fn clear(&mut self) {
         self.entries.clear();
     }
 
-    fn is_expired(&self, cached_at: u64) -> bool {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        now - cached_at > self.ttl.as_secs()
+    fn is_expired(&self, cached_at: u64, now: u64) -> bool {
+        now.saturating_sub(cached_at) > self.ttl.as_secs()
+    }
+}
+
+/// How often registry lookups were answered from the cache
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegistryCacheStats {
+    /// Answered from a fresh entry without a request
+    pub hits: u64,
+    /// Stale entry confirmed unchanged by a `304 Not Modified`
+    pub revalidated: u64,
+    /// Downloaded in full
+    pub misses: u64,
+}
+
+impl RegistryCacheStats {
+    /// Share of lookups that didn't download the metadata again
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.revalidated + self.misses;
+        (total > 0).then(|| (self.hits + self.revalidated) as f64 / total as f64)
+    }
+}
+
+/// Summary of what is cached
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheStats {
+    pub cache_dir: PathBuf,
+    pub dependency_entries: usize,
+    pub metadata_entries: usize,
+    pub registry_entries: usize,
+    /// Registry entries still within their TTL
+    pub registry_fresh: usize,
+    pub registry: RegistryCacheStats,
+}
+
+/// Cache for npm registry metadata keyed by package name, or GitHub API responses
+/// keyed by request.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegistryCache {
+    entries: HashMap<String, CachedRegistryEntry>,
+    ttl: Duration,
+    #[serde(default)]
+    stats: RegistryCacheStats,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedRegistryEntry {
+    body: serde_json::Value,
+    etag: Option<String>,
+    cached_at: u64, // Unix timestamp
+}
+
+impl RegistryCache {
+    const CACHE_FILE: &'static str = "registry_cache.json";
+    const GITHUB_CACHE_FILE: &'static str = "github_cache.json";
+    /// How long registry metadata is used before it is revalidated
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(900); // 15 minutes
+
+    fn load(fs: &dyn FileSystem, cache_dir: &Path, file: &str) -> Result<Self> {
+        read_state_in(fs, &cache_dir.join(file), Self::new)
+    }
+
+    fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl: Self::DEFAULT_TTL,
+            stats: RegistryCacheStats::default(),
+        }
+    }
+
+    fn fresh(&self, package: &str, now: u64) -> Option<&serde_json::Value> {
+        self.entries
+            .get(package)
+            .filter(|entry| now.saturating_sub(entry.cached_at) <= self.ttl.as_secs())
+            .map(|entry| &entry.body)
+    }
+
+    fn etag(&self, package: &str) -> Option<&str> {
+        self.entries.get(package)?.etag.as_deref()
+    }
+
+    /// Mark a revalidated entry fresh again, returning its body
+    fn touch(&mut self, package: &str, now: u64) -> Option<serde_json::Value> {
+        let entry = self.entries.get_mut(package)?;
+        entry.cached_at = now;
+        Some(entry.body.clone())
+    }
+
+    fn insert(
+        &mut self,
+        package: String,
+        body: serde_json::Value,
+        etag: Option<String>,
+        cached_at: u64,
+    ) {
+        self.entries.insert(
+            package,
+            CachedRegistryEntry {
+                body,
+                etag,
+                cached_at,
+            },
+        );
+    }
+
+    /// The body for `key`: fresh from the cache, revalidated with its ETag, or fetched
+    fn lookup(
+        &mut self,
+        key: &str,
+        now: u64,
+        fetch: impl FnOnce(Option<&str>) -> Result<RegistryResponse>,
+    ) -> Result<serde_json::Value> {
+        if let Some(body) = self.fresh(key, now) {
+            let body = body.clone();
+            self.stats.hits += 1;
+            return Ok(body);
+        }
+
+        let etag = self.etag(key).map(str::to_string);
+        match fetch(etag.as_deref())? {
+            RegistryResponse::NotModified => {
+                self.stats.revalidated += 1;
+                self.touch(key, now)
+                    .ok_or_else(|| anyhow::anyhow!("Server sent 304 for uncached {key}"))
+            }
+            RegistryResponse::Fetched { body, etag } => {
+                self.stats.misses += 1;
+                self.insert(key.to_string(), body.clone(), etag, now);
+                Ok(body)
+            }
+        }
+    }
+
+    fn save(&self, fs: &dyn FileSystem, cache_dir: &Path, file: &str) -> Result<()> {
+        write_state_in(fs, &cache_dir.join(file), self)
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.stats = RegistryCacheStats::default();
     }
 }
 
@@ -454,4 +707,145 @@ mod tests {
         let cached = cache_manager.get_dependency_status(&dependency);
         assert!(cached.is_none());
     }
+
+    #[test]
+    fn test_expiry_with_mock_clock() {
+        use crate::test_utils::mocks::{MemoryFileSystem, MockClock};
+
+        let clock = Arc::new(MockClock::default());
+        let fs = Arc::new(MemoryFileSystem::new());
+        let cache_dir = PathBuf::from("/cache");
+        let mut cache_manager =
+            CacheManager::open(cache_dir.clone(), clock.clone(), fs.clone()).unwrap();
+
+        let dependency = Dependency::Git;
+        cache_manager
+            .cache_dependency_status(dependency.clone(), DependencyStatus::Missing)
+            .unwrap();
+        cache_manager
+            .cache_server_metadata(
+                "test-server".to_string(),
+                ServerMetadataInfo {
+                    name: "test-server".to_string(),
+                    description: None,
+                    version: None,
+                    dependencies: vec![],
+                    config_schema: None,
+                },
+            )
+            .unwrap();
+
+        // Dependency results last an hour, metadata a day
+        clock.advance(Duration::from_secs(3600));
+        assert!(cache_manager.get_dependency_status(&dependency).is_some());
+        clock.advance(Duration::from_secs(1));
+        assert!(cache_manager.get_dependency_status(&dependency).is_none());
+        assert!(cache_manager.get_server_metadata("test-server").is_some());
+        clock.advance(Duration::from_secs(86400));
+        assert!(cache_manager.get_server_metadata("test-server").is_none());
+
+        // Both caches were saved, and a reopened manager reads them back
+        assert!(fs.exists(&cache_dir.join(DependencyCache::CACHE_FILE)));
+        clock.set(MockClock::default().now());
+        let reopened = CacheManager::open(cache_dir, clock, fs).unwrap();
+        assert!(reopened.get_dependency_status(&dependency).is_some());
+    }
+
+    #[test]
+    fn test_registry_cache_revalidates_with_etag() {
+        use crate::test_utils::mocks::{MemoryFileSystem, MockClock};
+        use serde_json::json;
+
+        let clock = Arc::new(MockClock::default());
+        let fs = Arc::new(MemoryFileSystem::new());
+        let mut cache_manager =
+            CacheManager::open(PathBuf::from("/cache"), clock.clone(), fs).unwrap();
+        let body = json!({ "name": "pkg", "dist-tags": { "latest": "1.0.0" } });
+
+        let fetched = cache_manager
+            .npm_metadata("pkg", |etag| {
+                assert_eq!(etag, None);
+                Ok(RegistryResponse::Fetched {
+                    body: body.clone(),
+                    etag: Some("\"v1\"".to_string()),
+                })
+            })
+            .unwrap();
+        assert_eq!(fetched, body);
+
+        // Fresh entries don't touch the network
+        let cached = cache_manager
+            .npm_metadata("pkg", |_| panic!("fresh entry should be reused"))
+            .unwrap();
+        assert_eq!(cached, body);
+
+        // Stale entries are revalidated with their ETag
+        clock.advance(RegistryCache::DEFAULT_TTL + Duration::from_secs(1));
+        let revalidated = cache_manager
+            .npm_metadata("pkg", |etag| {
+                assert_eq!(etag, Some("\"v1\""));
+                Ok(RegistryResponse::NotModified)
+            })
+            .unwrap();
+        assert_eq!(revalidated, body);
+
+        let stats = cache_manager.stats();
+        assert_eq!(stats.registry_entries, 1);
+        assert_eq!(stats.registry_fresh, 1);
+        assert_eq!(
+            stats.registry,
+            RegistryCacheStats {
+                hits: 1,
+                revalidated: 1,
+                misses: 1,
+            }
+        );
+        assert_eq!(stats.registry.hit_rate(), Some(2.0 / 3.0));
+    }
+
+    #[test]
+    fn test_github_cache_is_kept_apart_from_npm() {
+        use crate::test_utils::mocks::{MemoryFileSystem, MockClock};
+        use serde_json::json;
+
+        let clock = Arc::new(MockClock::default());
+        let fs = Arc::new(MemoryFileSystem::new());
+        let mut cache_manager =
+            CacheManager::open(PathBuf::from("/cache"), clock, fs.clone()).unwrap();
+
+        cache_manager
+            .github_metadata("owner/repo", |_| {
+                Ok(RegistryResponse::Fetched {
+                    body: json!({ "stars": 1 }),
+                    etag: None,
+                })
+            })
+            .unwrap();
+
+        // The same key in the npm cache is a separate entry
+        let npm = cache_manager
+            .npm_metadata("owner/repo", |_| {
+                Ok(RegistryResponse::Fetched {
+                    body: json!({ "name": "repo" }),
+                    etag: None,
+                })
+            })
+            .unwrap();
+        assert_eq!(npm, json!({ "name": "repo" }));
+        assert_eq!(cache_manager.stats().registry_entries, 1);
+        assert!(fs.exists(Path::new("/cache/github_cache.json")));
+
+        cache_manager.clear_all().unwrap();
+        assert!(!fs.exists(Path::new("/cache/github_cache.json")));
+    }
+
+    #[test]
+    fn test_not_modified_without_cache_is_an_error() {
+        assert!(RegistryResponse::NotModified.into_body().is_err());
+        let body = RegistryResponse::Fetched {
+            body: serde_json::json!(1),
+            etag: None,
+        };
+        assert_eq!(body.into_body().unwrap(), serde_json::json!(1));
+    }
 }
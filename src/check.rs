@@ -0,0 +1,560 @@
+//! `mcp check --sync`: drift between mcp-helper's record and client configs.
+//!
+//! The managed servers record (`managed-servers.json`) is mcp-helper's lockfile:
+//! which servers it added or imported, the package and version each runs, and
+//! which clients have them. Editing a client config by hand lets the two drift
+//! apart. [`find_drift`] lists servers a client has that the record doesn't,
+//! recorded servers a client no longer has, and servers whose package or version
+//! changed. The differences can then be resolved either way: update the record
+//! from the configs, or reconcile the configs with the record.
+
+use colored::Colorize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::client::{detect_clients, McpClient, ServerConfig};
+use crate::config::{ConfigManager, ManagedServer, ManagedServers, ServerKind};
+use crate::error::McpError;
+use crate::update::{find_npm_package_spec, with_package_version};
+use crate::utils::confirm::Confirmer;
+use crate::utils::prompt::Select;
+
+/// How one server in one client differs from the record
+#[derive(Debug, Clone, PartialEq)]
+pub enum DriftKind {
+    /// The client has a server the record doesn't list for it
+    Added(ServerConfig),
+    /// The record lists the server for the client, but its config doesn't have it
+    Removed,
+    /// The client runs another package or version than recorded
+    Changed {
+        recorded: ServerKind,
+        configured: ServerConfig,
+    },
+}
+
+/// A difference between the record and one client's config
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    pub server: String,
+    pub client: String,
+    pub kind: DriftKind,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DriftKind::Added(config) => write!(
+                f,
+                "{} {} in {}: {}, not in the record",
+                "+".green(),
+                self.server,
+                self.client,
+                ServerKind::infer(config)
+            ),
+            DriftKind::Removed => write!(
+                f,
+                "{} {} in {}: recorded, but missing from the config",
+                "-".red(),
+                self.server,
+                self.client
+            ),
+            DriftKind::Changed {
+                recorded,
+                configured,
+            } => {
+                let configured = ServerKind::infer(configured);
+                let what = match (recorded, &configured) {
+                    (ServerKind::Npm { package: a, .. }, ServerKind::Npm { package: b, .. })
+                        if a == b =>
+                    {
+                        "version drift"
+                    }
+                    _ => "changed",
+                };
+                write!(
+                    f,
+                    "{} {} in {}: {what}, recorded {recorded}, configured {configured}",
+                    "~".yellow(),
+                    self.server,
+                    self.client
+                )
+            }
+        }
+    }
+}
+
+/// Which side wins when resolving drift
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Update the record from what the client configs contain
+    UpdateRecord,
+    /// Change the client configs to match the record
+    Reconcile,
+}
+
+/// Differences between `record` and the servers of each installed client in
+/// `configs`, sorted by server and client. Clients missing from `configs` are
+/// not installed and can't be compared.
+pub fn find_drift(
+    record: &BTreeMap<String, ManagedServer>,
+    configs: &[(String, HashMap<String, ServerConfig>)],
+) -> Vec<Drift> {
+    let mut drift = Vec::new();
+    for (client, servers) in configs {
+        for (name, config) in servers {
+            let recorded = record
+                .get(name)
+                .filter(|server| server.clients.contains(client));
+            let kind = match recorded {
+                None => DriftKind::Added(config.clone()),
+                Some(server) if server.kind != ServerKind::infer(config) => DriftKind::Changed {
+                    recorded: server.kind.clone(),
+                    configured: config.clone(),
+                },
+                Some(_) => continue,
+            };
+            drift.push(Drift {
+                server: name.clone(),
+                client: client.clone(),
+                kind,
+            });
+        }
+    }
+    for (name, server) in record {
+        for client in &server.clients {
+            let Some((_, servers)) = configs.iter().find(|(c, _)| c == client) else {
+                continue;
+            };
+            if !servers.contains_key(name) {
+                drift.push(Drift {
+                    server: name.clone(),
+                    client: client.clone(),
+                    kind: DriftKind::Removed,
+                });
+            }
+        }
+    }
+    drift.sort_by(|a, b| (&a.server, &a.client).cmp(&(&b.server, &b.client)));
+    drift
+}
+
+/// The config that brings a client back in line with the recorded `server`:
+/// another client's matching config, the config `mcp import` found, or one
+/// rebuilt from the recorded kind
+pub fn recorded_config(
+    name: &str,
+    server: &ManagedServer,
+    configs: &[(String, HashMap<String, ServerConfig>)],
+) -> ServerConfig {
+    let matching = configs
+        .iter()
+        .filter_map(|(_, servers)| servers.get(name))
+        .find(|config| ServerKind::infer(config) == server.kind);
+    if let Some(config) = matching {
+        return config.clone();
+    }
+    if let Some(config) = server
+        .imported_config
+        .as_ref()
+        .filter(|config| ServerKind::infer(config) == server.kind)
+    {
+        return config.clone();
+    }
+
+    let (command, args) = match &server.kind {
+        ServerKind::Npm { package, version } => (
+            "npx".to_string(),
+            vec![
+                "-y".to_string(),
+                match version {
+                    Some(version) => format!("{package}@{version}"),
+                    None => package.clone(),
+                },
+            ],
+        ),
+        ServerKind::Docker { image } => (
+            "docker".to_string(),
+            vec![
+                "run".to_string(),
+                "-i".to_string(),
+                "--rm".to_string(),
+                image.clone(),
+            ],
+        ),
+        ServerKind::Python { package } => ("uvx".to_string(), vec![package.clone()]),
+        ServerKind::Binary { command } => (command.clone(), Vec::new()),
+    };
+    ServerConfig {
+        command,
+        args,
+        env: HashMap::new(),
+        cwd: None,
+    }
+}
+
+/// `configured` changed back to the recorded kind, keeping its arguments and
+/// environment when only the npm version differs
+fn reconciled_config(
+    name: &str,
+    server: &ManagedServer,
+    configured: &ServerConfig,
+    configs: &[(String, HashMap<String, ServerConfig>)],
+) -> ServerConfig {
+    if let (ServerKind::Npm { package, version }, Some(spec)) =
+        (&server.kind, find_npm_package_spec(configured))
+    {
+        if &spec.package == package {
+            return match version {
+                Some(version) => with_package_version(configured, &spec, version),
+                None => {
+                    let mut config = configured.clone();
+                    config.args[spec.arg_index] = package.clone();
+                    config
+                }
+            };
+        }
+    }
+    recorded_config(name, server, configs)
+}
+
+/// Compare the record with client configs and resolve the differences
+pub struct CheckCommand {
+    verbose: bool,
+    resolution: Option<Resolution>,
+    yes: bool,
+    interactive: bool,
+}
+
+impl CheckCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            resolution: None,
+            yes: false,
+            interactive: std::io::IsTerminal::is_terminal(&std::io::stdin()),
+        }
+    }
+
+    /// Resolve drift this way instead of asking
+    pub fn with_resolution(mut self, resolution: Option<Resolution>) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Change client configs without asking for confirmation
+    pub fn with_yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
+
+    pub fn execute_sync(&self) -> Result<(), McpError> {
+        let managed = ManagedServers::new().map_err(McpError::Other)?;
+        let record = managed.list().map_err(McpError::Other)?;
+        let clients = detect_clients();
+        let configs: Vec<(String, HashMap<String, ServerConfig>)> = clients
+            .iter()
+            .filter(|client| client.is_installed())
+            .map(|client| {
+                client
+                    .list_servers()
+                    .map(|servers| (client.name().to_string(), servers))
+                    .map_err(|e| {
+                        McpError::Other(
+                            e.context(format!("Failed to read {} configuration", client.name())),
+                        )
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        println!("{}", "🔍 Record vs client configs".blue().bold());
+        if self.verbose {
+            println!("  {}", managed.path().display().to_string().dimmed());
+        }
+        println!();
+
+        let drift = find_drift(&record, &configs);
+        if drift.is_empty() {
+            println!(
+                "{} The record matches {} client config(s)",
+                "✓".green(),
+                configs.len()
+            );
+            return Ok(());
+        }
+        for difference in &drift {
+            println!("  {difference}");
+        }
+        println!();
+
+        let resolution = match self.resolution {
+            Some(resolution) => Some(resolution),
+            None if self.interactive => self.ask_resolution()?,
+            None => None,
+        };
+        match resolution {
+            Some(Resolution::UpdateRecord) => self.update_record(&managed, &record, &drift)?,
+            Some(Resolution::Reconcile) => {
+                if !Confirmer::new(self.yes).confirm(&format!(
+                    "Change {} client config entr{} to match the record?",
+                    drift.len(),
+                    if drift.len() == 1 { "y" } else { "ies" }
+                ))? {
+                    println!("{} Nothing changed", "❌".red());
+                    return Err(drift_error(drift.len()));
+                }
+                self.reconcile(&clients, &record, &configs, &drift)?;
+            }
+            None => return Err(drift_error(drift.len())),
+        }
+        Ok(())
+    }
+
+    fn ask_resolution(&self) -> Result<Option<Resolution>, McpError> {
+        let items = [
+            "Update the record from the client configs",
+            "Change the client configs to match the record",
+            "Leave as is",
+        ];
+        let choice = Select::new()
+            .with_prompt("How should the differences be resolved?")
+            .items(&items)
+            .default(0)
+            .interact()?;
+        Ok(match choice {
+            0 => Some(Resolution::UpdateRecord),
+            1 => Some(Resolution::Reconcile),
+            _ => None,
+        })
+    }
+
+    fn update_record(
+        &self,
+        managed: &ManagedServers,
+        record: &BTreeMap<String, ManagedServer>,
+        drift: &[Drift],
+    ) -> Result<(), McpError> {
+        for difference in drift {
+            let (server, client) = (&difference.server, &difference.client);
+            let result = match &difference.kind {
+                DriftKind::Added(config) if !record.contains_key(server) => {
+                    managed.record_imported(server, config, client)
+                }
+                DriftKind::Added(config)
+                | DriftKind::Changed {
+                    configured: config, ..
+                } => managed.record_added(server, config, client),
+                DriftKind::Removed => managed.forget(server, client),
+            };
+            result.map_err(McpError::Other)?;
+        }
+        println!(
+            "{} Updated the record from {} difference(s)",
+            "✓".green(),
+            drift.len()
+        );
+        Ok(())
+    }
+
+    fn reconcile(
+        &self,
+        clients: &[Box<dyn McpClient>],
+        record: &BTreeMap<String, ManagedServer>,
+        configs: &[(String, HashMap<String, ServerConfig>)],
+        drift: &[Drift],
+    ) -> Result<(), McpError> {
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+        let mut failed = 0;
+        for difference in drift {
+            let Some(client) = clients.iter().find(|c| c.name() == difference.client) else {
+                continue;
+            };
+            let name = &difference.server;
+            let result = match (&difference.kind, record.get(name)) {
+                (DriftKind::Added(_), _) => {
+                    manager.remove_config(client.as_ref(), name).map(|_| ())
+                }
+                (DriftKind::Removed, Some(server)) => manager
+                    .apply_config(
+                        client.as_ref(),
+                        name,
+                        recorded_config(name, server, configs),
+                    )
+                    .map(|_| ()),
+                (DriftKind::Changed { configured, .. }, Some(server)) => manager
+                    .apply_config(
+                        client.as_ref(),
+                        name,
+                        reconciled_config(name, server, configured, configs),
+                    )
+                    .map(|_| ()),
+                (_, None) => continue,
+            };
+            match result {
+                Ok(()) => println!("  {} {} in {}", "✓".green(), name, client.name()),
+                Err(e) => {
+                    failed += 1;
+                    println!(
+                        "  {} {} in {} - {}",
+                        "✗".red(),
+                        name,
+                        client.name(),
+                        e.to_string().dimmed()
+                    );
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Failed to reconcile {} entr{}; run the command again to retry",
+                failed,
+                if failed == 1 { "y" } else { "ies" }
+            )));
+        }
+        println!("{} Client configs match the record", "✓".green());
+        Ok(())
+    }
+}
+
+fn drift_error(count: usize) -> McpError {
+    McpError::Other(anyhow::anyhow!(
+        "{} difference(s) between the record and client configs; run 'mcp check --sync --update-lockfile' or '--reconcile' to resolve them",
+        count
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ManagedOrigin;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    fn managed(kind: ServerKind, clients: &[&str]) -> ManagedServer {
+        ManagedServer {
+            kind,
+            origin: ManagedOrigin::Added,
+            clients: clients.iter().map(|c| c.to_string()).collect(),
+            imported_config: None,
+            channel: Default::default(),
+            publisher: None,
+            instance_of: None,
+            schema: None,
+            recorded_at: chrono::Utc::now(),
+        }
+    }
+
+    fn npm(package: &str, version: Option<&str>) -> ServerKind {
+        ServerKind::Npm {
+            package: package.to_string(),
+            version: version.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_find_drift() {
+        let record = BTreeMap::from([
+            (
+                "github".to_string(),
+                managed(npm("@org/github", Some("1.0.0")), &["Cursor", "VS Code"]),
+            ),
+            (
+                "fetch".to_string(),
+                managed(npm("@org/fetch", None), &["Cursor", "Windsurf"]),
+            ),
+        ]);
+        let configs = vec![
+            (
+                "Cursor".to_string(),
+                HashMap::from([
+                    (
+                        "github".to_string(),
+                        config("npx", &["-y", "@org/github@1.1.0"]),
+                    ),
+                    ("fetch".to_string(), config("npx", &["-y", "@org/fetch"])),
+                    ("notes".to_string(), config("node", &["notes.js"])),
+                ]),
+            ),
+            ("VS Code".to_string(), HashMap::new()),
+        ];
+
+        let drift = find_drift(&record, &configs);
+        let summary: Vec<(&str, &str, &str)> = drift
+            .iter()
+            .map(|d| {
+                let kind = match d.kind {
+                    DriftKind::Added(_) => "added",
+                    DriftKind::Removed => "removed",
+                    DriftKind::Changed { .. } => "changed",
+                };
+                (d.server.as_str(), d.client.as_str(), kind)
+            })
+            .collect();
+        // Windsurf isn't installed, so its missing fetch isn't drift
+        assert_eq!(
+            summary,
+            [
+                ("github", "Cursor", "changed"),
+                ("github", "VS Code", "removed"),
+                ("notes", "Cursor", "added"),
+            ]
+        );
+        assert!(drift[0].to_string().contains("version drift"));
+        assert!(drift[0]
+            .to_string()
+            .contains("recorded npm @org/github@1.0.0"));
+    }
+
+    #[test]
+    fn test_recorded_config_prefers_a_matching_client() {
+        let server = managed(npm("@org/github", Some("1.0.0")), &["Cursor", "VS Code"]);
+        let cursor = config("npx", &["-y", "@org/github@1.0.0", "--read-only"]);
+        let configs = vec![(
+            "Cursor".to_string(),
+            HashMap::from([("github".to_string(), cursor.clone())]),
+        )];
+        assert_eq!(recorded_config("github", &server, &configs), cursor);
+
+        let rebuilt = recorded_config("github", &server, &[]);
+        assert_eq!(rebuilt.command, "npx");
+        assert_eq!(rebuilt.args, ["-y", "@org/github@1.0.0"]);
+
+        let docker = managed(
+            ServerKind::Docker {
+                image: "mcp/fetch".to_string(),
+            },
+            &["Cursor"],
+        );
+        assert_eq!(
+            recorded_config("fetch", &docker, &[]).args,
+            ["run", "-i", "--rm", "mcp/fetch"]
+        );
+    }
+
+    #[test]
+    fn test_reconciled_config_only_changes_the_version() {
+        let server = managed(npm("@org/github", Some("1.0.0")), &["Cursor"]);
+        let mut configured = config("npx", &["-y", "@org/github@1.1.0", "--read-only"]);
+        configured
+            .env
+            .insert("GITHUB_TOKEN".to_string(), "${keychain:github}".to_string());
+
+        let reconciled = reconciled_config("github", &server, &configured, &[]);
+        assert_eq!(reconciled.args, ["-y", "@org/github@1.0.0", "--read-only"]);
+        assert_eq!(reconciled.env, configured.env);
+
+        let unpinned = managed(npm("@org/github", None), &["Cursor"]);
+        let reconciled = reconciled_config("github", &unpinned, &configured, &[]);
+        assert_eq!(reconciled.args[1], "@org/github");
+    }
+}
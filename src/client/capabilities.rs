@@ -0,0 +1,375 @@
+//! What each client can do with a server entry's configuration values.
+//!
+//! Every client accepts an `env` block, but they differ beyond that. VS Code and
+//! Cursor expand `${env:NAME}` from their own environment, Claude Code expands
+//! `${NAME}`, and VS Code can prompt for `${input:ID}` values and store them
+//! securely. Claude Desktop and Windsurf only take literal values.
+//!
+//! [`place_secrets`] uses these capabilities to decide where each secret-looking
+//! value goes for a given client:
+//!
+//! - Secrets passed to Docker as `-e NAME=value` move into the `env` block and
+//!   leave `-e NAME` behind, so they don't show up in process listings
+//! - A secret whose value matches the variable already set in this shell becomes
+//!   a reference, in clients that expand them, so it never lands in the config
+//! - Everything else stays a literal `env` value
+//!
+//! In clients with input prompts, [`prompt_for_secrets`] can instead replace the
+//! remaining literals with `${input:ID}` placeholders, so the client asks for
+//! each secret and keeps it out of the config file.
+
+use std::collections::HashMap;
+
+use crate::client::ServerConfig;
+use crate::server::docker::is_docker_command;
+use crate::utils::secrets::{is_secret_name, is_variable_reference, looks_like_secret_value};
+
+/// How a client references variables from its own environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvReferenceSyntax {
+    /// `${env:NAME}` (VS Code, Cursor)
+    Prefixed,
+    /// `${NAME}` (Claude Code)
+    Bare,
+}
+
+impl EnvReferenceSyntax {
+    pub fn reference(&self, name: &str) -> String {
+        match self {
+            Self::Prefixed => format!("${{env:{name}}}"),
+            Self::Bare => format!("${{{name}}}"),
+        }
+    }
+}
+
+/// Ways a client can hand configuration values to a server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientCapabilities {
+    /// Entries have an `env` block
+    pub env: bool,
+    /// Syntax for variables the client expands from its own environment
+    pub env_reference: Option<EnvReferenceSyntax>,
+    /// `${input:ID}` placeholders prompt the user and store the answer securely
+    pub input_prompts: bool,
+}
+
+impl Default for ClientCapabilities {
+    /// Literal `env` values only
+    fn default() -> Self {
+        Self {
+            env: true,
+            env_reference: None,
+            input_prompts: false,
+        }
+    }
+}
+
+/// Where a secret ended up in a client's entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretPlacement {
+    /// A literal value in the `env` block
+    Env,
+    /// A reference to the client's environment
+    EnvReference(String),
+    /// Moved out of Docker `-e` arguments into the `env` block
+    MovedToEnv,
+    /// An `${input:ID}` placeholder the client prompts for
+    Input(String),
+}
+
+impl SecretPlacement {
+    /// A note for the user when `name` was placed somewhere other than a literal
+    pub fn describe(&self, name: &str) -> Option<String> {
+        match self {
+            Self::Env => None,
+            Self::EnvReference(reference) => Some(format!(
+                "{name} is read from your environment as {reference}"
+            )),
+            Self::MovedToEnv => Some(format!(
+                "{name} moved from docker -e arguments to the env block"
+            )),
+            Self::Input(reference) => Some(format!(
+                "{name} is asked for when the server starts ({reference})"
+            )),
+        }
+    }
+}
+
+/// Whether a variable holds something that should not be visible in args or configs
+fn is_secret(name: &str, value: &str) -> bool {
+    !is_variable_reference(value) && (is_secret_name(name) || looks_like_secret_value(value))
+}
+
+/// Rewrite `config` so its secrets use the best mechanism `capabilities` offers.
+///
+/// `current_env` looks up variables in the environment `mcp` runs in. Returns the
+/// rewritten config and, sorted by name, where each secret was placed.
+pub fn place_secrets(
+    mut config: ServerConfig,
+    capabilities: ClientCapabilities,
+    current_env: &dyn Fn(&str) -> Option<String>,
+) -> (ServerConfig, Vec<(String, SecretPlacement)>) {
+    let mut placements = HashMap::new();
+
+    if capabilities.env && is_docker_command(&config.command) {
+        let mut args = Vec::with_capacity(config.args.len());
+        let mut iter = config.args.into_iter();
+        while let Some(arg) = iter.next() {
+            if arg != "-e" && arg != "--env" {
+                args.push(arg);
+                continue;
+            }
+            args.push(arg);
+            let Some(assignment) = iter.next() else { break };
+            match assignment.split_once('=') {
+                Some((name, value)) if is_secret(name, value) => {
+                    config.env.insert(name.to_string(), value.to_string());
+                    placements.insert(name.to_string(), SecretPlacement::MovedToEnv);
+                    args.push(name.to_string());
+                }
+                _ => args.push(assignment),
+            }
+        }
+        config.args = args;
+    }
+
+    if let Some(syntax) = capabilities.env_reference {
+        for (name, value) in config.env.iter_mut() {
+            if !is_secret(name, value) || current_env(name).as_deref() != Some(value.as_str()) {
+                continue;
+            }
+            *value = syntax.reference(name);
+            placements.insert(name.clone(), SecretPlacement::EnvReference(value.clone()));
+        }
+    }
+
+    for (name, value) in &config.env {
+        if is_secret(name, value) {
+            placements
+                .entry(name.clone())
+                .or_insert(SecretPlacement::Env);
+        }
+    }
+
+    let mut placements: Vec<_> = placements.into_iter().collect();
+    placements.sort_by(|a, b| a.0.cmp(&b.0));
+    (config, placements)
+}
+
+/// Names of `env` values that hold literal secrets, sorted
+pub fn literal_secrets(config: &ServerConfig) -> Vec<String> {
+    let mut names: Vec<String> = config
+        .env
+        .iter()
+        .filter(|(name, value)| is_secret(name, value))
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Replace literal secrets with `${input:ID}` placeholders the client prompts for
+pub fn prompt_for_secrets(
+    mut config: ServerConfig,
+    server_name: &str,
+) -> (ServerConfig, Vec<(String, SecretPlacement)>) {
+    let names = literal_secrets(&config);
+    let mut placements = Vec::with_capacity(names.len());
+    for name in names {
+        let reference = input_reference(&input_id(server_name, &name));
+        config.env.insert(name.clone(), reference.clone());
+        placements.push((name, SecretPlacement::Input(reference)));
+    }
+    (config, placements)
+}
+
+/// Ask whether `client` should prompt for `secrets` instead of storing them
+pub fn offer_input_prompts(client: &str, secrets: &[String]) -> bool {
+    crate::utils::prompt::Confirm::new()
+        .with_prompt(format!(
+            "Let {client} prompt for {} instead of saving it in its config?",
+            secrets.join(", ")
+        ))
+        .default(true)
+        .interact()
+        .unwrap_or(false)
+}
+
+/// Input id for variable `name` of `server_name`, e.g. `github-github_token`
+pub fn input_id(server_name: &str, name: &str) -> String {
+    let server: String = server_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{}-{}", server.trim_matches('-'), name.to_lowercase())
+}
+
+pub fn input_reference(id: &str) -> String {
+    format!("${{input:{id}}}")
+}
+
+/// Ids of every `${input:ID}` placeholder in `value`
+pub fn input_ids(value: &str) -> Vec<&str> {
+    let mut ids = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${input:") {
+        let after = &rest[start + "${input:".len()..];
+        let Some(end) = after.find('}') else { break };
+        ids.push(after[..end].trim());
+        rest = &after[end + 1..];
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(command: &str, args: &[&str], env: &[(&str, &str)]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            cwd: None,
+        }
+    }
+
+    fn shell(name: &str) -> Option<String> {
+        (name == "GITHUB_TOKEN").then(|| "ghp_1234567890abcdef".to_string())
+    }
+
+    #[test]
+    fn test_reference_syntax() {
+        assert_eq!(
+            EnvReferenceSyntax::Prefixed.reference("TOKEN"),
+            "${env:TOKEN}"
+        );
+        assert_eq!(EnvReferenceSyntax::Bare.reference("TOKEN"), "${TOKEN}");
+    }
+
+    #[test]
+    fn test_secret_matching_shell_becomes_reference() {
+        let original = config(
+            "npx",
+            &["-y", "@modelcontextprotocol/server-github"],
+            &[
+                ("GITHUB_TOKEN", "ghp_1234567890abcdef"),
+                ("API_KEY", "typed-in"),
+                ("LOG_LEVEL", "debug"),
+            ],
+        );
+        let capabilities = ClientCapabilities {
+            env_reference: Some(EnvReferenceSyntax::Prefixed),
+            ..Default::default()
+        };
+
+        let (placed, placements) = place_secrets(original.clone(), capabilities, &shell);
+        assert_eq!(placed.env["GITHUB_TOKEN"], "${env:GITHUB_TOKEN}");
+        assert_eq!(placed.env["API_KEY"], "typed-in");
+        assert_eq!(placed.env["LOG_LEVEL"], "debug");
+        assert_eq!(
+            placements,
+            vec![
+                ("API_KEY".to_string(), SecretPlacement::Env),
+                (
+                    "GITHUB_TOKEN".to_string(),
+                    SecretPlacement::EnvReference("${env:GITHUB_TOKEN}".to_string())
+                ),
+            ]
+        );
+
+        // Clients without references keep the literal value
+        let (literal, _) = place_secrets(original.clone(), ClientCapabilities::default(), &shell);
+        assert_eq!(literal, original);
+    }
+
+    #[test]
+    fn test_docker_secrets_move_out_of_args() {
+        let original = config(
+            "docker",
+            &[
+                "run",
+                "-i",
+                "-e",
+                "GITHUB_TOKEN=ghp_1234567890abcdef",
+                "-e",
+                "LOG_LEVEL=debug",
+                "ghcr.io/github/github-mcp-server",
+            ],
+            &[],
+        );
+
+        let (placed, placements) =
+            place_secrets(original, ClientCapabilities::default(), &|_| None);
+        assert_eq!(
+            placed.args,
+            [
+                "run",
+                "-i",
+                "-e",
+                "GITHUB_TOKEN",
+                "-e",
+                "LOG_LEVEL=debug",
+                "ghcr.io/github/github-mcp-server"
+            ]
+        );
+        assert_eq!(placed.env["GITHUB_TOKEN"], "ghp_1234567890abcdef");
+        assert_eq!(
+            placements,
+            vec![("GITHUB_TOKEN".to_string(), SecretPlacement::MovedToEnv)]
+        );
+    }
+
+    #[test]
+    fn test_prompt_for_secrets() {
+        let original = config(
+            "npx",
+            &[],
+            &[
+                ("GITHUB_TOKEN", "ghp_1234567890abcdef"),
+                ("LOG_LEVEL", "debug"),
+            ],
+        );
+
+        let (placed, placements) = prompt_for_secrets(original, "GitHub Server");
+        assert_eq!(
+            placed.env["GITHUB_TOKEN"],
+            "${input:github-server-github_token}"
+        );
+        assert_eq!(placed.env["LOG_LEVEL"], "debug");
+        assert_eq!(placements.len(), 1);
+        assert!(literal_secrets(&placed).is_empty());
+    }
+
+    #[test]
+    fn test_input_ids() {
+        assert_eq!(
+            input_ids("Bearer ${input:token} and ${input: other }"),
+            vec!["token", "other"]
+        );
+        assert!(input_ids("${env:TOKEN}").is_empty());
+        assert!(input_ids("${input:unterminated").is_empty());
+    }
+
+    #[test]
+    fn test_references_are_left_alone() {
+        let original = config("npx", &[], &[("GITHUB_TOKEN", "${input:github_token}")]);
+        let capabilities = ClientCapabilities {
+            env_reference: Some(EnvReferenceSyntax::Prefixed),
+            input_prompts: true,
+            ..Default::default()
+        };
+        let (placed, placements) = place_secrets(original.clone(), capabilities, &shell);
+        assert_eq!(placed, original);
+        assert!(placements.is_empty());
+    }
+}
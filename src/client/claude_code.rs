@@ -1,7 +1,10 @@
+use crate::client::capabilities::{ClientCapabilities, EnvReferenceSyntax};
+use crate::client::working_dir::{remove_stale_wrapper, unwrap_cwd, wrap_for_cwd};
 use crate::client::{
-    get_home_with_fallback, HomeDirectoryProvider, McpClient, RealHomeDirectoryProvider,
-    ServerConfig,
+    apply_command_template, get_home_with_fallback, HomeDirectoryProvider, McpClient,
+    RealHomeDirectoryProvider, ServerConfig,
 };
+use crate::remote::RemoteServerConfig;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -22,7 +25,8 @@ impl ClaudeCodeClient {
         }
     }
 
-    #[cfg(test)]
+    /// Resolve the config path against `home_provider` instead of the real home directory
+    #[cfg(any(test, debug_assertions))]
     pub fn new_with_provider(home_provider: Box<dyn HomeDirectoryProvider>) -> Self {
         Self {
             name: "Claude Code".to_string(),
@@ -54,6 +58,8 @@ impl McpClient for ClaudeCodeClient {
     }
 
     fn add_server(&self, name: &str, config: ServerConfig) -> Result<()> {
+        let config = apply_command_template(self.name(), config);
+        let config = wrap_for_cwd(self.name(), name, config)?;
         let config_path = self.config_path();
 
         // Create directory if it doesn't exist
@@ -63,8 +69,7 @@ impl McpClient for ClaudeCodeClient {
 
         // Read existing config or create new one
         let mut claude_code_config = if config_path.exists() {
-            let content = fs::read_to_string(&config_path)?;
-            crate::utils::json_validator::deserialize_json_safe::<ClaudeCodeConfig>(&content)?
+            crate::utils::config_file::read_json::<ClaudeCodeConfig>(&config_path)?
         } else {
             ClaudeCodeConfig::default()
         };
@@ -87,12 +92,11 @@ impl McpClient for ClaudeCodeClient {
 
         // Add or update server
         if let Some(ref mut servers) = claude_code_config.mcp_servers {
-            servers.insert(name.to_string(), claude_code_server);
+            servers.insert(name.to_string(), ClaudeCodeEntry::Stdio(claude_code_server));
         }
 
         // Write back to file atomically with secure permissions
-        let json = serde_json::to_string_pretty(&claude_code_config)?;
-        crate::utils::secure_file::write_json_secure(&config_path, &json)
+        crate::utils::config_file::write_json(&config_path, &claude_code_config)
             .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
 
         Ok(())
@@ -105,35 +109,154 @@ impl McpClient for ClaudeCodeClient {
             return Ok(HashMap::new());
         }
 
-        let content = fs::read_to_string(&config_path)?;
         let claude_code_config: ClaudeCodeConfig =
-            crate::utils::json_validator::deserialize_json_safe(&content)?;
+            crate::utils::config_file::read_json(&config_path)?;
 
         // Convert from Claude Code's format
         let mut servers = HashMap::new();
         if let Some(mcp_servers) = claude_code_config.mcp_servers {
-            for (name, claude_code_server) in mcp_servers {
+            for (name, entry) in mcp_servers {
+                let ClaudeCodeEntry::Stdio(claude_code_server) = entry else {
+                    continue;
+                };
                 let config = ServerConfig {
                     command: claude_code_server.command,
                     args: claude_code_server.args,
                     env: claude_code_server.env.unwrap_or_default(),
+                    cwd: None,
                 };
-                servers.insert(name, config);
+                servers.insert(name, unwrap_cwd(config));
             }
         }
 
         Ok(servers)
     }
+
+    fn capabilities(&self) -> ClientCapabilities {
+        ClientCapabilities {
+            env_reference: Some(EnvReferenceSyntax::Bare),
+            ..Default::default()
+        }
+    }
+
+    fn add_remote_server(&self, name: &str, remote: &RemoteServerConfig) -> Result<()> {
+        // Secret references can only be resolved by the bridge
+        if remote.uses_secret_references() {
+            return self.add_server(name, remote.bridge_config());
+        }
+
+        let config_path = self.config_path();
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut claude_code_config = if config_path.exists() {
+            crate::utils::config_file::read_json::<ClaudeCodeConfig>(&config_path)?
+        } else {
+            ClaudeCodeConfig::default()
+        };
+
+        claude_code_config
+            .mcp_servers
+            .get_or_insert_with(HashMap::new)
+            .insert(
+                name.to_string(),
+                ClaudeCodeEntry::Remote(ClaudeCodeRemoteServer {
+                    type_: remote.transport_type().to_string(),
+                    url: remote.url.clone(),
+                    headers: remote.headers.clone(),
+                }),
+            );
+
+        crate::utils::config_file::write_json(&config_path, &claude_code_config)
+            .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
+
+        // A previous stdio entry under this name may have left a wrapper behind
+        remove_stale_wrapper(self.name(), name);
+
+        Ok(())
+    }
+
+    fn list_remote_servers(&self) -> Result<HashMap<String, RemoteServerConfig>> {
+        let config_path = self.config_path();
+
+        if !config_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let claude_code_config: ClaudeCodeConfig =
+            crate::utils::config_file::read_json(&config_path)?;
+
+        Ok(claude_code_config
+            .mcp_servers
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(name, entry)| match entry {
+                ClaudeCodeEntry::Remote(remote) => Some((
+                    name,
+                    RemoteServerConfig {
+                        url: remote.url,
+                        headers: remote.headers,
+                    },
+                )),
+                ClaudeCodeEntry::Stdio(_) => None,
+            })
+            .collect())
+    }
+
+    fn remove_server(&self, name: &str) -> Result<bool> {
+        let config_path = self.config_path();
+
+        if !config_path.exists() {
+            return Ok(false);
+        }
+
+        let mut claude_code_config: ClaudeCodeConfig =
+            crate::utils::config_file::read_json(&config_path)?;
+
+        if claude_code_config
+            .mcp_servers
+            .as_mut()
+            .and_then(|servers| servers.remove(name))
+            .is_none()
+        {
+            return Ok(false);
+        }
+
+        crate::utils::config_file::write_json(&config_path, &claude_code_config)
+            .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
+
+        remove_stale_wrapper(self.name(), name);
+
+        Ok(true)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct ClaudeCodeConfig {
     #[serde(rename = "mcpServers", skip_serializing_if = "Option::is_none")]
-    mcp_servers: Option<HashMap<String, ClaudeCodeServer>>,
+    mcp_servers: Option<HashMap<String, ClaudeCodeEntry>>,
     #[serde(flatten)]
     other: serde_json::Value,
 }
 
+/// A stdio server (`command`) or a remote one (`type` plus `url`)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ClaudeCodeEntry {
+    Stdio(ClaudeCodeServer),
+    Remote(ClaudeCodeRemoteServer),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ClaudeCodeRemoteServer {
+    #[serde(rename = "type")]
+    type_: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    headers: HashMap<String, String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ClaudeCodeServer {
     command: String,
@@ -180,6 +303,7 @@ mod tests {
             command: "node".to_string(),
             args: vec!["server.js".to_string()],
             env: HashMap::new(),
+            cwd: None,
         };
 
         let result = client.add_server("test-server", config);
@@ -226,6 +350,7 @@ mod tests {
             command: "npx".to_string(),
             args: vec!["mcp-server".to_string()],
             env,
+            cwd: None,
         };
 
         client.add_server("env-test", config).unwrap();
@@ -273,6 +398,7 @@ mod tests {
             command: "test".to_string(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         };
 
         client.add_server("test-server", config).unwrap();
@@ -360,6 +486,7 @@ mod tests {
             command: "node".to_string(),
             args: vec!["new-server.js".to_string()],
             env,
+            cwd: None,
         };
 
         client.add_server("new-test-server", config).unwrap();
@@ -435,4 +562,43 @@ mod tests {
             "test-key"
         );
     }
+
+    #[test]
+    fn test_claude_code_remote_servers() {
+        let temp_dir = TempDir::new().unwrap();
+        let mock_provider = Box::new(MockHomeDirectoryProvider::new(
+            temp_dir.path().to_path_buf(),
+        ));
+        let client = ClaudeCodeClient::new_with_provider(mock_provider);
+
+        let remote = RemoteServerConfig::new("https://api.example.com/mcp")
+            .unwrap()
+            .with_header("X-Team", "core");
+        client.add_remote_server("remote", &remote).unwrap();
+
+        let secret = RemoteServerConfig::new("https://api.example.com/mcp")
+            .unwrap()
+            .with_header("Authorization", "Bearer ${keychain:example}");
+        client.add_remote_server("bridged", &secret).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join(".claude.json")).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(parsed["mcpServers"]["remote"]["type"], "http");
+        assert_eq!(parsed["mcpServers"]["remote"]["headers"]["X-Team"], "core");
+        assert_eq!(parsed["mcpServers"]["bridged"]["args"][0], "bridge");
+
+        let remotes = client.list_remote_servers().unwrap();
+        assert_eq!(remotes.get("remote"), Some(&remote));
+        assert!(!remotes.contains_key("bridged"));
+
+        let servers = client.list_servers().unwrap();
+        assert!(!servers.contains_key("remote"));
+        assert_eq!(
+            RemoteServerConfig::from_bridge_config(&servers["bridged"]),
+            Some(secret)
+        );
+
+        assert!(client.remove_server("remote").unwrap());
+        assert!(client.list_remote_servers().unwrap().is_empty());
+    }
 }
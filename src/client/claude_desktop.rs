@@ -1,4 +1,5 @@
-use crate::client::{McpClient, ServerConfig};
+use crate::client::working_dir::{remove_stale_wrapper, unwrap_cwd, wrap_for_cwd};
+use crate::client::{apply_command_template, McpClient, ServerConfig};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -35,6 +36,11 @@ impl ClaudeDesktopClient {
         }
     }
 
+    /// Use the config file at `config_path` instead of the platform default
+    pub fn with_config_path(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
     fn get_config_path() -> PathBuf {
         #[cfg(target_os = "windows")]
         {
@@ -93,11 +99,12 @@ impl ClaudeDesktopClient {
             });
         }
 
-        let content = fs::read_to_string(&self.config_path)
-            .with_context(|| format!("Failed to read config from {:#?}", self.config_path))?;
-
-        crate::utils::json_validator::deserialize_json_safe(&content)
-            .with_context(|| format!("Failed to parse JSON from {:#?}", self.config_path))
+        crate::utils::config_file::read_json(&self.config_path).with_context(|| {
+            format!(
+                "Failed to parse JSON from {:#?}; run 'mcp config repair' to fix it",
+                self.config_path
+            )
+        })
     }
 
     fn write_config(&self, config: &ClaudeConfig) -> Result<()> {
@@ -107,17 +114,18 @@ impl ClaudeDesktopClient {
                 .with_context(|| format!("Failed to create directory {parent:?}"))?;
         }
 
+        // Leave the file and its backup alone when nothing changed
+        if crate::utils::config_file::is_current(&self.config_path, config) {
+            return Ok(());
+        }
+
         // Create backup if file exists
         if self.config_path.exists() {
             self.create_backup()?;
         }
 
-        // Serialize with pretty printing
-        let json =
-            serde_json::to_string_pretty(config).context("Failed to serialize config to JSON")?;
-
         // Use secure file writing with proper permissions
-        crate::utils::secure_file::write_json_secure(&self.config_path, &json)
+        crate::utils::config_file::write_json(&self.config_path, config)
             .with_context(|| format!("Failed to write config to {:#?}", self.config_path))?;
 
         Ok(())
@@ -168,6 +176,9 @@ impl McpClient for ClaudeDesktopClient {
     }
 
     fn add_server(&self, name: &str, config: ServerConfig) -> Result<()> {
+        let config = apply_command_template(self.name(), config);
+        let config = wrap_for_cwd(self.name(), name, config)?;
+
         // Validate the server config
         Self::validate_config(&config)?;
 
@@ -200,17 +211,30 @@ impl McpClient for ClaudeDesktopClient {
             if let Ok(mcp_config) = serde_json::from_value::<McpServerConfig>(value) {
                 servers.insert(
                     name,
-                    ServerConfig {
+                    unwrap_cwd(ServerConfig {
                         command: mcp_config.command,
                         args: mcp_config.args,
                         env: mcp_config.env,
-                    },
+                        cwd: None,
+                    }),
                 );
             }
         }
 
         Ok(servers)
     }
+
+    fn remove_server(&self, name: &str) -> Result<bool> {
+        let mut claude_config = self.read_config()?;
+
+        if claude_config.mcp_servers.shift_remove(name).is_none() {
+            return Ok(false);
+        }
+
+        self.write_config(&claude_config)?;
+        remove_stale_wrapper(self.name(), name);
+        Ok(true)
+    }
 }
 
 impl Default for ClaudeDesktopClient {
@@ -376,6 +400,7 @@ mod tests {
             command: String::new(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         };
 
         let result = ClaudeDesktopClient::validate_config(&config);
@@ -392,6 +417,7 @@ mod tests {
             command: "node".to_string(),
             args: vec![],
             env,
+            cwd: None,
         };
 
         let result = ClaudeDesktopClient::validate_config(&config);
@@ -408,6 +434,7 @@ mod tests {
             command: "node".to_string(),
             args: vec![],
             env,
+            cwd: None,
         };
 
         let result = ClaudeDesktopClient::validate_config(&config);
@@ -429,6 +456,7 @@ mod tests {
                 "3000".to_string(),
             ],
             env,
+            cwd: None,
         };
 
         assert!(ClaudeDesktopClient::validate_config(&config).is_ok());
@@ -489,6 +517,7 @@ mod tests {
             command: "python".to_string(),
             args: vec!["-m".to_string(), "mcp_server".to_string()],
             env: HashMap::from([("PYTHONPATH".to_string(), "/app".to_string())]),
+            cwd: None,
         };
 
         client.add_server("test-server", server_config).unwrap();
@@ -567,6 +596,7 @@ mod tests {
             command: "node".to_string(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         };
 
         client.add_server("new-server", server_config).unwrap();
@@ -579,4 +609,35 @@ mod tests {
         let servers = client.list_servers().unwrap();
         assert!(servers.contains_key("new-server"));
     }
+
+    #[test]
+    fn test_remove_server_preserves_other_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.json");
+
+        let json_content = r#"{
+            "mcpServers": {
+                "first": {"command": "node", "args": [], "env": {}},
+                "second": {"command": "node", "args": [], "env": {}},
+                "third": {"command": "node", "args": [], "env": {}}
+            },
+            "otherField": "value"
+        }"#;
+        fs::write(&config_path, json_content).unwrap();
+
+        let client = ClaudeDesktopClient {
+            config_path: config_path.clone(),
+        };
+
+        assert!(client.remove_server("second").unwrap());
+        assert!(!client.remove_server("second").unwrap());
+
+        let config = client.read_config().unwrap();
+        let names: Vec<_> = config.mcp_servers.keys().cloned().collect();
+        assert_eq!(names, vec!["first", "third"]);
+        assert_eq!(
+            config.other.get("otherField"),
+            Some(&serde_json::json!("value"))
+        );
+    }
 }
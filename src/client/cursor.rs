@@ -1,7 +1,10 @@
+use crate::client::capabilities::{ClientCapabilities, EnvReferenceSyntax};
+use crate::client::working_dir::{remove_stale_wrapper, unwrap_cwd, wrap_for_cwd};
 use crate::client::{
-    get_home_with_fallback, HomeDirectoryProvider, McpClient, RealHomeDirectoryProvider,
-    ServerConfig,
+    apply_command_template, get_home_with_fallback, HomeDirectoryProvider, McpClient,
+    RealHomeDirectoryProvider, ServerConfig,
 };
+use crate::remote::RemoteServerConfig;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -22,7 +25,8 @@ impl CursorClient {
         }
     }
 
-    #[cfg(test)]
+    /// Resolve the config path against `home_provider` instead of the real home directory
+    #[cfg(any(test, debug_assertions))]
     pub fn new_with_provider(home_provider: Box<dyn HomeDirectoryProvider>) -> Self {
         Self {
             name: "Cursor".to_string(),
@@ -59,6 +63,8 @@ impl McpClient for CursorClient {
     }
 
     fn add_server(&self, name: &str, config: ServerConfig) -> Result<()> {
+        let config = apply_command_template(self.name(), config);
+        let config = wrap_for_cwd(self.name(), name, config)?;
         let config_path = self.config_path();
 
         // Create directory if it doesn't exist
@@ -68,8 +74,7 @@ impl McpClient for CursorClient {
 
         // Read existing config or create new one
         let mut cursor_config = if config_path.exists() {
-            let content = fs::read_to_string(&config_path)?;
-            crate::utils::json_validator::deserialize_json_safe::<CursorConfig>(&content)?
+            crate::utils::config_file::read_json::<CursorConfig>(&config_path)?
         } else {
             CursorConfig::default()
         };
@@ -85,11 +90,10 @@ impl McpClient for CursorClient {
         // Add or update server
         cursor_config
             .servers
-            .insert(name.to_string(), cursor_server);
+            .insert(name.to_string(), CursorEntry::Stdio(cursor_server));
 
         // Write back to file atomically with secure permissions
-        let json = serde_json::to_string_pretty(&cursor_config)?;
-        crate::utils::secure_file::write_json_secure(&config_path, &json)
+        crate::utils::config_file::write_json(&config_path, &cursor_config)
             .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
 
         Ok(())
@@ -102,28 +106,135 @@ impl McpClient for CursorClient {
             return Ok(HashMap::new());
         }
 
-        let content = fs::read_to_string(&config_path)?;
-        let cursor_config: CursorConfig =
-            crate::utils::json_validator::deserialize_json_safe(&content)?;
+        let cursor_config: CursorConfig = crate::utils::config_file::read_json(&config_path)?;
 
         // Convert from Cursor's format
         let mut servers = HashMap::new();
-        for (name, cursor_server) in cursor_config.servers {
+        for (name, entry) in cursor_config.servers {
+            let CursorEntry::Stdio(cursor_server) = entry else {
+                continue;
+            };
             let config = ServerConfig {
                 command: cursor_server.command,
                 args: cursor_server.args,
                 env: cursor_server.env,
+                cwd: None,
             };
-            servers.insert(name, config);
+            servers.insert(name, unwrap_cwd(config));
         }
 
         Ok(servers)
     }
+
+    fn capabilities(&self) -> ClientCapabilities {
+        ClientCapabilities {
+            env_reference: Some(EnvReferenceSyntax::Prefixed),
+            ..Default::default()
+        }
+    }
+
+    fn add_remote_server(&self, name: &str, remote: &RemoteServerConfig) -> Result<()> {
+        // Secret references can only be resolved by the bridge
+        if remote.uses_secret_references() {
+            return self.add_server(name, remote.bridge_config());
+        }
+
+        let config_path = self.config_path();
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut cursor_config = if config_path.exists() {
+            crate::utils::config_file::read_json::<CursorConfig>(&config_path)?
+        } else {
+            CursorConfig::default()
+        };
+
+        cursor_config.servers.insert(
+            name.to_string(),
+            CursorEntry::Remote(CursorRemoteServer {
+                type_: remote.transport_type().to_string(),
+                url: remote.url.clone(),
+                headers: remote.headers.clone(),
+            }),
+        );
+
+        crate::utils::config_file::write_json(&config_path, &cursor_config)
+            .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
+
+        // A previous stdio entry under this name may have left a wrapper behind
+        remove_stale_wrapper(self.name(), name);
+
+        Ok(())
+    }
+
+    fn list_remote_servers(&self) -> Result<HashMap<String, RemoteServerConfig>> {
+        let config_path = self.config_path();
+
+        if !config_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let cursor_config: CursorConfig = crate::utils::config_file::read_json(&config_path)?;
+
+        Ok(cursor_config
+            .servers
+            .into_iter()
+            .filter_map(|(name, entry)| match entry {
+                CursorEntry::Remote(remote) => Some((
+                    name,
+                    RemoteServerConfig {
+                        url: remote.url,
+                        headers: remote.headers,
+                    },
+                )),
+                CursorEntry::Stdio(_) => None,
+            })
+            .collect())
+    }
+
+    fn remove_server(&self, name: &str) -> Result<bool> {
+        let config_path = self.config_path();
+
+        if !config_path.exists() {
+            return Ok(false);
+        }
+
+        let mut cursor_config: CursorConfig = crate::utils::config_file::read_json(&config_path)?;
+
+        if cursor_config.servers.remove(name).is_none() {
+            return Ok(false);
+        }
+
+        crate::utils::config_file::write_json(&config_path, &cursor_config)
+            .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
+
+        remove_stale_wrapper(self.name(), name);
+
+        Ok(true)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct CursorConfig {
-    servers: HashMap<String, CursorServer>,
+    servers: HashMap<String, CursorEntry>,
+}
+
+/// A stdio server (`command`) or a remote one (`url`)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum CursorEntry {
+    Stdio(CursorServer),
+    Remote(CursorRemoteServer),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CursorRemoteServer {
+    #[serde(rename = "type")]
+    type_: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    headers: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -173,6 +284,7 @@ mod tests {
             command: "node".to_string(),
             args: vec!["server.js".to_string()],
             env: HashMap::new(),
+            cwd: None,
         };
 
         let result = client.add_server("test-server", config);
@@ -198,4 +310,29 @@ mod tests {
         let servers = client.list_servers().unwrap();
         assert!(servers.is_empty());
     }
+
+    #[test]
+    fn test_cursor_remove_server() {
+        let temp_dir = TempDir::new().unwrap();
+        let mock_provider = Box::new(MockHomeDirectoryProvider::new(
+            temp_dir.path().to_path_buf(),
+        ));
+        let client = CursorClient::new_with_provider(mock_provider);
+
+        assert!(!client.remove_server("missing").unwrap());
+
+        let config = ServerConfig {
+            command: "node".to_string(),
+            args: vec!["server.js".to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        client.add_server("keep", config.clone()).unwrap();
+        client.add_server("drop", config).unwrap();
+
+        assert!(client.remove_server("drop").unwrap());
+        let servers = client.list_servers().unwrap();
+        assert!(servers.contains_key("keep"));
+        assert!(!servers.contains_key("drop"));
+    }
 }
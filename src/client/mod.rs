@@ -31,20 +31,28 @@
 //!     command: "npx".to_string(),
 //!     args: vec!["@modelcontextprotocol/server-filesystem".to_string()],
 //!     env: HashMap::new(),
+//!     cwd: None,
 //! };
 //! // client.add_server("filesystem", config)?;
 //! ```
 
+pub mod capabilities;
 pub mod claude_code;
 pub mod claude_desktop;
 pub mod cursor;
+pub mod npx_fallback;
+pub mod running;
 pub mod vscode;
 pub mod windsurf;
+pub mod working_dir;
 
 use anyhow::Result;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::remote::RemoteServerConfig;
+
+pub use capabilities::ClientCapabilities;
 pub use claude_code::ClaudeCodeClient;
 pub use claude_desktop::ClaudeDesktopClient;
 pub use cursor::CursorClient;
@@ -65,6 +73,10 @@ pub struct ServerConfig {
     pub args: Vec<String>,
     /// Environment variables to set when running the server
     pub env: HashMap<String, String>,
+    /// Directory to start the server in. Clients without native support get a
+    /// wrapper script (see [`working_dir`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
 }
 
 /// Trait defining the interface for MCP clients.
@@ -90,6 +102,40 @@ pub trait McpClient: Send + Sync {
 
     /// List all servers currently configured for this client.
     fn list_servers(&self) -> Result<HashMap<String, ServerConfig>>;
+
+    /// How this client can pass configuration values to servers.
+    ///
+    /// The default is a literal `env` block, which every client supports.
+    fn capabilities(&self) -> ClientCapabilities {
+        ClientCapabilities::default()
+    }
+
+    /// Add a remote (streamable HTTP) server to this client.
+    ///
+    /// The default runs the server through `mcp bridge`, which works in any client
+    /// that can launch stdio servers. Clients with native remote support override
+    /// this, but still fall back to the bridge when headers reference secrets.
+    fn add_remote_server(&self, name: &str, remote: &RemoteServerConfig) -> Result<()> {
+        self.add_server(name, remote.bridge_config())
+    }
+
+    /// List the remote servers this client connects to natively.
+    ///
+    /// Bridged remote servers are stdio servers and appear in [`list_servers`](Self::list_servers).
+    fn list_remote_servers(&self) -> Result<HashMap<String, RemoteServerConfig>> {
+        Ok(HashMap::new())
+    }
+
+    /// Remove a server configuration from this client.
+    ///
+    /// Returns `Ok(true)` if the server was removed, or `Ok(false)` if no server
+    /// with that name was configured.
+    fn remove_server(&self, name: &str) -> Result<bool> {
+        anyhow::bail!(
+            "{} does not support removing servers (tried to remove '{name}')",
+            self.name()
+        )
+    }
 }
 
 /// Registry for managing multiple MCP clients.
@@ -142,6 +188,66 @@ impl ClientRegistry {
             .find(|client| client.name().eq_ignore_ascii_case(name))
             .map(|client| client.as_ref())
     }
+
+    /// The installed clients called `names` (case-insensitive), in the order given.
+    ///
+    /// Fails naming the installed clients if one of `names` isn't among them.
+    pub fn get_installed_by_names(&self, names: &[String]) -> Result<Vec<&dyn McpClient>> {
+        let mut selected: Vec<&dyn McpClient> = Vec::new();
+        for name in names {
+            match self
+                .get_by_name(name)
+                .filter(|client| client.is_installed())
+            {
+                Some(client) => {
+                    if !selected.iter().any(|s| s.name() == client.name()) {
+                        selected.push(client);
+                    }
+                }
+                None => {
+                    let installed: Vec<&str> = self
+                        .detect_installed()
+                        .iter()
+                        .map(|client| client.name())
+                        .collect();
+                    anyhow::bail!(
+                        "Client '{}' is not installed. Detected clients: {}",
+                        name,
+                        if installed.is_empty() {
+                            "none".to_string()
+                        } else {
+                            installed.join(", ")
+                        }
+                    );
+                }
+            }
+        }
+        Ok(selected)
+    }
+}
+
+/// Which clients to configure when they are given on the command line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ClientTarget {
+    /// Ask, or take every installed client when not interactive
+    #[default]
+    Prompt,
+    /// The clients named with `--client`
+    Named(Vec<String>),
+    /// Every installed client (`--all-clients`)
+    All,
+}
+
+impl ClientTarget {
+    pub fn from_flags(names: Vec<String>, all: bool) -> Self {
+        if all {
+            Self::All
+        } else if names.is_empty() {
+            Self::Prompt
+        } else {
+            Self::Named(names)
+        }
+    }
 }
 
 impl Default for ClientRegistry {
@@ -205,6 +311,47 @@ pub fn get_home_with_fallback(provider: &dyn HomeDirectoryProvider) -> PathBuf {
     })
 }
 
+/// Apply the user's command template for a client, if one is configured.
+///
+/// Clients call this right before serializing a [`ServerConfig`] so that
+/// per-client invocation patterns (e.g. wrapping in `cmd /c`) are honored.
+/// Without a template, a bare `npx` is resolved on Windows, see [`npx_fallback`].
+pub fn apply_command_template(client_name: &str, config: ServerConfig) -> ServerConfig {
+    match crate::config::Preferences::load() {
+        Ok(prefs) => match prefs.command_template(client_name) {
+            Some(template) => template.apply(&config),
+            None => npx_fallback::resolve_for_platform(config),
+        },
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable preferences: {}", e);
+            npx_fallback::resolve_for_platform(config)
+        }
+    }
+}
+
+/// Names of the `clients` where `name` already belongs to a server running
+/// something other than `config`, so adding `config` as `name` would replace it
+pub fn clients_with_name_taken<'a>(
+    clients: impl IntoIterator<Item = &'a dyn McpClient>,
+    name: &str,
+    config: &ServerConfig,
+) -> Vec<String> {
+    let kind = crate::config::ServerKind::infer(config);
+    clients
+        .into_iter()
+        .filter(|client| {
+            client
+                .list_servers()
+                .ok()
+                .and_then(|mut servers| servers.remove(name))
+                .is_some_and(|existing| {
+                    !crate::config::ServerKind::infer(&existing).is_same_server(&kind)
+                })
+        })
+        .map(|client| client.name().to_string())
+        .collect()
+}
+
 /// Detect and return all available MCP clients.
 ///
 /// This function creates instances of all supported MCP client implementations
@@ -230,6 +377,7 @@ pub fn get_home_with_fallback(provider: &dyn HomeDirectoryProvider) -> PathBuf {
 ///     }
 /// }
 /// ```
+#[tracing::instrument]
 pub fn detect_clients() -> Vec<Box<dyn McpClient>> {
     let mut registry = ClientRegistry::new();
 
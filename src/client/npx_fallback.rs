@@ -0,0 +1,236 @@
+//! How Windows client configs start npx.
+//!
+//! Clients spawn a server's `command` directly, and several of them don't apply
+//! `PATHEXT`, so a bare `npx` that works in a terminal fails to start because
+//! the file on disk is `npx.cmd`. Before a config is written on Windows, a bare
+//! `npx` is replaced by the first invocation that exists:
+//!
+//! 1. the absolute path of `npx.cmd`
+//! 2. `npm.cmd exec`, for installs without the npx shim
+//! 3. `node` with npm's `npx-cli.js`, when neither shim is on `PATH`
+//!
+//! `mcp doctor` reports existing entries that still use a bare `npx` and offers
+//! to rewrite them the same way.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::client::ServerConfig;
+
+/// A way to run npx that doesn't depend on the client resolving `.cmd` files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NpxInvocation {
+    /// The `npx.cmd` shim at this path
+    NpxCmd(PathBuf),
+    /// `npm.cmd exec` at this path
+    NpmExec(PathBuf),
+    /// `node` running npm's `npx-cli.js` directly
+    NodeCli { node: PathBuf, cli: PathBuf },
+}
+
+impl NpxInvocation {
+    /// The first invocation available on this machine's `PATH`
+    pub fn detect() -> Option<Self> {
+        Self::detect_with(|program| which::which(program).ok(), Path::exists)
+    }
+
+    /// The first invocation `find` can locate. `exists` is used for `npx-cli.js`,
+    /// which sits next to `node` rather than on `PATH`
+    pub fn detect_with(
+        find: impl Fn(&str) -> Option<PathBuf>,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Option<Self> {
+        if let Some(npx) = find("npx.cmd") {
+            return Some(Self::NpxCmd(npx));
+        }
+        if let Some(npm) = find("npm.cmd") {
+            return Some(Self::NpmExec(npm));
+        }
+        let node = find("node.exe").or_else(|| find("node"))?;
+        let cli = node
+            .parent()?
+            .join("node_modules")
+            .join("npm")
+            .join("bin")
+            .join("npx-cli.js");
+        exists(&cli).then_some(Self::NodeCli { node, cli })
+    }
+
+    /// `config` with its bare `npx` command replaced by this invocation, or `None`
+    /// when the config doesn't start a bare `npx`
+    pub fn rewrite(&self, config: &ServerConfig) -> Option<ServerConfig> {
+        if !uses_bare_npx(config) {
+            return None;
+        }
+        let (command, args) = match self {
+            Self::NpxCmd(npx) => (npx.display().to_string(), config.args.clone()),
+            Self::NpmExec(npm) => (npm.display().to_string(), npm_exec_args(&config.args)),
+            Self::NodeCli { node, cli } => {
+                let mut args = vec![cli.display().to_string()];
+                args.extend(config.args.iter().cloned());
+                (node.display().to_string(), args)
+            }
+        };
+        Some(ServerConfig {
+            command,
+            args,
+            ..config.clone()
+        })
+    }
+}
+
+impl fmt::Display for NpxInvocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NpxCmd(npx) => write!(f, "{}", npx.display()),
+            Self::NpmExec(npm) => write!(f, "{} exec", npm.display()),
+            Self::NodeCli { node, cli } => write!(f, "{} {}", node.display(), cli.display()),
+        }
+    }
+}
+
+/// Whether `config` runs `npx` by a name that relies on `PATHEXT`, i.e. without
+/// a directory or extension. `cmd /c npx` is fine, cmd.exe resolves it
+pub fn uses_bare_npx(config: &ServerConfig) -> bool {
+    config.command.eq_ignore_ascii_case("npx")
+}
+
+/// On Windows, `config` with a bare `npx` replaced by the best available
+/// invocation. Other platforms and configs are returned unchanged
+pub fn resolve_for_platform(config: ServerConfig) -> ServerConfig {
+    if !cfg!(windows) || !uses_bare_npx(&config) {
+        return config;
+    }
+    match NpxInvocation::detect().and_then(|invocation| invocation.rewrite(&config)) {
+        Some(resolved) => resolved,
+        None => {
+            tracing::warn!("No npx.cmd, npm.cmd or node found; writing npx unchanged");
+            config
+        }
+    }
+}
+
+/// npx arguments as `npm exec` arguments: npx's own options first, then `--` so
+/// the package's options aren't taken for npm's
+fn npm_exec_args(args: &[String]) -> Vec<String> {
+    let mut exec_args = vec!["exec".to_string()];
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        if !arg.starts_with('-') {
+            exec_args.push("--".to_string());
+            exec_args.push(arg.clone());
+            break;
+        }
+        exec_args.push(arg.clone());
+        if matches!(arg.as_str(), "-p" | "--package" | "-c" | "--call") {
+            exec_args.extend(rest.next().cloned());
+        }
+    }
+    exec_args.extend(rest.cloned());
+    exec_args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    fn node_dir() -> PathBuf {
+        PathBuf::from(r"C:\Program Files\nodejs")
+    }
+
+    #[test]
+    fn test_detect_prefers_npx_cmd() {
+        let all = |program: &str| Some(node_dir().join(program));
+        assert_eq!(
+            NpxInvocation::detect_with(all, |_| true),
+            Some(NpxInvocation::NpxCmd(node_dir().join("npx.cmd")))
+        );
+
+        let no_npx = |program: &str| (program != "npx.cmd").then(|| node_dir().join(program));
+        assert_eq!(
+            NpxInvocation::detect_with(no_npx, |_| true),
+            Some(NpxInvocation::NpmExec(node_dir().join("npm.cmd")))
+        );
+    }
+
+    #[test]
+    fn test_detect_falls_back_to_node_cli() {
+        let node_only = |program: &str| (program == "node.exe").then(|| node_dir().join(program));
+        let cli = node_dir().join("node_modules/npm/bin/npx-cli.js");
+        assert_eq!(
+            NpxInvocation::detect_with(node_only, |path| path.ends_with("npx-cli.js")),
+            Some(NpxInvocation::NodeCli {
+                node: node_dir().join("node.exe"),
+                cli,
+            })
+        );
+        assert_eq!(NpxInvocation::detect_with(node_only, |_| false), None);
+        assert_eq!(NpxInvocation::detect_with(|_| None, |_| true), None);
+    }
+
+    #[test]
+    fn test_rewrite() {
+        let server = config("npx", &["-y", "@scope/server", "--port", "3000"]);
+
+        let npx = NpxInvocation::NpxCmd(node_dir().join("npx.cmd"));
+        let rewritten = npx.rewrite(&server).unwrap();
+        assert!(rewritten.command.ends_with("npx.cmd"));
+        assert_eq!(rewritten.args, server.args);
+
+        let npm = NpxInvocation::NpmExec(node_dir().join("npm.cmd"));
+        assert_eq!(
+            npm.rewrite(&server).unwrap().args,
+            ["exec", "-y", "--", "@scope/server", "--port", "3000"]
+        );
+
+        let node = NpxInvocation::NodeCli {
+            node: node_dir().join("node.exe"),
+            cli: node_dir().join("npx-cli.js"),
+        };
+        let rewritten = node.rewrite(&server).unwrap();
+        assert!(rewritten.command.ends_with("node.exe"));
+        assert!(rewritten.args[0].ends_with("npx-cli.js"));
+        assert_eq!(rewritten.args[1..], server.args[..]);
+    }
+
+    #[test]
+    fn test_rewrite_leaves_resolved_commands() {
+        let npx = NpxInvocation::NpxCmd(node_dir().join("npx.cmd"));
+        assert!(npx.rewrite(&config("npx.cmd", &["server"])).is_none());
+        assert!(npx
+            .rewrite(&config("cmd", &["/c", "npx", "server"]))
+            .is_none());
+        assert!(npx.rewrite(&config("node", &["server.js"])).is_none());
+        assert!(npx.rewrite(&config("NPX", &["server"])).is_some());
+    }
+
+    #[test]
+    fn test_npm_exec_args_keep_option_values() {
+        let args: Vec<String> = ["--package", "@scope/tools", "-y", "tool", "-v"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            npm_exec_args(&args),
+            [
+                "exec",
+                "--package",
+                "@scope/tools",
+                "-y",
+                "--",
+                "tool",
+                "-v"
+            ]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Which MCP clients are running right now.
+//!
+//! When several clients are installed, the install and add prompts preselect the
+//! ones that are running instead of all of them, since a client nobody has open
+//! is the one most likely to be selected by accident. The defaults are chosen in
+//! this order:
+//!
+//! 1. `clients.default` in preferences, when it names an installed client
+//! 2. Installed clients with a running process, or the terminal mcp-helper runs
+//!    in (Claude Code sets `CLAUDECODE`, VS Code and Cursor set `TERM_PROGRAM`)
+//! 3. Every installed client, when none of them is running
+//!
+//! Processes are listed with `ps` or `tasklist`; if that fails, nothing counts
+//! as running and the prompt falls back to selecting everything.
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::utils::process::Process;
+
+/// How long listing processes may take before it is given up on
+const PROCESS_LIST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Executable names of each client's main process, without `.exe`
+pub fn process_names(client: &str) -> &'static [&'static str] {
+    match client {
+        "Claude Desktop" => &["Claude", "claude-desktop"],
+        "Claude Code" => &["claude"],
+        "Cursor" => &["Cursor", "cursor"],
+        "VS Code" => &["Code", "code", "code-insiders"],
+        "Windsurf" => &["Windsurf", "windsurf"],
+        _ => &[],
+    }
+}
+
+/// Whether the terminal mcp-helper runs in belongs to `client`
+fn is_host_terminal(client: &str, env: &dyn Fn(&str) -> Option<String>) -> bool {
+    match client {
+        "Claude Code" => env("CLAUDECODE").is_some(),
+        // Cursor's terminal reports itself as VS Code too
+        "Cursor" => env("CURSOR_TRACE_ID").is_some(),
+        "VS Code" => {
+            env("TERM_PROGRAM").as_deref() == Some("vscode") && env("CURSOR_TRACE_ID").is_none()
+        }
+        _ => false,
+    }
+}
+
+/// Executable names of every running process, without directories or `.exe`
+pub fn running_processes() -> Vec<String> {
+    let process = if cfg!(windows) {
+        Process::new("tasklist").args(["/fo", "csv", "/nh"])
+    } else {
+        Process::new("ps").args(["-A", "-o", "comm="])
+    };
+    match process.timeout(PROCESS_LIST_TIMEOUT).output() {
+        Ok(output) if output.success() => parse_process_list(&output.stdout_lossy()),
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            tracing::debug!("Failed to list running processes: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Process names from `ps -o comm=` lines or `tasklist /fo csv` rows
+pub fn parse_process_list(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let command = match line.strip_prefix('"') {
+                Some(rest) => rest.split('"').next()?,
+                None => line,
+            };
+            let name = Path::new(command).file_name()?.to_string_lossy();
+            let name = name.strip_suffix(".exe").unwrap_or(&name);
+            (!name.is_empty()).then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// The installed `clients` that are running, given the running `processes`
+pub fn running_clients<'a>(
+    clients: &[&'a str],
+    processes: &[String],
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Vec<&'a str> {
+    clients
+        .iter()
+        .copied()
+        .filter(|client| {
+            is_host_terminal(client, env)
+                || process_names(client)
+                    .iter()
+                    .any(|name| processes.iter().any(|process| process == name))
+        })
+        .collect()
+}
+
+/// Which of `clients` to preselect: the `preferred` ones if any are installed,
+/// else the `running` ones, else all of them
+pub fn default_selection(clients: &[&str], running: &[&str], preferred: &[String]) -> Vec<bool> {
+    let is_preferred = |client: &str| {
+        preferred
+            .iter()
+            .any(|name| name.eq_ignore_ascii_case(client))
+    };
+    if clients.iter().any(|client| is_preferred(client)) {
+        return clients.iter().map(|client| is_preferred(client)).collect();
+    }
+    if !running.is_empty() {
+        return clients
+            .iter()
+            .map(|client| running.contains(client))
+            .collect();
+    }
+    vec![true; clients.len()]
+}
+
+/// [`default_selection`] for this machine: its preferences, processes and terminal
+pub fn detect_default_selection(clients: &[&str]) -> Vec<bool> {
+    let preferred = crate::config::Preferences::load()
+        .map(|preferences| preferences.clients.default)
+        .unwrap_or_default();
+    if clients.len() < 2 {
+        return default_selection(clients, &[], &preferred);
+    }
+    let processes = running_processes();
+    let running = running_clients(clients, &processes, &|name| std::env::var(name).ok());
+    default_selection(clients, &running, &preferred)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENTS: &[&str] = &["Claude Desktop", "Cursor", "VS Code"];
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn test_parse_ps_and_tasklist_output() {
+        let ps = "/sbin/launchd\n/Applications/Claude.app/Contents/MacOS/Claude\n  code\n";
+        assert_eq!(parse_process_list(ps), vec!["launchd", "Claude", "code"]);
+
+        let tasklist = "\"Cursor.exe\",\"1234\",\"Console\",\"1\",\"120,000 K\"\r\n\"svchost.exe\",\"88\",\"Services\",\"0\",\"9,000 K\"\r\n";
+        assert_eq!(parse_process_list(tasklist), vec!["Cursor", "svchost"]);
+    }
+
+    #[test]
+    fn test_running_clients_from_processes() {
+        let processes = vec!["launchd".to_string(), "Cursor".to_string()];
+        assert_eq!(
+            running_clients(CLIENTS, &processes, &no_env),
+            vec!["Cursor"]
+        );
+        assert!(running_clients(CLIENTS, &[], &no_env).is_empty());
+    }
+
+    #[test]
+    fn test_running_clients_from_terminal() {
+        let vscode = |name: &str| (name == "TERM_PROGRAM").then(|| "vscode".to_string());
+        assert_eq!(running_clients(CLIENTS, &[], &vscode), vec!["VS Code"]);
+
+        let cursor = |name: &str| match name {
+            "TERM_PROGRAM" => Some("vscode".to_string()),
+            "CURSOR_TRACE_ID" => Some("abc".to_string()),
+            _ => None,
+        };
+        assert_eq!(running_clients(CLIENTS, &[], &cursor), vec!["Cursor"]);
+    }
+
+    #[test]
+    fn test_default_selection() {
+        // Nothing running: everything, as before
+        assert_eq!(default_selection(CLIENTS, &[], &[]), vec![true, true, true]);
+        // Running clients only
+        assert_eq!(
+            default_selection(CLIENTS, &["Cursor"], &[]),
+            vec![false, true, false]
+        );
+        // Preferences win over running clients
+        assert_eq!(
+            default_selection(CLIENTS, &["Cursor"], &["claude desktop".to_string()]),
+            vec![true, false, false]
+        );
+        // Preferences naming only clients that aren't installed are ignored
+        assert_eq!(
+            default_selection(CLIENTS, &["Cursor"], &["Windsurf".to_string()]),
+            vec![false, true, false]
+        );
+    }
+}
@@ -1,10 +1,13 @@
+use crate::client::capabilities::{input_ids, ClientCapabilities, EnvReferenceSyntax};
+use crate::client::working_dir::{normalize_cwd, remove_stale_wrapper};
 use crate::client::{
-    get_home_with_fallback, HomeDirectoryProvider, McpClient, RealHomeDirectoryProvider,
-    ServerConfig,
+    apply_command_template, get_home_with_fallback, HomeDirectoryProvider, McpClient,
+    RealHomeDirectoryProvider, ServerConfig,
 };
+use crate::remote::RemoteServerConfig;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -23,7 +26,8 @@ impl VSCodeClient {
         }
     }
 
-    #[cfg(test)]
+    /// Resolve the config path against `home_provider` instead of the real home directory
+    #[cfg(any(test, debug_assertions))]
     pub fn new_with_provider(home_provider: Box<dyn HomeDirectoryProvider>) -> Self {
         Self {
             name: "VS Code".to_string(),
@@ -114,6 +118,7 @@ impl McpClient for VSCodeClient {
     }
 
     fn add_server(&self, name: &str, config: ServerConfig) -> Result<()> {
+        let config = apply_command_template(self.name(), config);
         // Check for GitHub Copilot requirement
         if !self.check_copilot_installed() {
             eprintln!("⚠️  Warning: VS Code MCP support requires GitHub Copilot extension");
@@ -129,28 +134,31 @@ impl McpClient for VSCodeClient {
 
         // Read existing config or create new one
         let mut vscode_config = if config_path.exists() {
-            let content = fs::read_to_string(&config_path)?;
-            crate::utils::json_validator::deserialize_json_safe::<VSCodeConfig>(&content)?
+            crate::utils::config_file::read_json::<VSCodeConfig>(&config_path)?
         } else {
             VSCodeConfig::default()
         };
 
-        // Convert to VS Code's format
+        // Convert to VS Code's format; VS Code honors `cwd` itself
+        let cwd = config.cwd.as_deref().map(normalize_cwd).transpose()?;
         let vscode_server = VSCodeServer {
             type_: "stdio".to_string(),
             command: config.command,
             args: config.args,
             env: config.env,
+            cwd,
         };
 
+        // Define any `${input:...}` the entry uses so VS Code knows to prompt for it
+        add_missing_inputs(&mut vscode_config.inputs, name, &vscode_server);
+
         // Add or update server
         vscode_config
             .servers
-            .insert(name.to_string(), vscode_server);
+            .insert(name.to_string(), VSCodeEntry::Stdio(vscode_server));
 
         // Write back to file atomically with secure permissions
-        let json = serde_json::to_string_pretty(&vscode_config)?;
-        crate::utils::secure_file::write_json_secure(&config_path, &json)
+        crate::utils::config_file::write_json(&config_path, &vscode_config)
             .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
 
         println!("📝 Note: VS Code MCP servers are only available in GitHub Copilot Agent mode");
@@ -165,28 +173,209 @@ impl McpClient for VSCodeClient {
             return Ok(HashMap::new());
         }
 
-        let content = fs::read_to_string(&config_path)?;
-        let vscode_config: VSCodeConfig =
-            crate::utils::json_validator::deserialize_json_safe(&content)?;
+        let vscode_config: VSCodeConfig = crate::utils::config_file::read_json(&config_path)?;
 
         // Convert from VS Code's format
         let mut servers = HashMap::new();
-        for (name, vscode_server) in vscode_config.servers {
+        for (name, entry) in vscode_config.servers {
+            let VSCodeEntry::Stdio(vscode_server) = entry else {
+                continue;
+            };
             let config = ServerConfig {
                 command: vscode_server.command,
                 args: vscode_server.args,
                 env: vscode_server.env,
+                cwd: vscode_server.cwd,
             };
             servers.insert(name, config);
         }
 
         Ok(servers)
     }
+
+    fn capabilities(&self) -> ClientCapabilities {
+        ClientCapabilities {
+            env_reference: Some(EnvReferenceSyntax::Prefixed),
+            input_prompts: true,
+            ..Default::default()
+        }
+    }
+
+    fn add_remote_server(&self, name: &str, remote: &RemoteServerConfig) -> Result<()> {
+        // Secret references can only be resolved by the bridge
+        if remote.uses_secret_references() {
+            return self.add_server(name, remote.bridge_config());
+        }
+
+        let config_path = self.config_path();
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut vscode_config = if config_path.exists() {
+            crate::utils::config_file::read_json::<VSCodeConfig>(&config_path)?
+        } else {
+            VSCodeConfig::default()
+        };
+
+        vscode_config.servers.insert(
+            name.to_string(),
+            VSCodeEntry::Remote(VSCodeRemoteServer {
+                type_: remote.transport_type().to_string(),
+                url: remote.url.clone(),
+                headers: remote.headers.clone(),
+            }),
+        );
+
+        crate::utils::config_file::write_json(&config_path, &vscode_config)
+            .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
+
+        // A previous stdio entry under this name may have left a wrapper behind
+        remove_stale_wrapper(self.name(), name);
+
+        Ok(())
+    }
+
+    fn list_remote_servers(&self) -> Result<HashMap<String, RemoteServerConfig>> {
+        let config_path = self.config_path();
+
+        if !config_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let vscode_config: VSCodeConfig = crate::utils::config_file::read_json(&config_path)?;
+
+        Ok(vscode_config
+            .servers
+            .into_iter()
+            .filter_map(|(name, entry)| match entry {
+                VSCodeEntry::Remote(remote) => Some((
+                    name,
+                    RemoteServerConfig {
+                        url: remote.url,
+                        headers: remote.headers,
+                    },
+                )),
+                VSCodeEntry::Stdio(_) => None,
+            })
+            .collect())
+    }
+
+    fn remove_server(&self, name: &str) -> Result<bool> {
+        let config_path = self.config_path();
+
+        if !config_path.exists() {
+            return Ok(false);
+        }
+
+        let mut vscode_config: VSCodeConfig = crate::utils::config_file::read_json(&config_path)?;
+
+        let Some(removed) = vscode_config.servers.remove(name) else {
+            return Ok(false);
+        };
+        if let VSCodeEntry::Stdio(server) = &removed {
+            remove_unused_inputs(&mut vscode_config, server);
+        }
+
+        crate::utils::config_file::write_json(&config_path, &vscode_config)
+            .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
+
+        Ok(true)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct VSCodeConfig {
-    servers: HashMap<String, VSCodeServer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    inputs: Vec<VSCodeInput>,
+    servers: HashMap<String, VSCodeEntry>,
+}
+
+/// A value VS Code asks the user for, referenced as `${input:ID}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct VSCodeInput {
+    #[serde(rename = "type")]
+    type_: String,
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    password: bool,
+    /// Fields of hand-written inputs (`options`, `default`, ...) we don't interpret
+    #[serde(flatten)]
+    extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Input ids a server's command, args and env refer to
+fn server_input_ids(server: &VSCodeServer) -> HashSet<String> {
+    std::iter::once(&server.command)
+        .chain(&server.args)
+        .chain(server.env.values())
+        .flat_map(|value| input_ids(value.as_str()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Add a `promptString` input for every `${input:ID}` that has no definition
+fn add_missing_inputs(inputs: &mut Vec<VSCodeInput>, server_name: &str, server: &VSCodeServer) {
+    let mut variables: Vec<(&String, &String)> = server.env.iter().collect();
+    variables.sort();
+
+    for id in server_input_ids(server) {
+        if inputs.iter().any(|input| input.id == id) {
+            continue;
+        }
+        let variable = variables
+            .iter()
+            .find(|(_, value)| input_ids(value).contains(&id.as_str()))
+            .map(|(name, _)| name.as_str());
+        let description = match variable {
+            Some(variable) => format!("{variable} for the {server_name} MCP server"),
+            None => format!("Value for the {server_name} MCP server"),
+        };
+        inputs.push(VSCodeInput {
+            type_: "promptString".to_string(),
+            id,
+            description: Some(description),
+            password: variable.is_some_and(crate::utils::secrets::is_secret_name),
+            extra: serde_json::Map::new(),
+        });
+    }
+    inputs.sort_by(|a, b| a.id.cmp(&b.id));
+}
+
+/// Drop inputs only `removed` used
+fn remove_unused_inputs(config: &mut VSCodeConfig, removed: &VSCodeServer) {
+    let still_used: HashSet<String> = config
+        .servers
+        .values()
+        .filter_map(|entry| match entry {
+            VSCodeEntry::Stdio(server) => Some(server_input_ids(server)),
+            VSCodeEntry::Remote(_) => None,
+        })
+        .flatten()
+        .collect();
+    let orphaned = server_input_ids(removed);
+    config
+        .inputs
+        .retain(|input| still_used.contains(&input.id) || !orphaned.contains(&input.id));
+}
+
+/// A stdio server (`command`) or a remote one (`url`)
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum VSCodeEntry {
+    Stdio(VSCodeServer),
+    Remote(VSCodeRemoteServer),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct VSCodeRemoteServer {
+    #[serde(rename = "type")]
+    type_: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    headers: HashMap<String, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -196,6 +385,8 @@ struct VSCodeServer {
     command: String,
     args: Vec<String>,
     env: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cwd: Option<String>,
 }
 
 #[cfg(test)]
@@ -236,6 +427,7 @@ mod tests {
             command: "python".to_string(),
             args: vec!["server.py".to_string()],
             env: HashMap::new(),
+            cwd: None,
         };
 
         let result = client.add_server("test-server", config);
@@ -264,6 +456,7 @@ mod tests {
             command: "deno".to_string(),
             args: vec!["run".to_string(), "server.ts".to_string()],
             env: HashMap::new(),
+            cwd: None,
         };
 
         client.add_server("deno-server", config).unwrap();
@@ -278,6 +471,129 @@ mod tests {
         assert_eq!(server.args, vec!["run", "server.ts"]);
     }
 
+    #[test]
+    fn test_vscode_keeps_remote_and_stdio_servers_apart() {
+        let temp_dir = TempDir::new().unwrap();
+        let mock_provider = Box::new(MockHomeDirectoryProvider::new(
+            temp_dir.path().to_path_buf(),
+        ));
+        let client = VSCodeClient::new_with_provider(mock_provider);
+
+        let remote = RemoteServerConfig::new("https://api.example.com/mcp").unwrap();
+        client.add_remote_server("remote", &remote).unwrap();
+        let config = ServerConfig {
+            command: "deno".to_string(),
+            args: vec!["run".to_string(), "server.ts".to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        client.add_server("deno-server", config).unwrap();
+
+        let content = fs::read_to_string(client.config_path()).unwrap();
+        assert!(content.contains("\"type\": \"http\""));
+        assert!(!content.contains("headers"));
+
+        let servers = client.list_servers().unwrap();
+        assert_eq!(servers.len(), 1);
+        assert!(servers.contains_key("deno-server"));
+        assert_eq!(
+            client.list_remote_servers().unwrap().get("remote"),
+            Some(&remote)
+        );
+
+        assert!(client.remove_server("remote").unwrap());
+        assert!(client.list_remote_servers().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_vscode_defines_inputs_for_placeholders() {
+        let temp_dir = TempDir::new().unwrap();
+        let mock_provider = Box::new(MockHomeDirectoryProvider::new(
+            temp_dir.path().to_path_buf(),
+        ));
+        let client = VSCodeClient::new_with_provider(mock_provider);
+
+        // A hand-written input is kept as it is
+        fs::create_dir_all(temp_dir.path().join(".vscode")).unwrap();
+        fs::write(
+            client.config_path(),
+            r#"{"inputs":[{"type":"pickString","id":"region","options":["eu","us"]}],"servers":{}}"#,
+        )
+        .unwrap();
+
+        let mut env = HashMap::new();
+        env.insert(
+            "GITHUB_TOKEN".to_string(),
+            "${input:github-github_token}".to_string(),
+        );
+        env.insert("REGION".to_string(), "${input:region}".to_string());
+        let config = ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["@modelcontextprotocol/server-github".to_string()],
+            env,
+            cwd: None,
+        };
+        client.add_server("github", config.clone()).unwrap();
+        client.add_server("github-copy", config).unwrap();
+
+        let content = fs::read_to_string(client.config_path()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let inputs = parsed["inputs"].as_array().unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[0]["id"], "github-github_token");
+        assert_eq!(inputs[0]["type"], "promptString");
+        assert_eq!(inputs[0]["password"], true);
+        assert_eq!(inputs[1]["options"], serde_json::json!(["eu", "us"]));
+
+        // Inputs stay while another server uses them
+        assert!(client.remove_server("github").unwrap());
+        let content = fs::read_to_string(client.config_path()).unwrap();
+        assert!(content.contains("github-github_token"));
+
+        assert!(client.remove_server("github-copy").unwrap());
+        let content = fs::read_to_string(client.config_path()).unwrap();
+        assert!(!content.contains("github-github_token"));
+        assert!(!content.contains("\"region\""));
+    }
+
+    #[test]
+    fn test_vscode_writes_cwd_natively() {
+        let temp_dir = TempDir::new().unwrap();
+        let mock_provider = Box::new(MockHomeDirectoryProvider::new(
+            temp_dir.path().to_path_buf(),
+        ));
+        let client = VSCodeClient::new_with_provider(mock_provider);
+        let project = temp_dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+
+        let config = ServerConfig {
+            command: "node".to_string(),
+            args: vec!["server.js".to_string()],
+            env: HashMap::new(),
+            cwd: Some(format!("{}/", project.display())),
+        };
+        client.add_server("local", config.clone()).unwrap();
+
+        let servers = client.list_servers().unwrap();
+        assert_eq!(servers["local"].command, "node");
+        assert_eq!(
+            servers["local"].cwd.as_deref(),
+            Some(project.to_string_lossy().as_ref())
+        );
+
+        let missing = ServerConfig {
+            cwd: Some(
+                temp_dir
+                    .path()
+                    .join("missing")
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            ..config
+        };
+        assert!(client.add_server("broken", missing).is_err());
+    }
+
     #[test]
     fn test_check_copilot_installed() {
         let client = VSCodeClient::new();
@@ -1,7 +1,9 @@
+use crate::client::working_dir::{remove_stale_wrapper, unwrap_cwd, wrap_for_cwd};
 use crate::client::{
-    get_home_with_fallback, HomeDirectoryProvider, McpClient, RealHomeDirectoryProvider,
-    ServerConfig,
+    apply_command_template, get_home_with_fallback, HomeDirectoryProvider, McpClient,
+    RealHomeDirectoryProvider, ServerConfig,
 };
+use crate::remote::RemoteServerConfig;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -22,7 +24,8 @@ impl WindsurfClient {
         }
     }
 
-    #[cfg(test)]
+    /// Resolve the config path against `home_provider` instead of the real home directory
+    #[cfg(any(test, debug_assertions))]
     pub fn new_with_provider(home_provider: Box<dyn HomeDirectoryProvider>) -> Self {
         Self {
             name: "Windsurf".to_string(),
@@ -59,6 +62,8 @@ impl McpClient for WindsurfClient {
     }
 
     fn add_server(&self, name: &str, config: ServerConfig) -> Result<()> {
+        let config = apply_command_template(self.name(), config);
+        let config = wrap_for_cwd(self.name(), name, config)?;
         let config_path = self.config_path();
 
         // Create directory if it doesn't exist
@@ -68,8 +73,7 @@ impl McpClient for WindsurfClient {
 
         // Read existing config or create new one
         let mut windsurf_config = if config_path.exists() {
-            let content = fs::read_to_string(&config_path)?;
-            crate::utils::json_validator::deserialize_json_safe::<WindsurfConfig>(&content)?
+            crate::utils::config_file::read_json::<WindsurfConfig>(&config_path)?
         } else {
             WindsurfConfig::default()
         };
@@ -85,6 +89,7 @@ impl McpClient for WindsurfClient {
                 Some(config.env)
             },
             server_url: None, // For local servers
+            headers: None,
         };
 
         // Add or update server
@@ -93,8 +98,7 @@ impl McpClient for WindsurfClient {
             .insert(name.to_string(), windsurf_server);
 
         // Write back to file atomically with secure permissions
-        let json = serde_json::to_string_pretty(&windsurf_config)?;
-        crate::utils::secure_file::write_json_secure(&config_path, &json)
+        crate::utils::config_file::write_json(&config_path, &windsurf_config)
             .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
 
         Ok(())
@@ -107,9 +111,7 @@ impl McpClient for WindsurfClient {
             return Ok(HashMap::new());
         }
 
-        let content = fs::read_to_string(&config_path)?;
-        let windsurf_config: WindsurfConfig =
-            crate::utils::json_validator::deserialize_json_safe(&content)?;
+        let windsurf_config: WindsurfConfig = crate::utils::config_file::read_json(&config_path)?;
 
         // Convert from Windsurf's format
         let mut servers = HashMap::new();
@@ -120,13 +122,103 @@ impl McpClient for WindsurfClient {
                     command,
                     args: windsurf_server.args.unwrap_or_default(),
                     env: windsurf_server.env.unwrap_or_default(),
+                    cwd: None,
                 };
-                servers.insert(name, config);
+                servers.insert(name, unwrap_cwd(config));
             }
         }
 
         Ok(servers)
     }
+
+    fn add_remote_server(&self, name: &str, remote: &RemoteServerConfig) -> Result<()> {
+        // Secret references can only be resolved by the bridge
+        if remote.uses_secret_references() {
+            return self.add_server(name, remote.bridge_config());
+        }
+
+        let config_path = self.config_path();
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut windsurf_config = if config_path.exists() {
+            crate::utils::config_file::read_json::<WindsurfConfig>(&config_path)?
+        } else {
+            WindsurfConfig::default()
+        };
+
+        let windsurf_server = WindsurfServer {
+            command: None,
+            args: None,
+            env: None,
+            server_url: Some(remote.url.clone()),
+            headers: if remote.headers.is_empty() {
+                None
+            } else {
+                Some(remote.headers.clone())
+            },
+        };
+        windsurf_config
+            .mcp_servers
+            .insert(name.to_string(), windsurf_server);
+
+        crate::utils::config_file::write_json(&config_path, &windsurf_config)
+            .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
+
+        // A previous stdio entry under this name may have left a wrapper behind
+        remove_stale_wrapper(self.name(), name);
+
+        Ok(())
+    }
+
+    fn list_remote_servers(&self) -> Result<HashMap<String, RemoteServerConfig>> {
+        let config_path = self.config_path();
+
+        if !config_path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let windsurf_config: WindsurfConfig = crate::utils::config_file::read_json(&config_path)?;
+
+        Ok(windsurf_config
+            .mcp_servers
+            .into_iter()
+            .filter(|(_, server)| server.command.is_none())
+            .filter_map(|(name, server)| {
+                let url = server.server_url?;
+                Some((
+                    name,
+                    RemoteServerConfig {
+                        url,
+                        headers: server.headers.unwrap_or_default(),
+                    },
+                ))
+            })
+            .collect())
+    }
+
+    fn remove_server(&self, name: &str) -> Result<bool> {
+        let config_path = self.config_path();
+
+        if !config_path.exists() {
+            return Ok(false);
+        }
+
+        let mut windsurf_config: WindsurfConfig =
+            crate::utils::config_file::read_json(&config_path)?;
+
+        if windsurf_config.mcp_servers.remove(name).is_none() {
+            return Ok(false);
+        }
+
+        crate::utils::config_file::write_json(&config_path, &windsurf_config)
+            .with_context(|| format!("Failed to write config to {config_path:#?}"))?;
+
+        remove_stale_wrapper(self.name(), name);
+
+        Ok(true)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -145,6 +237,8 @@ struct WindsurfServer {
     env: Option<HashMap<String, String>>,
     #[serde(rename = "serverUrl", skip_serializing_if = "Option::is_none")]
     server_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    headers: Option<HashMap<String, String>>,
 }
 
 #[cfg(test)]
@@ -188,6 +282,7 @@ mod tests {
             command: "npx".to_string(),
             args: vec!["mcp-server".to_string()],
             env,
+            cwd: None,
         };
 
         let result = client.add_server("test-server", config);
@@ -233,6 +328,7 @@ mod tests {
             command: "python3".to_string(),
             args: vec!["-m".to_string(), "server".to_string()],
             env: HashMap::new(),
+            cwd: None,
         };
 
         client.add_server("python-server", config).unwrap();
@@ -249,4 +345,37 @@ mod tests {
         assert!(!content.contains("\"env\": {}"));
         assert!(!content.contains("\"serverUrl\""));
     }
+
+    #[test]
+    fn test_windsurf_remote_servers() {
+        let temp_dir = TempDir::new().unwrap();
+        let mock_provider = Box::new(MockHomeDirectoryProvider::new(
+            temp_dir.path().to_path_buf(),
+        ));
+        let client = WindsurfClient::new_with_provider(mock_provider);
+
+        let remote = RemoteServerConfig::new("https://api.example.com/mcp")
+            .unwrap()
+            .with_header("X-Team", "core");
+        client.add_remote_server("remote", &remote).unwrap();
+
+        let config_path = temp_dir
+            .path()
+            .join(".codeium")
+            .join("windsurf")
+            .join("mcp_config.json");
+        let parsed: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+        assert_eq!(
+            parsed["mcpServers"]["remote"]["serverUrl"],
+            "https://api.example.com/mcp"
+        );
+        assert!(parsed["mcpServers"]["remote"].get("command").is_none());
+
+        assert_eq!(
+            client.list_remote_servers().unwrap().get("remote"),
+            Some(&remote)
+        );
+        assert!(client.list_servers().unwrap().is_empty());
+    }
 }
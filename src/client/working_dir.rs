@@ -0,0 +1,297 @@
+//! Per-server working directories.
+//!
+//! VS Code starts a server in its `cwd` itself. The other clients only know a
+//! command and its arguments, so a server with a working directory is launched
+//! through a small wrapper script that changes directory and then runs the real
+//! command. Wrappers live in `<data dir>/mcp-helper/wrappers` and record the
+//! directory and command in their header, so listing a client's servers reports the
+//! original command and `cwd` rather than the wrapper.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::ServerConfig;
+use crate::audit_log;
+use crate::utils::paths;
+
+const CWD_MARKER: &str = "mcp-helper-cwd: ";
+const COMMAND_MARKER: &str = "mcp-helper-command: ";
+
+/// Validate a working directory and normalize it for this platform.
+///
+/// A leading `~` is expanded, separators are normalized on Windows, and the result
+/// must be an absolute path to an existing directory.
+pub fn normalize_cwd(raw: &str) -> Result<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        anyhow::bail!("Working directory must not be empty");
+    }
+    if trimmed.chars().any(|c| c.is_control() || c == '"') {
+        anyhow::bail!(
+            "Working directory '{}' contains characters that cannot be quoted safely",
+            trimmed
+        );
+    }
+
+    let expanded = match trimmed
+        .strip_prefix("~/")
+        .or_else(|| trimmed.strip_prefix("~\\"))
+        .or_else(|| (trimmed == "~").then_some(""))
+    {
+        Some(rest) => {
+            let dirs = directories::BaseDirs::new().context("Failed to find home directory")?;
+            dirs.home_dir().join(rest)
+        }
+        None => PathBuf::from(trimmed),
+    };
+    let normalized = normalize_separators(&expanded.to_string_lossy(), cfg!(windows));
+
+    let path = Path::new(&normalized);
+    if !path.is_absolute() {
+        anyhow::bail!("Working directory '{}' must be an absolute path", trimmed);
+    }
+    if !path.is_dir() {
+        anyhow::bail!(
+            "Working directory '{}' does not exist or is not a directory",
+            normalized
+        );
+    }
+    Ok(normalized)
+}
+
+/// Use the platform's separator and drop trailing ones (roots such as `/` and `C:\` keep theirs)
+fn normalize_separators(path: &str, windows: bool) -> String {
+    let path = if windows {
+        path.replace('/', "\\")
+    } else {
+        path.to_string()
+    };
+    let separator = if windows { '\\' } else { '/' };
+    let trimmed = path.trim_end_matches(separator);
+    if trimmed.is_empty() || (windows && trimmed.ends_with(':')) {
+        path[..trimmed.len() + 1].to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// A POSIX shell wrapper that starts `command` in `cwd`
+pub fn render_unix_wrapper(cwd: &str, command: &str) -> String {
+    format!(
+        "#!/bin/sh\n# Generated by mcp-helper; do not edit.\n# {CWD_MARKER}{cwd}\n# {COMMAND_MARKER}{command}\ncd {} || exit 1\nexec {} \"$@\"\n",
+        shell_quote(cwd),
+        shell_quote(command)
+    )
+}
+
+/// A batch wrapper that starts `command` in `cwd`
+pub fn render_windows_wrapper(cwd: &str, command: &str) -> String {
+    format!(
+        "@echo off\r\nrem Generated by mcp-helper; do not edit.\r\nrem {CWD_MARKER}{cwd}\r\nrem {COMMAND_MARKER}{command}\r\ncd /d \"{}\" || exit /b 1\r\ncall \"{}\" %*\r\n",
+        cwd.replace('%', "%%"),
+        command.replace('%', "%%")
+    )
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// Replace `config`'s command with a wrapper that starts it in `config.cwd`.
+///
+/// Configs without a working directory are returned unchanged.
+pub fn wrap_for_cwd(
+    client_name: &str,
+    server_name: &str,
+    config: ServerConfig,
+) -> Result<ServerConfig> {
+    let Some(cwd) = config.cwd.as_deref() else {
+        return Ok(config);
+    };
+    let cwd = normalize_cwd(cwd)?;
+
+    let path = wrapper_path(client_name, server_name)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let script = if cfg!(windows) {
+        render_windows_wrapper(&cwd, &config.command)
+    } else {
+        render_unix_wrapper(&cwd, &config.command)
+    };
+    let before = audit_log::file_hash(&path);
+    fs::write(&path, script).with_context(|| format!("Failed to write {}", path.display()))?;
+    audit_log::record_file_write(&path, before);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("Failed to make {} executable", path.display()))?;
+    }
+
+    Ok(ServerConfig {
+        command: path.to_string_lossy().to_string(),
+        args: config.args,
+        env: config.env,
+        cwd: None,
+    })
+}
+
+/// Report a wrapped server with its original command and working directory
+pub fn unwrap_cwd(config: ServerConfig) -> ServerConfig {
+    // Wrappers are always absolute paths; skip the directory lookup for `npx` and the like
+    if !Path::new(&config.command).is_absolute() {
+        return config;
+    }
+    let Ok(dir) = wrappers_dir() else {
+        return config;
+    };
+    if !Path::new(&config.command).starts_with(&dir) {
+        return config;
+    }
+    let Ok(script) = fs::read_to_string(&config.command) else {
+        return config;
+    };
+
+    match parse_wrapper(&script) {
+        Some((cwd, command)) => ServerConfig {
+            command,
+            cwd: Some(cwd),
+            ..config
+        },
+        None => config,
+    }
+}
+
+/// The `(cwd, command)` recorded in a wrapper's header
+pub fn parse_wrapper(script: &str) -> Option<(String, String)> {
+    let field = |marker: &str| {
+        script.lines().find_map(|line| {
+            let line = line
+                .trim_start_matches('#')
+                .trim_start_matches("rem")
+                .trim();
+            line.strip_prefix(marker.trim_end())
+                .map(|v| v.trim().to_string())
+        })
+    };
+    Some((field(CWD_MARKER)?, field(COMMAND_MARKER)?))
+}
+
+/// Delete the wrapper written for `server_name` in `client_name`, if any
+fn remove_wrapper(client_name: &str, server_name: &str) -> Result<()> {
+    let path = wrapper_path(client_name, server_name)?;
+    if path.exists() {
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Delete the wrapper a stdio entry for `server_name` may have left behind once the
+/// entry is removed or replaced by a remote one.
+///
+/// The client config has already been written by then, so a failure is only logged.
+pub fn remove_stale_wrapper(client_name: &str, server_name: &str) {
+    if let Err(e) = remove_wrapper(client_name, server_name) {
+        tracing::warn!("Failed to remove working directory wrapper: {}", e);
+    }
+}
+
+/// Where the wrapper for `server_name` in `client_name` is written
+pub fn wrapper_path(client_name: &str, server_name: &str) -> Result<PathBuf> {
+    let extension = if cfg!(windows) { "cmd" } else { "sh" };
+    Ok(wrappers_dir()?.join(format!(
+        "{}-{}.{extension}",
+        slug(client_name),
+        slug(server_name)
+    )))
+}
+
+fn slug(value: &str) -> String {
+    value
+        .trim_start_matches('@')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// `<data dir>/mcp-helper/wrappers`
+pub fn wrappers_dir() -> Result<PathBuf> {
+    Ok(paths::data_dir()?.join("wrappers"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_separators() {
+        assert_eq!(
+            normalize_separators("C:/work/data/", true),
+            "C:\\work\\data"
+        );
+        assert_eq!(normalize_separators("C:\\", true), "C:\\");
+        assert_eq!(normalize_separators("/srv/data/", false), "/srv/data");
+        assert_eq!(normalize_separators("/", false), "/");
+    }
+
+    #[test]
+    fn test_normalize_cwd_validation() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().to_string_lossy().to_string();
+        assert!(normalize_cwd(&dir).is_ok());
+
+        assert!(normalize_cwd("").is_err());
+        assert!(normalize_cwd("relative/dir").is_err());
+        assert!(normalize_cwd(&format!("{dir}/missing")).is_err());
+        assert!(normalize_cwd(&format!("{dir}\"; rm -rf")).is_err());
+
+        let file = temp_dir.path().join("file.txt");
+        fs::write(&file, "").unwrap();
+        assert!(normalize_cwd(&file.to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn test_wrappers_round_trip() {
+        let unix = render_unix_wrapper("/srv/it's here", "/usr/bin/node");
+        assert!(unix.contains("cd '/srv/it'\\''s here' || exit 1"));
+        assert!(unix.contains("exec '/usr/bin/node' \"$@\""));
+        assert_eq!(
+            parse_wrapper(&unix),
+            Some(("/srv/it's here".to_string(), "/usr/bin/node".to_string()))
+        );
+
+        let windows = render_windows_wrapper("C:\\work\\100%", "npx.cmd");
+        assert!(windows.contains("cd /d \"C:\\work\\100%%\""));
+        assert!(windows.contains("call \"npx.cmd\" %*"));
+        assert_eq!(
+            parse_wrapper(&windows),
+            Some(("C:\\work\\100%".to_string(), "npx.cmd".to_string()))
+        );
+
+        assert!(parse_wrapper("#!/bin/sh\nexec node\n").is_none());
+    }
+
+    #[test]
+    fn test_config_without_cwd_is_not_wrapped() {
+        let config = ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "server".to_string()],
+            env: Default::default(),
+            cwd: None,
+        };
+        assert_eq!(
+            wrap_for_cwd("Cursor", "server", config.clone()).unwrap(),
+            config
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Consistency of server definitions across clients.
+//!
+//! A server name configured differently in two clients (another version, other
+//! arguments) behaves differently depending on which tool starts it.
+//! [`find_divergent`] groups each name's definitions by how they launch the server,
+//! ignoring the differences mcp-helper introduces on purpose:
+//!
+//! - A `cmd /c` wrapper from a command template
+//! - npx options such as `-y` or `--prefer-offline`
+//! - `env` values, which hold client-specific secrets and references (names still count)
+//!
+//! [`harmonized`] turns one chosen definition into the entry for another client.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::client::ServerConfig;
+use crate::utils::paths::program_name;
+
+/// npx options that do not change which server runs
+const NPX_OPTIONS: &[&str] = &["-y", "--yes", "--prefer-offline", "--no-update-notifier"];
+
+/// Clients sharing one definition of a server
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    /// Client names, sorted
+    pub clients: Vec<String>,
+    pub config: ServerConfig,
+}
+
+impl Variant {
+    /// The launch command, e.g. `npx @modelcontextprotocol/server-github@1.2.0`
+    pub fn summary(&self) -> String {
+        let (command, args) = unwrap_cmd(&self.config);
+        std::iter::once(command)
+            .chain(args.into_iter().filter(|arg| !is_npx_option(arg)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A server defined differently by different clients
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub server: String,
+    /// Two or more variants, the most widely used first
+    pub variants: Vec<Variant>,
+}
+
+/// What decides whether two definitions run the same server
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Signature {
+    command: String,
+    args: Vec<String>,
+    env_names: BTreeSet<String>,
+    cwd: Option<String>,
+}
+
+fn signature(config: &ServerConfig) -> Signature {
+    let (command, args) = unwrap_cmd(config);
+    Signature {
+        command: program_name(&command),
+        args: args.into_iter().filter(|arg| !is_npx_option(arg)).collect(),
+        env_names: config.env.keys().cloned().collect(),
+        cwd: config.cwd.clone(),
+    }
+}
+
+/// Servers whose definitions differ between clients, sorted by name.
+///
+/// `definitions` holds each client's name and its configured servers.
+pub fn find_divergent(definitions: &[(String, HashMap<String, ServerConfig>)]) -> Vec<Divergence> {
+    let mut by_server: BTreeMap<&str, BTreeMap<Signature, Variant>> = BTreeMap::new();
+    for (client, servers) in definitions {
+        for (server, config) in servers {
+            by_server
+                .entry(server.as_str())
+                .or_default()
+                .entry(signature(config))
+                .or_insert_with(|| Variant {
+                    clients: Vec::new(),
+                    config: config.clone(),
+                })
+                .clients
+                .push(client.clone());
+        }
+    }
+
+    by_server
+        .into_iter()
+        .filter(|(_, variants)| variants.len() > 1)
+        .map(|(server, variants)| {
+            let mut variants: Vec<Variant> = variants.into_values().collect();
+            for variant in &mut variants {
+                variant.clients.sort();
+            }
+            variants.sort_by(|a, b| {
+                b.clients
+                    .len()
+                    .cmp(&a.clients.len())
+                    .then_with(|| a.clients.cmp(&b.clients))
+            });
+            Divergence {
+                server: server.to_string(),
+                variants,
+            }
+        })
+        .collect()
+}
+
+/// `canonical`'s definition as an entry replacing `current`.
+///
+/// A `cmd /c` wrapper follows `current`, and `env` values `current` already has are
+/// kept, so client-specific secrets and references survive.
+pub fn harmonized(canonical: &ServerConfig, current: &ServerConfig) -> ServerConfig {
+    let (command, args) = unwrap_cmd(canonical);
+    let (command, args) = if is_cmd_wrapped(current) {
+        let mut wrapped = vec!["/c".to_string(), command];
+        wrapped.extend(args);
+        ("cmd".to_string(), wrapped)
+    } else {
+        (command, args)
+    };
+
+    let env = canonical
+        .env
+        .iter()
+        .map(|(name, value)| {
+            let value = current.env.get(name).unwrap_or(value);
+            (name.clone(), value.clone())
+        })
+        .collect();
+
+    ServerConfig {
+        command,
+        args,
+        env,
+        cwd: canonical.cwd.clone(),
+    }
+}
+
+fn is_cmd_wrapped(config: &ServerConfig) -> bool {
+    program_name(&config.command) == "cmd"
+        && config
+            .args
+            .first()
+            .is_some_and(|arg| arg.eq_ignore_ascii_case("/c"))
+        && config.args.len() > 1
+}
+
+/// The program and arguments a `cmd /c` wrapper runs, or the config's own
+fn unwrap_cmd(config: &ServerConfig) -> (String, Vec<String>) {
+    if is_cmd_wrapped(config) {
+        (config.args[1].clone(), config.args[2..].to_vec())
+    } else {
+        (config.command.clone(), config.args.clone())
+    }
+}
+
+fn is_npx_option(arg: &str) -> bool {
+    NPX_OPTIONS.contains(&arg) || arg.starts_with("--cache=")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(command: &str, args: &[&str], env: &[(&str, &str)]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            cwd: None,
+        }
+    }
+
+    fn client(
+        name: &str,
+        servers: &[(&str, ServerConfig)],
+    ) -> (String, HashMap<String, ServerConfig>) {
+        (
+            name.to_string(),
+            servers
+                .iter()
+                .map(|(server, config)| (server.to_string(), config.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_expected_differences_are_ignored() {
+        let definitions = vec![
+            client(
+                "Claude Desktop",
+                &[(
+                    "github",
+                    config(
+                        "cmd",
+                        &["/c", "npx.cmd", "-y", "@modelcontextprotocol/server-github"],
+                        &[("GITHUB_TOKEN", "ghp_literal")],
+                    ),
+                )],
+            ),
+            client(
+                "VS Code",
+                &[(
+                    "github",
+                    config(
+                        "npx",
+                        &["--prefer-offline", "@modelcontextprotocol/server-github"],
+                        &[("GITHUB_TOKEN", "${env:GITHUB_TOKEN}")],
+                    ),
+                )],
+            ),
+        ];
+        assert!(find_divergent(&definitions).is_empty());
+    }
+
+    #[test]
+    fn test_version_difference_is_reported() {
+        let old = config("npx", &["-y", "@scope/server@1.0.0"], &[]);
+        let new = config("npx", &["-y", "@scope/server@2.0.0"], &[]);
+        let definitions = vec![
+            client(
+                "Cursor",
+                &[("server", new.clone()), ("only-here", old.clone())],
+            ),
+            client("Claude Code", &[("server", old.clone())]),
+            client("VS Code", &[("server", new.clone())]),
+        ];
+
+        let divergent = find_divergent(&definitions);
+        assert_eq!(divergent.len(), 1);
+        assert_eq!(divergent[0].server, "server");
+        let variants = &divergent[0].variants;
+        assert_eq!(variants[0].clients, ["Cursor", "VS Code"]);
+        assert_eq!(variants[0].summary(), "npx @scope/server@2.0.0");
+        assert_eq!(variants[1].clients, ["Claude Code"]);
+    }
+
+    #[test]
+    fn test_harmonized_keeps_client_specifics() {
+        let canonical = config(
+            "npx",
+            &["-y", "@scope/server@2.0.0"],
+            &[("TOKEN", "${env:TOKEN}"), ("REGION", "eu")],
+        );
+        let current = config(
+            "cmd",
+            &["/c", "npx", "-y", "@scope/server@1.0.0"],
+            &[("TOKEN", "literal"), ("STALE", "x")],
+        );
+
+        let result = harmonized(&canonical, &current);
+        assert_eq!(result.command, "cmd");
+        assert_eq!(result.args, ["/c", "npx", "-y", "@scope/server@2.0.0"]);
+        assert_eq!(result.env["TOKEN"], "literal");
+        assert_eq!(result.env["REGION"], "eu");
+        assert!(!result.env.contains_key("STALE"));
+
+        let unwrapped = harmonized(&current, &canonical);
+        assert_eq!(unwrapped.command, "npx");
+        assert_eq!(unwrapped.args, ["-y", "@scope/server@1.0.0"]);
+    }
+}
@@ -0,0 +1,196 @@
+//! Environment variables a new server shares with servers already in a client.
+//!
+//! Every server runs in its own process, so two entries setting `LOG_LEVEL` don't
+//! see each other's value. Names can still collide:
+//!
+//! - Both name the same resource, e.g. two servers told `PORT=3000` or
+//!   `LOG_FILE=/tmp/mcp.log`
+//! - A client launches servers through one shell or merges `env` blocks, so one
+//!   entry's value reaches another server
+//!
+//! Installs warn about shared names and suggest a per-server prefix, where the
+//! server supports one, or a wrapper that sets the variable in the server's own
+//! command line. Credentials and variables every process has are left out: two
+//! servers using the same `GITHUB_TOKEN` is expected.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::client::ServerConfig;
+use crate::utils::secrets::is_secret_name;
+
+/// Variables servers routinely share
+const COMMON_NAMES: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USERPROFILE",
+    "LANG",
+    "TZ",
+    "NODE_ENV",
+    "NODE_OPTIONS",
+];
+
+/// Name suffixes of variables that point at something only one process can own
+const RESOURCE_SUFFIXES: &[&str] = &["PORT", "_FILE", "_DIR", "_PATH", "_SOCKET", "_DB"];
+
+/// A variable the new server shares with servers already configured
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvOverlap {
+    pub name: String,
+    /// `(client, server)` pairs that set it too, sorted
+    pub servers: Vec<(String, String)>,
+    /// Whether one of them sets it to the same value
+    pub same_value: bool,
+}
+
+impl EnvOverlap {
+    /// Whether the variable names a port, file or directory that two servers
+    /// can't both use
+    pub fn is_resource(&self) -> bool {
+        RESOURCE_SUFFIXES
+            .iter()
+            .any(|suffix| self.name.ends_with(suffix))
+    }
+
+    /// Whether the servers likely get in each other's way, rather than merely
+    /// sharing a name
+    pub fn is_conflict(&self) -> bool {
+        self.is_resource() && self.same_value
+    }
+
+    /// The name a per-server prefix gives the variable, e.g. `GITHUB_PORT`
+    pub fn prefixed_name(&self, server: &str) -> String {
+        let prefix: String = server
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{}_{}", prefix.trim_matches('_'), self.name)
+    }
+}
+
+/// Variables `config` (for `server`) shares with the servers each client already
+/// has, sorted by name. An existing entry for `server` itself is about to be
+/// replaced and doesn't count.
+pub fn find_env_overlaps(
+    server: &str,
+    config: &ServerConfig,
+    clients: &[(String, HashMap<String, ServerConfig>)],
+) -> Vec<EnvOverlap> {
+    let mut overlaps: BTreeMap<&str, EnvOverlap> = BTreeMap::new();
+    for (client, servers) in clients {
+        for (other, other_config) in servers {
+            if other == server {
+                continue;
+            }
+            for (name, value) in &config.env {
+                if is_shared_by_design(name) {
+                    continue;
+                }
+                let Some(other_value) = other_config.env.get(name) else {
+                    continue;
+                };
+                let overlap = overlaps.entry(name).or_insert_with(|| EnvOverlap {
+                    name: name.clone(),
+                    servers: Vec::new(),
+                    same_value: false,
+                });
+                overlap.servers.push((client.clone(), other.clone()));
+                overlap.same_value |= other_value == value;
+            }
+        }
+    }
+    overlaps
+        .into_values()
+        .map(|mut overlap| {
+            overlap.servers.sort();
+            overlap
+        })
+        .collect()
+}
+
+fn is_shared_by_design(name: &str) -> bool {
+    COMMON_NAMES.contains(&name) || is_secret_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(env: &[(&str, &str)]) -> ServerConfig {
+        ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "some-server".to_string()],
+            env: env
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            cwd: None,
+        }
+    }
+
+    fn client(
+        name: &str,
+        servers: &[(&str, ServerConfig)],
+    ) -> (String, HashMap<String, ServerConfig>) {
+        (
+            name.to_string(),
+            servers
+                .iter()
+                .map(|(server, config)| (server.to_string(), config.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_find_env_overlaps() {
+        let clients = vec![
+            client(
+                "Cursor",
+                &[
+                    ("web", config(&[("PORT", "3000"), ("LOG_LEVEL", "info")])),
+                    (
+                        "github",
+                        config(&[("GITHUB_TOKEN", "ghp_x"), ("PATH", "/bin")]),
+                    ),
+                ],
+            ),
+            client("VS Code", &[("api", config(&[("PORT", "4000")]))]),
+        ];
+        let new = config(&[
+            ("PORT", "3000"),
+            ("LOG_LEVEL", "debug"),
+            ("GITHUB_TOKEN", "ghp_x"),
+            ("PATH", "/bin"),
+        ]);
+
+        let overlaps = find_env_overlaps("search", &new, &clients);
+        let names: Vec<&str> = overlaps.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, vec!["LOG_LEVEL", "PORT"]);
+
+        let log_level = &overlaps[0];
+        assert!(!log_level.same_value);
+        assert!(!log_level.is_conflict());
+
+        let port = &overlaps[1];
+        assert_eq!(
+            port.servers,
+            vec![
+                ("Cursor".to_string(), "web".to_string()),
+                ("VS Code".to_string(), "api".to_string()),
+            ]
+        );
+        assert!(port.is_conflict());
+        assert_eq!(port.prefixed_name("my-search"), "MY_SEARCH_PORT");
+    }
+
+    #[test]
+    fn test_replaced_entry_is_not_an_overlap() {
+        let clients = vec![client("Cursor", &[("web", config(&[("PORT", "3000")]))])];
+        assert!(find_env_overlaps("web", &config(&[("PORT", "3000")]), &clients).is_empty());
+    }
+}
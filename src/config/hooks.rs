@@ -0,0 +1,454 @@
+//! User-defined install hooks.
+//!
+//! Hooks are commands from preferences that run around every install:
+//!
+//! ```json
+//! {
+//!   "hooks": {
+//!     "pre_install": [{ "command": "git", "args": ["-C", "/home/me/dotfiles", "pull"] }],
+//!     "post_install": [{ "command": "/home/me/bin/mcp-notify", "timeout_secs": 10 }]
+//!   }
+//! }
+//! ```
+//!
+//! Each hook runs once per client with `MCP_HELPER_EVENT`, `SERVER_NAME` and `CLIENT`
+//! set, plus `STATUS` (`success` or `failure`) for `post_install`. A failing
+//! `pre_install` hook stops the install for that client; `post_install` failures
+//! are only reported.
+//!
+//! A hook never runs until the user has approved it. The first time a new or changed
+//! hook is about to run, mcp-helper asks for confirmation and remembers the answer;
+//! without a terminal, unapproved hooks are skipped.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::audit_log;
+use crate::config::Preferences;
+use crate::utils::paths;
+use crate::utils::process::{is_timeout, Process};
+use crate::utils::prompt::Confirm;
+use crate::utils::state_file::{read_state, write_state};
+
+/// Timeout for hooks that do not set `timeout_secs`
+pub const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hooks configured in preferences
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HookConfig {
+    #[serde(default)]
+    pub pre_install: Vec<Hook>,
+    #[serde(default)]
+    pub post_install: Vec<Hook>,
+}
+
+impl HookConfig {
+    pub fn hooks(&self, event: HookEvent) -> &[Hook] {
+        match event {
+            HookEvent::PreInstall => &self.pre_install,
+            HookEvent::PostInstall => &self.post_install,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre_install.is_empty() && self.post_install.is_empty()
+    }
+}
+
+/// A single hook command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hook {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+impl Hook {
+    pub fn timeout(&self) -> Duration {
+        self.timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_HOOK_TIMEOUT)
+    }
+
+    /// The command line as shown to the user
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Key identifying exactly this command and argument list, so any edit needs re-approval
+    fn approval_key(&self) -> String {
+        serde_json::to_string(&(&self.command, &self.args)).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreInstall,
+    PostInstall,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::PreInstall => "pre_install",
+            Self::PostInstall => "post_install",
+        }
+    }
+}
+
+/// What a hook is told about the install
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub server_name: String,
+    pub client: String,
+    pub status: Option<String>,
+}
+
+impl HookContext {
+    pub fn new(server_name: &str, client: &str) -> Self {
+        Self {
+            server_name: server_name.to_string(),
+            client: client.to_string(),
+            status: None,
+        }
+    }
+
+    /// Record whether the install succeeded, for `post_install` hooks
+    pub fn with_success(mut self, success: bool) -> Self {
+        self.status = Some(if success { "success" } else { "failure" }.to_string());
+        self
+    }
+
+    pub fn env(&self, event: HookEvent) -> Vec<(String, String)> {
+        let mut env = vec![
+            ("MCP_HELPER_EVENT".to_string(), event.as_str().to_string()),
+            ("SERVER_NAME".to_string(), self.server_name.clone()),
+            ("CLIENT".to_string(), self.client.clone()),
+        ];
+        if let Some(status) = &self.status {
+            env.push(("STATUS".to_string(), status.clone()));
+        }
+        env
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HookOutcome {
+    Succeeded,
+    Failed(String),
+    TimedOut,
+}
+
+/// Run one hook, killing it if it outlives its timeout.
+pub fn run_hook(hook: &Hook, event: HookEvent, context: &HookContext) -> Result<HookOutcome> {
+    let status = Process::new(&hook.command)
+        .args(&hook.args)
+        .envs(context.env(event))
+        .timeout(hook.timeout())
+        .status();
+    match status {
+        Ok(status) if status.success() => Ok(HookOutcome::Succeeded),
+        Ok(status) => Ok(HookOutcome::Failed(status.to_string())),
+        Err(e) if is_timeout(&e) => Ok(HookOutcome::TimedOut),
+        Err(e) => Err(e).with_context(|| format!("Failed to start hook '{}'", hook.command)),
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ApprovalsFile {
+    #[serde(default)]
+    approved: BTreeSet<String>,
+}
+
+/// Hook command lines the user has allowed to run
+#[derive(Debug, Clone)]
+pub struct HookApprovals {
+    path: PathBuf,
+}
+
+impl HookApprovals {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_path(Self::default_path()?))
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn default_path() -> Result<PathBuf> {
+        Ok(paths::data_dir()?.join("hook-approvals.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> Result<ApprovalsFile> {
+        read_state(&self.path, ApprovalsFile::default).context("Failed to read hook approvals")
+    }
+
+    pub fn is_approved(&self, hook: &Hook) -> bool {
+        self.load()
+            .map(|file| file.approved.contains(&hook.approval_key()))
+            .unwrap_or(false)
+    }
+
+    pub fn approve(&self, hook: &Hook) -> Result<()> {
+        let mut file = self.load()?;
+        file.approved.insert(hook.approval_key());
+        write_state(&self.path, &file).context("Failed to write hook approvals")
+    }
+}
+
+/// Runs configured hooks for one command execution
+pub struct HookRunner {
+    config: HookConfig,
+    approvals: Option<HookApprovals>,
+    interactive: bool,
+    verbose: bool,
+    /// Hooks declined or skipped during this execution, so each is raised only once
+    declined: HashSet<String>,
+}
+
+impl HookRunner {
+    pub fn new(config: HookConfig, approvals: Option<HookApprovals>, interactive: bool) -> Self {
+        Self {
+            config,
+            approvals,
+            interactive,
+            verbose: false,
+            declined: HashSet::new(),
+        }
+    }
+
+    /// Load hooks from preferences; unreadable preferences mean no hooks
+    pub fn from_preferences(interactive: bool, verbose: bool) -> Self {
+        let config = match Preferences::load() {
+            Ok(prefs) => prefs.hooks,
+            Err(e) => {
+                if verbose {
+                    println!("{} Could not read preferences: {}", "⚠".yellow(), e);
+                }
+                HookConfig::default()
+            }
+        };
+        let approvals = if config.is_empty() {
+            None
+        } else {
+            HookApprovals::new().ok()
+        };
+
+        let mut runner = Self::new(config, approvals, interactive);
+        runner.verbose = verbose;
+        runner
+    }
+
+    /// Run every hook for `event`.
+    ///
+    /// Returns an error if a `pre_install` hook fails or times out.
+    pub fn run(&mut self, event: HookEvent, context: &HookContext) -> Result<()> {
+        let hooks = self.config.hooks(event).to_vec();
+        for hook in hooks {
+            if !self.is_allowed(&hook)? {
+                continue;
+            }
+
+            if self.verbose {
+                println!(
+                    "  {} Running {} hook: {}",
+                    "ℹ".blue(),
+                    event.as_str(),
+                    hook.command_line()
+                );
+            }
+
+            let problem = match run_hook(&hook, event, context) {
+                Ok(HookOutcome::Succeeded) => continue,
+                Ok(HookOutcome::Failed(status)) => format!("exited with {status}"),
+                Ok(HookOutcome::TimedOut) => {
+                    format!("timed out after {}s", hook.timeout().as_secs())
+                }
+                Err(e) => e.to_string(),
+            };
+
+            if event == HookEvent::PreInstall {
+                anyhow::bail!("pre_install hook '{}' {}", hook.command_line(), problem);
+            }
+            println!(
+                "  {} {} hook '{}' {}",
+                "⚠".yellow(),
+                event.as_str(),
+                hook.command_line(),
+                problem
+            );
+        }
+        Ok(())
+    }
+
+    fn is_allowed(&mut self, hook: &Hook) -> Result<bool> {
+        let Some(approvals) = &self.approvals else {
+            return Ok(false);
+        };
+        if approvals.is_approved(hook) {
+            return Ok(true);
+        }
+
+        let command_line = hook.command_line();
+        if self.declined.contains(&hook.approval_key()) {
+            return Ok(false);
+        }
+
+        if !self.interactive {
+            println!(
+                "  {} Skipping unapproved hook '{}' (run interactively once to approve it)",
+                "⚠".yellow(),
+                command_line
+            );
+            self.declined.insert(hook.approval_key());
+            return Ok(false);
+        }
+
+        println!(
+            "{} Preferences define a hook that runs on every install:",
+            "⚠".yellow()
+        );
+        println!("  {}", command_line.cyan());
+        let allow = Confirm::new()
+            .with_prompt("Allow this hook to run?")
+            .default(false)
+            .interact()
+            .context("Failed to read user input")?;
+
+        if allow {
+            approvals.approve(hook)?;
+            audit_log::record_override(&command_line, &["Hook runs on every install".to_string()]);
+        } else {
+            self.declined.insert(hook.approval_key());
+        }
+        Ok(allow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn hook(command: &str, args: &[&str]) -> Hook {
+        Hook {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            timeout_secs: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_hook_config() {
+        let config: HookConfig = serde_json::from_str(
+            r#"{ "post_install": [{ "command": "notify", "args": ["done"], "timeout_secs": 5 }] }"#,
+        )
+        .unwrap();
+
+        assert!(config.hooks(HookEvent::PreInstall).is_empty());
+        let post = &config.hooks(HookEvent::PostInstall)[0];
+        assert_eq!(post.command_line(), "notify done");
+        assert_eq!(post.timeout(), Duration::from_secs(5));
+        assert_eq!(hook("x", &[]).timeout(), DEFAULT_HOOK_TIMEOUT);
+    }
+
+    #[test]
+    fn test_hook_context_env() {
+        let env = HookContext::new("github", "Cursor")
+            .with_success(false)
+            .env(HookEvent::PostInstall);
+        assert!(env.contains(&("SERVER_NAME".to_string(), "github".to_string())));
+        assert!(env.contains(&("CLIENT".to_string(), "Cursor".to_string())));
+        assert!(env.contains(&("STATUS".to_string(), "failure".to_string())));
+        assert!(env.contains(&("MCP_HELPER_EVENT".to_string(), "post_install".to_string())));
+    }
+
+    #[test]
+    fn test_approvals_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let approvals = HookApprovals::with_path(temp_dir.path().join("hook-approvals.json"));
+        let notify = hook("notify", &["done"]);
+
+        assert!(!approvals.is_approved(&notify));
+        approvals.approve(&notify).unwrap();
+        assert!(approvals.is_approved(&notify));
+        assert!(!approvals.is_approved(&hook("notify", &["other"])));
+    }
+
+    #[test]
+    fn test_unapproved_hooks_are_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = HookConfig {
+            pre_install: vec![hook("definitely-not-a-real-command-xyz", &[])],
+            post_install: Vec::new(),
+        };
+        let approvals = HookApprovals::with_path(temp_dir.path().join("hook-approvals.json"));
+        let mut runner = HookRunner::new(config, Some(approvals), false);
+
+        // The hook would fail to start, but it never runs without approval
+        assert!(runner
+            .run(HookEvent::PreInstall, &HookContext::new("github", "Cursor"))
+            .is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook_outcomes() {
+        let context = HookContext::new("github", "Cursor");
+
+        let ok = hook("sh", &["-c", "test \"$SERVER_NAME\" = github"]);
+        assert_eq!(
+            run_hook(&ok, HookEvent::PreInstall, &context).unwrap(),
+            HookOutcome::Succeeded
+        );
+
+        let failing = hook("sh", &["-c", "exit 3"]);
+        assert!(matches!(
+            run_hook(&failing, HookEvent::PreInstall, &context).unwrap(),
+            HookOutcome::Failed(_)
+        ));
+
+        let slow = Hook {
+            timeout_secs: Some(0),
+            ..hook("sleep", &["5"])
+        };
+        assert_eq!(
+            run_hook(&slow, HookEvent::PreInstall, &context).unwrap(),
+            HookOutcome::TimedOut
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_pre_install_hook_stops_install() {
+        let temp_dir = TempDir::new().unwrap();
+        let failing = hook("sh", &["-c", "exit 1"]);
+        let approvals = HookApprovals::with_path(temp_dir.path().join("hook-approvals.json"));
+        approvals.approve(&failing).unwrap();
+
+        let config = HookConfig {
+            pre_install: vec![failing.clone()],
+            post_install: vec![failing],
+        };
+        let mut runner = HookRunner::new(config, Some(approvals), false);
+        let context = HookContext::new("github", "Cursor");
+
+        assert!(runner.run(HookEvent::PreInstall, &context).is_err());
+        assert!(runner
+            .run(HookEvent::PostInstall, &context.with_success(true))
+            .is_ok());
+    }
+}
@@ -0,0 +1,570 @@
+//! Lint rules for server configurations, with safe autofixes.
+//!
+//! Each rule inspects a single [`ServerConfig`] and may attach a fixed copy of the
+//! configuration to its finding. Fixes only change what the rule is about and are
+//! skipped when they can't be applied without guessing (e.g. pinning a Docker image
+//! that isn't available locally).
+
+use std::fmt;
+use std::path::Path;
+
+use crate::client::ServerConfig;
+use crate::runner::{normalize_path, Platform};
+use crate::server::docker::is_docker_command;
+use crate::utils::paths::program_name;
+use crate::utils::process::Process;
+use crate::utils::secrets::{is_secret_name, is_variable_reference, looks_like_secret_value};
+
+/// Clients that expand `${env:NAME}` references in their configuration
+const ENV_INTERPOLATING_CLIENTS: &[&str] = &["VS Code", "Cursor"];
+
+/// `docker run` flags that take a separate value
+const DOCKER_VALUE_FLAGS: &[&str] = &[
+    "-e",
+    "--env",
+    "--env-file",
+    "-v",
+    "--volume",
+    "--mount",
+    "--name",
+    "--network",
+    "--net",
+    "-p",
+    "--publish",
+    "-w",
+    "--workdir",
+    "-u",
+    "--user",
+    "--entrypoint",
+    "--platform",
+    "--label",
+    "-l",
+];
+
+/// Upper bound on fix passes, in case two fixes would undo each other
+const MAX_FIX_PASSES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LintRule {
+    /// `command` is an absolute path to npx that no longer exists
+    MissingNpxPath,
+    /// Docker image uses the `latest` tag (explicitly or implicitly)
+    DockerLatestTag,
+    /// Environment variable holds a secret in plaintext
+    PlaintextSecret,
+    /// Arguments use `\` path separators on macOS/Linux
+    WindowsPathSeparator,
+    /// npx runs without `-y`, so it may block on an install prompt
+    NpxMissingYes,
+}
+
+impl LintRule {
+    pub fn id(&self) -> &'static str {
+        match self {
+            LintRule::MissingNpxPath => "npx-path-missing",
+            LintRule::DockerLatestTag => "docker-latest-tag",
+            LintRule::PlaintextSecret => "plaintext-secret",
+            LintRule::WindowsPathSeparator => "windows-path-separator",
+            LintRule::NpxMissingYes => "npx-missing-yes",
+        }
+    }
+}
+
+impl fmt::Display for LintRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// A problem found in one server configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintFinding {
+    pub rule: LintRule,
+    pub message: String,
+    /// The configuration with only this problem fixed, if a safe fix exists
+    pub fixed: Option<ServerConfig>,
+    /// What the fix does, or why none is available
+    pub fix_hint: String,
+}
+
+impl LintFinding {
+    pub fn is_fixable(&self) -> bool {
+        self.fixed.is_some()
+    }
+}
+
+type DigestResolver = Box<dyn Fn(&str) -> Option<String>>;
+
+/// Runs all lint rules against server configurations
+pub struct ConfigLinter {
+    platform: Platform,
+    resolve_digest: DigestResolver,
+    npx_available: bool,
+}
+
+impl ConfigLinter {
+    pub fn new() -> Self {
+        let platform = if cfg!(target_os = "windows") {
+            Platform::Windows
+        } else if cfg!(target_os = "macos") {
+            Platform::MacOS
+        } else {
+            Platform::Linux
+        };
+
+        Self {
+            platform,
+            resolve_digest: Box::new(local_image_digest),
+            npx_available: which::which("npx").is_ok(),
+        }
+    }
+
+    /// Lint as if configs were used on `platform`
+    pub fn with_platform(mut self, platform: Platform) -> Self {
+        self.platform = platform;
+        self
+    }
+
+    /// Use a custom lookup for `image:tag` -> `image@sha256:...`
+    pub fn with_digest_resolver(
+        mut self,
+        resolver: impl Fn(&str) -> Option<String> + 'static,
+    ) -> Self {
+        self.resolve_digest = Box::new(resolver);
+        self
+    }
+
+    /// Whether a bare `npx` command resolves on this machine
+    pub fn with_npx_available(mut self, available: bool) -> Self {
+        self.npx_available = available;
+        self
+    }
+
+    /// Check a configuration against every rule.
+    pub fn lint(&self, client_name: &str, config: &ServerConfig) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+        findings.extend(self.check_npx_path(config));
+        findings.extend(self.check_npx_yes(config));
+        findings.extend(self.check_docker_tag(config));
+        findings.extend(self.check_path_separators(config));
+        findings.extend(self.check_secrets(client_name, config));
+        findings
+    }
+
+    /// Apply every available fix. Returns the fixed config and the rules that were fixed.
+    pub fn fix(&self, client_name: &str, config: &ServerConfig) -> (ServerConfig, Vec<LintRule>) {
+        let mut current = config.clone();
+        let mut applied = Vec::new();
+
+        for _ in 0..MAX_FIX_PASSES {
+            let next = self
+                .lint(client_name, &current)
+                .into_iter()
+                .find_map(|finding| finding.fixed.map(|fixed| (finding.rule, fixed)));
+
+            match next {
+                Some((rule, fixed)) if fixed != current => {
+                    applied.push(rule);
+                    current = fixed;
+                }
+                _ => break,
+            }
+        }
+
+        (current, applied)
+    }
+
+    fn check_npx_path(&self, config: &ServerConfig) -> Option<LintFinding> {
+        let path = Path::new(&config.command);
+        if !is_absolute_anywhere(&config.command) || !is_npx(&config.command) || path.exists() {
+            return None;
+        }
+
+        let (fixed, fix_hint) = if self.npx_available {
+            let mut fixed = config.clone();
+            fixed.command = if self.platform == Platform::Windows {
+                "npx.cmd".to_string()
+            } else {
+                "npx".to_string()
+            };
+            let hint = format!("use `{}` from PATH", fixed.command);
+            (Some(fixed), hint)
+        } else {
+            (None, "install Node.js so npx is on PATH".to_string())
+        };
+
+        Some(LintFinding {
+            rule: LintRule::MissingNpxPath,
+            message: format!("npx not found at {}", config.command),
+            fixed,
+            fix_hint,
+        })
+    }
+
+    fn check_npx_yes(&self, config: &ServerConfig) -> Option<LintFinding> {
+        if !is_npx(&config.command) || config.args.iter().any(|a| a == "-y" || a == "--yes") {
+            return None;
+        }
+
+        let mut fixed = config.clone();
+        fixed.args.insert(0, "-y".to_string());
+
+        Some(LintFinding {
+            rule: LintRule::NpxMissingYes,
+            message: "npx runs without -y and may hang waiting for an install prompt".to_string(),
+            fixed: Some(fixed),
+            fix_hint: "add -y".to_string(),
+        })
+    }
+
+    fn check_docker_tag(&self, config: &ServerConfig) -> Option<LintFinding> {
+        if !is_docker_command(&config.command) {
+            return None;
+        }
+
+        let index = docker_image_index(&config.args)?;
+        let image = &config.args[index];
+        if !uses_latest_tag(image) {
+            return None;
+        }
+
+        let reference = if has_tag(image) {
+            image.clone()
+        } else {
+            format!("{image}:latest")
+        };
+
+        let (fixed, fix_hint) = match (self.resolve_digest)(&reference) {
+            Some(pinned) => {
+                let mut fixed = config.clone();
+                fixed.args[index] = pinned.clone();
+                (Some(fixed), format!("pin to {pinned}"))
+            }
+            None => (
+                None,
+                format!("pull {reference} or pin a specific tag by hand"),
+            ),
+        };
+
+        Some(LintFinding {
+            rule: LintRule::DockerLatestTag,
+            message: format!("{image} uses the latest tag, so updates arrive unannounced"),
+            fixed,
+            fix_hint,
+        })
+    }
+
+    fn check_path_separators(&self, config: &ServerConfig) -> Option<LintFinding> {
+        if self.platform == Platform::Windows {
+            return None;
+        }
+
+        let offending: Vec<&String> = config
+            .args
+            .iter()
+            .filter(|arg| looks_like_windows_path(arg))
+            .collect();
+        if offending.is_empty() {
+            return None;
+        }
+
+        let mut fixed = config.clone();
+        for arg in fixed.args.iter_mut() {
+            if looks_like_windows_path(arg) {
+                *arg = normalize_path(arg, self.platform);
+            }
+        }
+
+        Some(LintFinding {
+            rule: LintRule::WindowsPathSeparator,
+            message: format!(
+                "arguments use Windows path separators: {}",
+                offending
+                    .iter()
+                    .map(|a| a.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            fixed: Some(fixed),
+            fix_hint: "use / separators".to_string(),
+        })
+    }
+
+    fn check_secrets(&self, client_name: &str, config: &ServerConfig) -> Vec<LintFinding> {
+        let interpolates = ENV_INTERPOLATING_CLIENTS
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(client_name));
+
+        let mut keys: Vec<&String> = config
+            .env
+            .iter()
+            .filter(|(key, value)| {
+                !value.is_empty()
+                    && !is_variable_reference(value)
+                    && (is_secret_name(key) || looks_like_secret_value(value))
+            })
+            .map(|(key, _)| key)
+            .collect();
+        keys.sort();
+
+        keys.into_iter()
+            .map(|key| {
+                let value = &config.env[key];
+                let in_environment = std::env::var(key).is_ok_and(|current| current == *value);
+
+                let (fixed, fix_hint) = if !interpolates {
+                    (
+                        None,
+                        format!("{client_name} can't reference environment variables; consider a wrapper script"),
+                    )
+                } else if !in_environment {
+                    (
+                        None,
+                        format!("export {key} in your shell profile, then rerun with --fix"),
+                    )
+                } else {
+                    let mut fixed = config.clone();
+                    fixed
+                        .env
+                        .insert(key.clone(), format!("${{env:{key}}}"));
+                    (Some(fixed), format!("reference ${{env:{key}}} instead"))
+                };
+
+                LintFinding {
+                    rule: LintRule::PlaintextSecret,
+                    message: format!("{key} contains a secret in plaintext"),
+                    fixed,
+                    fix_hint,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for ConfigLinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_npx(command: &str) -> bool {
+    program_name(command) == "npx"
+}
+
+/// Absolute on this machine, or a Windows drive or UNC path from a synced config
+fn is_absolute_anywhere(command: &str) -> bool {
+    let bytes = command.as_bytes();
+    let drive = bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+    Path::new(command).is_absolute() || drive || command.starts_with(r"\\")
+}
+
+/// Index of the image argument in `docker run` arguments
+fn docker_image_index(args: &[String]) -> Option<usize> {
+    let run = args.iter().position(|a| a == "run")?;
+    let mut i = run + 1;
+    while i < args.len() {
+        let arg = &args[i];
+        if DOCKER_VALUE_FLAGS.contains(&arg.as_str()) {
+            i += 2;
+        } else if arg.starts_with('-') {
+            i += 1;
+        } else {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn has_tag(image: &str) -> bool {
+    let last_segment = image.rsplit('/').next().unwrap_or(image);
+    last_segment.contains(':')
+}
+
+fn uses_latest_tag(image: &str) -> bool {
+    if image.contains('@') {
+        return false;
+    }
+    !has_tag(image) || image.ends_with(":latest")
+}
+
+fn looks_like_windows_path(arg: &str) -> bool {
+    !arg.starts_with('-')
+        && arg.contains('\\')
+        && !arg.contains(' ')
+        && arg.split('\\').filter(|s| !s.is_empty()).count() >= 2
+}
+
+/// Resolve `image:tag` to the digest of the locally pulled image
+fn local_image_digest(reference: &str) -> Option<String> {
+    let output = Process::new("docker")
+        .args([
+            "image",
+            "inspect",
+            "--format",
+            "{{index .RepoDigests 0}}",
+            reference,
+        ])
+        .output()
+        .ok()?;
+
+    if !output.success() {
+        return None;
+    }
+
+    let digest = output.stdout_lossy().trim().to_string();
+    digest.contains("@sha256:").then_some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    fn linter() -> ConfigLinter {
+        ConfigLinter::new()
+            .with_platform(Platform::MacOS)
+            .with_npx_available(true)
+            .with_digest_resolver(|_| None)
+    }
+
+    fn rules(findings: &[LintFinding]) -> Vec<LintRule> {
+        findings.iter().map(|f| f.rule).collect()
+    }
+
+    #[test]
+    fn test_clean_config_has_no_findings() {
+        let config = config("npx", &["-y", "@modelcontextprotocol/server-github"]);
+        assert!(linter().lint("Claude Desktop", &config).is_empty());
+    }
+
+    #[test]
+    fn test_npx_rules() {
+        let missing_npx = std::env::temp_dir().join("mcp-missing-node").join("npx");
+        let config = config(
+            missing_npx.to_str().unwrap(),
+            &["@modelcontextprotocol/server-github"],
+        );
+        let findings = linter().lint("Claude Desktop", &config);
+        assert_eq!(
+            rules(&findings),
+            vec![LintRule::MissingNpxPath, LintRule::NpxMissingYes]
+        );
+
+        let (fixed, applied) = linter().fix("Claude Desktop", &config);
+        assert_eq!(fixed.command, "npx");
+        assert_eq!(
+            fixed.args,
+            vec!["-y", "@modelcontextprotocol/server-github"]
+        );
+        assert_eq!(applied.len(), 2);
+
+        let no_npx = ConfigLinter::new()
+            .with_platform(Platform::MacOS)
+            .with_npx_available(false);
+        let findings = no_npx.lint("Claude Desktop", &config);
+        assert!(!findings[0].is_fixable());
+    }
+
+    #[test]
+    fn test_docker_latest_tag() {
+        let implicit = config(
+            "docker",
+            &["run", "-i", "--rm", "-e", "TOKEN", "mcp/github"],
+        );
+        let explicit = config("docker", &["run", "-i", "mcp/github:latest", "--flag"]);
+        let pinned = config("docker", &["run", "-i", "mcp/github:1.2"]);
+        let digest = config("docker", &["run", "-i", "mcp/github@sha256:abc"]);
+
+        assert_eq!(
+            rules(&linter().lint("Cursor", &implicit)),
+            vec![LintRule::DockerLatestTag]
+        );
+        assert_eq!(
+            rules(&linter().lint("Cursor", &explicit)),
+            vec![LintRule::DockerLatestTag]
+        );
+        assert!(linter().lint("Cursor", &pinned).is_empty());
+        assert!(linter().lint("Cursor", &digest).is_empty());
+        assert!(!linter().lint("Cursor", &implicit)[0].is_fixable());
+
+        let resolving = linter().with_digest_resolver(|reference| {
+            assert_eq!(reference, "mcp/github:latest");
+            Some("mcp/github@sha256:1234".to_string())
+        });
+        let (fixed, _) = resolving.fix("Cursor", &implicit);
+        assert_eq!(fixed.args.last().unwrap(), "mcp/github@sha256:1234");
+        assert_eq!(fixed.args[4], "TOKEN");
+    }
+
+    #[test]
+    fn test_windows_style_commands() {
+        let npx = config(
+            r"C:\Program Files\nodejs\npx.cmd",
+            &["@modelcontextprotocol/server-github"],
+        );
+        assert_eq!(
+            rules(&linter().lint("Claude Desktop", &npx)),
+            vec![LintRule::MissingNpxPath, LintRule::NpxMissingYes]
+        );
+
+        let docker = config(
+            r"C:\Program Files\Docker\Docker\resources\bin\docker.exe",
+            &["run", "-i", "mcp/github"],
+        );
+        assert_eq!(
+            rules(&linter().lint("Cursor", &docker)),
+            vec![LintRule::DockerLatestTag]
+        );
+    }
+
+    #[test]
+    fn test_windows_path_separators() {
+        let config = config(
+            "npx",
+            &["-y", "server-filesystem", "Users\\me\\projects", "--re=\\d"],
+        );
+        let (fixed, applied) = linter().fix("Claude Desktop", &config);
+        assert_eq!(applied, vec![LintRule::WindowsPathSeparator]);
+        assert_eq!(fixed.args[2], "Users/me/projects");
+        assert_eq!(fixed.args[3], "--re=\\d");
+
+        let windows = linter().with_platform(Platform::Windows);
+        assert!(windows.lint("Claude Desktop", &config).is_empty());
+    }
+
+    #[test]
+    fn test_plaintext_secret() {
+        let mut config = config("npx", &["-y", "@modelcontextprotocol/server-github"]);
+        config.env.insert(
+            "MCP_LINT_TEST_GITHUB_TOKEN".to_string(),
+            "ghp_abcdefghijklmnopqrstuvwxyz".to_string(),
+        );
+        config
+            .env
+            .insert("REGION".to_string(), "eu-west-1".to_string());
+        config
+            .env
+            .insert("API_KEY".to_string(), "${env:API_KEY}".to_string());
+
+        let findings = linter().lint("Claude Desktop", &config);
+        assert_eq!(rules(&findings), vec![LintRule::PlaintextSecret]);
+        assert!(!findings[0].is_fixable());
+
+        // Not fixable while the variable isn't exported with the same value
+        let findings = linter().lint("VS Code", &config);
+        assert!(!findings[0].is_fixable());
+        assert!(findings[0].fix_hint.contains("export"));
+    }
+}
@@ -0,0 +1,503 @@
+//! Servers under mcp-helper management.
+//!
+//! Every server added through mcp-helper, or adopted from a hand-written client
+//! config with `mcp import`, gets an entry recording what kind of server it is and
+//! which clients have it. Imported entries also keep the config exactly as it was
+//! found, so normalizing it later never loses the original.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::client::ServerConfig;
+use crate::server::docker::image_tag;
+use crate::server::docker_requirements::image_from_run_args;
+use crate::server::publisher::PublisherVerification;
+use crate::server::BinaryServer;
+use crate::update::schema::ConfigSchema;
+use crate::update::{find_npm_package_spec, UpdateChannel};
+use crate::utils::paths;
+use crate::utils::state_file::{read_state, write_state};
+
+/// What a server runs, inferred from its command line
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ServerKind {
+    Npm {
+        package: String,
+        version: Option<String>,
+    },
+    Docker {
+        image: String,
+    },
+    Python {
+        package: String,
+    },
+    Binary {
+        command: String,
+    },
+}
+
+impl ServerKind {
+    /// Infer the kind of server `config` launches
+    pub fn infer(config: &ServerConfig) -> Self {
+        if let Some(spec) = find_npm_package_spec(config) {
+            return Self::Npm {
+                package: spec.package,
+                version: spec.version,
+            };
+        }
+
+        let program = paths::program_name(&config.command);
+        let first_arg = || {
+            config
+                .args
+                .iter()
+                .find(|arg| !arg.starts_with('-'))
+                .cloned()
+        };
+
+        match program.as_str() {
+            "docker" | "podman" => {
+                if let Some(image) = image_from_run_args(&config.args) {
+                    return Self::Docker {
+                        image: image.to_string(),
+                    };
+                }
+            }
+            "uvx" | "pipx" => {
+                let package = match config.args.first().map(String::as_str) {
+                    Some("run") => config.args.iter().skip(1).find(|a| !a.starts_with('-')),
+                    _ => config.args.iter().find(|a| !a.starts_with('-')),
+                };
+                if let Some(package) = package {
+                    return Self::Python {
+                        package: package.clone(),
+                    };
+                }
+            }
+            "python" | "python3" | "py" => {
+                let module = config
+                    .args
+                    .iter()
+                    .position(|arg| arg == "-m")
+                    .and_then(|index| config.args.get(index + 1))
+                    .cloned();
+                if let Some(package) = module.or_else(first_arg) {
+                    return Self::Python { package };
+                }
+            }
+            _ => {}
+        }
+
+        Self::Binary {
+            command: config.command.clone(),
+        }
+    }
+}
+
+impl ServerKind {
+    /// Whether both run the same package, image or program, whatever the npm version
+    pub fn is_same_server(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Npm { package: a, .. }, Self::Npm { package: b, .. }) => a == b,
+            _ => self == other,
+        }
+    }
+
+    /// `npm`, `docker`, `python` or `binary`
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Npm { .. } => "npm",
+            Self::Docker { .. } => "docker",
+            Self::Python { .. } => "python",
+            Self::Binary { .. } => "binary",
+        }
+    }
+
+    /// The npm or Python package, Docker image or executable
+    pub fn source(&self) -> &str {
+        match self {
+            Self::Npm { package, .. } | Self::Python { package } => package,
+            Self::Docker { image } => image,
+            Self::Binary { command } => command,
+        }
+    }
+
+    /// The version the server runs, where the config or its install tells: the pinned
+    /// npm or Python version, the image tag, or the GitHub release a binary came from
+    pub fn version(&self) -> Option<String> {
+        match self {
+            Self::Npm { version, .. } => version.clone(),
+            Self::Docker { image } => image_tag(image).map(str::to_string),
+            Self::Python { package } => package
+                .split_once("==")
+                .map(|(_, version)| version.to_string()),
+            Self::Binary { command } => BinaryServer::installed_from(Path::new(command))
+                .and_then(|url| BinaryServer::github_release_asset(&url))
+                .map(|(_, tag)| tag),
+        }
+    }
+}
+
+impl std::fmt::Display for ServerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Npm {
+                package,
+                version: Some(version),
+            } => write!(f, "npm {package}@{version}"),
+            Self::Npm { package, .. } => write!(f, "npm {package}"),
+            Self::Docker { image } => write!(f, "docker {image}"),
+            Self::Python { package } => write!(f, "python {package}"),
+            Self::Binary { command } => write!(f, "binary {command}"),
+        }
+    }
+}
+
+/// How a server came under management
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManagedOrigin {
+    Added,
+    Imported,
+}
+
+/// One managed server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManagedServer {
+    pub kind: ServerKind,
+    pub origin: ManagedOrigin,
+    pub clients: Vec<String>,
+    /// The hand-written config as found by `mcp import`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imported_config: Option<ServerConfig>,
+    /// Releases `mcp update` moves this server to
+    #[serde(default, skip_serializing_if = "UpdateChannel::is_stable")]
+    pub channel: UpdateChannel,
+    /// Publisher verification of the npm package when it was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<PublisherVerification>,
+    /// The server's default name when it was added under another one with `--as`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance_of: Option<String>,
+    /// Config schema of the npm package version last added or updated to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<ConfigSchema>,
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ManagedFile {
+    #[serde(default)]
+    servers: BTreeMap<String, ManagedServer>,
+}
+
+/// The record of servers mcp-helper manages
+#[derive(Debug, Clone)]
+pub struct ManagedServers {
+    path: PathBuf,
+}
+
+impl ManagedServers {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_path(Self::default_path()?))
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn default_path() -> Result<PathBuf> {
+        Ok(paths::data_dir()?.join("managed-servers.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> Result<ManagedFile> {
+        read_state(&self.path, ManagedFile::default).context("Failed to read managed servers")
+    }
+
+    fn save(&self, file: &ManagedFile) -> Result<()> {
+        write_state(&self.path, file).context("Failed to write managed servers")
+    }
+
+    /// Every managed server, by name
+    pub fn list(&self) -> Result<BTreeMap<String, ManagedServer>> {
+        Ok(self.load()?.servers)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<ManagedServer>> {
+        Ok(self.load()?.servers.remove(name))
+    }
+
+    /// Whether `name` is already managed in `client`
+    pub fn is_managed_in(&self, name: &str, client: &str) -> Result<bool> {
+        Ok(self
+            .get(name)?
+            .is_some_and(|server| server.clients.iter().any(|c| c == client)))
+    }
+
+    /// Record that `name` was added to `client` through mcp-helper
+    pub fn record_added(&self, name: &str, config: &ServerConfig, client: &str) -> Result<()> {
+        self.record(name, config, client, ManagedOrigin::Added)
+    }
+
+    /// Adopt a hand-written server found in `client`, keeping its config as found
+    pub fn record_imported(&self, name: &str, config: &ServerConfig, client: &str) -> Result<()> {
+        self.record(name, config, client, ManagedOrigin::Imported)
+    }
+
+    fn record(
+        &self,
+        name: &str,
+        config: &ServerConfig,
+        client: &str,
+        origin: ManagedOrigin,
+    ) -> Result<()> {
+        let mut file = self.load()?;
+        let entry = file
+            .servers
+            .entry(name.to_string())
+            .or_insert_with(|| ManagedServer {
+                kind: ServerKind::infer(config),
+                origin,
+                clients: Vec::new(),
+                imported_config: None,
+                channel: UpdateChannel::Stable,
+                publisher: None,
+                instance_of: None,
+                schema: None,
+                recorded_at: chrono::Utc::now(),
+            });
+
+        if origin == ManagedOrigin::Added {
+            entry.kind = ServerKind::infer(config);
+        } else if entry.imported_config.is_none() && entry.origin == ManagedOrigin::Imported {
+            entry.imported_config = Some(config.clone());
+        }
+        if !entry.clients.iter().any(|c| c == client) {
+            entry.clients.push(client.to_string());
+        }
+
+        self.save(&file)
+    }
+
+    /// Follow `channel` when updating `name`
+    pub fn set_channel(&self, name: &str, channel: UpdateChannel) -> Result<()> {
+        let mut file = self.load()?;
+        let entry = file
+            .servers
+            .get_mut(name)
+            .with_context(|| format!("Server '{name}' is not managed by mcp-helper"))?;
+        entry.channel = channel;
+        self.save(&file)
+    }
+
+    /// Record the publisher verification of the package `name` runs
+    pub fn set_publisher(&self, name: &str, publisher: PublisherVerification) -> Result<()> {
+        let mut file = self.load()?;
+        let entry = file
+            .servers
+            .get_mut(name)
+            .with_context(|| format!("Server '{name}' is not managed by mcp-helper"))?;
+        entry.publisher = Some(publisher);
+        self.save(&file)
+    }
+
+    /// Record the config schema of the package version `name` now runs
+    pub fn set_schema(&self, name: &str, schema: ConfigSchema) -> Result<()> {
+        let mut file = self.load()?;
+        let entry = file
+            .servers
+            .get_mut(name)
+            .with_context(|| format!("Server '{name}' is not managed by mcp-helper"))?;
+        entry.schema = Some(schema);
+        self.save(&file)
+    }
+
+    /// Record that `name` is another instance of the server normally named `default_name`
+    pub fn set_instance_of(&self, name: &str, default_name: &str) -> Result<()> {
+        let mut file = self.load()?;
+        let entry = file
+            .servers
+            .get_mut(name)
+            .with_context(|| format!("Server '{name}' is not managed by mcp-helper"))?;
+        entry.instance_of = Some(default_name.to_string());
+        self.save(&file)
+    }
+
+    /// Names of the instances added with `--as` in place of `default_name`
+    pub fn instances_of(&self, default_name: &str) -> Result<Vec<String>> {
+        Ok(self
+            .load()?
+            .servers
+            .into_iter()
+            .filter(|(_, server)| server.instance_of.as_deref() == Some(default_name))
+            .map(|(name, _)| name)
+            .collect())
+    }
+
+    /// Stop managing `name` in `client`, dropping the entry once no client has it
+    pub fn forget(&self, name: &str, client: &str) -> Result<()> {
+        let mut file = self.load()?;
+        let Some(entry) = file.servers.get_mut(name) else {
+            return Ok(());
+        };
+        entry.clients.retain(|c| c != client);
+        if entry.clients.is_empty() {
+            file.servers.remove(name);
+        }
+        self.save(&file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_infer_server_kind() {
+        assert_eq!(
+            ServerKind::infer(&config("npx", &["-y", "@org/server@1.2.0"])),
+            ServerKind::Npm {
+                package: "@org/server".to_string(),
+                version: Some("1.2.0".to_string()),
+            }
+        );
+        assert_eq!(
+            ServerKind::infer(&config(
+                "docker",
+                &[
+                    "run",
+                    "-i",
+                    "--rm",
+                    "-e",
+                    "TOKEN",
+                    "-v",
+                    "/a:/b",
+                    "mcp/fetch:1.0",
+                    "--verbose"
+                ]
+            )),
+            ServerKind::Docker {
+                image: "mcp/fetch:1.0".to_string()
+            }
+        );
+        assert_eq!(
+            ServerKind::infer(&config("uvx", &["mcp-server-git", "--repository", "."])),
+            ServerKind::Python {
+                package: "mcp-server-git".to_string()
+            }
+        );
+        assert_eq!(
+            ServerKind::infer(&config("/usr/bin/python3", &["-m", "mcp_server_time"])),
+            ServerKind::Python {
+                package: "mcp_server_time".to_string()
+            }
+        );
+        assert_eq!(
+            ServerKind::infer(&config("/opt/tools/my-server", &["--stdio"])),
+            ServerKind::Binary {
+                command: "/opt/tools/my-server".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_is_same_server() {
+        let v1 = ServerKind::infer(&config("npx", &["-y", "@org/server@1.0.0"]));
+        let v2 = ServerKind::infer(&config("npx", &["-y", "@org/server@2.0.0"]));
+        let other = ServerKind::infer(&config("npx", &["-y", "@org/other"]));
+        assert!(v1.is_same_server(&v2));
+        assert!(!v1.is_same_server(&other));
+        assert!(!v1.is_same_server(&ServerKind::infer(&config("server", &[]))));
+    }
+
+    #[test]
+    fn test_record_and_forget() {
+        let temp_dir = TempDir::new().unwrap();
+        let managed = ManagedServers::with_path(temp_dir.path().join("managed.json"));
+        let found = config("npx", &["-y", "@org/server"]);
+
+        managed
+            .record_imported("server", &found, "Claude Desktop")
+            .unwrap();
+        managed.record_imported("server", &found, "Cursor").unwrap();
+        assert!(managed.is_managed_in("server", "Cursor").unwrap());
+        assert!(!managed.is_managed_in("server", "VS Code").unwrap());
+
+        let entry = managed.get("server").unwrap().unwrap();
+        assert_eq!(entry.origin, ManagedOrigin::Imported);
+        assert_eq!(entry.clients, vec!["Claude Desktop", "Cursor"]);
+        assert_eq!(entry.imported_config, Some(found));
+
+        managed.forget("server", "Claude Desktop").unwrap();
+        managed.forget("server", "Cursor").unwrap();
+        assert!(managed.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set_channel() {
+        let temp_dir = TempDir::new().unwrap();
+        let managed = ManagedServers::with_path(temp_dir.path().join("managed.json"));
+        let added = config("npx", &["-y", "@org/server@1.0.0"]);
+
+        managed.record_added("server", &added, "Cursor").unwrap();
+        assert_eq!(
+            managed.get("server").unwrap().unwrap().channel,
+            UpdateChannel::Stable
+        );
+
+        managed.set_channel("server", UpdateChannel::Beta).unwrap();
+        managed.record_added("server", &added, "VS Code").unwrap();
+        assert_eq!(
+            managed.get("server").unwrap().unwrap().channel,
+            UpdateChannel::Beta
+        );
+        assert!(managed.set_channel("other", UpdateChannel::Beta).is_err());
+    }
+
+    #[test]
+    fn test_instances() {
+        let temp_dir = TempDir::new().unwrap();
+        let managed = ManagedServers::with_path(temp_dir.path().join("managed.json"));
+        let fs = |dir: &str| {
+            config(
+                "npx",
+                &["-y", "@modelcontextprotocol/server-filesystem", dir],
+            )
+        };
+
+        managed
+            .record_added("filesystem", &fs("/home"), "Cursor")
+            .unwrap();
+        for (name, dir) in [("docs-fs", "/docs"), ("code-fs", "/code")] {
+            managed.record_added(name, &fs(dir), "Cursor").unwrap();
+            managed.set_instance_of(name, "filesystem").unwrap();
+        }
+
+        assert_eq!(
+            managed.instances_of("filesystem").unwrap(),
+            ["code-fs", "docs-fs"]
+        );
+        assert!(managed.instances_of("docs-fs").unwrap().is_empty());
+        managed.forget("docs-fs", "Cursor").unwrap();
+        assert_eq!(managed.instances_of("filesystem").unwrap(), ["code-fs"]);
+        assert!(managed.set_instance_of("other", "filesystem").is_err());
+    }
+}
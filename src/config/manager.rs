@@ -2,12 +2,17 @@ use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::client::{McpClient, ServerConfig};
 use crate::config::validator::{ConfigValidator, ValidationError};
+use crate::config::variables::expand_server_config;
+use crate::debug_env::display_value;
 use crate::server::McpServer;
+use crate::utils::clock::{Clock, SystemClock};
+use crate::utils::filesystem::{FileSystem, RealFileSystem};
+use crate::utils::paths;
 
 /// Configuration snapshot for rollback support
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,19 +35,41 @@ pub struct ConfigHistory {
 pub struct ConfigManager {
     history_dir: PathBuf,
     max_history_entries: usize,
+    clock: Arc<dyn Clock>,
+    fs: Arc<dyn FileSystem>,
 }
 
 impl ConfigManager {
     pub fn new() -> Result<Self> {
-        let history_dir = Self::get_history_dir()?;
-        fs::create_dir_all(&history_dir)?;
+        Self::open(
+            Self::get_history_dir()?,
+            Arc::new(SystemClock),
+            Arc::new(RealFileSystem),
+        )
+    }
+
+    /// Keep history in `history_dir`, reading the time from `clock` and files through `fs`
+    pub fn open(
+        history_dir: PathBuf,
+        clock: Arc<dyn Clock>,
+        fs: Arc<dyn FileSystem>,
+    ) -> Result<Self> {
+        fs.create_dir_all(&history_dir)?;
 
         Ok(Self {
             history_dir,
             max_history_entries: 10,
+            clock,
+            fs,
         })
     }
 
+    /// Keep at most `max` snapshots
+    pub fn with_max_history_entries(mut self, max: usize) -> Self {
+        self.max_history_entries = max;
+        self
+    }
+
     /// Validate a configuration before applying it
     pub fn validate_config(
         &self,
@@ -77,13 +104,15 @@ impl ConfigManager {
         server_name: &str,
         new_config: ServerConfig,
     ) -> Result<ConfigSnapshot> {
+        let new_config = expand_server_config(new_config, server_name);
+
         // Get current configuration
         let current_servers = client.list_servers()?;
         let previous_config = current_servers.get(server_name).cloned();
 
         // Create snapshot before applying
         let snapshot = ConfigSnapshot {
-            timestamp: Utc::now(),
+            timestamp: self.clock.now(),
             client_name: client.name().to_string(),
             server_name: server_name.to_string(),
             config: new_config.clone(),
@@ -100,6 +129,38 @@ impl ConfigManager {
         Ok(snapshot)
     }
 
+    /// Remove a server configuration, keeping a snapshot so it can be restored
+    ///
+    /// Returns `Ok(None)` if the server is not configured for this client.
+    pub fn remove_config(
+        &self,
+        client: &dyn McpClient,
+        server_name: &str,
+    ) -> Result<Option<ConfigSnapshot>> {
+        let current_servers = client.list_servers()?;
+        let Some(removed) = current_servers.get(server_name).cloned() else {
+            return Ok(None);
+        };
+
+        // Rolling back this snapshot re-adds the removed configuration
+        let snapshot = ConfigSnapshot {
+            timestamp: self.clock.now(),
+            client_name: client.name().to_string(),
+            server_name: server_name.to_string(),
+            config: removed.clone(),
+            previous_config: Some(removed),
+            description: format!("Removed {server_name}"),
+        };
+
+        self.save_snapshot(&snapshot)?;
+
+        if !client.remove_server(server_name)? {
+            return Ok(None);
+        }
+
+        Ok(Some(snapshot))
+    }
+
     /// Rollback to a previous configuration
     pub fn rollback(&self, client: &dyn McpClient, snapshot: &ConfigSnapshot) -> Result<()> {
         if let Some(ref previous_config) = snapshot.previous_config {
@@ -108,7 +169,7 @@ impl ConfigManager {
 
             // Create a rollback snapshot
             let rollback_snapshot = ConfigSnapshot {
-                timestamp: Utc::now(),
+                timestamp: self.clock.now(),
                 client_name: snapshot.client_name.clone(),
                 server_name: snapshot.server_name.clone(),
                 config: previous_config.clone(),
@@ -131,6 +192,33 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// Write the configuration recorded in `snapshot` back to `client`
+    ///
+    /// The restore is recorded as a snapshot of its own, so it can be rolled back too.
+    pub fn restore(
+        &self,
+        client: &dyn McpClient,
+        snapshot: &ConfigSnapshot,
+    ) -> Result<ConfigSnapshot> {
+        let previous_config = client.list_servers()?.remove(&snapshot.server_name);
+        client.add_server(&snapshot.server_name, snapshot.config.clone())?;
+
+        let restore_snapshot = ConfigSnapshot {
+            timestamp: self.clock.now(),
+            client_name: client.name().to_string(),
+            server_name: snapshot.server_name.clone(),
+            config: snapshot.config.clone(),
+            previous_config,
+            description: format!(
+                "Restored configuration from {}",
+                snapshot.timestamp.format("%Y-%m-%d %H:%M:%S")
+            ),
+        };
+        self.save_snapshot(&restore_snapshot)?;
+
+        Ok(restore_snapshot)
+    }
+
     /// Get configuration history for a specific client and server
     pub fn get_history(
         &self,
@@ -150,8 +238,10 @@ impl ConfigManager {
             snapshots.retain(|s| s.server_name == server);
         }
 
-        // Sort by timestamp (newest first)
-        snapshots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        // Sort by timestamp (newest first); snapshots are appended, so among equal
+        // timestamps the later one is newer
+        snapshots.reverse();
+        snapshots.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
 
         Ok(snapshots)
     }
@@ -167,6 +257,8 @@ impl ConfigManager {
     }
 
     /// Compare two configurations and return differences
+    ///
+    /// Values of credential-like variables are masked.
     pub fn diff_configs(
         &self,
         old_config: &ServerConfig,
@@ -197,7 +289,10 @@ impl ConfigManager {
         // Added env vars
         for key in new_keys.difference(&old_keys) {
             if let Some(value) = new_config.env.get(*key) {
-                differences.push(format!("Added env var: {key}={value}"));
+                differences.push(format!(
+                    "Added env var: {key}={}",
+                    display_value(key, value)
+                ));
             }
         }
 
@@ -211,6 +306,10 @@ impl ConfigManager {
             let old_val = old_config.env.get(*key);
             let new_val = new_config.env.get(*key);
             if old_val != new_val {
+                let (old_val, new_val) = (
+                    old_val.map(|value| display_value(key, value)),
+                    new_val.map(|value| display_value(key, value)),
+                );
                 differences.push(format!("Modified env var {key}: {old_val:?} → {new_val:?}"));
             }
         }
@@ -224,9 +323,7 @@ impl ConfigManager {
 
         if history.snapshots.len() > self.max_history_entries {
             // Sort by timestamp (oldest first)
-            history
-                .snapshots
-                .sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+            history.snapshots.sort_by_key(|s| s.timestamp);
 
             // Keep only the most recent entries
             let to_remove = history.snapshots.len() - self.max_history_entries;
@@ -238,16 +335,7 @@ impl ConfigManager {
     }
 
     fn get_history_dir() -> Result<PathBuf> {
-        // Check if XDG_DATA_HOME is set (for testing)
-        if let Ok(xdg_data) = std::env::var("XDG_DATA_HOME") {
-            return Ok(PathBuf::from(xdg_data)
-                .join("mcp-helper")
-                .join("config-history"));
-        }
-
-        let base_dir = directories::ProjectDirs::from("com", "mcp", "mcp-helper")
-            .context("Failed to get project directories")?;
-        Ok(base_dir.data_dir().join("config-history"))
+        Ok(paths::data_dir()?.join("config-history"))
     }
 
     fn get_history_file(&self) -> PathBuf {
@@ -257,9 +345,11 @@ impl ConfigManager {
     fn load_history(&self) -> Result<ConfigHistory> {
         let history_file = self.get_history_file();
 
-        if history_file.exists() {
-            let contents =
-                fs::read_to_string(&history_file).context("Failed to read history file")?;
+        if self.fs.exists(&history_file) {
+            let contents = self
+                .fs
+                .read_to_string(&history_file)
+                .context("Failed to read history file")?;
             serde_json::from_str(&contents).context("Failed to parse history file")
         } else {
             Ok(ConfigHistory {
@@ -273,7 +363,8 @@ impl ConfigManager {
         let contents =
             serde_json::to_string_pretty(history).context("Failed to serialize history")?;
 
-        crate::utils::secure_file::write_json_secure(&history_file, &contents)
+        self.fs
+            .write_secure(&history_file, &contents)
             .context("Failed to write history file")?;
 
         Ok(())
@@ -367,6 +458,7 @@ mod tests {
             command: "node".to_string(),
             args: vec!["server.js".to_string()],
             env: HashMap::new(),
+            cwd: None,
         };
 
         let _snapshot1 = manager
@@ -404,6 +496,7 @@ mod tests {
             command: "node".to_string(),
             args: vec!["server.js".to_string()],
             env: HashMap::from([("PORT".to_string(), "3000".to_string())]),
+            cwd: None,
         };
 
         let mut config2 = config1.clone();
@@ -450,6 +543,7 @@ mod tests {
                 command: "test".to_string(),
                 args: vec![],
                 env: HashMap::new(),
+                cwd: None,
             },
             previous_config: None,
             description: "Test 1".to_string(),
@@ -463,6 +557,7 @@ mod tests {
                 command: "test".to_string(),
                 args: vec![],
                 env: HashMap::new(),
+                cwd: None,
             },
             previous_config: None,
             description: "Test 2".to_string(),
@@ -491,4 +586,41 @@ mod tests {
         // Clean up environment variable
         std::env::remove_var("XDG_DATA_HOME");
     }
+
+    #[test]
+    fn test_history_retention_with_mock_clock() {
+        use crate::test_utils::mocks::{MemoryFileSystem, MockClock};
+        use std::time::Duration;
+
+        let clock = Arc::new(MockClock::default());
+        let fs = Arc::new(MemoryFileSystem::new());
+        let manager = ConfigManager::open(PathBuf::from("/history"), clock.clone(), fs.clone())
+            .unwrap()
+            .with_max_history_entries(3);
+        let client = MockClient {
+            name: "test-client".to_string(),
+            servers: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        for port in 0..5 {
+            let config = ServerConfig {
+                command: "node".to_string(),
+                args: vec![format!("--port={port}")],
+                env: HashMap::new(),
+                cwd: None,
+            };
+            manager.apply_config(&client, "server", config).unwrap();
+            clock.advance(Duration::from_secs(60));
+        }
+
+        // Only the three newest snapshots survive, newest first
+        let history = manager.get_history(None, None).unwrap();
+        let ports: Vec<_> = history.iter().map(|s| s.config.args[0].clone()).collect();
+        assert_eq!(ports, ["--port=4", "--port=3", "--port=2"]);
+        assert_eq!(
+            (history[0].timestamp - history[2].timestamp).num_seconds(),
+            120
+        );
+        assert_eq!(fs.paths(), vec![PathBuf::from("/history/history.json")]);
+    }
 }
@@ -0,0 +1,203 @@
+//! Meta-servers: one logical name that expands to several servers.
+//!
+//! Definitions live in the preferences file under `meta_servers`:
+//!
+//! ```json
+//! {
+//!   "meta_servers": {
+//!     "backend-toolkit": {
+//!       "description": "Servers for backend development",
+//!       "members": [
+//!         { "server": "@modelcontextprotocol/server-postgres", "env": { "DATABASE_URL": "" } },
+//!         { "server": "@modelcontextprotocol/server-github", "env": { "GITHUB_TOKEN": "" } },
+//!         { "server": "docker:mcp/fetch", "name": "fetch" }
+//!       ]
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! Empty environment values are prompted for once during install, even when several
+//! members share the same variable. Installed meta-servers are tracked in
+//! [`MetaMembership`] so removing the meta-server removes every member it installed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::utils::paths;
+use crate::utils::state_file::{read_state, write_state};
+
+/// Composite server definition
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetaServerDefinition {
+    #[serde(default)]
+    pub description: Option<String>,
+    pub members: Vec<MetaMember>,
+}
+
+/// One server installed as part of a meta-server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetaMember {
+    /// Server spec as accepted by `mcp add` (npm package, `docker:image`, ...)
+    pub server: String,
+    /// Name used in client configs; defaults to the detected server name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Explicit command; when set, `server` is only used as the config name
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Preset environment; empty values are prompted for during install
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+impl MetaServerDefinition {
+    /// Environment variables left empty in the definition, deduplicated across members.
+    pub fn prompted_variables(&self) -> Vec<String> {
+        self.members
+            .iter()
+            .flat_map(|member| {
+                member
+                    .env
+                    .iter()
+                    .filter(|(_, value)| value.is_empty())
+                    .map(|(key, _)| key.clone())
+            })
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Servers installed by a meta-server
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InstalledMetaServer {
+    pub members: Vec<String>,
+    pub clients: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MembershipFile {
+    #[serde(default)]
+    meta_servers: BTreeMap<String, InstalledMetaServer>,
+}
+
+/// Tracks which servers each installed meta-server added
+#[derive(Debug, Clone)]
+pub struct MetaMembership {
+    path: PathBuf,
+}
+
+impl MetaMembership {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_path(Self::default_path()?))
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn default_path() -> Result<PathBuf> {
+        Ok(paths::data_dir()?.join("meta-servers.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn load(&self) -> Result<MembershipFile> {
+        read_state(&self.path, MembershipFile::default)
+            .context("Failed to read meta-server membership")
+    }
+
+    fn save(&self, file: &MembershipFile) -> Result<()> {
+        write_state(&self.path, file).context("Failed to write meta-server membership")
+    }
+
+    /// Record (or extend) the servers and clients an install added.
+    pub fn record(&self, meta_name: &str, members: &[String], clients: &[String]) -> Result<()> {
+        let mut file = self.load()?;
+        let entry = file.meta_servers.entry(meta_name.to_string()).or_default();
+
+        for member in members {
+            if !entry.members.contains(member) {
+                entry.members.push(member.clone());
+            }
+        }
+        for client in clients {
+            if !entry.clients.contains(client) {
+                entry.clients.push(client.clone());
+            }
+        }
+
+        self.save(&file)
+    }
+
+    /// Servers installed by `meta_name`, if it is installed
+    pub fn get(&self, meta_name: &str) -> Result<Option<InstalledMetaServer>> {
+        Ok(self.load()?.meta_servers.remove(meta_name))
+    }
+
+    /// Stop tracking a meta-server after its members were removed
+    pub fn forget(&self, meta_name: &str) -> Result<()> {
+        let mut file = self.load()?;
+        if file.meta_servers.remove(meta_name).is_some() {
+            self.save(&file)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_definition_deserialize_and_prompted_variables() {
+        let definition: MetaServerDefinition = serde_json::from_str(
+            r#"{
+                "members": [
+                    { "server": "@org/server-a", "env": { "TOKEN": "", "REGION": "eu" } },
+                    { "server": "@org/server-b", "name": "b", "env": { "TOKEN": "" } },
+                    { "server": "docker:org/c" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(definition.members.len(), 3);
+        assert_eq!(definition.members[1].name.as_deref(), Some("b"));
+        assert_eq!(definition.prompted_variables(), vec!["TOKEN"]);
+    }
+
+    #[test]
+    fn test_membership_record_and_forget() {
+        let temp_dir = TempDir::new().unwrap();
+        let membership = MetaMembership::with_path(temp_dir.path().join("meta.json"));
+
+        assert_eq!(membership.get("toolkit").unwrap(), None);
+
+        membership
+            .record(
+                "toolkit",
+                &["a".to_string(), "b".to_string()],
+                &["Cursor".to_string()],
+            )
+            .unwrap();
+        membership
+            .record("toolkit", &["b".to_string()], &["VS Code".to_string()])
+            .unwrap();
+
+        let installed = membership.get("toolkit").unwrap().unwrap();
+        assert_eq!(installed.members, vec!["a", "b"]);
+        assert_eq!(installed.clients, vec!["Cursor", "VS Code"]);
+
+        membership.forget("toolkit").unwrap();
+        assert_eq!(membership.get("toolkit").unwrap(), None);
+    }
+}
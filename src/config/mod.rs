@@ -1,5 +1,23 @@
+pub mod consistency;
+pub mod env_overlap;
+pub mod hooks;
+pub mod lint;
+pub mod managed;
 pub mod manager;
+pub mod meta;
+pub mod preferences;
+pub mod repair;
 pub mod validator;
+pub mod variables;
 
+pub use hooks::{Hook, HookConfig, HookContext, HookEvent, HookRunner};
+pub use lint::{ConfigLinter, LintFinding, LintRule};
+pub use managed::{ManagedOrigin, ManagedServer, ManagedServers, ServerKind};
 pub use manager::{ConfigHistory, ConfigManager, ConfigSnapshot};
+pub use meta::{InstalledMetaServer, MetaMember, MetaMembership, MetaServerDefinition};
+pub use preferences::{
+    ClientPolicy, CommandTemplate, DependencyCheckPolicy, DownloadPolicy, FileKeySource, NpmPolicy,
+    Preferences, SecretBackend, SecretsPolicy, SecurityPolicy, UpdatePolicy,
+};
 pub use validator::{ConfigValidator, ValidationError, ValidationResult};
+pub use variables::{expand_server_config, TemplateVariables};
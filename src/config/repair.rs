@@ -0,0 +1,462 @@
+//! Recovery of client config files that are no longer valid JSON.
+//!
+//! Hand edits leave comments and trailing commas behind, and interrupted writes
+//! leave files cut off. `mcp config repair` shows where parsing fails and offers,
+//! from least to most lossy:
+//!
+//! - Tolerant parsing that drops comments and trailing commas
+//! - The `.json.backup` clients write before every save
+//! - Rebuilding the servers from mcp-helper's config history
+//! - A fresh config holding the server entries that still parse on their own
+
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::client::{McpClient, ServerConfig};
+use crate::config::ConfigSnapshot;
+use crate::utils::parse_error::{parse_json, ParseError};
+use crate::utils::secure_file::write_json_secure;
+
+/// One way to repair a config, from [`repair_choices`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepairChoice {
+    /// The file with comments and trailing commas dropped
+    Tolerant(Value),
+    /// The backup the client wrote before its last save
+    Backup(PathBuf, Value),
+    /// Servers replayed from mcp-helper's config history
+    History(BTreeMap<String, ServerConfig>),
+    /// A fresh config with the entries that still parse
+    Salvage(Map<String, Value>),
+}
+
+impl RepairChoice {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Tolerant(_) => "Remove comments and trailing commas".to_string(),
+            Self::Backup(path, _) => format!("Restore the backup {}", path.display()),
+            Self::History(servers) => format!(
+                "Rebuild from mcp-helper's config history ({} server(s))",
+                servers.len()
+            ),
+            Self::Salvage(servers) => format!(
+                "Start a fresh config with the server entries that still parse ({})",
+                servers.len()
+            ),
+        }
+    }
+}
+
+/// Ways to repair the broken config of `client` at `path`, least lossy first
+pub fn repair_choices(
+    client: &str,
+    path: &Path,
+    content: &str,
+    history: &[ConfigSnapshot],
+) -> Vec<RepairChoice> {
+    let mut choices = Vec::new();
+    if let Some(value) = tolerant_parse(content) {
+        choices.push(RepairChoice::Tolerant(value));
+    }
+
+    let backup = path.with_extension("json.backup");
+    if let Some(value) = std::fs::read_to_string(&backup)
+        .ok()
+        .and_then(|backup| parse_json::<Value>("", &backup).ok())
+    {
+        choices.push(RepairChoice::Backup(backup, value));
+    }
+
+    let servers = history_servers(history, client);
+    if !servers.is_empty() {
+        choices.push(RepairChoice::History(servers));
+    }
+
+    choices.push(RepairChoice::Salvage(salvage_servers(
+        content,
+        servers_key(client),
+    )));
+    choices
+}
+
+/// Write `choice` over the broken config of `client` at `path`, keeping the broken
+/// `content` next to it. Returns where the broken file was kept.
+pub fn apply_repair(
+    client: &dyn McpClient,
+    path: &Path,
+    content: &str,
+    choice: &RepairChoice,
+) -> Result<PathBuf> {
+    // Keep the broken file so nothing is lost for good
+    let kept = path.with_file_name(format!(
+        "{}.corrupt-{}",
+        path.file_name().unwrap_or_default().to_string_lossy(),
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+    std::fs::write(&kept, content)?;
+
+    let key = servers_key(client.name());
+    let write = |value: &Value| -> Result<()> {
+        let json = serde_json::to_string_pretty(value)?;
+        write_json_secure(path, &json)
+    };
+    match choice {
+        RepairChoice::Tolerant(value) | RepairChoice::Backup(_, value) => write(value)?,
+        RepairChoice::Salvage(servers) => write(&serde_json::json!({ key: servers }))?,
+        RepairChoice::History(servers) => {
+            write(&serde_json::json!({ key: {} }))?;
+            for (name, config) in servers {
+                client.add_server(name, config.clone())?;
+            }
+        }
+    }
+    Ok(kept)
+}
+
+/// Key holding the servers in `client`'s config file
+pub fn servers_key(client: &str) -> &'static str {
+    if client == "VS Code" {
+        "servers"
+    } else {
+        "mcpServers"
+    }
+}
+
+/// Where `content` stops being valid JSON, or `None` if it parses
+pub fn diagnose(file: &str, content: &str) -> Option<ParseError> {
+    parse_json::<Value>(file, content).err()
+}
+
+/// The line an error points at, with a caret under its column
+pub fn excerpt(content: &str, error: &ParseError) -> String {
+    let line = content.lines().nth(error.line - 1).unwrap_or_default();
+    let number = error.line.to_string();
+    format!(
+        "{number} | {line}\n{} | {}^",
+        " ".repeat(number.len()),
+        " ".repeat(error.column.saturating_sub(1))
+    )
+}
+
+/// Parse `content` after dropping comments and trailing commas
+pub fn tolerant_parse(content: &str) -> Option<Value> {
+    let cleaned = strip_trailing_commas(&strip_comments(content));
+    parse_json("", &cleaned).ok()
+}
+
+/// `content` without `//` and `/* */` comments outside strings
+pub fn strip_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                // Keep the newline so line numbers stay the same
+                while chars.peek().is_some_and(|&next| next != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = '\0';
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                    }
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// `content` without commas directly before `}` or `]`
+pub fn strip_trailing_commas(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|next| !next.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Server entries under `key` that still parse on their own.
+///
+/// Scanning stops at the first entry that cannot be delimited, so a file cut off
+/// mid-write keeps every server before the cut.
+pub fn salvage_servers(content: &str, key: &str) -> Map<String, Value> {
+    let cleaned = strip_comments(content);
+    let bytes = cleaned.as_bytes();
+    let mut servers = Map::new();
+
+    let Some(start) = cleaned.find(&format!("\"{key}\"")) else {
+        return servers;
+    };
+    let mut pos = start + key.len() + 2;
+    if !expect(bytes, &mut pos, b':') || !expect(bytes, &mut pos, b'{') {
+        return servers;
+    }
+
+    loop {
+        skip_separators(bytes, &mut pos);
+        if pos >= bytes.len() || bytes[pos] != b'"' {
+            break;
+        }
+        let Some(name_end) = string_end(bytes, pos) else {
+            break;
+        };
+        let Ok(name) = serde_json::from_str::<String>(&cleaned[pos..=name_end]) else {
+            break;
+        };
+        pos = name_end + 1;
+        if !expect(bytes, &mut pos, b':') {
+            break;
+        }
+        skip_separators(bytes, &mut pos);
+        if pos >= bytes.len() || bytes[pos] != b'{' {
+            break;
+        }
+        let Some(entry_end) = matching_brace(bytes, pos) else {
+            break;
+        };
+        let entry = strip_trailing_commas(&cleaned[pos..=entry_end]);
+        if let Ok(value @ Value::Object(_)) = serde_json::from_str::<Value>(&entry) {
+            if value.get("command").is_some() || value.get("url").is_some() {
+                servers.insert(name, value);
+            }
+        }
+        pos = entry_end + 1;
+    }
+    servers
+}
+
+/// Servers of `client` as mcp-helper last wrote them, replayed from history
+pub fn history_servers(
+    snapshots: &[ConfigSnapshot],
+    client: &str,
+) -> BTreeMap<String, ServerConfig> {
+    let mut ordered: Vec<&ConfigSnapshot> = snapshots
+        .iter()
+        .filter(|snapshot| snapshot.client_name == client)
+        .collect();
+    ordered.sort_by_key(|snapshot| snapshot.timestamp);
+
+    let mut servers = BTreeMap::new();
+    for snapshot in ordered {
+        if snapshot.description.starts_with("Removed ") {
+            servers.remove(&snapshot.server_name);
+        } else {
+            servers.insert(snapshot.server_name.clone(), snapshot.config.clone());
+        }
+    }
+    servers
+}
+
+fn skip_separators(bytes: &[u8], pos: &mut usize) {
+    while *pos < bytes.len() && (bytes[*pos].is_ascii_whitespace() || bytes[*pos] == b',') {
+        *pos += 1;
+    }
+}
+
+fn expect(bytes: &[u8], pos: &mut usize, token: u8) -> bool {
+    while *pos < bytes.len() && bytes[*pos].is_ascii_whitespace() {
+        *pos += 1;
+    }
+    if *pos < bytes.len() && bytes[*pos] == token {
+        *pos += 1;
+        true
+    } else {
+        false
+    }
+}
+
+/// Index of the quote closing the string that opens at `start`
+fn string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut escaped = false;
+    for (i, &b) in bytes.iter().enumerate().skip(start + 1) {
+        if escaped {
+            escaped = false;
+        } else if b == b'\\' {
+            escaped = true;
+        } else if b == b'"' {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// Index of the brace closing the object that opens at `start`
+fn matching_brace(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = string_end(bytes, i)?,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return (bytes[i] == b'}').then_some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_diagnose_and_excerpt() {
+        assert!(diagnose("config.json", "{}").is_none());
+
+        let error = diagnose("config.json", "{\n  \"a\": 1 \"b\": 2\n}").unwrap();
+        assert_eq!(error.line, 2);
+        let shown = excerpt("{\n  \"a\": 1 \"b\": 2\n}", &error);
+        assert!(shown.starts_with("2 |   \"a\": 1 \"b\": 2\n  | "));
+        assert!(shown.ends_with('^'));
+    }
+
+    #[test]
+    fn test_tolerant_parse() {
+        let content = r#"{
+  // added by hand
+  "mcpServers": {
+    "files": { "command": "npx", "args": ["-y", "server", ], }, /* old: "x" */
+  },
+  "note": "keep // this, and this ,}"
+}"#;
+        let value = tolerant_parse(content).unwrap();
+        assert_eq!(value["mcpServers"]["files"]["args"][1], "server");
+        assert_eq!(value["note"], "keep // this, and this ,}");
+
+        assert!(tolerant_parse("{ \"a\": ").is_none());
+    }
+
+    #[test]
+    fn test_salvage_servers_from_truncated_file() {
+        let content = r#"{
+  "mcpServers": {
+    "files": { "command": "npx", "args": ["-y", "@modelcontextprotocol/server-filesystem"] },
+    "remote": { "url": "https://example.com/mcp", "headers": { "X": "{not a brace}" } },
+    "broken": { "command": "npx" "args": [] },
+    "notes": { "comm"#;
+        let servers = salvage_servers(content, "mcpServers");
+        assert_eq!(servers.keys().collect::<Vec<_>>(), vec!["files", "remote"]);
+        assert_eq!(servers["remote"]["headers"]["X"], "{not a brace}");
+
+        assert!(salvage_servers(content, "servers").is_empty());
+        assert!(salvage_servers("", "mcpServers").is_empty());
+    }
+
+    #[test]
+    fn test_history_servers_replays_snapshots() {
+        let config = |command: &str| ServerConfig {
+            command: command.to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        let snapshot =
+            |minute: u32, server: &str, description: &str, command: &str| ConfigSnapshot {
+                timestamp: Utc.with_ymd_and_hms(2026, 1, 1, 0, minute, 0).unwrap(),
+                client_name: "Claude Desktop".to_string(),
+                server_name: server.to_string(),
+                config: config(command),
+                previous_config: None,
+                description: description.to_string(),
+            };
+        let snapshots = vec![
+            snapshot(3, "a", "Configuration update for a", "node"),
+            snapshot(1, "a", "Configuration update for a", "npx"),
+            snapshot(2, "b", "Configuration update for b", "docker"),
+            snapshot(4, "b", "Removed b", "docker"),
+        ];
+
+        let servers = history_servers(&snapshots, "Claude Desktop");
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers["a"].command, "node");
+        assert!(history_servers(&snapshots, "Cursor").is_empty());
+    }
+
+    #[test]
+    fn test_repair_choices() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("claude_desktop_config.json");
+        let truncated = r#"{ "mcpServers": { "a": { "command": "npx" }, "b": {"#;
+
+        let choices = repair_choices("Claude Desktop", &path, truncated, &[]);
+        assert_eq!(choices.len(), 1);
+        assert!(matches!(&choices[0], RepairChoice::Salvage(servers) if servers.len() == 1));
+        assert_eq!(
+            choices[0].label(),
+            "Start a fresh config with the server entries that still parse (1)"
+        );
+
+        std::fs::write(
+            path.with_extension("json.backup"),
+            r#"{ "mcpServers": {} }"#,
+        )
+        .unwrap();
+        let choices = repair_choices("Claude Desktop", &path, "{ \"mcpServers\": {}, }", &[]);
+        assert!(matches!(choices[0], RepairChoice::Tolerant(_)));
+        assert!(matches!(choices[1], RepairChoice::Backup(..)));
+        assert!(matches!(choices[2], RepairChoice::Salvage(_)));
+    }
+
+    #[test]
+    fn test_servers_key() {
+        assert_eq!(servers_key("VS Code"), "servers");
+        assert_eq!(servers_key("Claude Desktop"), "mcpServers");
+    }
+}
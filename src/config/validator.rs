@@ -1,9 +1,14 @@
 use crate::server::{ConfigField, ConfigFieldType};
-use anyhow::{bail, Result};
+use crate::utils::process::Process;
+use anyhow::{bail, Context, Result};
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 use url::Url;
 
+/// How long `--version` or `--help` may take when checking that a command runs
+const AVAILABILITY_TIMEOUT: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone)]
 pub struct ValidationError {
     pub field: String,
@@ -141,8 +146,6 @@ impl ConfigValidator {
 
     /// Test if a command is available in the system
     pub fn test_command_availability(command: &str, _args: &[String]) -> Result<()> {
-        use std::process::Command;
-
         // Special handling for common commands
         let test_args = match command {
             "node" | "python" | "python3" | "docker" => vec!["--version"],
@@ -153,17 +156,15 @@ impl ConfigValidator {
             }
         };
 
-        let output = Command::new(command)
+        let output = Process::new(command)
             .args(&test_args)
+            .timeout(AVAILABILITY_TIMEOUT)
             .output()
-            .map_err(|e| anyhow::anyhow!("Command '{command}' not found: {e}"))?;
+            .with_context(|| format!("Command '{command}' not found"))?;
 
-        if !output.status.success() && output.status.code() != Some(1) {
+        if !output.success() && output.status.code() != Some(1) {
             // Exit code 1 is often used for --help, so we allow it
-            bail!(
-                "Command '{command}' failed: {}",
-                String::from_utf8_lossy(&output.stderr)
-            );
+            bail!("Command '{command}' failed: {}", output.stderr_lossy());
         }
 
         Ok(())
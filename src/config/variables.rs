@@ -0,0 +1,243 @@
+//! Variables mcp-helper fills in when it writes a server config.
+//!
+//! Commands, arguments, environment values and working directories may reference
+//! these, so one state file, install manifest or shared profile works on every
+//! machine without hardcoded paths:
+//!
+//! | Variable | Expands to |
+//! |---|---|
+//! | `{{home}}` | The user's home directory |
+//! | `{{data_dir}}` | A data directory of the server's own, under mcp-helper's data directory |
+//! | `{{cache_dir}}` | A cache directory of the server's own, under mcp-helper's cache directory |
+//! | `{{server_name}}` | The name the server is configured under |
+//!
+//! Paths use the platform's separator, including the `/`s written after a path
+//! variable, so `{{home}}/notes` becomes `C:\Users\me\notes` on Windows.
+//! `{{data_dir}}` and `{{cache_dir}}` are created when a config using them is
+//! written. Other `{{...}}` placeholders are left alone.
+
+use std::path::{PathBuf, MAIN_SEPARATOR};
+
+use crate::cache::CacheManager;
+use crate::client::ServerConfig;
+use crate::utils::paths;
+
+/// Names of the variables, as written between `{{` and `}}`
+pub const VARIABLES: &[&str] = &["home", "data_dir", "cache_dir", "server_name"];
+
+/// Whether `name` (without braces) is one of the [`VARIABLES`]
+pub fn is_variable(name: &str) -> bool {
+    VARIABLES.contains(&name)
+}
+
+/// Values of the variables for one server
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateVariables {
+    pub home: PathBuf,
+    pub data_dir: PathBuf,
+    pub cache_dir: PathBuf,
+    pub server_name: String,
+    /// Separator `/` after a path variable is turned into
+    pub separator: char,
+}
+
+impl TemplateVariables {
+    /// The variables for `server_name` on this machine
+    pub fn for_server(server_name: &str) -> anyhow::Result<Self> {
+        let home = directories::BaseDirs::new()
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?
+            .home_dir()
+            .to_path_buf();
+        let data = paths::data_dir()?;
+        let cache = CacheManager::default_cache_dir()?;
+        Ok(Self {
+            home,
+            data_dir: data.join("servers").join(server_name),
+            cache_dir: cache.join("servers").join(server_name),
+            server_name: server_name.to_string(),
+            separator: MAIN_SEPARATOR,
+        })
+    }
+
+    fn value(&self, name: &str) -> Option<(String, bool)> {
+        let path = |path: &PathBuf| Some((path.to_string_lossy().to_string(), true));
+        match name {
+            "home" => path(&self.home),
+            "data_dir" => path(&self.data_dir),
+            "cache_dir" => path(&self.cache_dir),
+            "server_name" => Some((self.server_name.clone(), false)),
+            _ => None,
+        }
+    }
+
+    /// `text` with every variable replaced
+    pub fn expand(&self, text: &str) -> String {
+        let mut result = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}") else {
+                break;
+            };
+            let name = rest[start + 2..start + end].trim();
+            result.push_str(&rest[..start]);
+            let after = &rest[start + end + 2..];
+            match self.value(name) {
+                Some((value, is_path)) => {
+                    result.push_str(&value);
+                    rest = after;
+                    if is_path && self.separator != '/' {
+                        // The rest of this path, up to the next separator between values
+                        let len = rest
+                            .find(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '{'))
+                            .unwrap_or(rest.len());
+                        result.push_str(&rest[..len].replace('/', &self.separator.to_string()));
+                        rest = &rest[len..];
+                    }
+                }
+                None => {
+                    result.push_str(&rest[start..start + end + 2]);
+                    rest = after;
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+
+    /// `config` with variables expanded in its command, arguments, environment values
+    /// and working directory
+    pub fn expand_config(&self, config: &ServerConfig) -> ServerConfig {
+        ServerConfig {
+            command: self.expand(&config.command),
+            args: config.args.iter().map(|arg| self.expand(arg)).collect(),
+            env: config
+                .env
+                .iter()
+                .map(|(key, value)| (key.clone(), self.expand(value)))
+                .collect(),
+            cwd: config.cwd.as_deref().map(|cwd| self.expand(cwd)),
+        }
+    }
+
+    /// Whether `config` references `{{name}}` anywhere
+    fn references(config: &ServerConfig, name: &str) -> bool {
+        let uses = |text: &str| {
+            text.split("{{")
+                .skip(1)
+                .filter_map(|part| part.split_once("}}"))
+                .any(|(found, _)| found.trim() == name)
+        };
+        uses(&config.command)
+            || config.args.iter().any(|arg| uses(arg))
+            || config.env.values().any(|value| uses(value))
+            || config.cwd.as_deref().is_some_and(uses)
+    }
+}
+
+/// `config` as it is written for `server_name`: with the variables expanded and the
+/// server's data and cache directories created if it uses them
+///
+/// The config is returned unchanged if it uses no variables or they can't be
+/// determined on this machine.
+pub fn expand_server_config(config: ServerConfig, server_name: &str) -> ServerConfig {
+    if !VARIABLES
+        .iter()
+        .any(|name| TemplateVariables::references(&config, name))
+    {
+        return config;
+    }
+    let variables = match TemplateVariables::for_server(server_name) {
+        Ok(variables) => variables,
+        Err(e) => {
+            tracing::warn!("Config variables of {} left unexpanded: {}", server_name, e);
+            return config;
+        }
+    };
+    for (name, dir) in [
+        ("data_dir", &variables.data_dir),
+        ("cache_dir", &variables.cache_dir),
+    ] {
+        if TemplateVariables::references(&config, name) {
+            if let Err(e) = std::fs::create_dir_all(dir) {
+                tracing::warn!("Failed to create {}: {}", dir.display(), e);
+            }
+        }
+    }
+    variables.expand_config(&config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn variables(separator: char) -> TemplateVariables {
+        let root = if separator == '/' {
+            "/home/me"
+        } else {
+            r"C:\Users\me"
+        };
+        let join = |rest: &str| PathBuf::from(format!("{root}{separator}{rest}"));
+        TemplateVariables {
+            home: PathBuf::from(root),
+            data_dir: join("data"),
+            cache_dir: join("cache"),
+            server_name: "notes".to_string(),
+            separator,
+        }
+    }
+
+    #[test]
+    fn test_expand() {
+        let unix = variables('/');
+        assert_eq!(unix.expand("{{home}}/notes"), "/home/me/notes");
+        assert_eq!(
+            unix.expand("--db={{ data_dir }}/{{server_name}}.db"),
+            "--db=/home/me/data/notes.db"
+        );
+        // Unknown placeholders and unterminated braces stay as they are
+        assert_eq!(unix.expand("{{API_KEY}} {{home"), "{{API_KEY}} {{home");
+    }
+
+    #[test]
+    fn test_expand_uses_platform_separator() {
+        let windows = variables('\\');
+        assert_eq!(
+            windows.expand("{{home}}/projects/a,{{cache_dir}}/tmp"),
+            r"C:\Users\me\projects\a,C:\Users\me\cache\tmp"
+        );
+        // Slashes outside paths are kept
+        assert_eq!(
+            windows.expand("https://example.com/{{server_name}}/x"),
+            "https://example.com/notes/x"
+        );
+    }
+
+    #[test]
+    fn test_expand_config() {
+        let config = ServerConfig {
+            command: "{{home}}/.local/bin/server".to_string(),
+            args: vec!["--name".to_string(), "{{server_name}}".to_string()],
+            env: HashMap::from([
+                ("CACHE".to_string(), "{{cache_dir}}".to_string()),
+                ("TOKEN".to_string(), "${TOKEN}".to_string()),
+            ]),
+            cwd: Some("{{data_dir}}".to_string()),
+        };
+        let expanded = variables('/').expand_config(&config);
+        assert_eq!(expanded.command, "/home/me/.local/bin/server");
+        assert_eq!(expanded.args, vec!["--name", "notes"]);
+        assert_eq!(expanded.env["CACHE"], "/home/me/cache");
+        assert_eq!(expanded.env["TOKEN"], "${TOKEN}");
+        assert_eq!(expanded.cwd.as_deref(), Some("/home/me/data"));
+
+        assert!(TemplateVariables::references(&config, "data_dir"));
+        let plain = ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["{{API_KEY}}".to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        assert!(!TemplateVariables::references(&plain, "home"));
+    }
+}
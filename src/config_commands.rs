@@ -1,24 +1,51 @@
 //! Configuration management commands for MCP Helper.
 //!
-//! This module implements the config subcommands: list, add, and remove.
+//! This module implements the config subcommands: list, add, remove, lint, repair,
+//! history and rollback.
 //! These commands manage server configurations across different MCP clients.
 
 use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use colored::Colorize;
-use dialoguer::{Confirm, Input, Select};
-use std::collections::HashMap;
+use dialoguer::Input;
+use std::collections::{BTreeSet, HashMap};
+use std::io::IsTerminal;
 
-use crate::client::{detect_clients, ServerConfig};
+use crate::client::{detect_clients, McpClient, ServerConfig};
+use crate::config::repair;
+use crate::config::{
+    expand_server_config, ConfigLinter, ConfigManager, ConfigSnapshot, InstalledMetaServer,
+    ManagedServers, MetaMembership,
+};
+use crate::docker_prune::{config_image, DockerPruneCommand};
 use crate::error::McpError;
+use crate::purge::{plan_purge, PurgePlan, PurgeRoots};
+use crate::update::UpdateChannel;
+use crate::utils::confirm::Confirmer;
+use crate::utils::pattern::NamePattern;
+use crate::utils::prompt::{Confirm, Select};
 
 /// List all configured servers across all MCP clients
 pub struct ConfigListCommand {
     verbose: bool,
+    filter: Option<NamePattern>,
 }
 
 impl ConfigListCommand {
     pub fn new(verbose: bool) -> Self {
-        Self { verbose }
+        Self {
+            verbose,
+            filter: None,
+        }
+    }
+
+    /// Only show servers whose names match a glob (or `re:` regex) pattern
+    pub fn set_filter(&mut self, pattern: Option<&str>) -> Result<(), McpError> {
+        self.filter = pattern
+            .map(NamePattern::parse)
+            .transpose()
+            .map_err(McpError::Other)?;
+        Ok(())
     }
 
     pub fn execute(&self) -> Result<(), McpError> {
@@ -28,6 +55,10 @@ impl ConfigListCommand {
         let clients = detect_clients();
         let mut found_any = false;
         let mut total_servers = 0;
+        // Channels are informational, so an unreadable record just hides them
+        let managed = ManagedServers::new()
+            .and_then(|managed| managed.list())
+            .unwrap_or_default();
 
         for client in &clients {
             if !client.is_installed() {
@@ -36,10 +67,23 @@ impl ConfigListCommand {
 
             let config_path = client.config_path();
 
-            match client.list_servers() {
-                Ok(servers) if !servers.is_empty() => {
+            let servers = client.list_servers().map(|servers| {
+                servers
+                    .into_iter()
+                    .filter(|(name, _)| self.filter.as_ref().is_none_or(|p| p.matches(name)))
+                    .collect::<HashMap<_, _>>()
+            });
+            let remote_servers: HashMap<_, _> = client
+                .list_remote_servers()
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(name, _)| self.filter.as_ref().is_none_or(|p| p.matches(name)))
+                .collect();
+
+            match servers {
+                Ok(servers) if !servers.is_empty() || !remote_servers.is_empty() => {
                     found_any = true;
-                    total_servers += servers.len();
+                    total_servers += servers.len() + remote_servers.len();
 
                     println!(
                         "{} {} ({})",
@@ -49,13 +93,47 @@ impl ConfigListCommand {
                     );
 
                     for (name, config) in servers.iter() {
+                        let channel = managed.get(name).map(|server| server.channel);
+                        let publisher = managed
+                            .get(name)
+                            .and_then(|server| server.publisher.as_ref());
+                        let instance_of = managed
+                            .get(name)
+                            .and_then(|server| server.instance_of.as_deref());
                         println!(
-                            "  • {}: {} {}",
+                            "  • {}{}: {} {}{}{}",
                             name.yellow(),
+                            match instance_of {
+                                Some(default_name) => {
+                                    format!(" (instance of {default_name})")
+                                        .dimmed()
+                                        .to_string()
+                                }
+                                None => String::new(),
+                            },
                             config.command.green(),
-                            config.args.join(" ").dimmed()
+                            config.args.join(" ").dimmed(),
+                            match channel {
+                                Some(UpdateChannel::Beta) => format!(" {}", "[beta]".magenta()),
+                                _ => String::new(),
+                            },
+                            match publisher {
+                                Some(publisher) if publisher.is_verified() => {
+                                    format!(" {}", publisher.badge())
+                                }
+                                _ => String::new(),
+                            }
                         );
 
+                        if self.verbose {
+                            if let Some(channel) = channel {
+                                println!("    Channel: {channel}");
+                            }
+                            if let Some(publisher) = publisher {
+                                println!("    Publisher: {publisher}");
+                            }
+                        }
+
                         if self.verbose && !config.env.is_empty() {
                             println!("    Environment:");
                             for (key, value) in &config.env {
@@ -63,6 +141,29 @@ impl ConfigListCommand {
                             }
                         }
                     }
+
+                    for (name, remote) in remote_servers.iter() {
+                        println!(
+                            "  • {}: {} {}",
+                            name.yellow(),
+                            remote.url.green(),
+                            "(remote)".dimmed()
+                        );
+
+                        // Header values may be credentials, so only their names are shown
+                        if self.verbose && !remote.headers.is_empty() {
+                            let mut names: Vec<_> = remote.headers.keys().collect();
+                            names.sort();
+                            println!(
+                                "    Headers: {}",
+                                names
+                                    .iter()
+                                    .map(|n| n.cyan().to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            );
+                        }
+                    }
                     println!();
                 }
                 Ok(_) => {
@@ -88,7 +189,9 @@ impl ConfigListCommand {
             }
         }
 
-        if !found_any {
+        if !found_any && self.filter.is_some() {
+            println!("No MCP servers match the filter.");
+        } else if !found_any {
             println!("No MCP servers configured yet.");
             println!();
             println!("To configure a server, run:");
@@ -125,9 +228,7 @@ impl ConfigAddCommand {
         let installed_clients: Vec<_> = clients.iter().filter(|c| c.is_installed()).collect();
 
         if installed_clients.is_empty() {
-            return Err(McpError::Other(anyhow::anyhow!(
-                "No MCP clients found. Please install Claude Desktop, VS Code, or another supported client."
-            )));
+            return Err(crate::add::no_clients_error());
         }
 
         // Select which client to add to
@@ -192,7 +293,12 @@ impl ConfigAddCommand {
         }
 
         // Create the server config
-        let config = ServerConfig { command, args, env };
+        let config = ServerConfig {
+            command,
+            args,
+            env,
+            cwd: None,
+        };
 
         // Show preview
         println!();
@@ -224,7 +330,7 @@ impl ConfigAddCommand {
 
         // Add the server
         selected_client
-            .add_server(server_name, config)
+            .add_server(server_name, expand_server_config(config, server_name))
             .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to add server: {}", e)))?;
 
         println!(
@@ -240,9 +346,21 @@ impl ConfigAddCommand {
 
 /// Remove a server from MCP client configuration
 pub struct ConfigRemoveCommand {
-    #[allow(dead_code)]
     verbose: bool,
     remove_all: bool,
+    client: Option<String>,
+    purge: bool,
+    prune_docker: bool,
+    yes: bool,
+    dry_run: bool,
+}
+
+/// What to remove, as picked from the interactive menu
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RemovalChoice {
+    Client(usize),
+    AllClients,
+    AllClientsAndArtifacts,
 }
 
 impl ConfigRemoveCommand {
@@ -250,13 +368,43 @@ impl ConfigRemoveCommand {
         Self {
             verbose,
             remove_all: false,
+            client: None,
+            purge: false,
+            prune_docker: false,
+            yes: false,
+            dry_run: false,
         }
     }
 
+    /// Only remove the server from this client
+    pub fn set_client(&mut self, client: Option<String>) {
+        self.client = client;
+    }
+
+    /// Also delete the server's virtualenv, binaries, cached downloads and secrets
+    pub fn set_purge(&mut self, purge: bool) {
+        self.purge = purge;
+    }
+
+    /// Remove without asking for confirmation
+    pub fn set_yes(&mut self, yes: bool) {
+        self.yes = yes;
+    }
+
     pub fn set_remove_all(&mut self, remove_all: bool) {
         self.remove_all = remove_all;
     }
 
+    /// Offer to remove the containers and images of removed Docker servers
+    pub fn set_prune_docker(&mut self, prune_docker: bool) {
+        self.prune_docker = prune_docker;
+    }
+
+    /// Only show what would be removed
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
     pub fn execute(&self, server_name: &str) -> Result<(), McpError> {
         println!("{} Removing server: {}", "→".green(), server_name.cyan());
         println!();
@@ -279,33 +427,34 @@ impl ConfigRemoveCommand {
         }
 
         if found_in_clients.is_empty() {
+            if let Ok(membership) = MetaMembership::new() {
+                if let Ok(Some(installed)) = membership.get(server_name) {
+                    return self.execute_meta(&membership, server_name, &installed);
+                }
+            }
+
+            let instances = ManagedServers::new()
+                .and_then(|managed| managed.instances_of(server_name))
+                .unwrap_or_default();
+            if !instances.is_empty() {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Server '{}' is configured as the instances {}; remove each by name",
+                    server_name,
+                    instances.join(", ")
+                )));
+            }
+
             return Err(McpError::Other(anyhow::anyhow!(
                 "Server '{}' not found in any MCP client configuration",
                 server_name
             )));
         }
 
-        // If remove_all is set, remove from all clients
-        let selected_clients = if self.remove_all {
-            found_in_clients.clone()
-        } else if found_in_clients.len() == 1 {
-            vec![found_in_clients[0]]
-        } else {
-            // Multiple clients and not remove_all, ask which one
-            println!("Server found in multiple clients:");
-            let client_names: Vec<_> = found_in_clients.iter().map(|c| c.name()).collect();
-
-            let selection = Select::new()
-                .with_prompt("Select client to remove from")
-                .items(&client_names)
-                .interact()
-                .map_err(|e| McpError::Other(anyhow::anyhow!("Selection failed: {}", e)))?;
-
-            vec![found_in_clients[selection]]
-        };
+        let (selected_clients, purge) = self.select_removal(server_name, &found_in_clients)?;
 
         // Show what will be removed
         println!("{}", "Will remove:".yellow());
+        let mut removed_configs = Vec::new();
         for client in &selected_clients {
             if let Ok(servers) = client.list_servers() {
                 if let Some(config) = servers.get(server_name) {
@@ -315,76 +464,1096 @@ impl ConfigRemoveCommand {
                         config.command.green(),
                         config.args.join(" ").dimmed()
                     );
+                    removed_configs.push(config.clone());
                 }
             }
         }
         println!();
 
-        // Confirm removal
-        let prompt = if selected_clients.len() > 1 {
-            format!(
-                "Remove this server from {} clients?",
-                selected_clients.len()
-            )
+        let purge_plan = if purge {
+            let plan =
+                self.plan_purge(&clients, &selected_clients, server_name, &removed_configs)?;
+            print_purge_plan(&plan);
+            Some(plan)
         } else {
-            "Remove this server configuration?".to_string()
+            None
         };
 
-        let confirm = Confirm::new()
-            .with_prompt(prompt)
-            .default(false)
-            .interact()
-            .map_err(|e| McpError::Other(anyhow::anyhow!("Confirmation failed: {}", e)))?;
+        if self.dry_run {
+            self.print_docker_dry_run(&removed_configs);
+            println!("{} Dry run: nothing was removed", "ℹ".blue());
+            return Ok(());
+        }
+
+        // Removing from several clients at once or deleting files asks for the server name
+        let confirmer = Confirmer::new(self.yes);
+        let confirm = if purge_plan
+            .as_ref()
+            .is_some_and(|plan| !plan.delete.is_empty())
+        {
+            confirmer.confirm_typed(
+                "Remove this server and delete the files and secrets listed above?",
+                server_name,
+            )?
+        } else if selected_clients.len() > 1 {
+            confirmer.confirm_typed(
+                &format!(
+                    "Remove this server from {} clients?",
+                    selected_clients.len()
+                ),
+                server_name,
+            )?
+        } else {
+            confirmer.confirm("Remove this server configuration?")?
+        };
 
         if !confirm {
             println!("{} Removal cancelled", "❌".red());
             return Ok(());
         }
 
-        // For now, we'll need to implement remove_server in the McpClient trait
-        // As a workaround, we inform the user to manually edit
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+
+        for client in &selected_clients {
+            remove_with_snapshot(&manager, *client, server_name)?;
+        }
+
+        if let Some(plan) = purge_plan {
+            purge_artifacts(&plan)?;
+        }
+
+        self.prune_docker_images(&removed_configs)
+    }
+
+    /// The clients to remove `server_name` from, and whether to purge its artifacts
+    fn select_removal<'a>(
+        &self,
+        server_name: &str,
+        found_in_clients: &[&'a dyn McpClient],
+    ) -> Result<(Vec<&'a dyn McpClient>, bool), McpError> {
+        if let Some(name) = &self.client {
+            let client = found_in_clients
+                .iter()
+                .find(|c| c.name().eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    McpError::Other(anyhow::anyhow!(
+                        "Server '{}' is not configured in {}; it is configured in: {}",
+                        server_name,
+                        name,
+                        found_in_clients
+                            .iter()
+                            .map(|c| c.name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ))
+                })?;
+            return Ok((vec![*client], self.purge));
+        }
+
+        let interactive = std::io::stdin().is_terminal();
+        if self.remove_all
+            || (found_in_clients.len() == 1 && (self.yes || self.purge || !interactive))
+        {
+            // Without a terminal the confirmation explains that --yes is needed
+            return Ok((found_in_clients.to_vec(), self.purge));
+        }
+        if self.yes || self.purge || !interactive {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Server '{}' is configured in {} clients; pass --all to remove it from every one or --client to pick one",
+                server_name,
+                found_in_clients.len()
+            )));
+        }
+
+        let choices = removal_choices(found_in_clients);
+        let labels: Vec<String> = choices
+            .iter()
+            .map(|choice| match choice {
+                RemovalChoice::Client(index) => {
+                    format!("Only the {} entry", found_in_clients[*index].name())
+                }
+                RemovalChoice::AllClients if found_in_clients.len() == 1 => {
+                    format!("Only the {} entry", found_in_clients[0].name())
+                }
+                RemovalChoice::AllClients => {
+                    format!("The entries in all {} clients", found_in_clients.len())
+                }
+                RemovalChoice::AllClientsAndArtifacts => {
+                    "Every entry, plus the server's files, cached downloads and secrets".to_string()
+                }
+            })
+            .collect();
+
+        let selection = Select::new()
+            .with_prompt("What should be removed?")
+            .items(&labels)
+            .default(0)
+            .interact()
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Selection failed: {}", e)))?;
+
+        Ok(match choices[selection] {
+            RemovalChoice::Client(index) => (vec![found_in_clients[index]], false),
+            RemovalChoice::AllClients => (found_in_clients.to_vec(), false),
+            RemovalChoice::AllClientsAndArtifacts => (found_in_clients.to_vec(), true),
+        })
+    }
+
+    /// Plan the purge, keeping what the servers that remain configured still use
+    fn plan_purge(
+        &self,
+        clients: &[Box<dyn McpClient>],
+        selected: &[&dyn McpClient],
+        server_name: &str,
+        removed: &[ServerConfig],
+    ) -> Result<PurgePlan, McpError> {
+        let mut still_used = Vec::new();
+        for client in clients.iter().filter(|c| c.is_installed()) {
+            let removing_here = selected.iter().any(|s| s.name() == client.name());
+            if let Ok(servers) = client.list_servers() {
+                still_used.extend(
+                    servers
+                        .into_iter()
+                        .filter(|(name, _)| !(removing_here && name == server_name))
+                        .map(|(_, config)| config),
+                );
+            }
+        }
+
+        let roots = PurgeRoots::new().map_err(McpError::Other)?;
+        Ok(plan_purge(removed, &still_used, &roots))
+    }
+
+    /// In a dry run, the images `--prune-docker` would clean up after
+    fn print_docker_dry_run(&self, removed: &[ServerConfig]) {
+        if !self.prune_docker {
+            return;
+        }
+        let images: BTreeSet<String> = removed.iter().filter_map(config_image).collect();
+        if images.is_empty() {
+            return;
+        }
         println!(
-            "{} Note: Server removal requires manual config editing",
-            "⚠".yellow()
+            "{}",
+            "Will prune the stopped containers and unused images of:".yellow()
         );
-        println!("This feature will be improved in a future update.");
+        for image in images {
+            println!("  • {image}");
+        }
+        println!();
+    }
+
+    /// Prune what the removed Docker servers left behind, if requested
+    fn prune_docker_images(&self, removed: &[ServerConfig]) -> Result<(), McpError> {
+        if !self.prune_docker {
+            return Ok(());
+        }
+        let images: BTreeSet<String> = removed.iter().filter_map(config_image).collect();
+        if images.is_empty() {
+            return Ok(());
+        }
+
         println!();
+        let mut prune = DockerPruneCommand::new(self.verbose);
+        prune.set_yes(self.yes);
+        prune.set_only_images(images);
+        prune.execute()
+    }
 
-        if selected_clients.len() == 1 {
+    /// Remove every server a meta-server installed
+    fn execute_meta(
+        &self,
+        membership: &MetaMembership,
+        meta_name: &str,
+        installed: &InstalledMetaServer,
+    ) -> Result<(), McpError> {
+        let clients = detect_clients();
+        let mut matches: Vec<(&dyn McpClient, &str)> = Vec::new();
+
+        for client in &clients {
+            if !client.is_installed() {
+                continue;
+            }
+
+            if let Ok(servers) = client.list_servers() {
+                for member in &installed.members {
+                    if servers.contains_key(member) {
+                        matches.push((client.as_ref(), member));
+                    }
+                }
+            }
+        }
+
+        if matches.is_empty() {
             println!(
-                "{} Server '{}' marked for removal from {}",
-                "✅".green(),
-                server_name.cyan(),
-                selected_clients[0].name()
+                "{} All servers from meta-server '{}' were already removed",
+                "ℹ".blue(),
+                meta_name.cyan()
             );
-            println!();
-            println!("To complete removal, manually edit:");
+            membership.forget(meta_name).map_err(McpError::Other)?;
+            return Ok(());
+        }
+
+        println!(
+            "{} '{}' is a meta-server; its servers will be removed",
+            "ℹ".blue(),
+            meta_name.cyan()
+        );
+        println!("{}", "Will remove:".yellow());
+        let mut current_client = "";
+        for (client, member) in &matches {
+            if client.name() != current_client {
+                current_client = client.name();
+                println!("  Client: {}", current_client.cyan());
+            }
+            println!("    • {}", member.yellow());
+        }
+        println!();
+
+        if self.dry_run {
+            println!("{} Dry run: nothing was removed", "ℹ".blue());
+            return Ok(());
+        }
+
+        let confirm = Confirmer::new(self.yes).confirm_typed(
+            &format!(
+                "Remove {} server configuration(s) installed by {}?",
+                matches.len(),
+                meta_name
+            ),
+            meta_name,
+        )?;
+
+        if !confirm {
+            println!("{} Removal cancelled", "❌".red());
+            return Ok(());
+        }
+
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+
+        let mut failed = 0;
+        for (client, member) in &matches {
+            if let Err(e) = remove_with_snapshot(&manager, *client, member) {
+                failed += 1;
+                eprintln!("{} {}", "✗".red(), e);
+            }
+        }
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Failed to remove {} of {} servers from meta-server '{}'",
+                failed,
+                matches.len(),
+                meta_name
+            )));
+        }
+
+        membership.forget(meta_name).map_err(McpError::Other)?;
+        Ok(())
+    }
+
+    /// Remove every server whose name matches `pattern` across all installed clients
+    pub fn execute_pattern(&self, pattern: &str) -> Result<(), McpError> {
+        let pattern = NamePattern::parse(pattern).map_err(McpError::Other)?;
+
+        println!(
+            "{} Removing servers matching: {}",
+            "→".green(),
+            pattern.as_str().cyan()
+        );
+        println!();
+
+        let clients = detect_clients();
+        let mut matches: Vec<(&dyn McpClient, String, ServerConfig)> = Vec::new();
+
+        for client in &clients {
+            if !client.is_installed() {
+                continue;
+            }
+
+            if let Ok(servers) = client.list_servers() {
+                let mut names: Vec<_> = servers
+                    .into_iter()
+                    .filter(|(name, _)| pattern.matches(name))
+                    .collect();
+                names.sort_by(|a, b| a.0.cmp(&b.0));
+                for (name, config) in names {
+                    matches.push((client.as_ref(), name, config));
+                }
+            }
+        }
+
+        if matches.is_empty() {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "No servers matching '{}' found in any MCP client configuration",
+                pattern.as_str()
+            )));
+        }
+
+        // Preview what will be removed
+        println!("{}", "Will remove:".yellow());
+        let mut current_client = "";
+        for (client, name, config) in &matches {
+            if client.name() != current_client {
+                current_client = client.name();
+                println!("  Client: {}", current_client.cyan());
+            }
             println!(
-                "  {}",
-                selected_clients[0]
-                    .config_path()
-                    .display()
-                    .to_string()
-                    .cyan()
+                "    • {}: {} {}",
+                name.yellow(),
+                config.command.green(),
+                config.args.join(" ").dimmed()
             );
+        }
+        println!();
+
+        if self.dry_run {
+            let configs: Vec<ServerConfig> = matches
+                .iter()
+                .map(|(_, _, config)| config.clone())
+                .collect();
+            self.print_docker_dry_run(&configs);
+            println!("{} Dry run: nothing was removed", "ℹ".blue());
+            return Ok(());
+        }
+
+        let confirm = Confirmer::new(self.yes).confirm_typed(
+            &format!("Remove {} server configuration(s)?", matches.len()),
+            pattern.as_str(),
+        )?;
+
+        if !confirm {
+            println!("{} Removal cancelled", "❌".red());
+            return Ok(());
+        }
+
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+
+        let mut removed = 0;
+        let mut failed = 0;
+        let mut removed_configs = Vec::new();
+        for (client, name, config) in &matches {
+            match remove_with_snapshot(&manager, *client, name) {
+                Ok(()) => {
+                    removed += 1;
+                    removed_configs.push(config.clone());
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("{} {}", "✗".red(), e);
+                }
+            }
+        }
+
+        println!();
+        println!(
+            "Removed {} server(s){}",
+            removed.to_string().green(),
+            if failed > 0 {
+                format!(", {} failed", failed.to_string().red())
+            } else {
+                String::new()
+            }
+        );
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Failed to remove {} of {} matching servers",
+                failed,
+                matches.len()
+            )));
+        }
+
+        self.prune_docker_images(&removed_configs)
+    }
+}
+
+/// Menu entries for removing a server found in `clients`
+fn removal_choices(clients: &[&dyn McpClient]) -> Vec<RemovalChoice> {
+    let mut choices: Vec<RemovalChoice> = if clients.len() > 1 {
+        (0..clients.len()).map(RemovalChoice::Client).collect()
+    } else {
+        Vec::new()
+    };
+    choices.push(RemovalChoice::AllClients);
+    choices.push(RemovalChoice::AllClientsAndArtifacts);
+    choices
+}
+
+fn print_purge_plan(plan: &PurgePlan) {
+    if plan.is_empty() {
+        println!("{} No files or secrets to delete", "ℹ".blue());
+        println!();
+        return;
+    }
+    if !plan.delete.is_empty() {
+        println!("{}", "Will delete:".yellow());
+        for artifact in &plan.delete {
+            println!("  • {artifact}");
+        }
+    }
+    if !plan.keep.is_empty() {
+        println!("{}", "Will keep:".dimmed());
+        for (artifact, reason) in &plan.keep {
+            println!("  • {} {}", artifact, format!("({reason})").dimmed());
+        }
+    }
+    println!();
+}
+
+/// Delete every planned artifact, reporting each failure
+fn purge_artifacts(plan: &PurgePlan) -> Result<(), McpError> {
+    let mut failed = 0;
+    for artifact in &plan.delete {
+        match artifact.remove() {
+            Ok(()) => println!("{} Deleted {}", "✅".green(), artifact),
+            Err(e) => {
+                failed += 1;
+                eprintln!("{} {:#}", "✗".red(), e);
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(McpError::Other(anyhow::anyhow!(
+            "Removed the server but failed to delete {} of {} artifacts",
+            failed,
+            plan.delete.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Check server configurations for common mistakes, optionally fixing them
+pub struct ConfigLintCommand {
+    verbose: bool,
+    fix: bool,
+}
+
+impl ConfigLintCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            fix: false,
+        }
+    }
+
+    /// Apply safe fixes through the config manager
+    pub fn set_fix(&mut self, fix: bool) {
+        self.fix = fix;
+    }
+
+    pub fn execute(&self) -> Result<(), McpError> {
+        self.execute_with(&ConfigLinter::new())
+    }
+
+    pub fn execute_with(&self, linter: &ConfigLinter) -> Result<(), McpError> {
+        println!("{}", "🔍 Linting MCP server configurations".blue().bold());
+        println!();
+
+        let manager = if self.fix {
+            Some(ConfigManager::new().map_err(|e| {
+                McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+            })?)
         } else {
+            None
+        };
+
+        let clients = detect_clients();
+        let mut checked = 0;
+        let mut remaining = 0;
+        let mut fixed = 0;
+
+        for client in &clients {
+            if !client.is_installed() {
+                continue;
+            }
+
+            let servers = match client.list_servers() {
+                Ok(servers) => servers,
+                Err(e) => {
+                    if self.verbose {
+                        println!(
+                            "{} {} - Error reading config: {}",
+                            "⚠".yellow(),
+                            client.name(),
+                            e.to_string().dimmed()
+                        );
+                    }
+                    continue;
+                }
+            };
+
+            let mut names: Vec<_> = servers.keys().cloned().collect();
+            names.sort();
+
+            for name in names {
+                let config = &servers[&name];
+                checked += 1;
+
+                let findings = linter.lint(client.name(), config);
+                if findings.is_empty() {
+                    if self.verbose {
+                        println!("{} {} / {}", "✓".green(), client.name(), name);
+                    }
+                    continue;
+                }
+
+                println!("{} / {}", client.name().cyan(), name.yellow());
+
+                let applied = match &manager {
+                    Some(manager) => {
+                        let (fixed_config, applied) = linter.fix(client.name(), config);
+                        if !applied.is_empty() {
+                            manager
+                                .apply_config(client.as_ref(), &name, fixed_config)
+                                .map_err(|e| {
+                                    McpError::Other(anyhow::anyhow!(
+                                        "Failed to apply fixes to {} in {}: {}",
+                                        name,
+                                        client.name(),
+                                        e
+                                    ))
+                                })?;
+                        }
+                        applied
+                    }
+                    None => Vec::new(),
+                };
+
+                for finding in &findings {
+                    if applied.contains(&finding.rule) {
+                        fixed += 1;
+                        println!(
+                            "  {} [{}] {} ({})",
+                            "✓".green(),
+                            finding.rule,
+                            finding.message,
+                            format!("fixed: {}", finding.fix_hint).green()
+                        );
+                    } else {
+                        remaining += 1;
+                        let hint = if finding.is_fixable() {
+                            format!("fixable with --fix: {}", finding.fix_hint)
+                        } else {
+                            finding.fix_hint.clone()
+                        };
+                        println!(
+                            "  {} [{}] {} ({})",
+                            "✗".red(),
+                            finding.rule,
+                            finding.message,
+                            hint.dimmed()
+                        );
+                    }
+                }
+                println!();
+            }
+        }
+
+        println!(
+            "Checked {} server(s): {} issue(s){}",
+            checked,
+            if remaining == 0 {
+                remaining.to_string().green()
+            } else {
+                remaining.to_string().red()
+            },
+            if fixed > 0 {
+                format!(", {} fixed", fixed.to_string().green())
+            } else {
+                String::new()
+            }
+        );
+
+        if fixed > 0 {
+            println!("Fixes can be undone with 'mcp config rollback <server>'.");
+        }
+
+        if remaining > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "{} configuration issue(s) found",
+                remaining
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Remove a single server from a client, recording a snapshot for rollback
+fn remove_with_snapshot(
+    manager: &ConfigManager,
+    client: &dyn McpClient,
+    server_name: &str,
+) -> Result<(), McpError> {
+    match manager.remove_config(client, server_name) {
+        Ok(Some(_)) => {
+            if let Err(e) =
+                ManagedServers::new().and_then(|managed| managed.forget(server_name, client.name()))
+            {
+                tracing::warn!("Failed to update managed servers: {}", e);
+            }
             println!(
-                "{} Server '{}' marked for removal from {} clients",
+                "{} Server '{}' removed from {}",
                 "✅".green(),
                 server_name.cyan(),
-                selected_clients.len()
+                client.name()
+            );
+            Ok(())
+        }
+        Ok(None) => {
+            println!(
+                "{} Server '{}' was no longer configured in {}",
+                "⚠".yellow(),
+                server_name.cyan(),
+                client.name()
             );
+            Ok(())
+        }
+        Err(e) => Err(McpError::Other(anyhow::anyhow!(
+            "Failed to remove '{}' from {}: {}",
+            server_name,
+            client.name(),
+            e
+        ))),
+    }
+}
+
+/// Repair client config files that are no longer valid JSON
+pub struct ConfigRepairCommand {
+    verbose: bool,
+    yes: bool,
+    client: Option<String>,
+}
+
+impl ConfigRepairCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            yes: false,
+            client: None,
+        }
+    }
+
+    /// Apply the least lossy repair without asking
+    pub fn set_yes(&mut self, yes: bool) {
+        self.yes = yes;
+    }
+
+    /// Only check the client with this name (case-insensitive)
+    pub fn set_client(&mut self, client: Option<String>) {
+        self.client = client;
+    }
+
+    pub fn execute(&self) -> Result<(), McpError> {
+        println!("{}", "🩹 Checking client config files".blue().bold());
+        println!();
+
+        let history = ConfigManager::new()
+            .and_then(|manager| manager.get_history(None, None))
+            .unwrap_or_default();
+        let mut broken = 0;
+        let mut repaired = 0;
+
+        for client in detect_clients() {
+            if !client.is_installed()
+                || self
+                    .client
+                    .as_ref()
+                    .is_some_and(|name| !name.eq_ignore_ascii_case(client.name()))
+            {
+                continue;
+            }
+            let path = client.config_path();
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+
+            let Some(error) = repair::diagnose(&path.display().to_string(), &content) else {
+                if self.verbose {
+                    println!("{} {}", "✓".green(), client.name());
+                }
+                continue;
+            };
+            broken += 1;
+
+            println!("{} {}: {}", "✗".red(), client.name().cyan(), error);
+            for line in repair::excerpt(&content, &error).lines() {
+                println!("    {}", line.dimmed());
+            }
+
+            let choices = repair::repair_choices(client.name(), &path, &content, &history);
+            let Some(choice) = self.choose(&choices)? else {
+                println!("  {} Left unchanged", "ℹ".blue());
+                println!();
+                continue;
+            };
+            self.apply(client.as_ref(), &path, &content, choice)?;
+            repaired += 1;
             println!();
-            println!("To complete removal, manually edit:");
-            for client in selected_clients {
+        }
+
+        if broken == 0 {
+            println!("{} All client config files are valid JSON", "✓".green());
+        } else {
+            println!("Repaired {repaired} of {broken} broken config file(s)");
+        }
+        Ok(())
+    }
+
+    /// The repair to apply, or `None` to leave the file alone
+    fn choose<'a>(
+        &self,
+        choices: &'a [repair::RepairChoice],
+    ) -> Result<Option<&'a repair::RepairChoice>, McpError> {
+        if self.yes {
+            return Ok(choices.first());
+        }
+        if !std::io::stdin().is_terminal() {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Cannot choose a repair without a terminal; pass --yes to apply the least lossy one"
+            )));
+        }
+
+        let mut items: Vec<String> = choices.iter().map(|choice| choice.label()).collect();
+        items.push("Leave the file unchanged".to_string());
+        let selection = Select::new()
+            .with_prompt("How should this config be repaired?")
+            .items(&items)
+            .default(0)
+            .interact()
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Selection failed: {}", e)))?;
+        Ok(choices.get(selection))
+    }
+
+    fn apply(
+        &self,
+        client: &dyn McpClient,
+        path: &std::path::Path,
+        content: &str,
+        choice: &repair::RepairChoice,
+    ) -> Result<(), McpError> {
+        let kept = repair::apply_repair(client, path, content, choice).map_err(|e| {
+            McpError::Other(anyhow::anyhow!(
+                "Failed to repair {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        println!(
+            "  {} {} (the broken file was kept as {})",
+            "✓".green(),
+            choice.label(),
+            kept.display()
+        );
+        Ok(())
+    }
+}
+
+/// Format of snapshot times in history output and `--to`
+const SNAPSHOT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Parse a snapshot time as shown by `mcp config history` (UTC) or as RFC 3339
+pub fn parse_snapshot_time(time: &str) -> Result<DateTime<Utc>, McpError> {
+    let time = time.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(time) {
+        return Ok(parsed.with_timezone(&Utc));
+    }
+    let naive = time.trim_end_matches('Z').replacen('T', " ", 1);
+    NaiveDateTime::parse_from_str(&naive, SNAPSHOT_TIME_FORMAT)
+        .map(|parsed| parsed.and_utc())
+        .map_err(|_| {
+            McpError::Other(anyhow::anyhow!(
+                "Invalid time '{}'; use the time shown by 'mcp config history' (YYYY-MM-DD HH:MM:SS)",
+                time
+            ))
+        })
+}
+
+/// History entries for `server`, newest first, limited to `client` (case-insensitive) if given
+fn server_history(
+    manager: &ConfigManager,
+    server: &str,
+    client: Option<&str>,
+) -> Result<Vec<ConfigSnapshot>, McpError> {
+    let mut history = manager
+        .get_history(None, Some(server))
+        .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to read config history: {}", e)))?;
+    if let Some(client) = client {
+        history.retain(|snapshot| snapshot.client_name.eq_ignore_ascii_case(client));
+    }
+    if history.is_empty() {
+        let scope = client
+            .map(|client| format!(" in {client}"))
+            .unwrap_or_default();
+        return Err(McpError::Other(anyhow::anyhow!(
+            "No configuration history for '{}'{}",
+            server,
+            scope
+        )));
+    }
+    Ok(history)
+}
+
+/// Show the recorded configuration changes of a server
+pub struct ConfigHistoryCommand {
+    verbose: bool,
+    client: Option<String>,
+}
+
+impl ConfigHistoryCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            client: None,
+        }
+    }
+
+    /// Only show changes to this client's config (case-insensitive)
+    pub fn set_client(&mut self, client: Option<String>) {
+        self.client = client;
+    }
+
+    pub fn execute(&self, server: &str) -> Result<(), McpError> {
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+        self.execute_with(&manager, server)
+    }
+
+    pub fn execute_with(&self, manager: &ConfigManager, server: &str) -> Result<(), McpError> {
+        let history = server_history(manager, server, self.client.as_deref())?;
+
+        println!(
+            "{} Configuration history of '{}' (newest first)",
+            "🕘".blue(),
+            server.cyan()
+        );
+        println!();
+        for snapshot in &history {
+            println!(
+                "{}  {}  {}",
+                snapshot
+                    .timestamp
+                    .format(SNAPSHOT_TIME_FORMAT)
+                    .to_string()
+                    .bold(),
+                snapshot.client_name.cyan(),
+                snapshot.description
+            );
+            let changes = match &snapshot.previous_config {
+                Some(previous) if previous == &snapshot.config => {
+                    vec!["Removed from the client".to_string()]
+                }
+                Some(previous) => manager.diff_configs(previous, &snapshot.config),
+                None => vec!["Added to the client".to_string()],
+            };
+            for change in changes {
+                println!("    {}", change.dimmed());
+            }
+            if self.verbose {
                 println!(
-                    "  • {}: {}",
+                    "    {} {} {}",
+                    "Command:".dimmed(),
+                    snapshot.config.command,
+                    snapshot.config.args.join(" ")
+                );
+            }
+        }
+        println!();
+        println!(
+            "Undo the latest change with 'mcp config rollback {server}', or restore an entry with --to \"<time>\"."
+        );
+        Ok(())
+    }
+}
+
+/// Restore an earlier configuration of a server from its history
+pub struct ConfigRollbackCommand {
+    verbose: bool,
+    client: Option<String>,
+    to: Option<DateTime<Utc>>,
+    yes: bool,
+}
+
+impl ConfigRollbackCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            client: None,
+            to: None,
+            yes: false,
+        }
+    }
+
+    /// Roll back this client's config (case-insensitive); required when several clients have history
+    pub fn set_client(&mut self, client: Option<String>) {
+        self.client = client;
+    }
+
+    /// Restore the configuration recorded at this time instead of undoing the latest change
+    pub fn set_to(&mut self, to: Option<DateTime<Utc>>) {
+        self.to = to;
+    }
+
+    /// Restore without asking for confirmation
+    pub fn set_yes(&mut self, yes: bool) {
+        self.yes = yes;
+    }
+
+    pub fn execute(&self, server: &str) -> Result<(), McpError> {
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+        self.execute_with(
+            &manager,
+            &detect_clients(),
+            Confirmer::new(self.yes),
+            server,
+        )
+    }
+
+    pub fn execute_with(
+        &self,
+        manager: &ConfigManager,
+        clients: &[Box<dyn McpClient>],
+        confirmer: Confirmer,
+        server: &str,
+    ) -> Result<(), McpError> {
+        let history = server_history(manager, server, self.client.as_deref())?;
+        let candidates: Vec<&ConfigSnapshot> = match self.to {
+            Some(to) => history
+                .iter()
+                .filter(|snapshot| snapshot.timestamp.timestamp() == to.timestamp())
+                .collect(),
+            None => history.iter().take(1).collect(),
+        };
+        let Some(&snapshot) = candidates.first() else {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "No history entry of '{}' at {}; 'mcp config history {}' lists them",
+                server,
+                self.to
+                    .map(|to| to.format(SNAPSHOT_TIME_FORMAT).to_string())
+                    .unwrap_or_default(),
+                server
+            )));
+        };
+        // Without --client the latest entry must not depend on which client is meant
+        if self.client.is_none() {
+            let mut names: Vec<&str> = if self.to.is_some() {
+                candidates.iter().map(|s| s.client_name.as_str()).collect()
+            } else {
+                history.iter().map(|s| s.client_name.as_str()).collect()
+            };
+            names.sort_unstable();
+            names.dedup();
+            if names.len() > 1 {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "'{}' has history in several clients ({}); choose one with --client",
+                    server,
+                    names.join(", ")
+                )));
+            }
+        }
+
+        let restored = match self.to {
+            Some(_) => Some(&snapshot.config),
+            None => snapshot.previous_config.as_ref(),
+        };
+        let Some(restored) = restored else {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Nothing to roll back to: '{}' was first added to {} at {}; use 'mcp remove {}' to remove it",
+                server,
+                snapshot.client_name,
+                snapshot.timestamp.format(SNAPSHOT_TIME_FORMAT),
+                server
+            )));
+        };
+
+        let client = clients
+            .iter()
+            .find(|client| client.name() == snapshot.client_name)
+            .ok_or_else(|| {
+                McpError::Other(anyhow::anyhow!(
+                    "{} is no longer available on this system",
+                    snapshot.client_name
+                ))
+            })?;
+        let current = client
+            .list_servers()
+            .map_err(|e| {
+                McpError::Other(anyhow::anyhow!(
+                    "Failed to read {} config: {}",
                     client.name(),
-                    client.config_path().display().to_string().cyan()
+                    e
+                ))
+            })?
+            .remove(server);
+
+        println!(
+            "{} Rolling back '{}' in {}",
+            "↩".blue(),
+            server.cyan(),
+            client.name()
+        );
+        match &current {
+            Some(current) if current == restored => {
+                println!(
+                    "{} The current configuration already matches; nothing to do",
+                    "✓".green()
                 );
+                return Ok(());
             }
+            Some(current) => {
+                for change in manager.diff_configs(current, restored) {
+                    println!("    {change}");
+                }
+            }
+            None => println!(
+                "    {}",
+                "Not configured at the moment; it will be added back".dimmed()
+            ),
+        }
+        if self.verbose {
+            println!(
+                "    {} {} {}",
+                "Command:".dimmed(),
+                restored.command,
+                restored.args.join(" ")
+            );
+        }
+
+        if !confirmer.confirm("Restore this configuration?")? {
+            println!("Rollback cancelled");
+            return Ok(());
         }
 
+        let result = match self.to {
+            Some(_) => manager.restore(client.as_ref(), snapshot).map(|_| ()),
+            None => manager.rollback(client.as_ref(), snapshot),
+        };
+        result.map_err(|e| {
+            McpError::Other(anyhow::anyhow!(
+                "Failed to roll back '{}' in {}: {}",
+                server,
+                client.name(),
+                e
+            ))
+        })?;
+
+        println!(
+            "{} Restored '{}' in {} (undo with 'mcp config rollback {}')",
+            "✅".green(),
+            server.cyan(),
+            client.name(),
+            server
+        );
         Ok(())
     }
 }
@@ -430,4 +1599,228 @@ mod tests {
         cmd.set_remove_all(true);
         assert!(cmd.remove_all);
     }
+
+    #[test]
+    fn test_config_remove_set_client_and_purge() {
+        let mut cmd = ConfigRemoveCommand::new(false);
+        cmd.set_client(Some("Cursor".to_string()));
+        cmd.set_purge(true);
+        assert_eq!(cmd.client.as_deref(), Some("Cursor"));
+        assert!(cmd.purge);
+    }
+
+    #[test]
+    fn test_removal_choices() {
+        use crate::test_utils::mocks::MockClientBuilder;
+
+        let cursor = MockClientBuilder::new("Cursor").build();
+        let vscode = MockClientBuilder::new("VS Code").build();
+        assert_eq!(
+            removal_choices(&[&cursor, &vscode]),
+            vec![
+                RemovalChoice::Client(0),
+                RemovalChoice::Client(1),
+                RemovalChoice::AllClients,
+                RemovalChoice::AllClientsAndArtifacts,
+            ]
+        );
+        assert_eq!(
+            removal_choices(&[&cursor]),
+            vec![
+                RemovalChoice::AllClients,
+                RemovalChoice::AllClientsAndArtifacts
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_lint_set_fix() {
+        let mut cmd = ConfigLintCommand::new(false);
+        assert!(!cmd.fix);
+        cmd.set_fix(true);
+        assert!(cmd.fix);
+    }
+
+    #[test]
+    fn test_config_list_set_filter() {
+        let mut cmd = ConfigListCommand::new(false);
+        assert!(cmd.filter.is_none());
+
+        cmd.set_filter(Some("server-*")).unwrap();
+        assert!(cmd.filter.as_ref().unwrap().matches("server-github"));
+
+        assert!(cmd.set_filter(Some("re:(")).is_err());
+
+        cmd.set_filter(None).unwrap();
+        assert!(cmd.filter.is_none());
+    }
+
+    /// A config manager with history in memory and a clock that starts at 2024-01-01
+    fn history_manager() -> (
+        ConfigManager,
+        std::sync::Arc<crate::test_utils::mocks::MockClock>,
+    ) {
+        use crate::test_utils::mocks::{MemoryFileSystem, MockClock};
+        use std::sync::Arc;
+
+        let clock = Arc::new(MockClock::default());
+        let manager = ConfigManager::open(
+            "/history".into(),
+            clock.clone(),
+            Arc::new(MemoryFileSystem::default()),
+        )
+        .unwrap();
+        (manager, clock)
+    }
+
+    fn server(version: &str) -> ServerConfig {
+        ServerConfig {
+            command: "npx".to_string(),
+            args: vec![format!("server-github@{version}")],
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_snapshot_time() {
+        let expected = parse_snapshot_time("2024-01-01T00:00:05Z").unwrap();
+        assert_eq!(
+            parse_snapshot_time("2024-01-01 00:00:05").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_snapshot_time("2024-01-01T00:00:05").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_snapshot_time("2024-01-01T01:00:05+01:00").unwrap(),
+            expected
+        );
+        assert!(parse_snapshot_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_rollback_undoes_the_latest_change() {
+        use crate::test_utils::mocks::MockClientBuilder;
+        use std::time::Duration;
+
+        let (manager, clock) = history_manager();
+        let clients: Vec<Box<dyn McpClient>> =
+            vec![Box::new(MockClientBuilder::new("Cursor").build())];
+        manager
+            .apply_config(clients[0].as_ref(), "github", server("1.0.0"))
+            .unwrap();
+        clock.advance(Duration::from_secs(60));
+        manager
+            .apply_config(clients[0].as_ref(), "github", server("2.0.0"))
+            .unwrap();
+
+        ConfigRollbackCommand::new(false)
+            .execute_with(&manager, &clients, Confirmer::new(true), "github")
+            .unwrap();
+        assert_eq!(
+            clients[0].list_servers().unwrap()["github"],
+            server("1.0.0")
+        );
+
+        // The rollback is recorded, so rolling back again redoes the change
+        assert_eq!(manager.get_history(None, Some("github")).unwrap().len(), 3);
+        clock.advance(Duration::from_secs(60));
+        ConfigRollbackCommand::new(false)
+            .execute_with(&manager, &clients, Confirmer::new(true), "github")
+            .unwrap();
+        assert_eq!(
+            clients[0].list_servers().unwrap()["github"],
+            server("2.0.0")
+        );
+    }
+
+    #[test]
+    fn test_rollback_to_a_time() {
+        use crate::test_utils::mocks::MockClientBuilder;
+        use std::time::Duration;
+
+        let (manager, clock) = history_manager();
+        let clients: Vec<Box<dyn McpClient>> =
+            vec![Box::new(MockClientBuilder::new("Cursor").build())];
+        for version in ["1.0.0", "2.0.0", "3.0.0"] {
+            manager
+                .apply_config(clients[0].as_ref(), "github", server(version))
+                .unwrap();
+            clock.advance(Duration::from_secs(60));
+        }
+
+        let mut cmd = ConfigRollbackCommand::new(false);
+        cmd.set_to(Some(parse_snapshot_time("2024-01-01 00:00:00").unwrap()));
+        cmd.execute_with(&manager, &clients, Confirmer::new(true), "github")
+            .unwrap();
+        assert_eq!(
+            clients[0].list_servers().unwrap()["github"],
+            server("1.0.0")
+        );
+
+        cmd.set_to(Some(parse_snapshot_time("2024-01-01 00:30:00").unwrap()));
+        let error = cmd
+            .execute_with(&manager, &clients, Confirmer::new(true), "github")
+            .unwrap_err();
+        assert!(error.to_string().contains("No history entry"), "{error}");
+    }
+
+    #[test]
+    fn test_rollback_needs_a_client_when_several_have_history() {
+        use crate::test_utils::mocks::MockClientBuilder;
+
+        let (manager, _clock) = history_manager();
+        let clients: Vec<Box<dyn McpClient>> = vec![
+            Box::new(MockClientBuilder::new("Cursor").build()),
+            Box::new(MockClientBuilder::new("VS Code").build()),
+        ];
+        for client in &clients {
+            manager
+                .apply_config(client.as_ref(), "github", server("1.0.0"))
+                .unwrap();
+            manager
+                .apply_config(client.as_ref(), "github", server("2.0.0"))
+                .unwrap();
+        }
+
+        let error = ConfigRollbackCommand::new(false)
+            .execute_with(&manager, &clients, Confirmer::new(true), "github")
+            .unwrap_err();
+        assert!(error.to_string().contains("--client"), "{error}");
+
+        let mut cmd = ConfigRollbackCommand::new(false);
+        cmd.set_client(Some("vs code".to_string()));
+        cmd.execute_with(&manager, &clients, Confirmer::new(true), "github")
+            .unwrap();
+        assert_eq!(
+            clients[1].list_servers().unwrap()["github"],
+            server("1.0.0")
+        );
+        assert_eq!(
+            clients[0].list_servers().unwrap()["github"],
+            server("2.0.0")
+        );
+    }
+
+    #[test]
+    fn test_rollback_of_first_install_is_refused() {
+        use crate::test_utils::mocks::MockClientBuilder;
+
+        let (manager, _clock) = history_manager();
+        let clients: Vec<Box<dyn McpClient>> =
+            vec![Box::new(MockClientBuilder::new("Cursor").build())];
+        manager
+            .apply_config(clients[0].as_ref(), "github", server("1.0.0"))
+            .unwrap();
+
+        let error = ConfigRollbackCommand::new(false)
+            .execute_with(&manager, &clients, Confirmer::new(true), "github")
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("Nothing to roll back to"),
+            "{error}"
+        );
+    }
 }
@@ -107,6 +107,7 @@ pub fn transform_to_server_config(
         command,
         args,
         env: config,
+        cwd: None,
     }
 }
 
@@ -109,7 +109,7 @@ mod tests {
 
             // Test valid number
             config.insert(field_name.clone(), valid_number);
-            let result = validate_field_types(&config, &[field.clone()]);
+            let result = validate_field_types(&config, std::slice::from_ref(&field));
             prop_assert!(result.is_ok());
 
             // Test invalid number
@@ -135,7 +135,7 @@ mod tests {
 
             // Test valid boolean
             config.insert(field_name.clone(), valid_bool);
-            let result = validate_field_types(&config, &[field.clone()]);
+            let result = validate_field_types(&config, std::slice::from_ref(&field));
             prop_assert!(result.is_ok());
 
             // Test invalid boolean
@@ -163,7 +163,7 @@ mod tests {
             // Test valid URL
             let valid_url = format!("{valid_protocol}://{host}{path}");
             config.insert(field_name.clone(), valid_url);
-            let result = validate_field_types(&config, &[field.clone()]);
+            let result = validate_field_types(&config, std::slice::from_ref(&field));
             prop_assert!(result.is_ok());
 
             // Test invalid URL (no protocol)
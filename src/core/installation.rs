@@ -102,6 +102,9 @@ pub fn determine_dependency_action(
         DependencyStatus::ConfigurationRequired { issue, solution: _ } => {
             DependencyAction::RequiresUserAction(format!("Configuration required: {issue}"))
         }
+        DependencyStatus::Unresponsive { command, .. } => {
+            DependencyAction::RequiresUserAction(format!("'{command}' is not responding"))
+        }
     }
 }
 
@@ -179,6 +182,7 @@ mod tests {
             },
             required_config: vec![],
             optional_config: vec![],
+            args_template: vec![],
         }
     }
 
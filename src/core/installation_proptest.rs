@@ -66,6 +66,10 @@ mod tests {
                 issue: "PATH not set".to_string(),
                 solution: "Add to PATH".to_string(),
             }),
+            Just(DependencyStatus::Unresponsive {
+                command: "docker info".to_string(),
+                timeout_secs: 10,
+            }),
         ]
     }
 
@@ -277,6 +281,7 @@ mod tests {
                 },
                 required_config: vec![],
                 optional_config: vec![],
+                args_template: vec![],
             };
 
             let plan1 = plan_installation(
@@ -89,6 +89,60 @@ pub fn validate_binary_url(url: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Validates crates.io crate name format
+pub fn validate_crate_name(crate_name: &str) -> Result<(), String> {
+    if crate_name.is_empty() {
+        return Err("Crate name cannot be empty".to_string());
+    }
+
+    if crate_name.len() > 64 {
+        return Err("Crate name cannot exceed 64 characters".to_string());
+    }
+
+    if !crate_name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return Err("Crate name must start with a letter".to_string());
+    }
+
+    if !crate_name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(
+            "Crate name can only contain letters, numbers, hyphens, and underscores".to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Validates a name given with `--as` for another instance of a server. The
+/// name becomes the server's key in client configs, input ids and exported
+/// scripts, so it is kept to letters, numbers, hyphens and underscores
+pub fn validate_instance_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Instance name cannot be empty".to_string());
+    }
+
+    if name.len() > 64 {
+        return Err("Instance name cannot exceed 64 characters".to_string());
+    }
+
+    if !name.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err("Instance name must start with a letter or number".to_string());
+    }
+
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(
+            "Instance name can only contain letters, numbers, hyphens, and underscores".to_string(),
+        );
+    }
+
+    Ok(())
+}
+
 /// Validates server type-specific constraints
 pub fn validate_server_type_constraints(server_type: &ServerType) -> Result<(), String> {
     match server_type {
@@ -105,6 +159,31 @@ pub fn validate_server_type_constraints(server_type: &ServerType) -> Result<(),
             // Python validation could be added here
             Ok(())
         }
+        ServerType::Cargo {
+            crate_name,
+            version: _,
+        } => validate_crate_name(crate_name),
+        ServerType::Deno { specifier } => {
+            if specifier.trim().is_empty() {
+                Err("Deno specifier cannot be empty".to_string())
+            } else {
+                Ok(())
+            }
+        }
+        ServerType::Jar { path } => {
+            if path.trim().is_empty() {
+                Err("JAR path cannot be empty".to_string())
+            } else {
+                Ok(())
+            }
+        }
+        ServerType::Dotnet { package, .. } => {
+            if package.trim().is_empty() {
+                Err(".NET tool package cannot be empty".to_string())
+            } else {
+                Ok(())
+            }
+        }
     }
 }
 
@@ -194,6 +273,16 @@ mod tests {
         assert!(result.unwrap_err().contains("must contain '/'"));
     }
 
+    #[test]
+    fn test_validate_instance_name() {
+        assert!(validate_instance_name("docs-fs").is_ok());
+        assert!(validate_instance_name("fs_2").is_ok());
+        assert!(validate_instance_name("").is_err());
+        assert!(validate_instance_name("-fs").is_err());
+        assert!(validate_instance_name("docs/fs").is_err());
+        assert!(validate_instance_name(&"a".repeat(65)).is_err());
+    }
+
     #[test]
     fn test_validate_docker_image_name_success() {
         assert!(validate_docker_image_name("nginx").is_ok());
@@ -229,6 +318,17 @@ mod tests {
         assert!(result.unwrap_err().contains("localhost"));
     }
 
+    #[test]
+    fn test_validate_crate_name() {
+        assert!(validate_crate_name("mcp-server_foo").is_ok());
+        assert!(validate_crate_name("").is_err());
+        assert!(validate_crate_name("1server").is_err());
+
+        let result = validate_crate_name("server;rm");
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("only contain"));
+    }
+
     #[test]
     fn test_assess_server_risk_level() {
         assert_eq!(
@@ -0,0 +1,505 @@
+//! `mcp debug-env`: compare how `mcp run` and a client would launch a server.
+//!
+//! A server that works from a terminal but fails in a client usually sees a
+//! different environment there:
+//!
+//! - Apps started from the Dock, Start menu or a desktop launcher don't read shell
+//!   profiles, so PATH lacks nvm, Homebrew or pyenv directories
+//! - Claude Desktop (through the MCP SDK's stdio transport) only passes a handful
+//!   of variables to servers, plus the entry's own `env`
+//! - The working directory is whatever the client chooses unless the entry sets `cwd`
+//!
+//! The client side is an estimate built from those rules, since the client's own
+//! process can't be inspected. The report lists where the command resolves in each
+//! context, PATH entries and variables present in only one of them, and values that
+//! differ. Values of credential-like variables are masked.
+
+use colored::Colorize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use crate::audit::mask;
+use crate::client::{detect_clients, ServerConfig};
+use crate::error::McpError;
+use crate::utils::process::Process;
+use crate::utils::secrets::is_secret_name;
+
+/// Variables the MCP SDK's stdio transport passes to servers on Windows
+const SDK_INHERITED_WINDOWS: &[&str] = &[
+    "APPDATA",
+    "HOMEDRIVE",
+    "HOMEPATH",
+    "LOCALAPPDATA",
+    "PATH",
+    "PROCESSOR_ARCHITECTURE",
+    "SYSTEMDRIVE",
+    "SYSTEMROOT",
+    "TEMP",
+    "USERNAME",
+    "USERPROFILE",
+    "PROGRAMFILES",
+];
+
+/// Variables the MCP SDK's stdio transport passes to servers elsewhere
+const SDK_INHERITED_UNIX: &[&str] = &["HOME", "LOGNAME", "PATH", "SHELL", "TERM", "USER"];
+
+/// PATH of apps launched by launchd on macOS when `launchctl getenv PATH` is empty
+const MACOS_GUI_PATH: &str = "/usr/bin:/bin:/usr/sbin:/sbin";
+
+/// Clients that launch servers with only the SDK's default variables
+const MINIMAL_ENV_CLIENTS: &[&str] = &["Claude Desktop"];
+
+/// How a server is started in one context
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchContext {
+    pub label: String,
+    pub command: String,
+    pub env: BTreeMap<String, String>,
+    /// `None` when the launcher picks the directory
+    pub cwd: Option<PathBuf>,
+}
+
+impl LaunchContext {
+    pub fn path(&self) -> &str {
+        self.env
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("PATH"))
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_default()
+    }
+
+    /// Where the command resolves on this context's PATH
+    pub fn resolve_command(&self) -> Option<PathBuf> {
+        let cwd = self
+            .cwd
+            .clone()
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("."));
+        which::which_in(&self.command, Some(self.path()), cwd).ok()
+    }
+}
+
+/// `mcp run` from this terminal: the shell's environment and directory
+pub fn run_context(
+    config: &ServerConfig,
+    shell_env: &BTreeMap<String, String>,
+    current_dir: Option<PathBuf>,
+) -> LaunchContext {
+    LaunchContext {
+        label: "mcp run".to_string(),
+        command: config.command.clone(),
+        env: shell_env.clone(),
+        cwd: current_dir,
+    }
+}
+
+/// The estimated context `client` starts the server in.
+///
+/// `gui_path` is the PATH apps get when not started from a terminal, if known.
+pub fn client_context(
+    client: &str,
+    config: &ServerConfig,
+    shell_env: &BTreeMap<String, String>,
+    gui_path: Option<&str>,
+) -> LaunchContext {
+    let inherited: BTreeMap<String, String> = if MINIMAL_ENV_CLIENTS.contains(&client) {
+        let names = if cfg!(windows) {
+            SDK_INHERITED_WINDOWS
+        } else {
+            SDK_INHERITED_UNIX
+        };
+        shell_env
+            .iter()
+            .filter(|(name, _)| names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    } else {
+        shell_env.clone()
+    };
+
+    let mut env = inherited;
+    if let Some(gui_path) = gui_path {
+        let key = env
+            .keys()
+            .find(|name| name.eq_ignore_ascii_case("PATH"))
+            .cloned()
+            .unwrap_or_else(|| "PATH".to_string());
+        env.insert(key, gui_path.to_string());
+    }
+    env.extend(config.env.clone());
+
+    LaunchContext {
+        label: client.to_string(),
+        command: config.command.clone(),
+        env,
+        cwd: config.cwd.as_ref().map(PathBuf::from),
+    }
+}
+
+/// A variable that is not the same in both contexts
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvDifference {
+    OnlyInRun {
+        name: String,
+        value: String,
+    },
+    OnlyInClient {
+        name: String,
+        value: String,
+    },
+    Differs {
+        name: String,
+        run: String,
+        client: String,
+    },
+}
+
+/// Variables that differ between the contexts, by name; PATH is compared separately
+pub fn diff_env(run: &LaunchContext, client: &LaunchContext) -> Vec<EnvDifference> {
+    let names: BTreeSet<&String> = run.env.keys().chain(client.env.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| !name.eq_ignore_ascii_case("PATH"))
+        .filter_map(|name| match (run.env.get(name), client.env.get(name)) {
+            (Some(value), None) => Some(EnvDifference::OnlyInRun {
+                name: name.clone(),
+                value: value.clone(),
+            }),
+            (None, Some(value)) => Some(EnvDifference::OnlyInClient {
+                name: name.clone(),
+                value: value.clone(),
+            }),
+            (Some(run), Some(client)) if run != client => Some(EnvDifference::Differs {
+                name: name.clone(),
+                run: run.clone(),
+                client: client.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// PATH entries only in `run` and only in `client`, each in PATH order
+pub fn diff_path(run: &str, client: &str) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let run: Vec<PathBuf> = std::env::split_paths(run).collect();
+    let client: Vec<PathBuf> = std::env::split_paths(client).collect();
+    let only_run = run
+        .iter()
+        .filter(|entry| !client.contains(entry))
+        .cloned()
+        .collect();
+    let only_client = client
+        .iter()
+        .filter(|entry| !run.contains(entry))
+        .cloned()
+        .collect();
+    (only_run, only_client)
+}
+
+/// `value`, masked if `name` looks like it holds a credential
+pub fn display_value(name: &str, value: &str) -> String {
+    if is_secret_name(name) {
+        mask(value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// PATH of apps not started from a terminal, where it can be determined
+pub fn gui_path() -> Option<String> {
+    if cfg!(target_os = "macos") {
+        let launchd = Process::new("launchctl")
+            .args(["getenv", "PATH"])
+            .output()
+            .ok()
+            .filter(|output| output.success())
+            .map(|output| output.stdout_lossy().trim().to_string())
+            .filter(|path| !path.is_empty());
+        Some(launchd.unwrap_or_else(|| MACOS_GUI_PATH.to_string()))
+    } else if cfg!(windows) {
+        // New processes get the registry PATH, not this shell's edits to it
+        let output = Process::new("powershell")
+            .args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                "[Environment]::GetEnvironmentVariable('Path','Machine') + ';' + [Environment]::GetEnvironmentVariable('Path','User')",
+            ])
+            .output()
+            .ok()?;
+        output
+            .success()
+            .then(|| output.stdout_lossy().trim().to_string())
+    } else {
+        // Desktop sessions on Linux usually share the login PATH
+        None
+    }
+}
+
+/// Compare the `mcp run` and client launch environments of a server
+pub struct DebugEnvCommand {
+    verbose: bool,
+    client: Option<String>,
+}
+
+impl DebugEnvCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            client: None,
+        }
+    }
+
+    /// Compare against this client (case-insensitive) instead of the first that has the server
+    pub fn set_client(&mut self, client: Option<String>) {
+        self.client = client;
+    }
+
+    pub fn execute(&self, server: &str) -> Result<(), McpError> {
+        let clients = detect_clients();
+        let mut found = None;
+        for client in clients.iter().filter(|c| c.is_installed()) {
+            if let Some(name) = &self.client {
+                if !name.eq_ignore_ascii_case(client.name()) {
+                    continue;
+                }
+            }
+            if let Some(config) = client
+                .list_servers()
+                .ok()
+                .and_then(|mut s| s.remove(server))
+            {
+                found = Some((client.name().to_string(), config));
+                break;
+            }
+        }
+        let Some((client, config)) = found else {
+            let scope = match &self.client {
+                Some(client) => format!("the {client} configuration"),
+                None => "any MCP client configuration".to_string(),
+            };
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Server '{}' not found in {}",
+                server,
+                scope
+            )));
+        };
+
+        let shell_env: BTreeMap<String, String> = std::env::vars().collect();
+        let run = run_context(&config, &shell_env, std::env::current_dir().ok());
+        let client_ctx = client_context(&client, &config, &shell_env, gui_path().as_deref());
+
+        println!(
+            "{} Launch environment of '{}': {} vs {} {}",
+            "🔍".blue(),
+            server.cyan(),
+            run.label.bold(),
+            client_ctx.label.bold(),
+            "(estimated)".dimmed()
+        );
+        println!();
+        self.print_command(&run, &client_ctx);
+        self.print_cwd(&run, &client_ctx);
+        self.print_path(&run, &client_ctx);
+        self.print_env(&run, &client_ctx);
+        Ok(())
+    }
+
+    fn print_command(&self, run: &LaunchContext, client: &LaunchContext) {
+        println!("{}", "Command".bold());
+        for context in [run, client] {
+            let resolved = match context.resolve_command() {
+                Some(path) => path.display().to_string().green(),
+                None => "not found on PATH".red(),
+            };
+            println!("  {:<16} {} → {}", context.label, context.command, resolved);
+        }
+        println!();
+    }
+
+    fn print_cwd(&self, run: &LaunchContext, client: &LaunchContext) {
+        println!("{}", "Working directory".bold());
+        for context in [run, client] {
+            let cwd = context
+                .cwd
+                .as_deref()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_else(|| "chosen by the client (relative paths may break)".to_string());
+            println!("  {:<16} {}", context.label, cwd);
+        }
+        println!();
+    }
+
+    fn print_path(&self, run: &LaunchContext, client: &LaunchContext) {
+        let (only_run, only_client) = diff_path(run.path(), client.path());
+        println!("{}", "PATH".bold());
+        if only_run.is_empty() && only_client.is_empty() {
+            println!("  {} Same entries", "✓".green());
+        }
+        print_entries(&format!("Only in {}", run.label), &only_run);
+        print_entries(&format!("Only in {}", client.label), &only_client);
+        println!();
+    }
+
+    fn print_env(&self, run: &LaunchContext, client: &LaunchContext) {
+        let differences = diff_env(run, client);
+        println!("{}", "Environment variables".bold());
+        if differences.is_empty() {
+            println!("  {} No differences", "✓".green());
+            return;
+        }
+
+        let only_run: Vec<_> = differences
+            .iter()
+            .filter_map(|d| match d {
+                EnvDifference::OnlyInRun { name, value } => Some((name, value)),
+                _ => None,
+            })
+            .collect();
+        if !only_run.is_empty() {
+            println!("  Only in {} ({}):", run.label, only_run.len());
+            for (name, value) in only_run {
+                if self.verbose {
+                    println!("    {} {}={}", "-".red(), name, display_value(name, value));
+                } else {
+                    println!("    {} {}", "-".red(), name);
+                }
+            }
+        }
+
+        for difference in &differences {
+            match difference {
+                EnvDifference::OnlyInClient { name, value } => println!(
+                    "  Only in {}: {} {}={}",
+                    client.label,
+                    "+".green(),
+                    name,
+                    display_value(name, value)
+                ),
+                EnvDifference::Differs {
+                    name,
+                    run: run_value,
+                    client: client_value,
+                } => println!(
+                    "  {} {}: {} in {}, {} in {}",
+                    "≠".yellow(),
+                    name,
+                    display_value(name, run_value),
+                    run.label,
+                    display_value(name, client_value),
+                    client.label
+                ),
+                EnvDifference::OnlyInRun { .. } => {}
+            }
+        }
+    }
+}
+
+fn print_entries(title: &str, entries: &[PathBuf]) {
+    if entries.is_empty() {
+        return;
+    }
+    println!("  {title}:");
+    for entry in entries {
+        println!("    {}", entry.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn shell_env() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("HOME".to_string(), "/home/me".to_string()),
+            (
+                "PATH".to_string(),
+                "/home/me/.nvm/bin:/usr/bin:/bin".to_string(),
+            ),
+            ("NVM_DIR".to_string(), "/home/me/.nvm".to_string()),
+            ("HTTPS_PROXY".to_string(), "http://proxy:3128".to_string()),
+        ])
+    }
+
+    fn config(env: &[(&str, &str)]) -> ServerConfig {
+        ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "server".to_string()],
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_minimal_client_env() {
+        let config = config(&[("GITHUB_TOKEN", "ghp_secret")]);
+        let run = run_context(&config, &shell_env(), Some(PathBuf::from("/work")));
+        let client = client_context(
+            "Claude Desktop",
+            &config,
+            &shell_env(),
+            Some("/usr/bin:/bin"),
+        );
+
+        assert_eq!(client.cwd, None);
+        assert_eq!(client.path(), "/usr/bin:/bin");
+        assert!(!client.env.contains_key("NVM_DIR"));
+
+        let differences = diff_env(&run, &client);
+        assert!(differences.contains(&EnvDifference::OnlyInRun {
+            name: "HTTPS_PROXY".to_string(),
+            value: "http://proxy:3128".to_string()
+        }));
+        assert!(differences.contains(&EnvDifference::OnlyInClient {
+            name: "GITHUB_TOKEN".to_string(),
+            value: "ghp_secret".to_string()
+        }));
+        assert!(differences.contains(&EnvDifference::OnlyInRun {
+            name: "NVM_DIR".to_string(),
+            value: "/home/me/.nvm".to_string()
+        }));
+    }
+
+    #[test]
+    fn test_full_env_clients_keep_the_shell_variables() {
+        let config = config(&[("NVM_DIR", "/opt/nvm")]);
+        let run = run_context(&config, &shell_env(), None);
+        let client = client_context("Cursor", &config, &shell_env(), None);
+
+        assert_eq!(client.path(), run.path());
+        assert_eq!(
+            diff_env(&run, &client),
+            vec![EnvDifference::Differs {
+                name: "NVM_DIR".to_string(),
+                run: "/home/me/.nvm".to_string(),
+                client: "/opt/nvm".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_path() {
+        let joined = |entries: &[&str]| {
+            std::env::join_paths(entries.iter().map(PathBuf::from))
+                .unwrap()
+                .into_string()
+                .unwrap()
+        };
+        let (only_run, only_client) = diff_path(
+            &joined(&["/home/me/.nvm/bin", "/usr/bin", "/bin"]),
+            &joined(&["/usr/bin", "/bin", "/usr/sbin"]),
+        );
+        assert_eq!(only_run, vec![PathBuf::from("/home/me/.nvm/bin")]);
+        assert_eq!(only_client, vec![PathBuf::from("/usr/sbin")]);
+    }
+
+    #[test]
+    fn test_display_value_masks_credentials() {
+        assert_eq!(display_value("API_TOKEN", "abc"), "****");
+        assert_eq!(display_value("NODE_ENV", "production"), "production");
+    }
+}
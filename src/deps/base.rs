@@ -1,35 +1,32 @@
 //! Base functionality and common patterns for dependency checkers
 
+use super::probe::{probe_output, run_probe, ProbeOutcome};
 use super::{DependencyStatus, InstallInstructions, InstallMethod};
 use anyhow::{Context, Result};
-use std::process::Command;
 
 /// Common functionality for dependency checkers
 pub struct DependencyCheckerBase;
 
 impl DependencyCheckerBase {
-    /// Execute a command and return its version output if successful
+    /// Execute a command and return its version output if successful.
+    ///
+    /// Fails with [`super::ProbeTimeout`] if the command outlives the probe timeout.
     pub fn get_command_version(command: &str, args: &[&str]) -> Result<Option<String>> {
-        let output = Command::new(command)
-            .args(args)
-            .output()
-            .with_context(|| format!("Failed to execute {command}"))?;
+        let output = probe_output(command, args)?;
 
-        if !output.status.success() {
+        if !output.success {
             return Ok(None);
         }
 
-        let version_output = String::from_utf8_lossy(&output.stdout);
-        Ok(Some(version_output.trim().to_string()))
+        Ok(Some(output.stdout.trim().to_string()))
     }
 
     /// Check if a command is available by trying to run it
     pub fn is_command_available(command: &str, test_args: &[&str]) -> bool {
-        Command::new(command)
-            .args(test_args)
-            .output()
-            .map(|output| output.status.success())
-            .unwrap_or(false)
+        matches!(
+            run_probe(command, test_args),
+            Ok(ProbeOutcome::Finished(output)) if output.success
+        )
     }
 
     /// Compare installed version against a minimum required version
@@ -97,15 +94,13 @@ impl CommonVersionParsers {
         output
             .strip_prefix(prefix)
             .and_then(|version_part| {
-                // Handle cases with additional info after version
-                if let Some(comma_pos) = version_part.find(',') {
-                    Some(&version_part[..comma_pos])
-                } else {
-                    version_part
-                        .split_whitespace()
-                        .next()
-                        .or(Some(version_part))
-                }
+                // Handle cases with additional info after version, e.g.
+                // "24.0.5, build ced0996" or "1.46.3 (stable, release, ...)"
+                let token = version_part
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or(version_part);
+                token.split(',').next()
             })
             .map(|v| v.trim().to_string())
     }
@@ -0,0 +1,153 @@
+//! Per-execution memoization of dependency checks.
+//!
+//! Installing several servers in one run (batch files, meta-servers) would otherwise
+//! check Node.js and resolve `npx` once per server. The cache lives as long as the
+//! command that owns it and is never persisted, so every new run checks again.
+//!
+//! Only satisfied checks are remembered. A missing or mismatched dependency is
+//! checked again for the next server, so a fix in between (such as an auto-install)
+//! is picked up.
+
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::deps::{check_responsive, DependencyCheck, DependencyChecker, DependencyStatus};
+
+/// Dependency check results and resolved commands shared across one execution
+#[derive(Debug, Default)]
+pub struct DependencyCheckCache {
+    checks: Mutex<Vec<DependencyCheck>>,
+    commands: Mutex<HashMap<String, Option<PathBuf>>>,
+}
+
+impl DependencyCheckCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A satisfied check already made for the dependency `checker` covers
+    pub fn cached(&self, checker: &dyn DependencyChecker) -> Option<DependencyCheck> {
+        let dependency = checker.checked_dependency()?;
+        self.checks
+            .lock()
+            .ok()?
+            .iter()
+            .find(|check| check.dependency == dependency)
+            .cloned()
+    }
+
+    /// Run `checker`, or reuse an earlier satisfied result for the same dependency.
+    ///
+    /// A probe that times out is reported as [`DependencyStatus::Unresponsive`].
+    pub fn check(&self, checker: &dyn DependencyChecker) -> Result<DependencyCheck> {
+        if let Some(check) = self.cached(checker) {
+            return Ok(check);
+        }
+
+        let check = check_responsive(checker)?;
+        if matches!(check.status, DependencyStatus::Installed { .. })
+            && checker.checked_dependency().is_some()
+        {
+            if let Ok(mut checks) = self.checks.lock() {
+                checks.push(check.clone());
+            }
+        }
+        Ok(check)
+    }
+
+    /// Resolve `command` on PATH once per execution
+    pub fn resolve_command(&self, command: &str) -> Option<PathBuf> {
+        let Ok(mut commands) = self.commands.lock() else {
+            return which::which(command).ok();
+        };
+        commands
+            .entry(command.to_string())
+            .or_insert_with(|| which::which(command).ok())
+            .clone()
+    }
+
+    /// Number of dependency checks currently remembered
+    pub fn len(&self) -> usize {
+        self.checks.lock().map(|checks| checks.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deps::Dependency;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingChecker {
+        status: DependencyStatus,
+        calls: AtomicUsize,
+    }
+
+    impl CountingChecker {
+        fn new(status: DependencyStatus) -> Self {
+            Self {
+                status,
+                calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl DependencyChecker for CountingChecker {
+        fn check(&self) -> Result<DependencyCheck> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(DependencyCheck {
+                dependency: Dependency::NodeJs { min_version: None },
+                status: self.status.clone(),
+                install_instructions: None,
+            })
+        }
+
+        fn checked_dependency(&self) -> Option<Dependency> {
+            Some(Dependency::NodeJs { min_version: None })
+        }
+    }
+
+    #[test]
+    fn test_reuses_satisfied_checks() {
+        let cache = DependencyCheckCache::new();
+        let checker = CountingChecker::new(DependencyStatus::Installed {
+            version: Some("20.0.0".to_string()),
+        });
+
+        assert!(cache.cached(&checker).is_none());
+        for _ in 0..10 {
+            cache.check(&checker).unwrap();
+        }
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 1);
+        assert!(cache.cached(&checker).is_some());
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_rechecks_missing_dependencies() {
+        let cache = DependencyCheckCache::new();
+        let checker = CountingChecker::new(DependencyStatus::Missing);
+
+        cache.check(&checker).unwrap();
+        cache.check(&checker).unwrap();
+        assert_eq!(checker.calls.load(Ordering::SeqCst), 2);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_command() {
+        let cache = DependencyCheckCache::new();
+        assert!(cache
+            .resolve_command("definitely-not-a-real-command-xyz")
+            .is_none());
+        assert!(cache
+            .resolve_command("definitely-not-a-real-command-xyz")
+            .is_none());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::deps::{
+    base::{CommonVersionParsers, DependencyCheckerBase},
+    Dependency, DependencyCheck, DependencyChecker, DependencyStatus,
+};
+use anyhow::Result;
+
+/// Checks for the Deno runtime used to run Deno scripts and jsr packages
+#[derive(Debug)]
+pub struct DenoChecker {
+    min_version: Option<String>,
+}
+
+impl DenoChecker {
+    pub fn new() -> Self {
+        Self { min_version: None }
+    }
+
+    pub fn with_min_version(min_version: impl Into<String>) -> Self {
+        Self {
+            min_version: Some(min_version.into()),
+        }
+    }
+
+    fn check_deno_version(&self) -> Option<String> {
+        let output = DependencyCheckerBase::get_command_version("deno", &["--version"]).ok()??;
+        CommonVersionParsers::parse_standard_format(&output, "deno ")
+    }
+}
+
+impl Default for DenoChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DependencyChecker for DenoChecker {
+    fn checked_dependency(&self) -> Option<Dependency> {
+        Some(Dependency::Deno {
+            min_version: self.min_version.clone(),
+        })
+    }
+
+    fn check(&self) -> Result<DependencyCheck> {
+        let dependency = Dependency::Deno {
+            min_version: self.min_version.clone(),
+        };
+
+        let status = match self.check_deno_version() {
+            Some(version) => {
+                if let Some(ref min_version) = self.min_version {
+                    DependencyCheckerBase::check_version_requirement(&version, min_version)?
+                } else {
+                    DependencyStatus::Installed {
+                        version: Some(version),
+                    }
+                }
+            }
+            None => DependencyStatus::Missing,
+        };
+
+        let install_instructions =
+            if DependencyCheckerBase::should_provide_install_instructions(&status) {
+                Some(crate::deps::get_install_instructions(&dependency))
+            } else {
+                None
+            };
+
+        Ok(DependencyCheck {
+            dependency,
+            status,
+            install_instructions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deno_checker_creation() {
+        assert!(DenoChecker::new().min_version.is_none());
+        assert_eq!(
+            DenoChecker::with_min_version("1.40.0").min_version,
+            Some("1.40.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_deno_version_output() {
+        let output = "deno 1.46.3 (stable, release, x86_64-unknown-linux-gnu)\nv8 12.9.202.5-rusty\ntypescript 5.5.2";
+        assert_eq!(
+            CommonVersionParsers::parse_standard_format(output, "deno "),
+            Some("1.46.3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deno_checker_reports_dependency() {
+        let check = DenoChecker::new().check().unwrap();
+        assert_eq!(check.dependency, Dependency::Deno { min_version: None });
+        if check.status == DependencyStatus::Missing {
+            assert!(check.install_instructions.is_some());
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use crate::deps::{
     base::{CommonVersionParsers, DependencyCheckerBase},
+    probe::{probe_output, probe_timeout, ProbeTimeout},
     Dependency, DependencyCheck, DependencyChecker, DependencyStatus, InstallInstructions,
 };
+use crate::utils::process::Process;
 use anyhow::{Context, Result};
-use std::process::Command;
 
 #[derive(Debug)]
 pub struct DockerChecker {
@@ -40,10 +41,13 @@ impl DockerChecker {
     }
 
     fn check_docker_running(&self) -> Result<bool> {
-        Ok(DependencyCheckerBase::is_command_available(
-            "docker",
-            &["info"],
-        ))
+        // `docker info` blocks while the daemon is asleep, so a timeout is reported
+        // rather than read as "not running"
+        match probe_output("docker", &["info"]) {
+            Ok(output) => Ok(output.success),
+            Err(e) if e.is::<ProbeTimeout>() => Err(e),
+            Err(_) => Ok(false),
+        }
     }
 
     fn check_docker_compose(&self) -> Result<Option<String>> {
@@ -57,13 +61,14 @@ impl DockerChecker {
     }
 
     fn try_docker_compose_new_syntax(&self) -> Result<Option<String>> {
-        let output = Command::new("docker")
+        let output = Process::new("docker")
             .args(["compose", "version"])
+            .timeout(probe_timeout())
             .output()
             .context("Failed to execute docker compose version")?;
 
-        if output.status.success() {
-            let version_output = String::from_utf8_lossy(&output.stdout);
+        if output.success() {
+            let version_output = output.stdout_lossy();
             if let Some(version_line) = version_output.lines().next() {
                 return Ok(self.parse_docker_compose_new_format(version_line));
             }
@@ -73,13 +78,14 @@ impl DockerChecker {
     }
 
     fn try_docker_compose_legacy_syntax(&self) -> Result<Option<String>> {
-        let output = Command::new("docker-compose")
+        let output = Process::new("docker-compose")
             .args(["--version"])
+            .timeout(probe_timeout())
             .output()
             .context("Failed to execute docker-compose --version")?;
 
-        if output.status.success() {
-            let version_output = String::from_utf8_lossy(&output.stdout);
+        if output.success() {
+            let version_output = output.stdout_lossy();
             if let Some(version_line) = version_output.lines().next() {
                 return Ok(self.parse_docker_compose_legacy_format(version_line));
             }
@@ -116,6 +122,13 @@ impl Default for DockerChecker {
 }
 
 impl DependencyChecker for DockerChecker {
+    fn checked_dependency(&self) -> Option<Dependency> {
+        Some(Dependency::Docker {
+            min_version: self.min_version.clone(),
+            requires_compose: self.check_compose,
+        })
+    }
+
     fn check(&self) -> Result<DependencyCheck> {
         let dependency = Dependency::Docker {
             min_version: self.min_version.clone(),
@@ -231,15 +244,23 @@ pub fn check_compose_available() -> Result<bool> {
 /// Get the best docker command to use (docker or podman)
 pub fn get_container_runtime() -> Result<String> {
     // Check for Docker first
-    if let Ok(output) = Command::new("docker").arg("--version").output() {
-        if output.status.success() {
+    if let Ok(output) = Process::new("docker")
+        .arg("--version")
+        .timeout(probe_timeout())
+        .output()
+    {
+        if output.success() {
             return Ok("docker".to_string());
         }
     }
 
     // Check for Podman as alternative
-    if let Ok(output) = Command::new("podman").arg("--version").output() {
-        if output.status.success() {
+    if let Ok(output) = Process::new("podman")
+        .arg("--version")
+        .timeout(probe_timeout())
+        .output()
+    {
+        if output.success() {
             return Ok("podman".to_string());
         }
     }
@@ -0,0 +1,154 @@
+use crate::deps::{
+    base::DependencyCheckerBase, Dependency, DependencyCheck, DependencyChecker, DependencyStatus,
+};
+use anyhow::Result;
+
+/// Checks for the .NET SDK used to install and run `dotnet tool` servers
+#[derive(Debug)]
+pub struct DotnetChecker {
+    min_version: Option<String>,
+}
+
+impl DotnetChecker {
+    pub fn new() -> Self {
+        Self { min_version: None }
+    }
+
+    pub fn with_min_version(min_version: impl Into<String>) -> Self {
+        Self {
+            min_version: Some(min_version.into()),
+        }
+    }
+
+    fn list(&self, what: &str) -> Option<String> {
+        DependencyCheckerBase::get_command_version("dotnet", &[what])
+            .ok()
+            .flatten()
+    }
+}
+
+impl Default for DotnetChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DependencyChecker for DotnetChecker {
+    fn checked_dependency(&self) -> Option<Dependency> {
+        Some(Dependency::Dotnet {
+            min_version: self.min_version.clone(),
+        })
+    }
+
+    fn check(&self) -> Result<DependencyCheck> {
+        let dependency = Dependency::Dotnet {
+            min_version: self.min_version.clone(),
+        };
+
+        let sdk = self
+            .list("--list-sdks")
+            .and_then(|output| newest_listed_version(&output, None));
+        let status = match sdk {
+            Some(version) => match &self.min_version {
+                Some(min_version) => {
+                    DependencyCheckerBase::check_version_requirement(&version, min_version)?
+                }
+                None => DependencyStatus::Installed {
+                    version: Some(version),
+                },
+            },
+            // `dotnet tool install` needs the SDK, the runtime alone is not enough
+            None => match self
+                .list("--list-runtimes")
+                .and_then(|output| newest_listed_version(&output, Some("Microsoft.NETCore.App")))
+            {
+                Some(runtime) => DependencyStatus::ConfigurationRequired {
+                    issue: format!(".NET runtime {runtime} is installed, but no .NET SDK"),
+                    solution: "Install the .NET SDK to install dotnet tools".to_string(),
+                },
+                None => DependencyStatus::Missing,
+            },
+        };
+
+        let install_instructions =
+            if DependencyCheckerBase::should_provide_install_instructions(&status)
+                || matches!(status, DependencyStatus::ConfigurationRequired { .. })
+            {
+                Some(crate::deps::get_install_instructions(&dependency))
+            } else {
+                None
+            };
+
+        Ok(DependencyCheck {
+            dependency,
+            status,
+            install_instructions,
+        })
+    }
+}
+
+/// Highest version in `dotnet --list-sdks` or `--list-runtimes` output.
+///
+/// SDK lines look like `8.0.404 [/usr/share/dotnet/sdk]`, runtime lines like
+/// `Microsoft.NETCore.App 8.0.11 [/usr/share/dotnet/shared/Microsoft.NETCore.App]`;
+/// `framework` selects runtime lines of one framework.
+pub fn newest_listed_version(output: &str, framework: Option<&str>) -> Option<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            if let Some(framework) = framework {
+                if words.next()? != framework {
+                    return None;
+                }
+            }
+            let version = words.next()?;
+            semver::Version::parse(version)
+                .ok()
+                .map(|parsed| (parsed, version))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, version)| version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dotnet_checker_creation() {
+        assert!(DotnetChecker::new().min_version.is_none());
+        assert_eq!(
+            DotnetChecker::with_min_version("8.0.100").min_version,
+            Some("8.0.100".to_string())
+        );
+    }
+
+    #[test]
+    fn test_newest_listed_sdk() {
+        let output = "6.0.428 [/usr/share/dotnet/sdk]\n8.0.404 [/usr/share/dotnet/sdk]\n9.0.100-rc.2.24474.11 [C:\\Program Files\\dotnet\\sdk]\n";
+        assert_eq!(
+            newest_listed_version(output, None),
+            Some("9.0.100-rc.2.24474.11".to_string())
+        );
+        assert_eq!(newest_listed_version("", None), None);
+    }
+
+    #[test]
+    fn test_newest_listed_runtime() {
+        let output = "Microsoft.AspNetCore.App 9.0.0 [/usr/share/dotnet/shared/Microsoft.AspNetCore.App]\nMicrosoft.NETCore.App 8.0.11 [/usr/share/dotnet/shared/Microsoft.NETCore.App]\n";
+        assert_eq!(
+            newest_listed_version(output, Some("Microsoft.NETCore.App")),
+            Some("8.0.11".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dotnet_checker_reports_dependency() {
+        let check = DotnetChecker::new().check().unwrap();
+        assert_eq!(check.dependency, Dependency::Dotnet { min_version: None });
+        if check.status == DependencyStatus::Missing {
+            assert!(check.install_instructions.is_some());
+        }
+    }
+}
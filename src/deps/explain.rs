@@ -0,0 +1,300 @@
+//! Plain-language explanations of dependency install commands.
+//!
+//! Before mcp-helper runs a package manager on the user's behalf it shows the
+//! exact command, the shell that runs it, which install method it came from and
+//! what each step changes on the system.
+
+use colored::Colorize;
+use std::fmt;
+
+use super::InstallMethod;
+
+/// How an install command is started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandShell {
+    /// The program is started directly with the command's words as arguments
+    Direct,
+    /// `sh -c`, for pipelines and `&&` chains
+    Sh,
+    /// `cmd /C`, for `&&` chains on Windows
+    Cmd,
+    /// `powershell -Command`, for PowerShell one-liners such as `irm ... | iex`
+    PowerShell,
+}
+
+impl CommandShell {
+    /// The shell `command` has to run in on this platform
+    pub fn for_command(command: &str) -> Self {
+        Self::for_platform(command, cfg!(target_os = "windows"))
+    }
+
+    pub fn for_platform(command: &str, windows: bool) -> Self {
+        let needs_shell = command.contains("&&") || command.contains('|');
+        if windows && is_powershell(command) {
+            Self::PowerShell
+        } else if needs_shell && windows {
+            Self::Cmd
+        } else if needs_shell {
+            Self::Sh
+        } else {
+            Self::Direct
+        }
+    }
+}
+
+impl fmt::Display for CommandShell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Direct => write!(f, "no shell"),
+            Self::Sh => write!(f, "sh"),
+            Self::Cmd => write!(f, "cmd"),
+            Self::PowerShell => write!(f, "PowerShell"),
+        }
+    }
+}
+
+fn is_powershell(command: &str) -> bool {
+    command
+        .split(|c: char| c.is_whitespace() || c == '|')
+        .any(|word| {
+            let word = word.to_lowercase();
+            word == "irm" || word == "iex" || word.starts_with("invoke-")
+        })
+}
+
+/// What running an install method will do
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandExplanation {
+    pub command: String,
+    pub shell: CommandShell,
+    /// The install method the command comes from
+    pub source: String,
+    /// One line per step of the command
+    pub changes: Vec<String>,
+}
+
+impl CommandExplanation {
+    pub fn new(method: &InstallMethod, dependency_name: &str) -> Self {
+        let source = match &method.description {
+            Some(description) => {
+                format!(
+                    "'{}' method for {dependency_name} ({description})",
+                    method.name
+                )
+            }
+            None => format!("'{}' method for {dependency_name}", method.name),
+        };
+        Self {
+            command: method.command.clone(),
+            shell: CommandShell::for_command(&method.command),
+            source,
+            changes: describe_command(&method.command),
+        }
+    }
+
+    /// Whether any step runs with administrator rights
+    pub fn is_elevated(&self) -> bool {
+        self.command
+            .split(|c: char| c.is_whitespace() || c == '&' || c == '|')
+            .any(|word| word == "sudo")
+    }
+
+    pub fn print(&self) {
+        println!("  {} Command to run ({}):", "📋".blue(), self.shell);
+        println!("      {}", self.command.cyan());
+        println!("    Source: {}", self.source);
+        println!("    What it changes:");
+        for change in &self.changes {
+            println!("      • {change}");
+        }
+    }
+}
+
+/// One description per `&&`-separated step of `command`
+pub fn describe_command(command: &str) -> Vec<String> {
+    command
+        .split("&&")
+        .map(str::trim)
+        .filter(|step| !step.is_empty())
+        .map(describe_step)
+        .collect()
+}
+
+/// What a single step, possibly a pipeline, does
+pub fn describe_step(step: &str) -> String {
+    if step.starts_with("https://") || step.starts_with("http://") {
+        return format!("Nothing is run; download the installer from {step}");
+    }
+    if let Some((source, sink)) = step.split_once('|') {
+        let url = source
+            .split_whitespace()
+            .find(|word| word.starts_with("https://") || word.starts_with("http://"));
+        let runner = sink.split_whitespace().next().unwrap_or_default();
+        if let Some(url) = url {
+            return match runner {
+                "iex" | "Invoke-Expression" => {
+                    format!("Downloads {url} and runs it as a PowerShell script")
+                }
+                _ => format!("Downloads {url} and runs it with {runner}"),
+            };
+        }
+    }
+
+    let mut words: Vec<&str> = step.split_whitespace().collect();
+    let elevated = words.first() == Some(&"sudo");
+    if elevated {
+        words.remove(0);
+    }
+    let description = describe_words(&words).unwrap_or_else(|| format!("Runs {}", words.join(" ")));
+    if elevated {
+        format!("{description}, as administrator (sudo)")
+    } else {
+        description
+    }
+}
+
+fn describe_words(words: &[&str]) -> Option<String> {
+    let (&program, rest) = words.split_first()?;
+    let action = rest.first().copied();
+    let packages = || {
+        rest.iter()
+            .skip(1)
+            .filter(|word| !word.starts_with('-'))
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let manager = match program {
+        "brew" => "Homebrew",
+        "winget" => "winget",
+        "choco" => "Chocolatey",
+        "scoop" => "Scoop",
+        "apt" | "apt-get" => "apt",
+        "dnf" => "dnf",
+        "yum" => "yum",
+        "snap" => "snap",
+        "port" => "MacPorts",
+        "sdk" => "SDKMAN!",
+        "pyenv" => "pyenv",
+        "xcode-select" if action == Some("--install") => {
+            return Some("Installs the Xcode Command Line Tools".to_string())
+        }
+        "pacman" if action == Some("-S") => {
+            return Some(format!("Installs {} with pacman", packages()))
+        }
+        _ => return None,
+    };
+
+    match action {
+        Some("install") if program == "brew" && rest.contains(&"--cask") => {
+            Some(format!("Installs the {} app with {manager}", packages()))
+        }
+        Some("install") if program == "sdk" => {
+            Some(format!("Installs {} with {manager}", rest[1..].join(" ")))
+        }
+        Some("install") if program == "pyenv" => Some(format!(
+            "Builds Python {} with pyenv and keeps it in ~/.pyenv",
+            packages()
+        )),
+        Some("install") => Some(format!("Installs {} with {manager}", packages())),
+        Some("update") if manager == "apt" => Some("Refreshes the apt package lists".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_for_command() {
+        assert_eq!(
+            CommandShell::for_platform("brew install node", false),
+            CommandShell::Direct
+        );
+        assert_eq!(
+            CommandShell::for_platform("curl https://pyenv.run | bash", false),
+            CommandShell::Sh
+        );
+        assert_eq!(
+            CommandShell::for_platform("sudo apt update && sudo apt install git", false),
+            CommandShell::Sh
+        );
+        assert_eq!(
+            CommandShell::for_platform("irm https://deno.land/install.ps1 | iex", true),
+            CommandShell::PowerShell
+        );
+        assert_eq!(
+            CommandShell::for_platform("winget install Git.Git", true),
+            CommandShell::Direct
+        );
+    }
+
+    #[test]
+    fn test_describe_command() {
+        assert_eq!(
+            describe_command("sudo apt update && sudo apt install nodejs npm"),
+            vec![
+                "Refreshes the apt package lists, as administrator (sudo)".to_string(),
+                "Installs nodejs, npm with apt, as administrator (sudo)".to_string(),
+            ]
+        );
+        assert_eq!(
+            describe_command("brew install --cask temurin@21"),
+            vec!["Installs the temurin@21 app with Homebrew".to_string()]
+        );
+        assert_eq!(
+            describe_command("winget install OpenJS.NodeJS"),
+            vec!["Installs OpenJS.NodeJS with winget".to_string()]
+        );
+        assert_eq!(
+            describe_command("curl -s https://get.sdkman.io | bash && sdk install java 21-tem"),
+            vec![
+                "Downloads https://get.sdkman.io and runs it with bash".to_string(),
+                "Installs java 21-tem with SDKMAN!".to_string(),
+            ]
+        );
+        assert_eq!(
+            describe_command("irm https://deno.land/install.ps1 | iex"),
+            vec![
+                "Downloads https://deno.land/install.ps1 and runs it as a PowerShell script"
+                    .to_string()
+            ]
+        );
+        assert_eq!(
+            describe_command("xcode-select --install"),
+            vec!["Installs the Xcode Command Line Tools".to_string()]
+        );
+        assert_eq!(
+            describe_command("https://www.python.org/downloads/"),
+            vec![
+                "Nothing is run; download the installer from https://www.python.org/downloads/"
+                    .to_string()
+            ]
+        );
+        assert_eq!(
+            describe_command("rustup default stable"),
+            vec!["Runs rustup default stable".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_explanation() {
+        let method = InstallMethod::new(
+            "apt",
+            "sudo apt update && sudo apt install git",
+            "Ubuntu/Debian package manager",
+        );
+        let explanation = CommandExplanation::new(&method, "Git");
+        assert_eq!(
+            explanation.source,
+            "'apt' method for Git (Ubuntu/Debian package manager)"
+        );
+        assert_eq!(explanation.changes.len(), 2);
+        assert!(explanation.is_elevated());
+
+        let method = InstallMethod::new("homebrew", "brew install node", "Homebrew");
+        assert!(!CommandExplanation::new(&method, "Node.js").is_elevated());
+    }
+}
@@ -0,0 +1,106 @@
+//! Audit history of the install commands mcp-helper ran for missing dependencies.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::utils::paths;
+use crate::utils::state_file::{read_state, write_state};
+
+/// Number of executed commands kept in the history
+pub const MAX_ENTRIES: usize = 200;
+
+/// One install command that was run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstallLogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub dependency: String,
+    /// Name of the install method the command came from
+    pub method: String,
+    pub command: String,
+    pub success: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct InstallLogFile {
+    #[serde(default)]
+    entries: Vec<InstallLogEntry>,
+}
+
+/// Persistent history of executed dependency install commands
+#[derive(Debug, Clone)]
+pub struct InstallLog {
+    path: PathBuf,
+}
+
+impl InstallLog {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_path(Self::default_path()?))
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn default_path() -> Result<PathBuf> {
+        Ok(paths::data_dir()?.join("dependency-installs.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All recorded commands, oldest first
+    pub fn entries(&self) -> Result<Vec<InstallLogEntry>> {
+        let file: InstallLogFile = read_state(&self.path, InstallLogFile::default)
+            .context("Failed to read dependency install history")?;
+        Ok(file.entries)
+    }
+
+    /// Append an executed command, dropping the oldest beyond [`MAX_ENTRIES`].
+    pub fn record(&self, entry: InstallLogEntry) -> Result<()> {
+        let mut entries = self.entries().unwrap_or_default();
+        entries.push(entry);
+        if entries.len() > MAX_ENTRIES {
+            entries.drain(..entries.len() - MAX_ENTRIES);
+        }
+        write_state(&self.path, &InstallLogFile { entries })
+            .context("Failed to write dependency install history")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(command: &str) -> InstallLogEntry {
+        InstallLogEntry {
+            timestamp: Utc::now(),
+            dependency: "Node.js".to_string(),
+            method: "homebrew".to_string(),
+            command: command.to_string(),
+            success: true,
+        }
+    }
+
+    #[test]
+    fn test_record_and_trim() {
+        let dir = TempDir::new().unwrap();
+        let log = InstallLog::with_path(dir.path().join("installs.json"));
+        assert!(log.entries().unwrap().is_empty());
+
+        log.record(entry("brew install node")).unwrap();
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].command, "brew install node");
+
+        for i in 0..MAX_ENTRIES {
+            log.record(entry(&format!("brew install node{i}"))).unwrap();
+        }
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].command, "brew install node0");
+    }
+}
@@ -1,8 +1,13 @@
-use crate::deps::{Dependency, DependencyCheck, InstallMethod};
+use crate::deps::explain::{CommandExplanation, CommandShell};
+use crate::deps::install_log::{InstallLog, InstallLogEntry};
+use crate::deps::{
+    Dependency, DependencyCheck, DependencyChecker, DependencyStatus, DockerChecker, InstallMethod,
+};
+use crate::utils::confirm::Confirmer;
+use crate::utils::process::{Process, INSTALL_TIMEOUT};
 use anyhow::{Context, Result};
 use colored::Colorize;
-use dialoguer::Confirm;
-use std::process::Command;
+use std::collections::BTreeSet;
 
 /// Tool installer for missing runtime dependencies (Node.js, Docker, Python, etc.)
 ///
@@ -11,6 +16,7 @@ use std::process::Command;
 pub struct DependencyInstaller {
     dry_run: bool,
     auto_confirm: bool,
+    log: Option<InstallLog>,
 }
 
 impl DependencyInstaller {
@@ -18,6 +24,7 @@ impl DependencyInstaller {
         Self {
             dry_run: false,
             auto_confirm: false,
+            log: None,
         }
     }
 
@@ -31,7 +38,14 @@ impl DependencyInstaller {
         self
     }
 
+    /// Record every executed install command in `log`
+    pub fn with_log(mut self, log: InstallLog) -> Self {
+        self.log = Some(log);
+        self
+    }
+
     /// Attempt to auto-install a missing dependency
+    #[tracing::instrument(skip_all, fields(status = ?check.status))]
     pub fn install_dependency(&self, check: &DependencyCheck) -> Result<bool> {
         let Some(instructions) = &check.install_instructions else {
             return Ok(false);
@@ -52,6 +66,9 @@ impl DependencyInstaller {
         // Find the best installation method
         let install_method = self.select_best_method(platform_methods)?;
 
+        let explanation = CommandExplanation::new(install_method, dependency_name);
+        explanation.print();
+
         if self.dry_run {
             println!(
                 "  {} [DRY RUN] Would install {} using: {}",
@@ -59,23 +76,15 @@ impl DependencyInstaller {
                 dependency_name,
                 install_method.name
             );
-            println!("    Command: {}", install_method.command.cyan());
             return Ok(true);
         }
 
-        // Confirm installation unless auto-confirm is enabled
-        if !self.auto_confirm {
-            let prompt = format!(
-                "Install {} using {}? This will run: {}",
-                dependency_name.cyan(),
-                install_method.name.green(),
-                install_method.command.yellow()
-            );
-
-            if !Confirm::new().with_prompt(prompt).interact()? {
-                println!("  {} Installation cancelled by user", "❌".red());
-                return Ok(false);
-            }
+        // Confirm installation unless --yes was given; download links are only shown
+        let manual = install_method.command.starts_with("http");
+        let prompt = format!("Run this command to install {}?", dependency_name.cyan());
+        if !manual && !Confirmer::new(self.auto_confirm).confirm(&prompt)? {
+            println!("  {} Installation cancelled by user", "❌".red());
+            return Ok(false);
         }
 
         // Execute the installation
@@ -140,13 +149,10 @@ impl DependencyInstaller {
                 method.command.underline()
             );
             return Ok(false); // Manual installation required
-        } else if method.command.contains("&&") {
-            // Execute compound command
-            self.execute_compound_command(&method.command)
         } else {
-            // Execute simple command
-            self.execute_simple_command(&method.command)
+            self.execute_command(&method.command)
         };
+        self.record(method, dependency_name, matches!(result, Ok(true)));
 
         match result {
             Ok(success) => {
@@ -178,32 +184,44 @@ impl DependencyInstaller {
         }
     }
 
-    fn execute_simple_command(&self, command_str: &str) -> Result<bool> {
-        let parts: Vec<&str> = command_str.split_whitespace().collect();
-        if parts.is_empty() {
-            anyhow::bail!("Empty command");
-        }
+    fn execute_command(&self, command_str: &str) -> Result<bool> {
+        let process = match CommandShell::for_command(command_str) {
+            CommandShell::Direct => {
+                let parts: Vec<&str> = command_str.split_whitespace().collect();
+                if parts.is_empty() {
+                    anyhow::bail!("Empty command");
+                }
+                Process::new(parts[0]).args(&parts[1..])
+            }
+            CommandShell::Sh => Process::new("sh").args(["-c", command_str]),
+            CommandShell::Cmd => Process::new("cmd").args(["/C", command_str]),
+            CommandShell::PowerShell => {
+                Process::new("powershell").args(["-NoProfile", "-Command", command_str])
+            }
+        };
 
-        let output = Command::new(parts[0])
-            .args(&parts[1..])
+        let output = process
+            .timeout(INSTALL_TIMEOUT)
             .output()
             .with_context(|| format!("Failed to execute command: {command_str}"))?;
 
-        Ok(output.status.success())
+        Ok(output.success())
     }
 
-    fn execute_compound_command(&self, command_str: &str) -> Result<bool> {
-        // For compound commands, we'll use the system shell
-        let shell_cmd = if cfg!(target_os = "windows") {
-            Command::new("cmd").args(["/C", command_str]).output()
-        } else {
-            Command::new("sh").args(["-c", command_str]).output()
+    fn record(&self, method: &InstallMethod, dependency_name: &str, success: bool) {
+        let Some(log) = &self.log else {
+            return;
         };
-
-        let output = shell_cmd
-            .with_context(|| format!("Failed to execute compound command: {command_str}"))?;
-
-        Ok(output.status.success())
+        let entry = InstallLogEntry {
+            timestamp: chrono::Utc::now(),
+            dependency: dependency_name.to_string(),
+            method: method.name.clone(),
+            command: method.command.clone(),
+            success,
+        };
+        if let Err(e) = log.record(entry) {
+            tracing::warn!("Failed to record install command: {e:#}");
+        }
     }
 
     /// Check if a dependency checker would require elevated privileges
@@ -213,6 +231,10 @@ impl DependencyInstaller {
             Dependency::Python { .. } => cfg!(target_os = "linux"), // Linux system packages need sudo
             Dependency::Docker { .. } => true, // Docker usually requires elevated privileges
             Dependency::Git => cfg!(target_os = "linux"), // Linux system packages need sudo
+            Dependency::Rust { .. } => false,  // rustup installs into the user's home directory
+            Dependency::Deno { .. } => false, // The install script targets the user's home directory
+            Dependency::Java { .. } => false, // SDKMAN! installs into the user's home directory
+            Dependency::Dotnet { .. } => cfg!(target_os = "linux"), // Linux system packages need sudo
         }
     }
 
@@ -228,13 +250,31 @@ impl DependencyInstaller {
         }
     }
 
-    /// Install multiple dependencies in the correct order
+    /// Install multiple dependencies, prerequisites first.
+    ///
+    /// A dependency whose prerequisite could not be installed is skipped. Results are
+    /// in the order of `checks`.
     pub fn install_dependencies(&self, checks: &[DependencyCheck]) -> Result<Vec<bool>> {
-        let mut results = Vec::new();
+        let graph = DependencyGraph::new(checks);
+        let mut results = vec![false; checks.len()];
+
+        for index in graph.install_order()? {
+            let check = &checks[index];
+            if let Some(failed) = graph
+                .prerequisites_of(index)
+                .find(|&prerequisite| !results[prerequisite])
+            {
+                println!(
+                    "  {} Skipping {}: {} was not installed",
+                    "⚠".yellow(),
+                    check.dependency.name(),
+                    checks[failed].dependency.name()
+                );
+                continue;
+            }
 
-        for check in checks {
             let result = self.install_dependency(check)?;
-            results.push(result);
+            results[index] = result;
 
             // If this was a successful installation, we might want to verify it worked
             if result && !self.dry_run {
@@ -245,6 +285,174 @@ impl DependencyInstaller {
 
         Ok(results)
     }
+
+    /// Install `check`'s dependency after any of its [`prerequisites`] that are missing
+    /// too, e.g. the Docker engine before compose.
+    ///
+    /// Returns whether `check`'s own dependency was installed.
+    pub fn install_with_prerequisites(&self, check: &DependencyCheck) -> Result<bool> {
+        self.install_after(check, check_prerequisite)
+    }
+
+    fn install_after(
+        &self,
+        check: &DependencyCheck,
+        check_prerequisite: impl Fn(&Dependency) -> Result<DependencyCheck>,
+    ) -> Result<bool> {
+        let mut checks = Vec::new();
+        for needed in prerequisites(&check.dependency) {
+            let prerequisite = check_prerequisite(&needed)?;
+            if !matches!(prerequisite.status, DependencyStatus::Installed { .. }) {
+                checks.push(prerequisite);
+            }
+        }
+        checks.push(check.clone());
+
+        let results = self.install_dependencies(&checks)?;
+        Ok(results.last().copied().unwrap_or(false))
+    }
+}
+
+/// Probe one of the dependencies [`prerequisites`] returns
+fn check_prerequisite(dependency: &Dependency) -> Result<DependencyCheck> {
+    match dependency {
+        Dependency::Docker {
+            min_version: Some(version),
+            ..
+        } => DockerChecker::with_min_version(version.clone()).check(),
+        Dependency::Docker { .. } => DockerChecker::new().check(),
+        other => anyhow::bail!("No prerequisite check for {}", other.name()),
+    }
+}
+
+/// Dependencies that must be in place before `dependency` can be installed
+pub fn prerequisites(dependency: &Dependency) -> Vec<Dependency> {
+    match dependency {
+        // Compose is a Docker plugin
+        Dependency::Docker {
+            min_version,
+            requires_compose: true,
+        } => vec![Dependency::Docker {
+            min_version: min_version.clone(),
+            requires_compose: false,
+        }],
+        _ => Vec::new(),
+    }
+}
+
+/// Position among dependencies with no ordering constraint between them.
+///
+/// Git goes first since servers installed from GitHub fetch their sources with it.
+fn install_rank(dependency: &Dependency) -> u8 {
+    match dependency {
+        Dependency::Git => 0,
+        Dependency::Docker {
+            requires_compose: false,
+            ..
+        } => 1,
+        Dependency::Docker {
+            requires_compose: true,
+            ..
+        } => 2,
+        Dependency::NodeJs { .. } => 3,
+        Dependency::Python { .. } => 4,
+        Dependency::Rust { .. } => 5,
+        Dependency::Deno { .. } => 6,
+        Dependency::Java { .. } => 7,
+        Dependency::Dotnet { .. } => 8,
+    }
+}
+
+/// Which of a set of dependency checks must be installed before which
+pub struct DependencyGraph {
+    /// For each check, the indices of the checks it needs first
+    prerequisites: Vec<Vec<usize>>,
+    ranks: Vec<u8>,
+}
+
+impl DependencyGraph {
+    /// Build the graph from [`prerequisites`]; prerequisites outside `checks` are ignored
+    pub fn new(checks: &[DependencyCheck]) -> Self {
+        let same = |a: &Dependency, b: &Dependency| match (a, b) {
+            // Any Docker without compose satisfies the engine prerequisite
+            (
+                Dependency::Docker {
+                    requires_compose: false,
+                    ..
+                },
+                Dependency::Docker {
+                    requires_compose: false,
+                    ..
+                },
+            ) => true,
+            _ => a == b,
+        };
+
+        let needs = checks
+            .iter()
+            .map(|check| {
+                prerequisites(&check.dependency)
+                    .iter()
+                    .filter_map(|needed| {
+                        checks
+                            .iter()
+                            .position(|other| same(&other.dependency, needed))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            prerequisites: needs,
+            ranks: checks
+                .iter()
+                .map(|check| install_rank(&check.dependency))
+                .collect(),
+        }
+    }
+
+    /// Add an ordering constraint: `before` is installed before `after`
+    pub fn add_edge(&mut self, before: usize, after: usize) {
+        if !self.prerequisites[after].contains(&before) {
+            self.prerequisites[after].push(before);
+        }
+    }
+
+    pub fn prerequisites_of(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.prerequisites[index].iter().copied()
+    }
+
+    /// Indices of the checks in installation order.
+    ///
+    /// Prerequisites come first; otherwise checks go by rank (Git, Docker, compose,
+    /// runtimes), then in the order given. Fails if the constraints form a cycle.
+    pub fn install_order(&self) -> Result<Vec<usize>> {
+        let count = self.prerequisites.len();
+        let mut waiting_on: Vec<usize> = self.prerequisites.iter().map(Vec::len).collect();
+        let mut ready: BTreeSet<(u8, usize)> = (0..count)
+            .filter(|&index| waiting_on[index] == 0)
+            .map(|index| (self.ranks[index], index))
+            .collect();
+        let mut order = Vec::with_capacity(count);
+
+        while let Some(next) = ready.pop_first() {
+            let (_, index) = next;
+            order.push(index);
+            for (dependent, needs) in self.prerequisites.iter().enumerate() {
+                for _ in needs.iter().filter(|&&needed| needed == index) {
+                    waiting_on[dependent] -= 1;
+                    if waiting_on[dependent] == 0 {
+                        ready.insert((self.ranks[dependent], dependent));
+                    }
+                }
+            }
+        }
+
+        if order.len() < count {
+            anyhow::bail!("Dependencies require each other; cannot decide an installation order");
+        }
+        Ok(order)
+    }
 }
 
 impl Default for DependencyInstaller {
@@ -304,10 +512,11 @@ pub fn detect_package_managers() -> Vec<String> {
 
 /// Check if a command exists in PATH
 fn command_exists(command: &str) -> bool {
-    Command::new(command)
+    Process::new(command)
         .arg("--version")
+        .timeout(crate::deps::probe::probe_timeout())
         .output()
-        .map(|output| output.status.success())
+        .map(|output| output.success())
         .unwrap_or(false)
 }
 
@@ -418,6 +627,122 @@ mod tests {
         assert!(!not_exists);
     }
 
+    fn check(dependency: Dependency) -> DependencyCheck {
+        DependencyCheck {
+            dependency,
+            status: crate::deps::DependencyStatus::Missing,
+            install_instructions: None,
+        }
+    }
+
+    fn docker(requires_compose: bool) -> Dependency {
+        Dependency::Docker {
+            min_version: None,
+            requires_compose,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_command_runs_pipelines_in_shell() {
+        let installer = DependencyInstaller::new();
+        assert!(installer.execute_command("echo ok | grep ok").unwrap());
+        assert!(!installer.execute_command("true && false").unwrap());
+        assert!(installer.execute_command("true").unwrap());
+    }
+
+    #[test]
+    fn test_install_order() {
+        let checks = vec![
+            check(Dependency::NodeJs { min_version: None }),
+            check(docker(true)),
+            check(Dependency::Git),
+            check(docker(false)),
+        ];
+        let graph = DependencyGraph::new(&checks);
+        assert_eq!(graph.prerequisites_of(1).collect::<Vec<_>>(), [3]);
+        assert_eq!(graph.install_order().unwrap(), [2, 3, 1, 0]);
+
+        // Without the engine in the set, compose has nothing to wait for
+        let checks = vec![
+            check(Dependency::Rust { min_version: None }),
+            check(docker(true)),
+        ];
+        assert_eq!(
+            DependencyGraph::new(&checks).install_order().unwrap(),
+            [1, 0]
+        );
+    }
+
+    #[test]
+    fn test_install_order_edges_and_cycles() {
+        let checks = vec![
+            check(Dependency::Git),
+            check(Dependency::Python { min_version: None }),
+        ];
+        let mut graph = DependencyGraph::new(&checks);
+        graph.add_edge(1, 0);
+        assert_eq!(graph.install_order().unwrap(), [1, 0]);
+
+        graph.add_edge(0, 1);
+        assert!(graph.install_order().is_err());
+    }
+
+    #[test]
+    fn test_install_dependencies_skips_after_failed_prerequisite() {
+        let installer = DependencyInstaller::new().with_dry_run();
+        let mut compose = check(docker(true));
+        compose.install_instructions = Some(crate::deps::get_install_instructions(&docker(true)));
+        // The engine has no install instructions, so it cannot be installed
+        let results = installer
+            .install_dependencies(&[compose, check(docker(false))])
+            .unwrap();
+        assert_eq!(results, [false, false]);
+    }
+
+    #[test]
+    fn test_install_with_prerequisites_installs_missing_engine_first() {
+        let installer = DependencyInstaller::new().with_dry_run();
+        let with_instructions = |dependency: Dependency| DependencyCheck {
+            install_instructions: Some(crate::deps::get_install_instructions(&dependency)),
+            ..check(dependency)
+        };
+
+        let probed = std::cell::RefCell::new(Vec::new());
+        let installed = installer
+            .install_after(&with_instructions(docker(true)), |needed| {
+                probed.borrow_mut().push(needed.clone());
+                Ok(with_instructions(needed.clone()))
+            })
+            .unwrap();
+        assert!(installed);
+        assert_eq!(probed.into_inner(), [docker(false)]);
+
+        // An engine that cannot be installed leaves compose alone
+        let installed = installer
+            .install_after(&with_instructions(docker(true)), |needed| {
+                Ok(check(needed.clone()))
+            })
+            .unwrap();
+        assert!(!installed);
+    }
+
+    #[test]
+    fn test_install_with_prerequisites_skips_installed_engine() {
+        let installer = DependencyInstaller::new().with_dry_run();
+        let mut compose = check(docker(true));
+        compose.install_instructions = Some(crate::deps::get_install_instructions(&docker(true)));
+        let installed = installer
+            .install_after(&compose, |needed| {
+                Ok(DependencyCheck {
+                    status: DependencyStatus::Installed { version: None },
+                    ..check(needed.clone())
+                })
+            })
+            .unwrap();
+        assert!(installed);
+    }
+
     #[test]
     fn test_install_dependencies_empty() {
         let installer = DependencyInstaller::new().with_dry_run();
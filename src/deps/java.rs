@@ -0,0 +1,177 @@
+use crate::deps::{
+    base::DependencyCheckerBase, probe::probe_output, Dependency, DependencyCheck,
+    DependencyChecker, DependencyStatus,
+};
+use anyhow::Result;
+
+/// Checks for the Java runtime used to run JAR-based servers
+#[derive(Debug)]
+pub struct JavaChecker {
+    min_version: Option<String>,
+}
+
+impl JavaChecker {
+    pub fn new() -> Self {
+        Self { min_version: None }
+    }
+
+    pub fn with_min_version(min_version: impl Into<String>) -> Self {
+        Self {
+            min_version: Some(min_version.into()),
+        }
+    }
+
+    fn check_java_version(&self) -> Result<Option<String>> {
+        // `java -version` prints to stderr; only newer runtimes also accept `--version`
+        let output = probe_output("java", &["-version"])?;
+        if !output.success {
+            return Ok(None);
+        }
+        Ok(parse_java_version(&output.stderr).or_else(|| parse_java_version(&output.stdout)))
+    }
+}
+
+impl Default for JavaChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DependencyChecker for JavaChecker {
+    fn checked_dependency(&self) -> Option<Dependency> {
+        Some(Dependency::Java {
+            min_version: self.min_version.clone(),
+        })
+    }
+
+    fn check(&self) -> Result<DependencyCheck> {
+        let dependency = Dependency::Java {
+            min_version: self.min_version.clone(),
+        };
+
+        let status = match self.check_java_version().ok().flatten() {
+            Some(version) => match &self.min_version {
+                Some(min_version) => {
+                    let required =
+                        normalize_java_version(min_version).unwrap_or_else(|| min_version.clone());
+                    DependencyCheckerBase::check_version_requirement(&version, &required)?
+                }
+                None => DependencyStatus::Installed {
+                    version: Some(version),
+                },
+            },
+            None => DependencyStatus::Missing,
+        };
+
+        let install_instructions =
+            if DependencyCheckerBase::should_provide_install_instructions(&status) {
+                Some(crate::deps::get_install_instructions(&dependency))
+            } else {
+                None
+            };
+
+        Ok(DependencyCheck {
+            dependency,
+            status,
+            install_instructions,
+        })
+    }
+}
+
+/// The runtime version in `java -version` output, as semver.
+///
+/// Handles the vendor variations of the first line: `java version "1.8.0_392"`
+/// (legacy numbering), `openjdk version "21" 2023-09-19` (no minor or patch),
+/// `openjdk version "17.0.8.1"` (Semeru, Corretto), `"23-ea"` builds and
+/// `Picked up JAVA_TOOL_OPTIONS` lines printed before it.
+pub fn parse_java_version(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (_, rest) = line.split_once(" version \"")?;
+        let (quoted, _) = rest.split_once('"')?;
+        normalize_java_version(quoted)
+    })
+}
+
+/// `major.minor.patch` for a Java version string such as `1.8.0_392`, `17.0.9+9` or `21`
+pub fn normalize_java_version(version: &str) -> Option<String> {
+    let core = version.trim().split(['-', '+']).next().unwrap_or_default();
+    let (core, update) = match core.split_once('_') {
+        Some((core, update)) => (core, update.parse::<u64>().ok()),
+        None => (core, None),
+    };
+
+    let mut parts = core
+        .split('.')
+        .map(|part| part.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    // Java 8 and earlier report themselves as 1.x, with the update after '_'
+    if parts[0] == 1 && parts.len() > 1 {
+        parts.remove(0);
+        parts.truncate(2);
+        parts.resize(2, 0);
+        parts.push(update.unwrap_or(0));
+    }
+    parts.resize(3.max(parts.len()), 0);
+    Some(format!("{}.{}.{}", parts[0], parts[1], parts[2]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_java_checker_creation() {
+        assert!(JavaChecker::new().min_version.is_none());
+        assert_eq!(
+            JavaChecker::with_min_version("17").min_version,
+            Some("17".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_java_version_output() {
+        let cases = [
+            (
+                "openjdk version \"17.0.9\" 2023-10-17\nOpenJDK Runtime Environment Temurin-17.0.9+9 (build 17.0.9+9)",
+                "17.0.9",
+            ),
+            (
+                "java version \"1.8.0_392\"\nJava(TM) SE Runtime Environment (build 1.8.0_392-b08)",
+                "8.0.392",
+            ),
+            ("openjdk version \"21\" 2023-09-19", "21.0.0"),
+            ("openjdk version \"17.0.8.1\" 2023-08-24", "17.0.8"),
+            ("openjdk version \"23-ea\" 2024-09-17", "23.0.0"),
+            ("openjdk version \"11.0.21-internal\" 2023-10-17", "11.0.21"),
+            (
+                "Picked up JAVA_TOOL_OPTIONS: -Dfile.encoding=UTF-8\njava version \"21.0.1\" 2023-10-17 LTS",
+                "21.0.1",
+            ),
+        ];
+        for (output, expected) in cases {
+            assert_eq!(
+                parse_java_version(output),
+                Some(expected.to_string()),
+                "{output}"
+            );
+        }
+        assert_eq!(parse_java_version("command not found"), None);
+    }
+
+    #[test]
+    fn test_normalize_min_version() {
+        assert_eq!(normalize_java_version("17"), Some("17.0.0".to_string()));
+        assert_eq!(normalize_java_version("1.8"), Some("8.0.0".to_string()));
+        assert_eq!(normalize_java_version("11.0.2"), Some("11.0.2".to_string()));
+        assert_eq!(normalize_java_version("latest"), None);
+    }
+
+    #[test]
+    fn test_java_checker_reports_dependency() {
+        let check = JavaChecker::new().check().unwrap();
+        assert_eq!(check.dependency, Dependency::Java { min_version: None });
+        if check.status == DependencyStatus::Missing {
+            assert!(check.install_instructions.is_some());
+        }
+    }
+}
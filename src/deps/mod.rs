@@ -1,17 +1,35 @@
 pub mod base;
+pub mod check_cache;
+pub mod deno;
 pub mod docker;
+pub mod dotnet;
+pub mod explain;
+pub mod install_log;
 pub mod installer;
+pub mod java;
 pub mod node;
+pub mod node_installs;
+pub mod probe;
 pub mod python;
+pub mod rust;
+pub mod verify;
 pub mod version;
 
 use anyhow::Result;
 use std::fmt;
 
+pub use check_cache::DependencyCheckCache;
+pub use deno::DenoChecker;
 pub use docker::DockerChecker;
-pub use installer::{detect_package_managers, DependencyInstaller};
+pub use dotnet::DotnetChecker;
+pub use installer::{detect_package_managers, DependencyGraph, DependencyInstaller};
+pub use java::JavaChecker;
 pub use node::NodeChecker;
+pub use node_installs::{NodeInstallReport, NodeSource, NodeTool};
+pub use probe::{check_responsive, probe_timeout, ProbeTimeout};
 pub use python::PythonChecker;
+pub use rust::RustChecker;
+pub use verify::{verify_installation, VerificationOutcome};
 pub use version::{VersionHelper, VersionRequirement};
 
 #[derive(Debug, Clone)]
@@ -34,14 +52,39 @@ pub enum Dependency {
         requires_compose: bool,
     },
     Git,
+    Rust {
+        min_version: Option<String>,
+    },
+    Deno {
+        min_version: Option<String>,
+    },
+    Java {
+        min_version: Option<String>,
+    },
+    Dotnet {
+        min_version: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum DependencyStatus {
-    Installed { version: Option<String> },
+    Installed {
+        version: Option<String>,
+    },
     Missing,
-    VersionMismatch { installed: String, required: String },
-    ConfigurationRequired { issue: String, solution: String },
+    VersionMismatch {
+        installed: String,
+        required: String,
+    },
+    ConfigurationRequired {
+        issue: String,
+        solution: String,
+    },
+    /// A probe such as `docker info` did not answer before its timeout
+    Unresponsive {
+        command: String,
+        timeout_secs: u64,
+    },
 }
 
 #[derive(Debug, Clone, Default)]
@@ -60,6 +103,13 @@ pub struct InstallMethod {
 
 pub trait DependencyChecker: Send + Sync {
     fn check(&self) -> Result<DependencyCheck>;
+
+    /// The dependency this checker verifies, when known without running the check.
+    ///
+    /// Used to share results between servers with the same requirement.
+    fn checked_dependency(&self) -> Option<Dependency> {
+        None
+    }
 }
 
 impl Dependency {
@@ -69,6 +119,24 @@ impl Dependency {
             Dependency::Python { .. } => "Python",
             Dependency::Docker { .. } => "Docker",
             Dependency::Git => "Git",
+            Dependency::Rust { .. } => "Rust",
+            Dependency::Deno { .. } => "Deno",
+            Dependency::Java { .. } => "Java",
+            Dependency::Dotnet { .. } => ".NET",
+        }
+    }
+
+    /// Executable names that indicate this dependency is on PATH
+    pub fn executables(&self) -> &'static [&'static str] {
+        match self {
+            Dependency::NodeJs { .. } => &["node"],
+            Dependency::Python { .. } => &["python3", "python"],
+            Dependency::Docker { .. } => &["docker"],
+            Dependency::Git => &["git"],
+            Dependency::Rust { .. } => &["cargo"],
+            Dependency::Deno { .. } => &["deno"],
+            Dependency::Java { .. } => &["java"],
+            Dependency::Dotnet { .. } => &["dotnet"],
         }
     }
 }
@@ -96,6 +164,15 @@ impl fmt::Display for DependencyStatus {
             DependencyStatus::ConfigurationRequired { issue, solution: _ } => {
                 write!(f, "Configuration required: {issue}")
             }
+            DependencyStatus::Unresponsive {
+                command,
+                timeout_secs,
+            } => {
+                write!(
+                    f,
+                    "Unresponsive ('{command}' did not answer within {timeout_secs}s)"
+                )
+            }
         }
     }
 }
@@ -323,11 +400,181 @@ const GIT_CONFIG: InstallConfig = InstallConfig {
     ],
 };
 
+const RUST_CONFIG: InstallConfig = InstallConfig {
+    windows: &[
+        (
+            "winget",
+            "winget install Rustlang.Rustup",
+            "Windows Package Manager (recommended)",
+        ),
+        (
+            "download",
+            "https://rustup.rs/",
+            "Download rustup-init.exe from rustup.rs",
+        ),
+    ],
+    macos: &[
+        (
+            "rustup",
+            "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh",
+            "Official rustup installer (recommended)",
+        ),
+        (
+            "homebrew",
+            "brew install rustup",
+            "Homebrew package manager",
+        ),
+    ],
+    linux: &[
+        (
+            "rustup",
+            "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh",
+            "Official rustup installer (recommended)",
+        ),
+        (
+            "apt",
+            "sudo apt install cargo",
+            "Debian/Ubuntu system package",
+        ),
+        (
+            "dnf",
+            "sudo dnf install cargo",
+            "Fedora/RHEL system package",
+        ),
+    ],
+};
+
+const DENO_CONFIG: InstallConfig = InstallConfig {
+    windows: &[
+        (
+            "winget",
+            "winget install DenoLand.Deno",
+            "Windows Package Manager (recommended)",
+        ),
+        (
+            "powershell",
+            "irm https://deno.land/install.ps1 | iex",
+            "Official install script",
+        ),
+    ],
+    macos: &[
+        (
+            "homebrew",
+            "brew install deno",
+            "Homebrew package manager (recommended)",
+        ),
+        (
+            "curl",
+            "curl -fsSL https://deno.land/install.sh | sh",
+            "Official install script",
+        ),
+    ],
+    linux: &[(
+        "curl",
+        "curl -fsSL https://deno.land/install.sh | sh",
+        "Official install script (recommended)",
+    )],
+};
+
+const JAVA_CONFIG: InstallConfig = InstallConfig {
+    windows: &[
+        (
+            "winget",
+            "winget install EclipseAdoptium.Temurin.21.JRE",
+            "Windows Package Manager (recommended)",
+        ),
+        (
+            "chocolatey",
+            "choco install temurin21jre",
+            "Chocolatey package manager",
+        ),
+        (
+            "download",
+            "https://adoptium.net/temurin/releases/",
+            "Direct download from adoptium.net",
+        ),
+    ],
+    macos: &[
+        (
+            "homebrew",
+            "brew install --cask temurin@21",
+            "Homebrew package manager (recommended)",
+        ),
+        ("sdkman", "sdk install java 21-tem", "Temurin via SDKMAN!"),
+        (
+            "download",
+            "https://adoptium.net/temurin/releases/",
+            "Direct download from adoptium.net",
+        ),
+    ],
+    linux: &[
+        (
+            "sdkman",
+            "curl -s https://get.sdkman.io | bash && sdk install java 21-tem",
+            "Temurin via SDKMAN! (recommended)",
+        ),
+        (
+            "apt",
+            "sudo apt update && sudo apt install temurin-21-jre",
+            "Debian/Ubuntu, after adding the Adoptium repository",
+        ),
+        (
+            "download",
+            "https://adoptium.net/temurin/releases/",
+            "Direct download from adoptium.net",
+        ),
+    ],
+};
+
+const DOTNET_CONFIG: InstallConfig = InstallConfig {
+    windows: &[
+        (
+            "winget",
+            "winget install Microsoft.DotNet.SDK.8",
+            "Windows Package Manager (recommended)",
+        ),
+        (
+            "download",
+            "https://dotnet.microsoft.com/download",
+            "Direct download from dotnet.microsoft.com",
+        ),
+    ],
+    macos: &[
+        (
+            "homebrew",
+            "brew install --cask dotnet-sdk",
+            "Homebrew package manager (recommended)",
+        ),
+        (
+            "curl",
+            "curl -sSL https://dot.net/v1/dotnet-install.sh | bash /dev/stdin --channel LTS",
+            "Official install script",
+        ),
+    ],
+    linux: &[
+        (
+            "apt",
+            "sudo apt update && sudo apt install dotnet-sdk-8.0",
+            "Debian/Ubuntu (recommended)",
+        ),
+        ("dnf", "sudo dnf install dotnet-sdk-8.0", "Fedora/RHEL"),
+        (
+            "curl",
+            "curl -sSL https://dot.net/v1/dotnet-install.sh | bash /dev/stdin --channel LTS",
+            "Official install script",
+        ),
+    ],
+};
+
 pub fn get_install_instructions(dependency: &Dependency) -> InstallInstructions {
     match dependency {
         Dependency::NodeJs { .. } => NODEJS_CONFIG.to_instructions(),
         Dependency::Python { .. } => PYTHON_CONFIG.to_instructions(),
         Dependency::Docker { .. } => DOCKER_CONFIG.to_instructions(),
         Dependency::Git => GIT_CONFIG.to_instructions(),
+        Dependency::Rust { .. } => RUST_CONFIG.to_instructions(),
+        Dependency::Deno { .. } => DENO_CONFIG.to_instructions(),
+        Dependency::Java { .. } => JAVA_CONFIG.to_instructions(),
+        Dependency::Dotnet { .. } => DOTNET_CONFIG.to_instructions(),
     }
 }
@@ -1,10 +1,11 @@
 use crate::deps::{
-    get_install_instructions, version::VersionHelper, Dependency, DependencyCheck,
-    DependencyChecker, DependencyStatus,
+    get_install_instructions,
+    probe::{probe_output, ProbeTimeout},
+    version::VersionHelper,
+    Dependency, DependencyCheck, DependencyChecker, DependencyStatus,
 };
 use crate::logging;
-use anyhow::{Context, Result};
-use std::process::Command;
+use anyhow::Result;
 use which::which;
 
 #[derive(Debug)]
@@ -32,19 +33,13 @@ impl NodeChecker {
     }
 
     fn get_node_version(node_cmd: &str) -> Result<String> {
-        let output = Command::new(node_cmd)
-            .arg("--version")
-            .output()
-            .context("Failed to execute node --version")?;
+        let output = probe_output(node_cmd, &["--version"])?;
 
-        if !output.status.success() {
-            anyhow::bail!("node --version failed with status: {}", output.status);
+        if !output.success {
+            anyhow::bail!("node --version failed");
         }
 
-        let version_str = String::from_utf8(output.stdout)
-            .context("Failed to parse node version output as UTF-8")?;
-
-        Ok(version_str.trim().to_string())
+        Ok(output.stdout.trim().to_string())
     }
 
     fn compare_versions(&self, installed: &str) -> Result<DependencyStatus> {
@@ -89,6 +84,12 @@ impl Default for NodeChecker {
 }
 
 impl DependencyChecker for NodeChecker {
+    fn checked_dependency(&self) -> Option<Dependency> {
+        Some(Dependency::NodeJs {
+            min_version: self.min_version.clone(),
+        })
+    }
+
     fn check(&self) -> Result<DependencyCheck> {
         let dependency = Dependency::NodeJs {
             min_version: self.min_version.clone(),
@@ -112,6 +113,7 @@ impl DependencyChecker for NodeChecker {
         // Get Node.js version
         let version = match Self::get_node_version(&node_cmd) {
             Ok(v) => v,
+            Err(e) if e.is::<ProbeTimeout>() => return Err(e),
             Err(_e) => {
                 return Ok(DependencyCheck {
                     dependency: dependency.clone(),
@@ -0,0 +1,392 @@
+//! Detection of conflicting Node.js installations.
+//!
+//! A machine with nvm, Homebrew and a system Node.js can end up running `node` from
+//! one installation and `npm`/`npx` from another. MCP clients started from the
+//! desktop also skip shell profiles, so version managers activated there (nvm, fnm,
+//! asdf, Volta) are not on the PATH of the servers those clients launch. Pinning
+//! absolute paths in client configs removes the ambiguity.
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+use crate::client::ServerConfig;
+use crate::deps::probe::probe_timeout;
+use crate::utils::process::Process;
+
+/// Commands that come with a Node.js installation
+pub const NODE_TOOLS: &[&str] = &["node", "npm", "npx"];
+
+/// Where a Node.js executable was installed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeSource {
+    Nvm,
+    Fnm,
+    Volta,
+    Asdf,
+    Homebrew,
+    Snap,
+    System,
+    Other,
+}
+
+impl NodeSource {
+    /// Classify an executable by its (resolved) location
+    pub fn from_path(path: &Path) -> Self {
+        let path = path.to_string_lossy().replace('\\', "/").to_lowercase();
+
+        if path.contains("/.nvm/") || path.contains("/nvm/") {
+            Self::Nvm
+        } else if path.contains("/fnm/")
+            || path.contains("/.fnm/")
+            || path.contains("fnm_multishells")
+        {
+            Self::Fnm
+        } else if path.contains("/.volta/") || path.contains("/volta/") {
+            Self::Volta
+        } else if path.contains("/.asdf/") {
+            Self::Asdf
+        } else if path.starts_with("/opt/homebrew/")
+            || path.contains("/cellar/")
+            || path.starts_with("/home/linuxbrew/")
+        {
+            Self::Homebrew
+        } else if path.contains("/snap/") {
+            Self::Snap
+        } else if path.starts_with("/usr/")
+            || path.starts_with("/bin/")
+            || path.contains("/program files/nodejs/")
+        {
+            Self::System
+        } else {
+            Self::Other
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Nvm => "nvm",
+            Self::Fnm => "fnm",
+            Self::Volta => "Volta",
+            Self::Asdf => "asdf",
+            Self::Homebrew => "Homebrew",
+            Self::Snap => "snap",
+            Self::System => "system",
+            Self::Other => "other",
+        }
+    }
+
+    /// Whether this source is only on PATH after a shell profile has run
+    pub fn is_shell_activated(&self) -> bool {
+        matches!(self, Self::Nvm | Self::Fnm | Self::Volta | Self::Asdf)
+    }
+}
+
+/// One resolved Node.js executable
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeTool {
+    pub name: String,
+    pub path: PathBuf,
+    pub version: Option<String>,
+    pub source: NodeSource,
+}
+
+impl NodeTool {
+    fn resolve(name: &str, path: PathBuf) -> Self {
+        let resolved = path.canonicalize().unwrap_or_else(|_| path.clone());
+        let source = match NodeSource::from_path(&path) {
+            NodeSource::System | NodeSource::Other => NodeSource::from_path(&resolved),
+            source => source,
+        };
+
+        Self {
+            name: name.to_string(),
+            version: tool_version(&path),
+            path,
+            source,
+        }
+    }
+
+    /// The directory the tool was found in on PATH
+    pub fn bin_dir(&self) -> Option<&Path> {
+        self.path.parent()
+    }
+
+    /// `/path/to/node (v20.11.0, nvm)`
+    pub fn describe(&self) -> String {
+        format!(
+            "{} ({}, {})",
+            self.path.display(),
+            self.version.as_deref().unwrap_or("unknown version"),
+            self.source.name()
+        )
+    }
+}
+
+fn tool_version(path: &Path) -> Option<String> {
+    let output = Process::new(path)
+        .arg("--version")
+        .timeout(probe_timeout())
+        .output()
+        .ok()?;
+    if !output.success() {
+        return None;
+    }
+    let version = output.stdout_lossy().trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// PATH as seen by MCP clients launched from the desktop
+///
+/// Shell-activated version managers are dropped; everything else is assumed to come
+/// from the login environment and is kept.
+pub fn client_search_path(path: &OsStr) -> OsString {
+    let entries: Vec<PathBuf> = std::env::split_paths(path)
+        .filter(|dir| !NodeSource::from_path(&dir.join("node")).is_shell_activated())
+        .collect();
+    std::env::join_paths(entries).unwrap_or_default()
+}
+
+/// Every Node.js tool reachable from PATH and from an MCP client's point of view
+#[derive(Debug, Clone, Default)]
+pub struct NodeInstallReport {
+    /// Every `node` on PATH, in resolution order
+    pub nodes: Vec<NodeTool>,
+    pub npm: Option<NodeTool>,
+    pub npx: Option<NodeTool>,
+    /// The `node` a desktop-launched client resolves
+    pub client_node: Option<NodeTool>,
+    /// The PATH used to resolve `client_node`
+    pub client_path: OsString,
+}
+
+impl NodeInstallReport {
+    pub fn detect() -> Self {
+        Self::detect_in(&std::env::var_os("PATH").unwrap_or_default())
+    }
+
+    pub fn detect_in(path: &OsStr) -> Self {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let client_path = client_search_path(path);
+
+        let mut nodes: Vec<NodeTool> = Vec::new();
+        if let Ok(found) = which::which_in_all("node", Some(path), &cwd) {
+            for node in found {
+                let canonical = node.canonicalize().unwrap_or_else(|_| node.clone());
+                let duplicate = nodes.iter().any(|known| {
+                    known
+                        .path
+                        .canonicalize()
+                        .unwrap_or_else(|_| known.path.clone())
+                        == canonical
+                });
+                if !duplicate {
+                    nodes.push(NodeTool::resolve("node", node));
+                }
+            }
+        }
+
+        let find = |name: &str, search: &OsStr| {
+            which::which_in(name, Some(search), &cwd)
+                .ok()
+                .map(|found| NodeTool::resolve(name, found))
+        };
+
+        let client_node = find("node", &client_path);
+        Self {
+            npm: find("npm", path),
+            npx: find("npx", path),
+            nodes,
+            client_node,
+            client_path,
+        }
+    }
+
+    /// The `node` a terminal resolves
+    pub fn active_node(&self) -> Option<&NodeTool> {
+        self.nodes.first()
+    }
+
+    /// `npm`/`npx` found in a different directory than the active `node`
+    pub fn mismatched_tools(&self) -> Vec<&NodeTool> {
+        let Some(node_dir) = self.active_node().and_then(NodeTool::bin_dir) else {
+            return Vec::new();
+        };
+        [&self.npm, &self.npx]
+            .into_iter()
+            .flatten()
+            .filter(|tool| tool.bin_dir() != Some(node_dir))
+            .collect()
+    }
+
+    /// Whether clients resolve a different `node` than the terminal, or none at all
+    pub fn client_differs(&self) -> bool {
+        match (self.active_node(), &self.client_node) {
+            (Some(active), Some(client)) => active.path != client.path,
+            (Some(_), None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn has_conflict(&self) -> bool {
+        !self.mismatched_tools().is_empty() || self.client_differs()
+    }
+
+    /// Pin `config` to the active installation, if it runs a bare Node.js command
+    pub fn pin(&self, config: &ServerConfig) -> Option<ServerConfig> {
+        let bin_dir = self.active_node()?.bin_dir()?;
+        pin_to_installation(config, bin_dir, &self.client_path)
+    }
+}
+
+/// Rewrite a bare `node`/`npm`/`npx` command to an absolute path in `bin_dir`.
+///
+/// `npx` and `npm` are scripts that look `node` up on PATH themselves, so `bin_dir`
+/// is also put first on the server's PATH unless the config already sets one.
+pub fn pin_to_installation(
+    config: &ServerConfig,
+    bin_dir: &Path,
+    client_path: &OsStr,
+) -> Option<ServerConfig> {
+    let command = config.command.trim_end_matches(".cmd");
+    if !NODE_TOOLS.contains(&command) {
+        return None;
+    }
+
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let pinned = which::which_in(&config.command, Some(bin_dir), cwd).ok()?;
+
+    let mut config = config.clone();
+    config.command = pinned.to_string_lossy().to_string();
+    if !config
+        .env
+        .keys()
+        .any(|key| key.eq_ignore_ascii_case("PATH"))
+    {
+        let entries =
+            std::iter::once(bin_dir.to_path_buf()).chain(std::env::split_paths(client_path));
+        if let Ok(path) = std::env::join_paths(entries) {
+            config
+                .env
+                .insert("PATH".to_string(), path.to_string_lossy().to_string());
+        }
+    }
+    Some(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_source_from_path() {
+        let cases = [
+            (
+                "/home/me/.nvm/versions/node/v20.11.0/bin/node",
+                NodeSource::Nvm,
+            ),
+            ("/opt/homebrew/bin/node", NodeSource::Homebrew),
+            (
+                "/usr/local/Cellar/node/21.0.0/bin/node",
+                NodeSource::Homebrew,
+            ),
+            ("/home/me/.volta/bin/node", NodeSource::Volta),
+            ("/home/me/.asdf/shims/node", NodeSource::Asdf),
+            ("/snap/bin/node", NodeSource::Snap),
+            ("/usr/bin/node", NodeSource::System),
+            (r"C:\Program Files\nodejs\node.exe", NodeSource::System),
+            ("/srv/tools/node", NodeSource::Other),
+        ];
+        for (path, source) in cases {
+            assert_eq!(NodeSource::from_path(Path::new(path)), source, "{path}");
+        }
+        assert!(NodeSource::Nvm.is_shell_activated());
+        assert!(!NodeSource::Homebrew.is_shell_activated());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_client_search_path_drops_version_managers() {
+        let path =
+            OsString::from("/home/me/.nvm/versions/node/v20.11.0/bin:/usr/local/bin:/usr/bin");
+        assert_eq!(
+            client_search_path(&path),
+            OsString::from("/usr/local/bin:/usr/bin")
+        );
+    }
+
+    #[cfg(unix)]
+    fn fake_tool(dir: &Path, name: &str, version: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\necho {version}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detects_npm_from_another_installation() {
+        let temp_dir = TempDir::new().unwrap();
+        let nvm_bin = temp_dir.path().join(".nvm/versions/node/v20.11.0/bin");
+        let other_bin = temp_dir.path().join("usr-local/bin");
+        fake_tool(&nvm_bin, "node", "v20.11.0");
+        fake_tool(&other_bin, "node", "v18.19.0");
+        fake_tool(&other_bin, "npm", "9.2.0");
+        fake_tool(&other_bin, "npx", "9.2.0");
+
+        let path = std::env::join_paths([&nvm_bin, &other_bin]).unwrap();
+        let report = NodeInstallReport::detect_in(&path);
+
+        assert_eq!(report.nodes.len(), 2);
+        let active = report.active_node().unwrap();
+        assert_eq!(active.version.as_deref(), Some("v20.11.0"));
+        assert_eq!(active.source, NodeSource::Nvm);
+        assert_eq!(report.mismatched_tools().len(), 2);
+
+        // Clients don't see nvm and fall through to the other installation
+        assert_eq!(
+            report.client_node.as_ref().unwrap().path,
+            other_bin.join("node")
+        );
+        assert!(report.client_differs());
+        assert!(report.has_conflict());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pin_to_installation() {
+        let temp_dir = TempDir::new().unwrap();
+        let bin = temp_dir.path().join("bin");
+        fake_tool(&bin, "npx", "10.0.0");
+
+        let config = ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "@acme/server".to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        let pinned = pin_to_installation(&config, &bin, OsStr::new("/usr/bin")).unwrap();
+        assert_eq!(pinned.command, bin.join("npx").to_string_lossy());
+        assert_eq!(pinned.args, config.args);
+        assert_eq!(pinned.env["PATH"], format!("{}:/usr/bin", bin.display()));
+
+        // An explicit PATH is kept, other commands are left alone
+        let mut with_path = config.clone();
+        with_path
+            .env
+            .insert("PATH".to_string(), "/custom".to_string());
+        let pinned = pin_to_installation(&with_path, &bin, OsStr::new("/usr/bin")).unwrap();
+        assert_eq!(pinned.env["PATH"], "/custom");
+
+        let docker = ServerConfig {
+            command: "docker".to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+            cwd: None,
+        };
+        assert!(pin_to_installation(&docker, &bin, OsStr::new("/usr/bin")).is_none());
+    }
+}
@@ -0,0 +1,220 @@
+//! Time-limited dependency probes.
+//!
+//! Checkers run commands such as `node --version` or `docker info`, and some of
+//! them can block indefinitely (`docker info` waits forever while Docker Desktop is
+//! asleep). Every probe runs with a deadline. When the deadline passes the process
+//! is killed, and the checker reports [`DependencyStatus::Unresponsive`] instead of
+//! hanging the install.
+//!
+//! The limit comes from `MCP_HELPER_PROBE_TIMEOUT` (seconds), then
+//! `"dependency_checks": { "timeout_secs": 20 }` in preferences, then
+//! [`DEFAULT_PROBE_TIMEOUT_SECS`].
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::time::Duration;
+
+use super::{DependencyCheck, DependencyChecker, DependencyStatus};
+use crate::utils::process::{is_timeout, Process};
+
+/// Environment variable overriding the probe timeout, in seconds
+pub const PROBE_TIMEOUT_ENV_VAR: &str = "MCP_HELPER_PROBE_TIMEOUT";
+
+/// Probe timeout used when nothing else is configured
+pub const DEFAULT_PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// How long a single dependency probe may run
+pub fn probe_timeout() -> Duration {
+    let from_env = std::env::var(PROBE_TIMEOUT_ENV_VAR)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok());
+    let secs = from_env
+        .or_else(|| {
+            crate::config::Preferences::load()
+                .ok()
+                .and_then(|prefs| prefs.dependency_checks.timeout_secs)
+        })
+        .filter(|secs| *secs > 0)
+        .unwrap_or(DEFAULT_PROBE_TIMEOUT_SECS);
+    Duration::from_secs(secs)
+}
+
+/// Output of a probe that finished in time
+#[derive(Debug, Clone)]
+pub struct ProbeOutput {
+    pub success: bool,
+    pub stdout: String,
+    /// Some tools, such as `java -version`, report on stderr
+    pub stderr: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum ProbeOutcome {
+    Finished(ProbeOutput),
+    TimedOut,
+}
+
+/// A probe that did not finish before its deadline
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbeTimeout {
+    pub command: String,
+    pub timeout_secs: u64,
+}
+
+impl fmt::Display for ProbeTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "'{}' did not respond within {}s",
+            self.command, self.timeout_secs
+        )
+    }
+}
+
+impl std::error::Error for ProbeTimeout {}
+
+/// Run `command` with the configured timeout
+pub fn run_probe(command: &str, args: &[&str]) -> Result<ProbeOutcome> {
+    run_probe_with_timeout(command, args, probe_timeout())
+}
+
+/// Run `command`, killing it if it outlives `timeout`
+pub fn run_probe_with_timeout(
+    command: &str,
+    args: &[&str],
+    timeout: Duration,
+) -> Result<ProbeOutcome> {
+    match Process::new(command).args(args).timeout(timeout).output() {
+        Ok(output) => Ok(ProbeOutcome::Finished(ProbeOutput {
+            success: output.success(),
+            stdout: output.stdout_lossy(),
+            stderr: output.stderr_lossy(),
+        })),
+        Err(e) if is_timeout(&e) => Ok(ProbeOutcome::TimedOut),
+        Err(e) => Err(e).with_context(|| format!("Failed to execute {command}")),
+    }
+}
+
+/// Run `command`, turning a timeout into a [`ProbeTimeout`] error
+pub fn probe_output(command: &str, args: &[&str]) -> Result<ProbeOutput> {
+    let timeout = probe_timeout();
+    match run_probe_with_timeout(command, args, timeout)? {
+        ProbeOutcome::Finished(output) => Ok(output),
+        ProbeOutcome::TimedOut => Err(ProbeTimeout {
+            command: std::iter::once(command)
+                .chain(args.iter().copied())
+                .collect::<Vec<_>>()
+                .join(" "),
+            timeout_secs: timeout.as_secs(),
+        }
+        .into()),
+    }
+}
+
+/// Run `checker`, reporting a timed-out probe as [`DependencyStatus::Unresponsive`]
+pub fn check_responsive(checker: &dyn DependencyChecker) -> Result<DependencyCheck> {
+    match checker.check() {
+        Ok(check) => Ok(check),
+        Err(e) => match (
+            e.downcast_ref::<ProbeTimeout>(),
+            checker.checked_dependency(),
+        ) {
+            (Some(timeout), Some(dependency)) => Ok(DependencyCheck {
+                dependency,
+                status: DependencyStatus::Unresponsive {
+                    command: timeout.command.clone(),
+                    timeout_secs: timeout.timeout_secs,
+                },
+                install_instructions: None,
+            }),
+            _ => Err(e),
+        },
+    }
+}
+
+/// What to do about a command that stopped responding
+pub fn unresponsive_advice(command: &str) -> String {
+    let program = command.split_whitespace().next().unwrap_or(command);
+    let specific = match program {
+        "docker" => {
+            "Docker Desktop may be asleep or still starting. Open it (or run 'sudo systemctl start docker') and wait until it reports it is running"
+        }
+        "podman" => "Start the Podman machine with 'podman machine start'",
+        "node" | "npm" | "npx" | "npx.cmd" if cfg!(target_os = "windows") => {
+            "Antivirus scanning often delays the first Node.js run on Windows; 'mcp doctor --only antivirus' checks for it"
+        }
+        _ => "Check that it runs in a terminal without prompting for input",
+    };
+    format!(
+        "{specific}. If it is just slow, allow more time with {PROBE_TIMEOUT_ENV_VAR}=<seconds>"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deps::Dependency;
+
+    struct HangingChecker;
+
+    impl DependencyChecker for HangingChecker {
+        fn check(&self) -> Result<DependencyCheck> {
+            Err(ProbeTimeout {
+                command: "docker info".to_string(),
+                timeout_secs: 3,
+            }
+            .into())
+        }
+
+        fn checked_dependency(&self) -> Option<Dependency> {
+            Some(Dependency::Docker {
+                min_version: None,
+                requires_compose: false,
+            })
+        }
+    }
+
+    #[test]
+    fn test_timeout_becomes_unresponsive_status() {
+        let check = check_responsive(&HangingChecker).unwrap();
+        assert_eq!(
+            check.status,
+            DependencyStatus::Unresponsive {
+                command: "docker info".to_string(),
+                timeout_secs: 3,
+            }
+        );
+        assert!(check.install_instructions.is_none());
+    }
+
+    #[test]
+    fn test_unresponsive_advice() {
+        assert!(unresponsive_advice("docker info").contains("Docker Desktop"));
+        assert!(unresponsive_advice("node --version").contains(PROBE_TIMEOUT_ENV_VAR));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_finishes() {
+        let outcome =
+            run_probe_with_timeout("sh", &["-c", "echo ready"], Duration::from_secs(5)).unwrap();
+        match outcome {
+            ProbeOutcome::Finished(output) => {
+                assert!(output.success);
+                assert_eq!(output.stdout.trim(), "ready");
+            }
+            ProbeOutcome::TimedOut => panic!("probe should have finished"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_probe_times_out() {
+        use std::time::Instant;
+
+        let started = Instant::now();
+        let outcome = run_probe_with_timeout("sleep", &["30"], Duration::from_millis(200)).unwrap();
+        assert!(matches!(outcome, ProbeOutcome::TimedOut));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
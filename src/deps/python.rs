@@ -1,9 +1,10 @@
 use crate::deps::{
     base::{CommonVersionParsers, DependencyCheckerBase},
+    probe::probe_timeout,
     Dependency, DependencyCheck, DependencyChecker, DependencyStatus,
 };
+use crate::utils::process::Process;
 use anyhow::Result;
-use std::process::Command;
 
 #[derive(Debug)]
 pub struct PythonChecker {
@@ -42,6 +43,12 @@ impl Default for PythonChecker {
 }
 
 impl DependencyChecker for PythonChecker {
+    fn checked_dependency(&self) -> Option<Dependency> {
+        Some(Dependency::Python {
+            min_version: self.min_version.clone(),
+        })
+    }
+
     fn check(&self) -> Result<DependencyCheck> {
         let python_commands = Self::get_python_command();
         let mut found_version: Option<String> = None;
@@ -118,14 +125,13 @@ pub fn get_pip_command() -> Result<String> {
 
     for cmd_str in pip_commands {
         let cmd_parts: Vec<&str> = cmd_str.split_whitespace().collect();
-        let mut command = Command::new(cmd_parts[0]);
-        for part in &cmd_parts[1..] {
-            command.arg(part);
-        }
-        command.arg("--version");
+        let command = Process::new(cmd_parts[0])
+            .args(&cmd_parts[1..])
+            .arg("--version")
+            .timeout(probe_timeout());
 
         if let Ok(output) = command.output() {
-            if output.status.success() {
+            if output.success() {
                 return Ok(cmd_str.to_string());
             }
         }
@@ -0,0 +1,108 @@
+use crate::deps::{
+    base::{CommonVersionParsers, DependencyCheckerBase},
+    Dependency, DependencyCheck, DependencyChecker, DependencyStatus,
+};
+use anyhow::Result;
+
+/// Checks for a Rust toolchain (cargo) used to build crate-based servers
+#[derive(Debug)]
+pub struct RustChecker {
+    min_version: Option<String>,
+}
+
+impl RustChecker {
+    pub fn new() -> Self {
+        Self { min_version: None }
+    }
+
+    pub fn with_min_version(min_version: impl Into<String>) -> Self {
+        Self {
+            min_version: Some(min_version.into()),
+        }
+    }
+
+    fn check_cargo_version(&self) -> Option<String> {
+        let output = DependencyCheckerBase::get_command_version("cargo", &["--version"]).ok()??;
+        CommonVersionParsers::parse_standard_format(&output, "cargo ")
+    }
+}
+
+impl Default for RustChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DependencyChecker for RustChecker {
+    fn checked_dependency(&self) -> Option<Dependency> {
+        Some(Dependency::Rust {
+            min_version: self.min_version.clone(),
+        })
+    }
+
+    fn check(&self) -> Result<DependencyCheck> {
+        let dependency = Dependency::Rust {
+            min_version: self.min_version.clone(),
+        };
+
+        let status = match self.check_cargo_version() {
+            Some(version) => {
+                if let Some(ref min_version) = self.min_version {
+                    DependencyCheckerBase::check_version_requirement(&version, min_version)?
+                } else {
+                    DependencyStatus::Installed {
+                        version: Some(version),
+                    }
+                }
+            }
+            None => DependencyStatus::Missing,
+        };
+
+        let install_instructions =
+            if DependencyCheckerBase::should_provide_install_instructions(&status) {
+                Some(crate::deps::get_install_instructions(&dependency))
+            } else {
+                None
+            };
+
+        Ok(DependencyCheck {
+            dependency,
+            status,
+            install_instructions,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_checker_creation() {
+        assert!(RustChecker::new().min_version.is_none());
+        assert_eq!(
+            RustChecker::with_min_version("1.75.0").min_version,
+            Some("1.75.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_cargo_version_output() {
+        assert_eq!(
+            CommonVersionParsers::parse_standard_format(
+                "cargo 1.79.0 (ffa9cf99a 2024-06-03)",
+                "cargo "
+            ),
+            Some("1.79.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rust_checker_reports_dependency() {
+        let check = RustChecker::new().check().unwrap();
+        assert_eq!(check.dependency, Dependency::Rust { min_version: None });
+        if check.status == DependencyStatus::Missing {
+            assert!(check.install_instructions.is_some());
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Post-install verification of runtime dependencies.
+//!
+//! After a package manager installs a tool (e.g. Node.js via winget), the PATH of
+//! the running process is stale: the new directory only shows up in shells started
+//! afterwards. This module re-probes the dependency using a refreshed PATH (the
+//! registry on Windows, common install locations elsewhere) so we can tell apart
+//! "installation failed" from "installed, but this terminal needs to be restarted".
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::Dependency;
+use crate::utils::process::Process;
+
+/// Result of verifying a freshly installed dependency
+#[derive(Debug, Clone, PartialEq)]
+pub enum VerificationOutcome {
+    /// The dependency is usable from the current process
+    Verified { path: PathBuf },
+    /// The dependency was found on a refreshed PATH, but not the current one
+    NeedsNewTerminal { path: PathBuf },
+    /// The dependency could not be found anywhere
+    NotFound,
+}
+
+impl VerificationOutcome {
+    /// Human-readable guidance for the user, if any action is required
+    pub fn guidance(&self, dependency_name: &str) -> Option<String> {
+        match self {
+            Self::Verified { .. } => None,
+            Self::NeedsNewTerminal { path } => Some(format!(
+                "{dependency_name} was installed to {} but this terminal still uses the old PATH.\n\
+                 Close this terminal and open a new one, then re-run the command.\n\
+                 Restart your MCP client (e.g. Claude Desktop) as well so it picks up the new PATH.",
+                path.display()
+            )),
+            Self::NotFound => Some(format!(
+                "{dependency_name} could not be found after installation.\n\
+                 Check the installer output above, then run 'mcp doctor' to diagnose PATH issues."
+            )),
+        }
+    }
+}
+
+/// Verify that a dependency is available, retrying to give installers time to settle.
+pub fn verify_installation(
+    dependency: &Dependency,
+    attempts: u32,
+    delay: Duration,
+) -> VerificationOutcome {
+    let mut outcome = VerificationOutcome::NotFound;
+
+    for attempt in 0..attempts.max(1) {
+        if attempt > 0 {
+            std::thread::sleep(delay);
+        }
+
+        outcome = probe(dependency);
+        if matches!(outcome, VerificationOutcome::Verified { .. }) {
+            break;
+        }
+    }
+
+    outcome
+}
+
+fn probe(dependency: &Dependency) -> VerificationOutcome {
+    let executables = dependency.executables();
+
+    for exe in executables {
+        if let Ok(path) = which::which(exe) {
+            return VerificationOutcome::Verified { path };
+        }
+    }
+
+    let refreshed = refreshed_path();
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    for exe in executables {
+        if let Ok(path) = which::which_in(exe, Some(&refreshed), &cwd) {
+            return VerificationOutcome::NeedsNewTerminal { path };
+        }
+    }
+
+    VerificationOutcome::NotFound
+}
+
+/// Build a PATH string reflecting what a newly started shell would see.
+pub fn refreshed_path() -> String {
+    let separator = if cfg!(target_os = "windows") {
+        ";"
+    } else {
+        ":"
+    };
+
+    let mut entries: Vec<String> = Vec::new();
+
+    if cfg!(target_os = "windows") {
+        for key in [
+            r"HKLM\SYSTEM\CurrentControlSet\Control\Session Manager\Environment",
+            r"HKCU\Environment",
+        ] {
+            if let Some(value) = query_registry_path(key) {
+                entries.extend(split_path(&expand_windows_env_vars(&value), separator));
+            }
+        }
+    }
+
+    if let Ok(current) = std::env::var("PATH") {
+        entries.extend(split_path(&current, separator));
+    }
+
+    entries.extend(common_install_locations());
+
+    let mut seen = std::collections::HashSet::new();
+    entries.retain(|entry| seen.insert(entry.clone()));
+    entries.join(separator)
+}
+
+fn split_path(value: &str, separator: &str) -> Vec<String> {
+    value
+        .split(separator)
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn query_registry_path(key: &str) -> Option<String> {
+    let output = Process::new("reg")
+        .args(["query", key, "/v", "Path"])
+        .output()
+        .ok()?;
+
+    if !output.success() {
+        return None;
+    }
+
+    parse_registry_value(&output.stdout_lossy())
+}
+
+/// Extract the value from `reg query <key> /v Path` output.
+pub fn parse_registry_value(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        ["REG_EXPAND_SZ", "REG_SZ"].iter().find_map(|kind| {
+            line.find(kind)
+                .map(|pos| line[pos + kind.len()..].trim().to_string())
+        })
+    })
+}
+
+/// Expand `%VAR%` references using the current process environment.
+pub fn expand_windows_env_vars(value: &str) -> String {
+    expand_windows_env_vars_with(value, |name| std::env::var(name).ok())
+}
+
+/// Expand `%VAR%` references with `lookup`; unknown variables are kept as written.
+fn expand_windows_env_vars_with(value: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find('%') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('%') {
+            Some(end) => {
+                let name = &after[..end];
+                match lookup(name) {
+                    Some(expanded) if !name.is_empty() => result.push_str(&expanded),
+                    _ => {
+                        result.push('%');
+                        result.push_str(name);
+                        result.push('%');
+                    }
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push('%');
+                rest = after;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Directories where installers commonly place binaries
+pub fn common_install_locations() -> Vec<String> {
+    let mut locations = Vec::new();
+    let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Ok(program_files) = std::env::var("ProgramFiles") {
+            locations.push(format!(r"{program_files}\nodejs"));
+            locations.push(format!(r"{program_files}\Docker\Docker\resources\bin"));
+            locations.push(format!(r"{program_files}\Git\cmd"));
+        }
+        if let Ok(appdata) = std::env::var("APPDATA") {
+            locations.push(format!(r"{appdata}\npm"));
+        }
+        if let Ok(local) = std::env::var("LOCALAPPDATA") {
+            locations.push(format!(r"{local}\Programs\Python\Python312"));
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        locations.push("/usr/local/bin".to_string());
+        locations.push("/opt/homebrew/bin".to_string());
+        locations.push("/snap/bin".to_string());
+        if let Some(home) = &home {
+            locations.push(home.join(".local").join("bin").display().to_string());
+            locations.push(home.join(".volta").join("bin").display().to_string());
+        }
+    }
+
+    if let Some(home) = &home {
+        locations.push(home.join(".cargo").join("bin").display().to_string());
+    }
+
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_registry_value() {
+        let output = "\r\nHKEY_CURRENT_USER\\Environment\r\n    Path    REG_EXPAND_SZ    %USERPROFILE%\\bin;C:\\tools\r\n";
+        assert_eq!(
+            parse_registry_value(output),
+            Some("%USERPROFILE%\\bin;C:\\tools".to_string())
+        );
+        assert_eq!(parse_registry_value("ERROR: not found"), None);
+    }
+
+    #[test]
+    fn test_expand_windows_env_vars() {
+        let lookup = |name: &str| (name == "MCP_VERIFY_TEST_DIR").then(|| "C:\\Test".to_string());
+        assert_eq!(
+            expand_windows_env_vars_with("%MCP_VERIFY_TEST_DIR%\\bin;%UNSET_MCP_VAR%", lookup),
+            "C:\\Test\\bin;%UNSET_MCP_VAR%"
+        );
+        assert_eq!(expand_windows_env_vars_with("100%", lookup), "100%");
+        assert_eq!(expand_windows_env_vars_with("%%", lookup), "%%");
+    }
+
+    #[test]
+    fn test_guidance() {
+        let outcome = VerificationOutcome::NeedsNewTerminal {
+            path: PathBuf::from("/opt/node/bin/node"),
+        };
+        let guidance = outcome.guidance("Node.js").unwrap();
+        assert!(guidance.contains("new one"));
+
+        assert!(VerificationOutcome::Verified {
+            path: PathBuf::from("node")
+        }
+        .guidance("Node.js")
+        .is_none());
+    }
+
+    #[test]
+    fn test_refreshed_path_contains_current_path() {
+        if let Ok(current) = std::env::var("PATH") {
+            if let Some(first) = current.split(if cfg!(windows) { ';' } else { ':' }).next() {
+                assert!(refreshed_path().contains(first));
+            }
+        }
+    }
+}
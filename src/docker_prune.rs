@@ -0,0 +1,487 @@
+//! `mcp docker prune`: remove Docker leftovers of servers that are no longer configured.
+//!
+//! Docker servers run in containers named `mcp-<image>`, and their images stay on
+//! disk after the server is removed. An image belongs to mcp-helper when an `mcp-*`
+//! container uses it or a client config recorded in the config history ran it.
+//! Anything still referenced by a current client config, or used by a running
+//! container, is kept.
+
+use colored::Colorize;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::client::{detect_clients, ServerConfig};
+use crate::config::ConfigManager;
+use crate::error::McpError;
+use crate::server::docker_requirements::image_from_run_args;
+use crate::utils::confirm::Confirmer;
+use crate::utils::process::Process;
+
+/// Prefix of every container name mcp-helper generates
+const CONTAINER_PREFIX: &str = "mcp-";
+
+/// Removing a large image can take a while on slow disks
+const REMOVE_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A container as listed by `docker ps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerInfo {
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    pub state: String,
+    pub size: String,
+}
+
+impl ContainerInfo {
+    pub fn is_running(&self) -> bool {
+        self.state == "running" || self.state == "restarting"
+    }
+}
+
+/// An image as listed by `docker images`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub id: String,
+    /// `repository:tag`
+    pub reference: String,
+    pub size: String,
+}
+
+/// Containers and images that can be removed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrunePlan {
+    pub containers: Vec<ContainerInfo>,
+    pub images: Vec<ImageInfo>,
+}
+
+impl PrunePlan {
+    pub fn is_empty(&self) -> bool {
+        self.containers.is_empty() && self.images.is_empty()
+    }
+
+    /// Keep only what belongs to `images`
+    pub fn restrict_to(mut self, images: &BTreeSet<String>) -> Self {
+        self.containers
+            .retain(|container| images.contains(&normalize_image(&container.image)));
+        self.images
+            .retain(|image| images.contains(&normalize_image(&image.reference)));
+        self
+    }
+}
+
+/// Parse `docker ps --format '{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.State}}\t{{.Size}}'`
+pub fn parse_containers(output: &str) -> Vec<ContainerInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                [id, name, image, state, size] => Some(ContainerInfo {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    image: image.to_string(),
+                    state: state.to_string(),
+                    size: size.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Parse `docker images --format '{{.ID}}\t{{.Repository}}\t{{.Tag}}\t{{.Size}}'`
+pub fn parse_images(output: &str) -> Vec<ImageInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            match fields.as_slice() {
+                // Untagged images cannot be matched against a config
+                [_, "<none>", _, _] | [_, _, "<none>", _] => None,
+                [id, repository, tag, size] => Some(ImageInfo {
+                    id: id.to_string(),
+                    reference: format!("{repository}:{tag}"),
+                    size: size.to_string(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// An image reference in the form `docker images` lists it.
+///
+/// `postgres` becomes `postgres:latest` and the implicit Docker Hub registry is
+/// dropped, so `docker.io/library/postgres:13` matches `postgres:13`.
+pub fn normalize_image(reference: &str) -> String {
+    let reference = reference
+        .strip_prefix("docker.io/library/")
+        .or_else(|| reference.strip_prefix("docker.io/"))
+        .unwrap_or(reference);
+    let name = reference.rsplit('/').next().unwrap_or(reference);
+    if name.contains(':') || name.contains('@') {
+        reference.to_string()
+    } else {
+        format!("{reference}:latest")
+    }
+}
+
+/// The normalized image a config runs, if it is a `docker run` server
+pub fn config_image(config: &ServerConfig) -> Option<String> {
+    let program = config
+        .command
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(&config.command)
+        .to_lowercase();
+    if program != "docker" && program != "docker.exe" {
+        return None;
+    }
+    image_from_run_args(&config.args).map(normalize_image)
+}
+
+/// Decide what to remove.
+///
+/// `known` are the images mcp-helper configured at some point and `referenced` the
+/// ones current configs still use, both normalized. Containers are only listed
+/// when stopped; images only when no running container uses them.
+pub fn plan_prune(
+    containers: &[ContainerInfo],
+    images: &[ImageInfo],
+    known: &BTreeSet<String>,
+    referenced: &BTreeSet<String>,
+) -> PrunePlan {
+    let ours: Vec<&ContainerInfo> = containers
+        .iter()
+        .filter(|container| container.name.starts_with(CONTAINER_PREFIX))
+        .collect();
+    let in_use: BTreeSet<String> = ours
+        .iter()
+        .filter(|container| container.is_running())
+        .map(|container| normalize_image(&container.image))
+        .collect();
+    let owned: BTreeSet<String> = ours
+        .iter()
+        .map(|container| normalize_image(&container.image))
+        .chain(known.iter().cloned())
+        .collect();
+
+    let unused = |image: &str| !referenced.contains(image) && !in_use.contains(image);
+
+    PrunePlan {
+        containers: ours
+            .iter()
+            .filter(|container| {
+                !container.is_running() && !referenced.contains(&normalize_image(&container.image))
+            })
+            .map(|container| (*container).clone())
+            .collect(),
+        images: images
+            .iter()
+            .filter(|image| {
+                let reference = normalize_image(&image.reference);
+                owned.contains(&reference) && unused(&reference)
+            })
+            .cloned()
+            .collect(),
+    }
+}
+
+/// Remove unused mcp-helper containers and images
+pub struct DockerPruneCommand {
+    verbose: bool,
+    yes: bool,
+    dry_run: bool,
+    /// Only consider these normalized images
+    only_images: Option<BTreeSet<String>>,
+}
+
+impl DockerPruneCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            yes: false,
+            dry_run: false,
+            only_images: None,
+        }
+    }
+
+    /// Remove without asking for confirmation
+    pub fn set_yes(&mut self, yes: bool) {
+        self.yes = yes;
+    }
+
+    /// Only list what would be removed
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// Limit pruning to the containers and images of these images
+    pub fn set_only_images(&mut self, images: BTreeSet<String>) {
+        self.only_images = Some(images);
+    }
+
+    pub fn execute(&self) -> Result<(), McpError> {
+        let containers = docker_lines(&[
+            "ps",
+            "-a",
+            "--filter",
+            &format!("name=^{CONTAINER_PREFIX}"),
+            "--format",
+            "{{.ID}}\t{{.Names}}\t{{.Image}}\t{{.State}}\t{{.Size}}",
+        ])
+        .map(|output| parse_containers(&output))?;
+        let images = docker_lines(&[
+            "images",
+            "--format",
+            "{{.ID}}\t{{.Repository}}\t{{.Tag}}\t{{.Size}}",
+        ])
+        .map(|output| parse_images(&output))?;
+
+        let mut plan = plan_prune(
+            &containers,
+            &images,
+            &history_images(),
+            &referenced_images(),
+        );
+        if let Some(only) = &self.only_images {
+            plan = plan.restrict_to(only);
+        }
+
+        if plan.is_empty() {
+            println!("{} No unused mcp-helper containers or images", "✓".green());
+            return Ok(());
+        }
+
+        print_plan(&plan);
+
+        if self.dry_run {
+            println!("{} Dry run: nothing was removed", "ℹ".blue());
+            return Ok(());
+        }
+
+        let confirm = Confirmer::new(self.yes).confirm("Remove these containers and images?")?;
+        if !confirm {
+            println!("{} Prune cancelled", "❌".red());
+            return Ok(());
+        }
+
+        let mut failed = 0;
+        for container in &plan.containers {
+            failed += usize::from(!self.remove("rm", &container.id, &container.name));
+        }
+        for image in &plan.images {
+            failed += usize::from(!self.remove("rmi", &image.id, &image.reference));
+        }
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Failed to remove {} of {} containers and images",
+                failed,
+                plan.containers.len() + plan.images.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run `docker <subcommand> <id>`, reporting the outcome
+    fn remove(&self, subcommand: &str, id: &str, label: &str) -> bool {
+        if self.verbose {
+            println!("  Running: docker {subcommand} {id}");
+        }
+        let removal = Process::new("docker")
+            .args([subcommand, id])
+            .timeout(REMOVE_TIMEOUT)
+            .output();
+        match removal {
+            Ok(output) if output.success() => {
+                println!("{} Removed {}", "✅".green(), label.cyan());
+                true
+            }
+            Ok(output) => {
+                eprintln!(
+                    "{} Failed to remove {}: {}",
+                    "✗".red(),
+                    label,
+                    output.stderr_lossy().trim()
+                );
+                false
+            }
+            Err(e) => {
+                eprintln!("{} Failed to remove {}: {}", "✗".red(), label, e);
+                false
+            }
+        }
+    }
+}
+
+fn print_plan(plan: &PrunePlan) {
+    if !plan.containers.is_empty() {
+        println!("{}", "Stopped containers:".yellow());
+        for container in &plan.containers {
+            println!(
+                "  • {} ({}) {}",
+                container.name.cyan(),
+                container.image,
+                container.size.dimmed()
+            );
+        }
+    }
+    if !plan.images.is_empty() {
+        println!("{}", "Unused images:".yellow());
+        for image in &plan.images {
+            println!("  • {} {}", image.reference.cyan(), image.size.dimmed());
+        }
+    }
+    println!();
+}
+
+/// Run a docker listing command and return its output
+fn docker_lines(args: &[&str]) -> Result<String, McpError> {
+    let output = Process::new("docker").args(args).output()?;
+    if !output.success() {
+        return Err(McpError::Other(anyhow::anyhow!(
+            "docker {} failed: {}",
+            args[0],
+            output.stderr_lossy().trim()
+        )));
+    }
+    Ok(output.stdout_lossy())
+}
+
+/// Images run by servers in the current client configs
+fn referenced_images() -> BTreeSet<String> {
+    detect_clients()
+        .iter()
+        .filter(|client| client.is_installed())
+        .filter_map(|client| client.list_servers().ok())
+        .flat_map(|servers| servers.into_values())
+        .filter_map(|config| config_image(&config))
+        .collect()
+}
+
+/// Images of every docker server recorded in the config history
+fn history_images() -> BTreeSet<String> {
+    let Ok(snapshots) = ConfigManager::new().and_then(|manager| manager.get_history(None, None))
+    else {
+        return BTreeSet::new();
+    };
+    snapshots
+        .iter()
+        .flat_map(|snapshot| std::iter::once(&snapshot.config).chain(&snapshot.previous_config))
+        .filter_map(config_image)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn container(name: &str, image: &str, state: &str) -> ContainerInfo {
+        ContainerInfo {
+            id: format!("id-{name}"),
+            name: name.to_string(),
+            image: image.to_string(),
+            state: state.to_string(),
+            size: "0B".to_string(),
+        }
+    }
+
+    fn image(reference: &str) -> ImageInfo {
+        ImageInfo {
+            id: format!("id-{reference}"),
+            reference: reference.to_string(),
+            size: "120MB".to_string(),
+        }
+    }
+
+    fn set(images: &[&str]) -> BTreeSet<String> {
+        images.iter().map(|image| image.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_docker_output() {
+        let containers = parse_containers(
+            "abc\tmcp-postgres-13\tpostgres:13\texited\t0B (virtual 400MB)\nbroken line\n",
+        );
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].id, "abc");
+        assert_eq!(containers[0].image, "postgres:13");
+        assert_eq!(containers[0].size, "0B (virtual 400MB)");
+        assert!(!containers[0].is_running());
+
+        let images = parse_images("1\tpostgres\t13\t400MB\n2\t<none>\t<none>\t1MB\n");
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].reference, "postgres:13");
+    }
+
+    #[test]
+    fn test_normalize_image() {
+        assert_eq!(normalize_image("postgres"), "postgres:latest");
+        assert_eq!(
+            normalize_image("docker.io/library/postgres:13"),
+            "postgres:13"
+        );
+        assert_eq!(
+            normalize_image("ghcr.io/org/server"),
+            "ghcr.io/org/server:latest"
+        );
+        assert_eq!(
+            normalize_image("localhost:5000/server"),
+            "localhost:5000/server:latest"
+        );
+    }
+
+    #[test]
+    fn test_config_image() {
+        let config = ServerConfig {
+            command: "docker".to_string(),
+            args: ["run", "--rm", "-i", "--name", "mcp-x", "mcp/fetch"]
+                .map(String::from)
+                .to_vec(),
+            env: HashMap::new(),
+            cwd: None,
+        };
+        assert_eq!(config_image(&config).as_deref(), Some("mcp/fetch:latest"));
+
+        let npx = ServerConfig {
+            command: "npx".to_string(),
+            ..config
+        };
+        assert_eq!(config_image(&npx), None);
+    }
+
+    #[test]
+    fn test_plan_keeps_referenced_and_running() {
+        let containers = [
+            container("mcp-old", "old:1", "exited"),
+            container("mcp-kept", "kept:1", "exited"),
+            container("mcp-live", "live:1", "running"),
+            container("other", "foreign:1", "exited"),
+        ];
+        let images = [
+            image("old:1"),
+            image("kept:1"),
+            image("live:1"),
+            image("foreign:1"),
+            image("removed:2"),
+        ];
+
+        let plan = plan_prune(
+            &containers,
+            &images,
+            &set(&["removed:2"]),
+            &set(&["kept:1"]),
+        );
+        let names: Vec<_> = plan.containers.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["mcp-old"]);
+        let references: Vec<_> = plan.images.iter().map(|i| i.reference.as_str()).collect();
+        assert_eq!(references, ["old:1", "removed:2"]);
+
+        let restricted = plan.restrict_to(&set(&["removed:2"]));
+        assert!(restricted.containers.is_empty());
+        assert_eq!(restricted.images.len(), 1);
+    }
+}
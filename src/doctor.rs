@@ -3,562 +3,2415 @@
 //! The doctor command diagnoses common MCP issues and provides actionable
 //! solutions. It checks for environment problems, configuration issues,
 //! and platform-specific quirks that might prevent MCP servers from working.
+//!
+//! Results can be rendered as human-readable text or as JSON for CI pipelines,
+//! scoped to specific check categories, and mapped to an exit status using a
+//! configurable severity threshold: [`EXIT_WARNINGS`] when the worst result is a
+//! warning, [`EXIT_ERRORS`] when it is an error.
+//!
+//! `--fix` first applies the fixes that can't lose anything: config files that only
+//! fail to parse because of comments or trailing commas, and on Windows, entries
+//! that start a bare `npx` clients can't find. Cleaning a corrupted npm cache
+//! is only ever offered interactively, since it throws away every cached package.
+//!
+//! Each category of checks is a [`DiagnosticCheck`]. Tools embedding the
+//! diagnostics call [`run_diagnostics`] for the typed results; [`DoctorCommand`]
+//! only presents them.
 
 use anyhow::Result;
 use colored::Colorize;
-#[cfg(target_os = "windows")]
-use std::collections::HashSet;
-use std::process::Command;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 
-use crate::client::detect_clients;
-use crate::deps::{DependencyChecker, DockerChecker, NodeChecker};
+use crate::cache::CacheManager;
+use crate::client::npx_fallback::{uses_bare_npx, NpxInvocation};
+use crate::client::{detect_clients, McpClient, ServerConfig};
+use crate::config::consistency::{find_divergent, harmonized, Divergence};
+use crate::config::repair::{self, RepairChoice};
+use crate::config::{ConfigManager, Preferences};
+use crate::deps::probe::unresponsive_advice;
+use crate::deps::{check_responsive, DockerChecker, NodeChecker, NodeInstallReport, NodeTool};
 use crate::error::McpError;
+use crate::remote::keychain;
+use crate::server::docker::docker_root_dir;
+use crate::server::BinaryServer;
+use crate::utils::antivirus;
+use crate::utils::disk_space;
+use crate::utils::headless::HeadlessEnvironment;
+use crate::utils::network_fs::{conflict_copies, StorageKind};
+use crate::utils::npm_cache::{self, Verification};
+use crate::utils::process::{is_not_found, Process};
+use crate::utils::state_crypto;
+use crate::utils::state_file::{verify_state_file, Corruption, IncidentLog};
 
 /// Diagnostic check result
-#[derive(Debug)]
-struct DiagnosticResult {
-    category: String,
-    check: String,
-    status: DiagnosticStatus,
-    message: Option<String>,
-    solution: Option<String>,
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticResult {
+    pub category: String,
+    pub check: String,
+    pub status: DiagnosticStatus,
+    pub message: Option<String>,
+    pub solution: Option<String>,
 }
 
-#[derive(Debug, PartialEq)]
-enum DiagnosticStatus {
+/// Severity of a diagnostic result, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticStatus {
     Ok,
     Warning,
     Error,
 }
 
-/// MCP Doctor command for diagnostics and troubleshooting
-pub struct DoctorCommand {
-    verbose: bool,
-}
+impl FromStr for DiagnosticStatus {
+    type Err = String;
 
-impl DoctorCommand {
-    pub fn new(verbose: bool) -> Self {
-        Self { verbose }
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ok" => Ok(Self::Ok),
+            "warning" | "warn" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            other => Err(format!(
+                "Invalid severity '{other}'. Expected one of: ok, warning, error"
+            )),
+        }
     }
+}
 
-    pub fn execute(&self) -> Result<(), McpError> {
-        println!("{}", "🏥 MCP Doctor - System Diagnostics".blue().bold());
-        println!();
-        println!("Running comprehensive system checks...");
-        println!();
-
-        let mut results = Vec::new();
-        let mut has_errors = false;
-        let mut has_warnings = false;
+impl fmt::Display for DiagnosticStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => write!(f, "ok"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
 
-        // Check Node.js and npm
-        self.check_nodejs(&mut results);
+/// `--fail-on` threshold: the least severe result that fails the run, or
+/// `never` to always exit 0 when the checks themselves ran
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailOn {
+    Never,
+    At(DiagnosticStatus),
+}
 
-        // Check Docker (optional)
-        self.check_docker(&mut results);
+impl FromStr for FailOn {
+    type Err = String;
 
-        // Check MCP clients
-        self.check_clients(&mut results);
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("never") {
+            return Ok(Self::Never);
+        }
+        s.parse().map(Self::At).map_err(|_| {
+            format!("Invalid severity '{s}'. Expected one of: never, ok, warning, error")
+        })
+    }
+}
 
-        // Check PATH environment
-        self.check_path(&mut results);
+/// Output format for doctor results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
 
-        // Check platform-specific issues
-        self.check_platform_specific(&mut results);
+impl FromStr for OutputFormat {
+    type Err = String;
 
-        // Check common server configurations
-        self.check_server_configs(&mut results);
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "Invalid output format '{other}'. Expected one of: text, json"
+            )),
+        }
+    }
+}
 
-        // Display results
-        println!("{}", "Diagnostic Results:".blue().bold());
-        println!();
+/// Check categories that can be selected with `--only`.
+pub const CHECK_CATEGORIES: &[&str] = &[
+    "node",
+    "docker",
+    "clients",
+    "path",
+    "platform",
+    "servers",
+    "consistency",
+    "state",
+    "antivirus",
+    "storage",
+    "disk",
+    "npm",
+];
 
-        for result in &results {
-            let status_symbol = match result.status {
-                DiagnosticStatus::Ok => "✓".green(),
-                DiagnosticStatus::Warning => "⚠".yellow(),
-                DiagnosticStatus::Error => "✗".red(),
-            };
+/// One category of checks. [`run_diagnostics`] runs mcp-helper's own; tools can
+/// run theirs alongside them with [`run_checks`].
+pub trait DiagnosticCheck: Send + Sync {
+    /// Category name as selected with `--only`, e.g. `node`
+    fn category(&self) -> &str;
 
-            println!(
-                "{} {} - {}",
-                status_symbol,
-                result.category.cyan(),
-                result.check
-            );
+    /// Run alone, after the parallel checks, because it measures timings
+    fn exclusive(&self) -> bool {
+        false
+    }
 
-            if let Some(message) = &result.message {
-                println!("  {}", message.dimmed());
-            }
+    /// Run the checks. `verbose` adds informational results.
+    fn run(&self, verbose: bool) -> Vec<DiagnosticResult>;
+}
 
-            if result.status != DiagnosticStatus::Ok {
-                if result.status == DiagnosticStatus::Error {
-                    has_errors = true;
-                } else {
-                    has_warnings = true;
-                }
+/// A built-in category of checks and the function that runs it
+struct BuiltinCheck {
+    category: &'static str,
+    run: fn(bool, &mut Vec<DiagnosticResult>),
+    exclusive: bool,
+}
 
-                if let Some(solution) = &result.solution {
-                    println!("  {} {}", "→ Solution:".green(), solution);
-                }
-            }
+impl BuiltinCheck {
+    const fn new(category: &'static str, run: fn(bool, &mut Vec<DiagnosticResult>)) -> Self {
+        Self {
+            category,
+            run,
+            exclusive: false,
         }
+    }
+}
 
-        // Summary
-        println!();
-        if has_errors {
-            println!(
-                "{}",
-                "❌ Critical issues found that need to be fixed"
-                    .red()
-                    .bold()
-            );
-            println!("Please address the errors above before using MCP Helper.");
-        } else if has_warnings {
-            println!(
-                "{}",
-                "⚠️  Some warnings found but MCP should work"
-                    .yellow()
-                    .bold()
-            );
-            println!("Consider addressing the warnings for optimal performance.");
-        } else {
-            println!(
-                "{}",
-                "✅ All checks passed! MCP is ready to use".green().bold()
-            );
-        }
+impl DiagnosticCheck for BuiltinCheck {
+    fn category(&self) -> &str {
+        self.category
+    }
 
-        println!();
-        println!("For more help:");
-        println!("  • Run with --verbose for detailed output");
-        println!("  • Check documentation at https://github.com/sapientpants/mcp-helper");
-        println!("  • Report issues at https://github.com/sapientpants/mcp-helper/issues");
+    fn exclusive(&self) -> bool {
+        self.exclusive
+    }
 
-        if has_errors {
-            Err(McpError::Other(anyhow::anyhow!(
-                "Critical issues found. Please fix them before continuing."
-            )))
-        } else {
-            Ok(())
-        }
+    fn run(&self, verbose: bool) -> Vec<DiagnosticResult> {
+        let mut results = Vec::new();
+        (self.run)(verbose, &mut results);
+        results
     }
+}
 
-    fn check_nodejs(&self, results: &mut Vec<DiagnosticResult>) {
-        let checker = NodeChecker::new();
+/// Every built-in check, in [`CHECK_CATEGORIES`] order
+const CHECKS: &[BuiltinCheck] = &[
+    BuiltinCheck::new("node", check_nodejs),
+    BuiltinCheck::new("docker", check_docker),
+    BuiltinCheck::new("clients", check_clients),
+    BuiltinCheck::new("path", check_path),
+    BuiltinCheck::new("platform", check_platform_specific),
+    BuiltinCheck::new("servers", check_server_configs),
+    BuiltinCheck::new("consistency", check_consistency),
+    BuiltinCheck::new("state", check_state_files),
+    BuiltinCheck {
+        category: "antivirus",
+        run: check_antivirus,
+        exclusive: true,
+    },
+    BuiltinCheck::new("storage", check_storage),
+    BuiltinCheck::new("disk", check_disk_space),
+    BuiltinCheck::new("npm", check_npm_cache),
+];
 
-        match checker.check() {
-            Ok(check) => {
-                match check.status {
-                    crate::deps::DependencyStatus::Installed { version } => {
-                        results.push(DiagnosticResult {
-                            category: "Node.js".to_string(),
-                            check: format!(
-                                "Installation ({})",
-                                version.as_deref().unwrap_or("unknown")
-                            ),
-                            status: DiagnosticStatus::Ok,
-                            message: None,
-                            solution: None,
-                        });
+/// mcp-helper's own checks, in [`CHECK_CATEGORIES`] order
+pub fn builtin_checks() -> Vec<&'static dyn DiagnosticCheck> {
+    CHECKS
+        .iter()
+        .map(|check| check as &dyn DiagnosticCheck)
+        .collect()
+}
 
-                        // Check npm
-                        self.check_command("npm", &["--version"], "npm", results);
+/// Which checks to run and how much to report
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticFilter {
+    categories: Vec<String>,
+    verbose: bool,
+}
 
-                        // Check npx
-                        self.check_npx_command(results);
-                    }
-                    crate::deps::DependencyStatus::Missing => {
-                        results.push(DiagnosticResult {
-                            category: "Node.js".to_string(),
-                            check: "Installation".to_string(),
-                            status: DiagnosticStatus::Error,
-                            message: Some("Node.js is not installed".to_string()),
-                            solution: Some("Install Node.js from https://nodejs.org or use your package manager".to_string()),
-                        });
-                    }
-                    crate::deps::DependencyStatus::VersionMismatch {
-                        installed,
-                        required,
-                    } => {
-                        results.push(DiagnosticResult {
-                            category: "Node.js".to_string(),
-                            check: "Version".to_string(),
-                            status: DiagnosticStatus::Warning,
-                            message: Some(format!(
-                                "Version {installed} installed, {required} recommended"
-                            )),
-                            solution: Some(
-                                "Consider updating Node.js for better compatibility".to_string(),
-                            ),
-                        });
-                    }
-                    _ => {}
-                }
-            }
-            Err(e) => {
-                results.push(DiagnosticResult {
-                    category: "Node.js".to_string(),
-                    check: "Detection".to_string(),
-                    status: DiagnosticStatus::Error,
-                    message: Some(format!("Failed to check Node.js: {e}")),
-                    solution: Some("Ensure Node.js is in your PATH".to_string()),
-                });
-            }
-        }
+impl DiagnosticFilter {
+    /// Every category, without informational results
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    fn check_docker(&self, results: &mut Vec<DiagnosticResult>) {
-        let checker = DockerChecker::new();
-
-        match checker.check() {
-            Ok(check) => {
-                match check.status {
-                    crate::deps::DependencyStatus::Installed { version } => {
-                        results.push(DiagnosticResult {
-                            category: "Docker".to_string(),
-                            check: format!(
-                                "Installation ({})",
-                                version.as_deref().unwrap_or("unknown")
-                            ),
-                            status: DiagnosticStatus::Ok,
-                            message: Some(
-                                "Optional - only needed for Docker-based servers".to_string(),
-                            ),
-                            solution: None,
-                        });
-                    }
-                    crate::deps::DependencyStatus::Missing => {
-                        if self.verbose {
-                            results.push(DiagnosticResult {
-                                category: "Docker".to_string(),
-                                check: "Installation".to_string(),
-                                status: DiagnosticStatus::Warning,
-                                message: Some("Docker not installed (optional)".to_string()),
-                                solution: Some("Install Docker Desktop if you plan to use container-based servers".to_string()),
-                            });
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            Err(_) => {
-                // Docker check failed, but it's optional so we don't report an error
-            }
-        }
+    /// Restrict the checks to the given categories (see [`CHECK_CATEGORIES`]).
+    /// `nodejs` is accepted for `node`.
+    pub fn with_categories(mut self, categories: Vec<String>) -> Self {
+        self.categories = categories
+            .into_iter()
+            .map(|c| match c.trim().to_lowercase().as_str() {
+                "nodejs" | "node.js" => "node".to_string(),
+                other => other.to_string(),
+            })
+            .collect();
+        self
     }
 
-    fn check_clients(&self, results: &mut Vec<DiagnosticResult>) {
-        let clients = detect_clients();
-        let installed_clients: Vec<_> = clients
+    /// Include informational results
+    pub fn with_verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Whether checks of `category` run
+    pub fn includes(&self, category: &str) -> bool {
+        self.categories.is_empty() || self.categories.iter().any(|c| c == category)
+    }
+
+    /// Reject categories none of the built-in checks have
+    pub fn validate(&self) -> Result<(), McpError> {
+        let unknown: Vec<_> = self
+            .categories
             .iter()
-            .filter(|c| c.is_installed())
-            .map(|c| c.name())
+            .filter(|c| !CHECK_CATEGORIES.contains(&c.as_str()))
+            .cloned()
             .collect();
 
-        if installed_clients.is_empty() {
-            results.push(DiagnosticResult {
-                category: "MCP Clients".to_string(),
-                check: "Installation".to_string(),
-                status: DiagnosticStatus::Error,
-                message: Some("No MCP clients found".to_string()),
-                solution: Some(
-                    "Install Claude Desktop, VS Code, or another supported MCP client".to_string(),
-                ),
-            });
+        if unknown.is_empty() {
+            Ok(())
         } else {
-            results.push(DiagnosticResult {
-                category: "MCP Clients".to_string(),
-                check: format!("Found {} client(s)", installed_clients.len()),
-                status: DiagnosticStatus::Ok,
-                message: Some(installed_clients.join(", ")),
-                solution: None,
-            });
-
-            // Check for config file access
-            for client in &clients {
-                if client.is_installed() {
-                    let config_path = client.config_path();
-                    if config_path.exists() {
-                        // Try to read the config
-                        match client.list_servers() {
-                            Ok(_) => {
-                                if self.verbose {
-                                    results.push(DiagnosticResult {
-                                        category: "Config Access".to_string(),
-                                        check: client.name().to_string(),
-                                        status: DiagnosticStatus::Ok,
-                                        message: None,
-                                        solution: None,
-                                    });
-                                }
-                            }
-                            Err(e) => {
-                                results.push(DiagnosticResult {
-                                    category: "Config Access".to_string(),
-                                    check: client.name().to_string(),
-                                    status: DiagnosticStatus::Warning,
-                                    message: Some(format!("Cannot read config: {e}")),
-                                    solution: Some(format!(
-                                        "Check permissions on {}",
-                                        config_path.display()
-                                    )),
-                                });
-                            }
-                        }
-                    }
-                }
-            }
+            Err(McpError::Other(anyhow::anyhow!(
+                "Unknown check category: {}. Valid categories: {}",
+                unknown.join(", "),
+                CHECK_CATEGORIES.join(", ")
+            )))
         }
     }
+}
 
-    fn check_path(&self, results: &mut Vec<DiagnosticResult>) {
-        // Check if common tools are in PATH
-        let tools = vec!["node", "npm", "git"];
-        let mut missing = Vec::new();
+/// Run the built-in checks `filter` selects and return their results, in
+/// [`CHECK_CATEGORIES`] order. Categories no check has select nothing; use
+/// [`DiagnosticFilter::validate`] to reject them.
+pub fn run_diagnostics(filter: &DiagnosticFilter) -> Vec<DiagnosticResult> {
+    run_checks(&builtin_checks(), filter, |_| {})
+}
 
-        for tool in tools {
-            if which::which(tool).is_err() {
-                missing.push(tool);
-            }
-        }
+/// Run the `checks` `filter` selects, passing each category's results to `on_done`
+/// as it finishes. The returned results are in `checks` order.
+///
+/// Up to [`MAX_PARALLEL_CHECKS`] categories run at once. Checks that time how
+/// long something takes run afterwards on their own, so the others don't skew
+/// the measurement.
+pub fn run_checks<F>(
+    checks: &[&dyn DiagnosticCheck],
+    filter: &DiagnosticFilter,
+    on_done: F,
+) -> Vec<DiagnosticResult>
+where
+    F: Fn(&[DiagnosticResult]) + Sync,
+{
+    let selected: Vec<(usize, &dyn DiagnosticCheck)> = checks
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|(_, check)| filter.includes(check.category()))
+        .collect();
+    let (exclusive, parallel): (Vec<_>, Vec<_>) = selected
+        .into_iter()
+        .partition(|(_, check)| check.exclusive());
 
-        if missing.is_empty() {
-            results.push(DiagnosticResult {
-                category: "PATH".to_string(),
-                check: "Common tools".to_string(),
-                status: DiagnosticStatus::Ok,
-                message: None,
-                solution: None,
-            });
-        } else {
-            results.push(DiagnosticResult {
-                category: "PATH".to_string(),
-                check: "Common tools".to_string(),
-                status: DiagnosticStatus::Warning,
-                message: Some(format!("Missing from PATH: {}", missing.join(", "))),
-                solution: Some("Add missing tools to your PATH environment variable".to_string()),
+    let parent = tracing::Span::current();
+    let run = |check: &dyn DiagnosticCheck| {
+        let _span =
+            tracing::debug_span!(parent: &parent, "doctor_check", category = check.category())
+                .entered();
+        check.run(filter.verbose)
+    };
+
+    let finished: Mutex<Vec<Option<Vec<DiagnosticResult>>>> = Mutex::new(vec![None; checks.len()]);
+    let finish = |index: usize, results: Vec<DiagnosticResult>| {
+        // Holding the lock while reporting keeps streamed output from interleaving
+        let mut finished = finished.lock().unwrap_or_else(|e| e.into_inner());
+        on_done(&results);
+        finished[index] = Some(results);
+    };
+
+    let workers = MAX_PARALLEL_CHECKS.min(parallel.len());
+    let queue: Mutex<VecDeque<(usize, &dyn DiagnosticCheck)>> =
+        Mutex::new(parallel.into_iter().collect());
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                let Some((index, check)) = next else { break };
+                finish(index, run(check));
             });
         }
+    });
+    for (index, check) in exclusive {
+        finish(index, run(check));
     }
 
-    fn check_platform_specific(&self, results: &mut Vec<DiagnosticResult>) {
-        #[cfg(target_os = "windows")]
-        {
-            // Check for npx.cmd
-            if which::which("npx.cmd").is_err() && which::which("npx").is_err() {
-                results.push(DiagnosticResult {
-                    category: "Windows".to_string(),
-                    check: "npx.cmd availability".to_string(),
-                    status: DiagnosticStatus::Warning,
-                    message: Some("npx.cmd not found in PATH".to_string()),
-                    solution: Some(
-                        "Restart terminal after Node.js installation or run: npm install -g npx"
-                            .to_string(),
-                    ),
-                });
-            }
+    finished
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner())
+        .into_iter()
+        .flatten()
+        .flatten()
+        .collect()
+}
 
-            // Check for common Windows PATH issues
-            if let Ok(path) = std::env::var("PATH") {
-                let paths: HashSet<_> = path.split(';').collect();
+/// How many check categories run at once
+pub const MAX_PARALLEL_CHECKS: usize = 4;
 
-                // Check for npm global bin
-                let npm_paths = vec![r"npm\node_modules\.bin", r"nodejs\node_modules\npm\bin"];
+/// Category of the antivirus checks
+const ANTIVIRUS_CATEGORY: &str = "Antivirus";
 
-                let has_npm_path = npm_paths
-                    .iter()
-                    .any(|p| paths.iter().any(|path| path.contains(p)));
+/// Category of the network filesystem and sync folder checks
+const STORAGE_CATEGORY: &str = "Config Storage";
 
-                if !has_npm_path && self.verbose {
-                    results.push(DiagnosticResult {
-                        category: "Windows".to_string(),
-                        check: "npm global bin in PATH".to_string(),
-                        status: DiagnosticStatus::Warning,
-                        message: Some("npm global bin directory might not be in PATH".to_string()),
-                        solution: Some("Add %APPDATA%\\npm to your PATH".to_string()),
-                    });
-                }
-            }
-        }
+/// Category of the free space checks
+const DISK_CATEGORY: &str = "Disk Space";
 
-        #[cfg(target_os = "macos")]
-        {
-            // Check for shell profile sourcing issues
-            if let Ok(home) = std::env::var("HOME") {
-                let profiles = [
-                    format!("{home}/.zshrc"),
-                    format!("{home}/.bash_profile"),
-                    format!("{home}/.bashrc"),
-                ];
+/// Category of the npm cache checks
+const NPM_CACHE_CATEGORY: &str = "npm Cache";
 
-                let has_profile = profiles.iter().any(|p| std::path::Path::new(p).exists());
+/// Below this much free space downloads are likely to fail
+const CRITICAL_SPACE_THRESHOLD: u64 = 100 * 1024 * 1024;
 
-                if !has_profile && self.verbose {
-                    results.push(DiagnosticResult {
-                        category: "macOS".to_string(),
-                        check: "Shell profile".to_string(),
-                        status: DiagnosticStatus::Warning,
-                        message: Some("No shell profile found".to_string()),
-                        solution: Some(
-                            "Create ~/.zshrc or ~/.bash_profile for PATH configuration".to_string(),
-                        ),
-                    });
-                }
-            }
+/// Exit code when the worst result at or above `--fail-on` is a warning
+pub const EXIT_WARNINGS: i32 = 2;
+
+/// Exit code when results at or above `--fail-on` include an error
+pub const EXIT_ERRORS: i32 = 3;
+
+/// Machine-readable doctor report
+#[derive(Debug, Serialize)]
+pub struct DoctorReport {
+    pub results: Vec<DiagnosticResult>,
+    pub summary: DoctorSummary,
+    /// Fixes `--fix` applied before the checks ran
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fixes: Vec<AppliedFix>,
+}
+
+/// A change `--fix` made to a client config, or failed to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AppliedFix {
+    pub client: String,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AppliedFix {
+    fn new(client: &str, action: String, result: Result<()>) -> Self {
+        Self {
+            client: client.to_string(),
+            action,
+            error: result.err().map(|e| format!("{e:#}")),
         }
+    }
+}
 
-        #[cfg(target_os = "linux")]
-        {
-            // Check for snap vs system Node.js conflicts
-            if which::which("node").is_ok() {
-                if let Ok(output) = Command::new("which").arg("node").output() {
-                    let path = String::from_utf8_lossy(&output.stdout);
-                    if path.contains("/snap/") && self.verbose {
-                        results.push(DiagnosticResult {
-                            category: "Linux".to_string(),
-                            check: "Node.js installation".to_string(),
-                            status: DiagnosticStatus::Warning,
-                            message: Some("Node.js installed via snap".to_string()),
-                            solution: Some("Snap Node.js can have permission issues. Consider using NodeSource or nvm".to_string()),
-                        });
-                    }
-                }
-            }
+/// Diagnostics at or above the `--fail-on` threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DoctorFailure {
+    /// The worst result found
+    pub severity: DiagnosticStatus,
+    pub threshold: DiagnosticStatus,
+}
+
+impl DoctorFailure {
+    /// [`EXIT_ERRORS`] or [`EXIT_WARNINGS`], so scripts can tell them apart
+    pub fn exit_code(&self) -> i32 {
+        match self.severity {
+            DiagnosticStatus::Error => EXIT_ERRORS,
+            _ => EXIT_WARNINGS,
         }
     }
+}
 
-    fn check_server_configs(&self, results: &mut Vec<DiagnosticResult>) {
-        // Check for common server configuration issues
-        let clients = detect_clients();
-        let mut total_servers = 0;
-        let mut servers_with_issues = Vec::new();
+impl fmt::Display for DoctorFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Diagnostics found issues at or above '{}' severity. Please fix them before continuing.",
+            self.threshold
+        )
+    }
+}
 
-        for client in &clients {
-            if !client.is_installed() {
-                continue;
-            }
+impl std::error::Error for DoctorFailure {}
 
-            if let Ok(servers) = client.list_servers() {
-                total_servers += servers.len();
-
-                for (name, config) in servers {
-                    // Check for common issues
-                    if config.command.is_empty() {
-                        servers_with_issues.push(format!("{name} (empty command)"));
-                    } else if config.command == "npx" && cfg!(target_os = "windows") {
-                        // On Windows, npx might need to be npx.cmd
-                        if which::which("npx").is_err() && which::which("npx.cmd").is_ok() {
-                            servers_with_issues
-                                .push(format!("{name} (should use npx.cmd on Windows)"));
-                        }
-                    }
-                }
-            }
-        }
+/// Count of results per severity
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DoctorSummary {
+    pub ok: usize,
+    pub warning: usize,
+    pub error: usize,
+}
 
-        if total_servers > 0 {
-            if servers_with_issues.is_empty() {
-                results.push(DiagnosticResult {
-                    category: "Server Configs".to_string(),
-                    check: format!("{total_servers} server(s) configured"),
-                    status: DiagnosticStatus::Ok,
-                    message: None,
-                    solution: None,
-                });
-            } else {
-                results.push(DiagnosticResult {
-                    category: "Server Configs".to_string(),
-                    check: "Configuration issues".to_string(),
-                    status: DiagnosticStatus::Warning,
-                    message: Some(format!("Issues found: {}", servers_with_issues.join(", "))),
-                    solution: Some("Run 'mcp list' to review configurations".to_string()),
-                });
+impl DoctorReport {
+    pub fn new(results: Vec<DiagnosticResult>) -> Self {
+        let mut summary = DoctorSummary::default();
+        for result in &results {
+            match result.status {
+                DiagnosticStatus::Ok => summary.ok += 1,
+                DiagnosticStatus::Warning => summary.warning += 1,
+                DiagnosticStatus::Error => summary.error += 1,
             }
         }
+        Self {
+            results,
+            summary,
+            fixes: Vec::new(),
+        }
     }
 
-    fn check_command(
-        &self,
-        command: &str,
-        args: &[&str],
-        name: &str,
-        results: &mut Vec<DiagnosticResult>,
-    ) {
-        match Command::new(command).args(args).output() {
-            Ok(output) if output.status.success() => {
-                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                results.push(DiagnosticResult {
-                    category: name.to_string(),
-                    check: format!("Installation ({version})"),
-                    status: DiagnosticStatus::Ok,
-                    message: None,
-                    solution: None,
-                });
-            }
-            _ => {
-                results.push(DiagnosticResult {
-                    category: name.to_string(),
-                    check: "Installation".to_string(),
-                    status: DiagnosticStatus::Warning,
-                    message: Some(format!("{name} not found or not working")),
-                    solution: Some(format!("Ensure {name} is installed and in PATH")),
-                });
-            }
-        }
+    /// Highest severity present in the report
+    pub fn max_severity(&self) -> DiagnosticStatus {
+        self.results
+            .iter()
+            .map(|r| r.status)
+            .max()
+            .unwrap_or(DiagnosticStatus::Ok)
     }
 
-    fn check_npx_command(&self, results: &mut Vec<DiagnosticResult>) {
-        let npx_cmd = if cfg!(target_os = "windows") {
-            "npx.cmd"
-        } else {
-            "npx"
+    /// Whether any result meets or exceeds the given failure threshold
+    pub fn fails_at(&self, threshold: DiagnosticStatus) -> bool {
+        self.results.iter().any(|r| r.status >= threshold)
+    }
+
+    /// The failure to exit with at `fail_on`, if the report fails it
+    pub fn failure(&self, fail_on: FailOn) -> Option<DoctorFailure> {
+        let FailOn::At(threshold) = fail_on else {
+            return None;
         };
+        self.fails_at(threshold).then(|| DoctorFailure {
+            severity: self.max_severity(),
+            threshold,
+        })
+    }
+}
 
-        match Command::new(npx_cmd).arg("--version").output() {
-            Ok(output) if output.status.success() => {
-                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                results.push(DiagnosticResult {
-                    category: "npx".to_string(),
-                    check: format!("Installation ({version}) [{npx_cmd}]"),
-                    status: DiagnosticStatus::Ok,
-                    message: None,
-                    solution: None,
-                });
-            }
-            _ => {
-                results.push(DiagnosticResult {
-                    category: "npx".to_string(),
-                    check: "Installation".to_string(),
-                    status: DiagnosticStatus::Warning,
-                    message: Some(format!("{npx_cmd} not found")),
-                    solution: Some(
-                        "npx will be downloaded on first use, or run: npm install -g npx"
-                            .to_string(),
-                    ),
-                });
-            }
+/// MCP Doctor command for diagnostics and troubleshooting
+/// `mcp doctor`: runs [`run_diagnostics`] and presents the results
+pub struct DoctorCommand {
+    filter: DiagnosticFilter,
+    output: OutputFormat,
+    fail_on: FailOn,
+    interactive: bool,
+    fix: bool,
+}
+
+impl DoctorCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            filter: DiagnosticFilter::new().with_verbose(verbose),
+            output: OutputFormat::Text,
+            fail_on: FailOn::At(DiagnosticStatus::Error),
+            interactive: false,
+            fix: false,
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Set the output format (text or JSON).
+    pub fn with_output_format(mut self, output: OutputFormat) -> Self {
+        self.output = output;
+        self
+    }
 
-    #[test]
-    fn test_doctor_command_creation() {
-        let doctor = DoctorCommand::new(false);
-        assert!(!doctor.verbose);
+    /// Set the minimum severity that causes the command to fail.
+    pub fn with_fail_on(mut self, fail_on: FailOn) -> Self {
+        self.fail_on = fail_on;
+        self
+    }
 
-        let doctor = DoctorCommand::new(true);
-        assert!(doctor.verbose);
+    /// Restrict the checks to the given categories (see [`CHECK_CATEGORIES`]).
+    /// `nodejs` is accepted for `node`.
+    pub fn with_categories(mut self, only: Vec<String>) -> Self {
+        self.filter = self.filter.with_categories(only);
+        self
     }
 
-    #[test]
-    fn test_diagnostic_status() {
-        assert_ne!(DiagnosticStatus::Ok, DiagnosticStatus::Warning);
-        assert_ne!(DiagnosticStatus::Ok, DiagnosticStatus::Error);
-        assert_ne!(DiagnosticStatus::Warning, DiagnosticStatus::Error);
+    /// Offer fixes that need confirmation, such as pinning Node.js paths.
+    pub fn with_interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    /// Apply safe fixes before running the checks
+    pub fn with_fix(mut self, fix: bool) -> Self {
+        self.fix = fix;
+        self
+    }
+
+    #[tracing::instrument(name = "doctor", skip(self), fields(only = ?self.filter.categories))]
+    pub fn execute(&self) -> Result<(), McpError> {
+        self.validate_categories()?;
+        let fixes = if self.fix {
+            self.apply_safe_fixes()
+        } else {
+            Vec::new()
+        };
+
+        if self.output == OutputFormat::Text {
+            println!("{}", "🏥 MCP Doctor - System Diagnostics".blue().bold());
+            println!();
+            if self.fix {
+                print_fixes(&fixes);
+            }
+            println!("Running comprehensive system checks...");
+            println!();
+            println!("{}", "Diagnostic Results:".blue().bold());
+            println!();
+        }
+
+        let report = match self.output {
+            OutputFormat::Json => {
+                let mut report = DoctorReport::new(self.run_checks());
+                report.fixes = fixes;
+                let json = serde_json::to_string_pretty(&report).map_err(|e| {
+                    McpError::Other(anyhow::anyhow!("Failed to serialize report: {}", e))
+                })?;
+                println!("{json}");
+                report
+            }
+            OutputFormat::Text => {
+                let report = DoctorReport::new(
+                    self.run_checks_streaming(|results| results.iter().for_each(print_result)),
+                );
+                self.print_summary(&report);
+                report
+            }
+        };
+
+        if self.interactive && self.output == OutputFormat::Text && self.category_enabled("node") {
+            self.offer_node_pinning()?;
+        }
+        if self.interactive
+            && self.output == OutputFormat::Text
+            && self.category_enabled("consistency")
+        {
+            self.offer_harmonizing()?;
+        }
+        if self.interactive
+            && self.output == OutputFormat::Text
+            && cfg!(target_os = "windows")
+            && self.category_enabled("servers")
+        {
+            self.offer_npx_fixes()?;
+        }
+        if self.interactive
+            && self.output == OutputFormat::Text
+            && report
+                .results
+                .iter()
+                .any(|r| r.category == ANTIVIRUS_CATEGORY && r.status != DiagnosticStatus::Ok)
+        {
+            self.offer_antivirus_guidance()?;
+        }
+        if self.interactive
+            && self.output == OutputFormat::Text
+            && report
+                .results
+                .iter()
+                .any(|r| r.category == NPM_CACHE_CATEGORY && r.status == DiagnosticStatus::Error)
+        {
+            self.offer_npm_cache_clean()?;
+        }
+
+        match report.failure(self.fail_on) {
+            Some(failure) => Err(McpError::Other(failure.into())),
+            None => Ok(()),
+        }
+    }
+
+    /// Repair what can be repaired without losing anything or asking
+    fn apply_safe_fixes(&self) -> Vec<AppliedFix> {
+        let clients = detect_clients();
+        let installed: Vec<&dyn McpClient> = clients
+            .iter()
+            .filter(|client| client.is_installed())
+            .map(|client| client.as_ref())
+            .collect();
+
+        let mut fixes = Vec::new();
+        for client in &installed {
+            let path = client.config_path();
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            if repair::diagnose("", &content).is_none() {
+                continue;
+            }
+            // Only comments and trailing commas are safe to drop; anything else
+            // is left to `mcp config repair`
+            let Some(value) = repair::tolerant_parse(&content) else {
+                continue;
+            };
+            let result =
+                repair::apply_repair(*client, &path, &content, &RepairChoice::Tolerant(value));
+            let action = match &result {
+                Ok(kept) => format!(
+                    "Removed comments and trailing commas from {} (the original was kept as {})",
+                    path.display(),
+                    kept.display()
+                ),
+                Err(_) => format!(
+                    "Remove comments and trailing commas from {}",
+                    path.display()
+                ),
+            };
+            fixes.push(AppliedFix::new(client.name(), action, result.map(|_| ())));
+        }
+
+        if cfg!(target_os = "windows") {
+            fixes.extend(self.fix_bare_npx(&installed));
+        }
+        fixes
+    }
+
+    /// Start npx through the invocation clients find on Windows
+    fn fix_bare_npx(&self, clients: &[&dyn McpClient]) -> Vec<AppliedFix> {
+        let Some(invocation) = NpxInvocation::detect() else {
+            return Vec::new();
+        };
+        let manager = match ConfigManager::new() {
+            Ok(manager) => manager,
+            Err(e) => {
+                tracing::warn!("Config history unavailable, npx entries left as is: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut fixes = Vec::new();
+        for client in clients {
+            let Ok(servers) = client.list_servers() else {
+                continue;
+            };
+            let mut names: Vec<&String> = servers.keys().collect();
+            names.sort();
+            for name in names {
+                let config = &servers[name];
+                if !uses_bare_npx(config) {
+                    continue;
+                }
+                let Some(fixed) = invocation.rewrite(config) else {
+                    continue;
+                };
+                let result = manager.apply_config(*client, name, fixed).map(|_| ());
+                fixes.push(AppliedFix::new(
+                    client.name(),
+                    format!("Start npx for {name} through {invocation}"),
+                    result,
+                ));
+            }
+        }
+        fixes
+    }
+
+    /// Run the selected checks and return their results.
+    pub fn run_checks(&self) -> Vec<DiagnosticResult> {
+        run_diagnostics(&self.filter)
+    }
+
+    /// Run the selected checks, passing each category's results to `on_done` as it
+    /// finishes. See [`run_checks`].
+    pub fn run_checks_streaming<F>(&self, on_done: F) -> Vec<DiagnosticResult>
+    where
+        F: Fn(&[DiagnosticResult]) + Sync,
+    {
+        run_checks(&builtin_checks(), &self.filter, on_done)
+    }
+
+    fn category_enabled(&self, category: &str) -> bool {
+        self.filter.includes(category)
+    }
+
+    fn validate_categories(&self) -> Result<(), McpError> {
+        self.filter.validate()
+    }
+
+    fn print_summary(&self, report: &DoctorReport) {
+        println!();
+        match report.max_severity() {
+            DiagnosticStatus::Error => {
+                println!(
+                    "{}",
+                    "❌ Critical issues found that need to be fixed"
+                        .red()
+                        .bold()
+                );
+                println!("Please address the errors above before using MCP Helper.");
+            }
+            DiagnosticStatus::Warning => {
+                println!(
+                    "{}",
+                    "⚠️  Some warnings found but MCP should work"
+                        .yellow()
+                        .bold()
+                );
+                println!("Consider addressing the warnings for optimal performance.");
+            }
+            DiagnosticStatus::Ok => {
+                println!(
+                    "{}",
+                    "✅ All checks passed! MCP is ready to use".green().bold()
+                );
+            }
+        }
+
+        println!();
+        println!("For more help:");
+        println!("  • Run with --verbose for detailed output");
+        println!("  • Use --output json for machine-readable results");
+        println!("  • Check documentation at https://github.com/sapientpants/mcp-helper");
+        println!("  • Report issues at https://github.com/sapientpants/mcp-helper/issues");
+    }
+
+    /// Pin bare node/npm/npx commands in client configs to the terminal's installation
+    fn offer_node_pinning(&self) -> Result<(), McpError> {
+        let installs = NodeInstallReport::detect();
+        let Some(bin_dir) = installs.active_node().and_then(NodeTool::bin_dir) else {
+            return Ok(());
+        };
+        if !installs.has_conflict() {
+            return Ok(());
+        }
+
+        let clients = detect_clients();
+        let mut pending = Vec::new();
+        for client in clients.iter().filter(|c| c.is_installed()) {
+            let Ok(servers) = client.list_servers() else {
+                continue;
+            };
+            let mut names: Vec<_> = servers.keys().cloned().collect();
+            names.sort();
+            for name in names {
+                if let Some(pinned) = installs.pin(&servers[&name]) {
+                    pending.push((client, name, pinned));
+                }
+            }
+        }
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        println!();
+        println!("{}", "Pin Node.js for MCP servers".blue().bold());
+        for (client, name, pinned) in &pending {
+            println!(
+                "  • {} ({}) → {}",
+                name.cyan(),
+                client.name(),
+                pinned.command
+            );
+        }
+        let confirmed = crate::utils::prompt::Confirm::new()
+            .with_prompt(format!(
+                "Pin {} server(s) to the installation in {}?",
+                pending.len(),
+                bin_dir.display()
+            ))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            return Ok(());
+        }
+
+        for (client, name, pinned) in pending {
+            match client.add_server(&name, pinned) {
+                Ok(()) => println!("  {} Pinned {} in {}", "✓".green(), name, client.name()),
+                Err(e) => println!(
+                    "  {} Failed to pin {} in {}: {}",
+                    "✗".red(),
+                    name,
+                    client.name(),
+                    e
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy one chosen definition of each divergent server to every client
+    fn offer_harmonizing(&self) -> Result<(), McpError> {
+        let clients = detect_clients();
+        let divergent = find_divergent(&installed_definitions(&clients));
+        if divergent.is_empty() {
+            return Ok(());
+        }
+
+        let manager = ConfigManager::new().map_err(McpError::Other)?;
+        println!();
+        println!("{}", "Harmonize server definitions".blue().bold());
+        for divergence in divergent {
+            let mut items: Vec<String> = divergence
+                .variants
+                .iter()
+                .map(|v| format!("{} ({})", v.summary(), v.clients.join(", ")))
+                .collect();
+            items.push("Leave as is".to_string());
+            let choice = crate::utils::prompt::Select::new()
+                .with_prompt(format!(
+                    "Which definition of {} should every client use?",
+                    divergence.server
+                ))
+                .items(&items)
+                .default(0)
+                .interact()?;
+            let Some(canonical) = divergence.variants.get(choice) else {
+                continue;
+            };
+
+            for variant in divergence.variants.iter().filter(|v| v != &canonical) {
+                for name in &variant.clients {
+                    let Some(client) = clients.iter().find(|c| c.name() == name) else {
+                        continue;
+                    };
+                    let config = harmonized(&canonical.config, &variant.config);
+                    match manager.apply_config(client.as_ref(), &divergence.server, config) {
+                        Ok(_) => println!(
+                            "  {} Updated {} in {}",
+                            "✓".green(),
+                            divergence.server,
+                            name
+                        ),
+                        Err(e) => println!(
+                            "  {} Failed to update {} in {}: {}",
+                            "✗".red(),
+                            divergence.server,
+                            name,
+                            e
+                        ),
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rewrite entries that start a bare `npx`, which clients may fail to find on Windows
+    fn offer_npx_fixes(&self) -> Result<(), McpError> {
+        let clients = detect_clients();
+        let broken: Vec<(&dyn McpClient, String, ServerConfig)> = clients
+            .iter()
+            .filter(|client| client.is_installed())
+            .filter_map(|client| Some((client.as_ref(), client.list_servers().ok()?)))
+            .flat_map(|(client, servers)| {
+                servers
+                    .into_iter()
+                    .filter(|(_, config)| uses_bare_npx(config))
+                    .map(move |(name, config)| (client, name, config))
+            })
+            .collect();
+        if broken.is_empty() {
+            return Ok(());
+        }
+        let Some(invocation) = NpxInvocation::detect() else {
+            return Ok(());
+        };
+
+        println!();
+        let fix = crate::utils::prompt::Confirm::new()
+            .with_prompt(format!(
+                "Rewrite {} server(s) to start npx through {invocation}?",
+                broken.len()
+            ))
+            .default(true)
+            .interact()?;
+        if !fix {
+            return Ok(());
+        }
+
+        let manager = ConfigManager::new().map_err(McpError::Other)?;
+        for (client, name, config) in broken {
+            let Some(fixed) = invocation.rewrite(&config) else {
+                continue;
+            };
+            match manager.apply_config(client, &name, fixed) {
+                Ok(_) => println!("  {} Updated {} in {}", "✓".green(), name, client.name()),
+                Err(e) => println!(
+                    "  {} Failed to update {} in {}: {}",
+                    "✗".red(),
+                    name,
+                    client.name(),
+                    e
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Show how to exclude mcp-helper's directories from Defender scanning
+    fn offer_antivirus_guidance(&self) -> Result<(), McpError> {
+        println!();
+        let show = crate::utils::prompt::Confirm::new()
+            .with_prompt("Show how to exclude mcp-helper's folders from antivirus scanning?")
+            .default(true)
+            .interact()?;
+        if !show {
+            return Ok(());
+        }
+
+        let paths = antivirus::exclusion_paths();
+        println!("{}", "Antivirus exclusions".blue().bold());
+        for path in &paths {
+            println!("  • {}", path.display());
+        }
+        for (index, step) in antivirus::exclusion_guidance(&paths).iter().enumerate() {
+            println!("  {}. {}", index + 1, step);
+        }
+        Ok(())
+    }
+
+    /// Clear an npm cache that failed verification
+    fn offer_npm_cache_clean(&self) -> Result<(), McpError> {
+        println!();
+        let clean = crate::utils::prompt::Confirm::new()
+            .with_prompt(format!(
+                "Run '{}'? Packages are downloaded again on their next start",
+                npm_cache::CLEAN_COMMAND
+            ))
+            .default(true)
+            .interact()?;
+        if !clean {
+            return Ok(());
+        }
+
+        match npm_cache::clean() {
+            Ok(()) => println!("  {} Cleaned the npm cache", "✓".green()),
+            Err(e) => println!("  {} {}", "✗".red(), e),
+        }
+        Ok(())
+    }
+}
+
+fn check_nodejs(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    let checker = NodeChecker::new();
+
+    match check_responsive(&checker) {
+        Ok(check) => {
+            match check.status {
+                crate::deps::DependencyStatus::Installed { version } => {
+                    results.push(DiagnosticResult {
+                        category: "Node.js".to_string(),
+                        check: format!(
+                            "Installation ({})",
+                            version.as_deref().unwrap_or("unknown")
+                        ),
+                        status: DiagnosticStatus::Ok,
+                        message: None,
+                        solution: None,
+                    });
+
+                    // Check npm
+                    check_command("npm", &["--version"], "npm", results);
+
+                    // Check npx
+                    check_npx_command(results);
+
+                    // Check for competing installations
+                    results.extend(node_installation_results(&NodeInstallReport::detect()));
+                }
+                crate::deps::DependencyStatus::Missing => {
+                    results.push(DiagnosticResult {
+                        category: "Node.js".to_string(),
+                        check: "Installation".to_string(),
+                        status: DiagnosticStatus::Error,
+                        message: Some("Node.js is not installed".to_string()),
+                        solution: Some(
+                            "Install Node.js from https://nodejs.org or use your package manager"
+                                .to_string(),
+                        ),
+                    });
+                }
+                crate::deps::DependencyStatus::VersionMismatch {
+                    installed,
+                    required,
+                } => {
+                    results.push(DiagnosticResult {
+                        category: "Node.js".to_string(),
+                        check: "Version".to_string(),
+                        status: DiagnosticStatus::Warning,
+                        message: Some(format!(
+                            "Version {installed} installed, {required} recommended"
+                        )),
+                        solution: Some(
+                            "Consider updating Node.js for better compatibility".to_string(),
+                        ),
+                    });
+                }
+                crate::deps::DependencyStatus::Unresponsive { command, .. } => {
+                    results.push(DiagnosticResult {
+                        category: "Node.js".to_string(),
+                        check: "Responsiveness".to_string(),
+                        status: DiagnosticStatus::Warning,
+                        message: Some(format!("'{command}' did not respond")),
+                        solution: Some(unresponsive_advice(&command)),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Err(e) => {
+            results.push(DiagnosticResult {
+                category: "Node.js".to_string(),
+                check: "Detection".to_string(),
+                status: DiagnosticStatus::Error,
+                message: Some(format!("Failed to check Node.js: {e}")),
+                solution: Some("Ensure Node.js is in your PATH".to_string()),
+            });
+        }
+    }
+}
+
+fn check_docker(verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    let checker = DockerChecker::new();
+
+    match check_responsive(&checker) {
+        Ok(check) => match check.status {
+            crate::deps::DependencyStatus::Installed { version } => {
+                results.push(DiagnosticResult {
+                    category: "Docker".to_string(),
+                    check: format!("Installation ({})", version.as_deref().unwrap_or("unknown")),
+                    status: DiagnosticStatus::Ok,
+                    message: Some("Optional - only needed for Docker-based servers".to_string()),
+                    solution: None,
+                });
+            }
+            crate::deps::DependencyStatus::Missing if verbose => {
+                results.push(DiagnosticResult {
+                    category: "Docker".to_string(),
+                    check: "Installation".to_string(),
+                    status: DiagnosticStatus::Warning,
+                    message: Some("Docker not installed (optional)".to_string()),
+                    solution: Some(
+                        "Install Docker Desktop if you plan to use container-based servers"
+                            .to_string(),
+                    ),
+                });
+            }
+            crate::deps::DependencyStatus::Unresponsive { command, .. } => {
+                results.push(DiagnosticResult {
+                    category: "Docker".to_string(),
+                    check: "Daemon".to_string(),
+                    status: DiagnosticStatus::Warning,
+                    message: Some(format!("'{command}' did not respond")),
+                    solution: Some(unresponsive_advice(&command)),
+                });
+            }
+            _ => {}
+        },
+        Err(_) => {
+            // Docker check failed, but it's optional so we don't report an error
+        }
+    }
+}
+
+fn check_clients(verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    let clients = detect_clients();
+    let installed_clients: Vec<_> = clients
+        .iter()
+        .filter(|c| c.is_installed())
+        .map(|c| c.name())
+        .collect();
+
+    if installed_clients.is_empty() {
+        results.push(no_clients_result(HeadlessEnvironment::detect()));
+    } else {
+        results.push(DiagnosticResult {
+            category: "MCP Clients".to_string(),
+            check: format!("Found {} client(s)", installed_clients.len()),
+            status: DiagnosticStatus::Ok,
+            message: Some(installed_clients.join(", ")),
+            solution: None,
+        });
+
+        // Check for config file access
+        for client in &clients {
+            if client.is_installed() {
+                let config_path = client.config_path();
+                if config_path.exists() {
+                    // Try to read the config
+                    match client.list_servers() {
+                        Ok(_) => {
+                            if verbose {
+                                results.push(DiagnosticResult {
+                                    category: "Config Access".to_string(),
+                                    check: client.name().to_string(),
+                                    status: DiagnosticStatus::Ok,
+                                    message: None,
+                                    solution: None,
+                                });
+                            }
+                        }
+                        Err(e) => {
+                            results.push(DiagnosticResult {
+                                category: "Config Access".to_string(),
+                                check: client.name().to_string(),
+                                status: DiagnosticStatus::Warning,
+                                message: Some(format!("Cannot read config: {e}")),
+                                solution: Some(format!(
+                                    "Check permissions on {}; if it isn't valid JSON, run 'mcp doctor --fix' or 'mcp config repair'",
+                                    config_path.display()
+                                )),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn check_path(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    // Check if common tools are in PATH
+    let tools = vec!["node", "npm", "git"];
+    let mut missing = Vec::new();
+
+    for tool in tools {
+        if which::which(tool).is_err() {
+            missing.push(tool);
+        }
+    }
+
+    if missing.is_empty() {
+        results.push(DiagnosticResult {
+            category: "PATH".to_string(),
+            check: "Common tools".to_string(),
+            status: DiagnosticStatus::Ok,
+            message: None,
+            solution: None,
+        });
+    } else {
+        results.push(DiagnosticResult {
+            category: "PATH".to_string(),
+            check: "Common tools".to_string(),
+            status: DiagnosticStatus::Warning,
+            message: Some(format!("Missing from PATH: {}", missing.join(", "))),
+            solution: Some("Add missing tools to your PATH environment variable".to_string()),
+        });
+    }
+}
+
+fn check_platform_specific(verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    #[cfg(target_os = "windows")]
+    {
+        // Check for npx.cmd
+        if which::which("npx.cmd").is_err() && which::which("npx").is_err() {
+            results.push(DiagnosticResult {
+                category: "Windows".to_string(),
+                check: "npx.cmd availability".to_string(),
+                status: DiagnosticStatus::Warning,
+                message: Some("npx.cmd not found in PATH".to_string()),
+                solution: Some(
+                    "Restart terminal after Node.js installation or run: npm install -g npx"
+                        .to_string(),
+                ),
+            });
+        }
+
+        // Check for common Windows PATH issues
+        if let Ok(path) = std::env::var("PATH") {
+            let paths: HashSet<_> = path.split(';').collect();
+
+            // Check for npm global bin
+            let npm_paths = vec![r"npm\node_modules\.bin", r"nodejs\node_modules\npm\bin"];
+
+            let has_npm_path = npm_paths
+                .iter()
+                .any(|p| paths.iter().any(|path| path.contains(p)));
+
+            if !has_npm_path && verbose {
+                results.push(DiagnosticResult {
+                    category: "Windows".to_string(),
+                    check: "npm global bin in PATH".to_string(),
+                    status: DiagnosticStatus::Warning,
+                    message: Some("npm global bin directory might not be in PATH".to_string()),
+                    solution: Some("Add %APPDATA%\\npm to your PATH".to_string()),
+                });
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Check for shell profile sourcing issues
+        if let Ok(home) = std::env::var("HOME") {
+            let profiles = [
+                format!("{home}/.zshrc"),
+                format!("{home}/.bash_profile"),
+                format!("{home}/.bashrc"),
+            ];
+
+            let has_profile = profiles.iter().any(|p| std::path::Path::new(p).exists());
+
+            if !has_profile && verbose {
+                results.push(DiagnosticResult {
+                    category: "macOS".to_string(),
+                    check: "Shell profile".to_string(),
+                    status: DiagnosticStatus::Warning,
+                    message: Some("No shell profile found".to_string()),
+                    solution: Some(
+                        "Create ~/.zshrc or ~/.bash_profile for PATH configuration".to_string(),
+                    ),
+                });
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        // Check for snap vs system Node.js conflicts
+        if which::which("node").is_ok() {
+            if let Ok(output) = Process::new("which").arg("node").output() {
+                let path = output.stdout_lossy();
+                if path.contains("/snap/") && verbose {
+                    results.push(DiagnosticResult {
+                        category: "Linux".to_string(),
+                        check: "Node.js installation".to_string(),
+                        status: DiagnosticStatus::Warning,
+                        message: Some("Node.js installed via snap".to_string()),
+                        solution: Some("Snap Node.js can have permission issues. Consider using NodeSource or nvm".to_string()),
+                    });
+                }
+            }
+        }
+
+        if verbose && keychain::SecretStore::current() == keychain::SecretStore::File {
+            results.push(DiagnosticResult {
+                category: "Linux".to_string(),
+                check: "Secret storage".to_string(),
+                status: DiagnosticStatus::Ok,
+                message: Some(
+                    "No Secret Service found; secrets are kept in mcp-helper's encrypted secrets file"
+                        .to_string(),
+                ),
+                solution: None,
+            });
+        }
+    }
+}
+
+fn check_server_configs(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    // Check for common server configuration issues
+    let clients = detect_clients();
+    let mut total_servers = 0;
+    let mut servers_with_issues = Vec::new();
+    let mut bare_npx = Vec::new();
+
+    for client in &clients {
+        if !client.is_installed() {
+            continue;
+        }
+
+        if let Ok(servers) = client.list_servers() {
+            total_servers += servers.len();
+
+            for (name, config) in servers {
+                // Check for common issues
+                if config.command.is_empty() {
+                    servers_with_issues.push(format!("{name} (empty command)"));
+                } else if uses_bare_npx(&config) && cfg!(target_os = "windows") {
+                    bare_npx.push((client.name().to_string(), name));
+                }
+            }
+        }
+    }
+
+    if total_servers > 0 {
+        if servers_with_issues.is_empty() {
+            results.push(DiagnosticResult {
+                category: "Server Configs".to_string(),
+                check: format!("{total_servers} server(s) configured"),
+                status: DiagnosticStatus::Ok,
+                message: None,
+                solution: None,
+            });
+        } else {
+            results.push(DiagnosticResult {
+                category: "Server Configs".to_string(),
+                check: "Configuration issues".to_string(),
+                status: DiagnosticStatus::Warning,
+                message: Some(format!("Issues found: {}", servers_with_issues.join(", "))),
+                solution: Some("Run 'mcp list' to review configurations".to_string()),
+            });
+        }
+    }
+    if !bare_npx.is_empty() {
+        results.extend(npx_fallback_results(
+            &bare_npx,
+            NpxInvocation::detect().as_ref(),
+        ));
+    }
+}
+
+fn check_consistency(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    let clients = detect_clients();
+    let definitions = installed_definitions(&clients);
+    if definitions.len() < 2 {
+        return;
+    }
+    results.extend(consistency_results(&find_divergent(&definitions)));
+}
+
+fn check_command(command: &str, args: &[&str], name: &str, results: &mut Vec<DiagnosticResult>) {
+    match Process::new(command).args(args).output() {
+        Ok(output) if output.success() => {
+            let version = output.stdout_lossy().trim().to_string();
+            results.push(DiagnosticResult {
+                category: name.to_string(),
+                check: format!("Installation ({version})"),
+                status: DiagnosticStatus::Ok,
+                message: None,
+                solution: None,
+            });
+        }
+        _ => {
+            results.push(DiagnosticResult {
+                category: name.to_string(),
+                check: "Installation".to_string(),
+                status: DiagnosticStatus::Warning,
+                message: Some(format!("{name} not found or not working")),
+                solution: Some(format!("Ensure {name} is installed and in PATH")),
+            });
+        }
+    }
+}
+
+fn check_state_files(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    let mut files = crate::state::state_files();
+    files.extend(Preferences::path());
+
+    match IncidentLog::new() {
+        Ok(log) => results.extend(state_file_results(&files, &log)),
+        Err(e) => results.push(DiagnosticResult {
+            category: "State Files".to_string(),
+            check: "Incident log".to_string(),
+            status: DiagnosticStatus::Warning,
+            message: Some(e.to_string()),
+            solution: None,
+        }),
+    }
+}
+
+fn check_antivirus(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    // Real-time scanning only causes these failures on Windows
+    if !cfg!(target_os = "windows") {
+        return;
+    }
+
+    let realtime = antivirus::defender_realtime_enabled();
+    let started = Instant::now();
+    let npx_start = Process::new("npx.cmd")
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.success())
+        .map(|_| started.elapsed());
+    results.extend(antivirus_results(realtime, npx_start));
+}
+
+fn check_storage(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    let mut files: Vec<(String, PathBuf)> = detect_clients()
+        .iter()
+        .filter(|client| client.is_installed())
+        .map(|client| (format!("{} config", client.name()), client.config_path()))
+        .collect();
+    files.extend(Preferences::path().map(|path| ("mcp-helper preferences".to_string(), path)));
+    files.extend(
+        crate::state::state_files()
+            .into_iter()
+            .map(|path| ("mcp-helper state".to_string(), path)),
+    );
+
+    let located: Vec<(String, PathBuf, StorageKind)> = files
+        .into_iter()
+        .map(|(label, path)| {
+            let kind = StorageKind::detect(&path);
+            (label, path, kind)
+        })
+        .collect();
+    results.extend(storage_results(&located));
+}
+
+fn check_disk_space(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    let mut locations: Vec<(String, PathBuf)> = Vec::new();
+    locations.extend(BinaryServer::bin_directory().map(|dir| ("Binary servers".to_string(), dir)));
+    locations
+        .extend(CacheManager::default_cache_dir().map(|dir| ("Download cache".to_string(), dir)));
+    locations.extend(docker_root_dir().map(|dir| ("Docker images".to_string(), dir)));
+
+    let measured: Vec<(String, PathBuf, Option<u64>)> = locations
+        .into_iter()
+        .map(|(label, path)| {
+            let available = disk_space::available_space(&path);
+            (label, path, available)
+        })
+        .collect();
+    results.extend(disk_space_results(&measured));
+}
+
+fn check_npm_cache(_verbose: bool, results: &mut Vec<DiagnosticResult>) {
+    match npm_cache::verify() {
+        Ok(verification) => results.extend(npm_cache_results(&verification)),
+        // Missing npm is reported by the Node.js checks
+        Err(e) if is_not_found(&e) => {}
+        Err(e) => results.push(DiagnosticResult {
+            category: NPM_CACHE_CATEGORY.to_string(),
+            check: "npm cache verify".to_string(),
+            status: DiagnosticStatus::Warning,
+            message: Some(e.to_string()),
+            solution: None,
+        }),
+    }
+}
+
+fn check_npx_command(results: &mut Vec<DiagnosticResult>) {
+    let npx_cmd = if cfg!(target_os = "windows") {
+        "npx.cmd"
+    } else {
+        "npx"
+    };
+
+    match Process::new(npx_cmd).arg("--version").output() {
+        Ok(output) if output.success() => {
+            let version = output.stdout_lossy().trim().to_string();
+            results.push(DiagnosticResult {
+                category: "npx".to_string(),
+                check: format!("Installation ({version}) [{npx_cmd}]"),
+                status: DiagnosticStatus::Ok,
+                message: None,
+                solution: None,
+            });
+        }
+        _ => {
+            results.push(DiagnosticResult {
+                category: "npx".to_string(),
+                check: "Installation".to_string(),
+                status: DiagnosticStatus::Warning,
+                message: Some(format!("{npx_cmd} not found")),
+                solution: Some(
+                    "npx will be downloaded on first use, or run: npm install -g npx".to_string(),
+                ),
+            });
+        }
+    }
+}
+
+fn print_result(result: &DiagnosticResult) {
+    let status_symbol = match result.status {
+        DiagnosticStatus::Ok => "✓".green(),
+        DiagnosticStatus::Warning => "⚠".yellow(),
+        DiagnosticStatus::Error => "✗".red(),
+    };
+
+    println!(
+        "{} {} - {}",
+        status_symbol,
+        result.category.cyan(),
+        result.check
+    );
+
+    if let Some(message) = &result.message {
+        println!("  {}", message.dimmed());
+    }
+
+    if result.status != DiagnosticStatus::Ok {
+        if let Some(solution) = &result.solution {
+            println!("  {} {}", "→ Solution:".green(), solution);
+        }
+    }
+}
+
+/// Servers configured in each installed client
+fn installed_definitions(
+    clients: &[Box<dyn McpClient>],
+) -> Vec<(String, HashMap<String, ServerConfig>)> {
+    clients
+        .iter()
+        .filter(|client| client.is_installed())
+        .filter_map(|client| Some((client.name().to_string(), client.list_servers().ok()?)))
+        .collect()
+}
+
+/// One warning per server that clients define differently
+fn print_fixes(fixes: &[AppliedFix]) {
+    if fixes.is_empty() {
+        println!("{} Nothing to fix automatically", "ℹ".blue());
+        println!();
+        return;
+    }
+    println!("{}", "Fixes:".blue().bold());
+    for fix in fixes {
+        match &fix.error {
+            None => println!("  {} {}: {}", "✓".green(), fix.client, fix.action),
+            Some(error) => println!(
+                "  {} {}: {} failed: {}",
+                "✗".red(),
+                fix.client,
+                fix.action,
+                error
+            ),
+        }
+    }
+    println!();
+}
+
+pub fn consistency_results(divergent: &[Divergence]) -> Vec<DiagnosticResult> {
+    if divergent.is_empty() {
+        return vec![DiagnosticResult {
+            category: "Consistency".to_string(),
+            check: "Shared servers are defined the same in every client".to_string(),
+            status: DiagnosticStatus::Ok,
+            message: None,
+            solution: None,
+        }];
+    }
+
+    divergent
+        .iter()
+        .map(|divergence| DiagnosticResult {
+            category: "Consistency".to_string(),
+            check: format!("{} differs between clients", divergence.server),
+            status: DiagnosticStatus::Warning,
+            message: Some(
+                divergence
+                    .variants
+                    .iter()
+                    .map(|v| format!("{}: {}", v.clients.join(", "), v.summary()))
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+            solution: Some(
+                "Run 'mcp doctor --only consistency' in a terminal to pick one definition for every client"
+                    .to_string(),
+            ),
+        })
+        .collect()
+}
+
+/// Report whether antivirus scanning is likely to interfere with installs.
+///
+/// `realtime` is Defender's real-time protection state and `npx_start` how long
+/// `npx --version` took, when it ran.
+pub fn antivirus_results(
+    realtime: Option<bool>,
+    npx_start: Option<Duration>,
+) -> Vec<DiagnosticResult> {
+    let result = match npx_start.filter(|elapsed| antivirus::is_slow_start(*elapsed)) {
+        Some(elapsed) => DiagnosticResult {
+            category: ANTIVIRUS_CATEGORY.to_string(),
+            check: "Possible antivirus interference".to_string(),
+            status: DiagnosticStatus::Warning,
+            message: Some(if realtime == Some(true) {
+                format!(
+                    "npx took {}s to start with Defender real-time protection on; scanning can make MCP servers time out",
+                    elapsed.as_secs()
+                )
+            } else {
+                format!(
+                    "npx took {}s to start; an antivirus product may be scanning Node.js",
+                    elapsed.as_secs()
+                )
+            }),
+            solution: Some(
+                "Exclude mcp-helper's folders from scanning; run 'mcp doctor --only antivirus' in a terminal for the steps"
+                    .to_string(),
+            ),
+        },
+        None if realtime == Some(true) => DiagnosticResult {
+            category: ANTIVIRUS_CATEGORY.to_string(),
+            check: "Defender real-time protection is on".to_string(),
+            status: DiagnosticStatus::Ok,
+            message: Some(
+                "No slowdown detected. If downloads fail with locked files, exclude mcp-helper's folders"
+                    .to_string(),
+            ),
+            solution: None,
+        },
+        None => DiagnosticResult {
+            category: ANTIVIRUS_CATEGORY.to_string(),
+            check: "No antivirus interference detected".to_string(),
+            status: DiagnosticStatus::Ok,
+            message: None,
+            solution: None,
+        },
+    };
+    vec![result]
+}
+
+/// Report config files on network filesystems or in sync-client folders, and
+/// conflict copies left next to any config.
+pub fn storage_results(files: &[(String, PathBuf, StorageKind)]) -> Vec<DiagnosticResult> {
+    let mut results = Vec::new();
+    let mut reported = HashSet::new();
+    for (label, path, kind) in files {
+        if !kind.is_local() && reported.insert((label.clone(), kind.clone())) {
+            let solution = match kind {
+                StorageKind::Synced(provider) => format!(
+                    "Close MCP clients before {provider} syncs changes from another machine, or exclude the folder from syncing"
+                ),
+                _ => "Avoid editing the config from two machines at once".to_string(),
+            };
+            results.push(DiagnosticResult {
+                category: STORAGE_CATEGORY.to_string(),
+                check: format!("{label} is in a {kind}"),
+                status: DiagnosticStatus::Warning,
+                message: Some(format!(
+                    "{}: MCP Helper writes it in place with a backup and verifies it, since renaming over it is not reliable there",
+                    path.display()
+                )),
+                solution: Some(solution),
+            });
+        }
+
+        let copies = conflict_copies(path);
+        if !copies.is_empty() {
+            results.push(DiagnosticResult {
+                category: STORAGE_CATEGORY.to_string(),
+                check: format!("Conflict copies of {}", path.display()),
+                status: DiagnosticStatus::Warning,
+                message: Some(
+                    copies
+                        .iter()
+                        .map(|copy| copy.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                solution: Some(
+                    "A sync client kept both versions after a conflicting edit. Merge any servers you still need into the config, then delete the copies"
+                        .to_string(),
+                ),
+            });
+        }
+    }
+
+    if results.is_empty() {
+        results.push(DiagnosticResult {
+            category: STORAGE_CATEGORY.to_string(),
+            check: "Config files are on local storage".to_string(),
+            status: DiagnosticStatus::Ok,
+            message: None,
+            solution: None,
+        });
+    }
+    results
+}
+
+/// Report free space where binaries, cached downloads and Docker images are stored.
+pub fn disk_space_results(locations: &[(String, PathBuf, Option<u64>)]) -> Vec<DiagnosticResult> {
+    locations
+        .iter()
+        .map(|(label, path, available)| {
+            let Some(available) = *available else {
+                return DiagnosticResult {
+                    category: DISK_CATEGORY.to_string(),
+                    check: label.clone(),
+                    status: DiagnosticStatus::Warning,
+                    message: Some(format!("Could not determine free space for {}", path.display())),
+                    solution: None,
+                };
+            };
+            let status = if available < CRITICAL_SPACE_THRESHOLD {
+                DiagnosticStatus::Error
+            } else if available < disk_space::LOW_SPACE_THRESHOLD {
+                DiagnosticStatus::Warning
+            } else {
+                DiagnosticStatus::Ok
+            };
+            DiagnosticResult {
+                category: DISK_CATEGORY.to_string(),
+                check: format!("{label}: {} free", disk_space::format_bytes(available)),
+                status,
+                message: (status != DiagnosticStatus::Ok).then(|| path.display().to_string()),
+                solution: (status != DiagnosticStatus::Ok).then(|| {
+                    "Free up space on this volume before installing binary or Docker servers; 'mcp docker prune' removes unused MCP images"
+                        .to_string()
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Report the outcome of `npm cache verify`.
+pub fn npm_cache_results(verification: &Verification) -> Vec<DiagnosticResult> {
+    let result = match verification {
+        Verification::Healthy { removed: 0 } => DiagnosticResult {
+            category: NPM_CACHE_CATEGORY.to_string(),
+            check: "Cache verified".to_string(),
+            status: DiagnosticStatus::Ok,
+            message: None,
+            solution: None,
+        },
+        Verification::Healthy { removed } => DiagnosticResult {
+            category: NPM_CACHE_CATEGORY.to_string(),
+            check: "Cache verified".to_string(),
+            status: DiagnosticStatus::Ok,
+            message: Some(format!(
+                "Removed corrupted cache entries: {removed}. Those packages are downloaded again on their next start"
+            )),
+            solution: None,
+        },
+        Verification::Corrupted(detail) => DiagnosticResult {
+            category: NPM_CACHE_CATEGORY.to_string(),
+            check: "Cache is corrupted".to_string(),
+            status: DiagnosticStatus::Error,
+            message: Some(format!(
+                "{detail}. npx servers fail to start with EINTEGRITY errors that only show in client logs"
+            )),
+            solution: Some(format!(
+                "Run 'mcp doctor --only npm' in a terminal, or run: {}",
+                npm_cache::CLEAN_COMMAND
+            )),
+        },
+        Verification::Failed(detail) => DiagnosticResult {
+            category: NPM_CACHE_CATEGORY.to_string(),
+            check: "Cache could not be verified".to_string(),
+            status: DiagnosticStatus::Warning,
+            message: Some(detail.clone()),
+            solution: Some(
+                "Run 'npm cache verify' to see the full error; a cache with root-owned files needs its owner fixed"
+                    .to_string(),
+            ),
+        },
+    };
+    vec![result]
+}
+
+/// Report entries, as (client, server) pairs, that start a bare `npx` on Windows,
+/// with the invocation `mcp doctor` would rewrite them to.
+pub fn npx_fallback_results(
+    entries: &[(String, String)],
+    invocation: Option<&NpxInvocation>,
+) -> Vec<DiagnosticResult> {
+    entries
+        .iter()
+        .map(|(client, server)| DiagnosticResult {
+            category: "Server Configs".to_string(),
+            check: format!("{server} in {client}"),
+            status: DiagnosticStatus::Warning,
+            message: Some(
+                "Starts a bare 'npx', which the client may not find without npx.cmd's extension"
+                    .to_string(),
+            ),
+            solution: Some(match invocation {
+                Some(invocation) => {
+                    format!("Run 'mcp doctor --fix' to use {invocation} instead")
+                }
+                None => "Install Node.js so npx.cmd, npm.cmd or node is on PATH".to_string(),
+            }),
+        })
+        .collect()
+}
+
+/// Report where `node`, `npm` and `npx` come from and which `node` MCP clients use.
+pub fn node_installation_results(report: &NodeInstallReport) -> Vec<DiagnosticResult> {
+    let Some(active) = report.active_node() else {
+        return Vec::new();
+    };
+    let pin_hint = "Run 'mcp doctor' in a terminal to pin absolute paths in client configs, or remove the extra installation from PATH";
+    let mut results = Vec::new();
+
+    if report.nodes.len() > 1 {
+        results.push(DiagnosticResult {
+            category: "Node.js".to_string(),
+            check: format!("{} installations on PATH", report.nodes.len()),
+            status: DiagnosticStatus::Ok,
+            message: Some(
+                report
+                    .nodes
+                    .iter()
+                    .map(NodeTool::describe)
+                    .collect::<Vec<_>>()
+                    .join("; "),
+            ),
+            solution: None,
+        });
+    }
+
+    for tool in report.mismatched_tools() {
+        results.push(DiagnosticResult {
+            category: "Node.js".to_string(),
+            check: format!("{} belongs to a different installation", tool.name),
+            status: DiagnosticStatus::Warning,
+            message: Some(format!(
+                "node: {}; {}: {}",
+                active.describe(),
+                tool.name,
+                tool.describe()
+            )),
+            solution: Some(pin_hint.to_string()),
+        });
+    }
+
+    let (status, check, message) = match &report.client_node {
+        Some(client) if client.path == active.path => (
+            DiagnosticStatus::Ok,
+            "MCP clients use the same installation".to_string(),
+            client.describe(),
+        ),
+        Some(client) => (
+            DiagnosticStatus::Warning,
+            "MCP clients use a different installation".to_string(),
+            format!(
+                "Terminal: {}; servers launched by desktop clients: {}",
+                active.describe(),
+                client.describe()
+            ),
+        ),
+        None => (
+            DiagnosticStatus::Warning,
+            "MCP clients cannot find node".to_string(),
+            format!(
+                "{} comes from {}, which is only on PATH after your shell profile runs; desktop clients don't load it",
+                active.path.display(),
+                active.source.name()
+            ),
+        ),
+    };
+    results.push(DiagnosticResult {
+        category: "Node.js".to_string(),
+        check,
+        status,
+        message: Some(message),
+        solution: (status != DiagnosticStatus::Ok).then(|| pin_hint.to_string()),
+    });
+
+    results
+}
+
+/// Repair corrupt state files and report every open incident.
+///
+/// Corrupt files are quarantined right away, so the next command starts from defaults
+/// instead of failing on them.
+pub fn state_file_results(files: &[PathBuf], log: &IncidentLog) -> Vec<DiagnosticResult> {
+    let mut results = Vec::new();
+    for file in files {
+        match verify_state_file(file) {
+            Ok(()) => {}
+            // Without the key the file cannot be checked, but it is not corrupt
+            Err(corruption @ Corruption::Locked(_)) => results.push(DiagnosticResult {
+                category: "State Files".to_string(),
+                check: format!("{} {}", file.display(), corruption),
+                status: DiagnosticStatus::Error,
+                message: Some(format!(
+                    "The key is read from the keychain entry '{}'",
+                    state_crypto::KEY_NAME
+                )),
+                solution: Some(format!(
+                    "Make the original key available again ({}), or delete the file to start over",
+                    keychain::store_hint(state_crypto::KEY_NAME)
+                )),
+            }),
+            Err(corruption) => {
+                if let Err(e) = log.quarantine(file, &corruption.to_string()) {
+                    tracing::warn!("Failed to quarantine {}: {}", file.display(), e);
+                }
+            }
+        }
+    }
+
+    let incidents = log.open_incidents();
+    if incidents.is_empty() && results.is_empty() {
+        return vec![DiagnosticResult {
+            category: "State Files".to_string(),
+            check: format!(
+                "{} file(s) intact",
+                files.iter().filter(|f| f.exists()).count()
+            ),
+            status: DiagnosticStatus::Ok,
+            message: None,
+            solution: None,
+        }];
+    }
+
+    results.extend(incidents.into_iter().map(|incident| DiagnosticResult {
+        category: "State Files".to_string(),
+        check: format!("{} {}", incident.file.display(), incident.reason),
+        status: DiagnosticStatus::Warning,
+        message: Some(format!(
+            "Moved to {} on {}; defaults were used instead",
+            incident.quarantined_to.display(),
+            incident.detected_at.format("%Y-%m-%d %H:%M")
+        )),
+        solution: Some(format!(
+            "Inspect the quarantined copy if needed, then delete it to clear this warning: {}",
+            incident.quarantined_to.display()
+        )),
+    }));
+    results
+}
+
+/// No clients is expected in a container or CI job, so it is only a warning there
+fn no_clients_result(environment: Option<HeadlessEnvironment>) -> DiagnosticResult {
+    match environment {
+        Some(environment) => DiagnosticResult {
+            category: "MCP Clients".to_string(),
+            check: "Installation".to_string(),
+            status: DiagnosticStatus::Warning,
+            message: Some(format!("No MCP clients found ({environment})")),
+            solution: Some(environment.guidance()),
+        },
+        None => DiagnosticResult {
+            category: "MCP Clients".to_string(),
+            check: "Installation".to_string(),
+            status: DiagnosticStatus::Error,
+            message: Some("No MCP clients found".to_string()),
+            solution: Some(
+                "Install Claude Desktop, VS Code, or another supported MCP client".to_string(),
+            ),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doctor_command_creation() {
+        let doctor = DoctorCommand::new(false);
+        assert!(!doctor.filter.verbose);
+
+        let doctor = DoctorCommand::new(true);
+        assert!(doctor.filter.verbose);
+    }
+
+    #[test]
+    fn test_diagnostic_status() {
+        assert_ne!(DiagnosticStatus::Ok, DiagnosticStatus::Warning);
+        assert_ne!(DiagnosticStatus::Ok, DiagnosticStatus::Error);
+        assert_ne!(DiagnosticStatus::Warning, DiagnosticStatus::Error);
+    }
+
+    fn result(status: DiagnosticStatus) -> DiagnosticResult {
+        DiagnosticResult {
+            category: "Test".to_string(),
+            check: "check".to_string(),
+            status,
+            message: None,
+            solution: None,
+        }
+    }
+
+    #[test]
+    fn test_severity_parsing_and_ordering() {
+        assert_eq!(
+            "warning".parse::<DiagnosticStatus>(),
+            Ok(DiagnosticStatus::Warning)
+        );
+        assert_eq!(
+            "ERROR".parse::<DiagnosticStatus>(),
+            Ok(DiagnosticStatus::Error)
+        );
+        assert!("fatal".parse::<DiagnosticStatus>().is_err());
+        assert!(DiagnosticStatus::Ok < DiagnosticStatus::Warning);
+        assert!(DiagnosticStatus::Warning < DiagnosticStatus::Error);
+    }
+
+    #[test]
+    fn test_report_threshold() {
+        let report = DoctorReport::new(vec![
+            result(DiagnosticStatus::Ok),
+            result(DiagnosticStatus::Warning),
+        ]);
+        assert_eq!(
+            report.summary,
+            DoctorSummary {
+                ok: 1,
+                warning: 1,
+                error: 0
+            }
+        );
+        assert!(report.fails_at(DiagnosticStatus::Warning));
+        assert!(!report.fails_at(DiagnosticStatus::Error));
+        assert!(report.fails_at(DiagnosticStatus::Ok));
+        assert!(!DoctorReport::new(vec![]).fails_at(DiagnosticStatus::Ok));
+    }
+
+    #[test]
+    fn test_fail_on_parsing() {
+        assert_eq!("never".parse::<FailOn>(), Ok(FailOn::Never));
+        assert_eq!("ok".parse::<FailOn>(), Ok(FailOn::At(DiagnosticStatus::Ok)));
+        assert_eq!(
+            "WARN".parse::<FailOn>(),
+            Ok(FailOn::At(DiagnosticStatus::Warning))
+        );
+        assert!("fatal"
+            .parse::<FailOn>()
+            .unwrap_err()
+            .contains("Invalid severity"));
+    }
+
+    #[test]
+    fn test_failure_exit_codes() {
+        let warnings = DoctorReport::new(vec![result(DiagnosticStatus::Warning)]);
+        assert_eq!(
+            warnings
+                .failure(FailOn::At(DiagnosticStatus::Warning))
+                .unwrap()
+                .exit_code(),
+            EXIT_WARNINGS
+        );
+        assert_eq!(warnings.failure(FailOn::At(DiagnosticStatus::Error)), None);
+        assert_eq!(warnings.failure(FailOn::Never), None);
+
+        let errors = DoctorReport::new(vec![
+            result(DiagnosticStatus::Warning),
+            result(DiagnosticStatus::Error),
+        ]);
+        let failure = errors
+            .failure(FailOn::At(DiagnosticStatus::Warning))
+            .unwrap();
+        assert_eq!(failure.exit_code(), EXIT_ERRORS);
+        assert!(failure.to_string().contains("'warning'"));
+    }
+
+    #[test]
+    fn test_report_json_shape() {
+        let report = DoctorReport::new(vec![result(DiagnosticStatus::Error)]);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["results"][0]["status"], "error");
+        assert_eq!(json["summary"]["error"], 1);
+        assert!(json.get("fixes").is_none());
+    }
+
+    #[test]
+    fn test_state_file_results_repair_corruption() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let log = IncidentLog::with_path(temp_dir.path().join("state-incidents.json"));
+        let good = temp_dir.path().join("meta-servers.json");
+        let bad = temp_dir.path().join("dependency_cache.json");
+        crate::utils::state_file::write_state(&good, &serde_json::json!({ "a": 1 })).unwrap();
+
+        let results = state_file_results(&[good.clone(), bad.clone()], &log);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, DiagnosticStatus::Ok);
+
+        std::fs::write(&bad, "{ truncated").unwrap();
+        let results = state_file_results(&[good, bad.clone()], &log);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, DiagnosticStatus::Warning);
+        assert!(results[0].check.contains("dependency_cache.json"));
+        assert!(!bad.exists());
+    }
+
+    #[test]
+    fn test_antivirus_results() {
+        let slow = antivirus_results(Some(true), Some(Duration::from_secs(40)));
+        assert_eq!(slow.len(), 1);
+        assert_eq!(slow[0].status, DiagnosticStatus::Warning);
+        assert!(slow[0].message.as_deref().unwrap().contains("Defender"));
+
+        let other = antivirus_results(None, Some(Duration::from_secs(40)));
+        assert!(other[0]
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("antivirus product"));
+
+        let fast = antivirus_results(Some(true), Some(Duration::from_millis(800)));
+        assert_eq!(fast[0].status, DiagnosticStatus::Ok);
+        assert_eq!(
+            antivirus_results(None, None)[0].status,
+            DiagnosticStatus::Ok
+        );
+    }
+
+    #[test]
+    fn test_storage_results() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = temp_dir.path().join("claude_desktop_config.json");
+        std::fs::write(&config, "{}").unwrap();
+
+        let local = storage_results(&[(
+            "Claude Desktop config".to_string(),
+            config.clone(),
+            StorageKind::Local,
+        )]);
+        assert_eq!(local.len(), 1);
+        assert_eq!(local[0].status, DiagnosticStatus::Ok);
+
+        std::fs::write(
+            temp_dir.path().join("claude_desktop_config-LAPTOP.json"),
+            "{}",
+        )
+        .unwrap();
+        let synced = storage_results(&[(
+            "Claude Desktop config".to_string(),
+            config,
+            StorageKind::Synced("OneDrive".to_string()),
+        )]);
+        assert_eq!(synced.len(), 2);
+        assert!(synced[0].check.contains("OneDrive folder"));
+        assert!(synced[0].solution.as_deref().unwrap().contains("OneDrive"));
+        assert!(synced[1]
+            .message
+            .as_deref()
+            .unwrap()
+            .contains("claude_desktop_config-LAPTOP.json"));
+    }
+
+    #[test]
+    fn test_disk_space_results() {
+        let gib = 1024 * 1024 * 1024;
+        let results = disk_space_results(&[
+            (
+                "Binary servers".to_string(),
+                PathBuf::from("/home/me/.mcp/bin"),
+                Some(20 * gib),
+            ),
+            (
+                "Docker images".to_string(),
+                PathBuf::from("/var/lib/docker"),
+                Some(gib / 2),
+            ),
+            (
+                "Download cache".to_string(),
+                PathBuf::from("/home/me/.cache"),
+                Some(1024),
+            ),
+            ("Elsewhere".to_string(), PathBuf::from("/mnt"), None),
+        ]);
+        let statuses: Vec<DiagnosticStatus> = results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                DiagnosticStatus::Ok,
+                DiagnosticStatus::Warning,
+                DiagnosticStatus::Error,
+                DiagnosticStatus::Warning
+            ]
+        );
+        assert_eq!(results[0].check, "Binary servers: 20.0 GiB free");
+        assert_eq!(results[1].message.as_deref(), Some("/var/lib/docker"));
+    }
+
+    #[test]
+    fn test_npx_fallback_results() {
+        let entries = vec![("Cursor".to_string(), "github".to_string())];
+        let invocation = NpxInvocation::NpxCmd(PathBuf::from(r"C:\nodejs\npx.cmd"));
+        let results = npx_fallback_results(&entries, Some(&invocation));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].check, "github in Cursor");
+        assert_eq!(results[0].status, DiagnosticStatus::Warning);
+        assert!(results[0]
+            .solution
+            .as_deref()
+            .unwrap()
+            .contains(r"C:\nodejs\npx.cmd"));
+
+        let results = npx_fallback_results(&entries, None);
+        assert!(results[0]
+            .solution
+            .as_deref()
+            .unwrap()
+            .starts_with("Install Node.js"));
+    }
+
+    #[test]
+    fn test_npm_cache_results() {
+        let healthy = npm_cache_results(&Verification::Healthy { removed: 0 });
+        assert_eq!(healthy[0].status, DiagnosticStatus::Ok);
+        assert!(healthy[0].message.is_none());
+
+        let repaired = npm_cache_results(&Verification::Healthy { removed: 2 });
+        assert_eq!(repaired[0].status, DiagnosticStatus::Ok);
+        assert!(repaired[0].message.as_ref().unwrap().contains(": 2"));
+
+        let corrupted = npm_cache_results(&Verification::Corrupted(
+            "npm ERR! code EINTEGRITY".to_string(),
+        ));
+        assert_eq!(corrupted[0].category, NPM_CACHE_CATEGORY);
+        assert_eq!(corrupted[0].status, DiagnosticStatus::Error);
+        assert!(corrupted[0]
+            .solution
+            .as_ref()
+            .unwrap()
+            .contains(npm_cache::CLEAN_COMMAND));
+
+        let failed = npm_cache_results(&Verification::Failed("npm ERR! code EACCES".to_string()));
+        assert_eq!(failed[0].status, DiagnosticStatus::Warning);
+    }
+
+    #[test]
+    fn test_consistency_results() {
+        use crate::config::consistency::Variant;
+
+        let ok = consistency_results(&[]);
+        assert_eq!(ok.len(), 1);
+        assert_eq!(ok[0].status, DiagnosticStatus::Ok);
+
+        let variant = |clients: &[&str], package: &str| Variant {
+            clients: clients.iter().map(|c| c.to_string()).collect(),
+            config: ServerConfig {
+                command: "npx".to_string(),
+                args: vec!["-y".to_string(), package.to_string()],
+                env: HashMap::new(),
+                cwd: None,
+            },
+        };
+        let divergent = vec![Divergence {
+            server: "github".to_string(),
+            variants: vec![
+                variant(&["Cursor", "VS Code"], "server-github@2.0.0"),
+                variant(&["Claude Desktop"], "server-github@1.0.0"),
+            ],
+        }];
+        let results = consistency_results(&divergent);
+        assert_eq!(results[0].status, DiagnosticStatus::Warning);
+        assert_eq!(results[0].check, "github differs between clients");
+        assert_eq!(
+            results[0].message.as_deref(),
+            Some(
+                "Cursor, VS Code: npx server-github@2.0.0; Claude Desktop: npx server-github@1.0.0"
+            )
+        );
+    }
+
+    #[test]
+    fn test_category_filter() {
+        let doctor = DoctorCommand::new(false).with_categories(vec!["PATH".to_string()]);
+        assert!(doctor.category_enabled("path"));
+        assert!(!doctor.category_enabled("docker"));
+        assert!(doctor.validate_categories().is_ok());
+
+        let doctor = DoctorCommand::new(false).with_categories(vec!["bogus".to_string()]);
+        assert!(doctor.validate_categories().is_err());
+
+        let doctor = DoctorCommand::new(false).with_categories(vec!["NodeJS".to_string()]);
+        assert!(doctor.category_enabled("node"));
+        assert!(doctor.validate_categories().is_ok());
+    }
+
+    #[test]
+    fn test_checks_match_categories() {
+        let categories: Vec<&str> = builtin_checks()
+            .iter()
+            .map(|check| check.category())
+            .collect();
+        assert_eq!(categories, CHECK_CATEGORIES);
+    }
+
+    #[test]
+    fn test_run_checks_streams_each_category() {
+        let doctor = DoctorCommand::new(false)
+            .with_categories(vec!["path".to_string(), "platform".to_string()]);
+        let streamed = Mutex::new(Vec::new());
+        let results = doctor.run_checks_streaming(|results| {
+            streamed
+                .lock()
+                .unwrap()
+                .extend(results.iter().map(|r| r.category.clone()))
+        });
+
+        let mut streamed = streamed.into_inner().unwrap();
+        let mut returned: Vec<String> = results.into_iter().map(|r| r.category).collect();
+        streamed.sort();
+        returned.sort();
+        assert_eq!(streamed, returned);
+        assert!(returned.contains(&"PATH".to_string()));
+    }
+
+    struct FixedCheck(&'static str, DiagnosticStatus);
+
+    impl DiagnosticCheck for FixedCheck {
+        fn category(&self) -> &str {
+            self.0
+        }
+
+        fn run(&self, verbose: bool) -> Vec<DiagnosticResult> {
+            let mut fixed = result(self.1);
+            fixed.category = self.0.to_string();
+            fixed.message = verbose.then(|| "details".to_string());
+            vec![fixed]
+        }
+    }
+
+    #[test]
+    fn test_run_custom_checks() {
+        let first = FixedCheck("first", DiagnosticStatus::Ok);
+        let second = FixedCheck("second", DiagnosticStatus::Error);
+        let checks: Vec<&dyn DiagnosticCheck> = vec![&first, &second];
+
+        let results = run_checks(&checks, &DiagnosticFilter::new(), |_| {});
+        let categories: Vec<&str> = results.iter().map(|r| r.category.as_str()).collect();
+        assert_eq!(categories, vec!["first", "second"]);
+        assert!(results.iter().all(|r| r.message.is_none()));
+
+        let filter = DiagnosticFilter::new()
+            .with_categories(vec!["second".to_string()])
+            .with_verbose(true);
+        let results = run_checks(&checks, &filter, |_| {});
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, DiagnosticStatus::Error);
+        assert_eq!(results[0].message.as_deref(), Some("details"));
+        assert!(filter.validate().is_err());
+    }
+
+    #[test]
+    fn test_node_installation_results() {
+        use crate::deps::NodeSource;
+
+        let tool = |name: &str, path: &str, source| NodeTool {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+            version: Some("v20.11.0".to_string()),
+            source,
+        };
+        let nvm_node = tool("node", "/home/me/.nvm/v20/bin/node", NodeSource::Nvm);
+        let report = NodeInstallReport {
+            nodes: vec![
+                nvm_node.clone(),
+                tool("node", "/usr/bin/node", NodeSource::System),
+            ],
+            npm: Some(tool("npm", "/usr/bin/npm", NodeSource::System)),
+            npx: Some(tool("npx", "/home/me/.nvm/v20/bin/npx", NodeSource::Nvm)),
+            client_node: Some(tool("node", "/usr/bin/node", NodeSource::System)),
+            client_path: "/usr/bin".into(),
+        };
+
+        let results = node_installation_results(&report);
+        let checks: Vec<_> = results.iter().map(|r| r.check.as_str()).collect();
+        assert_eq!(
+            checks,
+            [
+                "2 installations on PATH",
+                "npm belongs to a different installation",
+                "MCP clients use a different installation",
+            ]
+        );
+        assert_eq!(results[1].status, DiagnosticStatus::Warning);
+        assert!(results[2]
+            .message
+            .as_ref()
+            .unwrap()
+            .contains("/usr/bin/node"));
+
+        let consistent = NodeInstallReport {
+            nodes: vec![nvm_node.clone()],
+            client_node: Some(nvm_node),
+            ..Default::default()
+        };
+        let results = node_installation_results(&consistent);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, DiagnosticStatus::Ok);
+    }
+
+    #[test]
+    fn test_no_clients_is_a_warning_when_headless() {
+        use crate::utils::headless::HeadlessKind;
+
+        assert_eq!(no_clients_result(None).status, DiagnosticStatus::Error);
+
+        let result = no_clients_result(Some(HeadlessEnvironment {
+            kind: HeadlessKind::Container,
+            name: "a devcontainer".to_string(),
+        }));
+        assert_eq!(result.status, DiagnosticStatus::Warning);
+        assert!(result.message.unwrap().contains("devcontainer"));
+        assert!(result.solution.unwrap().contains("--manifest-out"));
     }
 }
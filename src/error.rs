@@ -26,8 +26,11 @@ use std::fmt;
 use crate::deps::InstallInstructions;
 
 pub mod builder;
+pub mod catalog;
 pub use builder::ErrorBuilder;
 
+use catalog::tr;
+
 /// Comprehensive error type for MCP Helper operations.
 ///
 /// Each error variant provides specific context and actionable guidance to help users
@@ -275,11 +278,11 @@ impl McpError {
         required_version: &Option<String>,
         install_instructions: &InstallInstructions,
     ) -> fmt::Result {
-        write_error_header(f, "Missing dependency", dependency)?;
+        write_error_header(f, tr("Missing dependency"), dependency)?;
         if let Some(version) = required_version {
-            write_detail(f, "Required version", version)?;
+            write_detail(f, tr("Required version"), version)?;
         }
-        write_section_header(f, "How to install:")?;
+        write_section_header(f, tr("How to install:"))?;
         format_install_instructions(f, install_instructions)
     }
 
@@ -290,10 +293,10 @@ impl McpError {
         required_version: &str,
         upgrade_instructions: &InstallInstructions,
     ) -> fmt::Result {
-        write_error_header(f, "Version mismatch for", dependency)?;
-        write_detail(f, "Current version", current_version)?;
-        write_detail(f, "Required version", required_version)?;
-        write_section_header(f, "How to upgrade:")?;
+        write_error_header(f, tr("Version mismatch for"), dependency)?;
+        write_detail(f, tr("Current version"), current_version)?;
+        write_detail(f, tr("Required version"), required_version)?;
+        write_section_header(f, tr("How to upgrade:"))?;
         format_install_instructions(f, upgrade_instructions)
     }
 
@@ -446,7 +449,7 @@ fn format_install_instructions(
         writeln!(f, "  {} {}", "•".blue(), method.name.bold())?;
         writeln!(f, "    {} {}", "$".cyan(), method.command)?;
         if let Some(desc) = &method.description {
-            writeln!(f, "    {} {}", "Note:".yellow(), desc)?;
+            writeln!(f, "    {} {}", tr("Note:").yellow(), tr(desc))?;
         }
     }
     Ok(())
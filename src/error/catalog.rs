@@ -0,0 +1,465 @@
+//! Translations for dependency install guidance.
+//!
+//! Messages are keyed by their English text, so untranslated strings (and anything
+//! produced outside this catalog) simply display as written. The locale comes from
+//! `MCP_HELPER_LANG`, then the usual `LC_ALL`, `LC_MESSAGES` and `LANG` variables.
+//! Any of them may hold a colon-separated preference list such as `ja:de`; each
+//! entry is tried in turn before falling back to English.
+
+use std::sync::OnceLock;
+
+/// Environment variables consulted for the display locale, in priority order
+const LOCALE_ENV_VARS: &[&str] = &["MCP_HELPER_LANG", "LC_ALL", "LC_MESSAGES", "LANG"];
+
+/// Languages with a catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    En,
+    Es,
+    De,
+    Ja,
+}
+
+impl Locale {
+    /// Parse a POSIX or BCP 47 tag such as `de_DE.UTF-8`, `es-MX` or `ja`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Self::En),
+            "es" => Some(Self::Es),
+            "de" => Some(Self::De),
+            "ja" => Some(Self::Ja),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Es => "es",
+            Self::De => "de",
+            Self::Ja => "ja",
+        }
+    }
+}
+
+/// Build a fallback chain from a locale setting; always ends in English
+pub fn locale_chain(setting: &str) -> Vec<Locale> {
+    let mut chain: Vec<Locale> = Vec::new();
+    for locale in setting.split(':').filter_map(Locale::from_tag) {
+        if !chain.contains(&locale) {
+            chain.push(locale);
+        }
+    }
+    if !chain.contains(&Locale::En) {
+        chain.push(Locale::En);
+    }
+    chain
+}
+
+/// The fallback chain for this process, detected once from the environment
+pub fn current_locales() -> &'static [Locale] {
+    static LOCALES: OnceLock<Vec<Locale>> = OnceLock::new();
+    LOCALES.get_or_init(|| {
+        let setting = LOCALE_ENV_VARS
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.trim().is_empty())
+            .unwrap_or_default();
+        locale_chain(&setting)
+    })
+}
+
+/// Translate `message` for the current locale
+pub fn tr(message: &str) -> &str {
+    translate(message, current_locales())
+}
+
+/// Translate `message` using the first locale in `chain` that has a translation
+pub fn translate<'a>(message: &'a str, chain: &[Locale]) -> &'a str {
+    let Some(entry) = CATALOG.iter().find(|entry| entry.en == message) else {
+        return message;
+    };
+
+    for locale in chain {
+        let translated = match locale {
+            Locale::En => return message,
+            Locale::Es => entry.es,
+            Locale::De => entry.de,
+            Locale::Ja => entry.ja,
+        };
+        if !translated.is_empty() {
+            return translated;
+        }
+    }
+    message
+}
+
+struct Entry {
+    en: &'static str,
+    es: &'static str,
+    de: &'static str,
+    ja: &'static str,
+}
+
+const fn entry(en: &'static str, es: &'static str, de: &'static str, ja: &'static str) -> Entry {
+    Entry { en, es, de, ja }
+}
+
+/// Names such as "Debian/Ubuntu" or "Arch Linux" read the same in every language
+/// and are left out.
+const CATALOG: &[Entry] = &[
+    // Numbered prompts for terminals without arrow keys
+    entry(
+        "Enter a number",
+        "Introduce un número",
+        "Nummer eingeben",
+        "番号を入力してください",
+    ),
+    entry(
+        "Enter numbers separated by commas, 'all' or 'none'; blank keeps the marked ones",
+        "Introduce números separados por comas, 'all' o 'none'; en blanco se mantienen los marcados",
+        "Nummern durch Kommas getrennt, 'all' oder 'none' eingeben; leer lässt die markierten",
+        "番号をカンマ区切りで入力するか、'all' または 'none' を入力してください（空欄で印の付いた項目のまま）",
+    ),
+    entry(
+        "Please answer y or n",
+        "Responde s (sí) o n (no)",
+        "Bitte mit j (ja) oder n (nein) antworten",
+        "y（はい）または n（いいえ）で答えてください",
+    ),
+    entry(
+        "Not a valid choice",
+        "No es una opción válida",
+        "Keine gültige Auswahl",
+        "無効な選択です",
+    ),
+    // Error and remediation text
+    entry(
+        "Missing dependency",
+        "Falta una dependencia",
+        "Fehlende Abhängigkeit",
+        "依存関係が見つかりません",
+    ),
+    entry(
+        "Version mismatch for",
+        "Versión incompatible de",
+        "Versionskonflikt bei",
+        "バージョンが一致しません",
+    ),
+    entry(
+        "Required version",
+        "Versión requerida",
+        "Erforderliche Version",
+        "必要なバージョン",
+    ),
+    entry(
+        "Current version",
+        "Versión actual",
+        "Aktuelle Version",
+        "現在のバージョン",
+    ),
+    entry(
+        "How to install:",
+        "Cómo instalar:",
+        "Installation:",
+        "インストール方法:",
+    ),
+    entry(
+        "How to upgrade:",
+        "Cómo actualizar:",
+        "Aktualisierung:",
+        "アップグレード方法:",
+    ),
+    entry("Note:", "Nota:", "Hinweis:", "注:"),
+    entry(
+        "{dependency} is not installed",
+        "{dependency} no está instalado",
+        "{dependency} ist nicht installiert",
+        "{dependency} がインストールされていません",
+    ),
+    entry(
+        "Node.js is required for npm-based MCP servers.",
+        "Node.js es necesario para los servidores MCP basados en npm.",
+        "Node.js wird für npm-basierte MCP-Server benötigt.",
+        "npm ベースの MCP サーバーには Node.js が必要です。",
+    ),
+    entry(
+        "To install Node.js:",
+        "Para instalar Node.js:",
+        "So installieren Sie Node.js:",
+        "Node.js のインストール方法:",
+    ),
+    // Install method descriptions
+    entry(
+        "Windows Package Manager (recommended)",
+        "Administrador de paquetes de Windows (recomendado)",
+        "Windows-Paketmanager (empfohlen)",
+        "Windows パッケージ マネージャー（推奨）",
+    ),
+    entry(
+        "Install via Windows Package Manager",
+        "Instalar con el administrador de paquetes de Windows",
+        "Installation über den Windows-Paketmanager",
+        "Windows パッケージ マネージャーでインストール",
+    ),
+    entry(
+        "Chocolatey package manager",
+        "Administrador de paquetes Chocolatey",
+        "Chocolatey-Paketmanager",
+        "Chocolatey パッケージマネージャー",
+    ),
+    entry(
+        "Homebrew package manager (recommended)",
+        "Administrador de paquetes Homebrew (recomendado)",
+        "Homebrew-Paketmanager (empfohlen)",
+        "Homebrew パッケージマネージャー（推奨）",
+    ),
+    entry(
+        "Homebrew package manager",
+        "Administrador de paquetes Homebrew",
+        "Homebrew-Paketmanager",
+        "Homebrew パッケージマネージャー",
+    ),
+    entry(
+        "Install via Homebrew",
+        "Instalar con Homebrew",
+        "Installation über Homebrew",
+        "Homebrew でインストール",
+    ),
+    entry(
+        "MacPorts package manager",
+        "Administrador de paquetes MacPorts",
+        "MacPorts-Paketmanager",
+        "MacPorts パッケージマネージャー",
+    ),
+    entry(
+        "Direct download from nodejs.org",
+        "Descarga directa desde nodejs.org",
+        "Direkter Download von nodejs.org",
+        "nodejs.org から直接ダウンロード",
+    ),
+    entry(
+        "Direct download from python.org",
+        "Descarga directa desde python.org",
+        "Direkter Download von python.org",
+        "python.org から直接ダウンロード",
+    ),
+    entry(
+        "Direct download from git-scm.com",
+        "Descarga directa desde git-scm.com",
+        "Direkter Download von git-scm.com",
+        "git-scm.com から直接ダウンロード",
+    ),
+    entry(
+        "Download rustup-init.exe from rustup.rs",
+        "Descargue rustup-init.exe desde rustup.rs",
+        "rustup-init.exe von rustup.rs herunterladen",
+        "rustup.rs から rustup-init.exe をダウンロード",
+    ),
+    entry(
+        "Node Version Manager",
+        "Administrador de versiones de Node",
+        "Node-Versionsverwaltung",
+        "Node バージョンマネージャー",
+    ),
+    entry(
+        "Python version manager",
+        "Administrador de versiones de Python",
+        "Python-Versionsverwaltung",
+        "Python バージョンマネージャー",
+    ),
+    entry(
+        "Official rustup installer (recommended)",
+        "Instalador oficial de rustup (recomendado)",
+        "Offizielles rustup-Installationsprogramm (empfohlen)",
+        "公式 rustup インストーラー（推奨）",
+    ),
+    entry(
+        "Official install script (recommended)",
+        "Script de instalación oficial (recomendado)",
+        "Offizielles Installationsskript (empfohlen)",
+        "公式インストールスクリプト（推奨）",
+    ),
+    entry(
+        "Official install script",
+        "Script de instalación oficial",
+        "Offizielles Installationsskript",
+        "公式インストールスクリプト",
+    ),
+    entry(
+        "Direct download from dotnet.microsoft.com",
+        "Descarga directa desde dotnet.microsoft.com",
+        "Direkter Download von dotnet.microsoft.com",
+        "dotnet.microsoft.com から直接ダウンロード",
+    ),
+    entry(
+        "Direct download from adoptium.net",
+        "Descarga directa desde adoptium.net",
+        "Direkter Download von adoptium.net",
+        "adoptium.net から直接ダウンロード",
+    ),
+    entry(
+        "Temurin via SDKMAN! (recommended)",
+        "Temurin con SDKMAN! (recomendado)",
+        "Temurin über SDKMAN! (empfohlen)",
+        "SDKMAN! で Temurin をインストール（推奨）",
+    ),
+    entry(
+        "Temurin via SDKMAN!",
+        "Temurin con SDKMAN!",
+        "Temurin über SDKMAN!",
+        "SDKMAN! で Temurin をインストール",
+    ),
+    entry(
+        "Debian/Ubuntu, after adding the Adoptium repository",
+        "Debian/Ubuntu, tras añadir el repositorio de Adoptium",
+        "Debian/Ubuntu, nach Hinzufügen des Adoptium-Repositorys",
+        "Debian/Ubuntu（Adoptium リポジトリの追加後）",
+    ),
+    entry(
+        "Debian/Ubuntu (recommended)",
+        "Debian/Ubuntu (recomendado)",
+        "Debian/Ubuntu (empfohlen)",
+        "Debian/Ubuntu（推奨）",
+    ),
+    entry(
+        "Debian/Ubuntu system package",
+        "Paquete del sistema de Debian/Ubuntu",
+        "Systempaket für Debian/Ubuntu",
+        "Debian/Ubuntu のシステムパッケージ",
+    ),
+    entry(
+        "Fedora/RHEL system package",
+        "Paquete del sistema de Fedora/RHEL",
+        "Systempaket für Fedora/RHEL",
+        "Fedora/RHEL のシステムパッケージ",
+    ),
+    entry(
+        "Snap package",
+        "Paquete Snap",
+        "Snap-Paket",
+        "Snap パッケージ",
+    ),
+    entry(
+        "Install via Snap",
+        "Instalar con Snap",
+        "Installation über Snap",
+        "Snap でインストール",
+    ),
+    entry(
+        "Docker Desktop for Windows (recommended)",
+        "Docker Desktop para Windows (recomendado)",
+        "Docker Desktop für Windows (empfohlen)",
+        "Docker Desktop for Windows（推奨）",
+    ),
+    entry(
+        "Docker Desktop for Mac (recommended)",
+        "Docker Desktop para Mac (recomendado)",
+        "Docker Desktop für Mac (empfohlen)",
+        "Docker Desktop for Mac（推奨）",
+    ),
+    entry(
+        "Docker Community Edition (recommended)",
+        "Docker Community Edition (recomendado)",
+        "Docker Community Edition (empfohlen)",
+        "Docker Community Edition（推奨）",
+    ),
+    entry(
+        "Xcode Command Line Tools (includes Git)",
+        "Herramientas de línea de comandos de Xcode (incluye Git)",
+        "Xcode-Befehlszeilentools (enthält Git)",
+        "Xcode コマンドラインツール（Git を含む）",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deps::{get_install_instructions, Dependency};
+
+    #[test]
+    fn test_locale_from_tag() {
+        assert_eq!(Locale::from_tag("de_DE.UTF-8"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("es-MX"), Some(Locale::Es));
+        assert_eq!(Locale::from_tag("ja"), Some(Locale::Ja));
+        assert_eq!(Locale::from_tag("C.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("fr_FR"), None);
+    }
+
+    #[test]
+    fn test_locale_chain_falls_back_to_english() {
+        assert_eq!(locale_chain("fr_FR:de"), vec![Locale::De, Locale::En]);
+        assert_eq!(locale_chain("ja:ja_JP"), vec![Locale::Ja, Locale::En]);
+        assert_eq!(locale_chain(""), vec![Locale::En]);
+        assert_eq!(locale_chain("en_GB:es"), vec![Locale::En, Locale::Es]);
+    }
+
+    #[test]
+    fn test_translate() {
+        let chain = locale_chain("de_DE.UTF-8");
+        assert_eq!(translate("How to install:", &chain), "Installation:");
+        assert_eq!(
+            translate("Install via Homebrew", &locale_chain("es")),
+            "Instalar con Homebrew"
+        );
+        assert_eq!(
+            translate("Missing dependency", &locale_chain("ja")),
+            "依存関係が見つかりません"
+        );
+
+        // English first in the chain wins, unknown messages pass through
+        assert_eq!(
+            translate("How to install:", &locale_chain("en:de")),
+            "How to install:"
+        );
+        assert_eq!(translate("Arch Linux", &chain), "Arch Linux");
+    }
+
+    #[test]
+    fn test_install_descriptions_are_translated() {
+        let dependencies = [
+            Dependency::NodeJs { min_version: None },
+            Dependency::Python { min_version: None },
+            Dependency::Docker {
+                min_version: None,
+                requires_compose: false,
+            },
+            Dependency::Git,
+            Dependency::Rust { min_version: None },
+            Dependency::Deno { min_version: None },
+            Dependency::Java { min_version: None },
+            Dependency::Dotnet { min_version: None },
+        ];
+        let untranslated = ["Debian/Ubuntu", "Fedora/RHEL", "Arch Linux"];
+
+        for dependency in &dependencies {
+            let instructions = get_install_instructions(dependency);
+            let methods = instructions
+                .windows
+                .iter()
+                .chain(&instructions.macos)
+                .chain(&instructions.linux);
+            for method in methods {
+                let description = method.description.as_deref().unwrap_or_default();
+                if untranslated.contains(&description) {
+                    continue;
+                }
+                for locale in [Locale::Es, Locale::De, Locale::Ja] {
+                    assert_ne!(
+                        translate(description, &[locale]),
+                        description,
+                        "missing {} translation for '{}'",
+                        locale.code(),
+                        description
+                    );
+                }
+            }
+        }
+    }
+}
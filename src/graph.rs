@@ -0,0 +1,386 @@
+//! Diagrams of which servers each client runs and what those servers need.
+//!
+//! `mcp graph` reads every installed client's configuration and prints a graph
+//! with three kinds of node: clients, servers and runtimes. An edge runs from a
+//! client to each server it is configured with, and from each server to the
+//! runtime its command needs (Node.js for `npx`, Docker for `docker`, and so on).
+//! The output is Graphviz DOT or a Mermaid flowchart, ready to paste into docs.
+
+use colored::Colorize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::client::{detect_clients, ServerConfig};
+use crate::error::McpError;
+use crate::remote::RemoteServerConfig;
+use crate::utils::paths::program_name;
+
+/// Output format for `mcp graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    Mermaid,
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dot" | "graphviz" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            other => Err(format!(
+                "Invalid graph format '{other}'. Expected one of: dot, mermaid"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Dot => write!(f, "dot"),
+            Self::Mermaid => write!(f, "mermaid"),
+        }
+    }
+}
+
+/// The runtime a server command depends on
+pub fn runtime_for(config: &ServerConfig) -> String {
+    if let Some(remote) = RemoteServerConfig::from_bridge_config(config) {
+        return remote_runtime(&remote.url);
+    }
+
+    let mut command = program_name(&config.command);
+    // `cmd /c npx ...` from a command template runs the program after /c
+    if command == "cmd" {
+        if let Some(program) = config
+            .args
+            .iter()
+            .skip_while(|arg| !arg.eq_ignore_ascii_case("/c"))
+            .nth(1)
+        {
+            command = program_name(program);
+        }
+    }
+
+    match command.as_str() {
+        "npx" | "npm" | "node" | "pnpm" | "yarn" | "bunx" | "bun" => "Node.js".to_string(),
+        "uvx" | "uv" | "python" | "python3" | "py" | "pip" | "pipx" => "Python".to_string(),
+        "docker" | "podman" => "Docker".to_string(),
+        "cargo" => "Rust".to_string(),
+        "deno" => "Deno".to_string(),
+        "java" => "Java".to_string(),
+        "" => "unknown".to_string(),
+        _ => "binary".to_string(),
+    }
+}
+
+/// The runtime node for a remote server: the host it lives on
+fn remote_runtime(url: &str) -> String {
+    let host = url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .unwrap_or_else(|| url.to_string());
+    format!("HTTP ({host})")
+}
+
+/// Clients, servers and runtimes, with the edges between them
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DependencyGraph {
+    /// Client name to the servers it is configured with
+    clients: BTreeMap<String, BTreeSet<String>>,
+    /// Server name to the runtimes it needs across all clients
+    servers: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl DependencyGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a client, even one with no servers
+    pub fn add_client(&mut self, client: &str) {
+        self.clients.entry(client.to_string()).or_default();
+    }
+
+    /// Record that `client` runs `server` on `runtime`
+    pub fn add_server(&mut self, client: &str, server: &str, runtime: &str) {
+        self.clients
+            .entry(client.to_string())
+            .or_default()
+            .insert(server.to_string());
+        self.servers
+            .entry(server.to_string())
+            .or_default()
+            .insert(runtime.to_string());
+    }
+
+    /// Build the graph from every installed client
+    pub fn from_installed_clients() -> Self {
+        let mut graph = Self::new();
+        for client in detect_clients() {
+            if !client.is_installed() {
+                continue;
+            }
+            let name = client.name();
+            graph.add_client(name);
+
+            match client.list_servers() {
+                Ok(servers) => {
+                    for (server, config) in &servers {
+                        graph.add_server(name, server, &runtime_for(config));
+                    }
+                }
+                Err(e) => tracing::warn!("Skipping servers of {}: {}", name, e),
+            }
+            if let Ok(remotes) = client.list_remote_servers() {
+                for (server, remote) in &remotes {
+                    graph.add_server(name, server, &remote_runtime(&remote.url));
+                }
+            }
+        }
+        graph
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    fn runtimes(&self) -> BTreeSet<&str> {
+        self.servers
+            .values()
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Graphviz DOT, laid out left to right
+    pub fn to_dot(&self) -> String {
+        let mut out =
+            String::from("digraph mcp {\n    rankdir=LR;\n    node [fontname=\"Helvetica\"];\n\n");
+
+        for client in self.clients.keys() {
+            out.push_str(&format!(
+                "    {} [label={}, shape=box, style=filled, fillcolor=\"#dbeafe\"];\n",
+                node_id("client", client),
+                dot_string(client)
+            ));
+        }
+        for server in self.servers.keys() {
+            out.push_str(&format!(
+                "    {} [label={}, shape=ellipse];\n",
+                node_id("server", server),
+                dot_string(server)
+            ));
+        }
+        for runtime in self.runtimes() {
+            out.push_str(&format!(
+                "    {} [label={}, shape=hexagon, style=filled, fillcolor=\"#fef3c7\"];\n",
+                node_id("runtime", runtime),
+                dot_string(runtime)
+            ));
+        }
+
+        out.push('\n');
+        for (client, servers) in &self.clients {
+            for server in servers {
+                out.push_str(&format!(
+                    "    {} -> {};\n",
+                    node_id("client", client),
+                    node_id("server", server)
+                ));
+            }
+        }
+        for (server, runtimes) in &self.servers {
+            for runtime in runtimes {
+                out.push_str(&format!(
+                    "    {} -> {};\n",
+                    node_id("server", server),
+                    node_id("runtime", runtime)
+                ));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// A Mermaid flowchart, laid out left to right
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+
+        for client in self.clients.keys() {
+            out.push_str(&format!(
+                "    {}[\"{}\"]\n",
+                node_id("client", client),
+                mermaid_label(client)
+            ));
+        }
+        for server in self.servers.keys() {
+            out.push_str(&format!(
+                "    {}([\"{}\"])\n",
+                node_id("server", server),
+                mermaid_label(server)
+            ));
+        }
+        for runtime in self.runtimes() {
+            out.push_str(&format!(
+                "    {}{{{{\"{}\"}}}}\n",
+                node_id("runtime", runtime),
+                mermaid_label(runtime)
+            ));
+        }
+
+        for (client, servers) in &self.clients {
+            for server in servers {
+                out.push_str(&format!(
+                    "    {} --> {}\n",
+                    node_id("client", client),
+                    node_id("server", server)
+                ));
+            }
+        }
+        for (server, runtimes) in &self.servers {
+            for runtime in runtimes {
+                out.push_str(&format!(
+                    "    {} --> {}\n",
+                    node_id("server", server),
+                    node_id("runtime", runtime)
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// An identifier that is valid in both DOT and Mermaid and unique per kind
+fn node_id(kind: &str, name: &str) -> String {
+    let mut id = String::from(kind);
+    id.push('_');
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            id.push(c.to_ascii_lowercase());
+        } else {
+            // Escape instead of replacing so "a-b" and "a_b" stay distinct
+            id.push_str(&format!("_{:x}_", c as u32));
+        }
+    }
+    id
+}
+
+fn dot_string(label: &str) -> String {
+    format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn mermaid_label(label: &str) -> String {
+    label.replace('"', "#quot;")
+}
+
+/// Print a diagram of installed clients, their servers and runtimes
+pub struct GraphCommand {
+    format: GraphFormat,
+}
+
+impl GraphCommand {
+    pub fn new(format: GraphFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn execute(&self) -> Result<(), McpError> {
+        let graph = DependencyGraph::from_installed_clients();
+        if graph.is_empty() {
+            eprintln!("{} No MCP clients found", "ℹ".blue());
+        }
+        // The diagram goes to stdout alone so it can be redirected to a file
+        print!("{}", graph.render(self.format));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    fn sample() -> DependencyGraph {
+        let mut graph = DependencyGraph::new();
+        graph.add_server("Claude Desktop", "filesystem", "Node.js");
+        graph.add_server("VS Code", "filesystem", "Node.js");
+        graph.add_server("VS Code", "postgres", "Docker");
+        graph.add_client("Cursor");
+        graph
+    }
+
+    #[test]
+    fn test_graph_format_parsing() {
+        assert_eq!("dot".parse::<GraphFormat>(), Ok(GraphFormat::Dot));
+        assert_eq!("Mermaid".parse::<GraphFormat>(), Ok(GraphFormat::Mermaid));
+        assert!("svg".parse::<GraphFormat>().is_err());
+        assert_eq!(GraphFormat::Mermaid.to_string(), "mermaid");
+    }
+
+    #[test]
+    fn test_runtime_for() {
+        assert_eq!(runtime_for(&config("npx", &["-y", "pkg"])), "Node.js");
+        assert_eq!(runtime_for(&config("C:\\node\\npx.cmd", &[])), "Node.js");
+        assert_eq!(runtime_for(&config("uvx", &["mcp-server-git"])), "Python");
+        assert_eq!(runtime_for(&config("docker", &["run", "img"])), "Docker");
+        assert_eq!(
+            runtime_for(&config("cmd", &["/c", "npx", "-y", "pkg"])),
+            "Node.js"
+        );
+        assert_eq!(runtime_for(&config("/opt/bin/server", &[])), "binary");
+
+        let remote = RemoteServerConfig::new("https://api.example.com/mcp")
+            .unwrap()
+            .bridge_config();
+        assert_eq!(runtime_for(&remote), "HTTP (api.example.com)");
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = sample().to_dot();
+        assert!(dot.starts_with("digraph mcp {"));
+        assert!(dot.contains("client_claude_20_desktop [label=\"Claude Desktop\""));
+        assert!(dot.contains("client_cursor [label=\"Cursor\""));
+        assert!(dot.contains("client_vs_20_code -> server_postgres;"));
+        assert!(dot.contains("server_filesystem -> runtime_node_2e_js;"));
+        // A shared server and runtime appear once
+        assert_eq!(dot.matches("server_filesystem [").count(), 1);
+        assert_eq!(dot.matches("server_filesystem -> ").count(), 1);
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let mermaid = sample().to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("client_vs_20_code[\"VS Code\"]"));
+        assert!(mermaid.contains("server_postgres([\"postgres\"])"));
+        assert!(mermaid.contains("runtime_docker{{\"Docker\"}}"));
+        assert!(mermaid.contains("server_postgres --> runtime_docker"));
+    }
+
+    #[test]
+    fn test_node_ids_stay_distinct() {
+        assert_ne!(node_id("server", "a-b"), node_id("server", "a_b"));
+        assert_eq!(mermaid_label("say \"hi\""), "say #quot;hi#quot;");
+        assert_eq!(dot_string("a\"b"), "\"a\\\"b\"");
+    }
+}
@@ -0,0 +1,336 @@
+//! `mcp help-server <name>`: offline documentation for a server.
+//!
+//! Documentation is assembled from what is already on this machine: the built-in
+//! registry entry, the package's `package.json` (`mcp` config schema and usage
+//! examples) and its README from the npx cache. Nothing is fetched over the network.
+
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::client::detect_clients;
+use crate::error::McpError;
+use crate::server::{ConfigField, ExtendedServerMetadata, MetadataLoader, RegistryEntry};
+use crate::utils::markdown::{highlight_code_line, render_markdown};
+
+/// README file names looked up in a cached package, in order
+const README_NAMES: &[&str] = &["README.md", "readme.md", "Readme.md", "README"];
+
+/// Everything known locally about a server
+#[derive(Debug, Default)]
+pub struct ServerDocs {
+    pub package: String,
+    pub registry: Option<RegistryEntry>,
+    pub metadata: Option<ExtendedServerMetadata>,
+    pub readme: Option<String>,
+}
+
+impl ServerDocs {
+    pub fn is_empty(&self) -> bool {
+        self.registry.is_none() && self.metadata.is_none() && self.readme.is_none()
+    }
+}
+
+/// Show documentation for a server without opening a browser
+pub struct HelpServerCommand {
+    verbose: bool,
+    search_roots: Vec<PathBuf>,
+}
+
+impl HelpServerCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            search_roots: default_package_roots(),
+        }
+    }
+
+    /// Directories containing `node_modules` trees to search for cached packages
+    pub fn with_search_roots(mut self, roots: Vec<PathBuf>) -> Self {
+        self.search_roots = roots;
+        self
+    }
+
+    pub fn execute(&self, name: &str) -> Result<(), McpError> {
+        let package = resolve_package_name(name);
+        if self.verbose && package != name {
+            eprintln!("{} '{}' is configured as {}", "ℹ".blue(), name, package);
+        }
+
+        let docs = self.collect(&package);
+        if docs.is_empty() {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "No documentation for '{}' is available offline. Run the server once (e.g. 'mcp run {}') so npx caches it, or see https://www.npmjs.com/package/{}",
+                name,
+                package,
+                package
+            )));
+        }
+
+        print!("{}", render_docs(&docs));
+        Ok(())
+    }
+
+    /// Gather registry metadata, package metadata and README for `package`
+    pub fn collect(&self, package: &str) -> ServerDocs {
+        let mut loader = MetadataLoader::new();
+        let registry = loader.load_from_registry(package).ok().flatten();
+
+        let package_dir = find_cached_package(&self.search_roots, package);
+        if self.verbose {
+            if let Some(dir) = &package_dir {
+                eprintln!("{} Using cached package at {}", "ℹ".blue(), dir.display());
+            }
+        }
+
+        let metadata = package_dir
+            .as_deref()
+            .and_then(|dir| loader.load_from_package_json(dir).ok());
+        let readme = package_dir.as_deref().and_then(read_readme);
+
+        ServerDocs {
+            package: package.to_string(),
+            registry,
+            metadata,
+            readme,
+        }
+    }
+}
+
+/// Render collected documentation for the terminal
+pub fn render_docs(docs: &ServerDocs) -> String {
+    let mut out = String::new();
+
+    let title = docs
+        .registry
+        .as_ref()
+        .map(|entry| entry.name.clone())
+        .unwrap_or_else(|| docs.package.clone());
+    out.push_str(&format!("📖 {}", title.bold()));
+    if title != docs.package {
+        out.push_str(&format!(" ({})", docs.package.dimmed()));
+    }
+    if docs.registry.as_ref().is_some_and(|entry| entry.verified) {
+        out.push_str(&format!(" {}", "✓ verified".green()));
+    }
+    out.push('\n');
+
+    let description = docs
+        .metadata
+        .as_ref()
+        .and_then(|m| m.description.clone())
+        .or_else(|| docs.registry.as_ref().map(|e| e.description.clone()));
+    if let Some(description) = description {
+        out.push_str(&format!("{description}\n"));
+    }
+    if let Some(entry) = &docs.registry {
+        out.push_str(&format!(
+            "{} {}   {} {}\n",
+            "Category:".dimmed(),
+            entry.category,
+            "Tags:".dimmed(),
+            entry.tags.join(", ")
+        ));
+    }
+
+    if let Some(metadata) = &docs.metadata {
+        render_fields(
+            &mut out,
+            "Required configuration",
+            &metadata.required_config,
+        );
+        render_fields(
+            &mut out,
+            "Optional configuration",
+            &metadata.optional_config,
+        );
+
+        if !metadata.examples.is_empty() {
+            out.push_str(&format!("\n{}\n", "Examples".bold().cyan()));
+            for example in &metadata.examples {
+                out.push_str(&format!("  {}\n", example.title.bold()));
+                if let Some(description) = &example.description {
+                    out.push_str(&format!("    {description}\n"));
+                }
+                if let Some(prompt) = &example.prompt {
+                    out.push_str(&format!("    {} \"{prompt}\"\n", "Ask:".dimmed()));
+                }
+                if let Some(tool) = &example.tool {
+                    out.push_str(&format!("    {} {tool}\n", "Tool:".dimmed()));
+                }
+                let mut config: Vec<_> = example.config.iter().collect();
+                config.sort();
+                for (key, value) in config {
+                    out.push_str(&format!(
+                        "    {}\n",
+                        highlight_code_line(&format!("--config {key}={value}"), "sh")
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(readme) = &docs.readme {
+        out.push_str(&format!("\n{}\n", "README".bold().cyan()));
+        out.push_str(&render_markdown(readme, 2));
+    }
+
+    out
+}
+
+pub(crate) fn render_fields(out: &mut String, heading: &str, fields: &[ConfigField]) {
+    if fields.is_empty() {
+        return;
+    }
+
+    out.push_str(&format!("\n{}\n", heading.bold().cyan()));
+    for field in fields {
+        out.push_str(&format!(
+            "  {}  {}",
+            field.name.yellow(),
+            format!("({:?})", field.field_type).to_lowercase().dimmed()
+        ));
+        if let Some(description) = &field.description {
+            out.push_str(&format!("  {description}"));
+        }
+        if let Some(default) = &field.default {
+            out.push_str(&format!(" {}", format!("[default: {default}]").dimmed()));
+        }
+        out.push('\n');
+    }
+}
+
+/// Map a configured server name to the npm package it runs, if it is one.
+pub fn resolve_package_name(name: &str) -> String {
+    for client in detect_clients() {
+        if !client.is_installed() {
+            continue;
+        }
+        let Ok(servers) = client.list_servers() else {
+            continue;
+        };
+        if let Some(config) = servers.get(name) {
+            if config.command.starts_with("npx") {
+                if let Some(package) = config.args.iter().find(|arg| !arg.starts_with('-')) {
+                    return strip_version(package).to_string();
+                }
+            }
+        }
+    }
+    strip_version(name).to_string()
+}
+
+/// `@scope/pkg@1.2.3` -> `@scope/pkg`
+pub fn strip_version(package: &str) -> &str {
+    // Skip the first character so a scope's leading '@' is not taken for a version
+    match package.get(1..).and_then(|rest| rest.rfind('@')) {
+        Some(at) => &package[..at + 1],
+        None => package,
+    }
+}
+
+/// Roots whose `node_modules` may contain a cached copy of a package
+fn default_package_roots() -> Vec<PathBuf> {
+    let npm_cache = std::env::var_os("NPM_CONFIG_CACHE")
+        .or_else(|| std::env::var_os("npm_config_cache"))
+        .map(PathBuf::from)
+        .or_else(|| {
+            if cfg!(windows) {
+                std::env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("npm-cache"))
+            } else {
+                directories::BaseDirs::new().map(|dirs| dirs.home_dir().join(".npm"))
+            }
+        });
+
+    let mut roots = Vec::new();
+    if let Some(npx_dir) = npm_cache.map(|cache| cache.join("_npx")) {
+        if let Ok(entries) = fs::read_dir(npx_dir) {
+            roots.extend(entries.flatten().map(|entry| entry.path()));
+        }
+    }
+    if let Ok(cwd) = std::env::current_dir() {
+        roots.push(cwd);
+    }
+    roots
+}
+
+/// Find `node_modules/<package>` with a package.json under any of `roots`
+pub fn find_cached_package(roots: &[PathBuf], package: &str) -> Option<PathBuf> {
+    roots
+        .iter()
+        .map(|root| root.join("node_modules").join(package))
+        .find(|dir| dir.join("package.json").is_file())
+}
+
+fn read_readme(dir: &Path) -> Option<String> {
+    README_NAMES
+        .iter()
+        .find_map(|name| fs::read_to_string(dir.join(name)).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_version() {
+        assert_eq!(strip_version("@scope/pkg@1.2.3"), "@scope/pkg");
+        assert_eq!(strip_version("@scope/pkg"), "@scope/pkg");
+        assert_eq!(strip_version("pkg@latest"), "pkg");
+        assert_eq!(strip_version("pkg"), "pkg");
+        assert_eq!(strip_version(""), "");
+    }
+
+    #[test]
+    fn test_collect_from_cached_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("abc123");
+        let package_dir = root.join("node_modules").join("@acme").join("weather");
+        fs::create_dir_all(&package_dir).unwrap();
+        fs::write(
+            package_dir.join("package.json"),
+            r#"{
+                "name": "@acme/weather",
+                "version": "1.0.0",
+                "description": "Weather forecasts",
+                "mcp": {
+                    "required_config": [
+                        { "name": "WEATHER_API_KEY", "field_type": "String", "description": "API key", "default": null }
+                    ],
+                    "examples": [
+                        { "title": "Forecast", "description": "Ask: what's the weather in Oslo?", "config": { "WEATHER_API_KEY": "demo" } }
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+        fs::write(
+            package_dir.join("README.md"),
+            "# Weather\n\nUse `forecast`.\n",
+        )
+        .unwrap();
+
+        let command = HelpServerCommand::new(false).with_search_roots(vec![root]);
+        let docs = command.collect("@acme/weather");
+        assert!(!docs.is_empty());
+        assert!(docs.registry.is_none());
+
+        let rendered = render_docs(&docs);
+        assert!(rendered.contains("Weather forecasts"));
+        assert!(rendered.contains("WEATHER_API_KEY"));
+        assert!(rendered.contains("what's the weather in Oslo?"));
+        assert!(rendered.contains("forecast"));
+    }
+
+    #[test]
+    fn test_collect_from_builtin_registry() {
+        let command = HelpServerCommand::new(false).with_search_roots(Vec::new());
+        let docs = command.collect("@modelcontextprotocol/server-filesystem");
+        assert!(docs.registry.is_some());
+        assert!(docs.readme.is_none());
+        assert!(render_docs(&docs).contains("Filesystem Server"));
+
+        assert!(command.collect("@nobody/nothing").is_empty());
+    }
+}
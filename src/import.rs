@@ -0,0 +1,335 @@
+//! `mcp import`: adopt hand-written client configs.
+//!
+//! Servers configured by hand before mcp-helper was in use are recorded in
+//! [`ManagedServers`] with their inferred kind, so `mcp update`, `mcp doctor` and
+//! friends treat them like servers added with `mcp add`. Importing never edits a
+//! client config. Suggested normalizations (version pins, Windows command shims) are
+//! only applied when confirmed, through [`ConfigManager`] so each change can be
+//! rolled back, and the original entry stays in the managed record.
+
+use colored::Colorize;
+
+use crate::client::{detect_clients, McpClient, ServerConfig};
+use crate::config::{ConfigManager, ManagedServers, ServerKind};
+use crate::error::McpError;
+use crate::update::{changelog, find_npm_package_spec, with_package_version};
+use crate::utils::prompt::Confirm;
+
+/// A non-destructive improvement to an imported server entry
+#[derive(Debug, Clone, PartialEq)]
+pub enum Normalization {
+    /// Pin an unversioned npm package to its current release
+    PinVersion { package: String },
+    /// Launch `npx`/`npm` through their `.cmd` shims on Windows
+    WindowsShim { shim: String },
+}
+
+impl Normalization {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::PinVersion { package } => format!("pin {package} to its latest release"),
+            Self::WindowsShim { shim } => format!("launch through the {shim} wrapper"),
+        }
+    }
+}
+
+/// Normalizations worth offering for `config`
+pub fn plan_normalizations(config: &ServerConfig, windows: bool) -> Vec<Normalization> {
+    let mut plan = Vec::new();
+
+    if let Some(spec) = find_npm_package_spec(config) {
+        if spec.version.is_none() {
+            plan.push(Normalization::PinVersion {
+                package: spec.package,
+            });
+        }
+    }
+    if windows && matches!(config.command.as_str(), "npx" | "npm") {
+        plan.push(Normalization::WindowsShim {
+            shim: format!("{}.cmd", config.command),
+        });
+    }
+
+    plan
+}
+
+/// Apply `plan` to `config`; `latest` looks up the current release of a package.
+pub fn apply_normalizations(
+    config: &ServerConfig,
+    plan: &[Normalization],
+    latest: &dyn Fn(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<ServerConfig> {
+    let mut normalized = config.clone();
+    for normalization in plan {
+        match normalization {
+            Normalization::PinVersion { package } => {
+                if let Some(spec) = find_npm_package_spec(&normalized) {
+                    let version = latest(package)?;
+                    normalized = with_package_version(&normalized, &spec, &version);
+                }
+            }
+            Normalization::WindowsShim { shim } => normalized.command = shim.clone(),
+        }
+    }
+    Ok(normalized)
+}
+
+/// One server found in a client config
+struct Found<'a> {
+    client: &'a dyn McpClient,
+    name: String,
+    config: ServerConfig,
+    kind: ServerKind,
+    plan: Vec<Normalization>,
+}
+
+/// Bring hand-written client configs under mcp-helper management
+pub struct ImportCommand {
+    verbose: bool,
+    dry_run: bool,
+    normalize: Option<bool>,
+}
+
+impl ImportCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            dry_run: false,
+            normalize: None,
+        }
+    }
+
+    /// Only show what would be imported
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// Apply (`true`) or skip (`false`) suggested normalizations without asking
+    pub fn set_normalize(&mut self, normalize: Option<bool>) {
+        self.normalize = normalize;
+    }
+
+    pub fn execute(&self) -> Result<(), McpError> {
+        let managed = ManagedServers::new().map_err(McpError::Other)?;
+        let clients = detect_clients();
+
+        let title = if self.dry_run {
+            "📥 Import preview (dry run)"
+        } else {
+            "📥 Importing MCP servers"
+        };
+        println!("{}", title.blue().bold());
+        println!();
+
+        let mut found = Vec::new();
+        let mut already_managed = 0;
+        for client in clients.iter().filter(|c| c.is_installed()) {
+            let servers = match client.list_servers() {
+                Ok(servers) => servers,
+                Err(e) => {
+                    println!(
+                        "{} {} - Error reading config: {}",
+                        "⚠".yellow(),
+                        client.name(),
+                        e.to_string().dimmed()
+                    );
+                    continue;
+                }
+            };
+
+            let mut names: Vec<_> = servers.keys().cloned().collect();
+            names.sort();
+            for name in names {
+                if managed
+                    .is_managed_in(&name, client.name())
+                    .map_err(McpError::Other)?
+                {
+                    already_managed += 1;
+                    if self.verbose {
+                        println!(
+                            "  {} {} in {} (already managed)",
+                            "=".dimmed(),
+                            name,
+                            client.name()
+                        );
+                    }
+                    continue;
+                }
+
+                let config = servers[&name].clone();
+                found.push(Found {
+                    client: client.as_ref(),
+                    kind: ServerKind::infer(&config),
+                    plan: plan_normalizations(&config, cfg!(target_os = "windows")),
+                    name,
+                    config,
+                });
+            }
+        }
+
+        if found.is_empty() {
+            println!(
+                "{} Nothing to import ({} server entr{} already managed)",
+                "ℹ".blue(),
+                already_managed,
+                if already_managed == 1 { "y" } else { "ies" }
+            );
+            return Ok(());
+        }
+
+        for entry in &found {
+            println!(
+                "  {} {} in {} {}",
+                "+".green(),
+                entry.name.cyan(),
+                entry.client.name(),
+                format!("({})", entry.kind).dimmed()
+            );
+            if !self.dry_run {
+                managed
+                    .record_imported(&entry.name, &entry.config, entry.client.name())
+                    .map_err(McpError::Other)?;
+            }
+        }
+
+        println!();
+        let verb = if self.dry_run {
+            "Would import"
+        } else {
+            "Imported"
+        };
+        println!(
+            "{} {} {} server entr{}",
+            "✓".green(),
+            verb,
+            found.len(),
+            if found.len() == 1 { "y" } else { "ies" }
+        );
+
+        let suggestions: Vec<&Found> = found.iter().filter(|f| !f.plan.is_empty()).collect();
+        if suggestions.is_empty() {
+            return Ok(());
+        }
+
+        println!();
+        println!("{}", "Suggested normalizations:".bold());
+        for entry in &suggestions {
+            for normalization in &entry.plan {
+                println!(
+                    "  • {} ({}): {}",
+                    entry.name.cyan(),
+                    entry.client.name(),
+                    normalization.describe()
+                );
+            }
+        }
+
+        if self.dry_run || !self.confirm_normalize()? {
+            return Ok(());
+        }
+        self.normalize_entries(&suggestions)
+    }
+
+    fn confirm_normalize(&self) -> Result<bool, McpError> {
+        if let Some(normalize) = self.normalize {
+            return Ok(normalize);
+        }
+
+        Confirm::new()
+            .with_prompt("Apply these changes? The current entries can be rolled back")
+            .default(false)
+            .interact()
+            .map_err(|e| {
+                McpError::Other(anyhow::anyhow!(
+                    "Confirmation failed: {} (use --normalize or --no-normalize when not running interactively)",
+                    e
+                ))
+            })
+    }
+
+    fn normalize_entries(&self, entries: &[&Found]) -> Result<(), McpError> {
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+        let latest = |package: &str| changelog::fetch_npm_package(package).map(|info| info.latest);
+
+        let mut failed = 0;
+        for entry in entries {
+            let result = apply_normalizations(&entry.config, &entry.plan, &latest)
+                .and_then(|normalized| manager.apply_config(entry.client, &entry.name, normalized));
+            let result = result.map(|_snapshot| ());
+            match result {
+                Ok(()) => println!(
+                    "  {} Normalized {} in {}",
+                    "✓".green(),
+                    entry.name,
+                    entry.client.name()
+                ),
+                Err(e) => {
+                    failed += 1;
+                    println!(
+                        "  {} {} in {} - {}",
+                        "✗".red(),
+                        entry.name,
+                        entry.client.name(),
+                        e.to_string().dimmed()
+                    );
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Failed to normalize {} server entr{}",
+                failed,
+                if failed == 1 { "y" } else { "ies" }
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_normalizations() {
+        let unpinned = config("npx", &["-y", "@org/server"]);
+        assert_eq!(
+            plan_normalizations(&unpinned, false),
+            vec![Normalization::PinVersion {
+                package: "@org/server".to_string()
+            }]
+        );
+        assert_eq!(plan_normalizations(&unpinned, true).len(), 2);
+
+        let pinned = config("npx", &["-y", "@org/server@1.0.0"]);
+        assert!(plan_normalizations(&pinned, false).is_empty());
+        assert!(plan_normalizations(&config("docker", &["run", "img"]), true).is_empty());
+    }
+
+    #[test]
+    fn test_apply_normalizations() {
+        let unpinned = config("npx", &["-y", "@org/server", "--stdio"]);
+        let plan = plan_normalizations(&unpinned, true);
+
+        let normalized =
+            apply_normalizations(&unpinned, &plan, &|_| Ok("2.1.0".to_string())).unwrap();
+        assert_eq!(normalized.command, "npx.cmd");
+        assert_eq!(normalized.args, ["-y", "@org/server@2.1.0", "--stdio"]);
+
+        let offline = apply_normalizations(&unpinned, &plan, &|_| anyhow::bail!("offline"));
+        assert!(offline.is_err());
+    }
+}
@@ -0,0 +1,556 @@
+//! `mcp info <server>`: everything known about a server in one view.
+//!
+//! Works for servers that are installed and ones that aren't. The view combines
+//! what mcp-helper recorded when the server was added, the client configs that
+//! run it, the built-in registry and cached package (see [`crate::help_server`]),
+//! the npm registry (description, versions, dependencies, config schema, license,
+//! links and README) and, when npm has no license, the GitHub repository. Network
+//! lookups go through the registry cache, so repeating `mcp info` costs a
+//! `304 Not Modified` at most; `--offline` skips them.
+
+use colored::Colorize;
+use semver::Version;
+use serde_json::Value;
+
+use crate::client::detect_clients;
+use crate::config::{ManagedOrigin, ManagedServer, ManagedServers, ServerKind};
+use crate::error::McpError;
+use crate::help_server::{render_fields, resolve_package_name, HelpServerCommand, ServerDocs};
+use crate::server::publisher::{self, PublisherVerification};
+use crate::server::{detect_server_type, github, npm_registry, ServerType};
+use crate::update::changelog::parse_github_repo;
+use crate::update::ConfigSchema;
+use crate::utils::markdown::render_markdown;
+
+/// What npm serves when a package was published without a README
+const MISSING_README: &str = "ERROR: No README data found!";
+
+/// A client config that runs the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct Installation {
+    pub client: String,
+    pub kind: ServerKind,
+}
+
+impl Installation {
+    /// The npm version the client pins, if any
+    pub fn version(&self) -> Option<&str> {
+        match &self.kind {
+            ServerKind::Npm { version, .. } => version.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Everything known about a server, from local records and registries
+#[derive(Debug, Clone)]
+pub struct ServerInfo {
+    /// The name asked about
+    pub name: String,
+    /// The package, image or command it resolves to
+    pub package: String,
+    pub server_type: ServerType,
+    pub description: Option<String>,
+    /// The `latest` dist-tag
+    pub latest: Option<String>,
+    pub installations: Vec<Installation>,
+    pub managed: Option<ManagedServer>,
+    /// What has to be installed to run the server, e.g. "Node.js >=18"
+    pub runtime: Option<String>,
+    pub dependencies: Vec<String>,
+    pub schema: ConfigSchema,
+    pub license: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub verification: Option<PublisherVerification>,
+    /// Listed as verified in the built-in registry
+    pub registry_verified: bool,
+    pub readme: Option<String>,
+    /// Lookups that failed, so gaps in the view are explained
+    pub unavailable: Vec<String>,
+}
+
+impl ServerInfo {
+    pub fn new(name: &str, package: &str) -> Self {
+        let server_type = detect_server_type(package);
+        Self {
+            name: name.to_string(),
+            package: package.to_string(),
+            runtime: runtime_of(&server_type, None),
+            server_type,
+            description: None,
+            latest: None,
+            installations: Vec::new(),
+            managed: None,
+            dependencies: Vec::new(),
+            schema: ConfigSchema::default(),
+            license: None,
+            homepage: None,
+            repository: None,
+            verification: None,
+            registry_verified: false,
+            readme: None,
+            unavailable: Vec::new(),
+        }
+    }
+
+    /// Take the type from a client config; a name like `postgres` may run a
+    /// Docker image or a Python package rather than the npm package it suggests
+    pub fn apply_kind(&mut self, kind: &ServerKind) {
+        let (package, server_type) = match kind {
+            ServerKind::Npm { .. } => return,
+            ServerKind::Docker { image } => (
+                image.clone(),
+                detect_server_type(&format!("docker:{image}")),
+            ),
+            ServerKind::Python { package } => (
+                package.clone(),
+                ServerType::Python {
+                    package: package.clone(),
+                    version: None,
+                },
+            ),
+            ServerKind::Binary { command } => (
+                command.clone(),
+                ServerType::Binary {
+                    url: command.clone(),
+                    checksum: None,
+                },
+            ),
+        };
+        self.package = package;
+        self.runtime = runtime_of(&server_type, None);
+        self.server_type = server_type;
+    }
+
+    /// Whether nothing at all is known beyond the name
+    pub fn is_empty(&self) -> bool {
+        self.installations.is_empty()
+            && self.managed.is_none()
+            && self.description.is_none()
+            && self.latest.is_none()
+            && self.readme.is_none()
+    }
+
+    /// Fill in what the built-in registry and the cached package know
+    pub fn apply_docs(&mut self, docs: ServerDocs) {
+        if let Some(entry) = docs.registry {
+            self.registry_verified = entry.verified;
+            self.description.get_or_insert(entry.description);
+        }
+        if let Some(metadata) = docs.metadata {
+            if metadata.description.is_some() {
+                self.description = metadata.description;
+            }
+            self.license = metadata.license.or(self.license.take());
+            self.homepage = metadata.homepage.or(self.homepage.take());
+            self.repository = metadata.repository.or(self.repository.take());
+            if !metadata.dependencies.is_empty() {
+                self.dependencies = metadata.dependencies;
+            }
+            self.schema.required_config = metadata.required_config;
+            self.schema.optional_config = metadata.optional_config;
+        }
+        if docs.readme.is_some() {
+            self.readme = docs.readme;
+        }
+    }
+
+    /// Fill in the npm registry's view, which is newer than a cached package
+    pub fn apply_npm_details(&mut self, details: &Value) {
+        let text = |value: &Value| {
+            value
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let latest = &details["latest"];
+
+        if let Some(description) = text(&details["description"]) {
+            self.description = Some(description);
+        }
+        self.latest = text(&details["dist-tags"]["latest"]).or(self.latest.take());
+        self.license = text(&details["license"])
+            .or_else(|| text(&details["license"]["type"]))
+            .or(self.license.take());
+        self.homepage = text(&details["homepage"]).or(self.homepage.take());
+        self.repository = text(&details["repository"])
+            .or_else(|| text(&details["repository"]["url"]))
+            .or(self.repository.take());
+        if let Some(readme) = text(&details["readme"]).filter(|r| r != MISSING_README) {
+            self.readme = Some(readme);
+        }
+
+        if let Some(dependencies) = latest["dependencies"].as_object() {
+            let mut names: Vec<String> = dependencies.keys().cloned().collect();
+            names.sort();
+            self.dependencies = names;
+        }
+        self.runtime = runtime_of(&self.server_type, latest["engines"]["node"].as_str());
+        let schema = ConfigSchema::from_mcp(&latest["mcp"]);
+        if schema != ConfigSchema::default() {
+            self.schema = schema;
+        }
+    }
+
+    /// Whether a client pins an npm version older than `latest`
+    pub fn update_available(&self) -> bool {
+        let Some(latest) = self.latest.as_deref().and_then(|v| Version::parse(v).ok()) else {
+            return false;
+        };
+        self.installations.iter().any(|installation| {
+            installation
+                .version()
+                .and_then(|v| Version::parse(v).ok())
+                .is_some_and(|version| version < latest)
+        })
+    }
+}
+
+/// Show details of a server, installed or not
+pub struct InfoCommand {
+    verbose: bool,
+    offline: bool,
+    show_readme: bool,
+}
+
+impl InfoCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            offline: false,
+            show_readme: false,
+        }
+    }
+
+    /// Only use what is on this machine
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Print the README after the details
+    pub fn with_readme(mut self, show_readme: bool) -> Self {
+        self.show_readme = show_readme;
+        self
+    }
+
+    pub fn execute(&self, name: &str) -> Result<(), McpError> {
+        let info = self.gather(name);
+        if info.is_empty() {
+            let mut message = format!(
+                "Nothing is known about '{name}': it isn't configured in any client and no registry lists it"
+            );
+            for reason in &info.unavailable {
+                message.push_str(&format!("\n  {reason}"));
+            }
+            return Err(McpError::Other(anyhow::anyhow!(message)));
+        }
+
+        print!("{}", render_info(&info, self.show_readme));
+        Ok(())
+    }
+
+    /// Collect what local records, the built-in registry and, unless offline,
+    /// the npm registry and GitHub know about `name`
+    pub fn gather(&self, name: &str) -> ServerInfo {
+        let package = resolve_package_name(name);
+        let mut info = ServerInfo::new(name, &package);
+
+        let mut installations: Vec<Installation> = detect_clients()
+            .iter()
+            .filter(|client| client.is_installed())
+            .filter_map(|client| {
+                let config = client.list_servers().ok()?.remove(name)?;
+                Some(Installation {
+                    client: client.name().to_string(),
+                    kind: ServerKind::infer(&config),
+                })
+            })
+            .collect();
+        installations.sort_by(|a, b| a.client.cmp(&b.client));
+        if let Some(installation) = installations.first() {
+            info.apply_kind(&installation.kind);
+        }
+        info.installations = installations;
+        info.managed = ManagedServers::new()
+            .and_then(|managed| managed.get(name))
+            .ok()
+            .flatten();
+        info.verification = info
+            .managed
+            .as_ref()
+            .and_then(|managed| managed.publisher.clone());
+
+        if matches!(info.server_type, ServerType::Npm { .. }) {
+            info.apply_docs(HelpServerCommand::new(self.verbose).collect(&package));
+        }
+
+        if self.offline || !matches!(info.server_type, ServerType::Npm { .. }) {
+            return info;
+        }
+        match npm_registry::package_details(&package) {
+            Ok(details) => info.apply_npm_details(&details),
+            Err(e) => info.unavailable.push(format!("{e:#}")),
+        }
+        if info.latest.is_some() {
+            match publisher::verify_npm_package(&package, None) {
+                Ok(verification) => info.verification = Some(verification),
+                Err(e) => info.unavailable.push(format!("{e:#}")),
+            }
+        }
+        if info.license.is_none() {
+            if let Some(repo) = info.repository.as_deref().and_then(parse_github_repo) {
+                match github::license(&repo) {
+                    Ok(license) => {
+                        info.license = license["spdx_id"]
+                            .as_str()
+                            .filter(|id| *id != "NOASSERTION")
+                            .or_else(|| license["name"].as_str())
+                            .map(str::to_string);
+                    }
+                    Err(e) => info.unavailable.push(format!("{e:#}")),
+                }
+            }
+        }
+        info
+    }
+}
+
+/// Render `info` for the terminal, with the README when `show_readme` is set
+pub fn render_info(info: &ServerInfo, show_readme: bool) -> String {
+    let mut out = format!("📦 {}", info.name.bold());
+    if info.package != info.name {
+        out.push_str(&format!(" ({})", info.package.dimmed()));
+    }
+    if info.registry_verified {
+        out.push_str(&format!(" {}", "✓ verified".green()));
+    }
+    out.push('\n');
+    if let Some(description) = &info.description {
+        out.push_str(&format!("{description}\n"));
+    }
+    out.push('\n');
+
+    let mut line = |label: &str, value: String| {
+        let label = format!("{:<14}", format!("{label}:"));
+        out.push_str(&format!("  {}{value}\n", label.dimmed()));
+    };
+    line("Type", type_label(&info.server_type).to_string());
+    if let Some(latest) = &info.latest {
+        line("Latest", latest.clone());
+    }
+
+    if info.installations.is_empty() {
+        line("Installed", "no".to_string());
+    } else {
+        let mut installed: Vec<String> = info
+            .installations
+            .iter()
+            .map(|installation| match installation.version() {
+                Some(version) => format!("{version} in {}", installation.client),
+                None => installation.client.clone(),
+            })
+            .collect();
+        installed.dedup();
+        let mut value = installed.join(", ");
+        if info.update_available() {
+            value.push_str(&format!(
+                " {}",
+                format!("(update available: mcp update {})", info.name).yellow()
+            ));
+        }
+        line("Installed", value);
+    }
+    if let Some(managed) = &info.managed {
+        let origin = match managed.origin {
+            ManagedOrigin::Added => "added",
+            ManagedOrigin::Imported => "imported",
+        };
+        let mut value = format!("{origin} {}", managed.recorded_at.format("%Y-%m-%d"));
+        if let Some(default_name) = &managed.instance_of {
+            value.push_str(&format!(", instance of {default_name}"));
+        }
+        line("Managed", value);
+    }
+
+    if let Some(runtime) = &info.runtime {
+        line("Runtime", runtime.clone());
+    }
+    if !info.dependencies.is_empty() {
+        line("Dependencies", info.dependencies.join(", "));
+    }
+    line(
+        "License",
+        info.license
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string()),
+    );
+    if let Some(homepage) = &info.homepage {
+        line("Homepage", homepage.clone());
+    }
+    if let Some(repository) = &info.repository {
+        line("Repository", repository.clone());
+    }
+    if let Some(verification) = &info.verification {
+        line(
+            "Publisher",
+            format!("{} {verification}", verification.badge()),
+        );
+    }
+
+    render_fields(
+        &mut out,
+        "Required configuration",
+        &info.schema.required_config,
+    );
+    render_fields(
+        &mut out,
+        "Optional configuration",
+        &info.schema.optional_config,
+    );
+
+    if let Some(readme) = &info.readme {
+        if show_readme {
+            out.push_str(&format!("\n{}\n", "README".bold().cyan()));
+            out.push_str(&render_markdown(readme, 2));
+        } else {
+            out.push_str(&format!(
+                "\n{}\n",
+                format!("README available: mcp info {} --readme", info.name).dimmed()
+            ));
+        }
+    }
+
+    if !info.unavailable.is_empty() {
+        out.push('\n');
+        for reason in &info.unavailable {
+            out.push_str(&format!("{} {}\n", "⚠".yellow(), reason.dimmed()));
+        }
+    }
+    out
+}
+
+fn type_label(server_type: &ServerType) -> &'static str {
+    match server_type {
+        ServerType::Npm { .. } => "npm package",
+        ServerType::Binary { .. } => "binary release",
+        ServerType::Python { .. } => "Python package",
+        ServerType::Docker { .. } => "Docker image",
+        ServerType::Cargo { .. } => "Rust crate",
+        ServerType::Deno { .. } => "Deno module",
+        ServerType::Jar { .. } => "Java JAR",
+        ServerType::Dotnet { .. } => ".NET tool",
+    }
+}
+
+/// What has to be installed to run a server of `server_type`
+fn runtime_of(server_type: &ServerType, node_range: Option<&str>) -> Option<String> {
+    let runtime = match server_type {
+        ServerType::Npm { .. } => {
+            return Some(match node_range {
+                Some(range) => format!("Node.js {range}"),
+                None => "Node.js".to_string(),
+            })
+        }
+        ServerType::Python { .. } => "Python (uv)",
+        ServerType::Docker { .. } => "Docker",
+        ServerType::Cargo { .. } => "Rust toolchain (cargo)",
+        ServerType::Deno { .. } => "Deno",
+        ServerType::Jar { .. } => "Java",
+        ServerType::Dotnet { .. } => ".NET SDK",
+        ServerType::Binary { .. } => return None,
+    };
+    Some(runtime.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn details() -> Value {
+        json!({
+            "name": "@acme/weather",
+            "description": "Weather forecasts",
+            "dist-tags": { "latest": "2.1.0" },
+            "license": "MIT",
+            "homepage": "https://acme.example/weather",
+            "repository": { "type": "git", "url": "git+https://github.com/acme/weather.git" },
+            "readme": "# Weather\n\nAsk for a forecast.",
+            "latest": {
+                "version": "2.1.0",
+                "dependencies": { "zod": "^3.0.0", "@modelcontextprotocol/sdk": "^1.0.0" },
+                "engines": { "node": ">=18" },
+                "mcp": {
+                    "required_config": [{ "name": "WEATHER_TOKEN", "field_type": "String" }]
+                }
+            }
+        })
+    }
+
+    fn installed(client: &str, version: &str) -> Installation {
+        Installation {
+            client: client.to_string(),
+            kind: ServerKind::Npm {
+                package: "@acme/weather".to_string(),
+                version: Some(version.to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_apply_npm_details() {
+        let mut info = ServerInfo::new("weather", "@acme/weather");
+        info.apply_npm_details(&details());
+
+        assert_eq!(info.latest.as_deref(), Some("2.1.0"));
+        assert_eq!(info.license.as_deref(), Some("MIT"));
+        assert_eq!(
+            info.repository.as_deref(),
+            Some("git+https://github.com/acme/weather.git")
+        );
+        assert_eq!(info.runtime.as_deref(), Some("Node.js >=18"));
+        assert_eq!(info.dependencies, ["@modelcontextprotocol/sdk", "zod"]);
+        assert_eq!(info.schema.required_config[0].name, "WEATHER_TOKEN");
+        assert!(info.readme.is_some());
+
+        let mut bare = ServerInfo::new("x", "x");
+        bare.apply_npm_details(&json!({ "readme": MISSING_README, "license": { "type": "ISC" } }));
+        assert!(bare.readme.is_none());
+        assert_eq!(bare.license.as_deref(), Some("ISC"));
+    }
+
+    #[test]
+    fn test_update_available() {
+        let mut info = ServerInfo::new("weather", "@acme/weather");
+        info.apply_npm_details(&details());
+        info.installations = vec![installed("Cursor", "2.1.0")];
+        assert!(!info.update_available());
+
+        info.installations
+            .push(installed("Claude Desktop", "2.0.0"));
+        assert!(info.update_available());
+    }
+
+    #[test]
+    fn test_render_info() {
+        let mut info = ServerInfo::new("weather", "@acme/weather");
+        info.apply_npm_details(&details());
+        info.installations = vec![installed("Claude Desktop", "2.0.0")];
+
+        let text = render_info(&info, false);
+        assert!(text.contains("weather"));
+        assert!(text.contains("Weather forecasts"));
+        assert!(text.contains("npm package"));
+        assert!(text.contains("2.0.0 in Claude Desktop"));
+        assert!(text.contains("mcp update weather"));
+        assert!(text.contains("WEATHER_TOKEN"));
+        assert!(text.contains("mcp info weather --readme"));
+        assert!(!text.contains("Ask for a forecast"));
+
+        assert!(render_info(&info, true).contains("Ask for a forecast"));
+        assert!(ServerInfo::new("nothing", "nothing").is_empty());
+    }
+}
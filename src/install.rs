@@ -19,6 +19,7 @@
 //! - **Interactive Configuration**: Guides users through server setup
 //! - **Batch Installation**: Configure multiple servers from a file
 //! - **Dry Run Mode**: Preview changes without making them
+//! - **Install Manifests**: Install from a vendor-hosted `install.json` over HTTPS
 //!
 //! # Example
 //!
@@ -39,21 +40,50 @@
 //! ```
 
 use colored::Colorize;
-use dialoguer::{Confirm, Input};
+use dialoguer::Input;
+use indicatif::ProgressBar;
 use std::collections::HashMap;
 use std::fs;
+use std::io::IsTerminal;
+use std::time::Duration;
 
+use crate::audit_log;
 use crate::cache::CacheManager;
-use crate::client::{detect_clients, ClientRegistry, ServerConfig};
-use crate::config::ConfigManager;
-use crate::deps::{Dependency, DependencyInstaller, DependencyStatus};
+use crate::client::capabilities::{
+    literal_secrets, offer_input_prompts, place_secrets, prompt_for_secrets,
+};
+use crate::client::running::detect_default_selection;
+use crate::client::{
+    clients_with_name_taken, detect_clients, ClientRegistry, ClientTarget, ServerConfig,
+};
+use crate::config::{
+    ConfigManager, HookContext, HookEvent, HookRunner, ManagedServers, Preferences,
+};
+use crate::core::validation::validate_instance_name;
+use crate::deps::probe::unresponsive_advice;
+use crate::deps::{
+    install_log::InstallLog, verify_installation, Dependency, DependencyCheckCache,
+    DependencyInstaller, DependencyStatus, VerificationOutcome,
+};
+use crate::error::catalog::tr;
 use crate::error::{McpError, Result};
 use crate::logging;
-use crate::security::{SecurityValidation, SecurityValidator};
+use crate::logging::progress::{self, step_percent, Progress};
+use crate::security::{
+    SecurityFailureClass, SecurityValidation, SecurityValidator, SecurityViolation,
+};
+use crate::server::args_template;
+use crate::server::install_manifest::{is_install_manifest_url, InstallManifest};
+use crate::server::next_steps::{npm_examples, NextSteps};
+use crate::server::platform::enforce_platform;
+use crate::server::prefetch::{PrefetchItem, Prefetcher};
+use crate::server::publisher::{check_publisher, verification_required};
 use crate::server::{
     detect_server_type, ConfigField, ConfigFieldType, McpServer, ServerMetadata, ServerSuggestions,
     ServerType,
 };
+use crate::utils::batch_file::{parse_batch_file, BatchEntry};
+use crate::utils::prompt::{Confirm, MultiSelect};
 
 /// Main installation command for MCP servers.
 ///
@@ -69,6 +99,8 @@ pub struct InstallCommand {
     security_validator: SecurityValidator,
     /// Cache manager for dependency and metadata caching
     cache_manager: CacheManager,
+    /// Dependency checks already satisfied during this execution (shared by batch installs)
+    dependency_checks: DependencyCheckCache,
     /// Whether to show verbose output
     verbose: bool,
     /// Whether to automatically install missing dependencies
@@ -79,6 +111,20 @@ pub struct InstallCommand {
     suggestions: ServerSuggestions,
     /// Configuration overrides from command line (key=value pairs)
     config_overrides: HashMap<String, String>,
+    /// Values for keys left unset after prompting (from install manifests)
+    config_defaults: HashMap<String, String>,
+    /// Whether any security warning aborts the installation
+    strict: bool,
+    /// Whether dependency install commands run without asking (`--yes`)
+    yes: bool,
+    /// Whether usage examples and the restart reminder are skipped (`--quiet`)
+    quiet: bool,
+    /// Name the server is configured under instead of its default one (`--as`)
+    instance_name: Option<String>,
+    /// Whether servers declaring other platforms install anyway (`--ignore-platform`)
+    ignore_platform: bool,
+    /// Clients given with `--client` or `--all-clients` instead of prompting
+    client_target: ClientTarget,
 }
 
 impl InstallCommand {
@@ -101,11 +147,19 @@ impl InstallCommand {
             config_manager: ConfigManager::new().expect("Failed to create config manager"),
             security_validator: SecurityValidator::new(),
             cache_manager: CacheManager::new().unwrap_or_else(|_| CacheManager::default()),
+            dependency_checks: DependencyCheckCache::new(),
             verbose,
             auto_install_deps: false,
             dry_run: false,
             suggestions: ServerSuggestions::new(),
             config_overrides: HashMap::new(),
+            config_defaults: HashMap::new(),
+            strict: false,
+            yes: false,
+            quiet: false,
+            instance_name: None,
+            ignore_platform: false,
+            client_target: ClientTarget::Prompt,
         }
     }
 
@@ -157,6 +211,77 @@ impl InstallCommand {
         self
     }
 
+    /// Enable or disable strict security mode.
+    ///
+    /// In strict mode every security warning aborts the installation with a
+    /// [`SecurityViolation`] instead of prompting, and binary downloads without a
+    /// checksum are rejected.
+    ///
+    /// # Arguments
+    /// * `strict` - Whether security warnings are treated as errors
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Run dependency install commands without asking for confirmation.
+    ///
+    /// Each command is still explained before it runs.
+    ///
+    /// # Arguments
+    /// * `yes` - Whether to skip the confirmation prompt
+    pub fn with_yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
+
+    /// Skip the usage examples and restart reminder printed after installing.
+    ///
+    /// With a progress stream they are still sent as a `next_steps` event.
+    ///
+    /// # Arguments
+    /// * `quiet` - Whether to skip the next steps output
+    pub fn with_quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Configure the server under another name, as one more instance of it.
+    ///
+    /// Two filesystem servers for different directories, for example, are
+    /// installed as `--as docs-fs` and `--as code-fs`.
+    ///
+    /// # Arguments
+    /// * `name` - Key of the server in client configs
+    pub fn with_instance_name(mut self, name: Option<String>) -> Self {
+        self.instance_name = name;
+        self
+    }
+
+    /// Install servers that declare other platforms anyway.
+    ///
+    /// A server whose package.json `os` or `cpu` excludes this platform is
+    /// refused unless this is set; it is then installed with a warning.
+    ///
+    /// # Arguments
+    /// * `ignore_platform` - Whether a platform mismatch only warns
+    pub fn with_ignore_platform(mut self, ignore_platform: bool) -> Self {
+        self.ignore_platform = ignore_platform;
+        self
+    }
+
+    /// Install to these clients instead of asking which ones.
+    ///
+    /// Named clients must be installed; otherwise the installation fails listing
+    /// the clients that are.
+    ///
+    /// # Arguments
+    /// * `target` - Clients from `--client` or `--all-clients`
+    pub fn with_client_target(mut self, target: ClientTarget) -> Self {
+        self.client_target = target;
+        self
+    }
+
     fn parse_config_args(config_args: &[String]) -> HashMap<String, String> {
         let mut config = HashMap::new();
 
@@ -199,24 +324,95 @@ impl InstallCommand {
     /// installer.execute("@modelcontextprotocol/server-filesystem")?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
+    #[tracing::instrument(
+        name = "install",
+        skip(self),
+        fields(server = server_name, dry_run = self.dry_run)
+    )]
     pub fn execute(&mut self, server_name: &str) -> Result<()> {
+        let result = if is_install_manifest_url(server_name) {
+            self.execute_manifest(server_name)
+        } else {
+            self.install_server(server_name, None)
+        };
+        Progress::new("install", server_name).finish(&result, "Installed");
+        result
+    }
+
+    /// Install the server described by a vendor-hosted install manifest.
+    ///
+    /// The manifest URL goes through the same security validation as any other
+    /// server source, then the server it names runs through the normal pipeline
+    /// with the manifest's configuration schema and defaults.
+    fn execute_manifest(&mut self, url: &str) -> Result<()> {
+        println!("{} Fetching install manifest: {}", "→".green(), url.cyan());
+
+        self.validate_server_security(url)?;
+        let manifest = InstallManifest::fetch(url).map_err(McpError::Other)?;
+
+        if let Some(description) = &manifest.description {
+            println!("  {}", description.dimmed());
+        }
+        if self.verbose {
+            eprintln!(
+                "{} Manifest installs {} with {} config field(s)",
+                "ℹ".blue(),
+                manifest.server,
+                manifest.config.len()
+            );
+        }
+
+        self.config_defaults = manifest.all_defaults();
+        let result = self.install_server(&manifest.server, Some(&manifest));
+        self.config_defaults.clear();
+        result
+    }
+
+    fn install_server(
+        &mut self,
+        server_name: &str,
+        manifest: Option<&InstallManifest>,
+    ) -> Result<()> {
         if self.verbose {
             eprintln!("{} Detecting server type for: {}", "ℹ".blue(), server_name);
         }
+        let progress = Progress::new("install", server_name);
+        if let Some(name) = &self.instance_name {
+            validate_instance_name(name).map_err(|e| McpError::Other(anyhow::anyhow!(e)))?;
+        }
 
         // Validate server source security
+        progress.step("validate", 10, "Validating server source");
         self.validate_server_security(server_name)?;
 
         // Parse server argument and detect type
         let server_type = detect_server_type(server_name);
+        self.check_binary_checksum(server_name, &server_type)?;
+        if let ServerType::Npm { package, version } = &server_type {
+            check_publisher(package, version.as_deref(), verification_required())
+                .map_err(McpError::Other)?;
+        }
+        enforce_platform(&server_type, self.ignore_platform || self.dry_run)
+            .map_err(|e| McpError::Other(e.into()))?;
 
         // Create appropriate server instance
-        let server = self.create_server(&server_type)?;
+        let mut server = self.create_server(&server_type)?;
+        if let Some(manifest) = manifest {
+            server = manifest.apply_to(server);
+        }
+        let default_name = manifest
+            .and_then(|m| m.name.clone())
+            .unwrap_or_else(|| server_name.to_string());
+        let config_name = self
+            .instance_name
+            .clone()
+            .unwrap_or_else(|| default_name.clone());
 
         // Run dependency checks
+        progress.step("dependencies", 40, "Checking dependencies");
         self.check_dependencies(&*server)?;
 
-        // Select target client(s)
+        // Select target client(s) before anything is downloaded or built
         let clients = self.select_clients()?;
 
         if clients.is_empty() {
@@ -224,13 +420,91 @@ impl InstallCommand {
                 "No MCP clients selected for installation"
             )));
         }
+        if self.instance_name.is_some() {
+            self.check_instance_name_free(&config_name, server_name, &clients)?;
+        }
+
+        // Binaries are downloaded (or taken from the download cache) now, since the
+        // client launches them from disk
+        if let ServerType::Binary { url, checksum } = &server_type {
+            if !self.dry_run {
+                progress.step("download", 50, format!("Downloading {url}"));
+                server = self.install_binary(url, checksum.clone())?;
+                if let Some(manifest) = manifest {
+                    server = manifest.apply_to(server);
+                }
+            }
+        }
+
+        // Rust crates are built ahead of time so the client can launch the binary directly
+        if let ServerType::Cargo {
+            crate_name,
+            version,
+        } = &server_type
+        {
+            progress.step("build", 50, format!("Building {crate_name}"));
+            self.install_cargo_crate(crate_name, version.clone())?;
+        }
+
+        // .NET tools likewise, so the config can point at the installed executable
+        if let ServerType::Dotnet { package, version } = &server_type {
+            progress.step("build", 50, format!("Installing {package}"));
+            self.install_dotnet_tool(package, version.clone())?;
+        }
 
         // Prompt for configuration
+        progress.step("configure", 60, "Collecting configuration");
         let config = self.prompt_configuration(&*server)?;
+        if let ServerType::Deno { .. } = &server_type {
+            self.check_deno_permissions(server_name, &config)?;
+        }
 
-        // Apply configuration to selected clients
-        for client_name in &clients {
-            self.install_to_client(client_name, server_name, &config)?;
+        // The server decides how it is launched: npx, docker run, python -m or the path
+        // a binary, crate or .NET tool was installed to
+        let launch = match server.generate_command_with_config(&config) {
+            Ok(launch) => launch,
+            // Nothing was downloaded or built in a dry run, so there is nothing to launch yet
+            Err(e) if self.dry_run => {
+                println!(
+                    "{} Would configure {} once it is installed ({})",
+                    "ℹ".blue(),
+                    config_name,
+                    e
+                );
+                return Ok(());
+            }
+            Err(e) => return Err(McpError::Other(e)),
+        };
+        let command_fields = server.command_config_fields();
+
+        // Apply configuration to selected clients, surrounded by the user's hooks
+        let mut hooks = HookRunner::from_preferences(true, self.verbose);
+        for (index, client_name) in clients.iter().enumerate() {
+            progress.step(
+                "client",
+                step_percent(70, 30, index, clients.len()),
+                format!("Installing to {client_name}"),
+            );
+            let context = HookContext::new(&config_name, client_name);
+            hooks
+                .run(HookEvent::PreInstall, &context)
+                .map_err(McpError::Other)?;
+
+            let result = self.install_to_client(
+                client_name,
+                &config_name,
+                &config,
+                &server.metadata().args_template,
+                &launch,
+                &command_fields,
+            );
+            if let Err(e) = hooks.run(
+                HookEvent::PostInstall,
+                &context.with_success(result.is_ok()),
+            ) {
+                println!("  {} post_install hooks: {}", "⚠".yellow(), e);
+            }
+            result?;
         }
 
         // Log successful server installation
@@ -239,8 +513,19 @@ impl InstallCommand {
             ServerType::Binary { .. } => "binary",
             ServerType::Python { .. } => "python",
             ServerType::Docker { .. } => "docker",
+            ServerType::Cargo { .. } => "cargo",
+            ServerType::Deno { .. } => "deno",
+            ServerType::Jar { .. } => "jar",
+            ServerType::Dotnet { .. } => "dotnet",
         };
         logging::log_server_installation(server_name, server_type_name, true);
+        if config_name != default_name {
+            let recorded = ManagedServers::new()
+                .and_then(|managed| managed.set_instance_of(&config_name, &default_name));
+            if let Err(e) = recorded {
+                tracing::warn!("Failed to record {} as an instance: {}", config_name, e);
+            }
+        }
 
         println!(
             "\n{} Successfully installed {} to {} client(s)",
@@ -249,6 +534,19 @@ impl InstallCommand {
             clients.len()
         );
 
+        if !self.quiet || progress::is_enabled() {
+            let examples = match &server_type {
+                ServerType::Npm { package, version } => npm_examples(package, version.as_deref()),
+                _ => Vec::new(),
+            };
+            let next_steps = NextSteps::new(&config_name, clients, examples);
+            progress.next_steps(&next_steps);
+            if !self.quiet {
+                println!();
+                print!("{next_steps}");
+            }
+        }
+
         Ok(())
     }
 
@@ -281,16 +579,24 @@ impl InstallCommand {
     /// installer.execute_batch("servers.txt")?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
+    #[tracing::instrument(name = "install_batch", skip(self))]
     pub fn execute_batch(&mut self, batch_file: &str) -> Result<()> {
+        let progress = Progress::overall("batch");
+        println!(
+            "{} Installing servers from batch file {}",
+            "→".green(),
+            batch_file.cyan()
+        );
         let batch_content = fs::read_to_string(batch_file).map_err(|e| {
             McpError::Other(anyhow::anyhow!(
                 "Failed to read batch file '{}': {}",
                 batch_file,
                 e
             ))
-        })?;
-
-        let batch_config = Self::parse_batch_file(&batch_content)?;
+        });
+        let batch_config = batch_content
+            .and_then(|content| Self::parse_batch_file(batch_file, &content))
+            .inspect_err(|e| progress.error(e))?;
 
         if batch_config.is_empty() {
             return Err(McpError::Other(anyhow::anyhow!(
@@ -304,15 +610,27 @@ impl InstallCommand {
             batch_config.len()
         );
 
+        if !self.dry_run {
+            progress.step("download", 0, "Downloading servers");
+            self.prefetch_downloads(batch_config.iter().map(|entry| &entry.server));
+        }
+
+        let total = batch_config.len();
         let mut success_count = 0;
         let mut failure_count = 0;
         let mut failures = Vec::new();
 
-        for (server_name, server_config) in batch_config {
+        for (index, entry) in batch_config.into_iter().enumerate() {
+            let server_name = entry.server;
             println!("\n{} Installing {}", "→".green(), server_name.cyan());
+            progress.step(
+                "server",
+                step_percent(0, 100, index, total),
+                format!("Installing {server_name} ({}/{total})", index + 1),
+            );
 
             // Set config overrides for this server
-            self.config_overrides = server_config;
+            self.config_overrides = entry.config;
 
             match self.execute(&server_name) {
                 Ok(()) => {
@@ -336,70 +654,79 @@ impl InstallCommand {
             for (server, error) in failures {
                 println!("  • {}: {}", server.cyan(), error);
             }
-            return Err(McpError::Other(anyhow::anyhow!(
+            let error = McpError::Other(anyhow::anyhow!(
                 "{} out of {} installations failed",
                 failure_count,
                 success_count + failure_count
-            )));
+            ));
+            progress.error(&error);
+            return Err(error);
         }
 
+        progress.done(format!("Installed {success_count} server(s)"));
         Ok(())
     }
 
-    fn parse_batch_file(content: &str) -> Result<HashMap<String, HashMap<String, String>>> {
-        let mut servers = HashMap::new();
-        let mut current_server: Option<String> = None;
-        let mut current_config = HashMap::new();
-
-        for line in content.lines() {
-            let line = line.trim();
-
-            // Skip empty lines and comments
-            if line.is_empty() || line.starts_with('#') {
-                continue;
-            }
-
-            // Check if this is a server declaration
-            if line.starts_with('[') && line.ends_with(']') {
-                // Save previous server if exists
-                if let Some(server_name) = current_server.take() {
-                    servers.insert(server_name, current_config.clone());
-                    current_config.clear();
-                }
-
-                // Start new server
-                current_server = Some(line[1..line.len() - 1].to_string());
-                continue;
-            }
-
-            // Parse key=value configuration
-            if let Some((key, value)) = line.split_once('=') {
-                current_config.insert(key.trim().to_string(), value.trim().to_string());
-            } else {
-                return Err(McpError::Other(anyhow::anyhow!(
-                    "Invalid line in batch file: '{}'. Expected key=value or [server-name]",
-                    line
-                )));
-            }
+    /// Start the batch's binary downloads and image pulls together, when there are
+    /// several of them (see [`crate::server::prefetch`]).
+    ///
+    /// Only sources that would install without a security prompt are fetched early;
+    /// anything that fails here is fetched again when its server is installed.
+    fn prefetch_downloads<'a>(&self, server_names: impl Iterator<Item = &'a String>) {
+        let items: Vec<PrefetchItem> = server_names
+            .filter(|name| {
+                self.perform_security_validation(name)
+                    .is_ok_and(|validation| validation.is_safe())
+            })
+            .filter_map(|name| PrefetchItem::from_server_type(&detect_server_type(name)))
+            .collect();
+        if items.len() < 2 {
+            return;
         }
 
-        // Save the last server
-        if let Some(server_name) = current_server {
-            servers.insert(server_name, current_config);
+        let policy = Preferences::load().unwrap_or_default().downloads;
+        println!(
+            "{} Downloading {} server(s), {} at a time",
+            "→".green(),
+            items.len(),
+            policy.max_concurrent().min(items.len())
+        );
+        let prefetcher = Prefetcher::new(policy, self.cache_manager.downloads_dir());
+        for result in prefetcher.run(items) {
+            if let Some(error) = &result.error {
+                println!(
+                    "  {} Could not download {} ahead of time: {}",
+                    "⚠".yellow(),
+                    result.item.label(),
+                    error
+                );
+            } else if self.verbose {
+                eprintln!(
+                    "{} Downloaded {} ({} attempt(s))",
+                    "ℹ".blue(),
+                    result.item.label(),
+                    result.attempts
+                );
+            }
         }
+    }
 
-        Ok(servers)
+    fn parse_batch_file(batch_file: &str, content: &str) -> Result<Vec<BatchEntry>> {
+        parse_batch_file(batch_file, content).map_err(|e| McpError::Other(e.into()))
     }
 
     fn validate_server_security(&self, server_name: &str) -> Result<()> {
         let validation = self.perform_security_validation(server_name)?;
         self.log_security_validation(server_name, &validation);
-        self.handle_security_warnings(&validation)?;
+        self.handle_security_warnings(server_name, &validation)?;
         Ok(())
     }
 
     fn perform_security_validation(&self, server_name: &str) -> Result<SecurityValidation> {
-        let result = if server_name.starts_with("http://") || server_name.starts_with("https://") {
+        let result = if let Some(specifier) = crate::server::deno::deno_specifier(server_name) {
+            // Deno script or jsr package
+            self.security_validator.validate_deno_specifier(specifier)
+        } else if server_name.starts_with("http://") || server_name.starts_with("https://") {
             // Direct URL
             self.security_validator.validate_url(server_name)
         } else if server_name.starts_with("docker:") {
@@ -436,7 +763,11 @@ impl InstallCommand {
         );
     }
 
-    fn handle_security_warnings(&self, validation: &SecurityValidation) -> Result<()> {
+    fn handle_security_warnings(
+        &self,
+        server_name: &str,
+        validation: &SecurityValidation,
+    ) -> Result<()> {
         if validation.warnings.is_empty() {
             if self.verbose {
                 println!("{} Security validation passed", "✓".green());
@@ -444,6 +775,12 @@ impl InstallCommand {
             return Ok(());
         }
 
+        if self.strict {
+            return Err(McpError::Other(anyhow::Error::new(
+                SecurityViolation::from_validation(server_name, validation),
+            )));
+        }
+
         self.display_security_warnings(&validation.warnings);
 
         if validation.should_block() {
@@ -453,12 +790,39 @@ impl InstallCommand {
         }
 
         if !validation.is_safe() && !self.dry_run {
-            self.prompt_security_confirmation()?
+            self.prompt_security_confirmation(server_name, &validation.warnings)?
         }
 
         Ok(())
     }
 
+    /// Warn about broad permissions granted to a Deno server
+    fn check_deno_permissions(
+        &self,
+        server_name: &str,
+        config: &HashMap<String, String>,
+    ) -> Result<()> {
+        use crate::server::deno::DenoServer;
+
+        let flags = DenoServer::permission_flags(config);
+        let validation = self.security_validator.validate_deno_permissions(&flags);
+        self.handle_security_warnings(server_name, &validation)
+    }
+
+    /// In strict mode, binaries must come with a checksum to verify the download against
+    fn check_binary_checksum(&self, server_name: &str, server_type: &ServerType) -> Result<()> {
+        if let ServerType::Binary { checksum: None, .. } = server_type {
+            if self.strict {
+                return Err(McpError::Other(anyhow::Error::new(SecurityViolation::new(
+                    SecurityFailureClass::UnsignedBinary,
+                    server_name,
+                    "Binary downloads must have a checksum in strict mode",
+                ))));
+            }
+        }
+        Ok(())
+    }
+
     fn display_security_warnings(&self, warnings: &[String]) {
         println!(
             "{} {}",
@@ -470,7 +834,7 @@ impl InstallCommand {
         }
     }
 
-    fn prompt_security_confirmation(&self) -> Result<()> {
+    fn prompt_security_confirmation(&self, server_name: &str, warnings: &[String]) -> Result<()> {
         println!();
         let proceed = Confirm::new()
             .with_prompt("Do you want to proceed despite these warnings?")
@@ -483,6 +847,7 @@ impl InstallCommand {
                 "Installation cancelled by user due to security warnings."
             )));
         }
+        audit_log::record_override(server_name, warnings);
         Ok(())
     }
 
@@ -517,15 +882,82 @@ impl InstallCommand {
                 };
                 Ok(Box::new(DockerServer::new(&docker_spec)?))
             }
+            ServerType::Cargo {
+                crate_name,
+                version,
+            } => {
+                use crate::server::cargo::CargoServer;
+                Ok(Box::new(CargoServer::from_crate(
+                    crate_name.clone(),
+                    version.clone(),
+                )))
+            }
+            ServerType::Deno { specifier } => {
+                use crate::server::deno::DenoServer;
+                Ok(Box::new(DenoServer::new(specifier)))
+            }
+            ServerType::Jar { path } => {
+                use crate::server::jar::JarServer;
+                Ok(Box::new(JarServer::new(path)))
+            }
+            ServerType::Dotnet { package, version } => {
+                use crate::server::dotnet::DotnetToolServer;
+                Ok(Box::new(DotnetToolServer::from_package(
+                    package.clone(),
+                    version.clone(),
+                )))
+            }
         }
     }
 
+    fn install_cargo_crate(&self, crate_name: &str, version: Option<String>) -> Result<()> {
+        use crate::server::cargo::CargoServer;
+
+        let server = CargoServer::from_crate(crate_name.to_string(), version);
+        if self.dry_run {
+            let args = server.install_args()?;
+            println!("{} Would run: cargo {}", "ℹ".blue(), args.join(" "));
+            return Ok(());
+        }
+
+        server.install()?;
+        Ok(())
+    }
+
+    fn install_dotnet_tool(&self, package: &str, version: Option<String>) -> Result<()> {
+        use crate::server::dotnet::DotnetToolServer;
+
+        let server = DotnetToolServer::from_package(package.to_string(), version);
+        if self.dry_run {
+            let args = server.install_args()?;
+            println!("{} Would run: dotnet {}", "ℹ".blue(), args.join(" "));
+            return Ok(());
+        }
+
+        if server.installed()?.is_none() {
+            server.install()?;
+        }
+        Ok(())
+    }
+
+    fn install_binary(&self, url: &str, checksum: Option<String>) -> Result<Box<dyn McpServer>> {
+        use crate::server::binary::BinaryServer;
+
+        let mut server = BinaryServer::from_url(url, checksum)?;
+        server.download_and_install(Some(&self.cache_manager))?;
+        Ok(Box::new(server))
+    }
+
     pub fn get_dependency_name(dependency: &Dependency) -> &'static str {
         match dependency {
             Dependency::NodeJs { .. } => "Node.js",
             Dependency::Python { .. } => "Python",
             Dependency::Docker { .. } => "Docker",
             Dependency::Git => "Git",
+            Dependency::Rust { .. } => "Rust",
+            Dependency::Deno { .. } => "Deno",
+            Dependency::Java { .. } => "Java",
+            Dependency::Dotnet { .. } => ".NET",
         }
     }
 
@@ -547,7 +979,11 @@ impl InstallCommand {
         dep_name: &str,
         check: &crate::deps::DependencyCheck,
     ) -> Result<()> {
-        println!("  {} {} is not installed", "✗".red(), dep_name);
+        println!(
+            "  {} {}",
+            "✗".red(),
+            tr("{dependency} is not installed").replace("{dependency}", dep_name)
+        );
 
         if let Some(instructions) = &check.install_instructions {
             let required_version = match &check.dependency {
@@ -567,21 +1003,50 @@ impl InstallCommand {
         )))
     }
 
+    #[tracing::instrument(skip_all, fields(server = %server.metadata().name))]
     fn check_dependencies(&mut self, server: &dyn McpServer) -> Result<()> {
+        let dependency = server.dependency();
+
+        // Servers sharing a requirement (e.g. Node.js for every npm server in a batch)
+        // only show the check once
+        if let Some(check) = self.dependency_checks.cached(&*dependency) {
+            if self.verbose {
+                eprintln!(
+                    "{} {} already checked",
+                    "ℹ".blue(),
+                    Self::get_dependency_name(&check.dependency)
+                );
+            }
+            return Ok(());
+        }
+
         println!("{} Checking dependencies...", "🔍".blue());
 
-        let dependency = server.dependency();
-        let check = dependency.check()?;
+        // Probes are time-limited; the spinner shows the install is still alive while
+        // a slow one (such as `docker info` waking Docker Desktop) runs
+        let spinner = ProgressBar::new_spinner();
+        if let Some(checked) = dependency.checked_dependency() {
+            spinner.set_message(format!(
+                "Checking {}...",
+                Self::get_dependency_name(&checked)
+            ));
+        }
+        spinner.enable_steady_tick(Duration::from_millis(100));
+        let check = self.dependency_checks.check(&*dependency);
+        spinner.finish_and_clear();
+        let check = check?;
 
         let dep_name = Self::get_dependency_name(&check.dependency);
 
-        // Cache the result for future use
-        if let Err(e) = self
-            .cache_manager
-            .cache_dependency_status(check.dependency.clone(), check.status.clone())
-        {
-            if self.verbose {
-                eprintln!("{} Failed to cache dependency status: {}", "⚠".yellow(), e);
+        // Cache the result for future use; an unresponsive probe says nothing lasting
+        if !matches!(check.status, DependencyStatus::Unresponsive { .. }) {
+            if let Err(e) = self
+                .cache_manager
+                .cache_dependency_status(check.dependency.clone(), check.status.clone())
+            {
+                if self.verbose {
+                    eprintln!("{} Failed to cache dependency status: {}", "⚠".yellow(), e);
+                }
             }
         }
 
@@ -640,49 +1105,66 @@ impl InstallCommand {
                     solution
                 )))
             }
+            DependencyStatus::Unresponsive {
+                command,
+                timeout_secs,
+            } => self.handle_unresponsive_dependency(dep_name, command, *timeout_secs),
         }
     }
 
-    fn attempt_auto_install(
-        &mut self,
+    /// A probe timed out. The check can be skipped when someone is at the terminal to
+    /// decide; otherwise the install stops with advice rather than waiting forever.
+    fn handle_unresponsive_dependency(
+        &self,
         dep_name: &str,
-        check: &crate::deps::DependencyCheck,
+        command: &str,
+        timeout_secs: u64,
     ) -> Result<()> {
+        let advice = unresponsive_advice(command);
         println!(
-            "  {} Attempting to auto-install {}...",
-            "🚀".blue(),
-            dep_name
+            "  {} {} is unresponsive: '{}' did not answer within {}s",
+            "⚠".yellow(),
+            dep_name,
+            command,
+            timeout_secs
         );
-
-        let mut installer = DependencyInstaller::new();
-        if self.dry_run {
-            installer = installer.with_dry_run();
-        }
-        if self.auto_install_deps {
-            installer = installer.with_auto_confirm();
+        println!("  {} {}", "💡".blue(), advice);
+
+        if std::io::stdin().is_terminal() {
+            let skip = Confirm::new()
+                .with_prompt(format!(
+                    "Skip the {dep_name} check and continue? The server may not start until {dep_name} responds"
+                ))
+                .default(false)
+                .interact()
+                .map_err(|e| {
+                    McpError::Other(anyhow::anyhow!("Failed to read user input: {}", e))
+                })?;
+            if skip {
+                println!("  {} Skipped the {} check", "⚠".yellow(), dep_name);
+                return Ok(());
+            }
         }
 
-        // Show elevation warning if needed
-        if let Some(warning) = installer.get_elevation_warning(&check.dependency) {
-            println!("  {} {}", "⚠".yellow(), warning);
-        }
+        Err(McpError::Other(anyhow::anyhow!(
+            "{} did not respond: '{}' timed out after {}s. {}",
+            dep_name,
+            command,
+            timeout_secs,
+            advice
+        )))
+    }
 
-        match installer.install_dependency(check) {
-            Ok(true) => {
-                println!("  {} Successfully installed {}", "✅".green(), dep_name);
-                Ok(())
-            }
-            Ok(false) => {
-                println!("  {} Could not auto-install {}", "⚠".yellow(), dep_name);
-                self.show_suggestions_for_dependency(dep_name, &check.dependency)?;
-                Self::handle_missing_dependency(dep_name, check)
-            }
-            Err(e) => {
-                println!("  {} Auto-installation failed: {}", "❌".red(), e);
-                self.show_suggestions_for_dependency(dep_name, &check.dependency)?;
-                Self::handle_missing_dependency(dep_name, check)
-            }
+    fn attempt_auto_install(
+        &mut self,
+        dep_name: &str,
+        check: &crate::deps::DependencyCheck,
+    ) -> Result<()> {
+        if auto_install_dependency(check, self.dry_run, self.yes)? {
+            return Ok(());
         }
+        self.show_suggestions_for_dependency(dep_name, &check.dependency)?;
+        Self::handle_missing_dependency(dep_name, check)
     }
 
     fn show_suggestions_for_dependency(
@@ -765,6 +1247,20 @@ impl InstallCommand {
             return Ok(vec![]);
         }
 
+        match &self.client_target {
+            ClientTarget::Named(names) => {
+                return Ok(self
+                    .client_registry
+                    .get_installed_by_names(names)
+                    .map_err(McpError::Other)?
+                    .into_iter()
+                    .map(|client| client.name().to_string())
+                    .collect());
+            }
+            ClientTarget::All => return Ok(installed_clients),
+            ClientTarget::Prompt => {}
+        }
+
         if installed_clients.len() == 1 {
             let client_name = &installed_clients[0];
             let confirm = Confirm::new()
@@ -779,9 +1275,10 @@ impl InstallCommand {
             }
         } else {
             println!("\n{}", "Select MCP clients to install to:".blue());
-            let selections = dialoguer::MultiSelect::new()
+            let names: Vec<&str> = installed_clients.iter().map(String::as_str).collect();
+            let selections = MultiSelect::new()
                 .items(&installed_clients)
-                .defaults(&vec![true; installed_clients.len()])
+                .defaults(&detect_default_selection(&names))
                 .interact()?;
 
             Ok(selections
@@ -881,6 +1378,7 @@ impl InstallCommand {
         for field in all_fields {
             self.process_config_field(&mut config, field, metadata, is_non_interactive)?;
         }
+        self.apply_config_defaults(&mut config);
 
         self.validate_final_config(server, &config)?;
         Ok(config)
@@ -904,7 +1402,15 @@ impl InstallCommand {
         if self.verbose {
             eprintln!("{} No configuration required for this server", "ℹ".blue());
         }
-        Ok(self.initialize_config())
+        let mut config = self.initialize_config();
+        self.apply_config_defaults(&mut config);
+        Ok(config)
+    }
+
+    fn apply_config_defaults(&self, config: &mut HashMap<String, String>) {
+        for (key, value) in &self.config_defaults {
+            config.entry(key.clone()).or_insert_with(|| value.clone());
+        }
     }
 
     fn display_config_mode(&self, is_non_interactive: bool) {
@@ -971,7 +1477,7 @@ impl InstallCommand {
     }
 
     fn handle_non_interactive_field(&self, field: &ConfigField, is_required: bool) -> Result<()> {
-        if is_required {
+        if is_required && !self.config_defaults.contains_key(&field.name) {
             return Err(McpError::Other(anyhow::anyhow!(
                 "Required configuration field '{}' not provided via --config",
                 field.name
@@ -1016,11 +1522,44 @@ impl InstallCommand {
         Ok(())
     }
 
+    /// Refuse to replace a different server that already uses the instance name
+    fn check_instance_name_free(
+        &self,
+        name: &str,
+        package: &str,
+        clients: &[String],
+    ) -> Result<()> {
+        let probe = ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["--yes".to_string(), package.to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        let taken = clients_with_name_taken(
+            clients
+                .iter()
+                .filter_map(|client| self.client_registry.get_by_name(client)),
+            name,
+            &probe,
+        );
+        if taken.is_empty() {
+            return Ok(());
+        }
+        Err(McpError::Other(anyhow::anyhow!(
+            "'{}' is already a different server in {}; choose another name with --as",
+            name,
+            taken.join(", ")
+        )))
+    }
+
     fn install_to_client(
         &mut self,
         client_name: &str,
         server_name: &str,
         config: &HashMap<String, String>,
+        args_template: &[String],
+        launch: &(String, Vec<String>),
+        command_fields: &[String],
     ) -> Result<()> {
         let client = self
             .client_registry
@@ -1041,23 +1580,57 @@ impl InstallCommand {
 
         println!("{} Installing to {}...", "→".green(), client_name.cyan());
 
+        let (command, mut args) = launch.clone();
+        // Values the server or its launcher take as arguments don't also go into its environment
+        args.extend(args_template::render(args_template, config));
+        let consumed = args_template::placeholders(args_template);
+        let env = config
+            .iter()
+            .filter(|(key, _)| !consumed.contains(*key) && !command_fields.contains(*key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
         let server_config = ServerConfig {
-            command: "npx".to_string(), // This will be properly set by the server
-            args: vec![
-                "--yes".to_string(),
-                server_name.to_string(),
-                "--stdio".to_string(),
-            ],
-            env: config.clone(),
+            command,
+            args,
+            env,
+            cwd: None,
         };
+        let capabilities = client.capabilities();
+        let (mut server_config, mut placements) =
+            place_secrets(server_config, capabilities, &|name| {
+                std::env::var(name).ok()
+            });
+        // Prompting only makes sense when the user is answering questions
+        if capabilities.input_prompts && self.config_overrides.is_empty() && !self.dry_run {
+            let secrets = literal_secrets(&server_config);
+            if !secrets.is_empty() && offer_input_prompts(client_name, &secrets) {
+                let (prompted, input_placements) = prompt_for_secrets(server_config, server_name);
+                server_config = prompted;
+                placements.extend(input_placements);
+            }
+        }
+        for (name, placement) in placements {
+            if let Some(note) = placement.describe(&name) {
+                println!("  {} {}", "ℹ".blue(), note);
+            }
+        }
 
         // Use ConfigManager to apply configuration with automatic backup
         match self
             .config_manager
-            .apply_config(client, server_name, server_config)
+            .apply_config(client, server_name, server_config.clone())
         {
             Ok(snapshot) => {
                 logging::log_config_change(client_name, server_name, "add");
+                // Instances are recorded so list, update and remove can tell them apart
+                if self.instance_name.is_some() {
+                    let recorded = ManagedServers::new().and_then(|managed| {
+                        managed.record_added(server_name, &server_config, client_name)
+                    });
+                    if let Err(e) = recorded {
+                        tracing::warn!("Failed to record managed server {}: {}", server_name, e);
+                    }
+                }
                 println!("  {} Installed to {}", "✓".green(), client_name);
                 if self.verbose {
                     println!(
@@ -1077,6 +1650,83 @@ impl InstallCommand {
     }
 }
 
+/// Install a missing dependency with the system package manager, prerequisites first,
+/// then look for it again on a refreshed PATH.
+///
+/// Shared by `mcp install` and `mcp add --auto-install-deps`. Returns `Ok(false)` when
+/// nothing was installed, so the caller can fall back to its own instructions, and
+/// an error when the install reported success but the dependency can't be found.
+pub fn auto_install_dependency(
+    check: &crate::deps::DependencyCheck,
+    dry_run: bool,
+    yes: bool,
+) -> Result<bool> {
+    let dep_name = InstallCommand::get_dependency_name(&check.dependency);
+    println!(
+        "  {} Attempting to auto-install {}...",
+        "🚀".blue(),
+        dep_name
+    );
+
+    let mut installer = DependencyInstaller::new();
+    if dry_run {
+        installer = installer.with_dry_run();
+    }
+    if yes {
+        installer = installer.with_auto_confirm();
+    }
+    match InstallLog::new() {
+        Ok(log) => installer = installer.with_log(log),
+        Err(e) => tracing::warn!("Dependency install history unavailable: {e:#}"),
+    }
+
+    // Show elevation warning if needed
+    if let Some(warning) = installer.get_elevation_warning(&check.dependency) {
+        println!("  {} {}", "⚠".yellow(), warning);
+    }
+
+    match installer.install_with_prerequisites(check) {
+        Ok(true) if dry_run => Ok(true),
+        Ok(true) => verify_auto_install(dep_name, &check.dependency).map(|()| true),
+        Ok(false) => {
+            println!("  {} Could not auto-install {}", "⚠".yellow(), dep_name);
+            Ok(false)
+        }
+        Err(e) => {
+            println!("  {} Auto-installation failed: {}", "❌".red(), e);
+            Ok(false)
+        }
+    }
+}
+
+fn verify_auto_install(dep_name: &str, dependency: &Dependency) -> Result<()> {
+    println!("  {} Verifying {} installation...", "🔍".blue(), dep_name);
+
+    let outcome = verify_installation(dependency, 3, std::time::Duration::from_secs(1));
+    if let Some(guidance) = outcome.guidance(dep_name) {
+        println!("  {} {}", "⚠".yellow(), guidance);
+    }
+
+    match outcome {
+        VerificationOutcome::Verified { path } => {
+            println!(
+                "  {} Successfully installed {} ({})",
+                "✅".green(),
+                dep_name,
+                path.display()
+            );
+            Ok(())
+        }
+        // The client launches the server in its own environment, so the
+        // configuration is still valid once the user restarts their terminal/client.
+        VerificationOutcome::NeedsNewTerminal { .. } => Ok(()),
+        VerificationOutcome::NotFound => Err(McpError::Other(anyhow::anyhow!(
+            "{} was installed but could not be found on PATH",
+            dep_name
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1099,6 +1749,10 @@ mod tests {
                 install_instructions: None,
             })
         }
+
+        fn checked_dependency(&self) -> Option<Dependency> {
+            Some(self.dependency.clone())
+        }
     }
 
     // Mock server for testing
@@ -1151,6 +1805,15 @@ mod tests {
         assert!(!installer.auto_install_deps);
     }
 
+    #[test]
+    fn test_with_yes() {
+        let installer = InstallCommand::new(false);
+        assert!(!installer.yes);
+
+        let installer = InstallCommand::new(false).with_yes(true);
+        assert!(installer.yes);
+    }
+
     #[test]
     fn test_with_dry_run() {
         let installer = InstallCommand::new(false).with_dry_run(true);
@@ -1314,10 +1977,14 @@ port=8080
 [server3]
 "#;
 
-        let result = InstallCommand::parse_batch_file(content);
+        let result = InstallCommand::parse_batch_file("servers.txt", content);
         assert!(result.is_ok());
 
-        let servers = result.unwrap();
+        let servers: HashMap<_, _> = result
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.server, entry.config))
+            .collect();
         assert_eq!(servers.len(), 3);
 
         let server1_config = &servers["server1"];
@@ -1334,8 +2001,52 @@ port=8080
     #[test]
     fn test_parse_batch_file_invalid() {
         let content = "invalid line without equals";
-        let result = InstallCommand::parse_batch_file(content);
+        let result = InstallCommand::parse_batch_file("servers.txt", content);
         assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("servers.txt:1:1"));
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_warnings() {
+        let installer = InstallCommand::new(false).with_strict(true);
+
+        let error = installer
+            .validate_server_security("https://unknown-domain.com/server")
+            .unwrap_err();
+        let McpError::Other(error) = error else {
+            panic!("expected a security violation");
+        };
+        let violation = error.downcast_ref::<SecurityViolation>().unwrap();
+        assert_eq!(violation.class, SecurityFailureClass::UntrustedSource);
+
+        assert!(installer
+            .validate_server_security("@modelcontextprotocol/server-filesystem")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_unsigned_binaries() {
+        let unsigned = ServerType::Binary {
+            url: "https://github.com/org/server/releases/download/v1/server".to_string(),
+            checksum: None,
+        };
+        let signed = ServerType::Binary {
+            url: "https://github.com/org/server/releases/download/v1/server".to_string(),
+            checksum: Some("abc123".to_string()),
+        };
+
+        let lenient = InstallCommand::new(false);
+        assert!(lenient.check_binary_checksum("server", &unsigned).is_ok());
+
+        let strict = InstallCommand::new(false).with_strict(true);
+        assert!(strict.check_binary_checksum("server", &signed).is_ok());
+        let Err(McpError::Other(error)) = strict.check_binary_checksum("server", &unsigned) else {
+            panic!("expected a security violation");
+        };
+        assert_eq!(
+            error.downcast_ref::<SecurityViolation>().unwrap().class,
+            SecurityFailureClass::UnsignedBinary
+        );
     }
 
     #[test]
@@ -1437,6 +2148,7 @@ port=8080
                 description: None,
                 default: None,
             }],
+            args_template: vec![],
         };
 
         let fields = installer.collect_all_fields(&metadata);
@@ -1521,6 +2233,7 @@ port=8080
             },
             required_config: vec![field1.clone()],
             optional_config: vec![field2.clone()],
+            args_template: vec![],
         };
 
         assert!(installer.is_required_field(&field1, &metadata));
@@ -1637,6 +2350,7 @@ port=8080
                 },
                 required_config: vec![],
                 optional_config: vec![],
+                args_template: vec![],
             },
             dependency: Dependency::NodeJs { min_version: None },
         };
@@ -1648,6 +2362,35 @@ port=8080
         let _ = result;
     }
 
+    #[test]
+    fn test_check_dependencies_shared_across_servers() {
+        let mut installer = InstallCommand::new(false);
+        let server = |name: &str, dependency: Dependency| MockServer {
+            metadata: ServerMetadata {
+                name: name.to_string(),
+                description: None,
+                server_type: ServerType::Npm {
+                    package: name.to_string(),
+                    version: None,
+                },
+                required_config: vec![],
+                optional_config: vec![],
+                args_template: vec![],
+            },
+            dependency,
+        };
+
+        for name in ["a", "b", "c"] {
+            let server = server(name, Dependency::NodeJs { min_version: None });
+            installer.check_dependencies(&server).unwrap();
+        }
+        assert_eq!(installer.dependency_checks.len(), 1);
+
+        let python = server("d", Dependency::Python { min_version: None });
+        installer.check_dependencies(&python).unwrap();
+        assert_eq!(installer.dependency_checks.len(), 2);
+    }
+
     #[test]
     fn test_prompt_string_field() {
         let _installer = InstallCommand::new(false);
@@ -1692,6 +2435,7 @@ port=8080
             },
             required_config: vec![],
             optional_config: vec![field.clone()],
+            args_template: vec![],
         };
 
         // With override already in config
@@ -1743,4 +2487,56 @@ port=8080
         // Just ensure the method handles all field types
         let _method = InstallCommand::prompt_for_field_value;
     }
+
+    #[test]
+    fn test_install_to_client_writes_the_servers_own_command() {
+        use crate::server::docker::DockerServer;
+        use crate::test_utils::mocks::MockClientBuilder;
+        use crate::utils::clock::SystemClock;
+        use crate::utils::filesystem::RealFileSystem;
+        use std::sync::Arc;
+
+        let temp_dir = TempDir::new().unwrap();
+        let mut installer = InstallCommand::new(false);
+        installer.config_manager = ConfigManager::open(
+            temp_dir.path().to_path_buf(),
+            Arc::new(SystemClock),
+            Arc::new(RealFileSystem),
+        )
+        .unwrap();
+        installer
+            .client_registry
+            .register(Box::new(MockClientBuilder::new("Mock").build()));
+
+        let server = DockerServer::new("mcp/postgres:16").unwrap();
+        let config = HashMap::from([
+            ("volumes".to_string(), "/data:/data".to_string()),
+            ("LOG_LEVEL".to_string(), "debug".to_string()),
+        ]);
+        let launch = server.generate_command_with_config(&config).unwrap();
+        installer
+            .install_to_client(
+                "Mock",
+                "postgres",
+                &config,
+                &[],
+                &launch,
+                &server.command_config_fields(),
+            )
+            .unwrap();
+
+        let servers = installer
+            .client_registry
+            .get_by_name("Mock")
+            .unwrap()
+            .list_servers()
+            .unwrap();
+        let written = &servers["postgres"];
+        assert_eq!(written.command, "docker");
+        assert!(written.args.contains(&"/data:/data".to_string()));
+        assert_eq!(written.args.last().unwrap(), "mcp/postgres:16");
+        // Docker options become arguments, everything else stays in the environment
+        assert_eq!(written.env.len(), 1);
+        assert_eq!(written.env["LOG_LEVEL"], "debug");
+    }
 }
@@ -29,15 +29,35 @@
 //! The library is organized into several key modules:
 //!
 //! - [`client`]: MCP client implementations (Claude Desktop, VS Code, etc.)
-//! - [`server`]: MCP server types (NPM, Docker, Binary, Python)
+//! - [`server`]: MCP server types (NPM, Docker, Binary, Python, Cargo, Deno, JAR, .NET tools)
 //! - [`deps`]: Dependency checking and installation instructions
 //! - [`install`]: Main installation command logic
 //! - [`setup`]: Environment setup and verification
 //! - [`security`]: Security validation for server sources
+//! - [`security_report`]: SARIF and JSON reports of security findings for CI
 //! - [`error`]: Error types and handling
-//! - [`runner`]: Core server execution logic
+//! - [`runner`]: Core server execution logic, run history and crash reports
 //! - [`config`]: Configuration management utilities
 //! - [`logging`]: Structured logging support
+//! - [`remote`]: Remote (streamable HTTP) servers and the stdio bridge
+//! - [`audit`]: Plaintext secret detection and migration into the keychain
+//! - [`audit_log`]: Append-only log of executed commands, modified files and accepted security warnings (`mcp audit`)
+//! - [`graph`]: Diagrams of clients, servers and the runtimes they depend on
+//! - [`export_script`]: Shell scripts that recreate the configured servers
+//! - [`docker_prune`]: Cleanup of containers and images left by removed Docker servers
+//! - [`purge`]: Files and secrets a removed server leaves behind
+//! - [`state`]: Encryption at rest for mcp-helper's own state files
+//! - [`debug_env`]: Comparison of terminal and client launch environments
+//! - [`debug_snapshot`]: Fixture bundles of client configs for bug reports (`mcp debug snapshot`)
+//! - [`apply`]: Declarative desired state for client configs (`mcp apply`)
+//! - [`info`]: Details of a server from local records and registries (`mcp info`)
+//! - [`check`]: Drift between the managed servers record and client configs (`mcp check --sync`)
+//! - [`test_server`]: Built-in echo server for checking client setup (`mcp test-server`)
+//! - [`uninstall`]: Removing a server and what was created for it (`mcp uninstall`)
+//! - [`outdated`]: Pinned servers with newer releases, including moved Docker tags (`mcp outdated`)
+//! - [`inventory`]: Machine-readable inventory of clients and servers for fleet tools (`mcp inventory`)
+//! - [`list`]: Servers configured across all clients, with their kind and version (`mcp list`)
+//! - [`playground`]: Interactive tool calls against a configured server (`mcp playground`)
 //!
 //! ## Platform Support
 //!
@@ -47,20 +67,46 @@
 //! - **Linux**: Ubuntu, Debian, CentOS, Fedora, Arch, Alpine (x64, ARM64)
 
 pub mod add;
+pub mod apply;
+pub mod audit;
+pub mod audit_log;
 pub mod cache;
+pub mod check;
 pub mod client;
 pub mod config;
 pub mod config_commands;
 pub mod core;
+pub mod debug_env;
+pub mod debug_snapshot;
 pub mod deps;
+pub mod docker_prune;
 pub mod doctor;
 pub mod error;
+pub mod export_script;
+pub mod graph;
+pub mod help_server;
+pub mod import;
+pub mod info;
 pub mod install;
+pub mod inventory;
+pub mod list;
 pub mod logging;
+pub mod outdated;
+pub mod playground;
+pub mod purge;
+pub mod remote;
 pub mod runner;
 pub mod security;
+pub mod security_report;
 pub mod server;
+pub mod service;
 pub mod setup;
+pub mod state;
+pub mod status;
+pub mod sync;
+pub mod test_server;
+pub mod uninstall;
+pub mod update;
 pub mod utils;
 
 // Test utilities module (always available in development/test builds)
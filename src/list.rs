@@ -0,0 +1,343 @@
+//! `mcp list`: every configured server, across all installed clients.
+//!
+//! A server configured the same way in several clients is one entry listing
+//! those clients; if the clients disagree on what it runs, each variant is its
+//! own entry. Every entry shows the kind of server (`npm`, `docker`, `python`,
+//! `binary` or `remote`), what it runs and its version where that is known.
+//! `mcp config list` still shows each client's config separately.
+
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::client::{detect_clients, ClientRegistry, McpClient};
+use crate::config::{ManagedServer, ManagedServers, ServerKind};
+use crate::doctor::OutputFormat;
+use crate::error::McpError;
+use crate::server::publisher::PublisherVerification;
+use crate::update::UpdateChannel;
+use crate::utils::pattern::NamePattern;
+
+/// One server, with the clients it is configured in
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ListedServer {
+    pub name: String,
+    /// `npm`, `docker`, `python`, `binary` or `remote`
+    pub kind: &'static str,
+    /// The npm or Python package, Docker image, executable or remote URL
+    pub source: String,
+    pub version: Option<String>,
+    pub clients: Vec<String>,
+    /// Update channel when it isn't `stable`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<UpdateChannel>,
+    /// The server's default name, for an instance added with `--as`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_of: Option<String>,
+    /// Publisher verification of the npm package when it was added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<PublisherVerification>,
+    /// The full command line, for `--verbose`
+    #[serde(skip)]
+    pub command: String,
+}
+
+/// Group the servers of `clients` by name and what they run
+pub fn collect_servers(
+    clients: &[&dyn McpClient],
+    managed: &BTreeMap<String, ManagedServer>,
+    filter: Option<&NamePattern>,
+) -> Vec<ListedServer> {
+    let mut servers: BTreeMap<(String, &'static str, String, Option<String>), ListedServer> =
+        BTreeMap::new();
+    let mut add = |client: &str, listed: ListedServer| {
+        let key = (
+            listed.name.clone(),
+            listed.kind,
+            listed.source.clone(),
+            listed.version.clone(),
+        );
+        servers
+            .entry(key)
+            .or_insert(listed)
+            .clients
+            .push(client.to_string());
+    };
+
+    for client in clients {
+        let stdio = match client.list_servers() {
+            Ok(servers) => servers,
+            Err(e) => {
+                tracing::debug!("Failed to list servers of {}: {}", client.name(), e);
+                continue;
+            }
+        };
+        for (name, config) in stdio {
+            if filter.is_some_and(|pattern| !pattern.matches(&name)) {
+                continue;
+            }
+            let kind = ServerKind::infer(&config);
+            let record = managed.get(&name);
+            add(
+                client.name(),
+                ListedServer {
+                    kind: kind.label(),
+                    source: kind.source().to_string(),
+                    version: kind.version(),
+                    clients: Vec::new(),
+                    channel: record
+                        .map(|record| record.channel)
+                        .filter(|channel| !channel.is_stable()),
+                    instance_of: record.and_then(|record| record.instance_of.clone()),
+                    publisher: record.and_then(|record| record.publisher.clone()),
+                    command: std::iter::once(config.command.as_str())
+                        .chain(config.args.iter().map(String::as_str))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    name,
+                },
+            );
+        }
+        for (name, remote) in client.list_remote_servers().unwrap_or_default() {
+            if filter.is_some_and(|pattern| !pattern.matches(&name)) {
+                continue;
+            }
+            add(
+                client.name(),
+                ListedServer {
+                    name,
+                    kind: "remote",
+                    source: remote.url.clone(),
+                    version: None,
+                    clients: Vec::new(),
+                    channel: None,
+                    instance_of: None,
+                    publisher: None,
+                    command: remote.url,
+                },
+            );
+        }
+    }
+
+    let mut listed: Vec<ListedServer> = servers.into_values().collect();
+    for server in &mut listed {
+        server.clients.sort();
+    }
+    listed
+}
+
+/// List every configured server once, with the clients it is in
+pub struct ListCommand {
+    verbose: bool,
+    output: OutputFormat,
+    filter: Option<NamePattern>,
+}
+
+impl ListCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            output: OutputFormat::Text,
+            filter: None,
+        }
+    }
+
+    pub fn with_output_format(mut self, output: OutputFormat) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Only list servers whose names match a glob (or `re:` regex) pattern
+    pub fn with_filter(mut self, pattern: Option<&str>) -> Result<Self, McpError> {
+        self.filter = pattern
+            .map(NamePattern::parse)
+            .transpose()
+            .map_err(McpError::Other)?;
+        Ok(self)
+    }
+
+    pub fn execute(&self) -> Result<(), McpError> {
+        let registry = ClientRegistry {
+            clients: detect_clients(),
+        };
+        // Channels and instances are informational, so an unreadable record just hides them
+        let managed = ManagedServers::new()
+            .and_then(|managed| managed.list())
+            .unwrap_or_default();
+        let servers = collect_servers(&registry.detect_installed(), &managed, self.filter.as_ref());
+
+        match self.output {
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&servers)
+                    .map_err(|e| McpError::Other(e.into()))?;
+                println!("{json}");
+            }
+            OutputFormat::Text => self.print(&servers),
+        }
+        Ok(())
+    }
+
+    fn print(&self, servers: &[ListedServer]) {
+        if servers.is_empty() {
+            if self.filter.is_some() {
+                println!("No MCP servers match the filter.");
+            } else {
+                println!("No MCP servers configured yet.");
+                println!();
+                println!("To configure a server, run:");
+                println!("  {}", "mcp install <server>".cyan());
+            }
+            return;
+        }
+
+        println!("{}", "📋 MCP Server Configurations".blue().bold());
+        println!();
+        let name_width = servers.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        for server in servers {
+            println!(
+                "  • {:<name_width$}  {:<7} {}{}{}{}{}",
+                server.name.yellow(),
+                server.kind,
+                server.source,
+                server
+                    .version
+                    .as_deref()
+                    .filter(|version| !server.source.ends_with(*version))
+                    .map(|version| format!(" {}", version.green()))
+                    .unwrap_or_default(),
+                match &server.channel {
+                    Some(channel) => format!(" {}", format!("[{channel}]").magenta()),
+                    None => String::new(),
+                },
+                match &server.instance_of {
+                    Some(default_name) => format!(" (instance of {default_name})")
+                        .dimmed()
+                        .to_string(),
+                    None => String::new(),
+                },
+                match &server.publisher {
+                    Some(publisher) if publisher.is_verified() => {
+                        format!(" {}", publisher.badge())
+                    }
+                    _ => String::new(),
+                },
+            );
+            println!(
+                "    {:<name_width$}{}",
+                "",
+                server.clients.join(", ").cyan()
+            );
+            if self.verbose {
+                println!("    {:<name_width$}{}", "", server.command.dimmed());
+            }
+        }
+        println!();
+        println!("Total: {} server(s)", servers.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ServerConfig;
+    use crate::test_utils::mocks::MockClientBuilder;
+    use std::collections::HashMap;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_servers_are_grouped_across_clients() {
+        let filesystem = config(
+            "npx",
+            &["--yes", "@modelcontextprotocol/server-filesystem@0.6.2"],
+        );
+        let desktop = MockClientBuilder::new("Claude Desktop")
+            .with_server("filesystem", filesystem.clone())
+            .with_server(
+                "postgres",
+                config("docker", &["run", "-i", "--rm", "mcp/postgres:13"]),
+            )
+            .build();
+        let cursor = MockClientBuilder::new("Cursor")
+            .with_server("filesystem", filesystem)
+            .with_server("fetch", config("uvx", &["mcp-server-fetch"]))
+            .build();
+
+        let servers = collect_servers(&[&desktop, &cursor], &BTreeMap::new(), None);
+        let summary: Vec<_> = servers
+            .iter()
+            .map(|s| {
+                (
+                    s.name.as_str(),
+                    s.kind,
+                    s.version.as_deref(),
+                    s.clients.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("fetch", "python", None, vec!["Cursor".to_string()]),
+                (
+                    "filesystem",
+                    "npm",
+                    Some("0.6.2"),
+                    vec!["Claude Desktop".to_string(), "Cursor".to_string()]
+                ),
+                (
+                    "postgres",
+                    "docker",
+                    Some("13"),
+                    vec!["Claude Desktop".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_differing_configs_are_listed_separately() {
+        let desktop = MockClientBuilder::new("Claude Desktop")
+            .with_server("github", config("npx", &["--yes", "@mcp/github@1.0.0"]))
+            .build();
+        let cursor = MockClientBuilder::new("Cursor")
+            .with_server("github", config("npx", &["--yes", "@mcp/github@1.1.0"]))
+            .build();
+
+        let servers = collect_servers(&[&desktop, &cursor], &BTreeMap::new(), None);
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].version.as_deref(), Some("1.0.0"));
+        assert_eq!(servers[0].clients, vec!["Claude Desktop"]);
+        assert_eq!(servers[1].version.as_deref(), Some("1.1.0"));
+    }
+
+    #[test]
+    fn test_filter_and_json_shape() {
+        let client = MockClientBuilder::new("Cursor")
+            .with_server("github-work", config("npx", &["--yes", "@mcp/github"]))
+            .with_server("slack", config("npx", &["--yes", "@mcp/slack"]))
+            .build();
+        let pattern = NamePattern::parse("github-*").unwrap();
+
+        let servers = collect_servers(&[&client], &BTreeMap::new(), Some(&pattern));
+        assert_eq!(servers.len(), 1);
+        let json = serde_json::to_value(&servers[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "github-work",
+                "kind": "npm",
+                "source": "@mcp/github",
+                "version": null,
+                "clients": ["Cursor"],
+            })
+        );
+    }
+}
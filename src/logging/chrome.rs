@@ -0,0 +1,255 @@
+//! Chrome trace event output for `--trace <file>.json`.
+//!
+//! Spans become begin/end ("B"/"E") pairs and events become instant ("i")
+//! events in the [Trace Event Format], so the file can be opened in
+//! `chrome://tracing` or <https://ui.perfetto.dev> and attached to bug reports.
+//!
+//! [Trace Event Format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+struct TraceWriter {
+    out: Box<dyn Write + Send>,
+    first: bool,
+    finished: bool,
+}
+
+impl TraceWriter {
+    fn write_event(&mut self, event: &Value) {
+        if self.finished {
+            return;
+        }
+        let separator = if self.first { "" } else { ",\n" };
+        self.first = false;
+        let _ = write!(self.out, "{separator}{event}");
+    }
+
+    fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            let _ = writeln!(self.out, "\n]");
+            let _ = self.out.flush();
+        }
+    }
+}
+
+/// Fields recorded on a span, stored in its extensions
+struct SpanFields(Map<String, Value>);
+
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), json!(format!("{value:?}")));
+    }
+}
+
+/// Layer that writes spans and events as Chrome trace events
+pub struct ChromeTraceLayer {
+    writer: Arc<Mutex<TraceWriter>>,
+    start: Instant,
+}
+
+/// Closes the trace file when dropped; keep it alive until the program exits.
+pub struct ChromeTraceGuard {
+    writer: Arc<Mutex<TraceWriter>>,
+}
+
+impl Drop for ChromeTraceGuard {
+    fn drop(&mut self) {
+        if let Ok(mut writer) = self.writer.lock() {
+            writer.finish();
+        }
+    }
+}
+
+impl ChromeTraceLayer {
+    /// Create a layer writing to `path`, truncating any existing file.
+    pub fn create(path: &Path) -> std::io::Result<(Self, ChromeTraceGuard)> {
+        let file = File::create(path)?;
+        Ok(Self::from_writer(BufWriter::new(file)))
+    }
+
+    /// Create a layer writing to an arbitrary sink
+    pub fn from_writer<W: Write + Send + 'static>(out: W) -> (Self, ChromeTraceGuard) {
+        let mut out: Box<dyn Write + Send> = Box::new(out);
+        let _ = writeln!(out, "[");
+        let writer = Arc::new(Mutex::new(TraceWriter {
+            out,
+            first: true,
+            finished: false,
+        }));
+
+        (
+            Self {
+                writer: writer.clone(),
+                start: Instant::now(),
+            },
+            ChromeTraceGuard { writer },
+        )
+    }
+
+    fn emit(&self, phase: &str, name: &str, category: &str, args: Map<String, Value>) {
+        let mut event = json!({
+            "name": name,
+            "cat": category,
+            "ph": phase,
+            "ts": self.start.elapsed().as_micros() as u64,
+            "pid": std::process::id(),
+            "tid": THREAD_ID.with(|id| *id),
+        });
+        if phase == "i" {
+            event["s"] = json!("t");
+        }
+        if !args.is_empty() {
+            event["args"] = Value::Object(args);
+        }
+
+        if let Ok(mut writer) = self.writer.lock() {
+            writer.write_event(&event);
+        }
+    }
+}
+
+impl<S> Layer<S> for ChromeTraceLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(fields));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            if let Some(SpanFields(fields)) = extensions.get_mut::<SpanFields>() {
+                values.record(&mut JsonVisitor(fields));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut args = Map::new();
+        event.record(&mut JsonVisitor(&mut args));
+        let metadata = event.metadata();
+        let name = args
+            .remove("message")
+            .and_then(|message| message.as_str().map(str::to_string))
+            .unwrap_or_else(|| metadata.name().to_string());
+        args.insert("level".to_string(), json!(metadata.level().as_str()));
+        self.emit("i", &name, metadata.target(), args);
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let args = span
+                .extensions()
+                .get::<SpanFields>()
+                .map(|fields| fields.0.clone())
+                .unwrap_or_default();
+            self.emit("B", span.name(), span.metadata().target(), args);
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            self.emit("E", span.name(), span.metadata().target(), Map::new());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_chrome_trace_is_valid_json() {
+        let buffer = SharedBuffer::default();
+        let (layer, guard) = ChromeTraceLayer::from_writer(buffer.clone());
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("install", server = "@org/server", dry_run = false);
+            let _entered = span.enter();
+            tracing::info!(attempt = 1, "Checking dependencies");
+        });
+        drop(guard);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<Value> = serde_json::from_str(&output).unwrap();
+        let phases: Vec<&str> = events.iter().map(|e| e["ph"].as_str().unwrap()).collect();
+        assert_eq!(phases, vec!["B", "i", "E"]);
+
+        assert_eq!(events[0]["name"], "install");
+        assert_eq!(events[0]["args"]["server"], "@org/server");
+        assert_eq!(events[0]["args"]["dry_run"], false);
+        assert_eq!(events[1]["name"], "Checking dependencies");
+        assert_eq!(events[1]["args"]["attempt"], 1);
+        assert_eq!(events[1]["args"]["level"], "INFO");
+    }
+
+    #[test]
+    fn test_empty_trace_is_valid_json() {
+        let buffer = SharedBuffer::default();
+        let (_layer, guard) = ChromeTraceLayer::from_writer(buffer.clone());
+        drop(guard);
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<Value> = serde_json::from_str(&output).unwrap();
+        assert!(events.is_empty());
+    }
+}
@@ -1,8 +1,39 @@
-use anyhow::Result;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+mod chrome;
+pub mod progress;
+
+pub use chrome::{ChromeTraceGuard, ChromeTraceLayer};
+
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::path::Path;
+use std::sync::Mutex;
+use tracing_subscriber::{
+    fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer,
+};
+
+/// Filter applied to `--trace` output regardless of `RUST_LOG`
+const TRACE_FILTER: &str = "mcp_helper=trace,info";
+
+/// Keeps trace output open; dropping it flushes and closes the trace file.
+pub enum TraceGuard {
+    Chrome(ChromeTraceGuard),
+    Log,
+}
 
 /// Initialize structured logging based on verbosity level
 pub fn init_logging(verbose: bool) -> Result<()> {
+    init_logging_with_trace(verbose, None).map(|_| ())
+}
+
+/// Initialize logging and optionally record a detailed trace to `trace_path`.
+///
+/// A path ending in `.json` produces a Chrome trace (open it in
+/// `chrome://tracing` or Perfetto); any other path gets a plain-text log with
+/// span timings, thread ids and targets.
+pub fn init_logging_with_trace(
+    verbose: bool,
+    trace_path: Option<&Path>,
+) -> Result<Option<TraceGuard>> {
     let env_filter = if verbose {
         EnvFilter::try_from_default_env()
             .unwrap_or_else(|_| EnvFilter::new("mcp_helper=debug,info"))
@@ -11,23 +42,63 @@ pub fn init_logging(verbose: bool) -> Result<()> {
             .unwrap_or_else(|_| EnvFilter::new("mcp_helper=info,warn,error"))
     };
 
+    // Logs go to stderr so `--output json` and other machine-readable stdout stays parseable
     let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
         .with_target(false)
         .with_level(true)
-        .with_ansi(true)
-        .compact();
+        .with_ansi(std::io::IsTerminal::is_terminal(&std::io::stderr()))
+        .compact()
+        .with_filter(env_filter);
+
+    let mut chrome_layer = None;
+    let mut log_layer = None;
+    let mut guard = None;
+
+    if let Some(path) = trace_path {
+        if is_chrome_trace_path(path) {
+            let (layer, chrome_guard) = ChromeTraceLayer::create(path)
+                .with_context(|| format!("Failed to create trace file {}", path.display()))?;
+            chrome_layer = Some(layer.with_filter(EnvFilter::new(TRACE_FILTER)));
+            guard = Some(TraceGuard::Chrome(chrome_guard));
+        } else {
+            let file = File::create(path)
+                .with_context(|| format!("Failed to create trace file {}", path.display()))?;
+            log_layer = Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(Mutex::new(file))
+                    .with_ansi(false)
+                    .with_target(true)
+                    .with_thread_ids(true)
+                    .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+                    .with_filter(EnvFilter::new(TRACE_FILTER)),
+            );
+            guard = Some(TraceGuard::Log);
+        }
+    }
 
     tracing_subscriber::registry()
-        .with(env_filter)
         .with(fmt_layer)
+        .with(chrome_layer)
+        .with(log_layer)
         .try_init()
         .map_err(|e| anyhow::anyhow!("Failed to initialize logging: {}", e))?;
 
     if verbose {
         tracing::info!("Verbose logging enabled");
     }
+    if let Some(path) = trace_path {
+        tracing::info!(path = %path.display(), "Trace output enabled");
+        log_system_info();
+    }
+
+    Ok(guard)
+}
 
-    Ok(())
+/// Whether `--trace` output should use the Chrome trace event format
+pub fn is_chrome_trace_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
 }
 
 /// Log dependency check operations
@@ -130,4 +201,12 @@ mod tests {
         log_server_installation("test-server", "npm", false);
         log_performance("dependency_check", 150);
     }
+
+    #[test]
+    fn test_is_chrome_trace_path() {
+        assert!(is_chrome_trace_path(Path::new("trace.json")));
+        assert!(is_chrome_trace_path(Path::new("/tmp/mcp-trace.JSON")));
+        assert!(!is_chrome_trace_path(Path::new("trace.log")));
+        assert!(!is_chrome_trace_path(Path::new("trace")));
+    }
 }
@@ -0,0 +1,297 @@
+//! Machine-readable progress for front-ends wrapping the CLI.
+//!
+//! With `--progress-stream TARGET`, install, update and batch operations write one
+//! JSON object per line as they go:
+//!
+//! ```text
+//! {"event":"step","operation":"install","server":"github","step":"dependencies","percent":40,"message":"Checking dependencies"}
+//! {"event":"next_steps","operation":"install","server":"github","message":"Next steps","next_steps":{"server":"github","restart":["Cursor"],"examples":[...]}}
+//! {"event":"done","operation":"install","server":"github","percent":100,"message":"Installed to 2 client(s)"}
+//! ```
+//!
+//! `TARGET` is `fd:N` for an inherited file descriptor (`fd:1` and `fd:2` are
+//! stdout and stderr) or `file:PATH` / a plain path for a file. Events are written
+//! and flushed one line at a time; without a stream nothing is emitted.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+/// Where progress events go, set once at startup
+static SINK: OnceLock<Mutex<Box<dyn Write + Send>>> = OnceLock::new();
+
+/// Destination given to `--progress-stream`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressTarget {
+    Fd(u32),
+    File(PathBuf),
+}
+
+impl FromStr for ProgressTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if let Some(fd) = s.strip_prefix("fd:") {
+            return fd
+                .parse()
+                .map(Self::Fd)
+                .map_err(|_| format!("Invalid file descriptor '{fd}'"));
+        }
+        let path = s.strip_prefix("file:").unwrap_or(s);
+        if path.is_empty() {
+            return Err("Expected fd:N, file:PATH or a path".to_string());
+        }
+        Ok(Self::File(PathBuf::from(path)))
+    }
+}
+
+impl fmt::Display for ProgressTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fd(fd) => write!(f, "fd:{fd}"),
+            Self::File(path) => write!(f, "file:{}", path.display()),
+        }
+    }
+}
+
+impl ProgressTarget {
+    fn open(&self) -> Result<Box<dyn Write + Send>> {
+        match self {
+            Self::Fd(1) => Ok(Box::new(std::io::stdout())),
+            Self::Fd(2) => Ok(Box::new(std::io::stderr())),
+            Self::Fd(fd) if cfg!(unix) => {
+                // Inherited descriptors are reachable by path on Linux and macOS
+                let file = OpenOptions::new()
+                    .write(true)
+                    .open(format!("/dev/fd/{fd}"))
+                    .with_context(|| format!("File descriptor {fd} is not open for writing"))?;
+                Ok(Box::new(file))
+            }
+            Self::Fd(fd) => anyhow::bail!(
+                "Writing progress to file descriptor {fd} is not supported on this platform; use file:PATH"
+            ),
+            Self::File(path) => {
+                let file = File::create(path).with_context(|| {
+                    format!("Failed to create progress stream {}", path.display())
+                })?;
+                Ok(Box::new(file))
+            }
+        }
+    }
+}
+
+/// Start writing progress events to `target`
+pub fn init(target: &ProgressTarget) -> Result<()> {
+    let writer = target.open()?;
+    SINK.set(Mutex::new(writer))
+        .map_err(|_| anyhow::anyhow!("Progress stream is already set"))
+}
+
+/// Whether a progress stream was set up
+pub fn is_enabled() -> bool {
+    SINK.get().is_some()
+}
+
+/// Kind of a progress event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Step,
+    /// Usage examples and what to do after an install
+    NextSteps,
+    Error,
+    Done,
+}
+
+/// One JSON line on the progress stream
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProgressEvent {
+    pub event: EventKind,
+    /// `install`, `update` or `batch`
+    pub operation: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub step: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent: Option<u8>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_steps: Option<Value>,
+}
+
+impl ProgressEvent {
+    pub fn to_json_line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+}
+
+/// Reports the progress of one operation on one server
+#[derive(Debug, Clone)]
+pub struct Progress {
+    operation: &'static str,
+    server: Option<String>,
+}
+
+impl Progress {
+    pub fn new(operation: &'static str, server: &str) -> Self {
+        Self {
+            operation,
+            server: Some(server.to_string()),
+        }
+    }
+
+    /// Progress of an operation spanning several servers
+    pub fn overall(operation: &'static str) -> Self {
+        Self {
+            operation,
+            server: None,
+        }
+    }
+
+    pub fn step(&self, step: &str, percent: u8, message: impl Into<String>) {
+        emit(&ProgressEvent {
+            step: Some(step.to_string()),
+            percent: Some(percent.min(100)),
+            ..self.event(EventKind::Step, message.into())
+        });
+    }
+
+    pub fn error(&self, error: &dyn fmt::Display) {
+        emit(&ProgressEvent {
+            error: Some(error.to_string()),
+            ..self.event(EventKind::Error, "Failed".to_string())
+        });
+    }
+
+    /// Report what to do after an install, e.g. a serialized `NextSteps`
+    pub fn next_steps(&self, next_steps: &impl Serialize) {
+        emit(&ProgressEvent {
+            next_steps: serde_json::to_value(next_steps).ok(),
+            ..self.event(EventKind::NextSteps, "Next steps".to_string())
+        });
+    }
+
+    pub fn done(&self, message: impl Into<String>) {
+        emit(&ProgressEvent {
+            percent: Some(100),
+            ..self.event(EventKind::Done, message.into())
+        });
+    }
+
+    /// Report `result` as `done` with `message` or as an `error`
+    pub fn finish<T, E: fmt::Display>(&self, result: &Result<T, E>, message: &str) {
+        match result {
+            Ok(_) => self.done(message),
+            Err(e) => self.error(e),
+        }
+    }
+
+    fn event(&self, event: EventKind, message: String) -> ProgressEvent {
+        ProgressEvent {
+            event,
+            operation: self.operation,
+            server: self.server.clone(),
+            step: None,
+            percent: None,
+            message,
+            error: None,
+            next_steps: None,
+        }
+    }
+}
+
+/// Percentage when item `index` of `total` starts, within `span` points after `start`
+pub fn step_percent(start: u8, span: u8, index: usize, total: usize) -> u8 {
+    let done = (usize::from(span) * index)
+        .checked_div(total)
+        .unwrap_or_default();
+    start.saturating_add(u8::try_from(done).unwrap_or(span))
+}
+
+/// Write `event` to the progress stream, if there is one
+pub fn emit(event: &ProgressEvent) {
+    let Some(sink) = SINK.get() else {
+        return;
+    };
+    let Ok(mut writer) = sink.lock() else {
+        return;
+    };
+    // A front-end that went away must not break the operation it was watching
+    if let Err(e) = writer
+        .write_all(event.to_json_line().as_bytes())
+        .and_then(|_| writer.flush())
+    {
+        tracing::debug!("Failed to write progress event: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!("fd:3".parse(), Ok(ProgressTarget::Fd(3)));
+        assert_eq!(
+            "file:/tmp/progress.jsonl".parse(),
+            Ok(ProgressTarget::File(PathBuf::from("/tmp/progress.jsonl")))
+        );
+        assert_eq!(
+            "progress.jsonl".parse(),
+            Ok(ProgressTarget::File(PathBuf::from("progress.jsonl")))
+        );
+        assert!("fd:x".parse::<ProgressTarget>().is_err());
+        assert!("file:".parse::<ProgressTarget>().is_err());
+        assert_eq!(ProgressTarget::Fd(3).to_string(), "fd:3");
+    }
+
+    #[test]
+    fn test_step_percent() {
+        assert_eq!(step_percent(70, 30, 0, 3), 70);
+        assert_eq!(step_percent(70, 30, 2, 3), 90);
+        assert_eq!(step_percent(0, 100, 1, 4), 25);
+        assert_eq!(step_percent(10, 50, 0, 0), 10);
+    }
+
+    #[test]
+    fn test_event_json_lines() {
+        let progress = Progress::new("install", "github");
+        let step = ProgressEvent {
+            step: Some("dependencies".to_string()),
+            percent: Some(40),
+            ..progress.event(EventKind::Step, "Checking dependencies".to_string())
+        };
+        assert_eq!(
+            step.to_json_line(),
+            "{\"event\":\"step\",\"operation\":\"install\",\"server\":\"github\",\"step\":\"dependencies\",\"percent\":40,\"message\":\"Checking dependencies\"}\n"
+        );
+
+        let error = ProgressEvent {
+            error: Some("boom".to_string()),
+            ..Progress::overall("batch").event(EventKind::Error, "Failed".to_string())
+        };
+        let value: serde_json::Value = serde_json::from_str(&error.to_json_line()).unwrap();
+        assert_eq!(value["event"], "error");
+        assert_eq!(value["error"], "boom");
+        assert!(value.get("server").is_none());
+
+        let next_steps = ProgressEvent {
+            next_steps: Some(serde_json::json!({ "restart": ["Cursor"] })),
+            ..progress.event(EventKind::NextSteps, "Next steps".to_string())
+        };
+        let value: serde_json::Value = serde_json::from_str(&next_steps.to_json_line()).unwrap();
+        assert_eq!(value["event"], "next_steps");
+        assert_eq!(value["next_steps"]["restart"][0], "Cursor");
+    }
+}
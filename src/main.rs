@@ -1,9 +1,24 @@
 use clap::{Parser, Subcommand};
 use colored::Colorize;
+use std::path::PathBuf;
 
 // Import from mcp_helper lib
+use mcp_helper::add::AddCommand;
+use mcp_helper::audit_log::AuditKind;
+use mcp_helper::client::ClientTarget;
+use mcp_helper::doctor::{DoctorFailure, FailOn, OutputFormat};
 use mcp_helper::error::McpError;
+use mcp_helper::export_script::ScriptShell;
+use mcp_helper::graph::GraphFormat;
+use mcp_helper::install::InstallCommand;
 use mcp_helper::logging;
+use mcp_helper::logging::progress::ProgressTarget;
+use mcp_helper::playground::DEFAULT_TIMEOUT_SECS;
+use mcp_helper::remote::bridge::{TransportKind, DEFAULT_HEARTBEAT_SECS};
+use mcp_helper::security::{strict_mode_enabled, SecurityViolation};
+use mcp_helper::security_report::ReportFormat;
+use mcp_helper::server::install_manifest::is_install_manifest_url;
+use mcp_helper::update::UpdateChannel;
 
 #[derive(Parser)]
 #[command(name = "mcp")]
@@ -16,6 +31,38 @@ struct Cli {
 
     #[arg(short, long, help = "Enable verbose output", global = true)]
     verbose: bool,
+
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "Write a detailed trace for bug reports",
+        long_help = "Write a detailed trace for bug reports (Chrome trace if FILE ends in .json, log otherwise)",
+        global = true
+    )]
+    trace: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Treat every security warning as an error",
+        long_help = "Treat every security warning as an error (also MCP_HELPER_STRICT=1 or security.strict in preferences)",
+        global = true
+    )]
+    strict: bool,
+
+    #[arg(
+        long,
+        value_name = "TARGET",
+        help = "Write JSON-lines progress events for install, update and batch to fd:N or file:PATH",
+        global = true
+    )]
+    progress_stream: Option<ProgressTarget>,
+
+    #[arg(
+        long,
+        help = "Query the npm registry directly instead of using cached metadata",
+        global = true
+    )]
+    no_cache: bool,
 }
 
 #[derive(Subcommand)]
@@ -36,27 +83,190 @@ enum Commands {
 
         #[arg(long, help = "Skip interactive prompts")]
         non_interactive: bool,
+
+        #[arg(
+            long = "allow-dir",
+            value_name = "DIR",
+            help = "Directory a filesystem server may access (repeatable)"
+        )]
+        allow_dirs: Vec<PathBuf>,
+
+        #[arg(
+            long,
+            help = "Give filesystem servers read-only access (Docker image only)"
+        )]
+        read_only: bool,
+
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Directory to start the server in (absolute; ~ is expanded)"
+        )]
+        cwd: Option<PathBuf>,
+
+        #[arg(
+            long,
+            conflicts_with_all = ["command", "args", "env", "allow_dirs", "read_only", "cwd"],
+            help = "URL of a remote (streamable HTTP) server to add instead of a local command"
+        )]
+        url: Option<String>,
+
+        #[arg(
+            long = "header",
+            value_name = "NAME=VALUE",
+            requires = "url",
+            help = "HTTP header for a remote server (repeatable); use ${keychain:NAME} or ${env:NAME} for secrets"
+        )]
+        headers: Vec<String>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with = "url",
+            help = "Write the client entry to FILE (mcpServers format) instead of into installed clients, e.g. inside a container"
+        )]
+        manifest_out: Option<PathBuf>,
+
+        #[arg(
+            long,
+            help = "With a scope wildcard (@myorg/*), add every matching package without asking"
+        )]
+        all: bool,
+
+        #[arg(
+            short,
+            long,
+            help = "Don't print usage examples and next steps after adding"
+        )]
+        quiet: bool,
+
+        #[arg(
+            long = "as",
+            value_name = "NAME",
+            conflicts_with = "url",
+            help = "Add the server under NAME, e.g. a second filesystem server as \"docs-fs\""
+        )]
+        instance: Option<String>,
+
+        #[arg(
+            long,
+            help = "Add the server even if it declares it doesn't support this OS or architecture"
+        )]
+        ignore_platform: bool,
+
+        #[arg(long, help = "Pin a Docker image to the digest its tag points at now")]
+        pin_digest: bool,
+
+        #[arg(
+            long = "client",
+            value_name = "NAME",
+            conflicts_with = "manifest_out",
+            help = "Client to add the server to instead of choosing (repeatable, case-insensitive)"
+        )]
+        clients: Vec<String>,
+
+        #[arg(
+            long,
+            conflicts_with_all = ["clients", "manifest_out"],
+            help = "Add the server to every installed client without asking"
+        )]
+        all_clients: bool,
     },
 
-    #[command(about = "List configured MCP servers")]
+    #[command(about = "List configured MCP servers across all clients")]
     List {
         #[arg(short, long, help = "Show detailed information")]
         verbose: bool,
+
+        #[arg(long, default_value = "text", help = "Output format (text, json)")]
+        output: OutputFormat,
+
+        #[arg(long, conflicts_with = "output", help = "Same as --output json")]
+        json: bool,
+
+        #[arg(
+            long,
+            help = "Only show servers matching a glob (e.g. \"github-*\") or \"re:<regex>\""
+        )]
+        filter: Option<String>,
     },
 
     #[command(about = "Remove an MCP server from configuration")]
     Remove {
-        #[arg(help = "Name of the server to remove")]
-        server: String,
+        #[arg(
+            help = "Name of the server to remove",
+            required_unless_present = "glob"
+        )]
+        server: Option<String>,
 
-        #[arg(long, help = "Remove from all clients")]
+        #[arg(long, conflicts_with = "client", help = "Remove from all clients")]
         all: bool,
+
+        #[arg(long, conflicts_with = "glob", help = "Only remove from this client")]
+        client: Option<String>,
+
+        #[arg(
+            long,
+            conflicts_with = "glob",
+            help = "Also delete the server's virtualenv, binaries, cached downloads and keychain secrets"
+        )]
+        purge: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "server",
+            help = "Remove all servers matching a glob (e.g. \"server-*\") or \"re:<regex>\""
+        )]
+        glob: Option<String>,
+
+        #[arg(
+            long,
+            help = "Also remove the stopped containers and unused images of removed Docker servers"
+        )]
+        prune_docker: bool,
+
+        #[arg(long, help = "Show what would be removed without changing anything")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Remove without asking for confirmation")]
+        yes: bool,
+    },
+
+    #[command(about = "Remove a server from clients and clean up what mcp-helper created for it")]
+    Uninstall {
+        #[arg(help = "Name of the server to uninstall")]
+        server: String,
+
+        #[arg(
+            long,
+            conflicts_with = "client",
+            help = "Remove from every client that has it"
+        )]
+        all_clients: bool,
+
+        #[arg(long, help = "Only remove from this client")]
+        client: Option<String>,
+
+        #[arg(
+            long,
+            help = "Also delete the server's files, cached downloads, secrets and Docker containers and images"
+        )]
+        cleanup: bool,
+
+        #[arg(long, help = "Show what would be removed without changing anything")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Remove without asking for confirmation")]
+        yes: bool,
     },
 
     #[command(about = "Install an MCP server", hide = true)] // Hidden/deprecated
     Install {
-        #[arg(help = "Name or path of the MCP server to install")]
-        server: String,
+        #[arg(
+            required_unless_present = "batch",
+            help = "Name or path of the MCP server to install"
+        )]
+        server: Option<String>,
 
         #[arg(long, help = "Automatically install missing dependencies")]
         auto_install_deps: bool,
@@ -69,6 +279,389 @@ enum Commands {
 
         #[arg(long, help = "Install servers from batch file")]
         batch: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            help = "Run dependency install commands without asking (they are still shown)"
+        )]
+        yes: bool,
+
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            requires = "dry_run",
+            help = "With --dry-run, print security findings for everything the install would pull in (sarif, json)"
+        )]
+        security_report: Option<ReportFormat>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write the client entry to FILE (mcpServers format) instead of into installed clients, e.g. inside a container"
+        )]
+        manifest_out: Option<PathBuf>,
+
+        #[arg(
+            long,
+            help = "With a scope wildcard (@myorg/*), install every matching package without asking"
+        )]
+        all: bool,
+
+        #[arg(
+            short,
+            long,
+            help = "Don't print usage examples and next steps after installing"
+        )]
+        quiet: bool,
+
+        #[arg(
+            long = "as",
+            value_name = "NAME",
+            conflicts_with = "batch",
+            help = "Install the server under NAME, e.g. a second filesystem server as \"docs-fs\""
+        )]
+        instance: Option<String>,
+
+        #[arg(
+            long,
+            help = "Install the server even if it declares it doesn't support this OS or architecture"
+        )]
+        ignore_platform: bool,
+
+        #[arg(long, help = "Pin a Docker image to the digest its tag points at now")]
+        pin_digest: bool,
+
+        #[arg(
+            long = "client",
+            value_name = "NAME",
+            conflicts_with = "manifest_out",
+            help = "Client to install the server to instead of choosing (repeatable, case-insensitive)"
+        )]
+        clients: Vec<String>,
+
+        #[arg(
+            long,
+            conflicts_with_all = ["clients", "manifest_out"],
+            help = "Install the server to every installed client without asking"
+        )]
+        all_clients: bool,
+    },
+
+    #[command(about = "Run an MCP server, recording crashes in the run history")]
+    Run {
+        #[arg(help = "Name of the MCP server to run")]
+        server: String,
+
+        #[arg(
+            long,
+            help = "Show how the name resolves and the command that would run, without running it"
+        )]
+        explain: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "explain",
+            help = "Run with only required settings and credentials, showing what was left out, to tell config problems from server problems"
+        )]
+        safe: bool,
+
+        #[arg(
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            help = "Additional arguments to pass to the server"
+        )]
+        args: Vec<String>,
+    },
+
+    #[command(about = "Show recent runs and crashes of servers started with 'mcp run'")]
+    Status {
+        #[arg(
+            long,
+            help = "Print one line like \"MCP: 7 ok, 1 warn, 0 err\" for shell prompts; exits 0 ok, 1 warn, 2 err, 3 unknown"
+        )]
+        summary: bool,
+    },
+
+    #[command(about = "List pinned servers with newer releases, including moved Docker tags")]
+    Outdated {
+        #[arg(long, default_value = "text", help = "Output format (text, json)")]
+        output: OutputFormat,
+    },
+
+    #[command(about = "Update an installed MCP server to its latest version")]
+    Update {
+        #[arg(required_unless_present = "all", help = "Name of the server to update")]
+        server: Option<String>,
+
+        #[arg(
+            long,
+            conflicts_with = "server",
+            help = "Update every configured server, applying all changes or none"
+        )]
+        all: bool,
+
+        #[arg(
+            long,
+            value_name = "SERVER",
+            requires = "all",
+            help = "With --all, leave this server at its current version (repeatable)"
+        )]
+        skip: Vec<String>,
+
+        #[arg(long, help = "Apply major (breaking) upgrades without prompting")]
+        allow_major: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "all",
+            help = "Release channel to follow from now on (stable, beta); defaults to the recorded one"
+        )]
+        channel: Option<UpdateChannel>,
+    },
+
+    #[command(about = "Copy every server from one client to other installed clients")]
+    SyncClients {
+        #[arg(long, help = "Client to copy servers from (e.g. \"Claude Desktop\")")]
+        from: String,
+
+        #[arg(
+            long,
+            default_value = "all",
+            value_delimiter = ',',
+            help = "Clients to copy servers to, comma-separated, or \"all\""
+        )]
+        to: Vec<String>,
+
+        #[arg(long, help = "Show what would change without writing any config")]
+        dry_run: bool,
+
+        #[arg(long, help = "Replace servers that differ in the target client")]
+        overwrite: bool,
+    },
+
+    #[command(about = "Add, update and remove servers to match a desired state file")]
+    Apply {
+        #[arg(help = "State file (YAML, or JSON with a .json extension)")]
+        file: PathBuf,
+
+        #[arg(long, help = "Show what would change without writing any config")]
+        plan: bool,
+
+        #[arg(short, long, help = "Apply the changes without asking")]
+        yes: bool,
+    },
+
+    #[command(about = "Compare mcp-helper's record of managed servers with client configs")]
+    Check {
+        #[arg(
+            long,
+            help = "Report servers added, removed or changed in client configs since mcp-helper recorded them"
+        )]
+        sync: bool,
+
+        #[arg(
+            long,
+            requires = "sync",
+            conflicts_with = "reconcile",
+            help = "Update the record (lockfile) from the client configs"
+        )]
+        update_lockfile: bool,
+
+        #[arg(
+            long,
+            requires = "sync",
+            help = "Change the client configs to match the record"
+        )]
+        reconcile: bool,
+
+        #[arg(short, long, help = "Change client configs without asking")]
+        yes: bool,
+    },
+
+    #[command(about = "Bring servers configured by hand under mcp-helper management")]
+    Import {
+        #[arg(long, help = "Show what would be imported without recording anything")]
+        dry_run: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "no_normalize",
+            help = "Apply suggested normalizations (version pins, wrappers) without prompting"
+        )]
+        normalize: bool,
+
+        #[arg(long, help = "Leave imported entries exactly as they are")]
+        no_normalize: bool,
+    },
+
+    #[command(about = "Run an MCP server in the background at login")]
+    Service {
+        #[command(subcommand)]
+        action: ServiceAction,
+    },
+
+    #[command(about = "Manage Docker containers and images created for MCP servers")]
+    Docker {
+        #[command(subcommand)]
+        action: DockerAction,
+    },
+
+    #[command(about = "Inspect mcp-helper's caches")]
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+
+    #[command(about = "Encrypt or decrypt mcp-helper's own state files")]
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    #[command(
+        about = "Review the commands mcp-helper ran, the files it changed and the security warnings accepted"
+    )]
+    Audit {
+        #[command(subcommand)]
+        action: AuditAction,
+    },
+
+    #[command(about = "Find plaintext secrets in client configs and move them to the keychain")]
+    AuditSecrets {
+        #[arg(
+            long,
+            help = "Move every secret found into the keychain without asking"
+        )]
+        migrate: bool,
+    },
+
+    #[command(about = "Run a command with ${keychain:NAME} and ${env:NAME} references resolved")]
+    Exec {
+        #[arg(
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "COMMAND",
+            help = "Command and arguments to run, after --"
+        )]
+        command: Vec<String>,
+    },
+
+    #[command(about = "Draw which servers each client uses and the runtimes they need")]
+    Graph {
+        #[arg(long, default_value = "mermaid", help = "Output format (dot, mermaid)")]
+        format: GraphFormat,
+    },
+
+    #[command(about = "Print a script of 'mcp add' commands that recreates the current servers")]
+    ExportScript {
+        #[arg(
+            long,
+            default_value = "bash",
+            help = "Script language (bash, powershell)"
+        )]
+        shell: ScriptShell,
+    },
+
+    #[command(about = "Compare a server's environment under 'mcp run' and under a client")]
+    DebugEnv {
+        #[arg(help = "Name of the configured server")]
+        server: String,
+
+        #[arg(
+            long,
+            help = "Client to compare against (default: first client with the server)"
+        )]
+        client: Option<String>,
+    },
+
+    #[command(about = "Collect information for bug reports")]
+    Debug {
+        #[command(subcommand)]
+        action: DebugAction,
+    },
+
+    #[command(about = "Relay a stdio MCP client to a remote HTTP or SSE server")]
+    Bridge {
+        #[arg(long, help = "URL of the remote server")]
+        url: String,
+
+        #[arg(
+            long = "header",
+            value_name = "NAME=VALUE",
+            help = "HTTP header sent with every request (repeatable); ${keychain:NAME} and ${env:NAME} are resolved at startup"
+        )]
+        headers: Vec<String>,
+
+        #[arg(
+            long,
+            default_value = "auto",
+            help = "Transport the server speaks (auto, streamable-http, sse)"
+        )]
+        transport: TransportKind,
+
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = DEFAULT_HEARTBEAT_SECS,
+            help = "Seconds between keep-alive pings; 0 disables them"
+        )]
+        heartbeat: u64,
+    },
+
+    #[command(about = "Run a minimal built-in MCP server with an echo tool over stdio")]
+    TestServer,
+
+    #[command(about = "Call a server's tools interactively with JSON arguments")]
+    Playground {
+        #[arg(help = "Name of the configured server, or builtin:test")]
+        server: String,
+
+        #[arg(
+            long,
+            help = "Client to take the server configuration from (default: first client with the server)"
+        )]
+        client: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "SECONDS",
+            default_value_t = DEFAULT_TIMEOUT_SECS,
+            help = "Seconds to wait for each reply from the server"
+        )]
+        timeout: u64,
+    },
+
+    #[command(about = "Show offline documentation for an MCP server")]
+    HelpServer {
+        #[arg(help = "Package or configured server name")]
+        name: String,
+    },
+
+    #[command(about = "Show details of an MCP server, installed or not")]
+    Info {
+        #[arg(help = "Package or configured server name")]
+        name: String,
+        #[arg(long, help = "Also show the server's README")]
+        readme: bool,
+        #[arg(
+            long,
+            help = "Only use what is on this machine, without registry lookups"
+        )]
+        offline: bool,
+    },
+
+    #[command(about = "Report clients, servers and policy compliance for fleet tools")]
+    Inventory {
+        #[arg(long, default_value = "text", help = "Output format (text, json)")]
+        output: OutputFormat,
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Write the JSON inventory to a file instead of printing it"
+        )]
+        file: Option<PathBuf>,
     },
 
     #[command(about = "Quick environment check (first-time setup)")]
@@ -81,7 +674,126 @@ enum Commands {
     },
 
     #[command(about = "Comprehensive diagnostics (troubleshooting)")]
-    Doctor,
+    Doctor {
+        #[arg(long, default_value = "text", help = "Output format (text, json)")]
+        output: OutputFormat,
+
+        #[arg(
+            long,
+            default_value = "error",
+            help = "Minimum severity that causes a non-zero exit (ok, warning, error), or never"
+        )]
+        fail_on: FailOn,
+
+        #[arg(
+            long,
+            visible_alias = "category",
+            value_delimiter = ',',
+            help = "Only run checks in these categories, comma-separated (node or nodejs, docker, clients, path, platform, servers, consistency, state, antivirus, storage, disk, npm)"
+        )]
+        only: Vec<String>,
+
+        #[arg(
+            long,
+            help = "Apply safe fixes first (comments and trailing commas in client configs, npx.cmd on Windows)"
+        )]
+        fix: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ServiceAction {
+    #[command(about = "Install a server as a systemd unit, launchd agent or Scheduled Task")]
+    Install {
+        #[arg(help = "Name of the configured server")]
+        server: String,
+
+        #[arg(long, help = "Client to take the server configuration from")]
+        client: Option<String>,
+    },
+    #[command(about = "Show whether server services are running")]
+    Status {
+        #[arg(help = "Server to check (all services when omitted)")]
+        server: Option<String>,
+    },
+    #[command(about = "Stop a server service and remove it")]
+    Remove {
+        #[arg(help = "Name of the server")]
+        server: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DockerAction {
+    #[command(about = "Remove mcp-* containers and images no current config uses")]
+    Prune {
+        #[arg(short, long, help = "Remove without asking for confirmation")]
+        yes: bool,
+
+        #[arg(long, help = "Only list what would be removed")]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    #[command(about = "Show cached entries and how often registry lookups hit the cache")]
+    Stats,
+}
+
+#[derive(Subcommand)]
+enum AuditAction {
+    #[command(about = "Show the most recent audit log entries")]
+    Show {
+        #[arg(long, default_value_t = 50, help = "Number of entries to show")]
+        limit: usize,
+
+        #[arg(long, help = "Only show one kind of entry: command, file or override")]
+        kind: Option<AuditKind>,
+
+        #[arg(
+            long,
+            help = "Only show entries since a date (YYYY-MM-DD) or RFC 3339 timestamp"
+        )]
+        since: Option<String>,
+    },
+    #[command(about = "Write the audit log as JSON lines")]
+    Export {
+        #[arg(long, help = "Write to a file instead of stdout")]
+        file: Option<PathBuf>,
+
+        #[arg(
+            long,
+            help = "Only export entries since a date (YYYY-MM-DD) or RFC 3339 timestamp"
+        )]
+        since: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DebugAction {
+    #[command(
+        about = "Write client configs, preferences and platform info as a test fixture bundle"
+    )]
+    Snapshot {
+        #[arg(long, help = "Redact secrets and the home directory before writing")]
+        anonymize: bool,
+        #[arg(
+            long,
+            value_name = "DIR",
+            default_value = "mcp-snapshot",
+            help = "Directory to write the bundle to"
+        )]
+        output: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum StateAction {
+    #[command(about = "Encrypt existing state with a keychain-held key and keep it encrypted")]
+    Encrypt,
+    #[command(about = "Decrypt existing state and store it in plaintext again")]
+    Decrypt,
 }
 
 #[derive(Subcommand)]
@@ -95,35 +807,107 @@ enum ConfigAction {
     List,
     #[command(about = "Remove a server from configuration")]
     Remove {
+        #[arg(help = "Name of the server", required_unless_present = "glob")]
+        server: Option<String>,
+
+        #[arg(
+            long,
+            conflicts_with = "server",
+            help = "Remove all servers matching a glob or \"re:<regex>\""
+        )]
+        glob: Option<String>,
+    },
+    #[command(about = "Check server configurations for common mistakes")]
+    Lint {
+        #[arg(long, help = "Apply safe fixes (recorded in config history)")]
+        fix: bool,
+    },
+    #[command(about = "Repair client config files that are no longer valid JSON")]
+    Repair {
+        #[arg(long, help = "Only repair this client's config")]
+        client: Option<String>,
+
+        #[arg(short, long, help = "Apply the least lossy repair without asking")]
+        yes: bool,
+    },
+    #[command(about = "Show the recorded configuration changes of a server")]
+    History {
         #[arg(help = "Name of the server")]
         server: String,
+
+        #[arg(long, help = "Only show changes to this client's config")]
+        client: Option<String>,
+    },
+    #[command(
+        about = "Undo the latest configuration change of a server, or restore an earlier one"
+    )]
+    Rollback {
+        #[arg(help = "Name of the server")]
+        server: String,
+
+        #[arg(
+            long,
+            value_name = "TIME",
+            help = "Restore the configuration recorded at this time, as shown by 'mcp config history'"
+        )]
+        to: Option<String>,
+
+        #[arg(long, help = "Client whose config to roll back")]
+        client: Option<String>,
+
+        #[arg(short, long, help = "Restore without asking for confirmation")]
+        yes: bool,
     },
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    setup_logging(&cli);
+    let trace_guard = setup_logging(&cli);
 
     let result = execute_command(cli);
 
+    // Flush the trace before handle_result may exit the process
+    drop(trace_guard);
     handle_result(result);
 }
 
 /// Set up logging based on CLI arguments
-fn setup_logging(cli: &Cli) {
-    if let Err(e) = logging::init_logging(cli.verbose) {
-        eprintln!("Warning: Failed to initialize logging: {e}");
-    }
+fn setup_logging(cli: &Cli) -> Option<logging::TraceGuard> {
+    let guard = match logging::init_logging_with_trace(cli.verbose, cli.trace.as_deref()) {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("Warning: Failed to initialize logging: {e}");
+            None
+        }
+    };
 
     if cli.verbose {
         logging::log_system_info();
         eprintln!("{}", "Verbose mode enabled".dimmed());
     }
+
+    if let Some(path) = &cli.trace {
+        if guard.is_some() {
+            eprintln!(
+                "{}",
+                format!("Writing trace to {}", path.display()).dimmed()
+            );
+        }
+    }
+
+    guard
 }
 
 /// Execute the requested command
 fn execute_command(cli: Cli) -> anyhow::Result<()> {
+    if let Some(target) = &cli.progress_stream {
+        logging::progress::init(target)?;
+    }
+    if cli.no_cache {
+        mcp_helper::cache::disable_registry_cache();
+    }
+
     match cli.command {
         Commands::Add {
             server,
@@ -131,36 +915,240 @@ fn execute_command(cli: Cli) -> anyhow::Result<()> {
             args,
             env,
             non_interactive,
-        } => execute_add_command(server, command, args, env, non_interactive, cli.verbose),
-        Commands::List { verbose } => execute_list_command(verbose || cli.verbose),
-        Commands::Remove { server, all } => execute_remove_command(server, all, cli.verbose),
+            allow_dirs,
+            read_only,
+            cwd,
+            url,
+            headers,
+            manifest_out,
+            all,
+            quiet,
+            instance,
+            ignore_platform,
+            pin_digest,
+            clients,
+            all_clients,
+        } => execute_add_command(
+            AddCommand::new(cli.verbose)
+                .with_allowed_dirs(allow_dirs)
+                .with_read_only(read_only)
+                .with_working_dir(cwd)
+                .with_remote(url, headers)
+                .with_manifest_out(manifest_out)
+                .with_select_all(all)
+                .with_quiet(quiet)
+                .with_instance_name(instance)
+                .with_ignore_platform(ignore_platform)
+                .with_pin_digest(pin_digest)
+                .with_client_target(ClientTarget::from_flags(clients, all_clients))
+                .with_strict(strict_mode_enabled(cli.strict)),
+            server,
+            command,
+            args,
+            env,
+            non_interactive,
+        ),
+        Commands::List {
+            verbose,
+            output,
+            json,
+            filter,
+        } => execute_list_command(
+            verbose || cli.verbose,
+            if json { OutputFormat::Json } else { output },
+            filter.as_deref(),
+        ),
+        Commands::Remove {
+            server,
+            all,
+            client,
+            purge,
+            glob,
+            prune_docker,
+            dry_run,
+            yes,
+        } => execute_remove_command(
+            server,
+            glob,
+            RemoveFlags {
+                all,
+                client,
+                purge,
+                prune_docker,
+                dry_run,
+                yes,
+            },
+            cli.verbose,
+        ),
+        Commands::Uninstall {
+            server,
+            all_clients,
+            client,
+            cleanup,
+            dry_run,
+            yes,
+        } => execute_uninstall_command(
+            &server,
+            RemoveFlags {
+                all: all_clients,
+                client,
+                purge: cleanup,
+                prune_docker: cleanup,
+                dry_run,
+                yes,
+            },
+            cli.verbose,
+        ),
         Commands::Install {
             server,
             auto_install_deps,
             dry_run,
             config,
             batch,
+            yes,
+            security_report,
+            manifest_out,
+            all,
+            quiet,
+            instance,
+            ignore_platform,
+            pin_digest,
+            clients,
+            all_clients,
         } => execute_install_command(
             server,
-            auto_install_deps,
-            dry_run,
+            DependencyFlags {
+                auto_install: auto_install_deps,
+                dry_run,
+                yes,
+            },
             config,
             batch,
+            InstallOptions {
+                security_report,
+                manifest_out,
+                all,
+                quiet,
+                instance,
+                ignore_platform,
+                pin_digest,
+                clients: ClientTarget::from_flags(clients, all_clients),
+            },
+            strict_mode_enabled(cli.strict),
             cli.verbose,
         ),
+        Commands::Run {
+            server,
+            explain,
+            safe,
+            args,
+        } => execute_run_command(server, args, explain, safe, cli.verbose),
+        Commands::Status { summary } => execute_status_command(summary, cli.verbose),
+        Commands::Outdated { output } => execute_outdated_command(output, cli.verbose),
+        // `--all` is implied by a missing server name
+        Commands::Update {
+            server,
+            skip,
+            allow_major,
+            channel,
+            ..
+        } => execute_update_command(server, skip, allow_major, channel, cli.verbose),
+        Commands::Import {
+            dry_run,
+            normalize,
+            no_normalize,
+        } => execute_import_command(dry_run, normalize, no_normalize, cli.verbose),
+        Commands::Service { action } => execute_service_command(action, cli.verbose),
+        Commands::Docker { action } => execute_docker_command(action, cli.verbose),
+        Commands::Cache { action } => execute_cache_command(action),
+        Commands::State { action } => execute_state_command(action, cli.verbose),
+        Commands::Graph { format } => execute_graph_command(format),
+        Commands::ExportScript { shell } => execute_export_script_command(shell),
+        Commands::DebugEnv { server, client } => {
+            execute_debug_env_command(&server, client, cli.verbose)
+        }
+        Commands::Debug { action } => execute_debug_command(action, cli.verbose),
+        Commands::Audit { action } => execute_audit_command(action),
+        Commands::AuditSecrets { migrate } => execute_audit_secrets_command(migrate, cli.verbose),
+        Commands::Exec { command } => execute_exec_command(&command),
+        Commands::Bridge {
+            url,
+            headers,
+            transport,
+            heartbeat,
+        } => execute_bridge_command(&url, &headers, transport, heartbeat),
+        Commands::TestServer => execute_test_server_command(),
+        Commands::Playground {
+            server,
+            client,
+            timeout,
+        } => execute_playground_command(&server, client, timeout, cli.verbose),
+        Commands::HelpServer { name } => execute_help_server_command(&name, cli.verbose),
+        Commands::Info {
+            name,
+            readme,
+            offline,
+        } => execute_info_command(&name, readme, offline, cli.verbose),
+        Commands::Inventory { output, file } => {
+            execute_inventory_command(output, file, strict_mode_enabled(cli.strict), cli.verbose)
+        }
         Commands::Setup => execute_setup_command(),
-        Commands::Config { action } => execute_config_command(action),
-        Commands::Doctor => execute_doctor_command(),
+        Commands::SyncClients {
+            from,
+            to,
+            dry_run,
+            overwrite,
+        } => execute_sync_clients_command(&from, &to, dry_run, overwrite, cli.verbose),
+        Commands::Apply { file, plan, yes } => execute_apply_command(&file, plan, yes, cli.verbose),
+        Commands::Check {
+            sync,
+            update_lockfile,
+            reconcile,
+            yes,
+        } => execute_check_command(sync, update_lockfile, reconcile, yes, cli.verbose),
+        Commands::Config { action } => execute_config_command(action, cli.verbose),
+        Commands::Doctor {
+            output,
+            fail_on,
+            only,
+            fix,
+        } => execute_doctor_command(output, fail_on, only, fix, cli.verbose),
     }
 }
 
+/// How the deprecated install command handles missing dependencies
+struct DependencyFlags {
+    auto_install: bool,
+    dry_run: bool,
+    yes: bool,
+}
+
+/// Options of the deprecated install command beyond dependency handling
+struct InstallOptions {
+    security_report: Option<ReportFormat>,
+    manifest_out: Option<PathBuf>,
+    /// Take every package a scope wildcard matches
+    all: bool,
+    /// Skip usage examples and next steps
+    quiet: bool,
+    /// Name to install the server under (`--as`)
+    instance: Option<String>,
+    /// Install even if the server doesn't support this platform
+    ignore_platform: bool,
+    /// Pin Docker images to the digest of their tag
+    pin_digest: bool,
+    /// Clients given with `--client` or `--all-clients`
+    clients: ClientTarget,
+}
+
 /// Execute the install command (deprecated - redirects to add)
 fn execute_install_command(
-    server: String,
-    _auto_install_deps: bool,
-    _dry_run: bool,
+    server: Option<String>,
+    deps: DependencyFlags,
     config: Vec<String>,
     batch: Option<String>,
+    options: InstallOptions,
+    strict: bool,
     verbose: bool,
 ) -> anyhow::Result<()> {
     eprintln!(
@@ -168,16 +1156,101 @@ fn execute_install_command(
         "⚠".yellow()
     );
 
-    if batch.is_some() {
-        eprintln!("Batch installation is not yet supported in 'mcp add'.");
-        return Err(anyhow::anyhow!("Batch mode not supported"));
+    if let Some(format) = options.security_report {
+        return execute_security_report(server.as_deref(), batch.as_deref(), format, strict);
+    }
+
+    // Batches go through the install pipeline, which shares dependency checks and
+    // downloads between servers; each entry brings its own configuration
+    if let Some(batch) = batch {
+        if options.manifest_out.is_some() || options.pin_digest {
+            return Err(anyhow::anyhow!(
+                "--manifest-out and --pin-digest are not supported with --batch"
+            ));
+        }
+        let mut installer = InstallCommand::new(verbose)
+            .with_strict(strict)
+            .with_auto_install_deps(deps.auto_install)
+            .with_dry_run(deps.dry_run)
+            .with_yes(deps.yes)
+            .with_quiet(options.quiet)
+            .with_ignore_platform(options.ignore_platform)
+            .with_client_target(options.clients);
+        return installer.execute_batch(&batch).map_err(convert_mcp_error);
+    }
+    let Some(server) = server else {
+        return Err(anyhow::anyhow!("No server given"));
+    };
+
+    // Vendor install manifests carry their own config schema, so they go through
+    // the install pipeline rather than 'mcp add'
+    if is_install_manifest_url(&server) {
+        if options.manifest_out.is_some() {
+            return Err(anyhow::anyhow!(
+                "--manifest-out is not supported for vendor install manifests"
+            ));
+        }
+        if options.pin_digest {
+            return Err(anyhow::anyhow!(
+                "--pin-digest is not supported for vendor install manifests"
+            ));
+        }
+        let mut installer = InstallCommand::new(verbose)
+            .with_config_overrides(config)
+            .with_strict(strict)
+            .with_auto_install_deps(deps.auto_install)
+            .with_dry_run(deps.dry_run)
+            .with_yes(deps.yes)
+            .with_quiet(options.quiet)
+            .with_instance_name(options.instance)
+            .with_ignore_platform(options.ignore_platform)
+            .with_client_target(options.clients);
+        return installer.execute(&server).map_err(convert_mcp_error);
     }
 
     // Parse config overrides into env vars
     let env: Vec<String> = config;
 
     // Redirect to add command
-    execute_add_command(server, None, Vec::new(), env, false, verbose)
+    execute_add_command(
+        AddCommand::new(verbose)
+            .with_manifest_out(options.manifest_out)
+            .with_select_all(options.all)
+            .with_quiet(options.quiet)
+            .with_instance_name(options.instance)
+            .with_ignore_platform(options.ignore_platform)
+            .with_pin_digest(options.pin_digest)
+            .with_client_target(options.clients)
+            .with_strict(strict)
+            .with_auto_install_deps(deps.auto_install)
+            .with_dry_run(deps.dry_run)
+            .with_yes(deps.yes),
+        server,
+        None,
+        Vec::new(),
+        env,
+        false,
+    )
+}
+
+/// Print the security findings of a dry-run install; strict mode fails on any finding
+fn execute_security_report(
+    server: Option<&str>,
+    batch: Option<&str>,
+    format: ReportFormat,
+    strict: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::security::SecurityValidator;
+    use mcp_helper::security_report::{scan_targets, SecurityReport};
+
+    let targets = scan_targets(server, batch)?;
+    let report = SecurityReport::scan(&SecurityValidator::new(), targets);
+    println!("{}", report.render(format));
+
+    match report.strict_violation() {
+        Some(violation) if strict => Err(violation.into()),
+        _ => Ok(()),
+    }
 }
 
 /// Execute the setup command
@@ -190,17 +1263,13 @@ fn execute_setup_command() -> anyhow::Result<()> {
 
 /// Execute the add command
 fn execute_add_command(
+    mut cmd: AddCommand,
     server: String,
     command: Option<String>,
     args: Vec<String>,
     env: Vec<String>,
     non_interactive: bool,
-    verbose: bool,
 ) -> anyhow::Result<()> {
-    use mcp_helper::add::AddCommand;
-
-    let mut cmd = AddCommand::new(verbose);
-
     // Parse environment variables
     let mut env_map = std::collections::HashMap::new();
     for env_var in env {
@@ -213,25 +1282,122 @@ fn execute_add_command(
         .map_err(convert_mcp_error)
 }
 
+/// Execute the bridge command; stdout carries protocol messages only
+fn execute_bridge_command(
+    url: &str,
+    headers: &[String],
+    transport: TransportKind,
+    heartbeat: u64,
+) -> anyhow::Result<()> {
+    use mcp_helper::remote::bridge::{Bridge, BridgeOptions, DEFAULT_MAX_RETRIES};
+    use mcp_helper::remote::RemoteServerConfig;
+    use std::time::Duration;
+
+    let remote = RemoteServerConfig::new(url)?.with_header_specs(headers)?;
+    let options = BridgeOptions {
+        transport,
+        heartbeat: (heartbeat > 0).then(|| Duration::from_secs(heartbeat)),
+        max_retries: DEFAULT_MAX_RETRIES,
+    };
+    Bridge::new(&remote, options)?.run(std::io::stdin().lock(), std::io::stdout())
+}
+
 /// Execute the list command
-fn execute_list_command(verbose: bool) -> anyhow::Result<()> {
+fn execute_list_command(
+    verbose: bool,
+    output: OutputFormat,
+    filter: Option<&str>,
+) -> anyhow::Result<()> {
+    use mcp_helper::list::ListCommand;
+
+    ListCommand::new(verbose)
+        .with_output_format(output)
+        .with_filter(filter)
+        .map_err(convert_mcp_error)?
+        .execute()
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the config list command
+fn execute_config_list_command() -> anyhow::Result<()> {
     use mcp_helper::config_commands::ConfigListCommand;
 
-    let cmd = ConfigListCommand::new(verbose);
-    cmd.execute().map_err(convert_mcp_error)
+    ConfigListCommand::new(false)
+        .execute()
+        .map_err(convert_mcp_error)
+}
+
+/// Which entries and artifacts the remove command deletes
+struct RemoveFlags {
+    all: bool,
+    client: Option<String>,
+    purge: bool,
+    prune_docker: bool,
+    dry_run: bool,
+    yes: bool,
 }
 
 /// Execute the remove command
-fn execute_remove_command(server: String, all: bool, verbose: bool) -> anyhow::Result<()> {
+fn execute_remove_command(
+    server: Option<String>,
+    glob: Option<String>,
+    flags: RemoveFlags,
+    verbose: bool,
+) -> anyhow::Result<()> {
     use mcp_helper::config_commands::ConfigRemoveCommand;
 
     let mut cmd = ConfigRemoveCommand::new(verbose);
-    cmd.set_remove_all(all);
-    cmd.execute(&server).map_err(convert_mcp_error)
+    cmd.set_remove_all(flags.all);
+    cmd.set_client(flags.client);
+    cmd.set_purge(flags.purge);
+    cmd.set_prune_docker(flags.prune_docker);
+    cmd.set_dry_run(flags.dry_run);
+    cmd.set_yes(flags.yes);
+    // clap guarantees that either a server name or --glob is present
+    match glob {
+        Some(pattern) => cmd.execute_pattern(&pattern),
+        None => cmd.execute(server.as_deref().unwrap_or_default()),
+    }
+    .map_err(convert_mcp_error)
+}
+
+/// Execute the uninstall command
+fn execute_uninstall_command(
+    server: &str,
+    flags: RemoveFlags,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::uninstall::UninstallCommand;
+
+    UninstallCommand::new(verbose)
+        .with_all_clients(flags.all)
+        .with_client(flags.client)
+        .with_cleanup(flags.purge || flags.prune_docker)
+        .with_dry_run(flags.dry_run)
+        .with_yes(flags.yes)
+        .execute(server)
+        .map_err(convert_mcp_error)
 }
 
 /// Execute config commands (deprecated - redirects to new top-level commands)
-fn execute_config_command(action: ConfigAction) -> anyhow::Result<()> {
+fn execute_config_command(action: ConfigAction, verbose: bool) -> anyhow::Result<()> {
+    match action {
+        ConfigAction::Lint { fix } => return execute_lint_command(fix, verbose),
+        ConfigAction::Repair { client, yes } => {
+            return execute_repair_command(client, yes, verbose)
+        }
+        ConfigAction::History { server, client } => {
+            return execute_history_command(&server, client, verbose)
+        }
+        ConfigAction::Rollback {
+            server,
+            to,
+            client,
+            yes,
+        } => return execute_rollback_command(&server, to.as_deref(), client, yes, verbose),
+        _ => {}
+    }
+
     eprintln!(
         "{} The 'config' subcommands are deprecated. Please use top-level commands instead:",
         "⚠".yellow()
@@ -242,19 +1408,492 @@ fn execute_config_command(action: ConfigAction) -> anyhow::Result<()> {
     eprintln!();
 
     match action {
-        ConfigAction::Add { server } => {
-            execute_add_command(server, None, Vec::new(), Vec::new(), false, false)
+        ConfigAction::Add { server } => execute_add_command(
+            AddCommand::new(false),
+            server,
+            None,
+            Vec::new(),
+            Vec::new(),
+            false,
+        ),
+        ConfigAction::List => execute_config_list_command(),
+        ConfigAction::Remove { server, glob } => {
+            let flags = RemoveFlags {
+                all: false,
+                client: None,
+                purge: false,
+                prune_docker: false,
+                dry_run: false,
+                yes: false,
+            };
+            execute_remove_command(server, glob, flags, false)
+        }
+        ConfigAction::Lint { fix } => execute_lint_command(fix, verbose),
+        ConfigAction::Repair { client, yes } => execute_repair_command(client, yes, verbose),
+        ConfigAction::History { server, client } => {
+            execute_history_command(&server, client, verbose)
         }
-        ConfigAction::List => execute_list_command(false),
-        ConfigAction::Remove { server } => execute_remove_command(server, false, false),
+        ConfigAction::Rollback {
+            server,
+            to,
+            client,
+            yes,
+        } => execute_rollback_command(&server, to.as_deref(), client, yes, verbose),
     }
 }
 
+/// Execute the config lint command
+fn execute_lint_command(fix: bool, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::config_commands::ConfigLintCommand;
+
+    let mut cmd = ConfigLintCommand::new(verbose);
+    cmd.set_fix(fix);
+    cmd.execute().map_err(convert_mcp_error)
+}
+
+/// Execute the config repair command
+fn execute_repair_command(client: Option<String>, yes: bool, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::config_commands::ConfigRepairCommand;
+
+    let mut cmd = ConfigRepairCommand::new(verbose);
+    cmd.set_client(client);
+    cmd.set_yes(yes);
+    cmd.execute().map_err(convert_mcp_error)
+}
+
+/// Execute the config history command
+fn execute_history_command(
+    server: &str,
+    client: Option<String>,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::config_commands::ConfigHistoryCommand;
+
+    let mut cmd = ConfigHistoryCommand::new(verbose);
+    cmd.set_client(client);
+    cmd.execute(server).map_err(convert_mcp_error)
+}
+
+/// Execute the config rollback command
+fn execute_rollback_command(
+    server: &str,
+    to: Option<&str>,
+    client: Option<String>,
+    yes: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::config_commands::{parse_snapshot_time, ConfigRollbackCommand};
+
+    let mut cmd = ConfigRollbackCommand::new(verbose);
+    cmd.set_to(
+        to.map(parse_snapshot_time)
+            .transpose()
+            .map_err(convert_mcp_error)?,
+    );
+    cmd.set_client(client);
+    cmd.set_yes(yes);
+    cmd.execute(server).map_err(convert_mcp_error)
+}
+
+/// Execute the test-server command
+fn execute_test_server_command() -> anyhow::Result<()> {
+    mcp_helper::test_server::serve(std::io::stdin().lock(), std::io::stdout().lock())
+}
+
+/// Execute the playground command
+fn execute_playground_command(
+    server: &str,
+    client: Option<String>,
+    timeout: u64,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::playground::PlaygroundCommand;
+
+    PlaygroundCommand::new(verbose)
+        .with_client(client)
+        .with_timeout(std::time::Duration::from_secs(timeout))
+        .execute(server)
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the help-server command
+fn execute_help_server_command(name: &str, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::help_server::HelpServerCommand;
+
+    HelpServerCommand::new(verbose)
+        .execute(name)
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the info command
+fn execute_info_command(
+    name: &str,
+    readme: bool,
+    offline: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::info::InfoCommand;
+
+    InfoCommand::new(verbose)
+        .with_offline(offline)
+        .with_readme(readme)
+        .execute(name)
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the inventory command
+fn execute_inventory_command(
+    output: OutputFormat,
+    file: Option<PathBuf>,
+    strict: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::inventory::InventoryCommand;
+
+    InventoryCommand::new(verbose)
+        .with_output_format(output)
+        .with_file(file)
+        .with_strict(strict)
+        .execute()
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the sync-clients command
+fn execute_sync_clients_command(
+    from: &str,
+    to: &[String],
+    dry_run: bool,
+    overwrite: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::sync::SyncClientsCommand;
+
+    let mut cmd = SyncClientsCommand::new(verbose);
+    cmd.set_dry_run(dry_run);
+    cmd.set_overwrite(overwrite);
+    cmd.execute(from, to).map_err(convert_mcp_error)
+}
+
+/// Execute the check command
+fn execute_check_command(
+    sync: bool,
+    update_lockfile: bool,
+    reconcile: bool,
+    yes: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::check::{CheckCommand, Resolution};
+
+    if !sync {
+        anyhow::bail!(
+            "Nothing to check; use 'mcp check --sync' to compare the record with client configs"
+        );
+    }
+    let resolution = if update_lockfile {
+        Some(Resolution::UpdateRecord)
+    } else if reconcile {
+        Some(Resolution::Reconcile)
+    } else {
+        None
+    };
+    CheckCommand::new(verbose)
+        .with_resolution(resolution)
+        .with_yes(yes)
+        .execute_sync()
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the apply command
+fn execute_apply_command(
+    file: &std::path::Path,
+    plan: bool,
+    yes: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::apply::ApplyCommand;
+
+    ApplyCommand::new(verbose)
+        .with_plan_only(plan)
+        .with_yes(yes)
+        .execute(file)
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the import command
+fn execute_import_command(
+    dry_run: bool,
+    normalize: bool,
+    no_normalize: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::import::ImportCommand;
+
+    let mut cmd = ImportCommand::new(verbose);
+    cmd.set_dry_run(dry_run);
+    cmd.set_normalize(match (normalize, no_normalize) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    });
+    cmd.execute().map_err(convert_mcp_error)
+}
+
+/// Execute a docker subcommand
+fn execute_docker_command(action: DockerAction, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::docker_prune::DockerPruneCommand;
+
+    match action {
+        DockerAction::Prune { yes, dry_run } => {
+            let mut cmd = DockerPruneCommand::new(verbose);
+            cmd.set_yes(yes);
+            cmd.set_dry_run(dry_run);
+            cmd.execute()
+        }
+    }
+    .map_err(convert_mcp_error)
+}
+
+/// Execute a state subcommand
+fn execute_state_command(action: StateAction, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::state::StateCommand;
+
+    let cmd = StateCommand::new(verbose);
+    match action {
+        StateAction::Encrypt => cmd.encrypt(),
+        StateAction::Decrypt => cmd.decrypt(),
+    }
+    .map_err(convert_mcp_error)
+}
+
+/// Execute a cache subcommand
+fn execute_cache_command(action: CacheAction) -> anyhow::Result<()> {
+    use mcp_helper::cache::CacheManager;
+
+    match action {
+        CacheAction::Stats => {
+            let stats = CacheManager::new()?.stats();
+            println!(
+                "{} {}",
+                "Cache directory:".bold(),
+                stats.cache_dir.display()
+            );
+            println!(
+                "  npm registry metadata: {} packages ({} fresh)",
+                stats.registry_entries, stats.registry_fresh
+            );
+            println!("  Dependency checks:     {}", stats.dependency_entries);
+            println!("  Server metadata:       {}", stats.metadata_entries);
+            println!();
+            println!("{}", "Registry lookups".bold());
+            println!("  Cache hits:   {}", stats.registry.hits);
+            println!(
+                "  Revalidated:  {} (304 Not Modified)",
+                stats.registry.revalidated
+            );
+            println!("  Downloaded:   {}", stats.registry.misses);
+            if let Some(rate) = stats.registry.hit_rate() {
+                println!("  Hit rate:     {:.0}%", rate * 100.0);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Execute a service subcommand
+fn execute_service_command(action: ServiceAction, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::service::ServiceCommand;
+
+    let cmd = ServiceCommand::new(verbose);
+    let result = match action {
+        ServiceAction::Install { server, client } => cmd.install(&server, client.as_deref()),
+        ServiceAction::Status { server } => cmd.status(server.as_deref()),
+        ServiceAction::Remove { server } => cmd.remove(&server),
+    };
+    result.map_err(convert_mcp_error)
+}
+
+/// Execute an audit subcommand
+fn execute_audit_command(action: AuditAction) -> anyhow::Result<()> {
+    use mcp_helper::audit_log::{parse_since, AuditExportCommand, AuditShowCommand};
+
+    match action {
+        AuditAction::Show { limit, kind, since } => AuditShowCommand::new(limit)
+            .with_kind(kind)
+            .with_since(since.as_deref().map(parse_since).transpose()?)
+            .execute()
+            .map_err(convert_mcp_error),
+        AuditAction::Export { file, since } => AuditExportCommand::new()
+            .with_file(file)
+            .with_since(since.as_deref().map(parse_since).transpose()?)
+            .execute()
+            .map_err(convert_mcp_error),
+    }
+}
+
+/// Execute the audit-secrets command
+fn execute_audit_secrets_command(migrate: bool, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::audit::AuditSecretsCommand;
+
+    let mut cmd = AuditSecretsCommand::new(verbose);
+    cmd.set_migrate(migrate);
+    cmd.execute().map_err(convert_mcp_error)
+}
+
+/// Execute the exec command, exiting with the command's status
+fn execute_exec_command(command: &[String]) -> anyhow::Result<()> {
+    use mcp_helper::audit::exec_with_secrets;
+
+    let (program, args) = command
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("No command given"))?;
+    let code = exec_with_secrets(program, args)?;
+    std::process::exit(code);
+}
+
+/// Execute the graph command
+fn execute_graph_command(format: GraphFormat) -> anyhow::Result<()> {
+    use mcp_helper::graph::GraphCommand;
+
+    GraphCommand::new(format)
+        .execute()
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the export-script command
+fn execute_export_script_command(shell: ScriptShell) -> anyhow::Result<()> {
+    use mcp_helper::export_script::ExportScriptCommand;
+
+    ExportScriptCommand::new(shell)
+        .execute()
+        .map_err(convert_mcp_error)
+}
+
+fn execute_debug_env_command(
+    server: &str,
+    client: Option<String>,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::debug_env::DebugEnvCommand;
+
+    let mut command = DebugEnvCommand::new(verbose);
+    command.set_client(client);
+    command.execute(server).map_err(convert_mcp_error)
+}
+
+/// Execute a debug subcommand
+fn execute_debug_command(action: DebugAction, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::debug_snapshot::DebugSnapshotCommand;
+
+    match action {
+        DebugAction::Snapshot { anonymize, output } => DebugSnapshotCommand::new(verbose)
+            .with_anonymize(anonymize)
+            .with_output(output)
+            .execute()
+            .map_err(convert_mcp_error),
+    }
+}
+
+/// Execute the run command
+fn execute_run_command(
+    server: String,
+    args: Vec<String>,
+    explain: bool,
+    safe: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::runner::{RunExplanation, RunHistory, ServerRunner};
+
+    let platform = if cfg!(target_os = "windows") {
+        mcp_helper::Platform::Windows
+    } else if cfg!(target_os = "macos") {
+        mcp_helper::Platform::MacOS
+    } else {
+        mcp_helper::Platform::Linux
+    };
+
+    if verbose {
+        eprintln!("Detected platform: {platform:?}");
+    }
+    if explain {
+        let runner = ServerRunner::new(platform, verbose);
+        print!("{}", RunExplanation::gather(&runner, &server, &args));
+        println!("{} Nothing was run", "ℹ".blue());
+        return Ok(());
+    }
+    println!("{} Running MCP server: {}", "→".green(), server.cyan());
+
+    let mut runner = ServerRunner::new(platform, verbose);
+    match RunHistory::new() {
+        Ok(history) => runner = runner.with_history(history),
+        Err(e) if verbose => eprintln!("Run history unavailable: {e}"),
+        Err(_) => {}
+    }
+
+    if safe {
+        runner.run_safe(&server, &args)
+    } else {
+        runner.run(&server, &args)
+    }
+}
+
+/// Execute the status command
+fn execute_status_command(summary: bool, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::status::StatusCommand;
+
+    let cmd = StatusCommand::new(verbose);
+    if summary {
+        std::process::exit(cmd.summary_line());
+    }
+    cmd.execute().map_err(convert_mcp_error)
+}
+
+/// Execute the outdated command
+fn execute_outdated_command(output: OutputFormat, verbose: bool) -> anyhow::Result<()> {
+    use mcp_helper::outdated::OutdatedCommand;
+
+    OutdatedCommand::new(verbose)
+        .with_output_format(output)
+        .execute()
+        .map_err(convert_mcp_error)
+}
+
+/// Execute the update command
+fn execute_update_command(
+    server: Option<String>,
+    skip: Vec<String>,
+    allow_major: bool,
+    channel: Option<UpdateChannel>,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use mcp_helper::update::UpdateCommand;
+
+    let command = UpdateCommand::new(verbose)
+        .with_allow_major(allow_major)
+        .with_channel(channel)
+        .with_skip(skip);
+    match server {
+        Some(server) => command.execute(&server),
+        None => command.execute_all(),
+    }
+    .map_err(convert_mcp_error)
+}
+
 /// Execute the doctor command
-fn execute_doctor_command() -> anyhow::Result<()> {
+fn execute_doctor_command(
+    output: OutputFormat,
+    fail_on: FailOn,
+    only: Vec<String>,
+    fix: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
     use mcp_helper::doctor::DoctorCommand;
 
-    let doctor = DoctorCommand::new(false); // verbose is global, not passed here
+    let doctor = DoctorCommand::new(verbose)
+        .with_output_format(output)
+        .with_fail_on(fail_on)
+        .with_categories(only)
+        .with_fix(fix)
+        .with_interactive(std::io::IsTerminal::is_terminal(&std::io::stdin()));
     doctor.execute().map_err(convert_mcp_error)
 }
 
@@ -270,6 +1909,16 @@ fn convert_mcp_error(e: McpError) -> anyhow::Error {
 fn handle_result(result: anyhow::Result<()>) {
     if let Err(e) = result {
         eprintln!();
+        // Security failures exit with a code specific to their class
+        if let Some(violation) = e.downcast_ref::<SecurityViolation>() {
+            eprintln!("{} {}", "✗".red().bold(), violation);
+            std::process::exit(violation.class.exit_code());
+        }
+        // Doctor exits with one code for warnings and another for errors
+        if let Some(failure) = e.downcast_ref::<DoctorFailure>() {
+            eprintln!("{} {}", "✗".red().bold(), failure);
+            std::process::exit(failure.exit_code());
+        }
         match e.downcast::<McpError>() {
             Ok(mcp_err) => {
                 eprintln!("{mcp_err}");
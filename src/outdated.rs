@@ -0,0 +1,248 @@
+//! `mcp outdated`: configured servers that no longer run the newest release.
+//!
+//! - npm packages pinned to a version older than the latest one on their update
+//!   channel
+//! - Docker images pinned to a digest (`--pin-digest`) whose tag now points at
+//!   another digest. Tags are looked up on Docker Hub; images from other
+//!   registries can't be checked.
+//!
+//! Unpinned servers pick up new releases on their own and aren't reported.
+
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::client::{detect_clients, ServerConfig};
+use crate::config::{ManagedServers, ServerKind};
+use crate::doctor::OutputFormat;
+use crate::error::McpError;
+use crate::server::docker::{hub_tag_digest, image_digest, image_tag};
+use crate::update::changelog::{fetch_npm_package, parse_tag_version};
+
+/// A server with a newer release than the one it runs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutdatedServer {
+    pub server: String,
+    /// `npm` or `docker`
+    pub kind: &'static str,
+    /// npm package or Docker image without its digest
+    pub source: String,
+    /// Version or digest the config runs
+    pub current: String,
+    /// Version or digest it would run if added again
+    pub latest: String,
+}
+
+impl OutdatedServer {
+    /// How to move the server to the latest release
+    pub fn fix(&self) -> String {
+        match self.kind {
+            "docker" => format!("mcp add docker:{} --pin-digest", self.source),
+            _ => format!("mcp update {}", self.server),
+        }
+    }
+}
+
+/// `server` runs npm `package` at `current`; is `latest` newer?
+pub fn npm_outdated(
+    server: &str,
+    package: &str,
+    current: &str,
+    latest: &str,
+) -> Option<OutdatedServer> {
+    let newer = match (parse_tag_version(current), parse_tag_version(latest)) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => current != latest,
+    };
+    newer.then(|| OutdatedServer {
+        server: server.to_string(),
+        kind: "npm",
+        source: package.to_string(),
+        current: current.to_string(),
+        latest: latest.to_string(),
+    })
+}
+
+/// `server` runs `image` pinned to a digest; does its tag point at `latest` now?
+pub fn docker_outdated(server: &str, image: &str, latest: &str) -> Option<OutdatedServer> {
+    let current = image_digest(image)?;
+    let source = image.split('@').next().unwrap_or(image);
+    (current != latest).then(|| OutdatedServer {
+        server: server.to_string(),
+        kind: "docker",
+        source: source.to_string(),
+        current: current.to_string(),
+        latest: latest.to_string(),
+    })
+}
+
+/// A digest shortened for tables, like `sha256:0123456789ab`
+fn short_digest(digest: &str) -> &str {
+    let end = digest.find(':').map_or(12, |colon| colon + 13);
+    digest.get(..end).unwrap_or(digest)
+}
+
+/// List servers with newer releases than they run
+pub struct OutdatedCommand {
+    verbose: bool,
+    output: OutputFormat,
+}
+
+impl OutdatedCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            output: OutputFormat::Text,
+        }
+    }
+
+    pub fn with_output_format(mut self, output: OutputFormat) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn execute(&self) -> Result<(), McpError> {
+        // A server configured in several clients is checked once
+        let mut servers: BTreeMap<String, ServerConfig> = BTreeMap::new();
+        for client in detect_clients().iter().filter(|c| c.is_installed()) {
+            match client.list_servers() {
+                Ok(configs) => {
+                    for (name, config) in configs {
+                        servers.entry(name).or_insert(config);
+                    }
+                }
+                Err(e) => tracing::debug!("Failed to list servers of {}: {}", client.name(), e),
+            }
+        }
+
+        let mut outdated = Vec::new();
+        for (name, config) in &servers {
+            if let Some(server) = self.check(name, config)? {
+                outdated.push(server);
+            }
+        }
+
+        match self.output {
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&outdated)
+                    .map_err(|e| McpError::Other(e.into()))?;
+                println!("{json}");
+            }
+            OutputFormat::Text => print_outdated(&outdated),
+        }
+        Ok(())
+    }
+
+    fn check(&self, name: &str, config: &ServerConfig) -> Result<Option<OutdatedServer>, McpError> {
+        match ServerKind::infer(config) {
+            ServerKind::Npm {
+                package,
+                version: Some(current),
+            } => {
+                let channel = ManagedServers::new()
+                    .and_then(|managed| managed.get(name))
+                    .map_err(McpError::Other)?
+                    .map(|server| server.channel)
+                    .unwrap_or_default();
+                let info = fetch_npm_package(&package)
+                    .map_err(McpError::Other)?
+                    .on_channel(channel);
+                Ok(npm_outdated(name, &package, &current, &info.latest))
+            }
+            ServerKind::Docker { image } if image_digest(&image).is_some() => {
+                let tagged = image.split('@').next().unwrap_or(&image);
+                match hub_tag_digest(tagged) {
+                    Some(latest) => Ok(docker_outdated(name, &image, &latest)),
+                    None => {
+                        if self.verbose {
+                            println!(
+                                "  {} Can't look up the tag of {} ({})",
+                                "-".dimmed(),
+                                name.dimmed(),
+                                image_tag(&image).unwrap_or("latest")
+                            );
+                        }
+                        Ok(None)
+                    }
+                }
+            }
+            _ => {
+                if self.verbose {
+                    println!("  {} {} is not pinned", "-".dimmed(), name.dimmed());
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+fn print_outdated(outdated: &[OutdatedServer]) {
+    if outdated.is_empty() {
+        println!(
+            "{} Every pinned server runs the latest release",
+            "✓".green()
+        );
+        return;
+    }
+
+    println!(
+        "{} {} server(s) are outdated:",
+        "⚠".yellow(),
+        outdated.len()
+    );
+    for server in outdated {
+        let (current, latest) = match server.kind {
+            "docker" => (short_digest(&server.current), short_digest(&server.latest)),
+            _ => (server.current.as_str(), server.latest.as_str()),
+        };
+        println!(
+            "  {} ({}) {} → {}",
+            server.server.cyan(),
+            server.source,
+            current.dimmed(),
+            latest.green()
+        );
+        println!("      {}", server.fix().dimmed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_npm_outdated() {
+        let outdated = npm_outdated("github", "@mcp/github", "1.2.0", "1.3.0").unwrap();
+        assert_eq!(outdated.kind, "npm");
+        assert_eq!(outdated.fix(), "mcp update github");
+
+        assert!(npm_outdated("github", "@mcp/github", "1.3.0", "1.3.0").is_none());
+        assert!(npm_outdated("github", "@mcp/github", "2.0.0-beta.1", "1.3.0").is_none());
+    }
+
+    #[test]
+    fn test_docker_outdated_when_tag_moves() {
+        let image = "mcp/postgres:13@sha256:aaaa";
+        assert!(docker_outdated("postgres", image, "sha256:aaaa").is_none());
+
+        let outdated = docker_outdated("postgres", image, "sha256:bbbb").unwrap();
+        assert_eq!(outdated.source, "mcp/postgres:13");
+        assert_eq!(outdated.current, "sha256:aaaa");
+        assert_eq!(
+            outdated.fix(),
+            "mcp add docker:mcp/postgres:13 --pin-digest"
+        );
+
+        // Images without a digest follow their tag already
+        assert!(docker_outdated("postgres", "mcp/postgres:13", "sha256:bbbb").is_none());
+    }
+
+    #[test]
+    fn test_short_digest() {
+        assert_eq!(
+            short_digest("sha256:0123456789abcdef0123"),
+            "sha256:0123456789ab"
+        );
+        assert_eq!(short_digest("sha256:abc"), "sha256:abc");
+    }
+}
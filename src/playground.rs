@@ -0,0 +1,710 @@
+//! `mcp playground <server>`: call a server's tools by hand.
+//!
+//! The playground starts a configured server the way a client would, performs the
+//! MCP handshake and then reads commands from stdin: `tools` lists the tools,
+//! `describe <tool>` shows a tool's input schema and `call <tool> {json}` (or just
+//! `<tool> {json}`) calls it and prints the response. This lets server authors and
+//! users check credentials and config without involving an LLM client.
+
+use anyhow::{anyhow, bail, Context, Result};
+use colored::Colorize;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::client::{detect_clients, ServerConfig};
+use crate::error::McpError;
+use crate::remote::resolve_secret_references;
+use crate::runner::StderrTail;
+use crate::test_server::{builtin_config, is_builtin, DEFAULT_PROTOCOL_VERSION};
+use crate::utils::npm_cache;
+
+/// How long to wait for a reply before giving up on a request
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Name the playground reports to servers in `clientInfo`
+const CLIENT_NAME: &str = "mcp-helper-playground";
+
+const METHOD_NOT_FOUND: i64 = -32601;
+
+const HELP: &str = "\
+Commands:
+  tools                    List the server's tools
+  describe <tool>          Show a tool's description and input schema
+  call <tool> [json]       Call a tool with JSON object arguments (default {})
+  <tool> [json]            Same as call
+  again                    Repeat the last call
+  help                     Show this help
+  quit                     Leave the playground";
+
+/// One line of playground input
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplCommand {
+    Empty,
+    Tools,
+    Describe(String),
+    Call { tool: String, arguments: Value },
+    Again,
+    Help,
+    Quit,
+}
+
+/// Parse one line of input; the error describes what is wrong with it
+pub fn parse_command(line: &str) -> Result<ReplCommand, String> {
+    let line = line.trim();
+    let (word, rest) = match line.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (line, ""),
+    };
+    match word {
+        "" => Ok(ReplCommand::Empty),
+        "tools" | "list" => Ok(ReplCommand::Tools),
+        "help" | "?" => Ok(ReplCommand::Help),
+        "quit" | "exit" => Ok(ReplCommand::Quit),
+        "again" | "!!" => Ok(ReplCommand::Again),
+        "describe" | "schema" => match rest {
+            "" => Err(format!("Usage: {word} <tool>")),
+            tool => Ok(ReplCommand::Describe(tool.to_string())),
+        },
+        "call" => {
+            let (tool, arguments) = match rest.split_once(char::is_whitespace) {
+                Some((tool, arguments)) => (tool, arguments),
+                None => (rest, ""),
+            };
+            if tool.is_empty() {
+                return Err("Usage: call <tool> [json]".to_string());
+            }
+            parse_call(tool, arguments)
+        }
+        tool => parse_call(tool, rest),
+    }
+}
+
+fn parse_call(tool: &str, arguments: &str) -> Result<ReplCommand, String> {
+    let arguments = match arguments.trim() {
+        "" => json!({}),
+        text => serde_json::from_str::<Value>(text).map_err(|e| {
+            format!(
+                "Invalid JSON arguments at column {}: {}",
+                e.column(),
+                e.to_string().split(" at line").next().unwrap_or_default()
+            )
+        })?,
+    };
+    if !arguments.is_object() {
+        return Err("Tool arguments must be a JSON object, e.g. {\"path\": \".\"}".to_string());
+    }
+    Ok(ReplCommand::Call {
+        tool: tool.to_string(),
+        arguments,
+    })
+}
+
+/// Render the result of `tools/call`; the flag is set when the tool reported an error
+pub fn render_tool_result(result: &Value) -> (String, bool) {
+    let mut parts = Vec::new();
+    for item in result
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let part = match item.get("type").and_then(Value::as_str) {
+            Some("text") => item
+                .get("text")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            Some(kind @ ("image" | "audio")) => format!(
+                "[{kind}: {}, {} bytes of base64]",
+                item.get("mimeType")
+                    .and_then(Value::as_str)
+                    .unwrap_or("unknown type"),
+                item.get("data").and_then(Value::as_str).map_or(0, str::len)
+            ),
+            Some("resource_link") => format!(
+                "[resource: {}]",
+                item.get("uri").and_then(Value::as_str).unwrap_or_default()
+            ),
+            Some("resource") => match item.pointer("/resource/text").and_then(Value::as_str) {
+                Some(text) => text.to_string(),
+                None => pretty(item),
+            },
+            _ => pretty(item),
+        };
+        parts.push(part);
+    }
+    if let Some(structured) = result.get("structuredContent") {
+        parts.push(format!("Structured content:\n{}", pretty(structured)));
+    }
+    if parts.is_empty() {
+        parts.push("(no content)".to_string());
+    }
+    let is_error = result
+        .get("isError")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    (parts.join("\n"), is_error)
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// A connection to an MCP server over newline-delimited JSON-RPC
+pub struct McpSession {
+    writer: Box<dyn Write + Send>,
+    messages: Receiver<Value>,
+    child: Option<Child>,
+    stderr: Arc<Mutex<StderrTail>>,
+    timeout: Duration,
+    next_id: u64,
+    /// `serverInfo` from the server's initialize reply
+    pub server_info: Value,
+}
+
+impl McpSession {
+    /// Start the server `config` describes and perform the handshake
+    pub fn spawn(config: &ServerConfig, timeout: Duration, verbose: bool) -> Result<Self> {
+        let mut cmd = Command::new(&config.command);
+        cmd.args(&config.args)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(cwd) = &config.cwd {
+            cmd.current_dir(cwd);
+        }
+        let mut child = cmd
+            .spawn()
+            .with_context(|| format!("Failed to execute command: {}", config.command))?;
+
+        let stderr = Arc::new(Mutex::new(StderrTail::default()));
+        if let Some(pipe) = child.stderr.take() {
+            let tail = Arc::clone(&stderr);
+            std::thread::spawn(move || {
+                for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                    if verbose {
+                        eprintln!("{}", line.dimmed());
+                    }
+                    if let Ok(mut tail) = tail.lock() {
+                        tail.push(line);
+                    }
+                }
+            });
+        }
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            bail!("Failed to open the server's stdio");
+        };
+
+        let mut session = Self::new(stdout, stdin, timeout);
+        session.child = Some(child);
+        session.stderr = stderr;
+        session.initialize()?;
+        Ok(session)
+    }
+
+    /// Perform the handshake with a server reachable through `reader` and `writer`
+    pub fn connect(
+        reader: impl Read + Send + 'static,
+        writer: impl Write + Send + 'static,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let mut session = Self::new(reader, writer, timeout);
+        session.initialize()?;
+        Ok(session)
+    }
+
+    fn new(
+        reader: impl Read + Send + 'static,
+        writer: impl Write + Send + 'static,
+        timeout: Duration,
+    ) -> Self {
+        let (sender, messages) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines().map_while(Result::ok) {
+                // Servers sometimes log to stdout; anything that isn't JSON is skipped
+                if let Ok(message) = serde_json::from_str::<Value>(&line) {
+                    if sender.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Self {
+            writer: Box::new(writer),
+            messages,
+            child: None,
+            stderr: Arc::default(),
+            timeout,
+            next_id: 1,
+            server_info: Value::Null,
+        }
+    }
+
+    fn initialize(&mut self) -> Result<()> {
+        let result = self
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": DEFAULT_PROTOCOL_VERSION,
+                    "capabilities": {},
+                    "clientInfo": { "name": CLIENT_NAME, "version": env!("CARGO_PKG_VERSION") },
+                }),
+            )
+            .map_err(|e| {
+                self.with_stderr(e.context("The server did not complete the handshake"))
+            })?;
+        self.server_info = result.get("serverInfo").cloned().unwrap_or(Value::Null);
+        self.send(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+    }
+
+    /// The tools the server offers
+    pub fn list_tools(&mut self) -> Result<Vec<Value>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let params = match &cursor {
+                Some(cursor) => json!({ "cursor": cursor }),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params)?;
+            tools.extend(
+                result
+                    .get("tools")
+                    .and_then(Value::as_array)
+                    .cloned()
+                    .unwrap_or_default(),
+            );
+            match result.get("nextCursor").and_then(Value::as_str) {
+                Some(next) if cursor.as_deref() != Some(next) => cursor = Some(next.to_string()),
+                _ => return Ok(tools),
+            }
+        }
+    }
+
+    /// Call `tool` and return the raw `tools/call` result
+    pub fn call_tool(&mut self, tool: &str, arguments: &Value) -> Result<Value> {
+        self.request(
+            "tools/call",
+            json!({ "name": tool, "arguments": arguments }),
+        )
+    }
+
+    /// Send a request and wait for its reply, answering requests from the server meanwhile
+    fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(&json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))?;
+
+        loop {
+            let message = match self.messages.recv_timeout(self.timeout) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => bail!(
+                    "No reply to '{}' within {} seconds",
+                    method,
+                    self.timeout.as_secs()
+                ),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(self.with_stderr(anyhow!("The server closed its output")))
+                }
+            };
+            if let Some(server_method) = message.get("method").and_then(Value::as_str) {
+                if let Some(request_id) = message.get("id") {
+                    let reply = match server_method {
+                        "ping" => json!({ "jsonrpc": "2.0", "id": request_id, "result": {} }),
+                        _ => json!({
+                            "jsonrpc": "2.0",
+                            "id": request_id,
+                            "error": { "code": METHOD_NOT_FOUND, "message": format!("Method not supported by the playground: {server_method}") },
+                        }),
+                    };
+                    self.send(&reply)?;
+                }
+                continue;
+            }
+            if message.get("id").and_then(Value::as_u64) != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                bail!(
+                    "{} (code {})",
+                    error
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or("Unknown error"),
+                    error
+                        .get("code")
+                        .and_then(Value::as_i64)
+                        .unwrap_or_default()
+                );
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    fn send(&mut self, message: &Value) -> Result<()> {
+        writeln!(self.writer, "{message}").context("Failed to write to the server")?;
+        self.writer.flush().context("Failed to write to the server")
+    }
+
+    /// `error` with the last lines the server wrote to stderr
+    fn with_stderr(&self, error: anyhow::Error) -> anyhow::Error {
+        // Give the stderr reader a moment to catch up with a server that just exited
+        std::thread::sleep(Duration::from_millis(100));
+        let lines = self
+            .stderr
+            .lock()
+            .map(|tail| tail.lines())
+            .unwrap_or_default();
+        if lines.is_empty() {
+            return error;
+        }
+        let mut context = format!("Server stderr:\n  {}", lines.join("\n  "));
+        if npm_cache::explains_failure(&lines) {
+            context.push_str(&format!("\n{}", npm_cache::CORRUPTION_HINT));
+        }
+        error.context(context)
+    }
+}
+
+impl Drop for McpSession {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Interactive tool calls against a configured server
+pub struct PlaygroundCommand {
+    verbose: bool,
+    client: Option<String>,
+    timeout: Duration,
+}
+
+impl PlaygroundCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            client: None,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+        }
+    }
+
+    /// Take the server configuration from this client (case-insensitive)
+    pub fn with_client(mut self, client: Option<String>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// How long to wait for each reply
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn execute(&self, server: &str) -> Result<(), McpError> {
+        let (source, config) = self.find_server(server)?;
+        let config = resolve_references(config)?;
+        if self.verbose {
+            eprintln!("Executing command: {} {:?}", config.command, config.args);
+        }
+
+        println!(
+            "{} Starting '{}' from {}",
+            "→".green(),
+            server.cyan(),
+            source
+        );
+        let mut session = McpSession::spawn(&config, self.timeout, self.verbose)?;
+        let name = session
+            .server_info
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or(server)
+            .to_string();
+        let version = session
+            .server_info
+            .get("version")
+            .and_then(Value::as_str)
+            .map(|version| format!(" {version}"))
+            .unwrap_or_default();
+        println!("{} Connected to {}{}", "✓".green(), name.bold(), version);
+        println!("Type 'help' for commands, 'quit' to leave.");
+        println!();
+
+        let stdin = std::io::stdin();
+        let stdout = std::io::stdout();
+        run_repl(&mut session, server, stdin.lock(), stdout.lock())?;
+        Ok(())
+    }
+
+    /// The configuration of `server` and where it came from
+    fn find_server(&self, server: &str) -> Result<(String, ServerConfig), McpError> {
+        if is_builtin(server) {
+            let (command, args, _) = builtin_config(server)?;
+            let config = ServerConfig {
+                command,
+                args,
+                env: Default::default(),
+                cwd: None,
+            };
+            return Ok(("mcp-helper".to_string(), config));
+        }
+
+        for client in detect_clients().iter().filter(|c| c.is_installed()) {
+            if let Some(name) = &self.client {
+                if !name.eq_ignore_ascii_case(client.name()) {
+                    continue;
+                }
+            }
+            if let Some(config) = client
+                .list_servers()
+                .ok()
+                .and_then(|mut servers| servers.remove(server))
+            {
+                return Ok((format!("the {} configuration", client.name()), config));
+            }
+        }
+        let scope = match &self.client {
+            Some(client) => format!("the {client} configuration"),
+            None => "any MCP client configuration".to_string(),
+        };
+        Err(McpError::Other(anyhow!(
+            "Server '{}' not found in {}",
+            server,
+            scope
+        )))
+    }
+}
+
+/// `config` with `${keychain:NAME}` and `${env:NAME}` references replaced
+fn resolve_references(mut config: ServerConfig) -> Result<ServerConfig> {
+    for value in config.env.values_mut().chain(config.args.iter_mut()) {
+        *value = resolve_secret_references(value)?;
+    }
+    Ok(config)
+}
+
+/// Read commands from `input` until it ends or the user quits
+pub fn run_repl(
+    session: &mut McpSession,
+    server: &str,
+    input: impl BufRead,
+    mut output: impl Write,
+) -> Result<()> {
+    let mut tools: Option<Vec<Value>> = None;
+    let mut last_call: Option<(String, Value)> = None;
+    let mut lines = input.lines();
+
+    loop {
+        write!(output, "{server}> ")?;
+        output.flush()?;
+        let Some(line) = lines.next() else {
+            writeln!(output)?;
+            return Ok(());
+        };
+        let command = match parse_command(&line?) {
+            Ok(command) => command,
+            Err(message) => {
+                writeln!(output, "{} {}", "✗".red(), message)?;
+                continue;
+            }
+        };
+
+        let result = match command {
+            ReplCommand::Empty => Ok(()),
+            ReplCommand::Quit => return Ok(()),
+            ReplCommand::Help => writeln!(output, "{HELP}").map_err(Into::into),
+            ReplCommand::Tools => session.list_tools().and_then(|list| {
+                print_tools(&mut output, &list)?;
+                tools = Some(list);
+                Ok(())
+            }),
+            ReplCommand::Describe(tool) => {
+                if tools.is_none() {
+                    tools = session.list_tools().ok();
+                }
+                describe_tool(&mut output, tools.as_deref().unwrap_or_default(), &tool)
+            }
+            ReplCommand::Again => match last_call.clone() {
+                Some((tool, arguments)) => call(&mut output, session, &tool, &arguments),
+                None => writeln!(output, "Nothing to repeat yet").map_err(Into::into),
+            },
+            ReplCommand::Call { tool, arguments } => {
+                let result = call(&mut output, session, &tool, &arguments);
+                last_call = Some((tool, arguments));
+                result
+            }
+        };
+        if let Err(e) = result {
+            writeln!(output, "{} {:#}", "✗".red(), e)?;
+        }
+    }
+}
+
+fn call(
+    output: &mut impl Write,
+    session: &mut McpSession,
+    tool: &str,
+    arguments: &Value,
+) -> Result<()> {
+    let result = session.call_tool(tool, arguments)?;
+    let (text, is_error) = render_tool_result(&result);
+    if is_error {
+        writeln!(output, "{} Tool reported an error:", "✗".red())?;
+    }
+    writeln!(output, "{text}")?;
+    Ok(())
+}
+
+fn print_tools(output: &mut impl Write, tools: &[Value]) -> Result<()> {
+    if tools.is_empty() {
+        writeln!(output, "The server offers no tools")?;
+        return Ok(());
+    }
+    for tool in tools {
+        let name = tool.get("name").and_then(Value::as_str).unwrap_or("?");
+        let description = tool
+            .get("description")
+            .and_then(Value::as_str)
+            .and_then(|text| text.lines().next())
+            .unwrap_or_default();
+        writeln!(output, "  {:<24} {}", name.cyan(), description)?;
+    }
+    Ok(())
+}
+
+fn describe_tool(output: &mut impl Write, tools: &[Value], name: &str) -> Result<()> {
+    let Some(tool) = tools
+        .iter()
+        .find(|tool| tool.get("name").and_then(Value::as_str) == Some(name))
+    else {
+        bail!("Unknown tool '{}'; 'tools' lists the available ones", name);
+    };
+    writeln!(output, "{}", name.bold())?;
+    if let Some(description) = tool.get("description").and_then(Value::as_str) {
+        writeln!(output, "{description}")?;
+    }
+    if let Some(schema) = tool.get("inputSchema") {
+        writeln!(output, "Input schema:\n{}", pretty(schema))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::serve;
+
+    /// A session with the built-in test server running on a thread
+    fn test_session() -> McpSession {
+        let (server_reader, client_writer) = std::io::pipe().unwrap();
+        let (client_reader, server_writer) = std::io::pipe().unwrap();
+        std::thread::spawn(move || serve(BufReader::new(server_reader), server_writer));
+        McpSession::connect(client_reader, client_writer, Duration::from_secs(5)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("  "), Ok(ReplCommand::Empty));
+        assert_eq!(parse_command("tools"), Ok(ReplCommand::Tools));
+        assert_eq!(parse_command("exit"), Ok(ReplCommand::Quit));
+        assert_eq!(
+            parse_command("describe echo"),
+            Ok(ReplCommand::Describe("echo".to_string()))
+        );
+        assert_eq!(
+            parse_command(r#"call echo {"message": "hi there"}"#),
+            Ok(ReplCommand::Call {
+                tool: "echo".to_string(),
+                arguments: json!({ "message": "hi there" }),
+            })
+        );
+        assert_eq!(
+            parse_command("list_files"),
+            Ok(ReplCommand::Call {
+                tool: "list_files".to_string(),
+                arguments: json!({}),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_command_rejects_bad_arguments() {
+        let error = parse_command(r#"call echo {"message": }"#).unwrap_err();
+        assert!(
+            error.starts_with("Invalid JSON arguments at column"),
+            "{error}"
+        );
+        assert!(parse_command("call echo [1]")
+            .unwrap_err()
+            .contains("JSON object"));
+        assert!(parse_command("call").is_err());
+        assert!(parse_command("describe").is_err());
+    }
+
+    #[test]
+    fn test_render_tool_result() {
+        let (text, is_error) = render_tool_result(&json!({
+            "content": [
+                { "type": "text", "text": "hello" },
+                { "type": "image", "mimeType": "image/png", "data": "AAAA" },
+            ],
+            "isError": true,
+        }));
+        assert_eq!(text, "hello\n[image: image/png, 4 bytes of base64]");
+        assert!(is_error);
+
+        let (text, is_error) = render_tool_result(&json!({ "content": [] }));
+        assert_eq!(text, "(no content)");
+        assert!(!is_error);
+    }
+
+    #[test]
+    fn test_session_lists_and_calls_tools() {
+        let mut session = test_session();
+        assert_eq!(
+            session.server_info["name"],
+            crate::test_server::TEST_SERVER_NAME
+        );
+
+        let tools = session.list_tools().unwrap();
+        assert_eq!(tools[0]["name"], "echo");
+
+        let result = session
+            .call_tool("echo", &json!({ "message": "hello" }))
+            .unwrap();
+        assert_eq!(render_tool_result(&result), ("hello".to_string(), false));
+
+        let error = session.call_tool("missing", &json!({})).unwrap_err();
+        assert!(error.to_string().contains("Unknown tool"), "{error}");
+    }
+
+    #[test]
+    fn test_repl_round_trip() {
+        let mut session = test_session();
+        let input = concat!(
+            "tools\n",
+            "echo {\"message\": \"first\"}\n",
+            "again\n",
+            "echo {oops}\n",
+            "quit\n",
+            "tools\n",
+        );
+        let mut output = Vec::new();
+        run_repl(&mut session, "test", input.as_bytes(), &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Return the given message"), "{output}");
+        assert_eq!(output.matches("first\n").count(), 2, "{output}");
+        assert!(output.contains("Invalid JSON arguments"), "{output}");
+        // Nothing after quit is read
+        assert_eq!(output.matches("Return the given message").count(), 1);
+    }
+}
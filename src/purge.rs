@@ -0,0 +1,344 @@
+//! Files and secrets a removed server leaves behind.
+//!
+//! `mcp remove --purge` deletes, next to the client entries:
+//!
+//! - the virtualenv a Python server runs from (a directory with `pyvenv.cfg`)
+//! - `--prefix` directories and binaries inside mcp-helper's `~/.mcp` folder
+//! - cached downloads of those binaries
+//! - `${keychain:NAME}` secrets the server's arguments or environment refer to
+//!
+//! Anything another configured server still uses is kept, as is anything
+//! mcp-helper can't have created: binaries and prefixes outside `~/.mcp` and
+//! virtualenvs outside the home directory.
+
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cache::CacheManager;
+use crate::client::ServerConfig;
+use crate::remote::{keychain, keychain_references};
+
+/// Something a server left behind that `--purge` can delete
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Artifact {
+    Venv(PathBuf),
+    Prefix(PathBuf),
+    Binary(PathBuf),
+    CachedDownload(PathBuf),
+    Secret(String),
+}
+
+impl Artifact {
+    /// Delete the artifact
+    pub fn remove(&self) -> Result<()> {
+        match self {
+            Self::Venv(path) | Self::Prefix(path) => fs::remove_dir_all(path)
+                .with_context(|| format!("Failed to delete {}", path.display())),
+            Self::Binary(path) | Self::CachedDownload(path) => fs::remove_file(path)
+                .with_context(|| format!("Failed to delete {}", path.display())),
+            Self::Secret(name) => keychain::delete(name),
+        }
+    }
+
+    fn path(&self) -> Option<&Path> {
+        match self {
+            Self::Venv(path)
+            | Self::Prefix(path)
+            | Self::Binary(path)
+            | Self::CachedDownload(path) => Some(path),
+            Self::Secret(_) => None,
+        }
+    }
+
+    /// Whether deleting `self` would break something that uses `other`
+    fn overlaps(&self, other: &Artifact) -> bool {
+        match (self.path(), other.path()) {
+            (Some(a), Some(b)) => b.starts_with(a) || a.starts_with(b),
+            (None, None) => self == other,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Artifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Venv(path) => write!(f, "virtualenv {}", path.display()),
+            Self::Prefix(path) => write!(f, "install prefix {}", path.display()),
+            Self::Binary(path) => write!(f, "binary {}", path.display()),
+            Self::CachedDownload(path) => write!(f, "cached download {}", path.display()),
+            Self::Secret(name) => write!(f, "keychain secret '{name}'"),
+        }
+    }
+}
+
+/// Where mcp-helper keeps what it installs
+#[derive(Debug, Clone)]
+pub struct PurgeRoots {
+    pub home: PathBuf,
+    /// `~/.mcp`
+    pub managed: PathBuf,
+    pub downloads: Option<PathBuf>,
+}
+
+impl PurgeRoots {
+    pub fn new() -> Result<Self> {
+        let home = directories::BaseDirs::new()
+            .context("Could not determine home directory")?
+            .home_dir()
+            .to_path_buf();
+        Ok(Self {
+            managed: home.join(".mcp"),
+            home,
+            downloads: CacheManager::new().ok().map(|cache| cache.downloads_dir()),
+        })
+    }
+}
+
+/// What `--purge` will delete and what it leaves alone
+#[derive(Debug, Default, PartialEq)]
+pub struct PurgePlan {
+    pub delete: Vec<Artifact>,
+    /// Artifacts that are kept, with the reason
+    pub keep: Vec<(Artifact, String)>,
+}
+
+impl PurgePlan {
+    pub fn is_empty(&self) -> bool {
+        self.delete.is_empty() && self.keep.is_empty()
+    }
+}
+
+/// Plan the purge of `removed`, keeping whatever `still_used` needs.
+pub fn plan_purge(
+    removed: &[ServerConfig],
+    still_used: &[ServerConfig],
+    roots: &PurgeRoots,
+) -> PurgePlan {
+    let mut plan = PurgePlan::default();
+    let mut found: Vec<Artifact> = Vec::new();
+    for config in removed {
+        for artifact in artifacts_of(config, roots) {
+            if !found.contains(&artifact) {
+                found.push(artifact);
+            }
+        }
+    }
+    found.sort();
+
+    let in_use: Vec<Artifact> = still_used
+        .iter()
+        .flat_map(|config| artifacts_of(config, roots))
+        .collect();
+
+    for artifact in found {
+        if let Some(reason) = not_ours(&artifact, roots) {
+            plan.keep.push((artifact, reason));
+        } else if in_use.iter().any(|used| artifact.overlaps(used)) {
+            plan.keep
+                .push((artifact, "still used by another server".to_string()));
+        } else {
+            plan.delete.push(artifact);
+        }
+    }
+    plan
+}
+
+/// Everything `config` points at that could be purged, before any filtering
+fn artifacts_of(config: &ServerConfig, roots: &PurgeRoots) -> Vec<Artifact> {
+    let mut artifacts = Vec::new();
+
+    let paths =
+        std::iter::once(config.command.as_str()).chain(config.args.iter().map(String::as_str));
+    for value in paths {
+        let path = Path::new(value);
+        if !path.is_absolute() || !path.is_file() {
+            continue;
+        }
+        if let Some(venv) = venv_of(path) {
+            artifacts.push(Artifact::Venv(venv));
+        } else if path.starts_with(&roots.managed) {
+            artifacts.extend(cached_downloads(path, roots));
+            artifacts.push(Artifact::Binary(path.to_path_buf()));
+        }
+    }
+
+    let mut args = config.args.iter();
+    while let Some(arg) = args.next() {
+        let prefix = match arg.strip_prefix("--prefix=") {
+            Some(value) => Some(value.to_string()),
+            None if arg == "--prefix" => args.next().cloned(),
+            None => None,
+        };
+        if let Some(prefix) = prefix.filter(|p| Path::new(p).is_dir()) {
+            artifacts.push(Artifact::Prefix(PathBuf::from(prefix)));
+        }
+    }
+
+    for value in config.args.iter().chain(config.env.values()) {
+        artifacts.extend(keychain_references(value).into_iter().map(Artifact::Secret));
+    }
+    artifacts
+}
+
+/// The virtualenv `python` runs from, if it is one
+fn venv_of(python: &Path) -> Option<PathBuf> {
+    let bin = python.parent()?;
+    let name = bin.file_name()?.to_str()?;
+    if name != "bin" && name != "Scripts" {
+        return None;
+    }
+    let venv = bin.parent()?;
+    venv.join("pyvenv.cfg")
+        .is_file()
+        .then(|| venv.to_path_buf())
+}
+
+/// Downloads cached for `binary`; their names end in `_<file name>`
+fn cached_downloads(binary: &Path, roots: &PurgeRoots) -> Vec<Artifact> {
+    let (Some(downloads), Some(file_name)) = (&roots.downloads, binary.file_name()) else {
+        return Vec::new();
+    };
+    let suffix = format!("_{}", file_name.to_string_lossy());
+    let Ok(entries) = fs::read_dir(downloads) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(&suffix))
+        .map(|entry| Artifact::CachedDownload(entry.path()))
+        .collect()
+}
+
+/// Why mcp-helper must not delete `artifact`, if it mustn't
+fn not_ours(artifact: &Artifact, roots: &PurgeRoots) -> Option<String> {
+    match artifact {
+        Artifact::Venv(path) if !path.starts_with(&roots.home) || path == &roots.home => {
+            Some("outside your home directory".to_string())
+        }
+        Artifact::Prefix(path) if !path.starts_with(&roots.managed) || path == &roots.managed => {
+            Some(format!("not inside {}", roots.managed.display()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn config(command: &Path, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string_lossy().to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    fn roots(temp: &TempDir) -> PurgeRoots {
+        let home = temp.path().join("home");
+        PurgeRoots {
+            managed: home.join(".mcp"),
+            downloads: Some(temp.path().join("cache").join("downloads")),
+            home,
+        }
+    }
+
+    #[test]
+    fn test_plan_purge_finds_artifacts() {
+        let temp = TempDir::new().unwrap();
+        let roots = roots(&temp);
+
+        let binary = roots.managed.join("bin").join("server-x");
+        touch(&binary);
+        let download = roots.downloads.as_ref().unwrap().join("abc123_server-x");
+        touch(&download);
+        let venv = roots.home.join("venvs").join("weather");
+        touch(&venv.join("pyvenv.cfg"));
+        let python = venv.join("bin").join("python");
+        touch(&python);
+        let prefix = roots.managed.join("prefixes").join("tool");
+        fs::create_dir_all(&prefix).unwrap();
+
+        let mut weather = config(
+            &python,
+            &["-m", "weather", "--prefix", &prefix.to_string_lossy()],
+        );
+        weather
+            .env
+            .insert("API_KEY".to_string(), "${keychain:weather}".to_string());
+        let plan = plan_purge(&[config(&binary, &[]), weather], &[], &roots);
+
+        assert_eq!(
+            plan.delete,
+            vec![
+                Artifact::Venv(venv),
+                Artifact::Prefix(prefix),
+                Artifact::Binary(binary),
+                Artifact::CachedDownload(download),
+                Artifact::Secret("weather".to_string()),
+            ]
+        );
+        assert!(plan.keep.is_empty());
+    }
+
+    #[test]
+    fn test_plan_purge_keeps_shared_and_foreign_artifacts() {
+        let temp = TempDir::new().unwrap();
+        let roots = roots(&temp);
+
+        let binary = roots.managed.join("bin").join("shared");
+        touch(&binary);
+        let foreign = temp.path().join("usr").join("bin").join("node");
+        touch(&foreign);
+        let outside = temp.path().join("srv").join("prefix");
+        fs::create_dir_all(&outside).unwrap();
+
+        let mut removed = config(&binary, &[&format!("--prefix={}", outside.display())]);
+        removed
+            .env
+            .insert("TOKEN".to_string(), "${keychain:github}".to_string());
+        let mut other = config(&binary, &["--other"]);
+        other
+            .env
+            .insert("TOKEN".to_string(), "${keychain:github}".to_string());
+
+        let plan = plan_purge(&[removed, config(&foreign, &[])], &[other], &roots);
+
+        assert!(plan.delete.is_empty(), "{:?}", plan.delete);
+        let kept: Vec<&Artifact> = plan.keep.iter().map(|(artifact, _)| artifact).collect();
+        assert_eq!(
+            kept,
+            vec![
+                &Artifact::Prefix(outside),
+                &Artifact::Binary(binary),
+                &Artifact::Secret("github".to_string()),
+            ]
+        );
+        assert_eq!(plan.keep[1].1, "still used by another server");
+    }
+
+    #[test]
+    fn test_remove_deletes_files_and_directories() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("bin").join("server");
+        touch(&file);
+        Artifact::Binary(file.clone()).remove().unwrap();
+        assert!(!file.exists());
+
+        let dir = temp.path().join("venv");
+        touch(&dir.join("pyvenv.cfg"));
+        Artifact::Venv(dir.clone()).remove().unwrap();
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,919 @@
+//! A stdio bridge to remote MCP servers.
+//!
+//! `mcp bridge --url URL [--header Name=Value]...` is the command written for
+//! clients that only launch stdio servers. It reads one JSON-RPC message per line
+//! from stdin, sends it to the server, and writes every reply to stdout, one
+//! message per line. Diagnostics go to stderr.
+//!
+//! Two transports are supported: streamable HTTP (see [`super::streamable`]) and
+//! the older HTTP+SSE transport (see [`super::sse`]). With `--transport auto`,
+//! URLs ending in `/sse` use SSE. Any other URL tries streamable HTTP first, and
+//! falls back to SSE when the server rejects the `POST` of `initialize`.
+//!
+//! The bridge keeps the client's session alive across network trouble:
+//!
+//! - Messages that cannot connect are retried with exponential backoff
+//! - When the server loses the session, the bridge reconnects, replays the
+//!   client's `initialize` handshake, and resends the message
+//! - A `ping` goes out every `--heartbeat` seconds, and a missed reply forces a
+//!   reconnect
+//!
+//! When stdin closes, in-flight requests get a few seconds to finish. Then the
+//! session is ended and the bridge exits.
+
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::sse::SseTransport;
+use super::streamable::StreamableHttpTransport;
+use super::{resolve_secret_references, RemoteServerConfig};
+
+/// Seconds between heartbeat pings unless `--heartbeat` says otherwise
+pub const DEFAULT_HEARTBEAT_SECS: u64 = 30;
+
+/// Connection attempts before a message or event stream gives up
+pub const DEFAULT_MAX_RETRIES: u32 = 5;
+
+/// JSON-RPC error code used for transport failures
+const TRANSPORT_ERROR_CODE: i64 = -32000;
+
+/// Requests the bridge sends itself use string ids with this prefix
+const INTERNAL_ID_PREFIX: &str = "mcp-bridge-";
+
+/// How long in-flight requests may run after stdin closes
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+const HEARTBEAT_TICK: Duration = Duration::from_millis(100);
+
+/// Which HTTP transport the remote server speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransportKind {
+    /// Pick from the URL, falling back from streamable HTTP to SSE
+    #[default]
+    Auto,
+    StreamableHttp,
+    Sse,
+}
+
+impl TransportKind {
+    /// The transport `url` most likely speaks: SSE for paths ending in `/sse`
+    pub fn detect(url: &str) -> Self {
+        let is_sse = url::Url::parse(url)
+            .map(|url| url.path().trim_end_matches('/').ends_with("/sse"))
+            .unwrap_or(false);
+        if is_sse {
+            Self::Sse
+        } else {
+            Self::StreamableHttp
+        }
+    }
+}
+
+impl FromStr for TransportKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "streamable-http" | "http" => Ok(Self::StreamableHttp),
+            "sse" => Ok(Self::Sse),
+            other => Err(format!(
+                "Invalid transport '{other}'. Expected one of: auto, streamable-http, sse"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for TransportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::StreamableHttp => write!(f, "streamable-http"),
+            Self::Sse => write!(f, "sse"),
+        }
+    }
+}
+
+/// How the bridge connects and how hard it tries to stay connected
+#[derive(Debug, Clone)]
+pub struct BridgeOptions {
+    pub transport: TransportKind,
+    /// Interval between pings; `None` disables heartbeats
+    pub heartbeat: Option<Duration>,
+    pub max_retries: u32,
+}
+
+impl Default for BridgeOptions {
+    fn default() -> Self {
+        Self {
+            transport: TransportKind::Auto,
+            heartbeat: Some(Duration::from_secs(DEFAULT_HEARTBEAT_SECS)),
+            max_retries: DEFAULT_MAX_RETRIES,
+        }
+    }
+}
+
+/// The server no longer knows the session
+#[derive(Debug)]
+pub(crate) struct SessionLost;
+
+impl fmt::Display for SessionLost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the server ended the session")
+    }
+}
+
+impl std::error::Error for SessionLost {}
+
+/// The server rejected a streamable HTTP `initialize`
+#[derive(Debug)]
+pub(crate) struct SseFallback;
+
+impl fmt::Display for SseFallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the server does not accept streamable HTTP requests")
+    }
+}
+
+impl std::error::Error for SseFallback {}
+
+/// No connection to the server is currently open
+#[derive(Debug)]
+pub(crate) struct Disconnected;
+
+impl fmt::Display for Disconnected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not connected to the server")
+    }
+}
+
+impl std::error::Error for Disconnected {}
+
+/// A way of exchanging messages with the server
+pub(crate) trait Transport: Send + Sync {
+    /// Send one message; replies are delivered to the transport's [`Sink`]
+    fn send(&self, body: &str, message: &Value, timeout: Option<Duration>) -> Result<()>;
+
+    /// Drop the current session so the next `initialize` starts a fresh one
+    fn reset(&self) -> Result<()>;
+
+    /// End the session for good
+    fn close(&self);
+}
+
+pub(crate) fn is_initialize(message: &Value) -> bool {
+    message.get("method").and_then(Value::as_str) == Some("initialize")
+}
+
+fn is_request(message: &Value) -> bool {
+    message.get("method").is_some() && message.get("id").is_some()
+}
+
+/// Delay before retry number `attempt` (from 0): 1s, 2s, 4s, ... up to 30s
+pub(crate) fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.min(5)).min(Duration::from_secs(30))
+}
+
+/// Errors worth retrying: the message never reached the server
+fn is_retryable(error: &anyhow::Error) -> bool {
+    error.is::<Disconnected>()
+        || error.chain().any(|cause| {
+            cause
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(|e| e.is_connect())
+        })
+}
+
+/// Where replies go: stdout, minus replies to the bridge's own requests
+pub(crate) struct Sink {
+    output: Mutex<Box<dyn Write + Send>>,
+    internal: Mutex<HashSet<String>>,
+    closed: AtomicBool,
+}
+
+impl Sink {
+    fn new(output: Box<dyn Write + Send>) -> Self {
+        Self {
+            output: Mutex::new(output),
+            internal: Mutex::new(HashSet::new()),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Write a message from the server to stdout
+    pub(crate) fn deliver(&self, message: &Value) -> Result<()> {
+        if let Some(id) = message
+            .get("id")
+            .and_then(Value::as_str)
+            .filter(|id| id.starts_with(INTERNAL_ID_PREFIX))
+        {
+            self.lock_internal().remove(id);
+            return Ok(());
+        }
+
+        let mut output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+        let written = writeln!(output, "{message}").and_then(|_| output.flush());
+        if written.is_err() {
+            self.closed.store(true, Ordering::SeqCst);
+        }
+        written.context("Failed to write to stdout")
+    }
+
+    /// Swallow the reply to the bridge's own request `id`
+    fn expect(&self, id: &str) {
+        self.lock_internal().insert(id.to_string());
+    }
+
+    fn forget(&self, id: &str) {
+        self.lock_internal().remove(id);
+    }
+
+    fn is_pending(&self, id: &str) -> bool {
+        self.lock_internal().contains(id)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    fn lock_internal(&self) -> std::sync::MutexGuard<'_, HashSet<String>> {
+        self.internal.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Relays JSON-RPC messages between stdio and a remote server
+pub struct Bridge {
+    url: String,
+    headers: HeaderMap,
+    options: BridgeOptions,
+}
+
+impl Bridge {
+    /// Create a bridge for `remote`, resolving secret references in its headers
+    pub fn new(remote: &RemoteServerConfig, options: BridgeOptions) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &remote.headers {
+            let resolved = resolve_secret_references(value)
+                .with_context(|| format!("Failed to resolve header '{name}'"))?;
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid header name '{name}'"))?;
+            let mut header_value = HeaderValue::from_str(&resolved)
+                .with_context(|| format!("Invalid value for header '{name}'"))?;
+            header_value.set_sensitive(true);
+            headers.insert(header_name, header_value);
+        }
+
+        Ok(Self {
+            url: remote.url.clone(),
+            headers,
+            options,
+        })
+    }
+
+    /// Forward messages from `input` until it closes, then end the session
+    pub fn run<R: BufRead, W: Write + Send + 'static>(&self, input: R, output: W) -> Result<()> {
+        let session = Arc::new(Session::new(self, Box::new(output)));
+        if let Some(interval) = self.options.heartbeat {
+            let session = Arc::clone(&session);
+            std::thread::spawn(move || session.heartbeat(interval));
+        }
+
+        for line in input.lines() {
+            // A read error means the client is gone, which ends the session like EOF
+            let Ok(line) = line else { break };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message: Value = match serde_json::from_str(&line) {
+                Ok(message) => message,
+                Err(e) => {
+                    eprintln!("mcp bridge: ignoring malformed message: {e}");
+                    continue;
+                }
+            };
+            session.remember_handshake(&line, &message);
+
+            // Requests run concurrently once the session is up; everything else
+            // stays in order
+            if is_request(&message) && !is_initialize(&message) && session.is_initialized() {
+                session.in_flight.fetch_add(1, Ordering::SeqCst);
+                let session = Arc::clone(&session);
+                std::thread::spawn(move || {
+                    session.forward(&line, &message);
+                    session.in_flight.fetch_sub(1, Ordering::SeqCst);
+                });
+            } else {
+                session.forward(&line, &message);
+            }
+
+            if session.sink.is_closed() {
+                break;
+            }
+        }
+
+        session.shut_down();
+        Ok(())
+    }
+}
+
+/// The client's handshake, replayed after reconnecting
+#[derive(Default)]
+struct Handshake {
+    initialize: Option<Value>,
+    initialized: Option<String>,
+}
+
+struct Session {
+    url: String,
+    headers: HeaderMap,
+    options: BridgeOptions,
+    sink: Arc<Sink>,
+    transport: Mutex<Option<Arc<dyn Transport>>>,
+    handshake: Mutex<Handshake>,
+    initialized: AtomicBool,
+    /// Bumped after every reconnect so concurrent failures reconnect only once
+    generation: AtomicU64,
+    reconnecting: Mutex<()>,
+    next_id: AtomicU64,
+    in_flight: AtomicUsize,
+    shutting_down: AtomicBool,
+}
+
+impl Session {
+    fn new(bridge: &Bridge, output: Box<dyn Write + Send>) -> Self {
+        Self {
+            url: bridge.url.clone(),
+            headers: bridge.headers.clone(),
+            options: bridge.options.clone(),
+            sink: Arc::new(Sink::new(output)),
+            transport: Mutex::new(None),
+            handshake: Mutex::new(Handshake::default()),
+            initialized: AtomicBool::new(false),
+            generation: AtomicU64::new(0),
+            reconnecting: Mutex::new(()),
+            next_id: AtomicU64::new(1),
+            in_flight: AtomicUsize::new(0),
+            shutting_down: AtomicBool::new(false),
+        }
+    }
+
+    fn is_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
+
+    fn remember_handshake(&self, line: &str, message: &Value) {
+        let mut handshake = self.handshake.lock().unwrap_or_else(|e| e.into_inner());
+        match message.get("method").and_then(Value::as_str) {
+            Some("initialize") => handshake.initialize = Some(message.clone()),
+            Some("notifications/initialized") => handshake.initialized = Some(line.to_string()),
+            _ => {}
+        }
+    }
+
+    /// Send a client message, answering failed requests with a JSON-RPC error
+    fn forward(&self, line: &str, message: &Value) {
+        match self.send(line, message, None) {
+            Ok(()) => {
+                if is_initialize(message) {
+                    self.initialized.store(true, Ordering::SeqCst);
+                }
+            }
+            Err(e) => {
+                let error = format!("{e:#}");
+                eprintln!("mcp bridge: {error}");
+                if let Some(id) = message.get("id").filter(|_| is_request(message)) {
+                    let reply = json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": TRANSPORT_ERROR_CODE, "message": error },
+                    });
+                    let _ = self.sink.deliver(&reply);
+                }
+            }
+        }
+    }
+
+    /// Send a message, retrying, reconnecting or switching transport as needed
+    fn send(&self, body: &str, message: &Value, timeout: Option<Duration>) -> Result<()> {
+        let mut attempt = 0;
+        let mut fell_back = false;
+        let mut reconnected = false;
+
+        loop {
+            let generation = self.generation.load(Ordering::SeqCst);
+            let result = self
+                .transport()
+                .and_then(|transport| transport.send(body, message, timeout));
+            let Err(e) = result else {
+                return Ok(());
+            };
+
+            if e.is::<SseFallback>() && !fell_back {
+                fell_back = true;
+                self.fall_back_to_sse()?;
+            } else if e.is::<SessionLost>() && !reconnected && !is_initialize(message) {
+                reconnected = true;
+                self.reconnect(generation)?;
+            } else if is_retryable(&e)
+                && attempt < self.options.max_retries
+                && !self.shutting_down.load(Ordering::SeqCst)
+            {
+                let delay = backoff(attempt);
+                eprintln!("mcp bridge: {e:#}; retrying in {}s", delay.as_secs());
+                std::thread::sleep(delay);
+                attempt += 1;
+            } else {
+                return Err(e);
+            }
+        }
+    }
+
+    fn transport(&self) -> Result<Arc<dyn Transport>> {
+        let mut slot = self.transport.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(transport) = slot.as_ref() {
+            return Ok(Arc::clone(transport));
+        }
+
+        let kind = match self.options.transport {
+            TransportKind::Auto => TransportKind::detect(&self.url),
+            kind => kind,
+        };
+        let transport = self.open(kind)?;
+        *slot = Some(Arc::clone(&transport));
+        Ok(transport)
+    }
+
+    fn open(&self, kind: TransportKind) -> Result<Arc<dyn Transport>> {
+        let transport: Arc<dyn Transport> = match kind {
+            TransportKind::Sse => Arc::new(SseTransport::connect(
+                &self.url,
+                self.headers.clone(),
+                self.options.max_retries,
+                Arc::clone(&self.sink),
+            )?),
+            TransportKind::StreamableHttp | TransportKind::Auto => {
+                Arc::new(StreamableHttpTransport::new(
+                    &self.url,
+                    self.headers.clone(),
+                    Arc::clone(&self.sink),
+                )?)
+            }
+        };
+        Ok(transport)
+    }
+
+    fn fall_back_to_sse(&self) -> Result<()> {
+        if self.options.transport != TransportKind::Auto {
+            anyhow::bail!(
+                "{} does not accept streamable HTTP requests; try --transport sse",
+                self.url
+            );
+        }
+        eprintln!(
+            "mcp bridge: {} does not accept streamable HTTP; using the SSE transport",
+            self.url
+        );
+        let transport = self.open(TransportKind::Sse)?;
+        *self.transport.lock().unwrap_or_else(|e| e.into_inner()) = Some(transport);
+        Ok(())
+    }
+
+    /// Start a new session and replay the client's handshake on it
+    fn reconnect(&self, seen_generation: u64) -> Result<()> {
+        let _guard = self.reconnecting.lock().unwrap_or_else(|e| e.into_inner());
+        if self.generation.load(Ordering::SeqCst) != seen_generation {
+            // Another message already reconnected
+            return Ok(());
+        }
+
+        eprintln!("mcp bridge: session lost; reconnecting to {}", self.url);
+        let transport = self.transport()?;
+        transport.reset()?;
+
+        let (initialize, initialized) = {
+            let handshake = self.handshake.lock().unwrap_or_else(|e| e.into_inner());
+            (handshake.initialize.clone(), handshake.initialized.clone())
+        };
+        if let Some(mut initialize) = initialize {
+            let id = self.internal_id("init");
+            initialize["id"] = json!(id);
+            self.sink.expect(&id);
+            transport.send(&initialize.to_string(), &initialize, None)?;
+        }
+        if let Some(line) = initialized {
+            let message: Value = serde_json::from_str(&line)?;
+            transport.send(&line, &message, None)?;
+        }
+
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        eprintln!("mcp bridge: reconnected");
+        Ok(())
+    }
+
+    /// Ping the server every `interval`, reconnecting when a ping goes unanswered
+    fn heartbeat(&self, interval: Duration) {
+        let mut pending: Option<String> = None;
+        let mut last_ping = Instant::now();
+
+        while !self.shutting_down.load(Ordering::SeqCst) {
+            std::thread::sleep(HEARTBEAT_TICK);
+            if last_ping.elapsed() < interval || !self.is_initialized() {
+                continue;
+            }
+            last_ping = Instant::now();
+            let generation = self.generation.load(Ordering::SeqCst);
+
+            // Replies on an SSE stream arrive asynchronously, so a missing one is
+            // only noticed at the next beat
+            if let Some(id) = pending.take() {
+                if self.sink.is_pending(&id) {
+                    self.sink.forget(&id);
+                    eprintln!(
+                        "mcp bridge: no heartbeat reply within {}s",
+                        interval.as_secs()
+                    );
+                    if let Err(e) = self.reconnect(generation) {
+                        eprintln!("mcp bridge: reconnect failed: {e:#}");
+                    }
+                    continue;
+                }
+            }
+
+            let id = self.internal_id("ping");
+            let ping = json!({ "jsonrpc": "2.0", "id": id, "method": "ping" });
+            self.sink.expect(&id);
+            match self.send(&ping.to_string(), &ping, Some(interval)) {
+                Ok(()) => pending = Some(id),
+                Err(e) => {
+                    self.sink.forget(&id);
+                    if self.shutting_down.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    eprintln!("mcp bridge: heartbeat failed: {e:#}");
+                    if let Err(e) = self.reconnect(generation) {
+                        eprintln!("mcp bridge: reconnect failed: {e:#}");
+                    }
+                }
+            }
+        }
+    }
+
+    fn internal_id(&self, kind: &str) -> String {
+        let n = self.next_id.fetch_add(1, Ordering::SeqCst);
+        format!("{INTERNAL_ID_PREFIX}{kind}-{n}")
+    }
+
+    /// Let in-flight requests finish, then end the session
+    fn shut_down(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while self.in_flight.load(Ordering::SeqCst) > 0 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        let abandoned = self.in_flight.load(Ordering::SeqCst);
+        if abandoned > 0 {
+            eprintln!("mcp bridge: abandoning {abandoned} unfinished request(s)");
+        }
+
+        let transport = self
+            .transport
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(transport) = transport {
+            transport.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+    use std::net::{TcpListener, TcpStream};
+
+    /// Output shared with the test after the bridge has finished
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn messages(&self) -> Vec<Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    /// Read one HTTP request, returning its head and body
+    fn read_request(stream: &mut TcpStream) -> Option<(String, String)> {
+        let mut head = Vec::new();
+        let mut byte = [0u8; 1];
+        while !head.ends_with(b"\r\n\r\n") {
+            if stream.read(&mut byte).ok()? == 0 {
+                return None;
+            }
+            head.push(byte[0]);
+        }
+        let head = String::from_utf8_lossy(&head).to_string();
+        let length = head
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().ok())?
+            })
+            .unwrap_or(0);
+        let mut body = vec![0u8; length];
+        stream.read_exact(&mut body).ok()?;
+        Some((head, String::from_utf8_lossy(&body).to_string()))
+    }
+
+    fn respond(stream: &mut TcpStream, status: &str, headers: &str, body: &str) {
+        let response = format!(
+            "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        let _ = stream.write_all(response.as_bytes());
+    }
+
+    /// Serve requests with `handler`, recording each request head
+    fn serve(
+        handler: impl Fn(&str, &str, &mut TcpStream) + Send + Sync + 'static,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler = Arc::new(handler);
+
+        let recorded = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let recorded = Arc::clone(&recorded);
+                let handler = Arc::clone(&handler);
+                std::thread::spawn(move || {
+                    while let Some((head, body)) = read_request(&mut stream) {
+                        recorded.lock().unwrap().push(head.to_lowercase());
+                        handler(&head, &body, &mut stream);
+                    }
+                });
+            }
+        });
+        (url, requests)
+    }
+
+    fn no_heartbeat() -> BridgeOptions {
+        BridgeOptions {
+            heartbeat: None,
+            ..Default::default()
+        }
+    }
+
+    const HANDSHAKE: &str = concat!(
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+        "\n",
+        r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+        "\n",
+        r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+        "\n"
+    );
+
+    #[test]
+    fn test_transport_kind() {
+        assert_eq!(
+            TransportKind::detect("https://example.com/sse"),
+            TransportKind::Sse
+        );
+        assert_eq!(
+            TransportKind::detect("https://example.com/mcp"),
+            TransportKind::StreamableHttp
+        );
+        assert_eq!("sse".parse::<TransportKind>(), Ok(TransportKind::Sse));
+        assert!("websocket".parse::<TransportKind>().is_err());
+        assert_eq!(TransportKind::StreamableHttp.to_string(), "streamable-http");
+    }
+
+    #[test]
+    fn test_backoff_is_capped() {
+        assert_eq!(backoff(0), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(8));
+        assert_eq!(backoff(10), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_sink_swallows_internal_replies() {
+        let output = SharedBuffer::default();
+        let sink = Sink::new(Box::new(output.clone()));
+        sink.expect("mcp-bridge-ping-1");
+        assert!(sink.is_pending("mcp-bridge-ping-1"));
+
+        sink.deliver(&json!({"jsonrpc": "2.0", "id": "mcp-bridge-ping-1", "result": {}}))
+            .unwrap();
+        sink.deliver(&json!({"jsonrpc": "2.0", "id": 3, "result": {}}))
+            .unwrap();
+
+        assert!(!sink.is_pending("mcp-bridge-ping-1"));
+        assert_eq!(output.messages().len(), 1);
+        assert_eq!(output.messages()[0]["id"], 3);
+    }
+
+    #[test]
+    fn test_relays_streamable_http_session() {
+        let (url, requests) = serve(|head, body, stream| {
+            if head.starts_with("DELETE") {
+                respond(stream, "200 OK", "", "");
+            } else if body.contains("\"initialize\"") {
+                respond(
+                    stream,
+                    "200 OK",
+                    "Content-Type: application/json\r\nMcp-Session-Id: abc123\r\n",
+                    r#"{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18"}}"#,
+                );
+            } else if body.contains("tools/list") {
+                respond(
+                    stream,
+                    "200 OK",
+                    "Content-Type: text/event-stream\r\n",
+                    "event: message\ndata: {\"jsonrpc\":\"2.0\",\"id\":2,\"result\":{\"tools\":[]}}\n\n",
+                );
+            } else {
+                respond(stream, "202 Accepted", "", "");
+            }
+        });
+
+        let remote = RemoteServerConfig::new(&format!("{url}/mcp"))
+            .unwrap()
+            .with_header("X-Team", "core");
+        let output = SharedBuffer::default();
+        Bridge::new(&remote, no_heartbeat())
+            .unwrap()
+            .run(Cursor::new(HANDSHAKE), output.clone())
+            .unwrap();
+
+        let messages = output.messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["result"]["protocolVersion"], "2025-06-18");
+        assert_eq!(messages[1]["id"], 2);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[0].contains("x-team: core"));
+        assert!(!requests[0].contains("mcp-session-id"));
+        for later in &requests[1..] {
+            assert!(later.contains("mcp-session-id: abc123"));
+        }
+        assert!(requests[2].contains("mcp-protocol-version: 2025-06-18"));
+        assert!(requests[3].starts_with("delete"));
+    }
+
+    #[test]
+    fn test_replays_handshake_when_session_is_lost() {
+        let sessions = Arc::new(AtomicU64::new(0));
+        let (url, requests) = serve(move |head, body, stream| {
+            let lower = head.to_lowercase();
+            if head.starts_with("DELETE") {
+                respond(stream, "200 OK", "", "");
+            } else if body.contains("\"initialize\"") {
+                let n = sessions.fetch_add(1, Ordering::SeqCst) + 1;
+                let id = serde_json::from_str::<Value>(body).unwrap()["id"].clone();
+                respond(
+                    stream,
+                    "200 OK",
+                    &format!("Content-Type: application/json\r\nMcp-Session-Id: s{n}\r\n"),
+                    &json!({"jsonrpc": "2.0", "id": id, "result": {}}).to_string(),
+                );
+            } else if lower.contains("mcp-session-id: s1") && body.contains("tools/list") {
+                respond(stream, "404 Not Found", "", "");
+            } else if body.contains("tools/list") {
+                respond(
+                    stream,
+                    "200 OK",
+                    "Content-Type: application/json\r\n",
+                    r#"{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}"#,
+                );
+            } else {
+                respond(stream, "202 Accepted", "", "");
+            }
+        });
+
+        let remote = RemoteServerConfig::new(&format!("{url}/mcp")).unwrap();
+        let output = SharedBuffer::default();
+        Bridge::new(&remote, no_heartbeat())
+            .unwrap()
+            .run(Cursor::new(HANDSHAKE), output.clone())
+            .unwrap();
+
+        // The replayed initialize is answered by the server but not shown to the client
+        let messages = output.messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0]["id"], 1);
+        assert_eq!(messages[1]["result"]["tools"], json!([]));
+
+        let requests = requests.lock().unwrap();
+        assert!(requests
+            .iter()
+            .any(|r| r.starts_with("post") && r.contains("mcp-session-id: s2")));
+    }
+
+    #[test]
+    fn test_falls_back_to_sse_transport() {
+        let events: Arc<Mutex<Option<TcpStream>>> = Arc::new(Mutex::new(None));
+        let stream_slot = Arc::clone(&events);
+        let (url, _requests) = serve(move |head, body, stream| {
+            if head.starts_with("GET") {
+                let mut events = stream.try_clone().unwrap();
+                let _ = events.write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\nevent: endpoint\ndata: /messages?session=1\n\n",
+                );
+                *stream_slot.lock().unwrap() = Some(events);
+            } else if head.starts_with("POST /mcp") {
+                respond(stream, "405 Method Not Allowed", "", "");
+            } else if head.starts_with("POST /messages") {
+                respond(stream, "202 Accepted", "", "");
+                let message: Value = serde_json::from_str(body).unwrap();
+                if message.get("id").is_some() {
+                    let reply = json!({"jsonrpc": "2.0", "id": message["id"], "result": {}});
+                    if let Some(events) = stream_slot.lock().unwrap().as_mut() {
+                        let _ = events
+                            .write_all(format!("event: message\ndata: {reply}\n\n").as_bytes());
+                    }
+                }
+            }
+        });
+
+        let remote = RemoteServerConfig::new(&format!("{url}/mcp")).unwrap();
+        let output = SharedBuffer::default();
+        Bridge::new(&remote, no_heartbeat())
+            .unwrap()
+            .run(Cursor::new(HANDSHAKE), output.clone())
+            .unwrap();
+
+        // Replies arrive on the event stream, possibly after stdin has closed
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while output.messages().len() < 2 && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let ids: HashSet<String> = output
+            .messages()
+            .iter()
+            .map(|m| m["id"].to_string())
+            .collect();
+        assert_eq!(ids, HashSet::from(["1".to_string(), "2".to_string()]));
+        drop(events);
+    }
+
+    #[test]
+    fn test_unreachable_server_answers_with_error() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/mcp", listener.local_addr().unwrap());
+        drop(listener);
+
+        let remote = RemoteServerConfig::new(&url).unwrap();
+        let output = SharedBuffer::default();
+        let options = BridgeOptions {
+            heartbeat: None,
+            max_retries: 0,
+            ..Default::default()
+        };
+        Bridge::new(&remote, options)
+            .unwrap()
+            .run(
+                Cursor::new(r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#),
+                output.clone(),
+            )
+            .unwrap();
+
+        let messages = output.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["error"]["code"], TRANSPORT_ERROR_CODE);
+    }
+
+    #[test]
+    fn test_invalid_header_rejected() {
+        let remote = RemoteServerConfig::new("http://localhost:1/mcp")
+            .unwrap()
+            .with_header("Bad Name", "x");
+        assert!(Bridge::new(&remote, BridgeOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,343 @@
+//! Reading secrets from the OS keychain.
+//!
+//! Secrets are stored under the service name `mcp-helper` with the secret's name
+//! as the account:
+//!
+//! - macOS: a generic password in the login keychain
+//! - Windows: a generic credential `NAME.mcp-helper` in the Credential Manager
+//! - Linux: `secret-tool store --label=NAME service mcp-helper account NAME`
+//!
+//! macOS and Windows are reached through their native APIs. On Linux the secret
+//! goes to `secret-tool` on stdin, so it never shows up in a process listing.
+//!
+//! Linux machines without a Secret Service, such as headless servers, keep
+//! secrets in an encrypted file instead (see [`super::secret_file`]); there too
+//! `MCP_SECRET_<NAME>` is read when the file has no such secret. `"backend"`
+//! under `"secrets"` in preferences picks the store: `auto` (the default),
+//! `keychain` or `file`. Where there is a keychain, the file's key is kept in it.
+
+use anyhow::{Context, Result};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::secret_file::SecretFileStore;
+use crate::config::{FileKeySource, Preferences, SecretBackend, SecretsPolicy};
+use crate::utils::process::Process;
+
+/// Service name secrets are stored under
+pub const KEYCHAIN_SERVICE: &str = "mcp-helper";
+
+/// Long enough for the user to answer a keychain unlock prompt
+#[cfg(not(any(target_os = "macos", windows)))]
+const KEYCHAIN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long the Secret Service probe may take before it counts as missing
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Where secrets are kept on this machine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretStore {
+    Keychain,
+    /// The encrypted secrets file
+    File,
+}
+
+impl SecretStore {
+    /// The store `policy` selects on this machine
+    pub fn select(policy: &SecretsPolicy) -> Self {
+        match policy.backend {
+            SecretBackend::Keychain => Self::Keychain,
+            SecretBackend::File => Self::File,
+            SecretBackend::Auto if cfg!(any(target_os = "macos", windows)) => Self::Keychain,
+            SecretBackend::Auto if secret_service_available() => Self::Keychain,
+            SecretBackend::Auto => Self::File,
+        }
+    }
+
+    /// The store selected by the user's preferences
+    pub fn current() -> Self {
+        Self::select(&secrets_policy())
+    }
+}
+
+/// Whether a Secret Service answers `secret-tool`, checked once per process.
+/// A lookup of a missing secret fails quietly; without a service or D-Bus
+/// session `secret-tool` also complains on stderr
+pub fn secret_service_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        if which::which("secret-tool").is_err() {
+            return false;
+        }
+        Process::new("secret-tool")
+            .args([
+                "lookup",
+                "service",
+                KEYCHAIN_SERVICE,
+                "account",
+                "mcp-helper-probe",
+            ])
+            .timeout(PROBE_TIMEOUT)
+            .output()
+            .is_ok_and(|output| output.stderr_lossy().trim().is_empty())
+    })
+}
+
+fn secrets_policy() -> SecretsPolicy {
+    Preferences::load()
+        .map(|preferences| preferences.secrets)
+        .unwrap_or_default()
+}
+
+/// Whether this machine has a keychain to keep secrets, or the secrets file's key, in
+pub fn keychain_available() -> bool {
+    cfg!(any(target_os = "macos", windows)) || secret_service_available()
+}
+
+fn secret_file() -> Result<SecretFileStore> {
+    let key_source = secrets_policy()
+        .file_key
+        .unwrap_or(if keychain_available() {
+            FileKeySource::Keychain
+        } else {
+            FileKeySource::Passphrase
+        });
+    SecretFileStore::new(key_source)
+}
+
+/// Look up the secret called `name`
+pub fn lookup(name: &str) -> Result<String> {
+    if name.is_empty() {
+        anyhow::bail!("Keychain reference is missing a secret name");
+    }
+
+    if SecretStore::current() == SecretStore::File {
+        let store = secret_file()?;
+        if let Some(secret) = store.lookup(name)? {
+            return Ok(secret);
+        }
+        let variable = fallback_variable(name);
+        return std::env::var(&variable).with_context(|| {
+            format!(
+                "Secret '{name}' not found in {} or the {variable} environment variable",
+                store.path().display()
+            )
+        });
+    }
+
+    keychain_lookup(name)
+}
+
+/// Look up `name` in the OS keychain itself, whatever store preferences select
+#[cfg(any(target_os = "macos", windows))]
+pub(super) fn keychain_lookup(name: &str) -> Result<String> {
+    match native_entry(name)?.get_password() {
+        Ok(secret) if !secret.is_empty() => Ok(secret),
+        Ok(_) | Err(keyring::Error::NoEntry) => anyhow::bail!(
+            "Secret '{name}' not found in the keychain. Store it with: {}",
+            store_hint(name)
+        ),
+        Err(e) => {
+            Err(e).with_context(|| format!("Failed to read secret '{name}' from the keychain"))
+        }
+    }
+}
+
+/// Look up `name` in the OS keychain itself, whatever store preferences select
+#[cfg(not(any(target_os = "macos", windows)))]
+pub(super) fn keychain_lookup(name: &str) -> Result<String> {
+    run_lookup(
+        "secret-tool",
+        &["lookup", "service", KEYCHAIN_SERVICE, "account", name],
+        name,
+    )
+}
+
+/// Store `secret` under `name`, replacing any existing value
+pub fn store(name: &str, secret: &str) -> Result<()> {
+    if name.is_empty() {
+        anyhow::bail!("Secrets need a name to be stored in the keychain");
+    }
+
+    if SecretStore::current() == SecretStore::File {
+        return secret_file()?
+            .store(name, secret)
+            .with_context(|| format!("Failed to store secret '{name}'"));
+    }
+
+    keychain_store(name, secret)
+}
+
+/// Store `secret` under `name` in the OS keychain itself, whatever store preferences select
+#[cfg(any(target_os = "macos", windows))]
+pub(super) fn keychain_store(name: &str, secret: &str) -> Result<()> {
+    native_entry(name)?
+        .set_password(secret)
+        .with_context(|| format!("Failed to store secret '{name}' in the keychain"))
+}
+
+/// Store `secret` under `name` in the OS keychain itself, whatever store preferences select
+#[cfg(not(any(target_os = "macos", windows)))]
+pub(super) fn keychain_store(name: &str, secret: &str) -> Result<()> {
+    let label = format!("--label={name}");
+    let output = Process::new("secret-tool")
+        .args([
+            "store",
+            label.as_str(),
+            "service",
+            KEYCHAIN_SERVICE,
+            "account",
+            name,
+        ])
+        .stdin(secret)
+        .timeout(KEYCHAIN_TIMEOUT)
+        .output()
+        .with_context(|| format!("Failed to run 'secret-tool' to store secret '{name}'"))?;
+    if !output.success() {
+        anyhow::bail!(
+            "'secret-tool' could not store secret '{name}': {}",
+            output.stderr_lossy().trim()
+        );
+    }
+    Ok(())
+}
+
+/// Delete the secret called `name`
+pub fn delete(name: &str) -> Result<()> {
+    if name.is_empty() {
+        anyhow::bail!("Keychain reference is missing a secret name");
+    }
+
+    if SecretStore::current() == SecretStore::File {
+        let store = secret_file()?;
+        if !store.delete(name)? {
+            anyhow::bail!("Secret '{name}' not found in {}", store.path().display());
+        }
+        return Ok(());
+    }
+
+    keychain_delete(name)
+}
+
+#[cfg(any(target_os = "macos", windows))]
+fn keychain_delete(name: &str) -> Result<()> {
+    match native_entry(name)?.delete_credential() {
+        Ok(()) => Ok(()),
+        Err(keyring::Error::NoEntry) => anyhow::bail!("Secret '{name}' not found in the keychain"),
+        Err(e) => {
+            Err(e).with_context(|| format!("Failed to delete secret '{name}' from the keychain"))
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn keychain_delete(name: &str) -> Result<()> {
+    let output = Process::new("secret-tool")
+        .args(["clear", "service", KEYCHAIN_SERVICE, "account", name])
+        .timeout(KEYCHAIN_TIMEOUT)
+        .output()
+        .with_context(|| format!("Failed to run 'secret-tool' to delete secret '{name}'"))?;
+    if !output.success() {
+        anyhow::bail!(
+            "'secret-tool' could not delete secret '{name}': {}",
+            output.stderr_lossy().trim()
+        );
+    }
+    Ok(())
+}
+
+/// The keychain entry for `name` under [`KEYCHAIN_SERVICE`]
+#[cfg(any(target_os = "macos", windows))]
+fn native_entry(name: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, name)
+        .with_context(|| format!("Invalid keychain entry for secret '{name}'"))
+}
+
+/// Command that stores `name` in the keychain, for error messages and docs.
+/// Each one prompts for the secret rather than taking it as an argument
+pub fn store_hint(name: &str) -> String {
+    if SecretStore::current() == SecretStore::File {
+        format!("export {}=<secret>", fallback_variable(name))
+    } else if cfg!(target_os = "macos") {
+        format!("security add-generic-password -U -s {KEYCHAIN_SERVICE} -a {name} -w")
+    } else if cfg!(windows) {
+        format!("cmdkey /generic:{name}.{KEYCHAIN_SERVICE} /user:{name} /pass")
+    } else {
+        format!("secret-tool store --label={name} service {KEYCHAIN_SERVICE} account {name}")
+    }
+}
+
+/// `MCP_SECRET_<NAME>` with the name upper-cased and punctuation replaced
+pub fn fallback_variable(name: &str) -> String {
+    let suffix: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("MCP_SECRET_{suffix}")
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+fn run_lookup(program: &str, args: &[&str], name: &str) -> Result<String> {
+    let output = Process::new(program)
+        .args(args)
+        .timeout(KEYCHAIN_TIMEOUT)
+        .output()
+        .with_context(|| format!("Failed to run '{program}' to read secret '{name}'"))?;
+
+    let secret = output
+        .stdout_lossy()
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    if !output.success() || secret.is_empty() {
+        anyhow::bail!(
+            "Secret '{name}' not found in the keychain. Store it with: {}",
+            store_hint(name)
+        );
+    }
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fallback_variable() {
+        assert_eq!(fallback_variable("github"), "MCP_SECRET_GITHUB");
+        assert_eq!(fallback_variable("my-api.key"), "MCP_SECRET_MY_API_KEY");
+    }
+
+    #[test]
+    fn test_select_store_from_policy() {
+        let policy = |backend| SecretsPolicy {
+            backend,
+            ..Default::default()
+        };
+        assert_eq!(
+            SecretStore::select(&policy(SecretBackend::Keychain)),
+            SecretStore::Keychain
+        );
+        assert_eq!(
+            SecretStore::select(&policy(SecretBackend::File)),
+            SecretStore::File
+        );
+        if cfg!(any(target_os = "macos", windows)) {
+            assert_eq!(
+                SecretStore::select(&policy(SecretBackend::Auto)),
+                SecretStore::Keychain
+            );
+        }
+    }
+
+    #[test]
+    fn test_store_hint_names_secret() {
+        assert!(store_hint("github").to_lowercase().contains("github"));
+        assert!(lookup("").is_err());
+    }
+}
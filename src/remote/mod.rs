@@ -0,0 +1,367 @@
+//! Remote MCP servers reached over HTTP.
+//!
+//! A remote server is a URL plus request headers (usually `Authorization`). Clients
+//! that understand the streamable HTTP transport get the URL written into their
+//! config directly. Claude Desktop only launches stdio servers, so it gets an
+//! `mcp bridge` command that relays stdio to HTTP instead. The bridge speaks both
+//! streamable HTTP and the older HTTP+SSE transport, and reconnects on its own
+//! when the network or the server drops the session (see [`bridge`]).
+//!
+//! Header values may reference secrets rather than contain them:
+//!
+//! - `${keychain:NAME}` is read from the OS keychain, or the encrypted secrets file
+//!   where there is none (see [`keychain`])
+//! - `${env:NAME}` is read from the bridge's environment
+//!
+//! References are resolved by the bridge at startup, so a server whose headers
+//! use them is always configured through the bridge, even in clients that support
+//! remote servers. The secret never lands in a client config file.
+
+pub mod bridge;
+pub mod keychain;
+pub mod secret_file;
+pub mod sse;
+pub mod streamable;
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use url::Url;
+
+use crate::client::ServerConfig;
+
+/// A server reached over the streamable HTTP transport
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteServerConfig {
+    /// Endpoint accepting JSON-RPC POSTs (e.g. `https://api.example.com/mcp`)
+    pub url: String,
+    /// Headers sent with every request; values may contain secret references
+    pub headers: HashMap<String, String>,
+}
+
+impl RemoteServerConfig {
+    /// Validate `url` and start a config without headers.
+    ///
+    /// Plain `http` is only accepted for loopback hosts, since headers usually
+    /// carry credentials.
+    pub fn new(url: &str) -> Result<Self> {
+        let parsed = Url::parse(url.trim()).with_context(|| format!("Invalid URL '{url}'"))?;
+        match parsed.scheme() {
+            "https" => {}
+            "http" if is_loopback(&parsed) => {}
+            "http" => anyhow::bail!(
+                "Refusing to send headers to '{url}' over plain HTTP; use https:// (plain HTTP is only allowed for localhost)"
+            ),
+            scheme => anyhow::bail!("Unsupported URL scheme '{scheme}' in '{url}'"),
+        }
+
+        Ok(Self {
+            url: parsed.to_string(),
+            headers: HashMap::new(),
+        })
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(name.into(), value.into());
+        self
+    }
+
+    /// Add headers given as `Name=Value` or `Name: Value`
+    pub fn with_header_specs<S: AsRef<str>>(mut self, specs: &[S]) -> Result<Self> {
+        for spec in specs {
+            let (name, value) = parse_header(spec.as_ref())?;
+            self.headers.insert(name, value);
+        }
+        Ok(self)
+    }
+
+    /// Whether any header value must be resolved at runtime
+    pub fn uses_secret_references(&self) -> bool {
+        self.headers
+            .values()
+            .any(|value| has_secret_reference(value))
+    }
+
+    /// The `type` clients record for this server: `sse` or `http`
+    pub fn transport_type(&self) -> &'static str {
+        match bridge::TransportKind::detect(&self.url) {
+            bridge::TransportKind::Sse => "sse",
+            _ => "http",
+        }
+    }
+
+    /// Names of headers that look like they hold a literal credential
+    pub fn literal_secret_headers(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .headers
+            .iter()
+            .filter(|(name, value)| {
+                !has_secret_reference(value)
+                    && (is_credential_header(name)
+                        || crate::utils::secrets::looks_like_secret_value(
+                            value.trim_start_matches("Bearer ").trim(),
+                        ))
+            })
+            .map(|(name, _)| name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// A stdio server config that runs `mcp bridge` against this server.
+    ///
+    /// Headers are passed unresolved, so secret references stay references.
+    pub fn bridge_config(&self) -> ServerConfig {
+        let mut args = vec!["bridge".to_string(), "--url".to_string(), self.url.clone()];
+        let mut headers: Vec<_> = self.headers.iter().collect();
+        headers.sort();
+        for (name, value) in headers {
+            args.push("--header".to_string());
+            args.push(format!("{name}={value}"));
+        }
+
+        ServerConfig {
+            command: helper_command(),
+            args,
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    /// Recover the remote server behind an `mcp bridge` command
+    pub fn from_bridge_config(config: &ServerConfig) -> Option<Self> {
+        let mut args = config.args.iter();
+        if args.next().map(String::as_str) != Some("bridge") {
+            return None;
+        }
+
+        let mut url = None;
+        let mut headers = HashMap::new();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--url" => url = args.next().cloned(),
+                "--header" => {
+                    let (name, value) = parse_header(args.next()?).ok()?;
+                    headers.insert(name, value);
+                }
+                _ => {}
+            }
+        }
+        Some(Self { url: url?, headers })
+    }
+}
+
+/// Split a `Name=Value` or `Name: Value` header argument
+pub fn parse_header(spec: &str) -> Result<(String, String)> {
+    let split = match (spec.find('='), spec.find(':')) {
+        (Some(eq), Some(colon)) => Some(eq.min(colon)),
+        (Some(i), None) | (None, Some(i)) => Some(i),
+        (None, None) => None,
+    };
+    let Some(index) = split else {
+        anyhow::bail!("Invalid header '{spec}'; expected Name=Value");
+    };
+
+    let name = spec[..index].trim();
+    let value = spec[index + 1..].trim();
+    let valid_name = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+    if !valid_name {
+        anyhow::bail!("Invalid header name '{name}' in '{spec}'");
+    }
+    if value.chars().any(|c| c == '\r' || c == '\n') {
+        anyhow::bail!("Header '{name}' must not contain line breaks");
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Whether `value` contains a `${keychain:...}` or `${env:...}` reference
+pub fn has_secret_reference(value: &str) -> bool {
+    value.contains("${keychain:") || value.contains("${env:")
+}
+
+/// Names of the `${keychain:...}` secrets `value` refers to
+pub fn keychain_references(value: &str) -> Vec<String> {
+    let names = std::cell::RefCell::new(Vec::new());
+    let _ = resolve_with(value, |kind, name| {
+        if kind == "keychain" {
+            names.borrow_mut().push(name.to_string());
+        }
+        Ok(String::new())
+    });
+    names.into_inner()
+}
+
+/// Replace every secret reference in `value` with the secret it names
+pub fn resolve_secret_references(value: &str) -> Result<String> {
+    resolve_with(value, |kind, name| match kind {
+        "keychain" => keychain::lookup(name),
+        "env" => {
+            std::env::var(name).with_context(|| format!("Environment variable '{name}' is not set"))
+        }
+        _ => unreachable!(),
+    })
+}
+
+fn resolve_with(value: &str, lookup: impl Fn(&str, &str) -> Result<String>) -> Result<String> {
+    let mut resolved = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        resolved.push_str(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let parsed = reference.find('}').and_then(|end| {
+            let (kind, name) = reference[..end].split_once(':')?;
+            matches!(kind, "keychain" | "env").then_some((kind, name, end))
+        });
+        match parsed {
+            Some((kind, name, end)) => {
+                resolved.push_str(&lookup(kind, name.trim())?);
+                rest = &reference[end + 1..];
+            }
+            None => {
+                resolved.push_str("${");
+                rest = reference;
+            }
+        }
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+fn is_credential_header(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    lower == "authorization"
+        || lower == "proxy-authorization"
+        || crate::utils::secrets::is_secret_name(name)
+}
+
+fn is_loopback(url: &Url) -> bool {
+    match url.host() {
+        Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+/// The command clients should run to reach this binary
+pub(crate) fn helper_command() -> String {
+    std::env::current_exe()
+        .ok()
+        .filter(|path| {
+            path.file_stem()
+                .is_some_and(|stem| stem.eq_ignore_ascii_case("mcp"))
+        })
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| "mcp".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_requires_https_for_remote_hosts() {
+        assert!(RemoteServerConfig::new("https://api.example.com/mcp").is_ok());
+        assert!(RemoteServerConfig::new("http://localhost:3000/mcp").is_ok());
+        assert!(RemoteServerConfig::new("http://127.0.0.1:3000/mcp").is_ok());
+        assert!(RemoteServerConfig::new("http://api.example.com/mcp").is_err());
+        assert!(RemoteServerConfig::new("ftp://example.com").is_err());
+        assert!(RemoteServerConfig::new("not a url").is_err());
+    }
+
+    #[test]
+    fn test_parse_header() {
+        assert_eq!(
+            parse_header("Authorization=Bearer ${keychain:github}").unwrap(),
+            (
+                "Authorization".to_string(),
+                "Bearer ${keychain:github}".to_string()
+            )
+        );
+        assert_eq!(
+            parse_header("X-Api-Key: abc=def").unwrap(),
+            ("X-Api-Key".to_string(), "abc=def".to_string())
+        );
+        assert!(parse_header("NoSeparator").is_err());
+        assert!(parse_header("Bad Name=value").is_err());
+        assert!(parse_header("X-Test=a\r\nInjected: b").is_err());
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let lookup = |kind: &str, name: &str| Ok(format!("<{kind}/{name}>"));
+        assert_eq!(
+            resolve_with("Bearer ${keychain:github}", lookup).unwrap(),
+            "Bearer <keychain/github>"
+        );
+        assert_eq!(
+            resolve_with("${env:A}-${env:B}", lookup).unwrap(),
+            "<env/A>-<env/B>"
+        );
+        assert_eq!(resolve_with("${other}", lookup).unwrap(), "${other}");
+        assert!(resolve_with("${keychain:missing}", |_, _| anyhow::bail!("nope")).is_err());
+    }
+
+    #[test]
+    fn test_keychain_references() {
+        assert_eq!(
+            keychain_references("${keychain:a} ${env:B} ${keychain: c }"),
+            vec!["a", "c"]
+        );
+        assert!(keychain_references("plain").is_empty());
+    }
+
+    #[test]
+    fn test_bridge_config_round_trip() {
+        let remote = RemoteServerConfig::new("https://api.example.com/mcp")
+            .unwrap()
+            .with_header("Authorization", "Bearer ${keychain:example}")
+            .with_header("X-Team", "core");
+        let config = remote.bridge_config();
+
+        assert_eq!(
+            &config.args[..3],
+            ["bridge", "--url", "https://api.example.com/mcp"]
+        );
+        assert!(config
+            .args
+            .contains(&"Authorization=Bearer ${keychain:example}".to_string()));
+        assert_eq!(
+            RemoteServerConfig::from_bridge_config(&config),
+            Some(remote)
+        );
+
+        let stdio = ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["server".to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        };
+        assert!(RemoteServerConfig::from_bridge_config(&stdio).is_none());
+    }
+
+    #[test]
+    fn test_literal_secret_headers() {
+        let remote = RemoteServerConfig::new("https://api.example.com/mcp")
+            .unwrap()
+            .with_header("Authorization", "Bearer abc")
+            .with_header("X-Custom", "Bearer ghp_abcdefghijklmnopqrstuvwxyz")
+            .with_header("X-Api-Key", "${env:API_KEY}")
+            .with_header("X-Team", "core");
+        assert_eq!(
+            remote.literal_secret_headers(),
+            vec!["Authorization", "X-Custom"]
+        );
+        assert!(remote.uses_secret_references());
+    }
+
+    #[test]
+    fn test_transport_type() {
+        let sse = RemoteServerConfig::new("https://api.example.com/sse/").unwrap();
+        let http = RemoteServerConfig::new("https://api.example.com/mcp").unwrap();
+        assert_eq!(sse.transport_type(), "sse");
+        assert_eq!(http.transport_type(), "http");
+    }
+}
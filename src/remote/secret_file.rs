@@ -0,0 +1,360 @@
+//! Encrypted file store for secrets on machines without a keychain.
+//!
+//! Headless Linux servers usually run no Secret Service (no desktop session, often
+//! no D-Bus), so `secret-tool` has nowhere to store anything. There secrets are
+//! kept in `secrets.json` in mcp-helper's data directory instead, sealed with
+//! [`crate::utils::state_crypto`] (XChaCha20-Poly1305) under either:
+//!
+//! - a random key kept in the OS keychain, the default where there is one (for
+//!   example with `"backend": "file"` on a desktop). Nothing to type.
+//! - a key stretched with PBKDF2 from a passphrase in `MCP_SECRETS_PASSPHRASE`, or
+//!   asked for in a terminal. The default without a keychain.
+//!
+//! The key source is chosen when the file is created (`"file_key"` under
+//! `"secrets"` in preferences) and recorded in the file:
+//!
+//! ```json
+//! { "format_version": 1, "key_source": "passphrase", "salt": "...", "key_id": "...", "nonce": "...", "ciphertext": "...", "mac": "..." }
+//! ```
+
+use anyhow::{Context, Result};
+use dialoguer::Password;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::keychain;
+use crate::config::FileKeySource;
+use crate::utils::paths;
+use crate::utils::secure_file;
+use crate::utils::state_crypto::{self, OpenError, Sealed, StateKey};
+
+/// Environment variable holding the passphrase of a passphrase-keyed file
+pub const PASSPHRASE_VARIABLE: &str = "MCP_SECRETS_PASSPHRASE";
+
+/// Keychain entry holding the hex-encoded key of a keychain-keyed file
+pub const KEY_NAME: &str = "secrets-file-key";
+
+const FORMAT_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+/// PBKDF2 rounds; a key is derived at most once per process and file
+const KDF_ROUNDS: u32 = 100_000;
+
+/// Keys already derived in this process, by salt
+static DERIVED_KEYS: Mutex<Vec<(String, StateKey)>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct SecretFile {
+    format_version: u32,
+    key_source: FileKeySource,
+    /// PBKDF2 salt of a passphrase-keyed file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    salt: Option<String>,
+    #[serde(flatten)]
+    sealed: Sealed,
+}
+
+/// The decrypted secrets and what they are sealed with
+struct Contents {
+    key_source: FileKeySource,
+    salt: Option<String>,
+    key: StateKey,
+    secrets: BTreeMap<String, String>,
+}
+
+/// Secrets sealed in one file, by name
+#[derive(Clone)]
+pub struct SecretFileStore {
+    path: PathBuf,
+    /// Key source for a file that doesn't exist yet
+    key_source: FileKeySource,
+    passphrase: Option<String>,
+}
+
+impl SecretFileStore {
+    pub fn new(key_source: FileKeySource) -> Result<Self> {
+        Ok(Self::with_path(Self::default_path()?, key_source))
+    }
+
+    pub fn with_path(path: PathBuf, key_source: FileKeySource) -> Self {
+        Self {
+            path,
+            key_source,
+            passphrase: None,
+        }
+    }
+
+    /// Use `passphrase` instead of reading `MCP_SECRETS_PASSPHRASE` or asking
+    pub fn with_passphrase(mut self, passphrase: impl Into<String>) -> Self {
+        self.passphrase = Some(passphrase.into());
+        self
+    }
+
+    fn default_path() -> Result<PathBuf> {
+        Ok(paths::data_dir()?.join("secrets.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The secret called `name`, if the file has one
+    pub fn lookup(&self, name: &str) -> Result<Option<String>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        Ok(self.open()?.secrets.remove(name))
+    }
+
+    /// Store `secret` under `name`, replacing any existing value
+    pub fn store(&self, name: &str, secret: &str) -> Result<()> {
+        let mut contents = self.open()?;
+        contents
+            .secrets
+            .insert(name.to_string(), secret.to_string());
+        self.save(&contents)
+    }
+
+    /// Delete the secret called `name`; `false` when there was none
+    pub fn delete(&self, name: &str) -> Result<bool> {
+        if !self.path.exists() {
+            return Ok(false);
+        }
+        let mut contents = self.open()?;
+        if contents.secrets.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.save(&contents)?;
+        Ok(true)
+    }
+
+    /// Decrypt the file, or start an empty one with a fresh key
+    fn open(&self) -> Result<Contents> {
+        if !self.path.exists() {
+            let (salt, key) = match self.key_source {
+                FileKeySource::Keychain => (None, self.create_keychain_key()?),
+                FileKeySource::Passphrase => {
+                    let mut salt = [0u8; SALT_LEN];
+                    state_crypto::fill_random(&mut salt)?;
+                    let salt = hex::encode(salt);
+                    let key = self.passphrase_key(&salt, true)?;
+                    (Some(salt), key)
+                }
+            };
+            return Ok(Contents {
+                key_source: self.key_source,
+                salt,
+                key,
+                secrets: BTreeMap::new(),
+            });
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read secrets from {}", self.path.display()))?;
+        let file: SecretFile = serde_json::from_str(&content)
+            .with_context(|| format!("{} is not a secrets file", self.path.display()))?;
+        if file.format_version > FORMAT_VERSION {
+            anyhow::bail!(
+                "{} was written by a newer mcp-helper (format {})",
+                self.path.display(),
+                file.format_version
+            );
+        }
+
+        let key = match (file.key_source, &file.salt) {
+            (FileKeySource::Keychain, _) => self.keychain_key()?,
+            (FileKeySource::Passphrase, Some(salt)) => self.passphrase_key(salt, false)?,
+            (FileKeySource::Passphrase, None) => {
+                anyhow::bail!("{} has no passphrase salt", self.path.display())
+            }
+        };
+        let plaintext = state_crypto::open(&key, &file.sealed).map_err(|e| match e {
+            OpenError::WrongKey(_) => match file.key_source {
+                FileKeySource::Keychain => anyhow::anyhow!(
+                    "{} was sealed with a key that isn't in this keychain",
+                    self.path.display()
+                ),
+                FileKeySource::Passphrase => {
+                    anyhow::anyhow!("Wrong passphrase for {}", self.path.display())
+                }
+            },
+            OpenError::Malformed | OpenError::Tampered => anyhow::anyhow!(
+                "{} is damaged or was modified outside mcp-helper",
+                self.path.display()
+            ),
+        })?;
+        let secrets = serde_json::from_slice(&plaintext)
+            .with_context(|| format!("Failed to parse secrets in {}", self.path.display()))?;
+
+        if let Some(salt) = &file.salt {
+            self.remember_key(salt, &key);
+        }
+        Ok(Contents {
+            key_source: file.key_source,
+            salt: file.salt,
+            key,
+            secrets,
+        })
+    }
+
+    fn save(&self, contents: &Contents) -> Result<()> {
+        let plaintext =
+            serde_json::to_vec(&contents.secrets).context("Failed to serialize secrets")?;
+        let file = SecretFile {
+            format_version: FORMAT_VERSION,
+            key_source: contents.key_source,
+            salt: contents.salt.clone(),
+            sealed: state_crypto::seal(&contents.key, &plaintext)?,
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&file).context("Failed to serialize secrets")?;
+        secure_file::write_json_secure(&self.path, &json)?;
+        if let Some(salt) = &contents.salt {
+            self.remember_key(salt, &contents.key);
+        }
+        Ok(())
+    }
+
+    /// The key of a keychain-keyed file
+    fn keychain_key(&self) -> Result<StateKey> {
+        let encoded = keychain::keychain_lookup(KEY_NAME).with_context(|| {
+            format!("The key for {} is not in the keychain", self.path.display())
+        })?;
+        StateKey::from_hex(&encoded)
+    }
+
+    /// The keychain's file key, or a new random one stored there
+    fn create_keychain_key(&self) -> Result<StateKey> {
+        if let Ok(key) = self.keychain_key() {
+            return Ok(key);
+        }
+        if !keychain::keychain_available() {
+            anyhow::bail!(
+                "No keychain to keep the key for {} in; set \"file_key\": \"passphrase\" under \"secrets\" in preferences",
+                self.path.display()
+            );
+        }
+        let key = StateKey::generate()?;
+        keychain::keychain_store(KEY_NAME, &key.to_hex())
+            .context("Failed to store the secrets file key in the keychain")?;
+        Ok(key)
+    }
+
+    fn passphrase_key(&self, salt: &str, creating: bool) -> Result<StateKey> {
+        if let Some(key) = self.remembered_key(salt) {
+            return Ok(key);
+        }
+        let salt_bytes = hex::decode(salt).context("Secrets file has an invalid salt")?;
+        let passphrase = self.passphrase(creating)?;
+        Ok(StateKey::derive_from(
+            passphrase.as_bytes(),
+            &salt_bytes,
+            KDF_ROUNDS,
+        ))
+    }
+
+    /// A key derived earlier in this process. Keys from an explicit passphrase
+    /// aren't shared, so a wrong one is never masked by a cached right one
+    fn remembered_key(&self, salt: &str) -> Option<StateKey> {
+        if self.passphrase.is_some() {
+            return None;
+        }
+        let keys = DERIVED_KEYS.lock().ok()?;
+        keys.iter()
+            .find(|(known, _)| known == salt)
+            .map(|(_, key)| key.clone())
+    }
+
+    fn remember_key(&self, salt: &str, key: &StateKey) {
+        if self.passphrase.is_some() {
+            return;
+        }
+        if let Ok(mut keys) = DERIVED_KEYS.lock() {
+            if !keys.iter().any(|(known, _)| known == salt) {
+                keys.push((salt.to_string(), key.clone()));
+            }
+        }
+    }
+
+    fn passphrase(&self, creating: bool) -> Result<String> {
+        if let Some(passphrase) = &self.passphrase {
+            return Ok(passphrase.clone());
+        }
+        if let Ok(passphrase) = std::env::var(PASSPHRASE_VARIABLE) {
+            if !passphrase.is_empty() {
+                return Ok(passphrase);
+            }
+        }
+        if !std::io::stdin().is_terminal() {
+            anyhow::bail!(
+                "{} is protected by a passphrase; set {PASSPHRASE_VARIABLE} to open it",
+                self.path.display()
+            );
+        }
+
+        let mut prompt = Password::new().with_prompt("Passphrase for mcp-helper secrets");
+        if creating {
+            prompt = prompt.with_confirmation("Repeat passphrase", "Passphrases don't match");
+        }
+        prompt
+            .interact()
+            .map_err(|e| anyhow::anyhow!("Failed to read passphrase: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn store(dir: &TempDir, passphrase: &str) -> SecretFileStore {
+        SecretFileStore::with_path(dir.path().join("secrets.json"), FileKeySource::Passphrase)
+            .with_passphrase(passphrase)
+    }
+
+    #[test]
+    fn test_store_lookup_and_delete() {
+        let dir = TempDir::new().unwrap();
+        let secrets = store(&dir, "correct horse");
+        assert_eq!(secrets.lookup("github").unwrap(), None);
+
+        secrets.store("github", "ghp_secret").unwrap();
+        secrets.store("slack", "xoxb-1").unwrap();
+        assert_eq!(
+            secrets.lookup("github").unwrap().as_deref(),
+            Some("ghp_secret")
+        );
+
+        let content = fs::read_to_string(secrets.path()).unwrap();
+        assert!(!content.contains("ghp_secret"));
+        assert!(content.contains("\"key_source\": \"passphrase\""));
+        assert!(content.contains("\"salt\""));
+
+        assert!(secrets.delete("github").unwrap());
+        assert!(!secrets.delete("github").unwrap());
+        assert_eq!(secrets.lookup("github").unwrap(), None);
+        assert_eq!(secrets.lookup("slack").unwrap().as_deref(), Some("xoxb-1"));
+    }
+
+    #[test]
+    fn test_rejects_wrong_passphrase_and_tampering() {
+        let dir = TempDir::new().unwrap();
+        store(&dir, "first").store("token", "value").unwrap();
+
+        let err = store(&dir, "second").lookup("token").unwrap_err();
+        assert!(err.to_string().contains("Wrong passphrase"));
+
+        let path = dir.path().join("secrets.json");
+        let mut file: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        file["ciphertext"] = serde_json::Value::String("00".repeat(8));
+        fs::write(&path, file.to_string()).unwrap();
+        let err = store(&dir, "first").lookup("token").unwrap_err();
+        assert!(err.to_string().contains("damaged"));
+    }
+}
@@ -0,0 +1,380 @@
+//! Server-sent events and the legacy HTTP+SSE transport.
+//!
+//! Older MCP servers speak the 2024-11-05 transport: the client opens a `GET`
+//! event stream, the server's first `endpoint` event names a URL to `POST`
+//! messages to, and every reply arrives on the event stream rather than in the
+//! `POST` response.
+//!
+//! The session lives as long as the event stream. When the stream drops, the
+//! transport reconnects with backoff, and the next message reports
+//! [`SessionLost`] so the bridge can initialize the new session before resending.
+
+use anyhow::{Context, Result};
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{HeaderMap, ACCEPT, CONTENT_TYPE};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+use url::Url;
+
+use super::bridge::{backoff, is_initialize, Disconnected, SessionLost, Sink, Transport};
+
+/// How long a message waits for the event stream to (re)connect
+const ENDPOINT_WAIT: Duration = Duration::from_secs(60);
+
+/// One server-sent event
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SseEvent {
+    pub event: Option<String>,
+    pub id: Option<String>,
+    pub data: String,
+}
+
+/// Read server-sent events from `reader`, calling `on_event` for each one
+pub fn read_sse_events<R: BufRead>(
+    reader: R,
+    mut on_event: impl FnMut(SseEvent) -> Result<()>,
+) -> Result<()> {
+    let mut event = SseEvent::default();
+    let mut has_data = false;
+
+    for line in reader.lines() {
+        let line = line.context("Failed to read event stream")?;
+        if line.is_empty() {
+            if has_data {
+                on_event(std::mem::take(&mut event))?;
+            }
+            event = SseEvent::default();
+            has_data = false;
+            continue;
+        }
+        if line.starts_with(':') {
+            continue;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line.as_str(), ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "data" => {
+                if has_data {
+                    event.data.push('\n');
+                }
+                event.data.push_str(value);
+                has_data = true;
+            }
+            "event" => event.event = Some(value.to_string()),
+            "id" => event.id = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    if has_data {
+        on_event(event)?;
+    }
+    Ok(())
+}
+
+/// Whether a response body is an event stream
+pub(crate) fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"))
+}
+
+#[derive(Debug, Default)]
+struct SseState {
+    /// Incremented for every new event stream; older streams stop reading
+    connection: u64,
+    /// The connection the current session was initialized on
+    session_connection: u64,
+    /// Where to POST messages for the current connection
+    endpoint: Option<String>,
+    last_event_id: Option<String>,
+    /// Consecutive failed connection attempts
+    failures: u32,
+    /// Set once reconnecting has been given up
+    failed: Option<String>,
+}
+
+/// The legacy HTTP+SSE transport
+pub(crate) struct SseTransport {
+    client: Client,
+    url: String,
+    headers: HeaderMap,
+    max_retries: u32,
+    sink: Arc<Sink>,
+    state: Mutex<SseState>,
+    ready: Condvar,
+    closed: AtomicBool,
+}
+
+impl SseTransport {
+    /// Open the event stream and wait for the server to name its endpoint
+    pub(crate) fn connect(
+        url: &str,
+        headers: HeaderMap,
+        max_retries: u32,
+        sink: Arc<Sink>,
+    ) -> Result<Arc<Self>> {
+        // The event stream stays open for the whole session
+        let client = Client::builder()
+            .timeout(None::<Duration>)
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        let transport = Arc::new(Self {
+            client,
+            url: url.to_string(),
+            headers,
+            max_retries,
+            sink,
+            state: Mutex::new(SseState::default()),
+            ready: Condvar::new(),
+            closed: AtomicBool::new(false),
+        });
+        transport.start_connection();
+        transport.wait_for_endpoint()?;
+        Ok(transport)
+    }
+
+    /// Open a new event stream on a background thread
+    fn start_connection(self: &Arc<Self>) {
+        let connection = {
+            let mut state = self.lock_state();
+            state.connection += 1;
+            state.endpoint = None;
+            state.connection
+        };
+        let transport = Arc::clone(self);
+        std::thread::spawn(move || transport.read_stream(connection));
+    }
+
+    fn read_stream(self: Arc<Self>, connection: u64) {
+        let result = self
+            .open_stream()
+            .and_then(|response| {
+                read_sse_events(BufReader::new(response), |event| {
+                    self.handle_event(connection, event)
+                })
+            })
+            .err();
+
+        if self.closed.load(Ordering::SeqCst) || !self.is_current(connection) {
+            return;
+        }
+
+        let failures = {
+            let mut state = self.lock_state();
+            state.endpoint = None;
+            state.failures += 1;
+            state.failures
+        };
+        if failures > self.max_retries {
+            let reason = result.map_or_else(
+                || "the event stream keeps closing".to_string(),
+                |e| format!("{e:#}"),
+            );
+            eprintln!("mcp bridge: giving up on {}: {reason}", self.url);
+            self.lock_state().failed = Some(reason);
+            self.ready.notify_all();
+            return;
+        }
+
+        let delay = backoff(failures - 1);
+        match result {
+            Some(e) => eprintln!(
+                "mcp bridge: event stream failed ({e:#}); reconnecting in {}s",
+                delay.as_secs()
+            ),
+            None => eprintln!(
+                "mcp bridge: event stream closed; reconnecting in {}s",
+                delay.as_secs()
+            ),
+        }
+        std::thread::sleep(delay);
+        if !self.closed.load(Ordering::SeqCst) && self.is_current(connection) {
+            self.start_connection();
+        }
+    }
+
+    fn open_stream(&self) -> Result<Response> {
+        let mut request = self
+            .client
+            .get(&self.url)
+            .headers(self.headers.clone())
+            .header(ACCEPT, "text/event-stream");
+        if let Some(id) = self.lock_state().last_event_id.clone() {
+            request = request.header("last-event-id", id);
+        }
+
+        let response = request
+            .send()
+            .with_context(|| format!("Failed to reach {}", self.url))?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Server returned HTTP {status} for the event stream");
+        }
+        if !is_event_stream(&response) {
+            anyhow::bail!("{} did not return an event stream", self.url);
+        }
+        Ok(response)
+    }
+
+    fn handle_event(&self, connection: u64, event: SseEvent) -> Result<()> {
+        if !self.is_current(connection) {
+            anyhow::bail!("connection replaced");
+        }
+        if let Some(id) = &event.id {
+            self.lock_state().last_event_id = Some(id.clone());
+        }
+
+        match event.event.as_deref() {
+            Some("endpoint") => {
+                let endpoint = Url::parse(&self.url)
+                    .and_then(|base| base.join(event.data.trim()))
+                    .with_context(|| format!("Invalid endpoint '{}'", event.data))?;
+                let mut state = self.lock_state();
+                state.endpoint = Some(endpoint.to_string());
+                state.failures = 0;
+                state.failed = None;
+                drop(state);
+                self.ready.notify_all();
+                Ok(())
+            }
+            None | Some("message") => {
+                if event.data.trim().is_empty() {
+                    return Ok(());
+                }
+                let reply: Value = serde_json::from_str(&event.data)
+                    .context("Server sent an event that is not JSON")?;
+                self.sink.deliver(&reply)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    fn is_current(&self, connection: u64) -> bool {
+        self.lock_state().connection == connection
+    }
+
+    /// The endpoint and connection messages should go to, once the stream is up
+    fn wait_for_endpoint(&self) -> Result<(String, u64)> {
+        let state = self.lock_state();
+        let (state, timeout) = self
+            .ready
+            .wait_timeout_while(state, ENDPOINT_WAIT, |state| {
+                state.endpoint.is_none() && state.failed.is_none()
+            })
+            .map_err(|_| anyhow::anyhow!("event stream state poisoned"))?;
+
+        if let Some(reason) = &state.failed {
+            anyhow::bail!("Lost connection to {}: {reason}", self.url);
+        }
+        match &state.endpoint {
+            Some(endpoint) if !timeout.timed_out() => Ok((endpoint.clone(), state.connection)),
+            _ => Err(Disconnected.into()),
+        }
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, SseState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Transport for Arc<SseTransport> {
+    fn send(&self, body: &str, message: &Value, timeout: Option<Duration>) -> Result<()> {
+        let (endpoint, connection) = self.wait_for_endpoint()?;
+        let initialize = is_initialize(message);
+        if !initialize && self.lock_state().session_connection != connection {
+            return Err(SessionLost.into());
+        }
+
+        let mut request = self
+            .client
+            .post(&endpoint)
+            .headers(self.headers.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+        let response = request
+            .send()
+            .with_context(|| format!("Failed to reach {endpoint}"))?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Err(SessionLost.into());
+        }
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            let detail = body.trim().chars().take(200).collect::<String>();
+            anyhow::bail!("Server returned HTTP {status}: {detail}");
+        }
+
+        if initialize {
+            self.lock_state().session_connection = connection;
+        }
+        Ok(())
+    }
+
+    fn reset(&self) -> Result<()> {
+        // Keep a stream that reconnected on its own; replace one the lost session used
+        let stale = {
+            let state = self.lock_state();
+            state.connection == state.session_connection || state.failed.is_some()
+        };
+        if stale {
+            self.lock_state().failures = 0;
+            self.start_connection();
+        }
+        self.wait_for_endpoint().map(|_| ())
+    }
+
+    fn close(&self) {
+        // A reader blocked on the stream ends with the process
+        self.closed.store(true, Ordering::SeqCst);
+        self.ready.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_sse_events() {
+        let stream = ": keep-alive\n\nevent: message\nid: 7\ndata: {\"a\":1}\n\ndata: line one\ndata: line two\n\ndata:{\"b\":2}";
+        let mut events = Vec::new();
+        read_sse_events(Cursor::new(stream), |event| {
+            events.push(event);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: Some("message".to_string()),
+                    id: Some("7".to_string()),
+                    data: "{\"a\":1}".to_string(),
+                },
+                SseEvent {
+                    data: "line one\nline two".to_string(),
+                    ..Default::default()
+                },
+                SseEvent {
+                    data: "{\"b\":2}".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+}
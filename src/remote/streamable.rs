@@ -0,0 +1,189 @@
+//! The streamable HTTP transport.
+//!
+//! Every message is a `POST`. The reply comes back either as a JSON body or as
+//! an SSE stream in the `POST` response. The `Mcp-Session-Id` returned by
+//! `initialize` is sent with every later request, along with the negotiated
+//! `MCP-Protocol-Version`. A `404` for a known session means the server dropped
+//! it, which is reported as [`SessionLost`].
+
+use anyhow::{Context, Result};
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{HeaderMap, ACCEPT, CONTENT_TYPE};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::io::BufReader;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::bridge::{is_initialize, SessionLost, Sink, SseFallback, Transport};
+use super::sse::{is_event_stream, read_sse_events};
+
+const SESSION_HEADER: &str = "mcp-session-id";
+const PROTOCOL_VERSION_HEADER: &str = "mcp-protocol-version";
+
+/// How long ending a session may take before it is abandoned
+const END_SESSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub(crate) struct StreamableHttpTransport {
+    client: Client,
+    url: String,
+    headers: HeaderMap,
+    sink: Arc<Sink>,
+    session_id: Mutex<Option<String>>,
+    protocol_version: Mutex<Option<String>>,
+}
+
+impl StreamableHttpTransport {
+    pub(crate) fn new(url: &str, headers: HeaderMap, sink: Arc<Sink>) -> Result<Self> {
+        // Streams stay open as long as a tool call runs, so there is no overall timeout
+        let client = Client::builder()
+            .timeout(None::<Duration>)
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            url: url.to_string(),
+            headers,
+            sink,
+            session_id: Mutex::new(None),
+            protocol_version: Mutex::new(None),
+        })
+    }
+
+    fn session_id(&self) -> Option<String> {
+        self.session_id.lock().ok().and_then(|id| id.clone())
+    }
+
+    fn relay(&self, response: Response, message: &Value) -> Result<()> {
+        let initialize = is_initialize(message);
+        if is_event_stream(&response) {
+            read_sse_events(BufReader::new(response), |event| {
+                if event.data.trim().is_empty() {
+                    return Ok(());
+                }
+                let reply: Value = serde_json::from_str(&event.data)
+                    .context("Server sent an event that is not JSON")?;
+                if initialize {
+                    self.record_protocol_version(&reply);
+                }
+                self.sink.deliver(&reply)
+            })
+        } else {
+            let body = response.text().context("Failed to read response")?;
+            if body.trim().is_empty() {
+                return Ok(());
+            }
+            let reply: Value =
+                serde_json::from_str(&body).context("Server sent a response that is not JSON")?;
+            if initialize {
+                self.record_protocol_version(&reply);
+            }
+            self.sink.deliver(&reply)
+        }
+    }
+
+    fn record_protocol_version(&self, reply: &Value) {
+        if let Some(version) = reply
+            .pointer("/result/protocolVersion")
+            .and_then(Value::as_str)
+        {
+            if let Ok(mut current) = self.protocol_version.lock() {
+                *current = Some(version.to_string());
+            }
+        }
+    }
+
+    /// Tell the server the session is over; servers may refuse with 405
+    fn end_session(&self) {
+        let Some(id) = self.session_id() else {
+            return;
+        };
+        let result = self
+            .client
+            .delete(&self.url)
+            .headers(self.headers.clone())
+            .header(SESSION_HEADER, id)
+            .timeout(END_SESSION_TIMEOUT)
+            .send();
+        if let Err(e) = result {
+            tracing::debug!("Failed to end MCP session: {}", e);
+        }
+    }
+}
+
+impl Transport for StreamableHttpTransport {
+    fn send(&self, body: &str, message: &Value, timeout: Option<Duration>) -> Result<()> {
+        let session_id = self.session_id();
+        let mut request = self
+            .client
+            .post(&self.url)
+            .headers(self.headers.clone())
+            .header(CONTENT_TYPE, "application/json")
+            .header(ACCEPT, "application/json, text/event-stream")
+            .body(body.to_string());
+        if let Some(id) = &session_id {
+            request = request.header(SESSION_HEADER, id);
+        }
+        if let Some(version) = self.protocol_version.lock().ok().and_then(|v| v.clone()) {
+            request = request.header(PROTOCOL_VERSION_HEADER, version);
+        }
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+
+        let response = request
+            .send()
+            .with_context(|| format!("Failed to reach {}", self.url))?;
+
+        if let Some(id) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            if let Ok(mut session) = self.session_id.lock() {
+                *session = Some(id.to_string());
+            }
+        }
+
+        let status = response.status();
+        if status == StatusCode::ACCEPTED || status == StatusCode::NO_CONTENT {
+            return Ok(());
+        }
+        if status == StatusCode::NOT_FOUND && session_id.is_some() {
+            return Err(SessionLost.into());
+        }
+        // Servers that predate streamable HTTP do not accept a POST here
+        if is_initialize(message)
+            && session_id.is_none()
+            && matches!(
+                status,
+                StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+            )
+        {
+            return Err(SseFallback.into());
+        }
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            let detail = body.trim().chars().take(200).collect::<String>();
+            anyhow::bail!("Server returned HTTP {status}: {detail}");
+        }
+
+        self.relay(response, message)
+    }
+
+    fn reset(&self) -> Result<()> {
+        self.end_session();
+        if let Ok(mut session) = self.session_id.lock() {
+            *session = None;
+        }
+        if let Ok(mut version) = self.protocol_version.lock() {
+            *version = None;
+        }
+        Ok(())
+    }
+
+    fn close(&self) {
+        self.end_session();
+    }
+}
@@ -0,0 +1,376 @@
+//! `mcp run <server> --explain`: how a server name resolves, without running it.
+//!
+//! Clients start servers from their own config, while `mcp run` treats the name as
+//! a local script or an npm package. When the two disagree a server works in one
+//! place and not the other, so the explanation lists every step: the mcp-helper
+//! record for the name, the client configs that define it, the server type `run`
+//! infers, the dependency check, and the final command with any wrappers or
+//! bridges it goes through.
+
+use std::fmt;
+
+use crate::client::{detect_clients, ServerConfig};
+use crate::config::{ManagedOrigin, ManagedServer, ManagedServers, ServerKind};
+use crate::deps::{DependencyCheck, DependencyChecker, DependencyStatus, NodeChecker};
+use crate::remote::RemoteServerConfig;
+use crate::server::{detect_server_type, ServerType};
+use crate::utils::paths::program_name;
+
+use super::ServerRunner;
+
+/// Every step of resolving a server name for `mcp run`
+#[derive(Debug, Clone)]
+pub struct RunExplanation {
+    pub server: String,
+    /// What mcp-helper recorded when the server was added or imported
+    pub managed: Option<ManagedServer>,
+    /// Client configs with an entry of this name, by client
+    pub configured: Vec<(String, ServerConfig)>,
+    /// Whether the name is a script on disk rather than a package
+    pub local_script: bool,
+    /// What the name would be installed as
+    pub server_type: ServerType,
+    /// Node.js, which `mcp run` needs for both npx and local scripts
+    pub dependency: Result<DependencyCheck, String>,
+    /// The command `mcp run` would execute
+    pub command: Result<(String, Vec<String>), String>,
+}
+
+impl RunExplanation {
+    /// Resolve `server` the way `runner` would, without executing anything
+    pub fn gather(runner: &ServerRunner, server: &str, args: &[String]) -> Self {
+        let managed = ManagedServers::new()
+            .and_then(|managed| managed.get(server))
+            .ok()
+            .flatten();
+
+        let mut configured: Vec<(String, ServerConfig)> = detect_clients()
+            .iter()
+            .filter(|client| client.is_installed())
+            .filter_map(|client| {
+                let config = client.list_servers().ok()?.remove(server)?;
+                Some((client.name().to_string(), config))
+            })
+            .collect();
+        configured.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let local_script = runner
+            .resolve_server_path(server)
+            .is_ok_and(|path| path.exists() && path.is_absolute());
+
+        Self {
+            server: server.to_string(),
+            managed,
+            configured,
+            local_script,
+            server_type: detect_server_type(server),
+            dependency: NodeChecker::new().check().map_err(|e| format!("{e:#}")),
+            command: runner
+                .command_for(server, args)
+                .map_err(|e| format!("{e:#}")),
+        }
+    }
+
+    /// Whether the dependency check passed and a command could be built
+    pub fn is_runnable(&self) -> bool {
+        self.command.is_ok()
+            && matches!(
+                self.dependency,
+                Ok(DependencyCheck {
+                    status: DependencyStatus::Installed { .. },
+                    ..
+                })
+            )
+    }
+}
+
+impl fmt::Display for RunExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Resolution of '{}' for mcp run:", self.server)?;
+
+        write!(f, "  1. mcp-helper record: ")?;
+        match &self.managed {
+            Some(managed) => {
+                writeln!(
+                    f,
+                    "{} ({}, in {})",
+                    managed.kind,
+                    match managed.origin {
+                        ManagedOrigin::Added => "added",
+                        ManagedOrigin::Imported => "imported",
+                    },
+                    if managed.clients.is_empty() {
+                        "no clients".to_string()
+                    } else {
+                        managed.clients.join(", ")
+                    }
+                )?;
+                if let ServerKind::Npm { package, .. } = &managed.kind {
+                    if package != &self.server {
+                        writeln!(
+                            f,
+                            "     Clients run {package}; mcp run only knows the name, try: mcp run {package}"
+                        )?;
+                    }
+                }
+            }
+            None => writeln!(f, "none (not added through mcp-helper)")?,
+        }
+
+        write!(f, "  2. Client configs: ")?;
+        if self.configured.is_empty() {
+            writeln!(f, "no installed client defines '{}'", self.server)?;
+        } else {
+            writeln!(f)?;
+            for (client, config) in &self.configured {
+                writeln!(
+                    f,
+                    "     {client}: {}",
+                    command_line(&config.command, &config.args)
+                )?;
+                for wrapper in wrappers(&config.command, &config.args) {
+                    writeln!(f, "       via {wrapper}")?;
+                }
+            }
+            writeln!(
+                f,
+                "     mcp run does not read client configs; it starts the name as shown below"
+            )?;
+        }
+
+        write!(f, "  3. Server type: ")?;
+        if self.local_script {
+            writeln!(f, "local script, run with node")?;
+        } else {
+            match &self.server_type {
+                ServerType::Npm { package, version } => match version {
+                    Some(version) => writeln!(f, "npm package {package}@{version}")?,
+                    None => writeln!(f, "npm package {package}")?,
+                },
+                other => writeln!(
+                    f,
+                    "{} spec, but mcp run only starts npm packages and local scripts; install it with 'mcp install {}' instead",
+                    type_name(other),
+                    self.server
+                )?,
+            }
+        }
+
+        write!(f, "  4. Dependencies: ")?;
+        match &self.dependency {
+            Ok(check) => writeln!(f, "{} {}", check.dependency.name(), check.status)?,
+            Err(e) => writeln!(f, "check failed: {e}")?,
+        }
+
+        write!(f, "  5. Command: ")?;
+        match &self.command {
+            Ok((command, args)) => {
+                writeln!(f, "{}", command_line(command, args))?;
+                let wrappers = wrappers(command, args);
+                if wrappers.is_empty() {
+                    writeln!(f, "  6. Wrappers: none")?;
+                } else {
+                    writeln!(f, "  6. Wrappers:")?;
+                    for wrapper in wrappers {
+                        writeln!(f, "     {wrapper}")?;
+                    }
+                }
+            }
+            Err(e) => {
+                writeln!(f, "none, resolution failed:")?;
+                for line in e.lines() {
+                    writeln!(f, "     {line}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `command` and `args` as one line, quoting arguments with spaces
+pub(super) fn command_line(command: &str, args: &[String]) -> String {
+    std::iter::once(command)
+        .chain(args.iter().map(String::as_str))
+        .map(|part| {
+            if part.contains(' ') {
+                format!("\"{part}\"")
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn type_name(server_type: &ServerType) -> &'static str {
+    match server_type {
+        ServerType::Npm { .. } => "npm",
+        ServerType::Binary { .. } => "binary",
+        ServerType::Python { .. } => "Python",
+        ServerType::Docker { .. } => "Docker",
+        ServerType::Cargo { .. } => "Cargo",
+        ServerType::Deno { .. } => "Deno",
+        ServerType::Jar { .. } => "JAR",
+        ServerType::Dotnet { .. } => ".NET tool",
+    }
+}
+
+/// Launchers, shells and bridges between `command` and the server itself
+pub fn wrappers(command: &str, args: &[String]) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut program = program_name(command);
+    let mut rest: &[String] = args;
+
+    if program == "cmd"
+        && rest
+            .first()
+            .is_some_and(|arg| arg.eq_ignore_ascii_case("/c"))
+    {
+        found.push("cmd /c (Windows shell, needed to start .cmd scripts)".to_string());
+        let Some((inner, inner_args)) = rest[1..].split_first() else {
+            return found;
+        };
+        program = program_name(inner);
+        rest = inner_args;
+    }
+
+    match program.as_str() {
+        "npx" => found.push("npx (downloads the npm package and starts it)".to_string()),
+        "uvx" => found.push("uvx (runs the Python package in a temporary environment)".to_string()),
+        "docker" | "podman" if rest.first().is_some_and(|arg| arg == "run") => {
+            found.push(format!("{program} run (starts the server in a container)"))
+        }
+        "mcp" => {
+            let config = ServerConfig {
+                command: command.to_string(),
+                args: rest.to_vec(),
+                env: Default::default(),
+                cwd: None,
+            };
+            if let Some(remote) = RemoteServerConfig::from_bridge_config(&config) {
+                found.push(format!(
+                    "mcp bridge (stdio bridge to the remote server at {})",
+                    remote.url
+                ));
+            } else if rest.first().is_some_and(|arg| arg == "exec") {
+                let inner: Vec<String> = rest
+                    .iter()
+                    .skip(1)
+                    .skip_while(|arg| *arg == "--")
+                    .cloned()
+                    .collect();
+                found.push("mcp exec (resolves keychain references first)".to_string());
+                if let Some((inner_command, inner_args)) = inner.split_first() {
+                    found.extend(wrappers(inner_command, inner_args));
+                }
+            }
+        }
+        _ => {}
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deps::Dependency;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_wrappers() {
+        assert_eq!(
+            wrappers("cmd.exe", &args(&["/c", "npx.cmd", "server"])),
+            vec![
+                "cmd /c (Windows shell, needed to start .cmd scripts)",
+                "npx (downloads the npm package and starts it)"
+            ]
+        );
+        assert_eq!(
+            wrappers(
+                "/usr/local/bin/mcp",
+                &args(&["bridge", "--url", "https://api.example.com/mcp"])
+            ),
+            vec!["mcp bridge (stdio bridge to the remote server at https://api.example.com/mcp)"]
+        );
+        assert_eq!(
+            wrappers("mcp", &args(&["exec", "--", "npx", "-y", "server"])),
+            vec![
+                "mcp exec (resolves keychain references first)",
+                "npx (downloads the npm package and starts it)"
+            ]
+        );
+        assert!(wrappers("node", &args(&["server.js"])).is_empty());
+    }
+
+    #[test]
+    fn test_explanation_shows_each_step() {
+        let explanation = RunExplanation {
+            server: "filesystem".to_string(),
+            managed: Some(ManagedServer {
+                kind: ServerKind::Npm {
+                    package: "@modelcontextprotocol/server-filesystem".to_string(),
+                    version: None,
+                },
+                origin: ManagedOrigin::Added,
+                clients: vec!["Claude Desktop".to_string()],
+                imported_config: None,
+                channel: Default::default(),
+                publisher: None,
+                instance_of: None,
+                schema: None,
+                recorded_at: chrono::Utc::now(),
+            }),
+            configured: vec![(
+                "Claude Desktop".to_string(),
+                ServerConfig {
+                    command: "npx".to_string(),
+                    args: args(&["-y", "@modelcontextprotocol/server-filesystem", "/tmp"]),
+                    env: Default::default(),
+                    cwd: None,
+                },
+            )],
+            local_script: false,
+            server_type: detect_server_type("filesystem"),
+            dependency: Ok(DependencyCheck {
+                dependency: Dependency::NodeJs { min_version: None },
+                status: DependencyStatus::Installed {
+                    version: Some("20.11.0".to_string()),
+                },
+                install_instructions: None,
+            }),
+            command: Ok(("npx".to_string(), args(&["filesystem"]))),
+        };
+
+        let text = explanation.to_string();
+        assert!(text.contains("try: mcp run @modelcontextprotocol/server-filesystem"));
+        assert!(
+            text.contains("Claude Desktop: npx -y @modelcontextprotocol/server-filesystem /tmp")
+        );
+        assert!(text.contains("3. Server type: npm package filesystem"));
+        assert!(text.contains("4. Dependencies: Node.js Installed (20.11.0)"));
+        assert!(text.contains("5. Command: npx filesystem"));
+        assert!(explanation.is_runnable());
+    }
+
+    #[test]
+    fn test_explanation_of_unsupported_type() {
+        let explanation = RunExplanation {
+            server: "docker:mcp/postgres".to_string(),
+            managed: None,
+            configured: Vec::new(),
+            local_script: false,
+            server_type: detect_server_type("docker:mcp/postgres"),
+            dependency: Err("node not found".to_string()),
+            command: Err("Could not find npx in PATH.".to_string()),
+        };
+
+        let text = explanation.to_string();
+        assert!(text.contains("none (not added through mcp-helper)"));
+        assert!(text.contains("Docker spec, but mcp run only starts npm packages"));
+        assert!(text.contains("Could not find npx in PATH."));
+        assert!(!explanation.is_runnable());
+    }
+}
@@ -0,0 +1,349 @@
+//! Persistent run history and crash reports for servers launched with `mcp run`.
+//!
+//! Every supervised run is appended to a rolling history. When a server exits
+//! unsuccessfully, the tail of its stderr is written to a crash report file next
+//! to the history so `mcp status` can point at it.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::utils::paths;
+
+/// Number of stderr lines kept for crash reports
+pub const STDERR_TAIL_LINES: usize = 50;
+
+/// A single supervised server run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub server: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// Exit code, or `None` if the process was terminated by a signal
+    pub exit_code: Option<i32>,
+    pub crash_report: Option<PathBuf>,
+}
+
+impl RunRecord {
+    pub fn crashed(&self) -> bool {
+        self.exit_code != Some(0)
+    }
+}
+
+/// Run history aggregated per server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerRunSummary {
+    pub server: String,
+    pub total_runs: usize,
+    pub last_run: RunRecord,
+    /// Crashes within the summary window
+    pub recent_crashes: usize,
+    pub last_crash_report: Option<PathBuf>,
+}
+
+/// Rolling buffer of the last lines written to stderr
+#[derive(Debug, Clone)]
+pub struct StderrTail {
+    lines: VecDeque<String>,
+    capacity: usize,
+}
+
+impl StderrTail {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, line: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.iter().cloned().collect()
+    }
+}
+
+impl Default for StderrTail {
+    fn default() -> Self {
+        Self::new(STDERR_TAIL_LINES)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryFile {
+    runs: Vec<RunRecord>,
+}
+
+/// Persistent store of supervised runs and their crash reports
+#[derive(Debug, Clone)]
+pub struct RunHistory {
+    dir: PathBuf,
+    max_entries: usize,
+}
+
+impl RunHistory {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_dir(Self::default_dir()?))
+    }
+
+    /// Use a custom directory for the history and crash reports
+    pub fn with_dir(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_entries: 200,
+        }
+    }
+
+    /// Keep at most this many runs; older runs and their reports are deleted
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    fn default_dir() -> Result<PathBuf> {
+        Ok(paths::data_dir()?.join("run-history"))
+    }
+
+    fn history_file(&self) -> PathBuf {
+        self.dir.join("runs.json")
+    }
+
+    fn crash_dir(&self) -> PathBuf {
+        self.dir.join("crashes")
+    }
+
+    /// All recorded runs, oldest first
+    pub fn records(&self) -> Result<Vec<RunRecord>> {
+        let path = self.history_file();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(&path).context("Failed to read run history")?;
+        let history: HistoryFile =
+            serde_json::from_str(&contents).context("Failed to parse run history")?;
+        Ok(history.runs)
+    }
+
+    /// Append a run, trimming the oldest entries beyond the retention limit.
+    pub fn record_run(&self, record: RunRecord) -> Result<()> {
+        let mut runs = self.records().unwrap_or_default();
+        runs.push(record);
+
+        if runs.len() > self.max_entries {
+            let excess = runs.len() - self.max_entries;
+            for old in runs.drain(..excess) {
+                if let Some(report) = old.crash_report {
+                    let _ = fs::remove_file(report);
+                }
+            }
+        }
+
+        fs::create_dir_all(&self.dir).context("Failed to create run history directory")?;
+        let json = serde_json::to_string_pretty(&HistoryFile { runs })
+            .context("Failed to serialize run history")?;
+        fs::write(self.history_file(), json).context("Failed to write run history")
+    }
+
+    /// Write a crash report with the exit status and stderr tail of a failed run.
+    pub fn write_crash_report(
+        &self,
+        server: &str,
+        started_at: DateTime<Utc>,
+        ended_at: DateTime<Utc>,
+        exit_code: Option<i32>,
+        stderr_tail: &[String],
+    ) -> Result<PathBuf> {
+        let crash_dir = self.crash_dir();
+        fs::create_dir_all(&crash_dir).context("Failed to create crash report directory")?;
+
+        let file_name = format!(
+            "{}-{}.log",
+            sanitize_file_name(server),
+            ended_at.format("%Y%m%dT%H%M%S%.3fZ")
+        );
+        let path = crash_dir.join(file_name);
+
+        let mut report = String::new();
+        report.push_str(&format!("Server:     {server}\n"));
+        report.push_str(&format!("Started:    {}\n", started_at.to_rfc3339()));
+        report.push_str(&format!("Ended:      {}\n", ended_at.to_rfc3339()));
+        report.push_str(&format!(
+            "Duration:   {}s\n",
+            (ended_at - started_at).num_seconds()
+        ));
+        report.push_str(&format!(
+            "Exit code:  {}\n",
+            exit_code.map_or_else(|| "terminated by signal".to_string(), |c| c.to_string())
+        ));
+        report.push_str(&format!(
+            "\n--- last {} lines of stderr ---\n",
+            stderr_tail.len()
+        ));
+        for line in stderr_tail {
+            report.push_str(line);
+            report.push('\n');
+        }
+
+        fs::write(&path, report)
+            .with_context(|| format!("Failed to write crash report {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Summarize runs per server, counting crashes within `window` of `now`.
+    pub fn summaries(&self, now: DateTime<Utc>, window: Duration) -> Result<Vec<ServerRunSummary>> {
+        Ok(summarize_runs(&self.records()?, now, window))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Group runs by server, counting crashes that ended within `window` of `now`.
+pub fn summarize_runs(
+    runs: &[RunRecord],
+    now: DateTime<Utc>,
+    window: Duration,
+) -> Vec<ServerRunSummary> {
+    let mut by_server: BTreeMap<&str, Vec<&RunRecord>> = BTreeMap::new();
+    for run in runs {
+        by_server.entry(&run.server).or_default().push(run);
+    }
+
+    by_server
+        .into_iter()
+        .filter_map(|(server, runs)| {
+            let last_run = runs.iter().max_by_key(|r| r.ended_at)?;
+            let recent_crashes = runs
+                .iter()
+                .filter(|r| r.crashed() && now - r.ended_at <= window)
+                .count();
+            let last_crash_report = runs
+                .iter()
+                .filter(|r| r.crashed())
+                .max_by_key(|r| r.ended_at)
+                .and_then(|r| r.crash_report.clone());
+
+            Some(ServerRunSummary {
+                server: server.to_string(),
+                total_runs: runs.len(),
+                last_run: (*last_run).clone(),
+                recent_crashes,
+                last_crash_report,
+            })
+        })
+        .collect()
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run(
+        server: &str,
+        minutes_ago: i64,
+        exit_code: Option<i32>,
+        now: DateTime<Utc>,
+    ) -> RunRecord {
+        RunRecord {
+            server: server.to_string(),
+            started_at: now - Duration::minutes(minutes_ago + 1),
+            ended_at: now - Duration::minutes(minutes_ago),
+            exit_code,
+            crash_report: None,
+        }
+    }
+
+    #[test]
+    fn test_stderr_tail_keeps_last_lines() {
+        let mut tail = StderrTail::new(2);
+        tail.push("one".to_string());
+        tail.push("two".to_string());
+        tail.push("three".to_string());
+        assert_eq!(tail.lines(), vec!["two", "three"]);
+    }
+
+    #[test]
+    fn test_summarize_runs_counts_recent_crashes() {
+        let now = Utc::now();
+        let runs = vec![
+            run("fs", 90, Some(1), now),
+            run("fs", 40, Some(1), now),
+            run("fs", 20, None, now),
+            run("fs", 5, Some(0), now),
+            run("github", 10, Some(0), now),
+        ];
+
+        let summaries = summarize_runs(&runs, now, Duration::hours(1));
+        assert_eq!(summaries.len(), 2);
+
+        let fs = &summaries[0];
+        assert_eq!(fs.server, "fs");
+        assert_eq!(fs.total_runs, 4);
+        assert_eq!(fs.recent_crashes, 2);
+        assert_eq!(fs.last_run.exit_code, Some(0));
+
+        assert_eq!(summaries[1].recent_crashes, 0);
+    }
+
+    #[test]
+    fn test_record_run_and_crash_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let history = RunHistory::with_dir(temp_dir.path().to_path_buf()).with_max_entries(2);
+        let now = Utc::now();
+
+        let report = history
+            .write_crash_report(
+                "@org/server",
+                now - Duration::seconds(3),
+                now,
+                Some(1),
+                &["Error: boom".to_string()],
+            )
+            .unwrap();
+        let contents = fs::read_to_string(&report).unwrap();
+        assert!(contents.contains("Exit code:  1"));
+        assert!(contents.contains("Error: boom"));
+        assert!(!report.file_name().unwrap().to_string_lossy().contains('/'));
+
+        let mut crashed = run("@org/server", 0, Some(1), now);
+        crashed.crash_report = Some(report.clone());
+        history.record_run(crashed).unwrap();
+        history
+            .record_run(run("@org/server", 0, Some(0), now))
+            .unwrap();
+        assert_eq!(history.records().unwrap().len(), 2);
+
+        // Trimming the oldest run removes its crash report too
+        history
+            .record_run(run("@org/server", 0, Some(0), now))
+            .unwrap();
+        assert_eq!(history.records().unwrap().len(), 2);
+        assert!(!report.exists());
+    }
+}
@@ -1,7 +1,20 @@
+pub mod explain;
+pub mod history;
+pub mod safe_mode;
+
 use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
 use colored::Colorize;
+use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Stdio};
+
+pub use explain::RunExplanation;
+pub use history::{RunHistory, RunRecord, ServerRunSummary, StderrTail};
+pub use safe_mode::SafeLaunch;
+
+use crate::client::ServerConfig;
+use crate::utils::npm_cache;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Platform {
@@ -13,55 +26,105 @@ pub enum Platform {
 pub struct ServerRunner {
     platform: Platform,
     verbose: bool,
+    history: Option<RunHistory>,
 }
 
 impl ServerRunner {
     pub fn new(platform: Platform, verbose: bool) -> Self {
-        Self { platform, verbose }
+        Self {
+            platform,
+            verbose,
+            history: None,
+        }
     }
 
-    pub fn run(&self, server: &str, args: &[String]) -> Result<()> {
-        // First, try to find the server
-        let server_path = self.resolve_server_path(server)?;
+    /// Record each run, and a crash report for failed runs, in `history`
+    pub fn with_history(mut self, history: RunHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
 
-        if self.verbose {
-            eprintln!("Resolved server path: {}", server_path.display());
-        }
+    #[tracing::instrument(name = "run", skip(self), fields(platform = ?self.platform))]
+    pub fn run(&self, server: &str, args: &[String]) -> Result<()> {
+        let (command, command_args) = self.command_for(server, args)?;
+        self.launch(
+            server,
+            &ServerConfig {
+                command,
+                args: command_args,
+                env: Default::default(),
+                cwd: None,
+            },
+        )
+    }
 
-        // Normalize arguments that might be paths
-        let normalized_args: Vec<String> = args
-            .iter()
-            .map(|arg| {
-                // Simple heuristic: if it looks like a path, normalize it
-                if arg.contains('/') || arg.contains('\\') {
-                    normalize_path(arg, self.platform)
-                } else {
-                    arg.clone()
-                }
-            })
-            .collect();
+    /// Run `server` with only its required configuration, printing what was
+    /// left out of the normal launch
+    #[tracing::instrument(name = "run_safe", skip(self), fields(platform = ?self.platform))]
+    pub fn run_safe(&self, server: &str, args: &[String]) -> Result<()> {
+        let launch = SafeLaunch::gather(self, server, args)?;
+        print!("{launch}");
+        self.launch(server, &launch.safe)
+    }
 
-        // Determine the command to use based on platform
-        let (command, command_args) =
-            self.get_command_for_platform(&server_path, &normalized_args)?;
+    /// Start `config`, passing stderr through and recording the run
+    fn launch(&self, server: &str, config: &ServerConfig) -> Result<()> {
+        let command = &config.command;
+        let command_args = &config.args;
 
         if self.verbose {
             eprintln!("Executing command: {command} {command_args:?}");
         }
 
         // Execute the command
-        let mut cmd = Command::new(&command);
-        cmd.args(&command_args);
+        let mut cmd = Command::new(command);
+        cmd.args(command_args);
 
-        // Inherit environment variables
+        // Inherit environment variables, then the server's own
         cmd.envs(std::env::vars());
+        cmd.envs(&config.env);
+        if let Some(cwd) = &config.cwd {
+            cmd.current_dir(cwd);
+        }
+
+        // Pass stderr through while keeping its tail for crash reports
+        cmd.stderr(Stdio::piped());
 
-        let status = cmd
-            .status()
+        let started_at = Utc::now();
+        let mut child = cmd
+            .spawn()
             .with_context(|| format!("Failed to execute command: {command}"))?;
 
+        let stderr = child.stderr.take();
+        let stderr_reader = std::thread::spawn(move || {
+            let mut tail = StderrTail::default();
+            if let Some(stderr) = stderr {
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    eprintln!("{line}");
+                    tail.push(line);
+                }
+            }
+            tail
+        });
+
+        let status = child
+            .wait()
+            .with_context(|| format!("Failed to execute command: {command}"))?;
+        let tail = stderr_reader.join().unwrap_or_default();
+        self.record_run(server, started_at, status.code(), &tail);
+
         if !status.success() {
             let exit_code = status.code().unwrap_or(-1);
+            if npm_cache::explains_failure(&tail.lines()) {
+                bail!(
+                    "Server '{}' exited with status: {}\n\
+                    npm reported an integrity error while fetching the package.\n\
+                    {}",
+                    server,
+                    exit_code,
+                    npm_cache::CORRUPTION_HINT
+                );
+            }
             match exit_code {
                 127 => bail!(
                     "Command not found: {}\n\
@@ -93,6 +156,76 @@ impl ServerRunner {
         Ok(())
     }
 
+    /// The command `run` executes for `server` and `args`
+    pub fn command_for(&self, server: &str, args: &[String]) -> Result<(String, Vec<String>)> {
+        // First, try to find the server
+        let server_path = self.resolve_server_path(server)?;
+
+        if self.verbose {
+            eprintln!("Resolved server path: {}", server_path.display());
+        }
+
+        // Normalize arguments that might be paths
+        let normalized_args: Vec<String> = args
+            .iter()
+            .map(|arg| {
+                // Simple heuristic: if it looks like a path, normalize it
+                if arg.contains('/') || arg.contains('\\') {
+                    normalize_path(arg, self.platform)
+                } else {
+                    arg.clone()
+                }
+            })
+            .collect();
+
+        // Determine the command to use based on platform
+        self.get_command_for_platform(&server_path, &normalized_args)
+    }
+
+    fn record_run(
+        &self,
+        server: &str,
+        started_at: DateTime<Utc>,
+        exit_code: Option<i32>,
+        tail: &StderrTail,
+    ) {
+        let Some(history) = &self.history else {
+            return;
+        };
+        let ended_at = Utc::now();
+
+        let crash_report = if exit_code == Some(0) {
+            None
+        } else {
+            match history.write_crash_report(server, started_at, ended_at, exit_code, &tail.lines())
+            {
+                Ok(path) => {
+                    eprintln!("{} Crash report saved to {}", "ℹ".blue(), path.display());
+                    Some(path)
+                }
+                Err(e) => {
+                    if self.verbose {
+                        eprintln!("Failed to write crash report: {e}");
+                    }
+                    None
+                }
+            }
+        };
+
+        let record = RunRecord {
+            server: server.to_string(),
+            started_at,
+            ended_at,
+            exit_code,
+            crash_report,
+        };
+        if let Err(e) = history.record_run(record) {
+            if self.verbose {
+                eprintln!("Failed to update run history: {e}");
+            }
+        }
+    }
+
     pub fn resolve_server_path(&self, server: &str) -> Result<PathBuf> {
         // Normalize the path for the current platform
         let normalized_server = normalize_path(server, self.platform);
@@ -303,4 +436,22 @@ mod tests {
             assert_eq!(args, vec!["my-server", "arg1"]);
         }
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_integrity_errors_point_at_the_npm_cache() {
+        let runner = ServerRunner::new(Platform::Linux, false);
+        let config = ServerConfig {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                "echo 'npm error code EINTEGRITY' >&2; exit 1".to_string(),
+            ],
+            env: std::collections::HashMap::new(),
+            cwd: None,
+        };
+
+        let error = runner.launch("some-server", &config).unwrap_err();
+        assert!(error.to_string().contains(npm_cache::CORRUPTION_HINT));
+    }
 }
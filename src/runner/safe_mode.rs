@@ -0,0 +1,380 @@
+//! `mcp run <server> --safe`: start a server with only what it needs.
+//!
+//! When a server misbehaves it's rarely clear whether the server is broken or
+//! the extra flags and variables in its config are. Safe mode takes the config a
+//! client would launch and keeps only the launcher (npx, uvx, docker and their
+//! options, up to the package or image), positional arguments, credentials and
+//! the variables the server's metadata marks as required. Every other option and
+//! variable is dropped so the server falls back to its defaults, and the
+//! differences from the normal launch are printed before it starts.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::client::{detect_clients, ServerConfig};
+use crate::config::ServerKind;
+use crate::help_server::HelpServerCommand;
+use crate::server::ConfigField;
+use crate::update::find_npm_package_spec;
+use crate::utils::paths::program_name;
+use crate::utils::secrets::is_secret_name;
+
+use super::explain::command_line;
+use super::ServerRunner;
+
+/// The normal and safe launch of a server, and what differs between them
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafeLaunch {
+    pub server: String,
+    /// Client whose config the normal launch comes from; `None` when no client
+    /// defines the server and the `mcp run` command is used instead
+    pub client: Option<String>,
+    pub normal: ServerConfig,
+    pub safe: ServerConfig,
+    /// Options dropped from the arguments, with their values
+    pub removed_args: Vec<String>,
+    /// Variables dropped from the environment
+    pub removed_env: Vec<String>,
+    /// Credentials kept because the server can't authenticate without them
+    pub kept_credentials: Vec<String>,
+    /// Required variables kept, or set to their default when missing
+    pub kept_required: Vec<String>,
+}
+
+impl SafeLaunch {
+    /// Strip `normal` down to its launcher, positional arguments, credentials and
+    /// the `required` variables
+    pub fn new(
+        server: &str,
+        client: Option<String>,
+        normal: ServerConfig,
+        required: &[ConfigField],
+    ) -> Self {
+        let launcher = launcher_len(&normal).min(normal.args.len());
+        let (kept_args, removed_args) = strip_options(&normal.args[launcher..]);
+        let mut args = normal.args[..launcher].to_vec();
+        args.extend(kept_args);
+
+        let mut env = HashMap::new();
+        let mut removed_env = Vec::new();
+        let mut kept_credentials = Vec::new();
+        let mut kept_required = Vec::new();
+        for (name, value) in &normal.env {
+            if required.iter().any(|field| &field.name == name) {
+                kept_required.push(name.clone());
+            } else if is_secret_name(name) {
+                kept_credentials.push(name.clone());
+            } else {
+                removed_env.push(name.clone());
+                continue;
+            }
+            env.insert(name.clone(), value.clone());
+        }
+        for field in required {
+            if let (false, Some(default)) = (env.contains_key(&field.name), &field.default) {
+                env.insert(field.name.clone(), default.clone());
+                kept_required.push(field.name.clone());
+            }
+        }
+        removed_env.sort();
+        kept_credentials.sort();
+        kept_required.sort();
+
+        let safe = ServerConfig {
+            command: normal.command.clone(),
+            args,
+            env,
+            cwd: normal.cwd.clone(),
+        };
+        Self {
+            server: server.to_string(),
+            client,
+            normal,
+            safe,
+            removed_args,
+            removed_env,
+            kept_credentials,
+            kept_required,
+        }
+    }
+
+    /// The safe launch of `server` from the first client config defining it, or
+    /// from the command `mcp run` would use
+    pub fn gather(runner: &ServerRunner, server: &str, args: &[String]) -> anyhow::Result<Self> {
+        let mut configured: Vec<(String, ServerConfig)> = detect_clients()
+            .iter()
+            .filter(|client| client.is_installed())
+            .filter_map(|client| {
+                let config = client.list_servers().ok()?.remove(server)?;
+                Some((client.name().to_string(), config))
+            })
+            .collect();
+        configured.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let (client, normal) = match configured.into_iter().next() {
+            Some((client, mut config)) => {
+                config.args.extend(args.iter().cloned());
+                (Some(client), config)
+            }
+            None => {
+                let (command, args) = runner.command_for(server, args)?;
+                let config = ServerConfig {
+                    command,
+                    args,
+                    env: HashMap::new(),
+                    cwd: None,
+                };
+                (None, config)
+            }
+        };
+
+        let required = match ServerKind::infer(&normal) {
+            ServerKind::Npm { package, .. } => HelpServerCommand::new(false)
+                .collect(&package)
+                .metadata
+                .map(|metadata| metadata.required_config)
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        Ok(Self::new(server, client, normal, &required))
+    }
+
+    /// Whether safe mode launches the server exactly as normal
+    pub fn is_unchanged(&self) -> bool {
+        self.normal == self.safe
+    }
+}
+
+impl fmt::Display for SafeLaunch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.client {
+            Some(client) => writeln!(f, "Safe mode for '{}' ({client}'s config):", self.server)?,
+            None => writeln!(f, "Safe mode for '{}':", self.server)?,
+        }
+        writeln!(
+            f,
+            "  Normal: {}",
+            command_line(&self.normal.command, &self.normal.args)
+        )?;
+        writeln!(
+            f,
+            "  Safe:   {}",
+            command_line(&self.safe.command, &self.safe.args)
+        )?;
+        for arg in &self.removed_args {
+            writeln!(f, "  - argument {arg}")?;
+        }
+        for name in &self.removed_env {
+            writeln!(f, "  - env {name}")?;
+        }
+        for name in &self.kept_credentials {
+            writeln!(f, "  = env {name} (credential)")?;
+        }
+        for name in &self.kept_required {
+            writeln!(f, "  = env {name} (required)")?;
+        }
+
+        if self.is_unchanged() {
+            writeln!(
+                f,
+                "Nothing to strip: if the server still fails, the problem is in the server or its environment"
+            )
+        } else {
+            writeln!(
+                f,
+                "If the server works in safe mode, one of the removed settings is at fault; add them back one at a time"
+            )
+        }
+    }
+}
+
+/// How many leading arguments start the server rather than configure it: npx and
+/// its options up to the package, `docker run` up to the image, uvx up to the
+/// Python package, or `node`/`python` up to the script
+fn launcher_len(config: &ServerConfig) -> usize {
+    if let Some(spec) = find_npm_package_spec(config) {
+        return spec.arg_index + 1;
+    }
+    let server = match ServerKind::infer(config) {
+        ServerKind::Docker { image } => Some(image),
+        ServerKind::Python { package } => Some(package),
+        ServerKind::Binary { .. } => match program_name(&config.command).as_str() {
+            "node" | "bun" | "deno" | "java" => config
+                .args
+                .iter()
+                .find(|arg| !arg.starts_with('-'))
+                .cloned(),
+            _ => None,
+        },
+        ServerKind::Npm { .. } => None,
+    };
+    server
+        .and_then(|server| config.args.iter().position(|arg| *arg == server))
+        .map_or(0, |index| index + 1)
+}
+
+/// Split server arguments into positional ones, which are kept, and options. An
+/// option without `=` takes the next argument as its value unless that is
+/// another option
+fn strip_options(args: &[String]) -> (Vec<String>, Vec<String>) {
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+    let mut rest = args.iter().peekable();
+    while let Some(arg) = rest.next() {
+        if !arg.starts_with('-') || arg == "-" {
+            kept.push(arg.clone());
+            continue;
+        }
+        let mut option = arg.clone();
+        if !arg.contains('=') {
+            if let Some(value) = rest.next_if(|next| !next.starts_with('-')) {
+                option = format!("{option} {value}");
+            }
+        }
+        removed.push(option);
+    }
+    (kept, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(command: &str, args: &[&str], env: &[(&str, &str)]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            cwd: None,
+        }
+    }
+
+    fn field(name: &str, default: Option<&str>) -> ConfigField {
+        ConfigField {
+            default: default.map(str::to_string),
+            ..ConfigField::string(name)
+        }
+    }
+
+    #[test]
+    fn test_strips_options_and_optional_env() {
+        let normal = config(
+            "npx",
+            &[
+                "-y",
+                "@modelcontextprotocol/server-github@1.2.0",
+                "--toolsets",
+                "all",
+                "--read-only",
+                "--log=debug",
+            ],
+            &[
+                ("GITHUB_TOKEN", "ghp_x"),
+                ("LOG_LEVEL", "debug"),
+                ("GITHUB_HOST", "github.example.com"),
+            ],
+        );
+        let launch = SafeLaunch::new(
+            "github",
+            Some("Cursor".to_string()),
+            normal,
+            &[field("GITHUB_HOST", None)],
+        );
+
+        assert_eq!(
+            launch.safe.args,
+            ["-y", "@modelcontextprotocol/server-github@1.2.0"]
+        );
+        assert_eq!(
+            launch.removed_args,
+            ["--toolsets all", "--read-only", "--log=debug"]
+        );
+        assert_eq!(launch.removed_env, ["LOG_LEVEL"]);
+        assert_eq!(launch.kept_credentials, ["GITHUB_TOKEN"]);
+        assert_eq!(launch.kept_required, ["GITHUB_HOST"]);
+        assert!(!launch.safe.env.contains_key("LOG_LEVEL"));
+    }
+
+    #[test]
+    fn test_keeps_launcher_and_positional_args() {
+        let docker = config(
+            "docker",
+            &[
+                "run",
+                "-i",
+                "--rm",
+                "-v",
+                "/docs:/docs",
+                "mcp/filesystem",
+                "/docs",
+                "--verbose",
+            ],
+            &[],
+        );
+        let launch = SafeLaunch::new("fs", None, docker, &[]);
+        assert_eq!(
+            launch.safe.args,
+            [
+                "run",
+                "-i",
+                "--rm",
+                "-v",
+                "/docs:/docs",
+                "mcp/filesystem",
+                "/docs"
+            ]
+        );
+        assert_eq!(launch.removed_args, ["--verbose"]);
+
+        let uvx = config("uvx", &["mcp-server-git", "--repository", "/src/app"], &[]);
+        let launch = SafeLaunch::new("git", None, uvx, &[]);
+        assert_eq!(launch.safe.args, ["mcp-server-git"]);
+
+        let node = config("node", &["--inspect", "server.js", "--port", "3000"], &[]);
+        assert_eq!(
+            SafeLaunch::new("local", None, node, &[]).safe.args,
+            ["--inspect", "server.js"]
+        );
+    }
+
+    #[test]
+    fn test_required_defaults_fill_missing_variables() {
+        let normal = config("npx", &["-y", "weather-mcp"], &[]);
+        let launch = SafeLaunch::new(
+            "weather",
+            None,
+            normal,
+            &[field("UNITS", Some("metric")), field("REGION", None)],
+        );
+        assert_eq!(
+            launch.safe.env.get("UNITS").map(String::as_str),
+            Some("metric")
+        );
+        assert!(!launch.safe.env.contains_key("REGION"));
+        assert_eq!(launch.kept_required, ["UNITS"]);
+    }
+
+    #[test]
+    fn test_display() {
+        let normal = config(
+            "npx",
+            &["-y", "weather-mcp", "--units", "imperial"],
+            &[("API_KEY", "secret"), ("DEBUG", "1")],
+        );
+        let text = SafeLaunch::new("weather", Some("Cursor".to_string()), normal, &[]).to_string();
+        assert!(text.contains("Safe mode for 'weather' (Cursor's config)"));
+        assert!(text.contains("Normal: npx -y weather-mcp --units imperial"));
+        assert!(text.contains("Safe:   npx -y weather-mcp\n"));
+        assert!(text.contains("- argument --units imperial"));
+        assert!(text.contains("- env DEBUG"));
+        assert!(text.contains("= env API_KEY (credential)"));
+        assert!(!text.contains("secret"));
+
+        let bare = config("npx", &["-y", "weather-mcp"], &[]);
+        let text = SafeLaunch::new("weather", None, bare, &[]).to_string();
+        assert!(text.contains("Nothing to strip"));
+    }
+}
@@ -1,6 +1,24 @@
 use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
 use url::Url;
 
+/// System directories that should never be exposed to a filesystem server
+#[cfg(not(target_os = "windows"))]
+const SYSTEM_DIRECTORIES: &[&str] = &[
+    "/bin", "/boot", "/dev", "/etc", "/lib", "/proc", "/sbin", "/sys", "/usr", "/System",
+];
+
+#[cfg(target_os = "windows")]
+const SYSTEM_DIRECTORIES: &[&str] = &[
+    "C:\\Windows",
+    "C:\\Program Files",
+    "C:\\Program Files (x86)",
+    "C:\\ProgramData",
+];
+
+/// Directories holding credentials, relative to the home directory
+const SENSITIVE_HOME_DIRECTORIES: &[&str] = &[".ssh", ".aws", ".gnupg", ".kube", ".docker"];
+
 /// Security validation for MCP server sources.
 ///
 /// The SecurityValidator checks server sources (URLs, NPM packages, Docker images)
@@ -78,6 +96,8 @@ impl SecurityValidator {
                 "pypi.org".to_string(),
                 "hub.docker.com".to_string(),
                 "registry.hub.docker.com".to_string(),
+                "jsr.io".to_string(),
+                "deno.land".to_string(),
             ],
             allow_http: false,
         }
@@ -94,6 +114,8 @@ impl SecurityValidator {
                 "pypi.org".to_string(),
                 "hub.docker.com".to_string(),
                 "registry.hub.docker.com".to_string(),
+                "jsr.io".to_string(),
+                "deno.land".to_string(),
                 "localhost".to_string(),
                 "127.0.0.1".to_string(),
             ],
@@ -262,7 +284,204 @@ impl SecurityValidator {
         Ok(validation)
     }
 
+    /// Validate a directory that a filesystem server will be allowed to access.
+    ///
+    /// Warns when the directory is overly broad (filesystem root, home directory),
+    /// a system directory, or a directory holding credentials.
+    pub fn validate_filesystem_path(&self, path: &Path) -> Result<SecurityValidation> {
+        let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+        Ok(Self::check_filesystem_path(path, home.as_deref()))
+    }
+
+    fn check_filesystem_path(path: &Path, home: Option<&Path>) -> SecurityValidation {
+        let mut validation = SecurityValidation {
+            url: format!("file:{}", path.display()),
+            is_trusted: true,
+            is_https: false,
+            warnings: Vec::new(),
+            domain: None,
+        };
+
+        if path
+            .components()
+            .any(|c| c == std::path::Component::ParentDir)
+        {
+            validation.warnings.push(
+                "Directory contains suspicious path traversal patterns (..); use an absolute path."
+                    .to_string(),
+            );
+            validation.is_trusted = false;
+            return validation;
+        }
+
+        if path.parent().is_none() {
+            validation.warnings.push(format!(
+                "'{}' is the filesystem root and grants access to every file on this machine.",
+                path.display()
+            ));
+            validation.is_trusted = false;
+            return validation;
+        }
+
+        if let Some(system_dir) = SYSTEM_DIRECTORIES
+            .iter()
+            .map(PathBuf::from)
+            .find(|dir| path.starts_with(dir))
+        {
+            validation.warnings.push(format!(
+                "'{}' is inside the system directory {}. Servers should not need access to it.",
+                path.display(),
+                system_dir.display()
+            ));
+            validation.is_trusted = false;
+        }
+
+        if let Some(home) = home {
+            if path == home {
+                validation.warnings.push(format!(
+                    "'{}' is your home directory, which includes credentials and personal files. \
+                     Consider allowing a specific project folder instead.",
+                    path.display()
+                ));
+                validation.is_trusted = false;
+            } else if home.starts_with(path) {
+                validation.warnings.push(format!(
+                    "'{}' contains your home directory. Consider allowing a specific project folder instead.",
+                    path.display()
+                ));
+                validation.is_trusted = false;
+            } else if let Some(sensitive) = SENSITIVE_HOME_DIRECTORIES
+                .iter()
+                .find(|dir| path.starts_with(home.join(dir)))
+            {
+                validation.warnings.push(format!(
+                    "'{}' is inside ~/{}, which usually holds credentials.",
+                    path.display(),
+                    sensitive
+                ));
+                validation.is_trusted = false;
+            }
+        }
+
+        validation
+    }
+
+    /// Validate a Deno specifier: a `jsr:` or `npm:` package, a URL or a local script
+    pub fn validate_deno_specifier(&self, specifier: &str) -> Result<SecurityValidation> {
+        if specifier.starts_with("http://") || specifier.starts_with("https://") {
+            return self.validate_url(specifier);
+        }
+        if let Some(package) = specifier.strip_prefix("npm:") {
+            return self.validate_npm_package(package);
+        }
+
+        let mut validation = SecurityValidation {
+            url: specifier.to_string(),
+            is_trusted: true,
+            is_https: true,
+            warnings: Vec::new(),
+            domain: None,
+        };
+        if specifier.starts_with("jsr:") {
+            // jsr packages are fetched from the registry over HTTPS
+            validation.domain = Some("jsr.io".to_string());
+        }
+        if specifier.contains("..") {
+            validation
+                .warnings
+                .push("Specifier contains suspicious path traversal patterns.".to_string());
+            validation.is_trusted = false;
+        }
+
+        Ok(validation)
+    }
+
+    /// Warn about broad permissions in `deno run` permission flags.
+    ///
+    /// Flags granting everything (`-A`, `--allow-all`), unrestricted read, write,
+    /// network or environment access, subprocesses, native libraries, or access to
+    /// broad directories are reported.
+    pub fn validate_deno_permissions(&self, flags: &[String]) -> SecurityValidation {
+        let home = directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+        Self::check_deno_permissions(flags, home.as_deref())
+    }
+
+    fn check_deno_permissions(flags: &[String], home: Option<&Path>) -> SecurityValidation {
+        let mut validation = SecurityValidation {
+            url: "deno permissions".to_string(),
+            is_trusted: true,
+            is_https: true,
+            warnings: Vec::new(),
+            domain: None,
+        };
+
+        for flag in flags {
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (flag.as_str(), None),
+            };
+            match (name, value) {
+                ("-A" | "--allow-all", _) => validation.warnings.push(format!(
+                    "{flag} grants every permission, turning off Deno's sandbox."
+                )),
+                ("--allow-run" | "--allow-ffi", _) => validation.warnings.push(format!(
+                    "{flag} lets the server run programs or native code outside Deno's sandbox."
+                )),
+                ("--allow-read" | "--allow-write", Some(paths)) => {
+                    for path in paths.split(',').filter(|p| !p.is_empty()) {
+                        let check = Self::check_filesystem_path(Path::new(path), home);
+                        validation.warnings.extend(
+                            check
+                                .warnings
+                                .into_iter()
+                                .map(|warning| format!("{name}: {warning}")),
+                        );
+                    }
+                }
+                (
+                    "--allow-read" | "--allow-write" | "--allow-net" | "--allow-env"
+                    | "--allow-sys",
+                    None,
+                ) => validation.warnings.push(format!(
+                    "{flag} is unrestricted; list the specific {} the server needs.",
+                    match name {
+                        "--allow-net" => "hosts",
+                        "--allow-env" => "variables",
+                        "--allow-sys" => "APIs",
+                        _ => "paths",
+                    }
+                )),
+                _ => {}
+            }
+        }
+
+        validation
+    }
+
     /// Get list of trusted domains
+    /// Validate a server spec as accepted by `mcp add` / `mcp install`.
+    ///
+    /// Deno specs are validated as Deno specifiers, URLs directly, `docker:` specs as
+    /// images, `owner/repo` specs as GitHub repositories and everything else as npm
+    /// packages.
+    pub fn validate_server_spec(&self, spec: &str) -> Result<SecurityValidation> {
+        if let Some(specifier) = crate::server::deno::deno_specifier(spec) {
+            self.validate_deno_specifier(specifier)
+        } else if spec.starts_with("http://") || spec.starts_with("https://") {
+            self.validate_url(spec)
+        } else if let Some(image) = spec.strip_prefix("docker:") {
+            self.validate_docker_image(image)
+        } else if spec.contains('/') && !spec.starts_with('@') {
+            if spec.starts_with("github.com/") {
+                self.validate_url(&format!("https://{spec}"))
+            } else {
+                self.validate_url(&format!("https://github.com/{spec}"))
+            }
+        } else {
+            self.validate_npm_package(spec)
+        }
+    }
+
     pub fn trusted_domains(&self) -> &[String] {
         &self.trusted_domains
     }
@@ -274,6 +493,121 @@ impl Default for SecurityValidator {
     }
 }
 
+/// Environment variable that turns on strict security mode when set to a truthy value
+pub const STRICT_ENV_VAR: &str = "MCP_HELPER_STRICT";
+
+/// Whether strict security mode is on.
+///
+/// Strict mode is enabled by the `--strict` flag, the `MCP_HELPER_STRICT` environment
+/// variable, or `"security": { "strict": true }` in preferences.
+pub fn strict_mode_enabled(flag: bool) -> bool {
+    if flag {
+        return true;
+    }
+    if let Ok(value) = std::env::var(STRICT_ENV_VAR) {
+        return matches!(
+            value.trim().to_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        );
+    }
+    crate::config::Preferences::load()
+        .map(|prefs| prefs.security.strict)
+        .unwrap_or(false)
+}
+
+/// Kind of security failure, each with its own process exit code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecurityFailureClass {
+    /// Server source fetched over plain HTTP
+    InsecureTransport,
+    /// Binary download without a checksum to verify it
+    UnsignedBinary,
+    /// Source outside the trusted registries
+    UntrustedSource,
+    /// Any other validator warning
+    PolicyWarning,
+    /// npm package without verified provenance, or with signature metadata that doesn't match
+    UnverifiedPublisher,
+}
+
+impl SecurityFailureClass {
+    /// Exit code used when a command fails with this class of violation
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::InsecureTransport => 20,
+            Self::UnsignedBinary => 21,
+            Self::UntrustedSource => 22,
+            Self::PolicyWarning => 23,
+            Self::UnverifiedPublisher => 24,
+        }
+    }
+}
+
+impl std::fmt::Display for SecurityFailureClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Self::InsecureTransport => "insecure transport",
+            Self::UnsignedBinary => "unsigned binary",
+            Self::UntrustedSource => "untrusted source",
+            Self::PolicyWarning => "security warning",
+            Self::UnverifiedPublisher => "unverified publisher",
+        };
+        write!(f, "{text}")
+    }
+}
+
+/// A security problem that strict mode refuses to proceed past
+#[derive(Debug, Clone, PartialEq)]
+pub struct SecurityViolation {
+    pub class: SecurityFailureClass,
+    /// What was being validated (server spec, URL or path)
+    pub subject: String,
+    pub reasons: Vec<String>,
+}
+
+impl SecurityViolation {
+    pub fn new(class: SecurityFailureClass, subject: &str, reason: impl Into<String>) -> Self {
+        Self {
+            class,
+            subject: subject.to_string(),
+            reasons: vec![reason.into()],
+        }
+    }
+
+    /// Classify a validation that produced warnings
+    pub fn from_validation(subject: &str, validation: &SecurityValidation) -> Self {
+        let class = if validation.url.starts_with("http://") {
+            SecurityFailureClass::InsecureTransport
+        } else if !validation.is_trusted {
+            SecurityFailureClass::UntrustedSource
+        } else {
+            SecurityFailureClass::PolicyWarning
+        };
+
+        Self {
+            class,
+            subject: subject.to_string(),
+            reasons: validation.warnings.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for SecurityViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let blocker = match self.class {
+            SecurityFailureClass::UnverifiedPublisher => "Publisher check",
+            _ => "Strict mode",
+        };
+        write!(f, "{blocker} blocked {} ({})", self.subject, self.class)?;
+        for reason in &self.reasons {
+            write!(f, "\n  • {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SecurityViolation {}
+
 /// Result of security validation
 #[derive(Debug, Clone)]
 pub struct SecurityValidation {
@@ -398,6 +732,43 @@ mod tests {
         assert!(result.is_trusted);
     }
 
+    #[test]
+    fn test_validate_filesystem_path_flags_broad_directories() {
+        let home = PathBuf::from("/home/user");
+
+        let root = SecurityValidator::check_filesystem_path(Path::new("/"), Some(&home));
+        assert!(!root.is_trusted);
+        assert!(root.warnings[0].contains("filesystem root"));
+
+        let home_dir = SecurityValidator::check_filesystem_path(&home, Some(&home));
+        assert!(!home_dir.is_trusted);
+        assert!(home_dir.warnings[0].contains("home directory"));
+
+        let parent = SecurityValidator::check_filesystem_path(Path::new("/home"), Some(&home));
+        assert!(parent.warnings[0].contains("contains your home directory"));
+
+        let ssh = SecurityValidator::check_filesystem_path(
+            Path::new("/home/user/.ssh/keys"),
+            Some(&home),
+        );
+        assert!(ssh.warnings[0].contains("credentials"));
+
+        let traversal =
+            SecurityValidator::check_filesystem_path(Path::new("/home/user/../other"), Some(&home));
+        assert!(traversal.should_block());
+    }
+
+    #[test]
+    fn test_validate_filesystem_path_allows_project_directory() {
+        let home = PathBuf::from("/home/user");
+        let project = SecurityValidator::check_filesystem_path(
+            Path::new("/home/user/projects/app"),
+            Some(&home),
+        );
+        assert!(project.is_safe());
+        assert!(!project.should_block());
+    }
+
     #[test]
     fn test_security_validation_methods() {
         let validation = SecurityValidation {
@@ -412,4 +783,101 @@ mod tests {
         assert!(!validation.warnings_text().is_empty());
         assert!(!validation.should_block()); // Trusted with non-serious warnings
     }
+
+    #[test]
+    fn test_validate_server_spec() {
+        let validator = SecurityValidator::new();
+
+        let npm = validator
+            .validate_server_spec("@modelcontextprotocol/server-filesystem")
+            .unwrap();
+        assert_eq!(npm.url, "npm:@modelcontextprotocol/server-filesystem");
+
+        let docker = validator.validate_server_spec("docker:nginx").unwrap();
+        assert_eq!(docker.url, "docker:nginx");
+
+        let github = validator.validate_server_spec("owner/repo").unwrap();
+        assert_eq!(github.url, "https://github.com/owner/repo");
+        assert!(github.is_safe());
+    }
+
+    #[test]
+    fn test_security_violation_classes() {
+        let validator = SecurityValidator::new();
+
+        let http = validator
+            .validate_url("http://github.com/user/repo")
+            .unwrap();
+        let violation = SecurityViolation::from_validation("http://github.com/user/repo", &http);
+        assert_eq!(violation.class, SecurityFailureClass::InsecureTransport);
+        assert_eq!(violation.class.exit_code(), 20);
+
+        let untrusted = validator
+            .validate_url("https://unknown-domain.com/server")
+            .unwrap();
+        let violation = SecurityViolation::from_validation("server", &untrusted);
+        assert_eq!(violation.class, SecurityFailureClass::UntrustedSource);
+        assert!(violation
+            .to_string()
+            .contains("not in the list of trusted sources"));
+
+        let npm = validator.validate_npm_package("rm").unwrap();
+        let violation = SecurityViolation::from_validation("rm", &npm);
+        assert_eq!(violation.class, SecurityFailureClass::PolicyWarning);
+
+        let codes: std::collections::HashSet<i32> = [
+            SecurityFailureClass::InsecureTransport,
+            SecurityFailureClass::UnsignedBinary,
+            SecurityFailureClass::UntrustedSource,
+            SecurityFailureClass::PolicyWarning,
+        ]
+        .iter()
+        .map(SecurityFailureClass::exit_code)
+        .collect();
+        assert_eq!(codes.len(), 4);
+        assert!(!codes.contains(&1));
+    }
+
+    #[test]
+    fn test_strict_mode_flag() {
+        assert!(strict_mode_enabled(true));
+    }
+
+    #[test]
+    fn test_validate_deno_specifier() {
+        let validator = SecurityValidator::new();
+
+        let jsr = validator.validate_server_spec("jsr:@scope/notes").unwrap();
+        assert!(jsr.is_safe());
+        assert_eq!(jsr.domain.as_deref(), Some("jsr.io"));
+
+        let remote = validator
+            .validate_server_spec("https://deno.land/x/mcp/main.ts")
+            .unwrap();
+        assert!(remote.is_trusted);
+
+        let traversal = validator.validate_server_spec("../../server.ts").unwrap();
+        assert!(!traversal.is_trusted);
+    }
+
+    #[test]
+    fn test_deno_permission_warnings() {
+        let home = PathBuf::from("/home/user");
+        let flags = |flags: &[&str]| -> Vec<String> {
+            let flags: Vec<String> = flags.iter().map(|f| f.to_string()).collect();
+            SecurityValidator::check_deno_permissions(&flags, Some(&home)).warnings
+        };
+
+        assert!(flags(&["--allow-read=./data", "--allow-net=api.example.com"]).is_empty());
+        assert_eq!(flags(&["-A"]).len(), 1);
+        assert_eq!(flags(&["--allow-run=git", "--allow-ffi"]).len(), 2);
+        assert!(flags(&["--allow-net"])[0].contains("hosts"));
+        assert!(flags(&["--allow-read=/"])[0].contains("filesystem root"));
+        assert!(flags(&["--allow-write=/home/user"])[0].starts_with("--allow-write: "));
+
+        let validation =
+            SecurityValidator::check_deno_permissions(&["-A".to_string()], Some(&home));
+        let violation = SecurityViolation::from_validation("server.ts", &validation);
+        assert_eq!(violation.class, SecurityFailureClass::PolicyWarning);
+    }
 }
@@ -0,0 +1,390 @@
+//! Security findings for a planned install, as SARIF or JSON.
+//!
+//! `mcp install --dry-run --security-report sarif` runs the security validator over
+//! everything an install would pull in — a single server, each server in a
+//! `--batch` file, or each package a registry manifest lists — and prints the
+//! findings without changing any configuration. SARIF output can be uploaded to
+//! code scanning tools; with `--strict` any finding also fails the command.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+use crate::security::{SecurityFailureClass, SecurityValidator, SecurityViolation};
+use crate::server::install_manifest::is_install_manifest_url;
+use crate::server::ManifestSource;
+use crate::utils::batch_file::parse_batch_file;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const INFORMATION_URI: &str = "https://github.com/sapientpants/mcp-helper";
+
+/// Output format of a security report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Sarif,
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sarif" => Ok(Self::Sarif),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "Unknown security report format '{s}'. Use sarif or json"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sarif => write!(f, "sarif"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// A server spec to validate and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanTarget {
+    pub spec: String,
+    /// Batch file or manifest that named the spec
+    pub file: Option<String>,
+    pub line: Option<usize>,
+}
+
+impl ScanTarget {
+    fn new(spec: impl Into<String>) -> Self {
+        Self {
+            spec: spec.into(),
+            file: None,
+            line: None,
+        }
+    }
+}
+
+/// One security problem found in a target
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub target: ScanTarget,
+    /// `None` when the spec couldn't be validated at all
+    pub class: Option<SecurityFailureClass>,
+    /// Whether a normal (non-strict) install would refuse the spec
+    pub blocking: bool,
+    pub message: String,
+}
+
+impl Finding {
+    /// Stable rule id for the finding's class
+    pub fn rule_id(&self) -> &'static str {
+        match self.class {
+            Some(SecurityFailureClass::InsecureTransport) => "insecure-transport",
+            Some(SecurityFailureClass::UnsignedBinary) => "unsigned-binary",
+            Some(SecurityFailureClass::UntrustedSource) => "untrusted-source",
+            Some(SecurityFailureClass::PolicyWarning) => "policy-warning",
+            Some(SecurityFailureClass::UnverifiedPublisher) => "unverified-publisher",
+            None => "validation-error",
+        }
+    }
+
+    /// SARIF level: `error` for findings that block an install, `warning` otherwise
+    pub fn level(&self) -> &'static str {
+        if self.blocking {
+            "error"
+        } else {
+            "warning"
+        }
+    }
+}
+
+/// All findings for a set of targets
+#[derive(Debug, Default)]
+pub struct SecurityReport {
+    pub targets: Vec<ScanTarget>,
+    pub findings: Vec<Finding>,
+}
+
+impl SecurityReport {
+    /// Validate every target
+    pub fn scan(validator: &SecurityValidator, targets: Vec<ScanTarget>) -> Self {
+        let mut findings = Vec::new();
+        for target in &targets {
+            match validator.validate_server_spec(&target.spec) {
+                Ok(validation) => {
+                    let class = SecurityViolation::from_validation(&target.spec, &validation).class;
+                    let blocking = validation.should_block();
+                    findings.extend(validation.warnings.iter().map(|warning| Finding {
+                        target: target.clone(),
+                        class: Some(class),
+                        blocking,
+                        message: warning.clone(),
+                    }));
+                }
+                Err(e) => findings.push(Finding {
+                    target: target.clone(),
+                    class: None,
+                    blocking: true,
+                    message: format!("{e:#}"),
+                }),
+            }
+        }
+        Self { targets, findings }
+    }
+
+    /// Render the report in `format`
+    pub fn render(&self, format: ReportFormat) -> String {
+        let value = match format {
+            ReportFormat::Sarif => self.to_sarif(),
+            ReportFormat::Json => self.to_json(),
+        };
+        serde_json::to_string_pretty(&value).unwrap_or_default()
+    }
+
+    /// The report as a SARIF 2.1.0 log
+    pub fn to_sarif(&self) -> Value {
+        let mut rule_ids: Vec<&str> = self.findings.iter().map(Finding::rule_id).collect();
+        rule_ids.sort_unstable();
+        rule_ids.dedup();
+        let rules: Vec<Value> = rule_ids
+            .iter()
+            .map(|id| {
+                json!({
+                    "id": id,
+                    "shortDescription": { "text": rule_description(id) },
+                })
+            })
+            .collect();
+
+        let results: Vec<Value> = self
+            .findings
+            .iter()
+            .map(|finding| {
+                let location = match &finding.target.file {
+                    Some(file) => {
+                        let mut physical = json!({ "artifactLocation": { "uri": file } });
+                        if let Some(line) = finding.target.line {
+                            physical["region"] = json!({ "startLine": line });
+                        }
+                        json!({
+                            "physicalLocation": physical,
+                            "logicalLocations": [{ "name": finding.target.spec }],
+                        })
+                    }
+                    None => json!({ "logicalLocations": [{ "name": finding.target.spec }] }),
+                };
+                json!({
+                    "ruleId": finding.rule_id(),
+                    "level": finding.level(),
+                    "message": { "text": format!("{}: {}", finding.target.spec, finding.message) },
+                    "locations": [location],
+                })
+            })
+            .collect();
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "mcp-helper",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": INFORMATION_URI,
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }],
+        })
+    }
+
+    /// The report as plain JSON
+    pub fn to_json(&self) -> Value {
+        let findings: Vec<Value> = self
+            .findings
+            .iter()
+            .map(|finding| {
+                json!({
+                    "server": finding.target.spec,
+                    "file": finding.target.file,
+                    "line": finding.target.line,
+                    "rule": finding.rule_id(),
+                    "level": finding.level(),
+                    "message": finding.message,
+                })
+            })
+            .collect();
+        json!({
+            "scanned": self.targets.iter().map(|target| &target.spec).collect::<Vec<_>>(),
+            "findings": findings,
+        })
+    }
+
+    /// In strict mode, the violation that fails the command, if there are findings
+    pub fn strict_violation(&self) -> Option<SecurityViolation> {
+        let first = self.findings.first()?;
+        Some(SecurityViolation {
+            class: first.class.unwrap_or(SecurityFailureClass::PolicyWarning),
+            subject: if self.targets.len() == 1 {
+                first.target.spec.clone()
+            } else {
+                format!("{} servers", self.targets.len())
+            },
+            reasons: self
+                .findings
+                .iter()
+                .map(|finding| format!("{}: {}", finding.target.spec, finding.message))
+                .collect(),
+        })
+    }
+}
+
+fn rule_description(id: &str) -> &'static str {
+    match id {
+        "insecure-transport" => "Server source is fetched over plain HTTP",
+        "unsigned-binary" => "Binary download has no checksum to verify it",
+        "untrusted-source" => "Server source is outside the trusted registries",
+        "policy-warning" => "Security validator warning",
+        "unverified-publisher" => "npm package has no verified build provenance",
+        _ => "Server source could not be validated",
+    }
+}
+
+/// What an install of `server` (or of the servers in `batch`) would validate
+pub fn scan_targets(server: Option<&str>, batch: Option<&str>) -> Result<Vec<ScanTarget>> {
+    let mut targets = Vec::new();
+
+    if let Some(batch) = batch {
+        let content = fs::read_to_string(batch)
+            .with_context(|| format!("Failed to read batch file '{batch}'"))?;
+        for entry in parse_batch_file(batch, &content)? {
+            targets.push(ScanTarget {
+                spec: entry.server,
+                file: Some(batch.to_string()),
+                line: Some(entry.line),
+            });
+        }
+    }
+
+    if let Some(server) = server {
+        if is_install_manifest_url(server) {
+            targets.push(ScanTarget::new(server));
+        } else if let Some(source) = ManifestSource::parse(server) {
+            targets.extend(manifest_targets(server, &source)?);
+        } else {
+            targets.push(ScanTarget::new(server));
+        }
+    }
+    Ok(targets)
+}
+
+/// The packages a registry manifest would install; packages the validator has no
+/// rules for (e.g. PyPI) are left out
+fn manifest_targets(reference: &str, source: &ManifestSource) -> Result<Vec<ScanTarget>> {
+    let manifest = source.load()?;
+    let mut targets = Vec::new();
+    if let ManifestSource::Url(url) = source {
+        targets.push(ScanTarget::new(url.clone()));
+    }
+    for package in &manifest.packages {
+        let spec = match package.registry_type.as_str() {
+            "npm" => package.identifier.clone(),
+            "oci" | "docker" => format!("docker:{}", package.identifier),
+            _ => continue,
+        };
+        targets.push(ScanTarget {
+            spec,
+            file: Some(reference.to_string()),
+            line: None,
+        });
+    }
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn report(specs: &[&str]) -> SecurityReport {
+        SecurityReport::scan(
+            &SecurityValidator::new(),
+            specs.iter().map(|spec| ScanTarget::new(*spec)).collect(),
+        )
+    }
+
+    #[test]
+    fn test_report_format_parsing() {
+        assert_eq!("SARIF".parse::<ReportFormat>(), Ok(ReportFormat::Sarif));
+        assert_eq!("json".parse::<ReportFormat>(), Ok(ReportFormat::Json));
+        assert!("xml".parse::<ReportFormat>().is_err());
+    }
+
+    #[test]
+    fn test_insecure_source_is_reported() {
+        let report = report(&[
+            "@modelcontextprotocol/server-filesystem",
+            "http://unknown.example/mcp-server",
+        ]);
+
+        assert_eq!(report.targets.len(), 2);
+        assert!(!report.findings.is_empty());
+        assert!(report
+            .findings
+            .iter()
+            .all(|finding| finding.target.spec == "http://unknown.example/mcp-server"));
+        assert_eq!(report.findings[0].rule_id(), "insecure-transport");
+        assert!(report.strict_violation().is_some());
+    }
+
+    #[test]
+    fn test_sarif_shape() {
+        let sarif = report(&["http://unknown.example/mcp-server"]).to_sarif();
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "mcp-helper");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["id"],
+            "insecure-transport"
+        );
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "insecure-transport");
+        assert_eq!(
+            result["locations"][0]["logicalLocations"][0]["name"],
+            "http://unknown.example/mcp-server"
+        );
+    }
+
+    #[test]
+    fn test_clean_report_has_no_violation() {
+        let report = report(&["@modelcontextprotocol/server-filesystem"]);
+        assert!(report.findings.is_empty());
+        assert!(report.strict_violation().is_none());
+        assert_eq!(report.to_sarif()["runs"][0]["results"], json!([]));
+    }
+
+    #[test]
+    fn test_batch_targets_keep_file_and_line() {
+        let temp = TempDir::new().unwrap();
+        let batch = temp.path().join("servers.txt");
+        fs::write(
+            &batch,
+            "# servers\n[server-a]\n\n[docker:postgres:13]\nx=1\n",
+        )
+        .unwrap();
+        let batch = batch.to_string_lossy().to_string();
+
+        let targets = scan_targets(None, Some(&batch)).unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[1].spec, "docker:postgres:13");
+        assert_eq!(targets[1].file.as_deref(), Some(batch.as_str()));
+        assert_eq!(targets[1].line, Some(4));
+    }
+}
@@ -0,0 +1,250 @@
+//! Unpacking binary releases shipped as archives.
+//!
+//! Many projects publish their release assets as `.tar.gz` or `.zip` files rather
+//! than bare executables. [`BinaryServer`](super::BinaryServer) unpacks those into
+//! a directory of their own under the managed bin directory and then looks for the
+//! executable inside with [`find_executable`].
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Archive formats release assets come in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveKind {
+    /// The archive format of `filename`, judging by its extension
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let lower = filename.to_lowercase();
+        if lower.ends_with(".tar.gz") || lower.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if lower.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// `filename` without its archive extension, e.g. `server-linux` for `server-linux.tar.gz`
+pub fn archive_stem(filename: &str) -> &str {
+    let lower = filename.to_lowercase();
+    [".tar.gz", ".tgz", ".zip"]
+        .iter()
+        .find(|ext| lower.ends_with(*ext))
+        .map(|ext| &filename[..filename.len() - ext.len()])
+        .unwrap_or(filename)
+}
+
+/// Unpack `archive` into `dest`, returning the files it contained
+///
+/// Entries that would land outside `dest` are skipped.
+pub fn extract(archive: &Path, kind: ArchiveKind, dest: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dest)
+        .with_context(|| format!("Failed to create directory {}", dest.display()))?;
+    let file = fs::File::open(archive)
+        .with_context(|| format!("Failed to open archive {}", archive.display()))?;
+
+    let mut files = Vec::new();
+    match kind {
+        ArchiveKind::TarGz => {
+            let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(file));
+            for entry in tar.entries().context("Failed to read tar.gz archive")? {
+                let mut entry = entry.context("Failed to read tar.gz archive entry")?;
+                let is_file = entry.header().entry_type().is_file();
+                let relative = entry
+                    .path()
+                    .context("Invalid path in archive")?
+                    .into_owned();
+                if entry
+                    .unpack_in(dest)
+                    .with_context(|| format!("Failed to unpack {}", relative.display()))?
+                    && is_file
+                {
+                    files.push(dest.join(relative));
+                }
+            }
+        }
+        ArchiveKind::Zip => {
+            let mut zip = zip::ZipArchive::new(file).context("Failed to read zip archive")?;
+            for index in 0..zip.len() {
+                let mut entry = zip
+                    .by_index(index)
+                    .context("Failed to read zip archive entry")?;
+                let Some(relative) = entry.enclosed_name() else {
+                    continue;
+                };
+                let path = dest.join(relative);
+                if entry.is_dir() {
+                    fs::create_dir_all(&path)?;
+                    continue;
+                }
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut out = fs::File::create(&path)
+                    .with_context(|| format!("Failed to create file: {}", path.display()))?;
+                std::io::copy(&mut entry, &mut out)
+                    .with_context(|| format!("Failed to unpack {}", path.display()))?;
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Extensions of files that ship next to the executable but are never it
+const NON_EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "md", "txt", "json", "toml", "yaml", "yml", "html", "sha256", "sig", "asc", "pem", "1",
+];
+
+fn is_candidate(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if name.starts_with("license") || name.starts_with("readme") || name.starts_with("changelog") {
+        return false;
+    }
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+    match extension.as_deref() {
+        Some("exe") => true,
+        _ if cfg!(windows) => false,
+        Some(ext) => !NON_EXECUTABLE_EXTENSIONS.contains(&ext),
+        None => true,
+    }
+}
+
+/// The executable among an archive's `files`, preferring one named `preferred`
+///
+/// Falls back to the only candidate when none has the preferred name, and fails
+/// when the choice is ambiguous.
+pub fn find_executable(files: &[PathBuf], preferred: &[&str]) -> Result<PathBuf> {
+    let candidates: Vec<&PathBuf> = files.iter().filter(|path| is_candidate(path)).collect();
+
+    let stem = |path: &Path| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default()
+    };
+    for name in preferred {
+        let name = name.to_lowercase();
+        if let Some(path) = candidates.iter().find(|path| stem(path) == name) {
+            return Ok((*path).clone());
+        }
+    }
+
+    match candidates.as_slice() {
+        [only] => Ok((*only).clone()),
+        [] => anyhow::bail!("The archive does not contain an executable"),
+        many => anyhow::bail!(
+            "Could not tell which file in the archive is the server: {}",
+            many.iter()
+                .filter_map(|path| path.file_name())
+                .map(|name| name.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_archive_kind_and_stem() {
+        assert_eq!(
+            ArchiveKind::from_filename("server-linux.tar.gz"),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::from_filename("server.TGZ"),
+            Some(ArchiveKind::TarGz)
+        );
+        assert_eq!(
+            ArchiveKind::from_filename("server-windows.zip"),
+            Some(ArchiveKind::Zip)
+        );
+        assert_eq!(ArchiveKind::from_filename("server-linux"), None);
+
+        assert_eq!(archive_stem("server-linux.tar.gz"), "server-linux");
+        assert_eq!(archive_stem("server.zip"), "server");
+        assert_eq!(archive_stem("server"), "server");
+    }
+
+    #[test]
+    fn test_extract_tar_gz() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("server.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            fs::File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for (path, contents) in [("server/my-server", "binary"), ("server/README.md", "docs")] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let dest = temp.path().join("out");
+        let files = extract(&archive, ArchiveKind::TarGz, &dest).unwrap();
+        assert_eq!(files.len(), 2);
+        let executable = find_executable(&files, &["my-server"]).unwrap();
+        assert_eq!(executable, dest.join("server").join("my-server"));
+        assert_eq!(fs::read_to_string(executable).unwrap(), "binary");
+    }
+
+    #[test]
+    fn test_extract_zip_skips_escaping_entries() {
+        let temp = TempDir::new().unwrap();
+        let archive = temp.path().join("server.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("my-server.exe", options).unwrap();
+        writer.write_all(b"binary").unwrap();
+        writer.start_file("../escape.exe", options).unwrap();
+        writer.write_all(b"nope").unwrap();
+        writer.finish().unwrap();
+
+        let dest = temp.path().join("out");
+        let files = extract(&archive, ArchiveKind::Zip, &dest).unwrap();
+        assert_eq!(files, vec![dest.join("my-server.exe")]);
+        assert!(!temp.path().join("escape.exe").exists());
+    }
+
+    #[test]
+    fn test_find_executable() {
+        let files: Vec<PathBuf> = ["LICENSE", "README.md", "tool.exe", "server.exe"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        assert_eq!(
+            find_executable(&files, &["server"]).unwrap(),
+            PathBuf::from("server.exe")
+        );
+        // Two candidates and neither preferred: ambiguous
+        assert!(find_executable(&files, &["other"]).is_err());
+
+        let single = vec![PathBuf::from("LICENSE"), PathBuf::from("only.exe")];
+        assert_eq!(
+            find_executable(&single, &[]).unwrap(),
+            PathBuf::from("only.exe")
+        );
+        assert!(find_executable(&[PathBuf::from("notes.txt")], &[]).is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! Command-line arguments rendered from configuration values.
+//!
+//! Some servers take their configuration as arguments rather than environment
+//! variables, e.g. the directories a filesystem server may access. Their metadata
+//! carries an argument template such as `["--root", "{{allowedDirectories}}"]`:
+//!
+//! - An argument that is only a placeholder expands to one argument per
+//!   comma-separated value, so `allowedDirectories=/a,/b` gives `/a /b`
+//! - Placeholders inside a longer argument (`--port={{port}}`) are replaced in place
+//! - Arguments whose values are missing are left out, together with a flag
+//!   right before a missing standalone placeholder
+//! - Variables mcp-helper provides itself (`{{home}}`, see
+//!   [`crate::config::variables`]) are kept for when the config is written
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::config::variables::is_variable;
+
+/// Render `template` with values from `config`
+pub fn render(template: &[String], config: &HashMap<String, String>) -> Vec<String> {
+    let mut rendered = Vec::new();
+    let mut pending_flag: Option<&String> = None;
+
+    for argument in template {
+        if let Some(flag) = pending_flag.take() {
+            if standalone_placeholder(argument).is_none() {
+                rendered.push(flag.clone());
+            } else if let Some(values) = expand(argument, config) {
+                if !values.is_empty() {
+                    rendered.push(flag.clone());
+                    rendered.extend(values);
+                }
+                continue;
+            }
+        }
+
+        if argument.starts_with('-') && placeholders_in(argument).next().is_none() {
+            pending_flag = Some(argument);
+            continue;
+        }
+
+        if let Some(values) = expand(argument, config) {
+            rendered.extend(values);
+        } else if let Some(value) = substitute(argument, config) {
+            rendered.push(value);
+        }
+    }
+
+    if let Some(flag) = pending_flag {
+        rendered.push(flag.clone());
+    }
+    rendered
+}
+
+/// Configuration fields referenced by `template`
+pub fn placeholders(template: &[String]) -> BTreeSet<String> {
+    template
+        .iter()
+        .flat_map(|argument| placeholders_in(argument))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Values for an argument that is only a placeholder; `None` for other arguments
+fn expand(argument: &str, config: &HashMap<String, String>) -> Option<Vec<String>> {
+    let name = standalone_placeholder(argument)?;
+    let values = config
+        .get(name)
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(values)
+}
+
+/// `argument` with every placeholder replaced, or `None` if a value is missing
+fn substitute(argument: &str, config: &HashMap<String, String>) -> Option<String> {
+    let mut result = String::new();
+    let mut rest = argument;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + end].trim();
+        result.push_str(&rest[..start]);
+        match config.get(name) {
+            Some(value) => result.push_str(value),
+            None if is_variable(name) => result.push_str(&rest[start..start + end + 2]),
+            None => return None,
+        }
+        rest = &rest[start + end + 2..];
+    }
+    result.push_str(rest);
+    Some(result)
+}
+
+fn standalone_placeholder(argument: &str) -> Option<&str> {
+    let name = argument.strip_prefix("{{")?.strip_suffix("}}")?.trim();
+    (!name.is_empty() && !name.contains(['{', '}']) && !is_variable(name)).then_some(name)
+}
+
+fn placeholders_in(argument: &str) -> impl Iterator<Item = &str> {
+    argument.split("{{").skip(1).filter_map(|part| {
+        let (name, _) = part.split_once("}}")?;
+        let name = name.trim();
+        (!name.is_empty() && !is_variable(name)).then_some(name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn config(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_standalone_placeholder_expands_lists() {
+        let rendered = render(
+            &template(&["{{allowedDirectories}}"]),
+            &config(&[("allowedDirectories", "/home/me/docs, /tmp")]),
+        );
+        assert_eq!(rendered, ["/home/me/docs", "/tmp"]);
+    }
+
+    #[test]
+    fn test_flag_with_value() {
+        let template = template(&["--stdio", "--root", "{{root}}", "--port={{port}}"]);
+        assert_eq!(
+            render(&template, &config(&[("root", "/srv"), ("port", "8080")])),
+            ["--stdio", "--root", "/srv", "--port=8080"]
+        );
+        // Missing values drop the argument and the flag that introduces it
+        assert_eq!(render(&template, &HashMap::new()), ["--stdio"]);
+    }
+
+    #[test]
+    fn test_trailing_flag_is_kept() {
+        assert_eq!(
+            render(
+                &template(&["{{dir}}", "--read-only"]),
+                &config(&[("dir", "/a")])
+            ),
+            ["/a", "--read-only"]
+        );
+    }
+
+    #[test]
+    fn test_helper_variables_are_kept() {
+        let template = template(&["--db", "{{data_dir}}/{{name}}.db", "{{home}}"]);
+        assert_eq!(
+            render(&template, &config(&[("name", "notes")])),
+            ["--db", "{{data_dir}}/notes.db", "{{home}}"]
+        );
+        assert_eq!(
+            placeholders(&template).into_iter().collect::<Vec<_>>(),
+            ["name"]
+        );
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let names = placeholders(&template(&[
+            "--root",
+            "{{ root }}",
+            "--url={{host}}:{{port}}",
+        ]));
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            ["host", "port", "root"]
+        );
+    }
+
+    #[test]
+    fn test_unterminated_placeholder_is_literal() {
+        assert_eq!(render(&template(&["{{oops"]), &HashMap::new()), ["{{oops"]);
+    }
+}
@@ -1,16 +1,27 @@
+use crate::audit_log;
 use crate::cache::CacheManager;
 use crate::deps::{Dependency, DependencyChecker, DependencyStatus};
-use crate::server::{ConfigField, ConfigFieldType, McpServer, ServerMetadata, ServerType};
+use crate::server::archive::{self, ArchiveKind};
+use crate::server::{github, ConfigField, McpServer, ServerMetadata, ServerType};
+use crate::utils::antivirus;
+use crate::utils::disk_space;
+use crate::utils::state_file::{read_state, write_state};
 use anyhow::{Context, Result};
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
 use reqwest::blocking::Client;
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Download URL of each binary in the bin directory, by file or archive directory name
+const RECEIPTS_FILE: &str = "installed.json";
 
 #[derive(Debug, Deserialize)]
 struct GitHubRelease {
@@ -46,20 +57,8 @@ impl BinaryServer {
                 checksum: checksum.clone(),
             },
             required_config: vec![],
-            optional_config: vec![
-                ConfigField {
-                    name: "working_directory".to_string(),
-                    field_type: ConfigFieldType::Path,
-                    description: Some("Working directory for the server".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "timeout".to_string(),
-                    field_type: ConfigFieldType::Number,
-                    description: Some("Server timeout in seconds".to_string()),
-                    default: Some("30".to_string()),
-                },
-            ],
+            optional_config: vec![ConfigField::working_directory(), ConfigField::timeout()],
+            args_template: vec![],
         };
 
         Self {
@@ -71,30 +70,52 @@ impl BinaryServer {
     }
 
     pub fn from_github_repo(repo: &str, version: Option<&str>) -> Result<Self> {
-        let client = Client::new();
-        let api_url = if let Some(v) = version {
-            format!("https://api.github.com/repos/{repo}/releases/tags/{v}")
-        } else {
-            format!("https://api.github.com/repos/{repo}/releases/latest")
-        };
-
-        let response = client
-            .get(&api_url)
-            .header("User-Agent", "mcp-helper")
-            .send()
-            .context("Failed to fetch GitHub release")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!("GitHub API request failed: {}", response.status());
-        }
-
-        let release: GitHubRelease = response
-            .json()
+        let release: GitHubRelease = serde_json::from_value(github::release(repo, version)?)
             .context("Failed to parse GitHub release response")?;
 
         let platform_asset = Self::select_platform_asset(&release.assets)?;
 
-        Ok(Self::new(&platform_asset.browser_download_url, None))
+        let mut server = Self::new(&platform_asset.browser_download_url, None);
+        server.metadata.name = repo.split('/').next_back().unwrap_or(repo).to_string();
+        Ok(server)
+    }
+
+    /// A server for `url`, which is either a download link or a GitHub repository or
+    /// release page whose asset for this platform is looked up
+    pub fn from_url(url: &str, checksum: Option<String>) -> Result<Self> {
+        match Self::github_release_spec(url) {
+            Some((repo, tag)) => {
+                let mut server = Self::from_github_repo(&repo, tag.as_deref())?;
+                server.checksum = checksum;
+                Ok(server)
+            }
+            None => Ok(Self::new(url, checksum)),
+        }
+    }
+
+    /// The repository and tag of a GitHub repository or release page URL, as opposed to
+    /// a direct asset download (`None`)
+    ///
+    /// `https://github.com/owner/repo` and `.../releases/latest` mean the latest
+    /// release, `.../releases/tag/v1.0` that tag.
+    pub(crate) fn github_release_spec(url: &str) -> Option<(String, Option<String>)> {
+        let path = url
+            .strip_prefix("https://github.com/")?
+            .split(['?', '#'])
+            .next()?
+            .trim_end_matches('/');
+        let parts: Vec<&str> = path.split('/').collect();
+        let repo = match parts.as_slice() {
+            [owner, name, ..] if !owner.is_empty() && !name.is_empty() => {
+                format!("{owner}/{name}")
+            }
+            _ => return None,
+        };
+        match parts[2..] {
+            [] | ["releases"] | ["releases", "latest"] => Some((repo, None)),
+            ["releases", "tag", tag] => Some((repo, Some(tag.to_string()))),
+            _ => None,
+        }
     }
 
     fn extract_name_from_url(url: &str) -> String {
@@ -140,22 +161,35 @@ impl BinaryServer {
             _ => vec![arch],
         };
 
+        // Checksums and signatures are published next to the binaries they cover
+        let binaries: Vec<&GitHubAsset> = assets
+            .iter()
+            .filter(|asset| !Self::is_auxiliary_asset(&asset.name))
+            .collect();
+        let platform_match = |name: &str| {
+            // "darwin" contains "win", so macOS assets are ruled out first
+            let for_macos = ["darwin", "macos", "osx", "apple"]
+                .iter()
+                .any(|p| name.contains(p));
+            if for_macos != (platform == "macos") {
+                return false;
+            }
+            patterns.iter().any(|p| name.contains(p))
+        };
+
         // Find best matching asset
-        for asset in assets {
+        for asset in &binaries {
             let name_lower = asset.name.to_lowercase();
-
-            let platform_match = patterns.iter().any(|p| name_lower.contains(p));
             let arch_match = arch_patterns.iter().any(|a| name_lower.contains(a));
 
-            if platform_match && arch_match {
+            if platform_match(&name_lower) && arch_match {
                 return Ok(asset);
             }
         }
 
         // Fallback: try platform match only
-        for asset in assets {
-            let name_lower = asset.name.to_lowercase();
-            if patterns.iter().any(|p| name_lower.contains(p)) {
+        for asset in &binaries {
+            if platform_match(&asset.name.to_lowercase()) {
                 return Ok(asset);
             }
         }
@@ -172,28 +206,60 @@ impl BinaryServer {
         );
     }
 
+    fn is_auxiliary_asset(name: &str) -> bool {
+        let name = name.to_lowercase();
+        [
+            ".sha256", ".sha512", ".sig", ".asc", ".pem", ".sbom", ".txt", ".json",
+        ]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+            || name.contains("checksums")
+    }
+
+    /// The file name of the download, without any query string
+    fn filename(&self) -> Result<&str> {
+        self.url
+            .split('/')
+            .next_back()
+            .and_then(|name| name.split('?').next())
+            .filter(|name| !name.is_empty())
+            .context("Could not determine filename from URL")
+    }
+
+    /// Where the download lands in the bin directory: the binary itself, or for an
+    /// archive the directory it is unpacked into
+    pub fn install_target(&self) -> Result<PathBuf> {
+        let filename = self.filename()?;
+        Ok(Self::bin_directory()?.join(archive::archive_stem(filename)))
+    }
+
     pub fn download_and_install(
         &mut self,
         cache_manager: Option<&CacheManager>,
     ) -> Result<PathBuf> {
-        let bin_dir = self.get_bin_directory()?;
+        let bin_dir = Self::bin_directory()?;
         fs::create_dir_all(&bin_dir)?;
 
-        let filename = self
-            .url
-            .split('/')
-            .next_back()
-            .and_then(|name| name.split('?').next())
-            .context("Could not determine filename from URL")?;
-
-        let binary_path = bin_dir.join(filename);
+        let filename = self.filename()?.to_string();
+        let archive_kind = ArchiveKind::from_filename(&filename);
+        let download_path = match archive_kind {
+            // Archives are unpacked into a directory named after them
+            Some(_) => bin_dir.join(format!("{filename}.download")),
+            None => bin_dir.join(&filename),
+        };
+        let before = match archive_kind {
+            Some(_) => None,
+            None => audit_log::file_hash(&download_path),
+        };
 
         // Check cache first
         let should_download = if let Some(cache_mgr) = cache_manager {
             if let Some(cached_path) = cache_mgr.get_cached_download(&self.url) {
                 println!("  {} Using cached download", "ℹ".blue());
                 // Copy from cache to final location
-                fs::copy(&cached_path, &binary_path).context("Failed to copy from cache")?;
+                antivirus::retry_if_locked(|| fs::copy(&cached_path, &download_path)).map_err(
+                    |e| antivirus::explain(e, "Failed to copy from cache to", &download_path),
+                )?;
                 false
             } else {
                 true
@@ -204,14 +270,30 @@ impl BinaryServer {
 
         if should_download {
             // Download the binary
-            self.download_binary(&binary_path, cache_manager)?;
+            self.download_binary(&download_path, cache_manager)?;
         }
 
-        // Verify checksum if provided
+        // Verify checksum if provided; the checksum covers the file as published
         if let Some(expected_checksum) = &self.checksum {
-            self.verify_checksum(&binary_path, expected_checksum)?;
+            if let Err(e) = self.verify_checksum(&download_path, expected_checksum) {
+                let _ = fs::remove_file(&download_path);
+                return Err(e);
+            }
         }
 
+        let binary_path = match archive_kind {
+            Some(kind) => {
+                let unpacked = self.unpack(
+                    &download_path,
+                    kind,
+                    &bin_dir.join(archive::archive_stem(&filename)),
+                );
+                let _ = fs::remove_file(&download_path);
+                unpacked?
+            }
+            None => download_path,
+        };
+
         // Make executable on Unix-like systems
         #[cfg(unix)]
         {
@@ -221,13 +303,91 @@ impl BinaryServer {
             fs::set_permissions(&binary_path, perms)?;
         }
 
+        audit_log::record_file_write(&binary_path, before);
+        if let Err(e) = self.record_receipt(&bin_dir, &binary_path) {
+            tracing::warn!("Failed to record where {} came from: {}", filename, e);
+        }
         self.binary_path = Some(binary_path.clone());
         println!("✅ Binary installed to: {}", binary_path.display());
 
         Ok(binary_path)
     }
 
-    fn get_bin_directory(&self) -> Result<PathBuf> {
+    /// Unpack a downloaded archive into `dest`, replacing an earlier version, and
+    /// return the server executable inside
+    fn unpack(&self, archive_path: &Path, kind: ArchiveKind, dest: &Path) -> Result<PathBuf> {
+        if dest.exists() {
+            fs::remove_dir_all(dest)
+                .with_context(|| format!("Failed to remove {}", dest.display()))?;
+        }
+        let files = archive::extract(archive_path, kind, dest)?;
+        let stem = dest
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let executable = archive::find_executable(&files, &[&self.metadata.name, &stem])?;
+        println!(
+            "  {} Unpacked {} file(s), using {}",
+            "ℹ".blue(),
+            files.len(),
+            executable
+                .strip_prefix(dest)
+                .unwrap_or(&executable)
+                .display()
+        );
+        Ok(executable)
+    }
+
+    /// The URL the binary is downloaded from
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The repository and release tag of a GitHub release asset URL like
+    /// `https://github.com/owner/repo/releases/download/v1.0/server-linux`
+    pub fn github_release_asset(url: &str) -> Option<(String, String)> {
+        let path = url.strip_prefix("https://github.com/")?;
+        match path.split('/').collect::<Vec<_>>().as_slice() {
+            [owner, name, "releases", "download", tag, _asset, ..] => {
+                Some((format!("{owner}/{name}"), tag.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Remember that the top-level entry of `bin_dir` holding `binary_path` was
+    /// downloaded from this server's URL, for [`Self::installed_from`]
+    fn record_receipt(&self, bin_dir: &Path, binary_path: &Path) -> Result<()> {
+        let Some(entry) = Self::bin_entry(bin_dir, binary_path) else {
+            return Ok(());
+        };
+        let path = bin_dir.join(RECEIPTS_FILE);
+        let mut receipts: BTreeMap<String, String> = read_state(&path, BTreeMap::new)?;
+        receipts.insert(entry, self.url.clone());
+        write_state(&path, &receipts)
+    }
+
+    /// The URL the installed binary at `path` was downloaded from, if mcp-helper
+    /// downloaded it
+    pub fn installed_from(path: &Path) -> Option<String> {
+        let bin_dir = Self::bin_directory().ok()?;
+        let entry = Self::bin_entry(&bin_dir, path)?;
+        let receipts: BTreeMap<String, String> =
+            read_state(&bin_dir.join(RECEIPTS_FILE), BTreeMap::new).ok()?;
+        receipts.get(&entry).cloned()
+    }
+
+    /// Name of the file or archive directory directly in `bin_dir` that `path` is in
+    fn bin_entry(bin_dir: &Path, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(bin_dir).ok()?;
+        relative
+            .components()
+            .next()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+    }
+
+    /// Where downloaded binaries are installed (`~/.mcp/bin`)
+    pub fn bin_directory() -> Result<PathBuf> {
         let home = directories::BaseDirs::new()
             .context("Could not determine home directory")?
             .home_dir()
@@ -241,8 +401,12 @@ impl BinaryServer {
         output_path: &Path,
         cache_manager: Option<&CacheManager>,
     ) -> Result<()> {
-        let client = Client::new();
-        let response = client
+        let client = Client::builder()
+            .connect_timeout(Duration::from_secs(30))
+            .user_agent("mcp-helper")
+            .build()
+            .context("Failed to create HTTP client")?;
+        let mut response = client
             .get(&self.url)
             .send()
             .context("Failed to start download")?;
@@ -251,6 +415,9 @@ impl BinaryServer {
             anyhow::bail!("Download failed with status: {}", response.status());
         }
 
+        if let Some(dir) = output_path.parent() {
+            disk_space::ensure_space(dir, response.content_length(), &self.url)?;
+        }
         let total_size = response.content_length().unwrap_or(0);
 
         let pb = ProgressBar::new(total_size);
@@ -259,15 +426,29 @@ impl BinaryServer {
             .unwrap()
             .progress_chars("#>-"));
 
-        let mut file = fs::File::create(output_path)
-            .with_context(|| format!("Failed to create file: {}", output_path.display()))?;
-
-        let content = response.bytes().context("Failed to read response body")?;
-
-        file.write_all(&content)
-            .context("Failed to write binary data")?;
+        let mut file = antivirus::retry_if_locked(|| fs::File::create(output_path))
+            .map_err(|e| antivirus::explain(e, "Failed to create file:", output_path))?;
+
+        // Don't leave a truncated binary behind if the download or write fails
+        let mut buf = vec![0; CHUNK_SIZE];
+        let written = loop {
+            let read = match response.read(&mut buf) {
+                Ok(0) => break Ok(()),
+                Ok(read) => read,
+                Err(e) => break Err(anyhow::Error::new(e).context("Failed to read response body")),
+            };
+            if let Err(e) = file.write_all(&buf[..read]) {
+                break Err(anyhow::Error::new(e).context("Failed to write binary data"));
+            }
+            pb.inc(read as u64);
+        };
+        if let Err(e) = written {
+            pb.abandon();
+            drop(file);
+            let _ = fs::remove_file(output_path);
+            return Err(e);
+        }
 
-        pb.set_position(content.len() as u64);
         pb.finish_with_message("Download complete");
 
         // Cache the download if cache manager is available
@@ -289,8 +470,9 @@ impl BinaryServer {
     }
 
     fn verify_checksum(&self, binary_path: &Path, expected: &str) -> Result<()> {
-        let contents = fs::read(binary_path)
-            .with_context(|| format!("Failed to read binary: {}", binary_path.display()))?;
+        // Scanners hold freshly downloaded executables open for a moment
+        let contents = antivirus::retry_if_locked(|| fs::read(binary_path))
+            .map_err(|e| antivirus::explain(e, "Failed to read binary:", binary_path))?;
 
         let mut hasher = Sha256::new();
         hasher.update(&contents);
@@ -318,6 +500,11 @@ impl McpServer for BinaryServer {
     fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
         use super::validation::ConfigValidation;
 
+        ConfigValidation::validate_fields(
+            config,
+            &self.metadata.required_config,
+            &self.metadata.optional_config,
+        )?;
         ConfigValidation::validate_working_directory(config)?;
         ConfigValidation::validate_timeout(config)?;
 
@@ -430,6 +617,141 @@ mod tests {
         assert!(!selected.name.is_empty());
     }
 
+    #[test]
+    fn test_select_platform_asset_skips_checksums_and_other_platforms() {
+        let asset = |name: &str| GitHubAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{name}"),
+            size: 1000,
+        };
+        let assets = vec![
+            asset("checksums.txt"),
+            asset("server-x86_64-apple-darwin.tar.gz.sha256"),
+            asset("server-x86_64-apple-darwin.tar.gz"),
+            asset("server-aarch64-apple-darwin.tar.gz"),
+            asset("server-x86_64-unknown-linux-gnu.tar.gz"),
+            asset("server-aarch64-unknown-linux-gnu.tar.gz"),
+            asset("server-x86_64-pc-windows-msvc.zip"),
+            asset("server-aarch64-pc-windows-msvc.zip"),
+        ];
+
+        let selected = BinaryServer::select_platform_asset(&assets).unwrap();
+        assert!(!BinaryServer::is_auxiliary_asset(&selected.name));
+        let name = selected.name.as_str();
+        match std::env::consts::OS {
+            "linux" => assert!(name.contains("linux")),
+            "macos" => assert!(name.contains("darwin")),
+            "windows" => assert!(name.contains("windows")),
+            _ => {}
+        }
+        if std::env::consts::ARCH == "x86_64" {
+            assert!(name.starts_with("server-x86_64"));
+        }
+    }
+
+    #[test]
+    fn test_github_release_spec() {
+        let repo = Some(("owner/server".to_string(), None));
+        assert_eq!(
+            BinaryServer::github_release_spec("https://github.com/owner/server"),
+            repo
+        );
+        assert_eq!(
+            BinaryServer::github_release_spec("https://github.com/owner/server/releases/latest/"),
+            repo
+        );
+        assert_eq!(
+            BinaryServer::github_release_spec("https://github.com/owner/server/releases/tag/v1.2"),
+            Some(("owner/server".to_string(), Some("v1.2".to_string())))
+        );
+        // Direct downloads are used as they are
+        assert_eq!(
+            BinaryServer::github_release_spec(
+                "https://github.com/owner/server/releases/download/v1.2/server-linux"
+            ),
+            None
+        );
+        assert_eq!(
+            BinaryServer::github_release_spec("https://example.com/server"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_install_target_strips_archive_extension() {
+        let bin_dir = BinaryServer::bin_directory().unwrap();
+        let archive = BinaryServer::new("https://example.com/v1/server-linux.tar.gz?x=1", None);
+        assert_eq!(
+            archive.install_target().unwrap(),
+            bin_dir.join("server-linux")
+        );
+        let binary = BinaryServer::new("https://example.com/v1/server-linux", None);
+        assert_eq!(
+            binary.install_target().unwrap(),
+            bin_dir.join("server-linux")
+        );
+    }
+
+    #[test]
+    fn test_unpack_finds_server_executable() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let archive_path = temp.path().join("server-linux.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&archive_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for name in ["LICENSE", "README.md", "server.exe"] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(b"contents").unwrap();
+        }
+        writer.finish().unwrap();
+
+        let server = BinaryServer::new("https://example.com/server.zip", None);
+        let dest = temp.path().join("server-linux");
+        // A leftover from an earlier version is replaced
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(dest.join("stale"), "old").unwrap();
+
+        let executable = server
+            .unpack(&archive_path, ArchiveKind::Zip, &dest)
+            .unwrap();
+        assert_eq!(executable, dest.join("server.exe"));
+        assert!(!dest.join("stale").exists());
+    }
+
+    #[test]
+    fn test_github_release_asset() {
+        assert_eq!(
+            BinaryServer::github_release_asset(
+                "https://github.com/owner/server/releases/download/v1.2.0/server-linux.tar.gz"
+            ),
+            Some(("owner/server".to_string(), "v1.2.0".to_string()))
+        );
+        assert_eq!(
+            BinaryServer::github_release_asset("https://github.com/owner/server/releases"),
+            None
+        );
+        assert_eq!(
+            BinaryServer::github_release_asset("https://example.com/v1.2.0/server"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_bin_entry() {
+        let bin_dir = Path::new("/home/me/.mcp/bin");
+        assert_eq!(
+            BinaryServer::bin_entry(bin_dir, &bin_dir.join("server-linux").join("server")),
+            Some("server-linux".to_string())
+        );
+        assert_eq!(
+            BinaryServer::bin_entry(bin_dir, &bin_dir.join("server")),
+            Some("server".to_string())
+        );
+        assert_eq!(
+            BinaryServer::bin_entry(bin_dir, Path::new("/usr/local/bin/server")),
+            None
+        );
+    }
+
     #[test]
     fn test_validate_config_timeout() {
         let server = BinaryServer::new("https://example.com/server", None);
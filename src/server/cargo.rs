@@ -0,0 +1,245 @@
+use crate::deps::{DependencyChecker, RustChecker};
+use crate::server::{ConfigField, McpServer, ServerMetadata, ServerType};
+use crate::utils::process::{Process, INSTALL_TIMEOUT};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// MCP server distributed as a Rust crate and installed with `cargo install`.
+///
+/// Crates are installed into a directory managed by MCP Helper
+/// (`~/.mcp/cargo`) rather than the user's global `~/.cargo/bin`, so the
+/// generated command always points at the exact binary that was installed.
+#[derive(Debug)]
+pub struct CargoServer {
+    metadata: ServerMetadata,
+    crate_name: String,
+    version: Option<String>,
+    binary_name: String,
+    install_root: Option<PathBuf>,
+}
+
+impl CargoServer {
+    /// Create a server from a spec such as `mcp-server-foo` or `mcp-server-foo@0.2.1`.
+    pub fn new(crate_spec: &str) -> Self {
+        let (crate_name, version) = Self::parse_crate_spec(crate_spec);
+        Self::from_crate(crate_name, version)
+    }
+
+    pub fn from_crate(crate_name: String, version: Option<String>) -> Self {
+        let metadata = ServerMetadata {
+            name: crate_name.clone(),
+            description: Some(format!("Rust MCP server: {crate_name}")),
+            server_type: ServerType::Cargo {
+                crate_name: crate_name.clone(),
+                version: version.clone(),
+            },
+            required_config: vec![],
+            optional_config: vec![ConfigField::working_directory(), ConfigField::timeout()],
+            args_template: vec![],
+        };
+
+        Self {
+            metadata,
+            binary_name: crate_name.clone(),
+            crate_name,
+            version,
+            install_root: None,
+        }
+    }
+
+    /// Use a binary name that differs from the crate name.
+    pub fn with_binary_name(mut self, binary_name: impl Into<String>) -> Self {
+        self.binary_name = binary_name.into();
+        self
+    }
+
+    /// Install into a custom root instead of the managed directory.
+    pub fn with_install_root(mut self, install_root: PathBuf) -> Self {
+        self.install_root = Some(install_root);
+        self
+    }
+
+    pub fn parse_crate_spec(crate_spec: &str) -> (String, Option<String>) {
+        match crate_spec.split_once('@') {
+            Some((name, version)) if !version.is_empty() => {
+                (name.to_string(), Some(version.to_string()))
+            }
+            Some((name, _)) => (name.to_string(), None),
+            None => (crate_spec.to_string(), None),
+        }
+    }
+
+    /// Root directory passed to `cargo install --root`
+    pub fn install_root(&self) -> Result<PathBuf> {
+        if let Some(root) = &self.install_root {
+            return Ok(root.clone());
+        }
+
+        let home = directories::BaseDirs::new()
+            .context("Could not determine home directory")?
+            .home_dir()
+            .to_path_buf();
+
+        Ok(home.join(".mcp").join("cargo"))
+    }
+
+    /// Path of the installed server binary
+    pub fn binary_path(&self) -> Result<PathBuf> {
+        let filename = if cfg!(windows) {
+            format!("{}.exe", self.binary_name)
+        } else {
+            self.binary_name.clone()
+        };
+
+        Ok(self.install_root()?.join("bin").join(filename))
+    }
+
+    /// Arguments for `cargo install`
+    pub fn install_args(&self) -> Result<Vec<String>> {
+        let mut args = vec![
+            "install".to_string(),
+            "--locked".to_string(),
+            "--root".to_string(),
+            self.install_root()?.to_string_lossy().to_string(),
+        ];
+
+        if let Some(version) = &self.version {
+            args.push("--version".to_string());
+            args.push(version.clone());
+        }
+
+        if self.binary_name != self.crate_name {
+            args.push("--bin".to_string());
+            args.push(self.binary_name.clone());
+        }
+
+        args.push(self.crate_name.clone());
+        Ok(args)
+    }
+
+    /// Build and install the crate with `cargo install`.
+    pub fn install(&self) -> Result<PathBuf> {
+        let args = self.install_args()?;
+        println!(
+            "{} Building {} with cargo (this may take a few minutes)...",
+            "→".green(),
+            self.crate_name.cyan()
+        );
+
+        let status = Process::new("cargo")
+            .args(&args)
+            .timeout(INSTALL_TIMEOUT)
+            .status()
+            .context("Failed to run cargo. Is the Rust toolchain installed?")?;
+
+        if !status.success() {
+            anyhow::bail!("cargo install {} failed with {}", self.crate_name, status);
+        }
+
+        let binary_path = self.binary_path()?;
+        if !binary_path.exists() {
+            anyhow::bail!(
+                "cargo install succeeded but {} was not found. \
+                 If the crate's binary has a different name, specify it explicitly.",
+                binary_path.display()
+            );
+        }
+
+        println!("✅ Crate installed to: {}", binary_path.display());
+        Ok(binary_path)
+    }
+}
+
+impl McpServer for CargoServer {
+    fn metadata(&self) -> &ServerMetadata {
+        &self.metadata
+    }
+
+    fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
+        use super::validation::ConfigValidation;
+
+        ConfigValidation::validate_fields(
+            config,
+            &self.metadata.required_config,
+            &self.metadata.optional_config,
+        )?;
+        ConfigValidation::validate_working_directory(config)?;
+        ConfigValidation::validate_timeout(config)?;
+
+        Ok(())
+    }
+
+    fn generate_command(&self) -> Result<(String, Vec<String>)> {
+        let binary_path = self.binary_path()?;
+        Ok((binary_path.to_string_lossy().to_string(), vec![]))
+    }
+
+    fn dependency(&self) -> Box<dyn DependencyChecker> {
+        Box::new(RustChecker::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deps::Dependency;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_crate_spec() {
+        assert_eq!(
+            CargoServer::parse_crate_spec("mcp-server-foo"),
+            ("mcp-server-foo".to_string(), None)
+        );
+        assert_eq!(
+            CargoServer::parse_crate_spec("mcp-server-foo@0.2.1"),
+            ("mcp-server-foo".to_string(), Some("0.2.1".to_string()))
+        );
+        assert_eq!(
+            CargoServer::parse_crate_spec("mcp-server-foo@"),
+            ("mcp-server-foo".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_cargo_server_metadata() {
+        let server = CargoServer::new("mcp-server-foo@1.0.0");
+        assert_eq!(server.metadata().name, "mcp-server-foo");
+        assert_eq!(
+            server.metadata().server_type,
+            ServerType::Cargo {
+                crate_name: "mcp-server-foo".to_string(),
+                version: Some("1.0.0".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_install_args_and_binary_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let server = CargoServer::new("mcp-server-foo@1.0.0")
+            .with_binary_name("foo-server")
+            .with_install_root(temp_dir.path().to_path_buf());
+
+        let args = server.install_args().unwrap();
+        assert_eq!(args[0], "install");
+        assert!(args.windows(2).any(|w| w == ["--version", "1.0.0"]));
+        assert!(args.windows(2).any(|w| w == ["--bin", "foo-server"]));
+        assert_eq!(args.last().unwrap(), "mcp-server-foo");
+
+        let (command, args) = server.generate_command().unwrap();
+        assert!(command.starts_with(temp_dir.path().join("bin").to_str().unwrap()));
+        assert!(command.contains("foo-server"));
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn test_cargo_server_dependency() {
+        let server = CargoServer::new("mcp-server-foo");
+        if let Ok(check) = server.dependency().check() {
+            assert_eq!(check.dependency, Dependency::Rust { min_version: None });
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use crate::deps::{DenoChecker, DependencyChecker};
+use crate::server::{is_path_spec, ConfigField, McpServer, ServerMetadata, ServerType};
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// Config fields that map to Deno permission flags
+const PERMISSION_FIELDS: &[(&str, &str, &str)] = &[
+    ("allow_read", "--allow-read", "Paths the server may read"),
+    ("allow_write", "--allow-write", "Paths the server may write"),
+    (
+        "allow_net",
+        "--allow-net",
+        "Hosts the server may connect to",
+    ),
+    (
+        "allow_env",
+        "--allow-env",
+        "Environment variables the server may read",
+    ),
+];
+
+/// File extensions Deno runs directly
+const SCRIPT_EXTENSIONS: &[&str] = &[".ts", ".tsx", ".mts"];
+
+/// MCP server shipped as a Deno script or jsr package and run with `deno run`.
+///
+/// Deno denies file, network and environment access unless granted, so the
+/// generated command only passes the permissions listed in the server's config.
+#[derive(Debug)]
+pub struct DenoServer {
+    metadata: ServerMetadata,
+    specifier: String,
+}
+
+impl DenoServer {
+    /// Create a server from a specifier such as `jsr:@scope/server` or `./server.ts`.
+    pub fn new(specifier: &str) -> Self {
+        let name = Self::server_name(specifier);
+        let optional_config = PERMISSION_FIELDS
+            .iter()
+            .map(|(name, _, description)| {
+                ConfigField::string(name)
+                    .describe(format!("{description}, comma-separated (* for any)"))
+            })
+            .collect();
+
+        let metadata = ServerMetadata {
+            name: name.clone(),
+            description: Some(format!("Deno MCP server: {name}")),
+            server_type: ServerType::Deno {
+                specifier: specifier.to_string(),
+            },
+            required_config: vec![],
+            optional_config,
+            args_template: vec![],
+        };
+
+        Self {
+            metadata,
+            specifier: specifier.to_string(),
+        }
+    }
+
+    /// The script or package Deno runs
+    pub fn specifier(&self) -> &str {
+        &self.specifier
+    }
+
+    /// Short name for a specifier: the package or file name without version or extension
+    pub fn server_name(specifier: &str) -> String {
+        let path = specifier
+            .strip_prefix("jsr:")
+            .or_else(|| specifier.strip_prefix("npm:"))
+            .unwrap_or(specifier);
+        let path = path.split(['?', '#']).next().unwrap_or(path);
+        let last = path
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or(path);
+        let last = last
+            .split('@')
+            .find(|part| !part.is_empty())
+            .unwrap_or(last);
+        let name = SCRIPT_EXTENSIONS
+            .iter()
+            .find_map(|ext| last.strip_suffix(ext))
+            .unwrap_or(last);
+        name.to_string()
+    }
+
+    /// Whether `arg` is a `deno run` permission flag rather than a server argument
+    pub fn is_permission_flag(arg: &str) -> bool {
+        arg == "-A" || arg.starts_with("--allow-") || arg.starts_with("--deny-")
+    }
+
+    /// Permission flags granted by `config`.
+    ///
+    /// `allow_read=/data,/tmp` becomes `--allow-read=/data,/tmp` and `*` grants
+    /// the permission without restriction.
+    pub fn permission_flags(config: &HashMap<String, String>) -> Vec<String> {
+        PERMISSION_FIELDS
+            .iter()
+            .filter_map(|(field, flag, _)| {
+                let entries = Self::entries(config.get(*field)?);
+                if entries.is_empty() {
+                    None
+                } else if entries.contains(&"*") {
+                    Some(flag.to_string())
+                } else {
+                    Some(format!("{flag}={}", entries.join(",")))
+                }
+            })
+            .collect()
+    }
+
+    fn entries(value: &str) -> Vec<&str> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .collect()
+    }
+}
+
+/// The Deno specifier in a server spec, if it names a Deno server.
+///
+/// Matches `deno:<specifier>`, `jsr:` packages and TypeScript files given as a path
+/// or URL. A bare `name.ts` could be an npm package, so it is left to npm.
+pub fn deno_specifier(spec: &str) -> Option<&str> {
+    if let Some(specifier) = spec.strip_prefix("deno:") {
+        return (!specifier.is_empty()).then_some(specifier);
+    }
+    if spec.starts_with("jsr:") {
+        return Some(spec);
+    }
+    let is_remote = spec.starts_with("https://") || spec.starts_with("http://");
+    if !is_remote && !is_path_spec(spec) {
+        return None;
+    }
+    let path = spec.split(['?', '#']).next().unwrap_or(spec);
+    SCRIPT_EXTENSIONS
+        .iter()
+        .any(|ext| path.ends_with(ext))
+        .then_some(spec)
+}
+
+impl McpServer for DenoServer {
+    fn metadata(&self) -> &ServerMetadata {
+        &self.metadata
+    }
+
+    fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
+        for (field, _, _) in PERMISSION_FIELDS {
+            if let Some(value) = config.get(*field).filter(|v| !v.trim().is_empty()) {
+                if value.split(',').any(|entry| entry.trim().is_empty()) {
+                    anyhow::bail!("{field} has an empty entry: '{value}'");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_command(&self) -> Result<(String, Vec<String>)> {
+        self.generate_command_with_config(&HashMap::new())
+    }
+
+    /// `deno run` with the permissions granted by `config`
+    fn generate_command_with_config(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<(String, Vec<String>)> {
+        let mut args = vec!["run".to_string(), "--no-prompt".to_string()];
+        args.extend(Self::permission_flags(config));
+        args.push(self.specifier.clone());
+        Ok(("deno".to_string(), args))
+    }
+
+    fn command_config_fields(&self) -> Vec<String> {
+        PERMISSION_FIELDS
+            .iter()
+            .map(|(name, _, _)| name.to_string())
+            .collect()
+    }
+
+    fn dependency(&self) -> Box<dyn DependencyChecker> {
+        Box::new(DenoChecker::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_deno_specifier() {
+        assert_eq!(deno_specifier("jsr:@std/server"), Some("jsr:@std/server"));
+        assert_eq!(
+            deno_specifier("deno:npm:mcp-server"),
+            Some("npm:mcp-server")
+        );
+        assert_eq!(deno_specifier("./server.ts"), Some("./server.ts"));
+        assert_eq!(
+            deno_specifier("https://example.com/mod.ts?v=1"),
+            Some("https://example.com/mod.ts?v=1")
+        );
+        assert_eq!(deno_specifier("deno:"), None);
+        assert_eq!(deno_specifier(".ts"), None);
+        assert_eq!(deno_specifier("server.ts"), None);
+        assert_eq!(
+            deno_specifier("C:\\mcp\\server.mts"),
+            Some("C:\\mcp\\server.mts")
+        );
+        assert_eq!(deno_specifier("server.py"), None);
+        assert_eq!(deno_specifier("@scope/package"), None);
+    }
+
+    #[test]
+    fn test_server_name() {
+        assert_eq!(DenoServer::server_name("jsr:@scope/notes@1.2.0"), "notes");
+        assert_eq!(DenoServer::server_name("jsr:@scope/notes"), "notes");
+        assert_eq!(DenoServer::server_name("./servers/weather.ts"), "weather");
+        assert_eq!(
+            DenoServer::server_name("https://deno.land/x/mcp@v1/main.ts?x=1"),
+            "main"
+        );
+    }
+
+    #[test]
+    fn test_permission_flags() {
+        let flags = DenoServer::permission_flags(&config(&[
+            ("allow_read", "/data, /tmp"),
+            ("allow_net", "*"),
+            ("allow_env", ""),
+        ]));
+        assert_eq!(flags, vec!["--allow-read=/data,/tmp", "--allow-net"]);
+    }
+
+    #[test]
+    fn test_generate_command_with_config() {
+        let server = DenoServer::new("jsr:@scope/notes");
+        let (command, args) = server
+            .generate_command_with_config(&config(&[("allow_write", "./notes")]))
+            .unwrap();
+        assert_eq!(command, "deno");
+        assert_eq!(
+            args,
+            vec![
+                "run",
+                "--no-prompt",
+                "--allow-write=./notes",
+                "jsr:@scope/notes"
+            ]
+        );
+
+        let (_, args) = server.generate_command().unwrap();
+        assert_eq!(args, vec!["run", "--no-prompt", "jsr:@scope/notes"]);
+    }
+
+    #[test]
+    fn test_validate_config_rejects_empty_entries() {
+        let server = DenoServer::new("./server.ts");
+        assert!(server
+            .validate_config(&config(&[("allow_read", "/data")]))
+            .is_ok());
+        assert!(server
+            .validate_config(&config(&[("allow_read", "/data,,/tmp")]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_is_permission_flag() {
+        assert!(DenoServer::is_permission_flag(
+            "--allow-net=api.example.com"
+        ));
+        assert!(DenoServer::is_permission_flag("-A"));
+        assert!(DenoServer::is_permission_flag("--deny-env"));
+        assert!(!DenoServer::is_permission_flag("--port"));
+    }
+}
@@ -1,7 +1,14 @@
 use crate::deps::{DependencyChecker, DockerChecker};
-use crate::server::{ConfigField, ConfigFieldType, McpServer, ServerMetadata, ServerType};
+use crate::server::docker_requirements::{docker_hub_repository, image_repository};
+use crate::server::validation::ConfigValidation;
+use crate::server::{ConfigField, McpServer, ServerMetadata, ServerType};
+use crate::utils::disk_space;
+use crate::utils::paths::program_name;
+use crate::utils::process::{Process, INSTALL_TIMEOUT};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Debug)]
 pub struct DockerServer {
@@ -25,76 +32,23 @@ impl DockerServer {
             },
             required_config: vec![],
             optional_config: vec![
-                ConfigField {
-                    name: "volumes".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some(
-                        "Volume mounts (host:container format, comma-separated)".to_string(),
-                    ),
-                    default: None,
-                },
-                ConfigField {
-                    name: "environment".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some(
-                        "Environment variables (KEY=value format, comma-separated)".to_string(),
-                    ),
-                    default: None,
-                },
-                ConfigField {
-                    name: "ports".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some(
-                        "Port mappings (host:container format, comma-separated)".to_string(),
-                    ),
-                    default: None,
-                },
-                ConfigField {
-                    name: "network".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some("Docker network to use".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "entrypoint".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some("Custom entrypoint command".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "working_dir".to_string(),
-                    field_type: ConfigFieldType::Path,
-                    description: Some("Working directory inside container".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "user".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some("User to run as (uid:gid or username)".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "restart_policy".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some(
-                        "Container restart policy (no, always, unless-stopped, on-failure)"
-                            .to_string(),
-                    ),
-                    default: Some("unless-stopped".to_string()),
-                },
-                ConfigField {
-                    name: "memory_limit".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some("Memory limit (e.g., 512m, 1g)".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "cpu_limit".to_string(),
-                    field_type: ConfigFieldType::String,
-                    description: Some("CPU limit (e.g., 0.5, 2)".to_string()),
-                    default: None,
-                },
+                ConfigField::string("volumes")
+                    .describe("Volume mounts (host:container format, comma-separated)"),
+                ConfigField::string("environment")
+                    .describe("Environment variables (KEY=value format, comma-separated)"),
+                ConfigField::string("ports")
+                    .describe("Port mappings (host:container format, comma-separated)"),
+                ConfigField::string("network").describe("Docker network to use"),
+                ConfigField::string("entrypoint").describe("Custom entrypoint command"),
+                ConfigField::path("working_dir").describe("Working directory inside container"),
+                ConfigField::string("user").describe("User to run as (uid:gid or username)"),
+                ConfigField::string("restart_policy")
+                    .describe("Container restart policy (no, always, unless-stopped, on-failure)")
+                    .default_value("unless-stopped"),
+                ConfigField::string("memory_limit").describe("Memory limit (e.g., 512m, 1g)"),
+                ConfigField::number("cpu_limit").describe("CPU limit (e.g., 0.5, 2)"),
             ],
+            args_template: vec![],
         };
 
         Ok(Self {
@@ -195,15 +149,17 @@ impl DockerServer {
             self.image.clone()
         };
 
+        ensure_image_space(&full_image)?;
         println!("🐳 Pulling Docker image: {full_image}");
 
-        let output = std::process::Command::new("docker")
+        let output = Process::new("docker")
             .args(["pull", &full_image])
+            .timeout(INSTALL_TIMEOUT)
             .output()
             .context("Failed to execute docker pull command")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr_lossy();
             anyhow::bail!("Failed to pull Docker image {}: {}", full_image, error_msg);
         }
 
@@ -218,12 +174,12 @@ impl DockerServer {
             self.image.clone()
         };
 
-        let output = std::process::Command::new("docker")
+        let output = Process::new("docker")
             .args(["image", "inspect", &full_image])
             .output()
             .context("Failed to execute docker image inspect command")?;
 
-        Ok(output.status.success())
+        Ok(output.success())
     }
 }
 
@@ -233,11 +189,15 @@ impl McpServer for DockerServer {
     }
 
     fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
+        ConfigValidation::validate_fields(
+            config,
+            &self.metadata.required_config,
+            &self.metadata.optional_config,
+        )?;
         self.validate_volumes(config)?;
         self.validate_environment_variables(config)?;
         self.validate_ports(config)?;
         self.validate_restart_policy(config)?;
-        self.validate_numeric_values(config)?;
         Ok(())
     }
 
@@ -246,81 +206,7 @@ impl McpServer for DockerServer {
         self.generate_command_with_config(&config)
     }
 
-    fn dependency(&self) -> Box<dyn DependencyChecker> {
-        Box::new(DockerChecker::new())
-    }
-}
-
-impl DockerServer {
-    fn validate_volumes(&self, config: &HashMap<String, String>) -> Result<()> {
-        if let Some(volumes) = config.get("volumes") {
-            for volume in volumes.split(',') {
-                let volume = volume.trim();
-                if !volume.is_empty() && !volume.contains(':') {
-                    anyhow::bail!(
-                        "Invalid volume format '{}'. Expected 'host:container' format",
-                        volume
-                    );
-                }
-            }
-        }
-        Ok(())
-    }
-
-    fn validate_environment_variables(&self, config: &HashMap<String, String>) -> Result<()> {
-        if let Some(env_vars) = config.get("environment") {
-            for env_var in env_vars.split(',') {
-                let env_var = env_var.trim();
-                if !env_var.is_empty() && !env_var.contains('=') {
-                    anyhow::bail!(
-                        "Invalid environment variable format '{}'. Expected 'KEY=value' format",
-                        env_var
-                    );
-                }
-            }
-        }
-        Ok(())
-    }
-
-    fn validate_ports(&self, config: &HashMap<String, String>) -> Result<()> {
-        if let Some(ports) = config.get("ports") {
-            for port in ports.split(',') {
-                let port = port.trim();
-                if !port.is_empty() && !port.contains(':') {
-                    anyhow::bail!(
-                        "Invalid port format '{}'. Expected 'host:container' format",
-                        port
-                    );
-                }
-            }
-        }
-        Ok(())
-    }
-
-    fn validate_restart_policy(&self, config: &HashMap<String, String>) -> Result<()> {
-        if let Some(restart_policy) = config.get("restart_policy") {
-            let valid_policies = ["no", "always", "unless-stopped", "on-failure"];
-            if !valid_policies.contains(&restart_policy.as_str()) {
-                anyhow::bail!(
-                    "Invalid restart policy '{}'. Valid options: {}",
-                    restart_policy,
-                    valid_policies.join(", ")
-                );
-            }
-        }
-        Ok(())
-    }
-
-    fn validate_numeric_values(&self, config: &HashMap<String, String>) -> Result<()> {
-        if let Some(cpu_limit) = config.get("cpu_limit") {
-            cpu_limit
-                .parse::<f64>()
-                .with_context(|| format!("Invalid CPU limit '{cpu_limit}'. Expected a number"))?;
-        }
-        Ok(())
-    }
-
-    pub fn generate_command_with_config(
+    fn generate_command_with_config(
         &self,
         config: &HashMap<String, String>,
     ) -> Result<(String, Vec<String>)> {
@@ -402,12 +288,241 @@ impl DockerServer {
 
         Ok(("docker".to_string(), args))
     }
+
+    fn command_config_fields(&self) -> Vec<String> {
+        self.metadata
+            .optional_config
+            .iter()
+            .map(|field| field.name.clone())
+            .collect()
+    }
+
+    fn dependency(&self) -> Box<dyn DependencyChecker> {
+        Box::new(DockerChecker::new())
+    }
+}
+
+impl DockerServer {
+    fn validate_volumes(&self, config: &HashMap<String, String>) -> Result<()> {
+        if let Some(volumes) = config.get("volumes") {
+            for volume in volumes.split(',') {
+                let volume = volume.trim();
+                if !volume.is_empty() && !volume.contains(':') {
+                    anyhow::bail!(
+                        "Invalid volume format '{}'. Expected 'host:container' format",
+                        volume
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_environment_variables(&self, config: &HashMap<String, String>) -> Result<()> {
+        if let Some(env_vars) = config.get("environment") {
+            for env_var in env_vars.split(',') {
+                let env_var = env_var.trim();
+                if !env_var.is_empty() && !env_var.contains('=') {
+                    anyhow::bail!(
+                        "Invalid environment variable format '{}'. Expected 'KEY=value' format",
+                        env_var
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_ports(&self, config: &HashMap<String, String>) -> Result<()> {
+        if let Some(ports) = config.get("ports") {
+            for port in ports.split(',') {
+                let port = port.trim();
+                if !port.is_empty() && !port.contains(':') {
+                    anyhow::bail!(
+                        "Invalid port format '{}'. Expected 'host:container' format",
+                        port
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_restart_policy(&self, config: &HashMap<String, String>) -> Result<()> {
+        if let Some(restart_policy) = config.get("restart_policy") {
+            let valid_policies = ["no", "always", "unless-stopped", "on-failure"];
+            if !valid_policies.contains(&restart_policy.as_str()) {
+                anyhow::bail!(
+                    "Invalid restart policy '{}'. Valid options: {}",
+                    restart_policy,
+                    valid_policies.join(", ")
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `command` runs Docker or Podman, whatever its case, directory or
+/// separators, so `C:\Program Files\Docker\docker.exe` counts too
+pub fn is_docker_command(command: &str) -> bool {
+    matches!(program_name(command).as_str(), "docker" | "podman")
+}
+
+/// Unpacked images take roughly this many times their compressed size
+const UNPACKED_SIZE_FACTOR: u64 = 2;
+
+/// Where Docker stores images, if that is a directory on this machine. Docker
+/// Desktop keeps images inside a VM, so its root isn't checked.
+pub fn docker_root_dir() -> Option<PathBuf> {
+    let output = Process::new("docker")
+        .args(["info", "--format", "{{.DockerRootDir}}"])
+        .timeout(Duration::from_secs(10))
+        .output()
+        .ok()?;
+    let root = PathBuf::from(output.stdout_lossy().trim());
+    (output.success() && root.is_dir()).then_some(root)
+}
+
+/// Docker Hub's details of the tag `image` refers to. `None` for other registries
+/// or when Hub can't be reached.
+fn docker_hub_tag(image: &str) -> Option<serde_json::Value> {
+    let repository = docker_hub_repository(image)?;
+    let tag = image_tag(image).unwrap_or("latest");
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("mcp-helper")
+        .build()
+        .ok()?;
+    let response = client
+        .get(format!(
+            "https://hub.docker.com/v2/repositories/{repository}/tags/{tag}"
+        ))
+        .send()
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response.json().ok()
+}
+
+/// Expected disk use of `image` once pulled, from the compressed size Docker Hub
+/// reports. `None` for other registries or when Hub can't be reached.
+pub fn estimated_image_size(image: &str) -> Option<u64> {
+    let compressed = docker_hub_tag(image)?["full_size"].as_u64()?;
+    Some(compressed.saturating_mul(UNPACKED_SIZE_FACTOR))
+}
+
+/// The digest `image`'s tag points at on Docker Hub now. `None` for other
+/// registries or when Hub can't be reached.
+pub fn hub_tag_digest(image: &str) -> Option<String> {
+    docker_hub_tag(image)?["digest"]
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Pull `image` and return the digest of the manifest its tag resolved to
+pub fn resolve_digest(image: &str) -> Result<String> {
+    let output = Process::new("docker")
+        .args(["pull", image])
+        .timeout(INSTALL_TIMEOUT)
+        .output()
+        .context("Failed to execute docker pull command")?;
+    if !output.success() {
+        anyhow::bail!(
+            "Failed to pull Docker image {}: {}",
+            image,
+            output.stderr_lossy()
+        );
+    }
+
+    let output = Process::new("docker")
+        .args([
+            "image",
+            "inspect",
+            "--format",
+            "{{json .RepoDigests}}",
+            image,
+        ])
+        .timeout(Duration::from_secs(30))
+        .output()
+        .context("Failed to execute docker image inspect command")?;
+    if !output.success() {
+        anyhow::bail!(
+            "Failed to inspect Docker image {}: {}",
+            image,
+            output.stderr_lossy()
+        );
+    }
+    let repo_digests: Vec<String> = serde_json::from_str(output.stdout_lossy().trim())
+        .with_context(|| format!("Unexpected output from docker image inspect {image}"))?;
+    repo_digest(&repo_digests, image)
+        .with_context(|| format!("Docker has no registry digest for {image}"))
+}
+
+/// The digest for `image` among Docker's `RepoDigests`, which look like
+/// `mcp/postgres@sha256:...` and cover every repository the image was pulled from
+fn repo_digest(repo_digests: &[String], image: &str) -> Option<String> {
+    let repository = image_repository(image);
+    let digests: Vec<(&str, &str)> = repo_digests
+        .iter()
+        .filter_map(|entry| entry.split_once('@'))
+        .collect();
+    digests
+        .iter()
+        .find(|(repo, _)| {
+            *repo == repository || repo.strip_prefix("docker.io/") == Some(repository)
+        })
+        .or(digests.first())
+        .map(|(_, digest)| digest.to_string())
+}
+
+/// The digest in an image reference like `mcp/postgres:13@sha256:...`
+pub fn image_digest(image: &str) -> Option<&str> {
+    image.split_once('@').map(|(_, digest)| digest)
+}
+
+/// `image` pinned to `digest`. The tag stays in the reference, where Docker
+/// ignores it, so `mcp outdated` can tell when it moves to another digest.
+pub fn with_digest(image: &str, digest: &str) -> String {
+    let without_digest = image.split('@').next().unwrap_or(image);
+    format!("{without_digest}@{digest}")
+}
+
+/// The tag in an image reference like `mcp/postgres:13`, without any digest
+pub(crate) fn image_tag(image: &str) -> Option<&str> {
+    let without_digest = image.split('@').next().unwrap_or(image);
+    let colon = without_digest.rfind(':')?;
+    let tag = &without_digest[colon + 1..];
+    (!tag.contains('/')).then_some(tag)
+}
+
+/// Fail before pulling `image` if Docker's storage has no room for it
+pub fn ensure_image_space(image: &str) -> Result<()> {
+    let Some(root) = docker_root_dir() else {
+        return Ok(());
+    };
+    disk_space::ensure_space(
+        &root,
+        estimated_image_size(image),
+        &format!("Docker image {image}"),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_docker_command() {
+        assert!(is_docker_command("docker"));
+        assert!(is_docker_command("/usr/local/bin/podman"));
+        assert!(is_docker_command("Docker"));
+        assert!(is_docker_command(r"C:\Program Files\Docker\DOCKER.EXE"));
+        assert!(!is_docker_command("npx"));
+        assert!(!is_docker_command("/opt/docker/bin/node"));
+    }
+
     #[test]
     fn test_parse_docker_spec() {
         // Test with tag
@@ -582,4 +697,46 @@ mod tests {
         let port_index = port_index.unwrap();
         assert_eq!(args[port_index + 1], "8080:80");
     }
+
+    #[test]
+    fn test_image_tag() {
+        assert_eq!(image_tag("mcp/postgres:13"), Some("13"));
+        assert_eq!(image_tag("mcp/postgres"), None);
+        assert_eq!(image_tag("localhost:5000/mcp/postgres"), None);
+        assert_eq!(image_tag("mcp/postgres:13@sha256:abc"), Some("13"));
+    }
+
+    #[test]
+    fn test_digest_references() {
+        assert_eq!(
+            with_digest("mcp/postgres:13", "sha256:abc"),
+            "mcp/postgres:13@sha256:abc"
+        );
+        assert_eq!(
+            with_digest("mcp/postgres:13@sha256:old", "sha256:new"),
+            "mcp/postgres:13@sha256:new"
+        );
+        assert_eq!(
+            image_digest("mcp/postgres:13@sha256:abc"),
+            Some("sha256:abc")
+        );
+        assert_eq!(image_digest("mcp/postgres:13"), None);
+    }
+
+    #[test]
+    fn test_repo_digest() {
+        let repo_digests = vec![
+            "ghcr.io/acme/postgres@sha256:mirror".to_string(),
+            "mcp/postgres@sha256:hub".to_string(),
+        ];
+        assert_eq!(
+            repo_digest(&repo_digests, "mcp/postgres:13"),
+            Some("sha256:hub".to_string())
+        );
+        assert_eq!(
+            repo_digest(&repo_digests, "other/image"),
+            Some("sha256:mirror".to_string())
+        );
+        assert_eq!(repo_digest(&[], "mcp/postgres:13"), None);
+    }
 }
@@ -0,0 +1,537 @@
+//! Heuristics for Docker servers that need host networking or access to the Docker daemon.
+//!
+//! Such servers usually start fine and then fail silently: they cannot reach services on
+//! the host's localhost, or cannot talk to Docker because the socket is not mounted. The
+//! image's own configuration, its registry description and any compose file that uses the
+//! image tend to give these requirements away, so we look there before writing the config.
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::utils::process::Process;
+
+/// Path of the Docker daemon socket on Linux and macOS hosts
+pub const DOCKER_SOCKET: &str = "/var/run/docker.sock";
+
+/// Compose file names checked in the working directory
+const COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// A runtime requirement the container cannot work without
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DockerRequirement {
+    /// Shares the host network stack (`--network host`)
+    HostNetwork,
+    /// Talks to the host Docker daemon through its socket (Docker-outside-of-Docker)
+    DockerSocket,
+    /// Runs its own Docker daemon (Docker-in-Docker), which needs `--privileged`
+    Privileged,
+}
+
+impl DockerRequirement {
+    /// `docker run` flags that satisfy the requirement
+    pub fn flags(&self) -> Vec<String> {
+        match self {
+            Self::HostNetwork => vec!["--network".to_string(), "host".to_string()],
+            Self::DockerSocket => {
+                vec!["-v".to_string(), format!("{DOCKER_SOCKET}:{DOCKER_SOCKET}")]
+            }
+            Self::Privileged => vec!["--privileged".to_string()],
+        }
+    }
+
+    /// What the container can do once the flags are added
+    pub fn security_warning(&self) -> &'static str {
+        match self {
+            Self::HostNetwork => {
+                "Host networking lets the container reach every service listening on this machine, including ones bound to localhost"
+            }
+            Self::DockerSocket => {
+                "Mounting the Docker socket gives the container full control of Docker, which is equivalent to root access on this machine"
+            }
+            Self::Privileged => {
+                "Privileged containers can access all host devices and bypass container isolation"
+            }
+        }
+    }
+
+    /// Whether the flags must be confirmed explicitly before they are added
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(self, Self::DockerSocket | Self::Privileged)
+    }
+
+    /// Whether `docker run` arguments already satisfy the requirement
+    pub fn is_satisfied_by(&self, args: &[String]) -> bool {
+        match self {
+            Self::HostNetwork => {
+                args.windows(2).any(|pair| {
+                    matches!(pair[0].as_str(), "--network" | "--net") && pair[1] == "host"
+                }) || args
+                    .iter()
+                    .any(|arg| arg == "--network=host" || arg == "--net=host")
+            }
+            Self::DockerSocket => args.iter().any(|arg| arg.contains("docker.sock")),
+            Self::Privileged => args.iter().any(|arg| arg == "--privileged"),
+        }
+    }
+}
+
+impl fmt::Display for DockerRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HostNetwork => write!(f, "host networking"),
+            Self::DockerSocket => write!(f, "access to the Docker socket"),
+            Self::Privileged => write!(f, "privileged mode (Docker-in-Docker)"),
+        }
+    }
+}
+
+/// A requirement together with where it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequirementHint {
+    pub requirement: DockerRequirement,
+    /// Human-readable source, e.g. "image label" or "compose.yaml"
+    pub evidence: String,
+}
+
+impl RequirementHint {
+    fn new(requirement: DockerRequirement, evidence: impl Into<String>) -> Self {
+        Self {
+            requirement,
+            evidence: evidence.into(),
+        }
+    }
+}
+
+/// Look for requirements in free text such as a registry description or compose file.
+pub fn detect_from_text(text: &str, source: &str) -> Vec<RequirementHint> {
+    let lower = text.to_lowercase();
+    let mut hints = Vec::new();
+
+    let host_network_markers = [
+        "--network host",
+        "--network=host",
+        "--net=host",
+        "--net host",
+        "network_mode: host",
+        "network_mode: \"host\"",
+        "network_mode: 'host'",
+    ];
+    if host_network_markers
+        .iter()
+        .any(|marker| lower.contains(marker))
+    {
+        hints.push(RequirementHint::new(DockerRequirement::HostNetwork, source));
+    }
+
+    if lower.contains("docker.sock") {
+        hints.push(RequirementHint::new(
+            DockerRequirement::DockerSocket,
+            source,
+        ));
+    }
+
+    if lower.contains("--privileged") || lower.contains("privileged: true") {
+        hints.push(RequirementHint::new(DockerRequirement::Privileged, source));
+    }
+
+    hints
+}
+
+/// Look for requirements in the output of `docker image inspect`.
+///
+/// Checks declared volumes, environment defaults such as `DOCKER_HOST`, and labels.
+pub fn detect_from_image_config(inspect: &Value) -> Vec<RequirementHint> {
+    // `docker image inspect` prints an array with one entry per image
+    let image = inspect
+        .as_array()
+        .and_then(|a| a.first())
+        .unwrap_or(inspect);
+    let config = &image["Config"];
+    let mut hints = Vec::new();
+
+    if let Some(volumes) = config["Volumes"].as_object() {
+        if volumes.keys().any(|volume| volume.contains("docker.sock")) {
+            hints.push(RequirementHint::new(
+                DockerRequirement::DockerSocket,
+                "image declares a Docker socket volume",
+            ));
+        }
+    }
+
+    if let Some(env) = config["Env"].as_array() {
+        let docker_host = env
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|var| var.starts_with("DOCKER_HOST=unix://"));
+        if docker_host {
+            hints.push(RequirementHint::new(
+                DockerRequirement::DockerSocket,
+                "image sets DOCKER_HOST to a Unix socket",
+            ));
+        }
+    }
+
+    if let Some(labels) = config["Labels"].as_object() {
+        let label_text = labels
+            .iter()
+            .map(|(key, value)| format!("{key}={}\n", value.as_str().unwrap_or_default()))
+            .collect::<String>();
+        hints.extend(detect_from_text(&label_text, "image labels"));
+
+        let lower = label_text.to_lowercase();
+        if lower.contains("network=host") || lower.contains("network_mode=host") {
+            hints.push(RequirementHint::new(
+                DockerRequirement::HostNetwork,
+                "image labels",
+            ));
+        }
+    }
+
+    hints
+}
+
+/// Collects requirement hints for an image from every available source
+pub struct DockerRequirementInspector {
+    compose_dir: Option<PathBuf>,
+    registry_lookup: bool,
+}
+
+impl DockerRequirementInspector {
+    pub fn new() -> Self {
+        Self {
+            compose_dir: std::env::current_dir().ok(),
+            registry_lookup: true,
+        }
+    }
+
+    /// Directory searched for compose files that use the image
+    pub fn with_compose_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.compose_dir = dir;
+        self
+    }
+
+    /// Whether to fetch the image description from Docker Hub
+    pub fn with_registry_lookup(mut self, registry_lookup: bool) -> Self {
+        self.registry_lookup = registry_lookup;
+        self
+    }
+
+    /// Requirements for `image` (with optional tag), deduplicated by requirement.
+    ///
+    /// Sources that cannot be read are skipped; detection is best effort.
+    pub fn inspect(&self, image: &str) -> Vec<RequirementHint> {
+        let mut hints = Vec::new();
+
+        if let Ok(inspect) = inspect_local_image(image) {
+            hints.extend(detect_from_image_config(&inspect));
+        }
+
+        if let Some(dir) = &self.compose_dir {
+            hints.extend(detect_from_compose_files(dir, image));
+        }
+
+        if self.registry_lookup {
+            match fetch_registry_description(image) {
+                Ok(Some(description)) => {
+                    hints.extend(detect_from_text(&description, "Docker Hub description"))
+                }
+                Ok(None) => {}
+                Err(e) => tracing::debug!("Registry lookup for {} failed: {}", image, e),
+            }
+        }
+
+        dedup_hints(hints)
+    }
+}
+
+impl Default for DockerRequirementInspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keep the first hint for each requirement, ordered by requirement.
+pub fn dedup_hints(mut hints: Vec<RequirementHint>) -> Vec<RequirementHint> {
+    hints.sort_by_key(|hint| hint.requirement);
+    hints.dedup_by_key(|hint| hint.requirement);
+    hints
+}
+
+/// Insert the flags for `requirement` into `docker run` arguments, before the image.
+pub fn apply_requirement(args: &[String], requirement: DockerRequirement) -> Vec<String> {
+    if requirement.is_satisfied_by(args) {
+        return args.to_vec();
+    }
+
+    let insert_at = args
+        .iter()
+        .position(|arg| arg == "run")
+        .map_or(0, |index| index + 1);
+
+    let mut result = args.to_vec();
+    result.splice(insert_at..insert_at, requirement.flags());
+    result
+}
+
+/// The image reference in `docker run` arguments, if it can be found
+pub fn image_from_run_args(args: &[String]) -> Option<&str> {
+    // Options that take a separate value
+    const VALUE_OPTIONS: &[&str] = &[
+        "-v",
+        "--volume",
+        "-e",
+        "--env",
+        "-p",
+        "--publish",
+        "--network",
+        "--net",
+        "--name",
+        "--mount",
+        "--user",
+        "-u",
+        "--workdir",
+        "-w",
+        "--entrypoint",
+        "--restart",
+        "--env-file",
+    ];
+
+    let mut iter = args.iter().skip_while(|arg| *arg != "run").skip(1);
+    while let Some(arg) = iter.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            iter.next();
+        } else if !arg.starts_with('-') {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+fn inspect_local_image(image: &str) -> Result<Value> {
+    let output = Process::new("docker")
+        .args(["image", "inspect", image])
+        .output()
+        .context("Failed to execute docker image inspect command")?;
+
+    if !output.success() {
+        anyhow::bail!("Image {} is not available locally", image);
+    }
+
+    serde_json::from_slice(&output.stdout).context("Failed to parse docker image inspect output")
+}
+
+fn detect_from_compose_files(dir: &Path, image: &str) -> Vec<RequirementHint> {
+    let repository = image_repository(image);
+
+    COMPOSE_FILES
+        .iter()
+        .filter_map(|name| {
+            let contents = fs::read_to_string(dir.join(name)).ok()?;
+            contents
+                .contains(repository)
+                .then(|| detect_from_text(&contents, name))
+        })
+        .flatten()
+        .collect()
+}
+
+/// Docker Hub repository path for an image, or `None` for other registries
+pub fn docker_hub_repository(image: &str) -> Option<String> {
+    let repository = image_repository(image);
+    let mut parts = repository.splitn(2, '/');
+    let first = parts.next()?;
+
+    match parts.next() {
+        // Official images live under "library"
+        None => Some(format!("library/{first}")),
+        // A registry host contains a dot or port, or is localhost
+        Some(_) if first.contains('.') || first.contains(':') || first == "localhost" => None,
+        Some(rest) if rest.contains('/') => None,
+        Some(rest) => Some(format!("{first}/{rest}")),
+    }
+}
+
+/// The image reference without tag or digest
+pub(crate) fn image_repository(image: &str) -> &str {
+    let without_digest = image.split('@').next().unwrap_or(image);
+    match without_digest.rfind(':') {
+        Some(colon) if !without_digest[colon..].contains('/') => &without_digest[..colon],
+        _ => without_digest,
+    }
+}
+
+fn fetch_registry_description(image: &str) -> Result<Option<String>> {
+    let Some(repository) = docker_hub_repository(image) else {
+        return Ok(None);
+    };
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("mcp-helper")
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let response = client
+        .get(format!(
+            "https://hub.docker.com/v2/repositories/{repository}/"
+        ))
+        .send()
+        .context("Failed to query Docker Hub")?;
+
+    if !response.status().is_success() {
+        return Ok(None);
+    }
+
+    let body: Value = response
+        .json()
+        .context("Failed to parse Docker Hub response")?;
+    let description = [&body["description"], &body["full_description"]]
+        .iter()
+        .filter_map(|value| value.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(Some(description))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_detect_from_text() {
+        let readme = "Run with:\n\n    docker run -i --rm --network=host \\\n      -v /var/run/docker.sock:/var/run/docker.sock mcp/docker";
+        let hints = detect_from_text(readme, "README");
+        let requirements: Vec<_> = hints.iter().map(|h| h.requirement).collect();
+        assert_eq!(
+            requirements,
+            vec![
+                DockerRequirement::HostNetwork,
+                DockerRequirement::DockerSocket
+            ]
+        );
+        assert_eq!(hints[0].evidence, "README");
+
+        assert!(detect_from_text("A friendly fetch server", "README").is_empty());
+    }
+
+    #[test]
+    fn test_detect_from_compose_text() {
+        let compose = "services:\n  dind:\n    image: docker:dind\n    privileged: true\n  mcp:\n    image: org/mcp\n    network_mode: host\n";
+        let requirements: Vec<_> = detect_from_text(compose, "compose.yaml")
+            .into_iter()
+            .map(|h| h.requirement)
+            .collect();
+        assert!(requirements.contains(&DockerRequirement::HostNetwork));
+        assert!(requirements.contains(&DockerRequirement::Privileged));
+    }
+
+    #[test]
+    fn test_detect_from_image_config() {
+        let inspect = json!([{
+            "Config": {
+                "Env": ["PATH=/usr/bin", "DOCKER_HOST=unix:///var/run/docker.sock"],
+                "Volumes": { "/var/run/docker.sock": {} },
+                "Labels": { "io.mcp.network": "host" }
+            }
+        }]);
+
+        let hints = dedup_hints(detect_from_image_config(&inspect));
+        let requirements: Vec<_> = hints.iter().map(|h| h.requirement).collect();
+        assert_eq!(
+            requirements,
+            vec![
+                DockerRequirement::HostNetwork,
+                DockerRequirement::DockerSocket
+            ]
+        );
+
+        assert!(detect_from_image_config(&json!([{ "Config": {} }])).is_empty());
+    }
+
+    #[test]
+    fn test_compose_files_must_reference_image() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("compose.yaml"),
+            "services:\n  mcp:\n    image: org/mcp-docker:1.0\n    volumes:\n      - /var/run/docker.sock:/var/run/docker.sock\n",
+        )
+        .unwrap();
+
+        let hints = detect_from_compose_files(temp_dir.path(), "org/mcp-docker:1.0");
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].requirement, DockerRequirement::DockerSocket);
+        assert_eq!(hints[0].evidence, "compose.yaml");
+
+        assert!(detect_from_compose_files(temp_dir.path(), "org/other").is_empty());
+    }
+
+    #[test]
+    fn test_apply_requirement_inserts_before_image() {
+        let run = args(&["run", "--rm", "-i", "mcp/docker"]);
+
+        let with_network = apply_requirement(&run, DockerRequirement::HostNetwork);
+        assert_eq!(
+            with_network,
+            args(&["run", "--network", "host", "--rm", "-i", "mcp/docker"])
+        );
+        assert_eq!(image_from_run_args(&with_network), Some("mcp/docker"));
+
+        let with_socket = apply_requirement(&with_network, DockerRequirement::DockerSocket);
+        assert!(DockerRequirement::DockerSocket.is_satisfied_by(&with_socket));
+        assert_eq!(image_from_run_args(&with_socket), Some("mcp/docker"));
+
+        // Already satisfied requirements are left alone
+        assert_eq!(
+            apply_requirement(&with_socket, DockerRequirement::DockerSocket),
+            with_socket
+        );
+    }
+
+    #[test]
+    fn test_is_satisfied_by() {
+        assert!(DockerRequirement::HostNetwork.is_satisfied_by(&args(&["run", "--net=host"])));
+        assert!(!DockerRequirement::HostNetwork.is_satisfied_by(&args(&[
+            "run",
+            "--network",
+            "bridge"
+        ])));
+        assert!(DockerRequirement::Privileged.is_satisfied_by(&args(&["run", "--privileged"])));
+    }
+
+    #[test]
+    fn test_docker_hub_repository() {
+        assert_eq!(
+            docker_hub_repository("mcp/fetch:latest"),
+            Some("mcp/fetch".to_string())
+        );
+        assert_eq!(
+            docker_hub_repository("postgres:16"),
+            Some("library/postgres".to_string())
+        );
+        assert_eq!(docker_hub_repository("ghcr.io/org/server:1.0"), None);
+        assert_eq!(docker_hub_repository("localhost:5000/server"), None);
+    }
+
+    #[test]
+    fn test_confirmation_policy() {
+        assert!(!DockerRequirement::HostNetwork.needs_confirmation());
+        assert!(DockerRequirement::DockerSocket.needs_confirmation());
+        assert!(DockerRequirement::Privileged.needs_confirmation());
+    }
+}
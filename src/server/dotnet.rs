@@ -0,0 +1,351 @@
+use crate::deps::{DependencyChecker, DotnetChecker};
+use crate::server::{McpServer, ServerMetadata, ServerType};
+use crate::utils::process::{Process, INSTALL_TIMEOUT};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// MCP server published as a .NET tool and installed with `dotnet tool install`.
+///
+/// Tools are installed into a directory managed by MCP Helper
+/// (`~/.mcp/dotnet`) with `--tool-path` rather than as global tools, so the
+/// generated command points at the exact executable that was installed and
+/// updates don't touch the user's own tools.
+#[derive(Debug)]
+pub struct DotnetToolServer {
+    metadata: ServerMetadata,
+    package: String,
+    version: Option<String>,
+    tool_path: Option<PathBuf>,
+}
+
+/// A tool reported by `dotnet tool list`
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstalledTool {
+    /// Package id, lower-cased by the dotnet CLI
+    pub package: String,
+    pub version: String,
+    pub commands: Vec<String>,
+}
+
+impl DotnetToolServer {
+    /// Create a server from a spec such as `Contoso.Mcp.Server` or `Contoso.Mcp.Server@1.2.0`.
+    pub fn new(tool_spec: &str) -> Self {
+        let (package, version) = Self::parse_tool_spec(tool_spec);
+        Self::from_package(package, version)
+    }
+
+    pub fn from_package(package: String, version: Option<String>) -> Self {
+        let metadata = ServerMetadata {
+            name: package.clone(),
+            description: Some(format!(".NET MCP server: {package}")),
+            server_type: ServerType::Dotnet {
+                package: package.clone(),
+                version: version.clone(),
+            },
+            required_config: vec![],
+            optional_config: vec![],
+            args_template: vec![],
+        };
+
+        Self {
+            metadata,
+            package,
+            version,
+            tool_path: None,
+        }
+    }
+
+    /// Install into a custom directory instead of the managed one.
+    pub fn with_tool_path(mut self, tool_path: PathBuf) -> Self {
+        self.tool_path = Some(tool_path);
+        self
+    }
+
+    pub fn parse_tool_spec(tool_spec: &str) -> (String, Option<String>) {
+        match tool_spec.split_once('@') {
+            Some((name, version)) if !version.is_empty() => {
+                (name.to_string(), Some(version.to_string()))
+            }
+            Some((name, _)) => (name.to_string(), None),
+            None => (tool_spec.to_string(), None),
+        }
+    }
+
+    pub fn package(&self) -> &str {
+        &self.package
+    }
+
+    /// Directory passed to `dotnet tool install --tool-path`
+    pub fn tool_path(&self) -> Result<PathBuf> {
+        if let Some(path) = &self.tool_path {
+            return Ok(path.clone());
+        }
+        managed_tool_path()
+    }
+
+    /// Arguments for `dotnet tool install`
+    pub fn install_args(&self) -> Result<Vec<String>> {
+        self.tool_args("install")
+    }
+
+    /// Arguments for `dotnet tool update`; without a pinned version this moves to the latest
+    pub fn update_args(&self) -> Result<Vec<String>> {
+        self.tool_args("update")
+    }
+
+    fn tool_args(&self, action: &str) -> Result<Vec<String>> {
+        let mut args = vec![
+            "tool".to_string(),
+            action.to_string(),
+            "--tool-path".to_string(),
+            self.tool_path()?.to_string_lossy().to_string(),
+        ];
+        if let Some(version) = &self.version {
+            args.push("--version".to_string());
+            args.push(version.clone());
+        }
+        args.push(self.package.clone());
+        Ok(args)
+    }
+
+    /// The installed tool for this package, if any
+    pub fn installed(&self) -> Result<Option<InstalledTool>> {
+        let tools = list_tools(&self.tool_path()?)?;
+        Ok(tools
+            .into_iter()
+            .find(|tool| tool.package.eq_ignore_ascii_case(&self.package)))
+    }
+
+    /// Path of the tool's executable, once installed
+    pub fn executable_path(&self) -> Result<Option<PathBuf>> {
+        let Some(tool) = self.installed()? else {
+            return Ok(None);
+        };
+        let Some(command) = tool.commands.first() else {
+            return Ok(None);
+        };
+        Ok(Some(executable_in(&self.tool_path()?, command)))
+    }
+
+    /// Install the tool with `dotnet tool install`, returning its executable
+    pub fn install(&self) -> Result<PathBuf> {
+        println!(
+            "{} Installing {} with dotnet tool...",
+            "→".green(),
+            self.package.cyan()
+        );
+        self.run_dotnet(&self.install_args()?)?;
+
+        let executable = self.executable_path()?.with_context(|| {
+            format!(
+                "dotnet tool install succeeded but {} is not listed in {}",
+                self.package,
+                self.tool_path()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default()
+            )
+        })?;
+        println!("✅ Tool installed to: {}", executable.display());
+        Ok(executable)
+    }
+
+    /// Update the installed tool, returning the versions before and after
+    pub fn update(&self) -> Result<(String, String)> {
+        let before = self
+            .installed()?
+            .with_context(|| format!("{} is not installed", self.package))?;
+        self.run_dotnet(&self.update_args()?)?;
+        let after = self
+            .installed()?
+            .with_context(|| format!("{} disappeared during the update", self.package))?;
+        Ok((before.version, after.version))
+    }
+
+    fn run_dotnet(&self, args: &[String]) -> Result<()> {
+        let status = Process::new("dotnet")
+            .args(args)
+            .timeout(INSTALL_TIMEOUT)
+            .status()
+            .context("Failed to run dotnet. Is the .NET SDK installed?")?;
+        if !status.success() {
+            anyhow::bail!("dotnet {} failed with {}", args.join(" "), status);
+        }
+        Ok(())
+    }
+}
+
+/// `~/.mcp/dotnet`, where MCP Helper installs .NET tools
+pub fn managed_tool_path() -> Result<PathBuf> {
+    let home = directories::BaseDirs::new()
+        .context("Could not determine home directory")?
+        .home_dir()
+        .to_path_buf();
+    Ok(home.join(".mcp").join("dotnet"))
+}
+
+/// Tools installed in `tool_path`; none if the directory doesn't exist yet
+pub fn list_tools(tool_path: &Path) -> Result<Vec<InstalledTool>> {
+    if !tool_path.exists() {
+        return Ok(Vec::new());
+    }
+    let output = Process::new("dotnet")
+        .args(["tool", "list", "--tool-path"])
+        .arg(tool_path)
+        .output()
+        .context("Failed to run dotnet tool list")?;
+    if !output.success() {
+        anyhow::bail!("dotnet tool list failed: {}", output.stderr_lossy().trim());
+    }
+    Ok(parse_tool_list(&output.stdout_lossy()))
+}
+
+/// Parse the table printed by `dotnet tool list`
+pub fn parse_tool_list(output: &str) -> Vec<InstalledTool> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let package = words.next()?.to_string();
+            let version = words.next()?.to_string();
+            let commands = words
+                .flat_map(|word| word.split(','))
+                .filter(|command| !command.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some(InstalledTool {
+                package,
+                version,
+                commands,
+            })
+        })
+        .collect()
+}
+
+fn executable_in(tool_path: &Path, command: &str) -> PathBuf {
+    if cfg!(windows) {
+        tool_path.join(format!("{command}.exe"))
+    } else {
+        tool_path.join(command)
+    }
+}
+
+/// The managed tool a server command runs, if it is one
+pub fn managed_tool_for_command(command: &str) -> Result<Option<DotnetToolServer>> {
+    let tool_path = managed_tool_path()?;
+    let command = Path::new(command);
+    if command.parent() != Some(tool_path.as_path()) {
+        return Ok(None);
+    }
+    let Some(stem) = command.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+        return Ok(None);
+    };
+
+    Ok(list_tools(&tool_path)?
+        .into_iter()
+        .find(|tool| tool.commands.iter().any(|c| c.eq_ignore_ascii_case(&stem)))
+        .map(|tool| DotnetToolServer::from_package(tool.package, None)))
+}
+
+impl McpServer for DotnetToolServer {
+    fn metadata(&self) -> &ServerMetadata {
+        &self.metadata
+    }
+
+    fn validate_config(&self, _config: &HashMap<String, String>) -> Result<()> {
+        Ok(())
+    }
+
+    fn generate_command(&self) -> Result<(String, Vec<String>)> {
+        let executable = self
+            .executable_path()?
+            .with_context(|| format!("{} is not installed yet", self.package))?;
+        Ok((executable.to_string_lossy().to_string(), vec![]))
+    }
+
+    fn dependency(&self) -> Box<dyn DependencyChecker> {
+        Box::new(DotnetChecker::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_tool_spec() {
+        assert_eq!(
+            DotnetToolServer::parse_tool_spec("Contoso.Mcp.Server@1.2.0"),
+            ("Contoso.Mcp.Server".to_string(), Some("1.2.0".to_string()))
+        );
+        assert_eq!(
+            DotnetToolServer::parse_tool_spec("Contoso.Mcp.Server"),
+            ("Contoso.Mcp.Server".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_install_and_update_args() {
+        let temp_dir = TempDir::new().unwrap();
+        let server = DotnetToolServer::new("Contoso.Mcp.Server@1.2.0")
+            .with_tool_path(temp_dir.path().to_path_buf());
+
+        let args = server.install_args().unwrap();
+        assert_eq!(args[..2], ["tool", "install"]);
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "--tool-path" && Path::new(&w[1]) == temp_dir.path()));
+        assert!(args.windows(2).any(|w| w == ["--version", "1.2.0"]));
+        assert_eq!(args.last().unwrap(), "Contoso.Mcp.Server");
+
+        let server = DotnetToolServer::new("Contoso.Mcp.Server")
+            .with_tool_path(temp_dir.path().to_path_buf());
+        let args = server.update_args().unwrap();
+        assert_eq!(args[..2], ["tool", "update"]);
+        assert!(!args.contains(&"--version".to_string()));
+    }
+
+    #[test]
+    fn test_parse_tool_list() {
+        let output = "Package Id               Version      Commands\n\
+                      ----------------------------------------------------\n\
+                      contoso.mcp.server       1.2.0        contoso-mcp\n\
+                      dotnet-multi             0.3.1        one,two\n";
+        assert_eq!(
+            parse_tool_list(output),
+            vec![
+                InstalledTool {
+                    package: "contoso.mcp.server".to_string(),
+                    version: "1.2.0".to_string(),
+                    commands: vec!["contoso-mcp".to_string()],
+                },
+                InstalledTool {
+                    package: "dotnet-multi".to_string(),
+                    version: "0.3.1".to_string(),
+                    commands: vec!["one".to_string(), "two".to_string()],
+                },
+            ]
+        );
+        assert!(parse_tool_list("").is_empty());
+    }
+
+    #[test]
+    fn test_metadata_and_missing_tool_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let server = DotnetToolServer::new("Contoso.Mcp.Server")
+            .with_tool_path(temp_dir.path().join("missing"));
+        assert_eq!(
+            server.metadata().server_type,
+            ServerType::Dotnet {
+                package: "Contoso.Mcp.Server".to_string(),
+                version: None,
+            }
+        );
+        assert_eq!(server.installed().unwrap(), None);
+        assert!(server.generate_command().is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! Native folder pickers for choosing the directories of filesystem servers.
+//!
+//! Typing directories by hand invites typos and wrong separators, so `mcp add`
+//! offers the platform's own folder dialog through `rfd`: the system dialog on
+//! macOS and Windows, and the XDG desktop portal on Linux desktops.
+//!
+//! Without a usable dialog (SSH sessions, no display) callers fall back to
+//! manual entry. Picked paths still go through [`normalize_selection`] before
+//! they are written to a server config.
+
+use std::path::PathBuf;
+
+use crate::server::scope::resolve_directory;
+
+/// The platform's folder dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FolderPicker;
+
+impl FolderPicker {
+    /// The dialog, if one can be shown in this session
+    pub fn detect() -> Option<Self> {
+        // A dialog would open on the remote machine's screen, not the user's
+        if std::env::var_os("SSH_CONNECTION").is_some() {
+            return None;
+        }
+        if cfg!(any(target_os = "macos", windows)) || has_display() {
+            Some(Self)
+        } else {
+            None
+        }
+    }
+
+    /// Show the dialog; an empty list means it was cancelled or could not be shown
+    pub fn pick(&self, title: &str) -> Vec<PathBuf> {
+        rfd::FileDialog::new()
+            .set_title(title)
+            .pick_folders()
+            .unwrap_or_default()
+    }
+}
+
+fn has_display() -> bool {
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some_and(|value| !value.is_empty()))
+}
+
+/// Picked directories made absolute and canonical, without duplicates or
+/// directories inside another picked one, plus the paths that were rejected and why
+pub fn normalize_selection(paths: &[PathBuf]) -> (Vec<PathBuf>, Vec<(PathBuf, String)>) {
+    let mut resolved: Vec<PathBuf> = Vec::new();
+    let mut rejected = Vec::new();
+    for path in paths {
+        match resolve_directory(path) {
+            Ok(dir) if !resolved.contains(&dir) => resolved.push(dir),
+            Ok(_) => {}
+            Err(e) => rejected.push((path.clone(), e.to_string())),
+        }
+    }
+
+    let covered: Vec<PathBuf> = resolved
+        .iter()
+        .filter(|dir| {
+            resolved
+                .iter()
+                .any(|other| other != *dir && dir.starts_with(other))
+        })
+        .cloned()
+        .collect();
+    resolved.retain(|dir| !covered.contains(dir));
+    (resolved, rejected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_selection() {
+        let temp_dir = TempDir::new().unwrap();
+        let docs = temp_dir.path().join("docs");
+        let nested = docs.join("drafts");
+        let notes = temp_dir.path().join("notes");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(&notes).unwrap();
+        let file = temp_dir.path().join("todo.txt");
+        std::fs::write(&file, "").unwrap();
+
+        let (dirs, rejected) = normalize_selection(&[
+            nested.clone(),
+            docs.clone(),
+            notes.join("..").join("notes"),
+            notes.clone(),
+            file.clone(),
+            temp_dir.path().join("missing"),
+        ]);
+
+        assert_eq!(
+            dirs,
+            vec![
+                resolve_directory(&docs).unwrap(),
+                resolve_directory(&notes).unwrap()
+            ]
+        );
+        assert_eq!(rejected.len(), 2);
+        assert_eq!(rejected[0].0, file);
+        assert!(rejected[0].1.contains("Not a directory"));
+    }
+}
@@ -0,0 +1,146 @@
+//! GitHub API lookups shared by binary installs and release notes.
+//!
+//! Requests carry `GITHUB_TOKEN` (or `GH_TOKEN`) when it is set, which raises the
+//! limit from 60 to 5,000 requests an hour, and go through
+//! [`crate::utils::rate_limit`]. Responses are kept in a GitHub cache of their own,
+//! apart from npm metadata, and revalidated with their ETag; GitHub doesn't count a
+//! `304 Not Modified` against the limit.
+
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::cache::{registry_cache_enabled, CacheManager, RegistryResponse};
+use crate::utils::rate_limit::{self, Api};
+
+pub const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Environment variables a GitHub token is read from, in order
+const TOKEN_VARIABLES: &[&str] = &["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// The GitHub token from the environment, if any
+pub fn token() -> Option<String> {
+    TOKEN_VARIABLES
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .map(|token| token.trim().to_string())
+        .find(|token| !token.is_empty())
+}
+
+fn http_client() -> Result<Client> {
+    Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent("mcp-helper")
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// The latest 100 releases of `repo` (`owner/name`): tag and notes of each
+pub fn releases(repo: &str) -> Result<Value> {
+    let url = format!("{GITHUB_API_URL}/repos/{repo}/releases?per_page=100");
+    github_json(&format!("github:releases:{repo}"), &url, |full| {
+        let releases: Vec<Value> = full
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|release| {
+                json!({
+                    "tag_name": release["tag_name"],
+                    "body": release["body"],
+                })
+            })
+            .collect();
+        json!(releases)
+    })
+    .with_context(|| format!("GitHub release lookup for {repo} failed"))
+}
+
+/// One release of `repo`, the latest when `tag` is `None`: its tag and assets
+pub fn release(repo: &str, tag: Option<&str>) -> Result<Value> {
+    let url = match tag {
+        Some(tag) => format!("{GITHUB_API_URL}/repos/{repo}/releases/tags/{tag}"),
+        None => format!("{GITHUB_API_URL}/repos/{repo}/releases/latest"),
+    };
+    let key = format!("github:release:{repo}@{}", tag.unwrap_or("latest"));
+    github_json(&key, &url, |full| {
+        let assets: Vec<Value> = full["assets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|asset| {
+                json!({
+                    "name": asset["name"],
+                    "browser_download_url": asset["browser_download_url"],
+                    "size": asset["size"],
+                })
+            })
+            .collect();
+        json!({ "tag_name": full["tag_name"], "assets": assets })
+    })
+    .with_context(|| format!("GitHub release lookup for {repo} failed"))
+}
+
+/// The license GitHub detected in `repo`: its SPDX id and name
+pub fn license(repo: &str) -> Result<Value> {
+    let url = format!("{GITHUB_API_URL}/repos/{repo}/license");
+    github_json(&format!("github:license:{repo}"), &url, |full| {
+        json!({
+            "spdx_id": full["license"]["spdx_id"],
+            "name": full["license"]["name"],
+        })
+    })
+    .with_context(|| format!("GitHub license lookup for {repo} failed"))
+}
+
+/// GET `url` as JSON, cached under `key`; `trim` keeps what is worth caching
+fn github_json(key: &str, url: &str, trim: impl Fn(Value) -> Value) -> Result<Value> {
+    match CacheManager::new() {
+        Ok(mut cache) if registry_cache_enabled() => {
+            cache.github_metadata(key, |etag| request(url, etag, &trim))
+        }
+        _ => request(url, None, &trim)?.into_body(),
+    }
+}
+
+/// Request `url`, revalidating `etag` if given
+fn request(
+    url: &str,
+    etag: Option<&str>,
+    trim: impl Fn(Value) -> Value,
+) -> Result<RegistryResponse> {
+    let client = http_client()?;
+    let token = token();
+    let response = rate_limit::send(Api::GitHub, || {
+        let mut request = client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+        if let Some(token) = &token {
+            request = request.bearer_auth(token);
+        }
+        if let Some(etag) = etag {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        request
+    })?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED && etag.is_some() {
+        return Ok(RegistryResponse::NotModified);
+    }
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let full: Value = response
+        .json()
+        .context("Failed to parse GitHub API response")?;
+    Ok(RegistryResponse::Fetched {
+        body: trim(full),
+        etag,
+    })
+}
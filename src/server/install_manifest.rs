@@ -0,0 +1,402 @@
+//! Vendor-hosted install manifests (`mcp install https://vendor.com/mcp/install.json`).
+//!
+//! An install manifest is a small JSON document that tells the normal install pipeline
+//! which server to install and how to configure it:
+//!
+//! ```json
+//! {
+//!   "name": "acme",
+//!   "description": "Acme issue tracker",
+//!   "server": "@acme/mcp-server@2.1.0",
+//!   "config": [
+//!     { "name": "ACME_API_KEY", "type": "string", "required": true,
+//!       "description": "API key from https://acme.example/settings" },
+//!     { "name": "ACME_REGION", "default": "eu" }
+//!   ],
+//!   "defaults": { "ACME_TELEMETRY": "off" },
+//!   "args": ["--region", "{{ACME_REGION}}"]
+//! }
+//! ```
+//!
+//! `server` accepts the same specs as `mcp install` (npm packages, `docker:image`, ...).
+//! `args` is an argument template rendered from the configuration (see
+//! [`crate::server::args_template`]).
+//! Manifests are only fetched over HTTPS and are size limited.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
+
+use crate::server::{ConfigField, ConfigFieldType, McpServer, ServerMetadata};
+use crate::utils::parse_error::parse_json;
+
+/// Largest manifest accepted, in bytes
+pub const MAX_MANIFEST_SIZE: u64 = 64 * 1024;
+
+/// Fetch the `kind` of manifest at `url`: only over HTTPS, so nobody on the way
+/// can substitute the command it installs, and no larger than [`MAX_MANIFEST_SIZE`]
+pub(crate) fn fetch_manifest(url: &str, kind: &str) -> Result<String> {
+    if !url.to_lowercase().starts_with("https://") {
+        anyhow::bail!("The {kind} must be served over HTTPS: {url}");
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .user_agent("mcp-helper")
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let response = client
+        .get(url)
+        .send()
+        .with_context(|| format!("Failed to fetch {kind} from {url}"))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Failed to fetch {kind} from {url}: HTTP {}",
+            response.status()
+        );
+    }
+    let too_large =
+        || anyhow::anyhow!("The {kind} at {url} is larger than {MAX_MANIFEST_SIZE} bytes");
+    if response
+        .content_length()
+        .is_some_and(|len| len > MAX_MANIFEST_SIZE)
+    {
+        return Err(too_large());
+    }
+
+    let mut body = String::new();
+    response
+        .take(MAX_MANIFEST_SIZE + 1)
+        .read_to_string(&mut body)
+        .with_context(|| format!("Failed to read {kind} body"))?;
+    if body.len() as u64 > MAX_MANIFEST_SIZE {
+        return Err(too_large());
+    }
+    Ok(body)
+}
+
+/// A vendor install manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstallManifest {
+    /// Name used in client configs; defaults to the name derived from `server`
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Server spec handed to the install pipeline
+    pub server: String,
+    /// Configuration schema, replacing the one detected from the server type
+    #[serde(default)]
+    pub config: Vec<ManifestField>,
+    /// Values used for configuration keys not supplied with `--config`
+    #[serde(default)]
+    pub defaults: HashMap<String, String>,
+    /// Arguments for the server command, with `{{field}}` placeholders
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// A configuration field declared by a manifest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestField {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: ManifestFieldKind,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestFieldKind {
+    #[default]
+    String,
+    Number,
+    Boolean,
+    Path,
+    Url,
+}
+
+impl From<ManifestFieldKind> for ConfigFieldType {
+    fn from(kind: ManifestFieldKind) -> Self {
+        match kind {
+            ManifestFieldKind::String => ConfigFieldType::String,
+            ManifestFieldKind::Number => ConfigFieldType::Number,
+            ManifestFieldKind::Boolean => ConfigFieldType::Boolean,
+            ManifestFieldKind::Path => ConfigFieldType::Path,
+            ManifestFieldKind::Url => ConfigFieldType::Url,
+        }
+    }
+}
+
+impl ManifestField {
+    fn to_config_field(&self, defaults: &HashMap<String, String>) -> ConfigField {
+        ConfigField {
+            description: self.description.clone(),
+            default: self
+                .default
+                .clone()
+                .or_else(|| defaults.get(&self.name).cloned()),
+            ..ConfigField::new(&self.name, self.kind.into())
+        }
+    }
+}
+
+/// Whether an install argument refers to a hosted install manifest
+pub fn is_install_manifest_url(reference: &str) -> bool {
+    let lower = reference.to_lowercase();
+    (lower.starts_with("https://") || lower.starts_with("http://"))
+        && reference
+            .split(['?', '#'])
+            .next()
+            .is_some_and(|path| path.ends_with(".json"))
+}
+
+impl InstallManifest {
+    /// Parse and validate a manifest document.
+    pub fn from_json(content: &str) -> Result<Self> {
+        let value: serde_json::Value = parse_json("install manifest", content)?;
+
+        if value.get("server").is_none_or(|server| !server.is_string())
+            && value.get("packages").is_some()
+        {
+            anyhow::bail!(
+                "This is an MCP registry manifest (server.json); add it with 'mcp add <url>' instead"
+            );
+        }
+
+        let manifest: Self = parse_json("install manifest", content)?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    fn validate(&self) -> Result<()> {
+        let server = self.server.trim();
+        if server.is_empty() {
+            anyhow::bail!("Install manifest does not name a server");
+        }
+        if is_install_manifest_url(server) {
+            anyhow::bail!("Install manifests cannot point at another manifest ({server})");
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        for field in &self.config {
+            if field.name.trim().is_empty() {
+                anyhow::bail!("Install manifest declares a config field without a name");
+            }
+            if !seen.insert(field.name.as_str()) {
+                anyhow::bail!(
+                    "Install manifest declares config field '{}' twice",
+                    field.name
+                );
+            }
+        }
+
+        for name in crate::server::args_template::placeholders(&self.args) {
+            if !seen.contains(name.as_str()) && !self.defaults.contains_key(&name) {
+                anyhow::bail!("Install manifest args refer to undeclared config field '{name}'");
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch a manifest over HTTPS.
+    ///
+    /// Callers are expected to have run the URL through the security validator first.
+    pub fn fetch(url: &str) -> Result<Self> {
+        Self::from_json(&fetch_manifest(url, "install manifest")?)
+    }
+
+    /// Every default value, with a field's own default taking precedence over `defaults`
+    pub fn all_defaults(&self) -> HashMap<String, String> {
+        let mut defaults = self.defaults.clone();
+        for field in &self.config {
+            if let Some(default) = &field.default {
+                defaults.insert(field.name.clone(), default.clone());
+            }
+        }
+        defaults
+    }
+
+    /// Wrap the server created from `server`, replacing its metadata with the manifest's.
+    pub fn apply_to(&self, server: Box<dyn McpServer>) -> Box<dyn McpServer> {
+        let mut metadata = server.metadata().clone();
+        if let Some(description) = &self.description {
+            metadata.description = Some(description.clone());
+        }
+        if !self.config.is_empty() {
+            metadata.required_config = self
+                .config
+                .iter()
+                .filter(|field| field.required)
+                .map(|field| field.to_config_field(&self.defaults))
+                .collect();
+            metadata.optional_config = self
+                .config
+                .iter()
+                .filter(|field| !field.required)
+                .map(|field| field.to_config_field(&self.defaults))
+                .collect();
+        }
+        if !self.args.is_empty() {
+            metadata.args_template = self.args.clone();
+        }
+
+        Box::new(ManifestServer {
+            inner: server,
+            metadata,
+        })
+    }
+}
+
+/// A server whose configuration schema comes from an install manifest
+struct ManifestServer {
+    inner: Box<dyn McpServer>,
+    metadata: ServerMetadata,
+}
+
+impl McpServer for ManifestServer {
+    fn metadata(&self) -> &ServerMetadata {
+        &self.metadata
+    }
+
+    fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
+        for field in &self.metadata.required_config {
+            if !config.contains_key(&field.name) {
+                anyhow::bail!("Missing required configuration field: {}", field.name);
+            }
+        }
+        self.inner.validate_config(config)
+    }
+
+    fn generate_command(&self) -> Result<(String, Vec<String>)> {
+        self.inner.generate_command()
+    }
+
+    fn generate_command_with_config(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<(String, Vec<String>)> {
+        self.inner.generate_command_with_config(config)
+    }
+
+    fn command_config_fields(&self) -> Vec<String> {
+        self.inner.command_config_fields()
+    }
+
+    fn dependency(&self) -> Box<dyn crate::deps::DependencyChecker> {
+        self.inner.dependency()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::NpmServer;
+    use crate::utils::parse_error::ParseError;
+
+    const MANIFEST: &str = r#"{
+        "name": "acme",
+        "description": "Acme issue tracker",
+        "server": "@acme/mcp-server@2.1.0",
+        "config": [
+            { "name": "ACME_API_KEY", "type": "string", "required": true },
+            { "name": "ACME_REGION" },
+            { "name": "ACME_PORT", "type": "number", "default": "8080" }
+        ],
+        "defaults": { "ACME_REGION": "eu", "ACME_TELEMETRY": "off" },
+        "args": ["--region", "{{ACME_REGION}}"]
+    }"#;
+
+    #[test]
+    fn test_is_install_manifest_url() {
+        assert!(is_install_manifest_url(
+            "https://acme.example/mcp/install.json"
+        ));
+        assert!(is_install_manifest_url(
+            "https://acme.example/install.json?v=2"
+        ));
+        assert!(!is_install_manifest_url("https://github.com/acme/server"));
+        assert!(!is_install_manifest_url("./install.json"));
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = InstallManifest::from_json(MANIFEST).unwrap();
+        assert_eq!(manifest.server, "@acme/mcp-server@2.1.0");
+        assert_eq!(manifest.config.len(), 3);
+        assert_eq!(manifest.config[1].kind, ManifestFieldKind::String);
+        assert_eq!(manifest.config[2].kind, ManifestFieldKind::Number);
+        assert_eq!(
+            manifest.all_defaults(),
+            HashMap::from([
+                ("ACME_PORT".to_string(), "8080".to_string()),
+                ("ACME_REGION".to_string(), "eu".to_string()),
+                ("ACME_TELEMETRY".to_string(), "off".to_string())
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_manifests() {
+        assert!(InstallManifest::from_json(r#"{ "server": "" }"#).is_err());
+        assert!(
+            InstallManifest::from_json(r#"{ "server": "https://evil.example/next.json" }"#)
+                .is_err()
+        );
+        assert!(InstallManifest::from_json(
+            r#"{ "server": "pkg", "config": [{ "name": "A" }, { "name": "A" }] }"#
+        )
+        .is_err());
+
+        let error =
+            InstallManifest::from_json(r#"{ "name": "io.github.org/weather", "packages": [] }"#)
+                .unwrap_err();
+        assert!(error.to_string().contains("mcp add"));
+
+        let error =
+            InstallManifest::from_json(r#"{ "server": "pkg", "args": ["{{ROOT}}"] }"#).unwrap_err();
+        assert!(error.to_string().contains("ROOT"));
+    }
+
+    #[test]
+    fn test_errors_name_line_and_column() {
+        let error = InstallManifest::from_json("{\n  \"server\": \"pkg\",\n  \"config\": 3\n}")
+            .unwrap_err();
+        let parse = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(parse.file, "install manifest");
+        assert_eq!(parse.line, 3);
+    }
+
+    #[test]
+    fn test_fetch_requires_https() {
+        let error = InstallManifest::fetch("http://acme.example/install.json").unwrap_err();
+        assert!(error.to_string().contains("HTTPS"));
+    }
+
+    #[test]
+    fn test_apply_to_replaces_schema() {
+        let manifest = InstallManifest::from_json(MANIFEST).unwrap();
+        let server = manifest.apply_to(Box::new(NpmServer::new("@acme/mcp-server").unwrap()));
+
+        let metadata = server.metadata();
+        assert_eq!(metadata.description.as_deref(), Some("Acme issue tracker"));
+        assert_eq!(metadata.required_config.len(), 1);
+        assert_eq!(metadata.required_config[0].name, "ACME_API_KEY");
+        assert_eq!(metadata.optional_config.len(), 2);
+        assert_eq!(metadata.optional_config[0].default.as_deref(), Some("eu"));
+        assert_eq!(metadata.args_template, ["--region", "{{ACME_REGION}}"]);
+
+        assert!(server.validate_config(&HashMap::new()).is_err());
+        let config = HashMap::from([("ACME_API_KEY".to_string(), "key".to_string())]);
+        assert!(server.validate_config(&config).is_ok());
+    }
+}
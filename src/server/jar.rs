@@ -0,0 +1,207 @@
+use crate::deps::{DependencyChecker, JavaChecker};
+use crate::server::{is_path_spec, ConfigField, McpServer, ServerMetadata, ServerType};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Config field holding extra JVM options
+const JVM_OPTIONS_FIELD: &str = "jvm_options";
+
+/// MCP server shipped as an executable JAR and run with `java -jar`.
+///
+/// Clients start servers from a directory of their choosing, so the generated
+/// command always refers to the JAR by its absolute path.
+#[derive(Debug)]
+pub struct JarServer {
+    metadata: ServerMetadata,
+    path: String,
+}
+
+impl JarServer {
+    /// Create a server from the path of a JAR file
+    pub fn new(path: &str) -> Self {
+        let name = Self::server_name(path);
+        let metadata = ServerMetadata {
+            name: name.clone(),
+            description: Some(format!("Java MCP server: {name}")),
+            server_type: ServerType::Jar {
+                path: path.to_string(),
+            },
+            required_config: vec![],
+            optional_config: vec![ConfigField::string(JVM_OPTIONS_FIELD)
+                .describe("JVM options such as -Xmx512m, space-separated")],
+            args_template: vec![],
+        };
+
+        Self {
+            metadata,
+            path: path.to_string(),
+        }
+    }
+
+    /// Short name for a JAR: the file name without version or classifier,
+    /// so `confluence-mcp-1.4.0-all.jar` becomes `confluence-mcp`
+    pub fn server_name(path: &str) -> String {
+        let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+        let stem = file
+            .get(..file.len().saturating_sub(4))
+            .filter(|_| file.to_lowercase().ends_with(".jar"))
+            .unwrap_or(file);
+        let name = stem
+            .split('-')
+            .take_while(|part| !part.starts_with(|c: char| c.is_ascii_digit()))
+            .collect::<Vec<_>>()
+            .join("-");
+        if name.is_empty() {
+            stem.to_string()
+        } else {
+            name
+        }
+    }
+
+    /// Absolute path of the JAR; fails if it does not exist
+    pub fn resolve(path: &str) -> Result<PathBuf> {
+        let path = Path::new(path);
+        if !path.is_file() {
+            anyhow::bail!("JAR file not found: {}", path.display());
+        }
+        crate::server::scope::dunce_canonicalize(path)
+            .with_context(|| format!("Failed to resolve {}", path.display()))
+    }
+
+    /// Whether `arg` is a JVM option that goes before `-jar` rather than a server argument
+    pub fn is_jvm_option(arg: &str) -> bool {
+        arg.starts_with("-X") || arg.starts_with("-D") || arg.starts_with("-javaagent:")
+    }
+}
+
+/// The JAR path in a server spec, if it names a local JAR file.
+///
+/// Matches `jar:<path>` and paths ending in `.jar`. A bare `name.jar` could be an
+/// npm package and remote URLs are downloads, so both are left to other types.
+pub fn jar_path(spec: &str) -> Option<&str> {
+    if let Some(path) = spec.strip_prefix("jar:") {
+        return (!path.is_empty()).then_some(path);
+    }
+    (is_path_spec(spec) && spec.to_lowercase().ends_with(".jar")).then_some(spec)
+}
+
+impl McpServer for JarServer {
+    fn metadata(&self) -> &ServerMetadata {
+        &self.metadata
+    }
+
+    fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
+        Self::resolve(&self.path)?;
+        if let Some(options) = config.get(JVM_OPTIONS_FIELD) {
+            if let Some(option) = options.split_whitespace().find(|o| !o.starts_with('-')) {
+                anyhow::bail!("{JVM_OPTIONS_FIELD} must only contain options, found '{option}'");
+            }
+        }
+        Ok(())
+    }
+
+    fn generate_command(&self) -> Result<(String, Vec<String>)> {
+        self.generate_command_with_config(&HashMap::new())
+    }
+
+    /// `java -jar` with the JVM options from `config`
+    fn generate_command_with_config(
+        &self,
+        config: &HashMap<String, String>,
+    ) -> Result<(String, Vec<String>)> {
+        let jar = Self::resolve(&self.path)?;
+        let mut args: Vec<String> = config
+            .get(JVM_OPTIONS_FIELD)
+            .map(|options| options.split_whitespace().map(str::to_string).collect())
+            .unwrap_or_default();
+        args.push("-jar".to_string());
+        args.push(jar.display().to_string());
+        Ok(("java".to_string(), args))
+    }
+
+    fn command_config_fields(&self) -> Vec<String> {
+        vec![JVM_OPTIONS_FIELD.to_string()]
+    }
+
+    fn dependency(&self) -> Box<dyn DependencyChecker> {
+        Box::new(JavaChecker::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_jar_path() {
+        assert_eq!(jar_path("./server.jar"), Some("./server.jar"));
+        assert_eq!(jar_path("C:\\mcp\\Server.JAR"), Some("C:\\mcp\\Server.JAR"));
+        assert_eq!(jar_path("jar:/opt/mcp/server"), Some("/opt/mcp/server"));
+        assert_eq!(jar_path("jar:"), None);
+        assert_eq!(jar_path(".jar"), None);
+        assert_eq!(jar_path("server.jar"), None);
+        assert_eq!(jar_path("https://example.com/server.jar"), None);
+        assert_eq!(jar_path("server.py"), None);
+    }
+
+    #[test]
+    fn test_server_name() {
+        assert_eq!(
+            JarServer::server_name("/opt/mcp/confluence-mcp-1.4.0-all.jar"),
+            "confluence-mcp"
+        );
+        assert_eq!(JarServer::server_name("C:\\mcp\\jira.jar"), "jira");
+        assert_eq!(JarServer::server_name("./2024-server.jar"), "2024-server");
+    }
+
+    #[test]
+    fn test_generate_command_with_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let jar = temp_dir.path().join("connector-2.0.jar");
+        std::fs::write(&jar, b"PK").unwrap();
+        let server = JarServer::new(&jar.display().to_string());
+
+        let config = HashMap::from([(
+            JVM_OPTIONS_FIELD.to_string(),
+            "-Xmx512m  -Dfile.encoding=UTF-8".to_string(),
+        )]);
+        let (command, args) = server.generate_command_with_config(&config).unwrap();
+        assert_eq!(command, "java");
+        assert_eq!(
+            args,
+            vec![
+                "-Xmx512m".to_string(),
+                "-Dfile.encoding=UTF-8".to_string(),
+                "-jar".to_string(),
+                JarServer::resolve(&jar.display().to_string())
+                    .unwrap()
+                    .display()
+                    .to_string(),
+            ]
+        );
+        assert!(Path::new(&args[3]).is_absolute());
+        assert_eq!(server.metadata().name, "connector");
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let jar = temp_dir.path().join("server.jar");
+        let server = JarServer::new(&jar.display().to_string());
+        assert!(server.validate_config(&HashMap::new()).is_err());
+
+        std::fs::write(&jar, b"PK").unwrap();
+        assert!(server.validate_config(&HashMap::new()).is_ok());
+        let bad = HashMap::from([(JVM_OPTIONS_FIELD.to_string(), "-Xmx1g extra".to_string())]);
+        assert!(server.validate_config(&bad).is_err());
+    }
+
+    #[test]
+    fn test_is_jvm_option() {
+        assert!(JarServer::is_jvm_option("-Xmx1g"));
+        assert!(JarServer::is_jvm_option("-Dconfig=prod"));
+        assert!(!JarServer::is_jvm_option("--port"));
+    }
+}
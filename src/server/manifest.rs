@@ -0,0 +1,479 @@
+//! Loader for MCP registry server manifests (`server.json`).
+//!
+//! The MCP registry publishes a manifest per server describing the packages it is
+//! distributed as (npm, PyPI, OCI images, ...), the arguments each package expects and
+//! the environment variables it reads. This module fetches a manifest, picks the
+//! package that can actually run on this machine and turns it into a [`ServerConfig`].
+//!
+//! Manifests can be referenced as:
+//! - `registry:<server-id>` - looked up in the MCP registry
+//! - `https://example.com/server.json` - fetched directly (HTTPS only, at most
+//!   [`MAX_MANIFEST_SIZE`](crate::server::install_manifest::MAX_MANIFEST_SIZE) bytes)
+//! - `./path/to/server.json` - read from disk
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::client::ServerConfig;
+use crate::server::install_manifest::fetch_manifest;
+use crate::server::npm::npx_preference_flags;
+use crate::utils::parse_error::parse_json;
+
+/// Default MCP registry, overridable with `MCP_REGISTRY_URL`
+pub const DEFAULT_REGISTRY_URL: &str = "https://registry.modelcontextprotocol.io";
+
+/// Where a manifest should be loaded from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ManifestSource {
+    RegistryId(String),
+    Url(String),
+    File(PathBuf),
+}
+
+impl ManifestSource {
+    /// Recognise a manifest reference, returning `None` for ordinary server specs.
+    pub fn parse(reference: &str) -> Option<Self> {
+        if let Some(id) = reference.strip_prefix("registry:") {
+            return Some(Self::RegistryId(id.to_string()));
+        }
+
+        let is_json = reference
+            .split(['?', '#'])
+            .next()
+            .is_some_and(|path| path.ends_with(".json"));
+        if !is_json {
+            return None;
+        }
+
+        if reference.starts_with("https://") || reference.starts_with("http://") {
+            Some(Self::Url(reference.to_string()))
+        } else {
+            Some(Self::File(PathBuf::from(reference)))
+        }
+    }
+
+    /// Load and parse the manifest.
+    pub fn load(&self) -> Result<ServerManifest> {
+        let name = match self {
+            Self::File(path) => path.display().to_string(),
+            Self::Url(url) => url.clone(),
+            Self::RegistryId(id) => format!("registry entry {id}"),
+        };
+        let content = match self {
+            Self::File(path) => fs::read_to_string(path)
+                .with_context(|| format!("Failed to read manifest {}", path.display()))?,
+            Self::Url(url) => fetch_manifest(url, "server manifest")?,
+            Self::RegistryId(id) => {
+                let base = std::env::var("MCP_REGISTRY_URL")
+                    .unwrap_or_else(|_| DEFAULT_REGISTRY_URL.to_string());
+                let url = url::Url::parse(&format!("{}/v0/servers/", base.trim_end_matches('/')))
+                    .and_then(|url| url.join(&urlencode(id)))
+                    .context("Invalid registry URL")?;
+                fetch_manifest(url.as_str(), "registry manifest")?
+            }
+        };
+
+        ServerManifest::parse(&name, &content)
+    }
+}
+
+fn urlencode(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// A server manifest in MCP registry format
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerManifest {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub packages: Vec<ManifestPackage>,
+}
+
+/// One way of distributing the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestPackage {
+    #[serde(alias = "registry_name", alias = "registryType")]
+    pub registry_type: String,
+    #[serde(alias = "name")]
+    pub identifier: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default, alias = "runtimeHint")]
+    pub runtime_hint: Option<String>,
+    #[serde(default)]
+    pub transport: Option<ManifestTransport>,
+    #[serde(default, alias = "runtimeArguments")]
+    pub runtime_arguments: Vec<ManifestArgument>,
+    #[serde(default, alias = "packageArguments")]
+    pub package_arguments: Vec<ManifestArgument>,
+    #[serde(default, alias = "environmentVariables")]
+    pub environment_variables: Vec<ManifestVariable>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestTransport {
+    #[serde(rename = "type")]
+    pub transport_type: String,
+}
+
+/// A command-line argument declared by a package
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestArgument {
+    #[serde(rename = "type", default = "ManifestArgument::default_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+impl ManifestArgument {
+    fn default_kind() -> String {
+        "positional".to_string()
+    }
+}
+
+/// An environment variable declared by a package
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, alias = "isRequired")]
+    pub is_required: bool,
+    #[serde(default, alias = "isSecret")]
+    pub is_secret: bool,
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+impl ServerManifest {
+    /// Parse a manifest, accepting both bare manifests and registry responses
+    /// that wrap the manifest in a `server` field.
+    pub fn from_json(content: &str) -> Result<Self> {
+        Self::parse("server manifest", content)
+    }
+
+    /// [`from_json`](Self::from_json), naming `file` in errors along with the
+    /// line and column of the problem
+    pub fn parse(file: &str, content: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Wrapped {
+            server: ServerManifest,
+        }
+
+        let value: serde_json::Value = parse_json(file, content)?;
+        if value.get("server").is_some_and(|server| server.is_object()) {
+            Ok(parse_json::<Wrapped>(file, content)?.server)
+        } else {
+            Ok(parse_json(file, content)?)
+        }
+    }
+
+    /// Short name used as the server key in client configs (`io.github.org/weather` -> `weather`)
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit('/').next().unwrap_or(&self.name)
+    }
+
+    /// Pick the best stdio package for this machine.
+    ///
+    /// Packages are tried in manifest order; the first whose runtime is available wins.
+    pub fn select_package(&self) -> Option<&ManifestPackage> {
+        self.select_package_with(|command| which::which(command).is_ok())
+    }
+
+    /// Pick a package using a custom runtime availability check.
+    pub fn select_package_with(
+        &self,
+        is_available: impl Fn(&str) -> bool,
+    ) -> Option<&ManifestPackage> {
+        self.packages
+            .iter()
+            .filter(|package| package.is_stdio())
+            .find(|package| package.runtime().is_some_and(|rt| is_available(&rt)))
+    }
+}
+
+impl ManifestPackage {
+    /// Whether this package runs over stdio (the default when unspecified)
+    pub fn is_stdio(&self) -> bool {
+        self.transport
+            .as_ref()
+            .is_none_or(|transport| transport.transport_type == "stdio")
+    }
+
+    /// The command used to launch this package, or `None` if the registry type is unsupported
+    pub fn runtime(&self) -> Option<String> {
+        if let Some(hint) = &self.runtime_hint {
+            return Some(hint.clone());
+        }
+
+        match self.registry_type.as_str() {
+            "npm" => Some("npx".to_string()),
+            "pypi" => Some("uvx".to_string()),
+            "oci" | "docker" => Some("docker".to_string()),
+            "nuget" => Some("dnx".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Build a client configuration, substituting `{NAME}` placeholders from `values`.
+    pub fn to_server_config(&self, values: &HashMap<String, String>) -> Result<ServerConfig> {
+        let command = self.runtime().with_context(|| {
+            format!(
+                "Unsupported package registry '{}' for {}",
+                self.registry_type, self.identifier
+            )
+        })?;
+
+        let mut args = Self::render_arguments(&self.runtime_arguments, values);
+
+        match self.registry_type.as_str() {
+            "oci" | "docker" => {
+                if self.runtime_arguments.is_empty() {
+                    args.extend(["run", "--rm", "-i"].map(String::from));
+                }
+                for var in &self.environment_variables {
+                    if values.contains_key(&var.name) {
+                        args.push("-e".to_string());
+                        args.push(var.name.clone());
+                    }
+                }
+                args.push(match &self.version {
+                    Some(version) => format!("{}:{version}", self.identifier),
+                    None => self.identifier.clone(),
+                });
+            }
+            "npm" => {
+                if self.runtime_hint.is_none() {
+                    args.extend(npx_preference_flags());
+                    args.push("-y".to_string());
+                }
+                args.push(self.versioned_identifier("@"));
+            }
+            "pypi" => args.push(self.versioned_identifier("==")),
+            "nuget" => args.push(self.versioned_identifier("@")),
+            _ => args.push(self.identifier.clone()),
+        }
+
+        args.extend(Self::render_arguments(&self.package_arguments, values));
+
+        let env = self
+            .environment_variables
+            .iter()
+            .filter_map(|var| {
+                values
+                    .get(&var.name)
+                    .cloned()
+                    .map(|value| (var.name.clone(), value))
+            })
+            .collect();
+
+        Ok(ServerConfig {
+            command,
+            args,
+            env,
+            cwd: None,
+        })
+    }
+
+    fn versioned_identifier(&self, separator: &str) -> String {
+        match &self.version {
+            Some(version) => format!("{}{separator}{version}", self.identifier),
+            None => self.identifier.clone(),
+        }
+    }
+
+    fn render_arguments(
+        arguments: &[ManifestArgument],
+        values: &HashMap<String, String>,
+    ) -> Vec<String> {
+        let mut rendered = Vec::new();
+
+        for argument in arguments {
+            let value = argument
+                .value
+                .as_ref()
+                .or(argument.default.as_ref())
+                .map(|value| substitute(value, values));
+
+            match (argument.kind.as_str(), &argument.name, value) {
+                ("named", Some(name), Some(value)) => {
+                    rendered.push(name.clone());
+                    rendered.push(value);
+                }
+                ("named", Some(name), None) => rendered.push(name.clone()),
+                (_, _, Some(value)) => rendered.push(value),
+                _ => {}
+            }
+        }
+
+        rendered
+    }
+}
+
+fn substitute(value: &str, values: &HashMap<String, String>) -> String {
+    values
+        .iter()
+        .fold(value.to_string(), |acc, (name, replacement)| {
+            acc.replace(&format!("{{{name}}}"), replacement)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"{
+        "name": "io.github.example/weather",
+        "description": "Weather lookups",
+        "version": "1.2.0",
+        "packages": [
+            {
+                "registry_type": "oci",
+                "identifier": "example/weather",
+                "version": "1.2.0",
+                "environment_variables": [
+                    {"name": "WEATHER_API_KEY", "is_required": true, "is_secret": true}
+                ]
+            },
+            {
+                "registry_type": "npm",
+                "identifier": "@example/weather",
+                "version": "1.2.0",
+                "transport": {"type": "stdio"},
+                "package_arguments": [
+                    {"type": "named", "name": "--units", "default": "metric"},
+                    {"type": "positional", "value": "{WEATHER_API_KEY}"}
+                ],
+                "environment_variables": [
+                    {"name": "WEATHER_API_KEY", "is_required": true, "is_secret": true},
+                    {"name": "WEATHER_REGION", "default": "eu"}
+                ]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_source() {
+        assert_eq!(
+            ManifestSource::parse("registry:io.github.example/weather"),
+            Some(ManifestSource::RegistryId(
+                "io.github.example/weather".to_string()
+            ))
+        );
+        assert_eq!(
+            ManifestSource::parse("https://example.com/server.json?ref=main"),
+            Some(ManifestSource::Url(
+                "https://example.com/server.json?ref=main".to_string()
+            ))
+        );
+        assert_eq!(
+            ManifestSource::parse("./server.json"),
+            Some(ManifestSource::File(PathBuf::from("./server.json")))
+        );
+        assert_eq!(ManifestSource::parse("@example/weather"), None);
+        assert_eq!(ManifestSource::parse("https://example.com/binary"), None);
+    }
+
+    #[test]
+    fn test_plain_http_manifest_is_refused() {
+        let source = ManifestSource::parse("http://example.com/server.json").unwrap();
+        let error = source.load().unwrap_err().to_string();
+        assert!(error.contains("must be served over HTTPS"), "{error}");
+    }
+
+    #[test]
+    fn test_parse_manifest_and_registry_wrapper() {
+        let manifest = ServerManifest::from_json(MANIFEST).unwrap();
+        assert_eq!(manifest.short_name(), "weather");
+        assert_eq!(manifest.packages.len(), 2);
+
+        let wrapped = format!(r#"{{"server": {MANIFEST}}}"#);
+        assert_eq!(ServerManifest::from_json(&wrapped).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_select_package_prefers_available_runtime() {
+        let manifest = ServerManifest::from_json(MANIFEST).unwrap();
+
+        let package = manifest.select_package_with(|cmd| cmd == "npx").unwrap();
+        assert_eq!(package.registry_type, "npm");
+
+        let package = manifest.select_package_with(|_| true).unwrap();
+        assert_eq!(package.registry_type, "oci");
+
+        assert!(manifest.select_package_with(|_| false).is_none());
+    }
+
+    #[test]
+    fn test_npm_package_config() {
+        let manifest = ServerManifest::from_json(MANIFEST).unwrap();
+        let package = &manifest.packages[1];
+        let values = HashMap::from([("WEATHER_API_KEY".to_string(), "secret".to_string())]);
+
+        let config = package.to_server_config(&values).unwrap();
+        assert_eq!(config.command, "npx");
+        assert_eq!(
+            config.args,
+            vec![
+                "-y",
+                "@example/weather@1.2.0",
+                "--units",
+                "metric",
+                "secret"
+            ]
+        );
+        assert_eq!(
+            config.env.get("WEATHER_API_KEY"),
+            Some(&"secret".to_string())
+        );
+        assert!(!config.env.contains_key("WEATHER_REGION"));
+    }
+
+    #[test]
+    fn test_oci_package_config() {
+        let manifest = ServerManifest::from_json(MANIFEST).unwrap();
+        let package = &manifest.packages[0];
+        let values = HashMap::from([("WEATHER_API_KEY".to_string(), "secret".to_string())]);
+
+        let config = package.to_server_config(&values).unwrap();
+        assert_eq!(config.command, "docker");
+        assert_eq!(
+            config.args,
+            vec![
+                "run",
+                "--rm",
+                "-i",
+                "-e",
+                "WEATHER_API_KEY",
+                "example/weather:1.2.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_registry() {
+        let package = ManifestPackage {
+            registry_type: "mcpb".to_string(),
+            identifier: "bundle".to_string(),
+            version: None,
+            runtime_hint: None,
+            transport: None,
+            runtime_arguments: vec![],
+            package_arguments: vec![],
+            environment_variables: vec![],
+        };
+        assert!(package.runtime().is_none());
+        assert!(package.to_server_config(&HashMap::new()).is_err());
+    }
+}
@@ -29,14 +29,24 @@ pub struct PlatformSupport {
     pub windows: bool,
     pub macos: bool,
     pub linux: bool,
+    /// Architectures in npm's `cpu` notation (`x64`, `!arm`); empty means any
+    #[serde(default)]
+    pub cpu: Vec<String>,
     pub min_node_version: Option<String>,
     pub min_python_version: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UsageExample {
     pub title: String,
     pub description: Option<String>,
+    /// Something to ask the assistant once the server is running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// A tool call the server answers, e.g. `search_issues {"query": "bug"}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(default)]
     pub config: HashMap<String, String>,
 }
 
@@ -52,6 +62,8 @@ struct PackageJson {
     pub license: Option<String>,
     pub keywords: Option<Vec<String>>,
     pub engines: Option<PackageEngines>,
+    pub os: Option<Vec<String>>,
+    pub cpu: Option<Vec<String>>,
     pub mcp: Option<McpConfig>,
 }
 
@@ -80,6 +92,9 @@ pub struct RegistryEntry {
     pub popularity_score: f64,
     pub last_updated: String,
     pub verified: bool,
+    /// Platforms the server is restricted to, when it declares any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_support: Option<PlatformSupport>,
 }
 
 /// Parameters for creating a registry entry
@@ -187,7 +202,7 @@ impl MetadataLoader {
     ) -> Result<ExtendedServerMetadata> {
         let author = Self::extract_json_field_as_string(&package.author, "name");
         let repository = Self::extract_json_field_as_string(&package.repository, "url");
-        let platform_support = Self::create_platform_support(&package.engines);
+        let platform_support = Self::create_platform_support(&package);
         let server_type = ServerType::Npm {
             package: package.name.clone(),
             version: Some(package.version.clone()),
@@ -227,13 +242,15 @@ impl MetadataLoader {
         }
     }
 
-    fn create_platform_support(engines: &Option<PackageEngines>) -> PlatformSupport {
+    fn create_platform_support(package: &PackageJson) -> PlatformSupport {
+        let engines = &package.engines;
         PlatformSupport {
-            windows: true, // Assume all platforms unless specified otherwise
-            macos: true,
-            linux: true,
             min_node_version: engines.as_ref().and_then(|e| e.node.clone()),
             min_python_version: engines.as_ref().and_then(|e| e.python.clone()),
+            ..PlatformSupport::from_npm(
+                package.os.as_deref().unwrap_or_default(),
+                package.cpu.as_deref().unwrap_or_default(),
+            )
         }
     }
 
@@ -338,6 +355,7 @@ impl MetadataLoader {
             popularity_score: params.popularity_score,
             last_updated: params.last_updated.to_string(),
             verified: params.verified,
+            platform_support: None,
         }
     }
 }
@@ -494,6 +512,31 @@ mod tests {
             .contains("package.json not found"));
     }
 
+    #[test]
+    fn test_load_platforms_from_package_json() {
+        let temp_dir = TempDir::new().unwrap();
+        let package_json = json!({
+            "name": "mac-mcp-server",
+            "version": "1.0.0",
+            "os": ["darwin"],
+            "cpu": ["arm64", "x64"]
+        });
+        fs::write(
+            temp_dir.path().join("package.json"),
+            serde_json::to_string_pretty(&package_json).unwrap(),
+        )
+        .unwrap();
+
+        let mut loader = MetadataLoader::new();
+        let support = loader
+            .load_from_package_json(temp_dir.path())
+            .unwrap()
+            .platform_support;
+        assert!(support.macos);
+        assert!(!support.linux && !support.windows);
+        assert_eq!(support.cpu, vec!["arm64", "x64"]);
+    }
+
     #[test]
     fn test_platform_support_default() {
         let support = PlatformSupport::default();
@@ -509,6 +552,8 @@ mod tests {
         let example = UsageExample {
             title: "Test Example".to_string(),
             description: Some("A test example".to_string()),
+            prompt: None,
+            tool: None,
             config: {
                 let mut config = HashMap::new();
                 config.insert("key".to_string(), "value".to_string());
@@ -1,7 +1,7 @@
 //! MCP server implementations and types.
 //!
 //! This module provides support for different types of MCP servers including NPM packages,
-//! Docker images, GitHub repositories (binaries), and Python packages. Each server type
+//! Docker images, GitHub repositories (binaries), Python packages, Rust crates, Deno scripts, JAR files, and .NET tools. Each server type
 //! has specific installation and configuration requirements.
 //!
 //! # Examples
@@ -50,11 +50,28 @@
 //! }
 //! ```
 
+pub mod archive;
+pub mod args_template;
 pub mod binary;
+pub mod cargo;
+pub mod deno;
 pub mod docker;
+pub mod docker_requirements;
+pub mod dotnet;
+pub mod folder_picker;
+pub mod github;
+pub mod install_manifest;
+pub mod jar;
+pub mod manifest;
 pub mod metadata;
+pub mod next_steps;
 pub mod npm;
+pub mod npm_registry;
+pub mod platform;
+pub mod prefetch;
+pub mod publisher;
 pub mod python;
+pub mod scope;
 pub mod suggestions;
 pub mod validation;
 
@@ -64,12 +81,18 @@ use std::collections::HashMap;
 use crate::deps::DependencyChecker;
 
 pub use binary::BinaryServer;
+pub use cargo::CargoServer;
+pub use deno::DenoServer;
 pub use docker::DockerServer;
+pub use dotnet::DotnetToolServer;
+pub use jar::JarServer;
+pub use manifest::{ManifestSource, ServerManifest};
 pub use metadata::{
     ExtendedServerMetadata, MetadataLoader, PlatformSupport, RegistryEntry, UsageExample,
 };
 pub use npm::NpmServer;
 pub use python::PythonServer;
+pub use scope::{configured_directories, is_filesystem_server, FilesystemScope};
 pub use suggestions::{ServerSuggestions, Suggestion, SuggestionFeasibility, SuggestionReason};
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -90,6 +113,20 @@ pub enum ServerType {
         image: String,
         tag: Option<String>,
     },
+    Cargo {
+        crate_name: String,
+        version: Option<String>,
+    },
+    Deno {
+        specifier: String,
+    },
+    Jar {
+        path: String,
+    },
+    Dotnet {
+        package: String,
+        version: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -99,9 +136,12 @@ pub struct ServerMetadata {
     pub server_type: ServerType,
     pub required_config: Vec<ConfigField>,
     pub optional_config: Vec<ConfigField>,
+    /// Arguments appended to the server command, with `{{field}}` placeholders
+    /// filled from the collected configuration (see [`args_template`])
+    pub args_template: Vec<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ConfigField {
     pub name: String,
     pub field_type: ConfigFieldType,
@@ -118,6 +158,62 @@ pub enum ConfigFieldType {
     Url,
 }
 
+/// Builders for declaring a server's config schema, e.g.
+/// `ConfigField::path("config").describe("Config file").default_value("config.toml")`.
+/// [`validation::ConfigValidation::validate_fields`] checks a config against it.
+impl ConfigField {
+    pub fn new(name: &str, field_type: ConfigFieldType) -> Self {
+        Self {
+            name: name.to_string(),
+            field_type,
+            description: None,
+            default: None,
+        }
+    }
+
+    pub fn string(name: &str) -> Self {
+        Self::new(name, ConfigFieldType::String)
+    }
+
+    pub fn number(name: &str) -> Self {
+        Self::new(name, ConfigFieldType::Number)
+    }
+
+    pub fn boolean(name: &str) -> Self {
+        Self::new(name, ConfigFieldType::Boolean)
+    }
+
+    pub fn path(name: &str) -> Self {
+        Self::new(name, ConfigFieldType::Path)
+    }
+
+    pub fn url(name: &str) -> Self {
+        Self::new(name, ConfigFieldType::Url)
+    }
+
+    pub fn describe(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn default_value(mut self, default: impl Into<String>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    /// `working_directory`, shared by servers that run a local command
+    pub fn working_directory() -> Self {
+        Self::path("working_directory").describe("Working directory for the server")
+    }
+
+    /// `timeout` in seconds, shared by servers that run a local command
+    pub fn timeout() -> Self {
+        Self::number("timeout")
+            .describe("Server timeout in seconds")
+            .default_value("30")
+    }
+}
+
 pub trait McpServer: Send + Sync {
     fn metadata(&self) -> &ServerMetadata;
 
@@ -125,9 +221,27 @@ pub trait McpServer: Send + Sync {
 
     fn generate_command(&self) -> Result<(String, Vec<String>)>;
 
+    /// The command for a server configured with `config`.
+    ///
+    /// Servers whose launch command depends on their configuration (Docker
+    /// options, Deno permissions, JVM options) override this.
+    fn generate_command_with_config(
+        &self,
+        _config: &HashMap<String, String>,
+    ) -> Result<(String, Vec<String>)> {
+        self.generate_command()
+    }
+
+    /// Config fields [`Self::generate_command_with_config`] turns into arguments,
+    /// which therefore don't belong in the server's environment
+    fn command_config_fields(&self) -> Vec<String> {
+        Vec::new()
+    }
+
     fn dependency(&self) -> Box<dyn DependencyChecker>;
 }
 
+#[tracing::instrument(level = "debug", ret)]
 pub fn detect_server_type(package: &str) -> ServerType {
     if let Some(stripped) = package.strip_prefix("docker:") {
         let parts: Vec<&str> = stripped.splitn(2, ':').collect();
@@ -138,6 +252,23 @@ pub fn detect_server_type(package: &str) -> ServerType {
                 .map(|s| s.to_string())
                 .or(Some("latest".to_string())),
         }
+    } else if let Some(stripped) = package.strip_prefix("cargo:") {
+        let (crate_name, version) = CargoServer::parse_crate_spec(stripped);
+        ServerType::Cargo {
+            crate_name,
+            version,
+        }
+    } else if let Some(stripped) = package.strip_prefix("dotnet:") {
+        let (package, version) = DotnetToolServer::parse_tool_spec(stripped);
+        ServerType::Dotnet { package, version }
+    } else if let Some(path) = jar::jar_path(package) {
+        ServerType::Jar {
+            path: path.to_string(),
+        }
+    } else if let Some(specifier) = deno::deno_specifier(package) {
+        ServerType::Deno {
+            specifier: specifier.to_string(),
+        }
     } else if package.starts_with("https://") || package.starts_with("http://") {
         ServerType::Binary {
             url: package.to_string(),
@@ -188,5 +319,19 @@ pub fn parse_npm_package(package: &str) -> (String, Option<String>) {
     }
 }
 
+/// Whether `spec` is written as a filesystem path rather than a package name:
+/// `./`, `../`, `~/`, `/`, or a Windows drive or `.\` prefix
+pub fn is_path_spec(spec: &str) -> bool {
+    const PREFIXES: &[&str] = &["./", "../", "~/", "/", ".\\", "..\\"];
+    if PREFIXES.iter().any(|prefix| spec.starts_with(prefix)) {
+        return true;
+    }
+    let bytes = spec.as_bytes();
+    bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/')
+}
+
 #[cfg(test)]
 mod mod_proptest;
@@ -0,0 +1,173 @@
+//! What to do after a server is installed.
+//!
+//! Clients only load new servers after a restart, and a fresh install gives no
+//! hint of what the server is for. [`NextSteps`] lists the clients to restart and
+//! up to [`MAX_EXAMPLES`] usage examples from the package's `mcp.examples`
+//! metadata: prompts to try and tool calls the server answers. `--quiet` skips
+//! the printout; with `--progress-stream` the same information is sent as a
+//! `next_steps` event.
+
+use serde::Serialize;
+use std::fmt;
+
+use crate::server::{npm_registry, UsageExample};
+
+/// Most examples shown after an install
+pub const MAX_EXAMPLES: usize = 3;
+
+/// Clients to restart and examples to try after installing a server
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NextSteps {
+    pub server: String,
+    /// Clients the server was added to, which must restart to load it
+    pub restart: Vec<String>,
+    pub examples: Vec<UsageExample>,
+}
+
+impl NextSteps {
+    /// Next steps for `server`, keeping the most useful of `examples`: those
+    /// with a prompt or tool call come first
+    pub fn new(server: &str, restart: Vec<String>, examples: Vec<UsageExample>) -> Self {
+        let (mut examples, rest): (Vec<_>, Vec<_>) = examples
+            .into_iter()
+            .partition(|example| example.prompt.is_some() || example.tool.is_some());
+        examples.extend(rest);
+        examples.truncate(MAX_EXAMPLES);
+        Self {
+            server: server.to_string(),
+            restart,
+            examples,
+        }
+    }
+}
+
+impl fmt::Display for NextSteps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Next steps:")?;
+        if !self.restart.is_empty() {
+            writeln!(
+                f,
+                "  • Restart {} to load '{}'",
+                self.restart.join(", "),
+                self.server
+            )?;
+        }
+        for example in &self.examples {
+            match &example.prompt {
+                Some(prompt) => writeln!(f, "  • Try asking: \"{prompt}\"")?,
+                None => writeln!(f, "  • {}", example.title)?,
+            }
+            if let Some(tool) = &example.tool {
+                writeln!(f, "    Tool: {tool}")?;
+            }
+            if example.prompt.is_none() {
+                if let Some(description) = &example.description {
+                    writeln!(f, "    {description}")?;
+                }
+            }
+        }
+        writeln!(f, "  • Docs: mcp help-server {}", self.server)
+    }
+}
+
+/// Usage examples `package` publishes in its package.json `mcp` section; none
+/// when the lookup fails
+pub fn npm_examples(package: &str, version: Option<&str>) -> Vec<UsageExample> {
+    match npm_registry::mcp_metadata(package, version) {
+        Ok(mcp) => parse_examples(&mcp),
+        Err(e) => {
+            tracing::debug!("No usage examples for {}: {:#}", package, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Examples in an `mcp` metadata section, skipping malformed entries
+fn parse_examples(mcp: &serde_json::Value) -> Vec<UsageExample> {
+    mcp["examples"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|example| serde_json::from_value(example.clone()).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn example(title: &str, prompt: Option<&str>) -> UsageExample {
+        UsageExample {
+            title: title.to_string(),
+            description: Some(format!("{title} description")),
+            prompt: prompt.map(str::to_string),
+            tool: None,
+            config: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_examples_with_prompts_come_first() {
+        let next_steps = NextSteps::new(
+            "weather",
+            vec!["Cursor".to_string()],
+            vec![
+                example("Setup", None),
+                example("Forecast", Some("What's the weather in Oslo?")),
+                example("Alerts", Some("Any storm warnings today?")),
+                example("Radar", Some("Show the radar for Bergen")),
+            ],
+        );
+        let titles: Vec<&str> = next_steps
+            .examples
+            .iter()
+            .map(|e| e.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Forecast", "Alerts", "Radar"]);
+    }
+
+    #[test]
+    fn test_display() {
+        let mut forecast = example("Forecast", Some("What's the weather in Oslo?"));
+        forecast.tool = Some(r#"get_forecast {"city": "Oslo"}"#.to_string());
+        let next_steps = NextSteps::new(
+            "weather",
+            vec!["Claude Desktop".to_string(), "Cursor".to_string()],
+            vec![forecast, example("Setup", None)],
+        );
+
+        let text = next_steps.to_string();
+        assert!(text.contains("Restart Claude Desktop, Cursor to load 'weather'"));
+        assert!(text.contains("Try asking: \"What's the weather in Oslo?\""));
+        assert!(text.contains(r#"Tool: get_forecast {"city": "Oslo"}"#));
+        assert!(text.contains("• Setup\n    Setup description"));
+        assert!(text.contains("mcp help-server weather"));
+    }
+
+    #[test]
+    fn test_parse_examples() {
+        let mcp = json!({
+            "examples": [
+                { "title": "Forecast", "prompt": "Weather in Oslo?" },
+                { "description": "missing title" },
+                { "title": "Setup", "config": { "API_KEY": "demo" } }
+            ]
+        });
+        let examples = parse_examples(&mcp);
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].prompt.as_deref(), Some("Weather in Oslo?"));
+        assert_eq!(examples[1].config["API_KEY"], "demo");
+        assert!(parse_examples(&serde_json::Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_serializes_structurally() {
+        let next_steps = NextSteps::new("weather", vec!["Cursor".to_string()], Vec::new());
+        let value = serde_json::to_value(&next_steps).unwrap();
+        assert_eq!(
+            value,
+            json!({ "server": "weather", "restart": ["Cursor"], "examples": [] })
+        );
+    }
+}
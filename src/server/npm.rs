@@ -1,10 +1,38 @@
+use crate::cache::CacheManager;
+use crate::config::Preferences;
 use crate::deps::Dependency;
 use crate::server::{detect_server_type, ConfigField, McpServer, ServerMetadata, ServerType};
 use anyhow::Result;
 use std::collections::HashMap;
 
-#[cfg(test)]
-use crate::server::ConfigFieldType;
+/// npm options from preferences for generated `npx` commands (see
+/// [`crate::config::NpmPolicy`]); unreadable preferences add none.
+pub fn npx_preference_flags() -> Vec<String> {
+    let policy = match Preferences::load() {
+        Ok(prefs) => prefs.npm,
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable preferences: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let cache_dir = if policy.shared_cache {
+        CacheManager::npm_cache_dir()
+            .map_err(|e| tracing::warn!("No npm cache directory: {}", e))
+            .ok()
+    } else {
+        None
+    };
+    policy.npx_flags(cache_dir.as_deref())
+}
+
+/// Argument templates for well-known packages that take configuration as arguments
+fn known_args_template(package: &str) -> Vec<String> {
+    match package {
+        "@modelcontextprotocol/server-filesystem" => vec!["{{allowedDirectories}}".to_string()],
+        _ => Vec::new(),
+    }
+}
 
 #[derive(Debug)]
 pub struct NpmServer {
@@ -27,6 +55,7 @@ impl NpmServer {
                     },
                     required_config: vec![],
                     optional_config: vec![],
+                    args_template: known_args_template(&package),
                 };
 
                 Ok(Self {
@@ -49,6 +78,7 @@ impl NpmServer {
             },
             required_config: vec![],
             optional_config: vec![],
+            args_template: known_args_template(&package),
         };
 
         Self {
@@ -100,7 +130,11 @@ impl McpServer for NpmServer {
     fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
         use super::validation::ConfigValidation;
 
-        ConfigValidation::validate_required_fields(config, &self.metadata.required_config)?;
+        ConfigValidation::validate_fields(
+            config,
+            &self.metadata.required_config,
+            &self.metadata.optional_config,
+        )?;
 
         Ok(())
     }
@@ -109,13 +143,14 @@ impl McpServer for NpmServer {
         let npx_cmd = self.get_npx_command();
         let package_arg = self.build_package_arg();
 
-        // Basic npx arguments
-        let mut args = vec![
+        // npm options from preferences, then the basic npx arguments
+        let mut args = npx_preference_flags();
+        args.extend([
             // Ensure package is installed/updated
             "--yes".to_string(),
             // The package to run
             package_arg,
-        ];
+        ]);
 
         // Add stdio transport for MCP
         args.push("--stdio".to_string());
@@ -138,6 +173,7 @@ mod tests {
         let server = NpmServer::new("@modelcontextprotocol/server-filesystem").unwrap();
         assert_eq!(server.package, "@modelcontextprotocol/server-filesystem");
         assert_eq!(server.version, None);
+        assert_eq!(server.metadata.args_template, ["{{allowedDirectories}}"]);
     }
 
     #[test]
@@ -185,15 +221,8 @@ mod tests {
 
     #[test]
     fn test_validate_config_required_field() {
-        let server = NpmServer::from_package("test".to_string(), None).with_config(
-            vec![ConfigField {
-                name: "api_key".to_string(),
-                field_type: ConfigFieldType::String,
-                description: None,
-                default: None,
-            }],
-            vec![],
-        );
+        let server = NpmServer::from_package("test".to_string(), None)
+            .with_config(vec![ConfigField::string("api_key")], vec![]);
 
         let mut config = HashMap::new();
         assert!(server.validate_config(&config).is_err());
@@ -204,15 +233,8 @@ mod tests {
 
     #[test]
     fn test_validate_config_number_field() {
-        let server = NpmServer::from_package("test".to_string(), None).with_config(
-            vec![ConfigField {
-                name: "port".to_string(),
-                field_type: ConfigFieldType::Number,
-                description: None,
-                default: None,
-            }],
-            vec![],
-        );
+        let server = NpmServer::from_package("test".to_string(), None)
+            .with_config(vec![ConfigField::number("port")], vec![]);
 
         let mut config = HashMap::new();
         config.insert("port".to_string(), "not-a-number".to_string());
@@ -224,15 +246,8 @@ mod tests {
 
     #[test]
     fn test_validate_config_url_field() {
-        let server = NpmServer::from_package("test".to_string(), None).with_config(
-            vec![ConfigField {
-                name: "endpoint".to_string(),
-                field_type: ConfigFieldType::Url,
-                description: None,
-                default: None,
-            }],
-            vec![],
-        );
+        let server = NpmServer::from_package("test".to_string(), None)
+            .with_config(vec![ConfigField::url("endpoint")], vec![]);
 
         let mut config = HashMap::new();
         config.insert("endpoint".to_string(), "not-a-url".to_string());
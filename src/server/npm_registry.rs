@@ -0,0 +1,265 @@
+//! npm registry lookups shared by every command that needs package metadata.
+//!
+//! Responses go through the registry cache in [`crate::cache`] unless
+//! `--no-cache` was given. Only the fields callers use are kept, since full
+//! packuments of busy packages run to megabytes. Throttled requests back off
+//! (see [`crate::utils::rate_limit`]).
+
+use anyhow::{Context, Result};
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::cache::{registry_cache_enabled, CacheManager, RegistryResponse};
+use crate::utils::rate_limit::{self, Api};
+
+pub const NPM_REGISTRY_URL: &str = "https://registry.npmjs.org";
+
+/// Largest page the registry search API returns
+const SEARCH_PAGE_SIZE: usize = 250;
+
+/// A package found by a scope search
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ScopePackage {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+fn http_client() -> Result<Client> {
+    Client::builder()
+        .timeout(Duration::from_secs(15))
+        .user_agent("mcp-helper")
+        .build()
+        .context("Failed to create HTTP client")
+}
+
+/// Registry metadata of `package`: its name, dist-tags and repository
+pub fn packument(package: &str) -> Result<Value> {
+    let url = format!("{NPM_REGISTRY_URL}/{}", package.replace('/', "%2F"));
+    registry_json(package, &url, |full| {
+        json!({
+            "name": full["name"],
+            "dist-tags": full["dist-tags"],
+            "repository": full["repository"],
+        })
+    })
+    .with_context(|| format!("npm registry lookup for {package} failed"))
+}
+
+/// What `mcp info` shows about `package`: its description, dist-tags, license,
+/// links and README, and the dependencies, engines and `mcp` section of `latest`
+pub fn package_details(package: &str) -> Result<Value> {
+    let url = format!("{NPM_REGISTRY_URL}/{}", package.replace('/', "%2F"));
+    registry_json(&format!("details:{package}"), &url, |full| {
+        let latest = full["dist-tags"]["latest"]
+            .as_str()
+            .map(|version| &full["versions"][version])
+            .unwrap_or(&Value::Null);
+        json!({
+            "name": full["name"],
+            "description": full["description"],
+            "dist-tags": full["dist-tags"],
+            "license": full["license"],
+            "homepage": full["homepage"],
+            "repository": full["repository"],
+            "readme": full["readme"],
+            "latest": {
+                "version": latest["version"],
+                "dependencies": latest["dependencies"],
+                "engines": latest["engines"],
+                "mcp": latest["mcp"],
+            },
+        })
+    })
+    .with_context(|| format!("npm registry lookup for {package} failed"))
+}
+
+/// Registry metadata of one published version (`latest` when `version` is `None`):
+/// its name, version, `dist` (tarball integrity, signatures, attestations) and
+/// the `os` and `cpu` it is restricted to
+pub fn version_metadata(package: &str, version: Option<&str>) -> Result<Value> {
+    let version = version.unwrap_or("latest");
+    let url = format!(
+        "{NPM_REGISTRY_URL}/{}/{version}",
+        package.replace('/', "%2F")
+    );
+    registry_json(&format!("version:{package}@{version}"), &url, |full| {
+        json!({
+            "name": full["name"],
+            "version": full["version"],
+            "dist": full["dist"],
+            "os": full["os"],
+            "cpu": full["cpu"],
+        })
+    })
+    .with_context(|| format!("npm registry lookup for {package}@{version} failed"))
+}
+
+/// The `mcp` section of a published version's package.json (config schema and
+/// usage examples), or `null` when it has none
+pub fn mcp_metadata(package: &str, version: Option<&str>) -> Result<Value> {
+    let version = version.unwrap_or("latest");
+    let url = format!(
+        "{NPM_REGISTRY_URL}/{}/{version}",
+        package.replace('/', "%2F")
+    );
+    registry_json(&format!("mcp:{package}@{version}"), &url, |mut full| {
+        full["mcp"].take()
+    })
+    .with_context(|| format!("npm registry lookup for {package}@{version} failed"))
+}
+
+/// The registry's current signing keys
+pub fn signing_keys() -> Result<Value> {
+    let url = format!("{NPM_REGISTRY_URL}/-/npm/v1/keys");
+    registry_json("keys", &url, |full| full).context("npm registry signing key lookup failed")
+}
+
+/// The attestations bundle at `url`, as linked from a version's `dist.attestations`
+pub fn attestations(package: &str, version: &str, url: &str) -> Result<Value> {
+    if !url.starts_with(NPM_REGISTRY_URL) {
+        anyhow::bail!("Attestations for {package}@{version} are not hosted on the npm registry");
+    }
+    registry_json(&format!("attestations:{package}@{version}"), url, |full| {
+        full
+    })
+    .with_context(|| format!("npm attestation lookup for {package}@{version} failed"))
+}
+
+/// The scope in a wildcard spec like `@myorg/*`
+pub fn scope_wildcard(spec: &str) -> Option<&str> {
+    let scope = spec.strip_suffix("/*")?;
+    (scope.len() > 1 && scope.starts_with('@') && !scope[1..].contains(['/', '*'])).then_some(scope)
+}
+
+/// Packages published under `scope` (e.g. `@myorg`) with an `mcp` keyword
+pub fn search_scope(scope: &str) -> Result<Vec<ScopePackage>> {
+    let name = scope.trim_start_matches('@');
+    let url = format!(
+        "{NPM_REGISTRY_URL}/-/v1/search?text=scope:{name}%20keywords:mcp&size={SEARCH_PAGE_SIZE}"
+    );
+    let results = registry_json(&format!("search:{scope}"), &url, |full| {
+        let packages: Vec<Value> = full["objects"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|object| object["package"].clone())
+            .collect();
+        json!(packages)
+    })
+    .with_context(|| format!("npm registry search for {scope} failed"))?;
+
+    let packages: Vec<ScopePackage> =
+        serde_json::from_value(results).context("Failed to parse npm search results")?;
+    Ok(mcp_packages_in_scope(scope, packages))
+}
+
+/// Keep packages that really are in `scope` and carry an `mcp` keyword, sorted by name
+pub fn mcp_packages_in_scope(scope: &str, packages: Vec<ScopePackage>) -> Vec<ScopePackage> {
+    let prefix = format!("{scope}/");
+    let mut packages: Vec<ScopePackage> = packages
+        .into_iter()
+        .filter(|package| package.name.starts_with(&prefix))
+        .filter(|package| {
+            package
+                .keywords
+                .iter()
+                .any(|keyword| keyword.to_lowercase().contains("mcp"))
+        })
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    packages.dedup_by(|a, b| a.name == b.name);
+    packages
+}
+
+/// GET `url` as JSON, cached under `key`; `trim` keeps what is worth caching
+fn registry_json(key: &str, url: &str, trim: impl Fn(Value) -> Value) -> Result<Value> {
+    match CacheManager::new() {
+        Ok(mut cache) if registry_cache_enabled() => {
+            cache.npm_metadata(key, |etag| request(url, etag, &trim))
+        }
+        _ => request(url, None, &trim)?.into_body(),
+    }
+}
+
+/// Request `url`, revalidating `etag` if given
+fn request(
+    url: &str,
+    etag: Option<&str>,
+    trim: impl Fn(Value) -> Value,
+) -> Result<RegistryResponse> {
+    let client = http_client()?;
+    let response = rate_limit::send(Api::NpmRegistry, || {
+        let request = client.get(url);
+        match etag {
+            Some(etag) => request.header(reqwest::header::IF_NONE_MATCH, etag),
+            None => request,
+        }
+    })
+    .context("Failed to query npm registry")?;
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED && etag.is_some() {
+        return Ok(RegistryResponse::NotModified);
+    }
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let full: Value = response
+        .json()
+        .context("Failed to parse npm registry response")?;
+    Ok(RegistryResponse::Fetched {
+        body: trim(full),
+        etag,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str, keywords: &[&str]) -> ScopePackage {
+        ScopePackage {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: None,
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_scope_wildcard() {
+        assert_eq!(scope_wildcard("@myorg/*"), Some("@myorg"));
+        assert_eq!(scope_wildcard("@myorg/server"), None);
+        assert_eq!(scope_wildcard("myorg/*"), None);
+        assert_eq!(scope_wildcard("@/*"), None);
+        assert_eq!(scope_wildcard("@a/b/*"), None);
+    }
+
+    #[test]
+    fn test_mcp_packages_in_scope() {
+        let packages = mcp_packages_in_scope(
+            "@myorg",
+            vec![
+                package("@myorg/weather", &["MCP", "weather"]),
+                package("@myorg/utils", &["lodash"]),
+                package("@other/server", &["mcp"]),
+                package("@myorg/github", &["mcp-server"]),
+                package("@myorg/github", &["mcp-server"]),
+            ],
+        );
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["@myorg/github", "@myorg/weather"]);
+    }
+}
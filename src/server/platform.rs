@@ -0,0 +1,297 @@
+//! Operating systems and architectures a server supports.
+//!
+//! npm packages declare them in package.json's `os` and `cpu` fields, e.g.
+//! `"os": ["darwin", "linux"]` or `"cpu": ["!arm"]`. npm itself refuses such a
+//! package only when it's installed globally; `npx` runs it anyway and the server
+//! fails later with a native module error. Installs check the declaration up front
+//! and offer alternatives that do run here.
+
+use anyhow::Result;
+use colored::Colorize;
+use serde_json::Value;
+use std::fmt;
+
+use crate::server::npm_registry;
+use crate::server::{PlatformSupport, ServerSuggestions, ServerType};
+
+/// An operating system and architecture, named the way npm names them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    /// `darwin`, `linux`, `win32`, ...
+    pub os: String,
+    /// `x64`, `arm64`, `ia32`, ...
+    pub arch: String,
+}
+
+impl Platform {
+    pub fn new(os: &str, arch: &str) -> Self {
+        Self {
+            os: os.to_string(),
+            arch: arch.to_string(),
+        }
+    }
+
+    /// The platform mcp-helper is running on
+    pub fn current() -> Self {
+        let os = match std::env::consts::OS {
+            "macos" => "darwin",
+            "windows" => "win32",
+            other => other,
+        };
+        let arch = match std::env::consts::ARCH {
+            "x86_64" => "x64",
+            "aarch64" => "arm64",
+            "x86" => "ia32",
+            "powerpc64" => "ppc64",
+            other => other,
+        };
+        Self::new(os, arch)
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.os, self.arch)
+    }
+}
+
+/// A server that declares it doesn't run on this platform
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlatformMismatch {
+    pub server: String,
+    pub platform: Platform,
+    /// What the server supports, e.g. "darwin, linux (x64)"
+    pub supported: String,
+}
+
+impl PlatformMismatch {
+    /// The mismatch without the hint on overriding it
+    pub fn summary(&self) -> String {
+        format!(
+            "{} doesn't support {} (it supports {})",
+            self.server, self.platform, self.supported
+        )
+    }
+}
+
+impl fmt::Display for PlatformMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}; pass --ignore-platform to install it anyway",
+            self.summary()
+        )
+    }
+}
+
+impl std::error::Error for PlatformMismatch {}
+
+impl PlatformSupport {
+    /// Support declared by npm's `os` and `cpu` lists, where `!name` excludes a
+    /// platform and an empty list allows every one
+    pub fn from_npm(os: &[String], cpu: &[String]) -> Self {
+        Self {
+            windows: npm_list_allows(os, "win32"),
+            macos: npm_list_allows(os, "darwin"),
+            linux: npm_list_allows(os, "linux"),
+            cpu: cpu.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether a server with this support runs on `platform`
+    pub fn supports(&self, platform: &Platform) -> bool {
+        let os = match platform.os.as_str() {
+            "win32" => self.windows,
+            "darwin" => self.macos,
+            "linux" => self.linux,
+            _ => true,
+        };
+        os && npm_list_allows(&self.cpu, &platform.arch)
+    }
+
+    /// The supported systems and architectures, e.g. "darwin, linux (x64)"
+    pub fn describe(&self) -> String {
+        let systems: Vec<&str> = [
+            (self.macos, "darwin"),
+            (self.linux, "linux"),
+            (self.windows, "win32"),
+        ]
+        .into_iter()
+        .filter_map(|(supported, name)| supported.then_some(name))
+        .collect();
+        let systems = if systems.is_empty() {
+            "no known system".to_string()
+        } else {
+            systems.join(", ")
+        };
+        if self.cpu.is_empty() {
+            systems
+        } else {
+            format!("{systems} ({})", self.cpu.join(", "))
+        }
+    }
+}
+
+/// Whether an npm `os` or `cpu` list allows `name`
+fn npm_list_allows(list: &[String], name: &str) -> bool {
+    if list
+        .iter()
+        .any(|entry| entry.strip_prefix('!') == Some(name))
+    {
+        return false;
+    }
+    let allowed: Vec<&String> = list
+        .iter()
+        .filter(|entry| !entry.starts_with('!'))
+        .collect();
+    allowed.is_empty() || allowed.iter().any(|entry| entry.as_str() == name)
+}
+
+/// The platforms an npm package version declares, if it restricts them at all
+pub fn npm_platform_support(
+    package: &str,
+    version: Option<&str>,
+) -> Result<Option<PlatformSupport>> {
+    let metadata = npm_registry::version_metadata(package, version)?;
+    let list = |field: &str| -> Vec<String> {
+        match &metadata[field] {
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect(),
+            Value::String(item) => vec![item.clone()],
+            _ => Vec::new(),
+        }
+    };
+    let (os, cpu) = (list("os"), list("cpu"));
+    if os.is_empty() && cpu.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(PlatformSupport::from_npm(&os, &cpu)))
+}
+
+/// Check that the server `server_type` installs runs here. Only npm packages
+/// declare their platforms; a failed lookup is logged and not held against them
+pub fn check_platform(server_type: &ServerType) -> Result<(), PlatformMismatch> {
+    let ServerType::Npm { package, version } = server_type else {
+        return Ok(());
+    };
+    let support = match npm_platform_support(package, version.as_deref()) {
+        Ok(Some(support)) => support,
+        Ok(None) => return Ok(()),
+        Err(e) => {
+            tracing::debug!("Platform check for {} failed: {:#}", package, e);
+            return Ok(());
+        }
+    };
+    ensure_supported(package, &support, &Platform::current())
+}
+
+/// Check the server `server_type` installs against this platform. A mismatch
+/// lists servers that do run here and fails unless `ignore` is set
+pub fn enforce_platform(server_type: &ServerType, ignore: bool) -> Result<(), PlatformMismatch> {
+    let Err(mismatch) = check_platform(server_type) else {
+        return Ok(());
+    };
+    println!("{} {}", "⚠".yellow(), mismatch.summary().yellow());
+    print_platform_alternatives(&mismatch);
+    if ignore {
+        println!("  {} Installing anyway (--ignore-platform)", "ℹ".blue());
+        return Ok(());
+    }
+    Err(mismatch)
+}
+
+fn print_platform_alternatives(mismatch: &PlatformMismatch) {
+    let alternatives = ServerSuggestions::new().suggest_platform_alternatives(&mismatch.server);
+    if alternatives.is_empty() {
+        return;
+    }
+    println!(
+        "\n{} Servers that run on {}:",
+        "💡".blue(),
+        mismatch.platform.to_string().cyan()
+    );
+    for (i, suggestion) in alternatives.iter().enumerate() {
+        println!(
+            "  {}. {} - {}",
+            i + 1,
+            suggestion.server.name.cyan(),
+            suggestion.server.description
+        );
+        println!(
+            "     {} mcp add {}",
+            "📦".blue(),
+            suggestion.server.package_name
+        );
+    }
+    println!();
+}
+
+/// Fail with a [`PlatformMismatch`] unless `support` includes `platform`
+pub fn ensure_supported(
+    server: &str,
+    support: &PlatformSupport,
+    platform: &Platform,
+) -> Result<(), PlatformMismatch> {
+    if support.supports(platform) {
+        return Ok(());
+    }
+    Err(PlatformMismatch {
+        server: server.to_string(),
+        platform: platform.clone(),
+        supported: support.describe(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|item| item.to_string()).collect()
+    }
+
+    #[test]
+    fn test_npm_lists() {
+        let support = PlatformSupport::from_npm(&strings(&["darwin", "linux"]), &[]);
+        assert!(support.supports(&Platform::new("linux", "arm64")));
+        assert!(!support.supports(&Platform::new("win32", "x64")));
+        // Systems npm knows but the declaration doesn't mention aren't held against
+        assert!(support.supports(&Platform::new("freebsd", "x64")));
+
+        let support = PlatformSupport::from_npm(&strings(&["!win32"]), &strings(&["!arm"]));
+        assert!(support.supports(&Platform::new("darwin", "arm64")));
+        assert!(!support.supports(&Platform::new("linux", "arm")));
+        assert!(!support.supports(&Platform::new("win32", "x64")));
+
+        let support = PlatformSupport::from_npm(&[], &strings(&["x64"]));
+        assert!(support.supports(&Platform::new("win32", "x64")));
+        assert!(!support.supports(&Platform::new("darwin", "arm64")));
+        assert_eq!(support.describe(), "darwin, linux, win32 (x64)");
+    }
+
+    #[test]
+    fn test_ensure_supported() {
+        let support = PlatformSupport::from_npm(&strings(&["darwin"]), &strings(&["arm64"]));
+        assert!(
+            ensure_supported("mac-server", &support, &Platform::new("darwin", "arm64")).is_ok()
+        );
+
+        let error =
+            ensure_supported("mac-server", &support, &Platform::new("linux", "x64")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "mac-server doesn't support linux-x64 (it supports darwin (arm64)); \
+             pass --ignore-platform to install it anyway"
+        );
+    }
+
+    #[test]
+    fn test_current_platform_uses_npm_names() {
+        let platform = Platform::current();
+        assert!(!["macos", "windows"].contains(&platform.os.as_str()));
+        assert!(!["x86_64", "aarch64"].contains(&platform.arch.as_str()));
+    }
+}
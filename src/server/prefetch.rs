@@ -0,0 +1,456 @@
+//! Parallel downloads ahead of a batch install.
+//!
+//! A batch is installed one server at a time, so several binaries or Docker images
+//! would otherwise be downloaded one after another. [`Prefetcher`] starts those
+//! downloads together before the first server is configured: binaries land in the
+//! download cache that [`BinaryServer::download_and_install`](super::BinaryServer::download_and_install)
+//! reads from, and images are pulled into the local Docker image store.
+//!
+//! Limits come from the `downloads` preferences ([`DownloadPolicy`]):
+//!
+//! - At most `max_concurrent` downloads run at once
+//! - Binary downloads share one `max_bytes_per_sec` budget (Docker manages its own pulls)
+//! - Each download is retried on its own, so a failure never holds up the others
+//!
+//! Prefetching is speculative. A download that still fails is reported and then
+//! attempted again by the normal install of that server.
+
+use anyhow::{Context, Result};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use reqwest::blocking::Client;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cache::CacheManager;
+use crate::config::DownloadPolicy;
+use crate::server::docker::ensure_image_space;
+use crate::server::{BinaryServer, ServerType};
+use crate::utils::disk_space;
+use crate::utils::process::{Process, INSTALL_TIMEOUT};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Something a batch install would otherwise download while installing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PrefetchItem {
+    /// A binary stored in the download cache
+    Binary { url: String },
+    /// A Docker image pulled as `image:tag`
+    Docker { image: String },
+}
+
+impl PrefetchItem {
+    /// The download `server_type` needs, if any
+    pub fn from_server_type(server_type: &ServerType) -> Option<Self> {
+        match server_type {
+            // Release pages are resolved to an asset only when the server is installed
+            ServerType::Binary { url, .. } if BinaryServer::github_release_spec(url).is_some() => {
+                None
+            }
+            ServerType::Binary { url, .. } => Some(Self::Binary { url: url.clone() }),
+            ServerType::Docker { image, tag } => Some(Self::Docker {
+                image: match tag {
+                    Some(tag) => format!("{image}:{tag}"),
+                    None => image.clone(),
+                },
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Binary { url } => url
+                .rsplit('/')
+                .next()
+                .and_then(|name| name.split('?').next())
+                .filter(|name| !name.is_empty())
+                .unwrap_or(url),
+            Self::Docker { image } => image,
+        }
+    }
+}
+
+/// How one prefetch ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefetchResult {
+    pub item: PrefetchItem,
+    /// Attempts made, including the successful one
+    pub attempts: u32,
+    /// The last error, if every attempt failed
+    pub error: Option<String>,
+}
+
+impl PrefetchResult {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Bandwidth budget shared by every download thread
+pub struct RateLimiter {
+    bytes_per_sec: Option<u64>,
+    /// When the first bytes were counted, and how many have been counted since
+    state: Mutex<Option<(Instant, u64)>>,
+}
+
+impl RateLimiter {
+    /// A limiter allowing `bytes_per_sec` in total; `None` or 0 means unlimited
+    pub fn new(bytes_per_sec: Option<u64>) -> Self {
+        Self {
+            bytes_per_sec: bytes_per_sec.filter(|rate| *rate > 0),
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Count `bytes` against the budget, sleeping until they fit within it
+    pub fn consume(&self, bytes: u64) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            thread::sleep(wait);
+        }
+    }
+
+    /// Count `bytes` and return how long the caller must wait before using them
+    fn reserve(&self, bytes: u64) -> Duration {
+        let Some(rate) = self.bytes_per_sec else {
+            return Duration::ZERO;
+        };
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let (start, counted) = state.get_or_insert_with(|| (Instant::now(), 0));
+        *counted += bytes;
+        let due = Duration::from_secs_f64(*counted as f64 / rate as f64);
+        due.saturating_sub(start.elapsed())
+    }
+}
+
+/// Runs downloads in parallel within a [`DownloadPolicy`]
+pub struct Prefetcher {
+    policy: DownloadPolicy,
+    downloads_dir: PathBuf,
+    /// Wait before the first retry; later retries wait proportionally longer
+    retry_delay: Duration,
+}
+
+impl Prefetcher {
+    pub fn new(policy: DownloadPolicy, downloads_dir: PathBuf) -> Self {
+        Self {
+            policy,
+            downloads_dir,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// Download every item, returning one result per item in the same order
+    pub fn run(&self, items: Vec<PrefetchItem>) -> Vec<PrefetchResult> {
+        self.run_with(items, |item, bar, limiter| match item {
+            PrefetchItem::Binary { url } => download(url, &self.downloads_dir, bar, limiter),
+            PrefetchItem::Docker { image } => pull(image, bar),
+        })
+    }
+
+    fn run_with<F>(&self, items: Vec<PrefetchItem>, fetch: F) -> Vec<PrefetchResult>
+    where
+        F: Fn(&PrefetchItem, &ProgressBar, &RateLimiter) -> Result<()> + Sync,
+    {
+        let total = items.len();
+        let workers = self.policy.max_concurrent().min(total);
+        let limiter = RateLimiter::new(self.policy.max_bytes_per_sec);
+        let progress = MultiProgress::new();
+        let queue: Mutex<VecDeque<(usize, PrefetchItem)>> =
+            Mutex::new(items.into_iter().enumerate().collect());
+        let results: Mutex<Vec<Option<PrefetchResult>>> = Mutex::new(vec![None; total]);
+
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    let next = queue.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+                    let Some((index, item)) = next else { break };
+                    let bar = progress.add(ProgressBar::new_spinner());
+                    let result = self.fetch_with_retries(item, &bar, &limiter, &fetch);
+                    results.lock().unwrap_or_else(|e| e.into_inner())[index] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    fn fetch_with_retries<F>(
+        &self,
+        item: PrefetchItem,
+        bar: &ProgressBar,
+        limiter: &RateLimiter,
+        fetch: &F,
+    ) -> PrefetchResult
+    where
+        F: Fn(&PrefetchItem, &ProgressBar, &RateLimiter) -> Result<()>,
+    {
+        let label = item.label().to_string();
+        let retries = self.policy.retries();
+        bar.set_message(label.clone());
+        bar.enable_steady_tick(Duration::from_millis(100));
+
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match fetch(&item, bar, limiter) {
+                Ok(()) => {
+                    bar.finish_with_message(format!("✓ {label}"));
+                    return PrefetchResult {
+                        item,
+                        attempts,
+                        error: None,
+                    };
+                }
+                Err(e) if attempts <= retries => {
+                    bar.set_message(format!("{label} (retry {attempts}/{retries}: {e})"));
+                    thread::sleep(self.retry_delay * attempts);
+                }
+                Err(e) => {
+                    bar.abandon_with_message(format!("✗ {label}: {e}"));
+                    return PrefetchResult {
+                        item,
+                        attempts,
+                        error: Some(format!("{e:#}")),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Download `url` into the download cache, unless it is already there
+fn download(url: &str, dir: &Path, bar: &ProgressBar, limiter: &RateLimiter) -> Result<()> {
+    let filename = CacheManager::url_to_filename(url);
+    let target = dir.join(&filename);
+    if target.exists() {
+        return Ok(());
+    }
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create download cache {}", dir.display()))?;
+
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .user_agent("mcp-helper")
+        .build()
+        .context("Failed to create HTTP client")?;
+    let mut response = client.get(url).send().context("Failed to start download")?;
+    if !response.status().is_success() {
+        anyhow::bail!("Download failed with status: {}", response.status());
+    }
+
+    disk_space::ensure_space(dir, response.content_length(), url)?;
+    if let Some(length) = response.content_length() {
+        bar.set_length(length);
+        bar.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} {msg} [{bar:30.cyan/blue}] {bytes}/{total_bytes} ({bytes_per_sec})")
+                .unwrap()
+                .progress_chars("#>-"),
+        );
+    }
+
+    // Write next to the target and rename, so an interrupted download is never cached
+    let partial = dir.join(format!("{filename}.part"));
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&partial)
+            .with_context(|| format!("Failed to create file: {}", partial.display()))?;
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            let read = response
+                .read(&mut buf)
+                .context("Failed to read response body")?;
+            if read == 0 {
+                break;
+            }
+            limiter.consume(read as u64);
+            file.write_all(&buf[..read])
+                .context("Failed to write binary data")?;
+            bar.inc(read as u64);
+        }
+        fs::rename(&partial, &target).context("Failed to move download into the cache")
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+/// Pull `image` unless Docker already has it
+fn pull(image: &str, bar: &ProgressBar) -> Result<()> {
+    let present = Process::new("docker")
+        .args(["image", "inspect", image])
+        .output()
+        .context("Failed to execute docker image inspect")?;
+    if present.success() {
+        return Ok(());
+    }
+
+    ensure_image_space(image)?;
+    bar.set_message(format!("{image} (pulling)"));
+    let output = Process::new("docker")
+        .args(["pull", "--quiet", image])
+        .timeout(INSTALL_TIMEOUT)
+        .output()
+        .context("Failed to execute docker pull command")?;
+    if !output.success() {
+        anyhow::bail!("{}", output.stderr_lossy().trim());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    fn binary(name: &str) -> PrefetchItem {
+        PrefetchItem::Binary {
+            url: format!("https://example.com/releases/{name}"),
+        }
+    }
+
+    fn prefetcher(max_concurrent: usize, retries: u32, dir: &Path) -> Prefetcher {
+        let policy = DownloadPolicy {
+            max_concurrent: Some(max_concurrent),
+            max_bytes_per_sec: None,
+            retries: Some(retries),
+        };
+        Prefetcher {
+            retry_delay: Duration::ZERO,
+            ..Prefetcher::new(policy, dir.to_path_buf())
+        }
+    }
+
+    #[test]
+    fn test_items_from_server_types() {
+        let docker = ServerType::Docker {
+            image: "postgres".to_string(),
+            tag: Some("13".to_string()),
+        };
+        assert_eq!(
+            PrefetchItem::from_server_type(&docker),
+            Some(PrefetchItem::Docker {
+                image: "postgres:13".to_string()
+            })
+        );
+        let npm = ServerType::Npm {
+            package: "@modelcontextprotocol/server-github".to_string(),
+            version: None,
+        };
+        assert_eq!(PrefetchItem::from_server_type(&npm), None);
+
+        let item = PrefetchItem::Binary {
+            url: "https://example.com/dl/server-linux?token=1".to_string(),
+        };
+        assert_eq!(item.label(), "server-linux");
+    }
+
+    #[test]
+    fn test_rate_limiter_shares_budget() {
+        let unlimited = RateLimiter::new(None);
+        assert_eq!(unlimited.reserve(1_000_000), Duration::ZERO);
+        assert_eq!(RateLimiter::new(Some(0)).reserve(1_000_000), Duration::ZERO);
+
+        let limiter = RateLimiter::new(Some(1000));
+        let first = limiter.reserve(500);
+        assert!(first <= Duration::from_millis(500) && first > Duration::from_millis(400));
+        // The second caller waits for the first caller's bytes too
+        let second = limiter.reserve(500);
+        assert!(second <= Duration::from_secs(1) && second > Duration::from_millis(900));
+    }
+
+    #[test]
+    fn test_failures_are_isolated_and_retried() {
+        let dir = TempDir::new().unwrap();
+        let calls: Mutex<HashMap<String, u32>> = Mutex::new(HashMap::new());
+        let items = vec![binary("ok"), binary("flaky"), binary("broken")];
+
+        let results = prefetcher(3, 2, dir.path()).run_with(items.clone(), |item, _, _| {
+            let mut calls = calls.lock().unwrap();
+            let count = calls.entry(item.label().to_string()).or_default();
+            *count += 1;
+            match (item.label(), *count) {
+                ("ok", _) | ("flaky", 2) => Ok(()),
+                _ => anyhow::bail!("connection reset"),
+            }
+        });
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results.iter().map(|r| r.item.clone()).collect::<Vec<_>>(),
+            items
+        );
+        assert!(results[0].succeeded());
+        assert_eq!(results[0].attempts, 1);
+        assert!(results[1].succeeded());
+        assert_eq!(results[1].attempts, 2);
+        assert!(!results[2].succeeded());
+        assert_eq!(results[2].attempts, 3);
+        assert_eq!(results[2].error.as_deref(), Some("connection reset"));
+    }
+
+    #[test]
+    fn test_concurrency_is_capped() {
+        let dir = TempDir::new().unwrap();
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let items = (0..6).map(|i| binary(&format!("server-{i}"))).collect();
+
+        let results = prefetcher(2, 0, dir.path()).run_with(items, |_, _, _| {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            active.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        assert_eq!(results.len(), 6);
+        assert!(results.iter().all(PrefetchResult::succeeded));
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_download_into_cache() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/server-bin", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Length: 6\r\nConnection: close\r\n\r\nbinary",
+                )
+                .unwrap();
+        });
+
+        let dir = TempDir::new().unwrap();
+        let results =
+            prefetcher(1, 0, dir.path()).run(vec![PrefetchItem::Binary { url: url.clone() }]);
+        server.join().unwrap();
+
+        assert!(results[0].succeeded(), "{:?}", results[0].error);
+        let cached = dir.path().join(CacheManager::url_to_filename(&url));
+        assert_eq!(fs::read_to_string(cached).unwrap(), "binary");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        // Already cached, so no second request is made
+        let again = prefetcher(1, 0, dir.path()).run(vec![PrefetchItem::Binary { url }]);
+        assert!(again[0].succeeded());
+    }
+}
@@ -0,0 +1,394 @@
+//! Verified publisher checks for npm packages.
+//!
+//! The npm registry signs every tarball it serves and, for packages published from
+//! a CI workflow with `--provenance`, links an attestation bundle whose SLSA
+//! provenance statement names the exact tarball and the repository it was built
+//! from. Before installing, mcp-helper checks that metadata is consistent: every
+//! signature must use one of the registry's published keys, and the provenance
+//! statement must be for this package, this version and this tarball digest.
+//! Signatures themselves are not re-verified cryptographically; `npm audit
+//! signatures` does that for installed dependencies.
+//!
+//! A package with consistent provenance gets a "verified publisher" badge.
+//! Setting `security.require_verified_publisher` in preferences refuses anything
+//! less, and metadata that doesn't match the package always stops the install.
+
+use anyhow::Result;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+use crate::config::Preferences;
+use crate::security::{SecurityFailureClass, SecurityViolation};
+use crate::server::npm_registry;
+
+/// Predicate types of SLSA build provenance start with this
+const SLSA_PROVENANCE_PREFIX: &str = "https://slsa.dev/provenance/";
+
+/// What the registry's signature metadata says about a published version
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum PublisherVerification {
+    /// Provenance ties the tarball to a CI build of `repository`
+    Verified { repository: Option<String> },
+    /// Signed by the registry, without build provenance
+    Signed,
+    /// Neither registry signatures nor provenance
+    Unsigned,
+    /// Signatures or provenance that don't match the package
+    Invalid { reason: String },
+}
+
+impl PublisherVerification {
+    pub fn is_verified(&self) -> bool {
+        matches!(self, Self::Verified { .. })
+    }
+
+    /// Short label for lists, e.g. `[verified publisher]`
+    pub fn badge(&self) -> String {
+        match self {
+            Self::Verified { .. } => "[✓ verified publisher]".green().to_string(),
+            Self::Signed => "[registry-signed]".dimmed().to_string(),
+            Self::Unsigned => "[unsigned]".yellow().to_string(),
+            Self::Invalid { .. } => "[✗ signature mismatch]".red().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for PublisherVerification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Verified {
+                repository: Some(repository),
+            } => write!(f, "verified publisher, built from {repository}"),
+            Self::Verified { repository: None } => write!(f, "verified publisher"),
+            Self::Signed => write!(f, "signed by the registry, no build provenance"),
+            Self::Unsigned => write!(f, "no registry signature or build provenance"),
+            Self::Invalid { reason } => write!(f, "verification failed: {reason}"),
+        }
+    }
+}
+
+/// Look up and judge `package` at `version` (latest when `None`)
+pub fn verify_npm_package(package: &str, version: Option<&str>) -> Result<PublisherVerification> {
+    let metadata = npm_registry::version_metadata(package, version)?;
+    let keys = npm_registry::signing_keys()?;
+    let attestations = match metadata["dist"]["attestations"]["url"].as_str() {
+        Some(url) => {
+            let version = metadata["version"].as_str().unwrap_or("latest");
+            Some(npm_registry::attestations(package, version, url)?)
+        }
+        None => None,
+    };
+    Ok(evaluate(&metadata, &keys, attestations.as_ref()))
+}
+
+/// Judge a version's registry metadata against the registry's signing keys and
+/// the attestation bundle it links to
+pub fn evaluate(
+    metadata: &Value,
+    keys: &Value,
+    attestations: Option<&Value>,
+) -> PublisherVerification {
+    let invalid = |reason: String| PublisherVerification::Invalid { reason };
+    let name = metadata["name"].as_str().unwrap_or_default();
+    let version = metadata["version"].as_str().unwrap_or_default();
+    let dist = &metadata["dist"];
+
+    let known_keys: Vec<&str> = keys["keys"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|key| key["keyid"].as_str())
+        .collect();
+    let signatures = dist["signatures"].as_array().cloned().unwrap_or_default();
+    for signature in &signatures {
+        let keyid = signature["keyid"].as_str().unwrap_or_default();
+        if !known_keys.contains(&keyid) {
+            return invalid(format!(
+                "signed with a key the registry doesn't publish ({keyid})"
+            ));
+        }
+    }
+
+    let Some(attestations) = attestations else {
+        return if signatures.is_empty() {
+            PublisherVerification::Unsigned
+        } else {
+            PublisherVerification::Signed
+        };
+    };
+
+    let provenance = attestations["attestations"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|attestation| {
+            attestation["predicateType"]
+                .as_str()
+                .is_some_and(|kind| kind.starts_with(SLSA_PROVENANCE_PREFIX))
+        });
+    let Some(provenance) = provenance else {
+        return invalid("the attestation bundle has no build provenance".to_string());
+    };
+    let statement = provenance["bundle"]["dsseEnvelope"]["payload"]
+        .as_str()
+        .and_then(decode_base64)
+        .and_then(|payload| serde_json::from_slice::<Value>(&payload).ok());
+    let Some(statement) = statement else {
+        return invalid("the provenance statement can't be read".to_string());
+    };
+
+    let expected_subject = format!("pkg:npm/{name}@{version}");
+    let subject = statement["subject"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|subject| {
+            subject["name"]
+                .as_str()
+                .is_some_and(|s| s.replace("%40", "@") == expected_subject)
+        });
+    let Some(subject) = subject else {
+        return invalid(format!("the provenance is not for {name}@{version}"));
+    };
+
+    let tarball_digest = dist["integrity"]
+        .as_str()
+        .and_then(|integrity| integrity.strip_prefix("sha512-"))
+        .and_then(decode_base64)
+        .map(hex::encode);
+    let attested_digest = subject["digest"]["sha512"].as_str();
+    if tarball_digest.is_none() || tarball_digest.as_deref() != attested_digest {
+        return invalid("the provenance digest doesn't match the published tarball".to_string());
+    }
+
+    PublisherVerification::Verified {
+        repository: source_repository(&statement["predicate"]),
+    }
+}
+
+/// The repository a SLSA v1 or v0.2 provenance predicate was built from
+fn source_repository(predicate: &Value) -> Option<String> {
+    if let Some(repository) =
+        predicate["buildDefinition"]["externalParameters"]["workflow"]["repository"].as_str()
+    {
+        return Some(repository.to_string());
+    }
+    // v0.2: "git+https://github.com/org/repo@refs/heads/main"
+    let uri = predicate["invocation"]["configSource"]["uri"].as_str()?;
+    let uri = uri.strip_prefix("git+").unwrap_or(uri);
+    Some(uri.split('@').next().unwrap_or(uri).to_string())
+}
+
+/// Standard base64, with or without padding
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in text.trim_end_matches('=').bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+/// Whether preferences require a verified publisher for npm installs
+pub fn verification_required() -> bool {
+    Preferences::load()
+        .map(|prefs| prefs.security.require_verified_publisher)
+        .unwrap_or(false)
+}
+
+/// Verify `package` before installing it and print the result. Invalid metadata
+/// fails with a [`SecurityViolation`], as does anything short of a verified
+/// publisher when `required`. A failed lookup only matters when `required`.
+pub fn check_publisher(
+    package: &str,
+    version: Option<&str>,
+    required: bool,
+) -> Result<Option<PublisherVerification>> {
+    let violation = |reason: String| {
+        anyhow::Error::new(SecurityViolation::new(
+            SecurityFailureClass::UnverifiedPublisher,
+            package,
+            reason,
+        ))
+    };
+
+    let verification = match verify_npm_package(package, version) {
+        Ok(verification) => verification,
+        Err(e) if required => {
+            return Err(violation(format!(
+                "Could not check the publisher of {package}: {e:#}"
+            )))
+        }
+        Err(e) => {
+            tracing::debug!("Publisher check for {} failed: {:#}", package, e);
+            return Ok(None);
+        }
+    };
+
+    match &verification {
+        PublisherVerification::Invalid { reason } => {
+            return Err(violation(format!(
+                "Registry signature metadata for {package} doesn't match: {reason}"
+            )))
+        }
+        verification if required && !verification.is_verified() => {
+            return Err(violation(format!(
+                "Preferences require a verified publisher, but {package} has {verification}"
+            )))
+        }
+        PublisherVerification::Verified { .. } => {
+            println!("  {} {}", "✓".green(), verification.to_string().green())
+        }
+        _ => println!("  {} {}", "ℹ".blue(), verification),
+    }
+    Ok(Some(verification))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// sha512 of an empty tarball, as npm integrity and as hex
+    const INTEGRITY: &str = "sha512-z4PhNX7vuL3xVChQ1m2AB9Yg5AULVxXcg/SpIdNs6c5H0NE8XYXysP+DGNKHfuwvY7kxvUdBeoGlODJ6+SfaPg==";
+    const DIGEST: &str = "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e";
+
+    fn keys() -> Value {
+        json!({ "keys": [{ "keyid": "SHA256:registry-key", "expires": null }] })
+    }
+
+    fn metadata(attested: bool) -> Value {
+        let mut dist = json!({
+            "integrity": INTEGRITY,
+            "signatures": [{ "keyid": "SHA256:registry-key", "sig": "MEUCIQ..." }],
+        });
+        if attested {
+            dist["attestations"] = json!({
+                "url": "https://registry.npmjs.org/-/npm/v1/attestations/@acme%2fweather@1.2.0",
+                "provenance": { "predicateType": "https://slsa.dev/provenance/v1" },
+            });
+        }
+        json!({ "name": "@acme/weather", "version": "1.2.0", "dist": dist })
+    }
+
+    fn encode_base64(bytes: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut out = String::new();
+        for chunk in bytes.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, b)| n | (u32::from(*b) << (16 - 8 * i)));
+            for i in 0..=chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 63) as usize] as char);
+            }
+        }
+        out
+    }
+
+    fn bundle(subject: &str, digest: &str) -> Value {
+        let statement = json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{ "name": subject, "digest": { "sha512": digest } }],
+            "predicateType": "https://slsa.dev/provenance/v1",
+            "predicate": {
+                "buildDefinition": {
+                    "externalParameters": {
+                        "workflow": { "repository": "https://github.com/acme/weather" }
+                    }
+                }
+            }
+        });
+        json!({
+            "attestations": [{
+                "predicateType": "https://slsa.dev/provenance/v1",
+                "bundle": { "dsseEnvelope": {
+                    "payload": encode_base64(statement.to_string().as_bytes()),
+                    "payloadType": "application/vnd.in-toto+json",
+                }},
+            }]
+        })
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64("aGVsbG8").unwrap(), b"hello");
+        assert!(decode_base64("not base64!").is_none());
+        assert_eq!(
+            hex::encode(decode_base64(INTEGRITY.strip_prefix("sha512-").unwrap()).unwrap()),
+            DIGEST
+        );
+    }
+
+    #[test]
+    fn test_verified_provenance() {
+        let attestations = bundle("pkg:npm/%40acme/weather@1.2.0", DIGEST);
+        let verification = evaluate(&metadata(true), &keys(), Some(&attestations));
+        assert_eq!(
+            verification,
+            PublisherVerification::Verified {
+                repository: Some("https://github.com/acme/weather".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_signed_and_unsigned() {
+        assert_eq!(
+            evaluate(&metadata(false), &keys(), None),
+            PublisherVerification::Signed
+        );
+        let unsigned =
+            json!({ "name": "x", "version": "1.0.0", "dist": { "integrity": INTEGRITY } });
+        assert_eq!(
+            evaluate(&unsigned, &keys(), None),
+            PublisherVerification::Unsigned
+        );
+    }
+
+    #[test]
+    fn test_mismatches_are_invalid() {
+        let unknown_key = evaluate(&metadata(false), &json!({ "keys": [] }), None);
+        assert!(matches!(unknown_key, PublisherVerification::Invalid { .. }));
+
+        let other_version = bundle("pkg:npm/%40acme/weather@1.1.0", DIGEST);
+        let verification = evaluate(&metadata(true), &keys(), Some(&other_version));
+        assert!(verification
+            .to_string()
+            .contains("not for @acme/weather@1.2.0"));
+
+        let other_tarball = bundle("pkg:npm/%40acme/weather@1.2.0", &"0".repeat(128));
+        let verification = evaluate(&metadata(true), &keys(), Some(&other_tarball));
+        assert!(verification.to_string().contains("digest doesn't match"));
+    }
+
+    #[test]
+    fn test_source_repository_v02() {
+        let predicate = json!({
+            "invocation": { "configSource": { "uri": "git+https://github.com/acme/weather@refs/heads/main" } }
+        });
+        assert_eq!(
+            source_repository(&predicate).as_deref(),
+            Some("https://github.com/acme/weather")
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::deps::{DependencyChecker, PythonChecker};
-use crate::server::{ConfigField, ConfigFieldType, McpServer, ServerMetadata, ServerType};
+use crate::server::{ConfigField, McpServer, ServerMetadata, ServerType};
+use crate::utils::process::{Process, INSTALL_TIMEOUT};
 use anyhow::{Context, Result};
 use std::collections::HashMap;
 use std::path::Path;
@@ -26,31 +27,14 @@ impl PythonServer {
             },
             required_config: vec![],
             optional_config: vec![
-                ConfigField {
-                    name: "python_path".to_string(),
-                    field_type: ConfigFieldType::Path,
-                    description: Some("Path to Python interpreter".to_string()),
-                    default: Some("python3".to_string()),
-                },
-                ConfigField {
-                    name: "working_directory".to_string(),
-                    field_type: ConfigFieldType::Path,
-                    description: Some("Working directory for the server".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "virtual_env".to_string(),
-                    field_type: ConfigFieldType::Path,
-                    description: Some("Path to virtual environment".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "timeout".to_string(),
-                    field_type: ConfigFieldType::Number,
-                    description: Some("Server timeout in seconds".to_string()),
-                    default: Some("30".to_string()),
-                },
+                ConfigField::path("python_path")
+                    .describe("Path to Python interpreter")
+                    .default_value("python3"),
+                ConfigField::working_directory(),
+                ConfigField::path("virtual_env").describe("Path to virtual environment"),
+                ConfigField::timeout(),
             ],
+            args_template: vec![],
         };
 
         Ok(Self {
@@ -78,25 +62,13 @@ impl PythonServer {
             },
             required_config: vec![],
             optional_config: vec![
-                ConfigField {
-                    name: "python_path".to_string(),
-                    field_type: ConfigFieldType::Path,
-                    description: Some("Path to Python interpreter".to_string()),
-                    default: Some("python3".to_string()),
-                },
-                ConfigField {
-                    name: "working_directory".to_string(),
-                    field_type: ConfigFieldType::Path,
-                    description: Some("Working directory for the server".to_string()),
-                    default: None,
-                },
-                ConfigField {
-                    name: "virtual_env".to_string(),
-                    field_type: ConfigFieldType::Path,
-                    description: Some("Path to virtual environment".to_string()),
-                    default: None,
-                },
+                ConfigField::path("python_path")
+                    .describe("Path to Python interpreter")
+                    .default_value("python3"),
+                ConfigField::working_directory(),
+                ConfigField::path("virtual_env").describe("Path to virtual environment"),
             ],
+            args_template: vec![],
         };
 
         Self {
@@ -153,13 +125,13 @@ impl PythonServer {
 
     #[allow(dead_code)]
     fn is_package_installed(&self, python_cmd: &str) -> Result<bool> {
-        let output = std::process::Command::new(python_cmd)
+        let output = Process::new(python_cmd)
             .arg("-c")
             .arg(format!("import {}", self.package))
             .output()
             .context("Failed to check if Python package is installed")?;
 
-        Ok(output.status.success())
+        Ok(output.success())
     }
 
     pub fn install_package(&self, _python_cmd: &str) -> Result<()> {
@@ -172,22 +144,17 @@ impl PythonServer {
             self.package.clone()
         };
 
-        let mut command = std::process::Command::new(pip_cmd.split_whitespace().next().unwrap());
-
         // Add pip command parts (handle cases like "python3 -m pip")
         let pip_parts: Vec<&str> = pip_cmd.split_whitespace().collect();
-        for part in &pip_parts[1..] {
-            command.arg(part);
-        }
-
-        command.args(["install", &package_spec]);
-
-        let output = command
+        let output = Process::new(pip_parts[0])
+            .args(&pip_parts[1..])
+            .args(["install", &package_spec])
+            .timeout(INSTALL_TIMEOUT)
             .output()
             .context("Failed to install Python package")?;
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
+        if !output.success() {
+            let error_msg = output.stderr_lossy();
             anyhow::bail!("Failed to install package {}: {}", package_spec, error_msg);
         }
 
@@ -204,12 +171,10 @@ impl McpServer for PythonServer {
     fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
         // Validate Python path if provided
         if let Some(python_path) = config.get("python_path") {
-            let output = std::process::Command::new(python_path)
-                .arg("--version")
-                .output();
+            let output = Process::new(python_path).arg("--version").output();
 
             match output {
-                Ok(output) if output.status.success() => {}
+                Ok(output) if output.success() => {}
                 _ => anyhow::bail!("Invalid Python path: {}", python_path),
             }
         }
@@ -226,6 +191,11 @@ impl McpServer for PythonServer {
 
         // Use common validation utilities
         use super::validation::ConfigValidation;
+        ConfigValidation::validate_fields(
+            config,
+            &self.metadata.required_config,
+            &self.metadata.optional_config,
+        )?;
         ConfigValidation::validate_working_directory(config)?;
         ConfigValidation::validate_timeout(config)?;
 
@@ -0,0 +1,311 @@
+//! Directory scoping for filesystem-style MCP servers.
+//!
+//! Filesystem servers (such as `@modelcontextprotocol/server-filesystem` or the
+//! `mcp/filesystem` Docker image) take the directories they may access as arguments.
+//! [`FilesystemScope`] collects the allowed directories and rewrites a server
+//! configuration so it only exposes those, optionally read-only.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use super::docker::is_docker_command;
+use crate::client::ServerConfig;
+use crate::security::{SecurityValidation, SecurityValidator};
+
+/// Package and image names of servers that take allowed directories as arguments
+const FILESYSTEM_SERVER_MARKERS: &[&str] = &["server-filesystem", "mcp/filesystem"];
+
+/// Mount point for directories inside the filesystem server container
+const CONTAINER_ROOT: &str = "/projects";
+
+/// Directories a filesystem server is allowed to access
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FilesystemScope {
+    directories: Vec<PathBuf>,
+    read_only: bool,
+}
+
+impl FilesystemScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mount directories read-only (Docker-based servers only)
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Add an allowed directory. The path must exist and is stored in absolute form.
+    pub fn add_directory(&mut self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        let absolute = resolve_directory(path.as_ref())?;
+        if !self.directories.contains(&absolute) {
+            self.directories.push(absolute.clone());
+        }
+        Ok(absolute)
+    }
+
+    pub fn directories(&self) -> &[PathBuf] {
+        &self.directories
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.directories.is_empty()
+    }
+
+    /// Run every allowed directory through the security validator.
+    pub fn validate(&self, validator: &SecurityValidator) -> Result<Vec<SecurityValidation>> {
+        self.directories
+            .iter()
+            .map(|dir| validator.validate_filesystem_path(dir))
+            .collect()
+    }
+
+    /// Return a copy of `config` that only exposes the scoped directories.
+    pub fn apply(&self, config: &ServerConfig) -> Result<ServerConfig> {
+        if self.directories.is_empty() {
+            anyhow::bail!("At least one allowed directory is required");
+        }
+
+        let marker_index =
+            find_marker_index(config).context("Server configuration is not a filesystem server")?;
+
+        let mut updated = config.clone();
+        if is_docker_command(&config.command) {
+            updated.args = self.docker_args(&config.args, marker_index);
+        } else {
+            if self.read_only {
+                anyhow::bail!(
+                    "Read-only access requires the Docker image (mcp/filesystem); \
+                     the npm package always allows writes"
+                );
+            }
+            updated.args.truncate(marker_index + 1);
+            updated.args.extend(
+                self.directories
+                    .iter()
+                    .map(|d| d.to_string_lossy().to_string()),
+            );
+        }
+
+        Ok(updated)
+    }
+
+    fn docker_args(&self, args: &[String], image_index: usize) -> Vec<String> {
+        // Drop existing mounts; the scope replaces them
+        let mut result = Vec::new();
+        let mut iter = args[..image_index].iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--mount" || arg == "-v" || arg == "--volume" {
+                iter.next();
+            } else if !arg.starts_with("--mount=") && !arg.starts_with("--volume=") {
+                result.push(arg.clone());
+            }
+        }
+
+        for dir in &self.directories {
+            let name = dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "root".to_string());
+            let mut mount = format!(
+                "type=bind,src={},dst={CONTAINER_ROOT}/{name}",
+                dir.to_string_lossy()
+            );
+            if self.read_only {
+                mount.push_str(",ro");
+            }
+            result.push("--mount".to_string());
+            result.push(mount);
+        }
+
+        result.push(args[image_index].clone());
+        result.push(CONTAINER_ROOT.to_string());
+        result
+    }
+}
+
+/// Whether the configuration runs a server that takes allowed directories as arguments
+pub fn is_filesystem_server(config: &ServerConfig) -> bool {
+    find_marker_index(config).is_some()
+}
+
+/// Directories currently exposed by a filesystem server configuration
+pub fn configured_directories(config: &ServerConfig) -> Vec<PathBuf> {
+    let Some(index) = find_marker_index(config) else {
+        return Vec::new();
+    };
+
+    if is_docker_command(&config.command) {
+        config.args[..index]
+            .iter()
+            .filter_map(|arg| {
+                arg.split(',')
+                    .find_map(|part| {
+                        part.strip_prefix("src=")
+                            .or_else(|| part.strip_prefix("source="))
+                    })
+                    .map(PathBuf::from)
+            })
+            .collect()
+    } else {
+        config.args[index + 1..]
+            .iter()
+            .filter(|arg| !arg.starts_with('-'))
+            .map(PathBuf::from)
+            .collect()
+    }
+}
+
+/// Resolve a user-supplied directory to an existing absolute path.
+pub fn resolve_directory(path: &Path) -> Result<PathBuf> {
+    let absolute = dunce_canonicalize(path)
+        .with_context(|| format!("Directory not found: {}", path.display()))?;
+
+    if !absolute.is_dir() {
+        anyhow::bail!("Not a directory: {}", absolute.display());
+    }
+    Ok(absolute)
+}
+
+fn find_marker_index(config: &ServerConfig) -> Option<usize> {
+    config.args.iter().position(|arg| {
+        let name = arg.split('@').find(|s| !s.is_empty()).unwrap_or(arg);
+        let name = name.split(':').next().unwrap_or(name);
+        FILESYSTEM_SERVER_MARKERS
+            .iter()
+            .any(|marker| name.ends_with(marker))
+    })
+}
+
+/// Canonicalize without the `\\?\` prefix Windows adds, which clients can't read.
+pub(crate) fn dunce_canonicalize(path: &Path) -> std::io::Result<PathBuf> {
+    let canonical = path.canonicalize()?;
+    #[cfg(windows)]
+    {
+        let text = canonical.to_string_lossy();
+        if let Some(stripped) = text.strip_prefix(r"\\?\") {
+            return Ok(PathBuf::from(stripped));
+        }
+    }
+    Ok(canonical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_is_filesystem_server() {
+        assert!(is_filesystem_server(&config(
+            "npx",
+            &[
+                "-y",
+                "@modelcontextprotocol/server-filesystem@1.0.0",
+                "/tmp"
+            ]
+        )));
+        assert!(is_filesystem_server(&config(
+            "docker",
+            &["run", "--rm", "-i", "mcp/filesystem:latest"]
+        )));
+        assert!(!is_filesystem_server(&config(
+            "npx",
+            &["@modelcontextprotocol/server-github"]
+        )));
+    }
+
+    #[test]
+    fn test_add_directory_rejects_missing_and_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("file.txt");
+        std::fs::write(&file, "").unwrap();
+
+        let mut scope = FilesystemScope::new();
+        assert!(scope
+            .add_directory(temp_dir.path().join("missing"))
+            .is_err());
+        assert!(scope.add_directory(&file).is_err());
+
+        scope.add_directory(temp_dir.path()).unwrap();
+        scope.add_directory(temp_dir.path()).unwrap();
+        assert_eq!(scope.directories().len(), 1);
+    }
+
+    #[test]
+    fn test_apply_npm_replaces_directories() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut scope = FilesystemScope::new();
+        let dir = scope.add_directory(temp_dir.path()).unwrap();
+
+        let original = config(
+            "npx",
+            &["-y", "@modelcontextprotocol/server-filesystem", "/"],
+        );
+        let scoped = scope.apply(&original).unwrap();
+        assert_eq!(
+            scoped.args,
+            vec![
+                "-y".to_string(),
+                "@modelcontextprotocol/server-filesystem".to_string(),
+                dir.to_string_lossy().to_string()
+            ]
+        );
+        assert_eq!(configured_directories(&scoped), vec![dir]);
+
+        assert!(scope.with_read_only(true).apply(&original).is_err());
+    }
+
+    #[test]
+    fn test_apply_docker_mounts_read_only() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut scope = FilesystemScope::new().with_read_only(true);
+        let dir = scope.add_directory(temp_dir.path()).unwrap();
+
+        let original = config(
+            "docker",
+            &[
+                "run",
+                "--rm",
+                "-i",
+                "--mount",
+                "type=bind,src=/,dst=/projects/root",
+                "mcp/filesystem",
+                "/projects",
+            ],
+        );
+        let scoped = scope.apply(&original).unwrap();
+
+        let mounts: Vec<_> = scoped
+            .args
+            .windows(2)
+            .filter(|w| w[0] == "--mount")
+            .map(|w| w[1].clone())
+            .collect();
+        assert_eq!(mounts.len(), 1);
+        assert!(mounts[0].ends_with(",ro"));
+        assert_eq!(configured_directories(&scoped), vec![dir]);
+        assert_eq!(scoped.args.last().unwrap(), "/projects");
+    }
+
+    #[test]
+    fn test_apply_requires_directories() {
+        let original = config("npx", &["@modelcontextprotocol/server-filesystem"]);
+        assert!(FilesystemScope::new().apply(&original).is_err());
+    }
+}
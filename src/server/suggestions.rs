@@ -1,4 +1,5 @@
 use crate::deps::{Dependency, DependencyChecker, DependencyStatus};
+use crate::server::platform::Platform;
 use crate::server::{RegistryEntry, ServerType};
 use std::collections::HashMap;
 
@@ -6,6 +7,8 @@ use std::collections::HashMap;
 pub struct ServerSuggestions {
     registry: HashMap<String, RegistryEntry>,
     similarity_cache: HashMap<String, Vec<String>>,
+    /// Platform suggestions must run on
+    platform: Platform,
 }
 
 #[derive(Debug, Clone)]
@@ -29,6 +32,7 @@ impl ServerSuggestions {
         Self {
             registry: Self::create_mock_registry(),
             similarity_cache: HashMap::new(),
+            platform: Platform::current(),
         }
     }
 
@@ -56,7 +60,8 @@ impl ServerSuggestions {
         suggestions.extend(self.find_similar_name_alternatives(target_server));
         suggestions.extend(self.find_platform_compatible_alternatives());
 
-        // Remove duplicates and sort by score
+        // Remove duplicates and servers that can't run here, then sort by score
+        suggestions.retain(|suggestion| self.runs_here(&suggestion.server));
         self.deduplicate_and_score(&mut suggestions);
         suggestions.sort_by(|a, b| {
             b.score
@@ -68,6 +73,41 @@ impl ServerSuggestions {
         suggestions.into_iter().take(5).collect()
     }
 
+    /// Suggest servers that run on this platform in place of `target_server`,
+    /// which doesn't; ones in its category come first
+    pub fn suggest_platform_alternatives(&self, target_server: &str) -> Vec<Suggestion> {
+        let category = self
+            .registry
+            .get(target_server)
+            .map(|entry| entry.category.clone());
+        let mut suggestions: Vec<Suggestion> = self
+            .find_platform_compatible_alternatives()
+            .into_iter()
+            .filter(|suggestion| suggestion.server.package_name != target_server)
+            .map(|mut suggestion| {
+                if category.as_ref() == Some(&suggestion.server.category) {
+                    suggestion.score *= 2.0;
+                }
+                suggestion
+            })
+            .collect();
+        self.deduplicate_and_score(&mut suggestions);
+        suggestions.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        suggestions.into_iter().take(5).collect()
+    }
+
+    /// Whether `entry` declares no platforms or includes this one
+    fn runs_here(&self, entry: &RegistryEntry) -> bool {
+        entry
+            .platform_support
+            .as_ref()
+            .is_none_or(|support| support.supports(&self.platform))
+    }
+
     fn find_lower_requirement_alternatives(
         &self,
         _target_server: &str,
@@ -167,26 +207,23 @@ impl ServerSuggestions {
 
     fn find_platform_compatible_alternatives(&self) -> Vec<Suggestion> {
         let mut suggestions = Vec::new();
-        let current_platform = std::env::consts::OS;
 
         for entry in self.registry.values() {
-            // This is a simplified check - in reality, we'd check platform support metadata
-            let platform_compatible = match entry.server_type {
-                ServerType::Npm { .. } => true, // NPM works everywhere with Node.js
-                ServerType::Binary { .. } => true, // Binaries can be platform-specific
-                ServerType::Python { .. } => true, // Python works everywhere
-                ServerType::Docker { .. } => true, // Docker works everywhere (if installed)
+            // Servers that declare their platforms and include this one rank above
+            // ones that merely don't say
+            let declared = match &entry.platform_support {
+                Some(support) if support.supports(&self.platform) => true,
+                Some(_) => continue,
+                None => false,
             };
 
-            if platform_compatible {
-                suggestions.push(Suggestion {
-                    server: entry.clone(),
-                    reason: SuggestionReason::PlatformCompatible {
-                        platform: current_platform.to_string(),
-                    },
-                    score: entry.popularity_score * 0.6, // Platform compatibility bonus
-                });
-            }
+            suggestions.push(Suggestion {
+                server: entry.clone(),
+                reason: SuggestionReason::PlatformCompatible {
+                    platform: self.platform.to_string(),
+                },
+                score: entry.popularity_score * if declared { 0.8 } else { 0.6 },
+            });
         }
 
         suggestions
@@ -288,6 +325,10 @@ impl ServerSuggestions {
             ServerType::Npm { .. } => Box::new(crate::deps::NodeChecker::new()),
             ServerType::Python { .. } => Box::new(crate::deps::PythonChecker::new()),
             ServerType::Docker { .. } => Box::new(crate::deps::DockerChecker::new()),
+            ServerType::Cargo { .. } => Box::new(crate::deps::RustChecker::new()),
+            ServerType::Deno { .. } => Box::new(crate::deps::DenoChecker::new()),
+            ServerType::Jar { .. } => Box::new(crate::deps::JavaChecker::new()),
+            ServerType::Dotnet { .. } => Box::new(crate::deps::DotnetChecker::new()),
             ServerType::Binary { .. } => {
                 // Binary servers typically have no dependencies
                 return SuggestionFeasibility::Ready;
@@ -310,6 +351,7 @@ impl ServerSuggestions {
                         config_issue: "Dependency configuration required".to_string(),
                     }
                 }
+                DependencyStatus::Unresponsive { .. } => SuggestionFeasibility::Unknown,
             },
             Err(_) => SuggestionFeasibility::Unknown,
         }
@@ -337,6 +379,7 @@ impl ServerSuggestions {
                 popularity_score: 9.5,
                 last_updated: "2024-01-15".to_string(),
                 verified: true,
+                platform_support: None,
             },
         );
 
@@ -359,6 +402,7 @@ impl ServerSuggestions {
                 popularity_score: 8.2,
                 last_updated: "2024-01-08".to_string(),
                 verified: true,
+                platform_support: None,
             },
         );
 
@@ -381,6 +425,7 @@ impl ServerSuggestions {
                 popularity_score: 7.8,
                 last_updated: "2024-01-12".to_string(),
                 verified: false,
+                platform_support: None,
             },
         );
 
@@ -404,6 +449,7 @@ impl ServerSuggestions {
                 popularity_score: 8.9,
                 last_updated: "2024-01-20".to_string(),
                 verified: true,
+                platform_support: None,
             },
         );
 
@@ -541,6 +587,7 @@ mod tests {
             popularity_score: 1.0,
             last_updated: "2024-01-01".to_string(),
             verified: false,
+            platform_support: None,
         };
 
         let entry2 = RegistryEntry {
@@ -556,6 +603,7 @@ mod tests {
             popularity_score: 1.0,
             last_updated: "2024-01-01".to_string(),
             verified: false,
+            platform_support: None,
         };
 
         assert!(suggestions.has_similar_tags(&entry1, &entry2));
@@ -610,4 +658,32 @@ mod tests {
         suggestions.deduplicate_and_score(&mut suggestion_list);
         assert_eq!(suggestion_list.len(), 1); // Should deduplicate
     }
+
+    #[test]
+    fn test_platform_alternatives_skip_unsupported_servers() {
+        let mut suggestions = ServerSuggestions::new();
+        suggestions.platform = Platform::new("linux", "x64");
+        let mac_only = suggestions.registry.get_mut("mcp-file-browser").unwrap();
+        mac_only.platform_support = Some(crate::server::PlatformSupport::from_npm(
+            &["darwin".to_string()],
+            &[],
+        ));
+
+        let alternatives =
+            suggestions.suggest_platform_alternatives("@modelcontextprotocol/server-filesystem");
+        assert!(!alternatives.is_empty());
+        assert!(alternatives
+            .iter()
+            .all(|s| s.server.package_name != "mcp-file-browser"
+                && s.server.package_name != "@modelcontextprotocol/server-filesystem"));
+        assert!(matches!(
+            &alternatives[0].reason,
+            SuggestionReason::PlatformCompatible { platform } if platform == "linux-x64"
+        ));
+
+        let alternatives = suggestions.suggest_alternatives("mcp-file", None);
+        assert!(alternatives
+            .iter()
+            .all(|s| s.server.package_name != "mcp-file-browser"));
+    }
 }
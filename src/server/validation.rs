@@ -152,6 +152,22 @@ impl ConfigValidation {
         Ok(())
     }
 
+    /// Validate a config against a server's schema: required fields must be present,
+    /// and every declared field that is set must match its type
+    pub fn validate_fields(
+        config: &HashMap<String, String>,
+        required_fields: &[ConfigField],
+        optional_fields: &[ConfigField],
+    ) -> Result<()> {
+        Self::validate_required_fields(config, required_fields)?;
+        for field in optional_fields {
+            if let Some(value) = config.get(&field.name) {
+                Self::validate_field_type(field, value)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Internal helper to validate URL format
     fn validate_url_format(value: &str) -> Result<()> {
         if !value.starts_with("http://") && !value.starts_with("https://") {
@@ -381,4 +397,27 @@ mod tests {
         assert!(ConfigValidation::validate_field_type(&field, "not-a-url").is_err());
         assert!(ConfigValidation::validate_field_type(&field, "ftp://example.com").is_err());
     }
+
+    #[test]
+    fn test_validate_fields_checks_schema() {
+        let required = vec![ConfigField::url("endpoint").describe("API endpoint")];
+        let optional = vec![
+            ConfigField::timeout(),
+            ConfigField::boolean("verbose").default_value("false"),
+        ];
+        assert_eq!(optional[0].field_type, ConfigFieldType::Number);
+        assert_eq!(optional[0].default.as_deref(), Some("30"));
+
+        let mut config =
+            HashMap::from([("endpoint".to_string(), "https://example.com".to_string())]);
+        assert!(ConfigValidation::validate_fields(&config, &required, &optional).is_ok());
+
+        config.insert("verbose".to_string(), "sometimes".to_string());
+        let error = ConfigValidation::validate_fields(&config, &required, &optional).unwrap_err();
+        assert!(error.to_string().contains("'verbose'"));
+
+        config.insert("verbose".to_string(), "true".to_string());
+        config.remove("endpoint");
+        assert!(ConfigValidation::validate_fields(&config, &required, &optional).is_err());
+    }
 }
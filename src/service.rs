@@ -0,0 +1,631 @@
+//! `mcp service`: run a server at login, independent of any client.
+//!
+//! HTTP/SSE servers are useful to keep running in the background. `mcp service install`
+//! takes a server's configuration from a client and registers it with the platform's
+//! supervisor:
+//!
+//! - Linux: a systemd user unit in `~/.config/systemd/user`
+//! - macOS: a launchd agent in `~/Library/LaunchAgents`
+//! - Windows: a Scheduled Task that starts at logon and runs a restart-loop wrapper
+//!
+//! Every service is named `mcp-<server>` so `mcp service status` can find them all.
+
+use colored::Colorize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::client::{detect_clients, ServerConfig};
+use crate::error::McpError;
+use crate::utils::paths;
+use crate::utils::process::Process;
+use crate::utils::secure_file::write_secure;
+
+/// Prefix shared by every generated service
+const SERVICE_PREFIX: &str = "mcp-";
+
+/// launchd labels are reverse-DNS names
+const LAUNCHD_LABEL_PREFIX: &str = "com.mcp-helper.";
+
+/// Seconds to wait before restarting a server that exited
+const RESTART_DELAY_SECS: u32 = 5;
+
+/// The supervisor used on this platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceManager {
+    Systemd,
+    Launchd,
+    ScheduledTask,
+}
+
+impl ServiceManager {
+    pub fn current() -> Self {
+        if cfg!(target_os = "windows") {
+            Self::ScheduledTask
+        } else if cfg!(target_os = "macos") {
+            Self::Launchd
+        } else {
+            Self::Systemd
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Systemd => "systemd",
+            Self::Launchd => "launchd",
+            Self::ScheduledTask => "Task Scheduler",
+        }
+    }
+}
+
+/// Everything needed to run one server as a service
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceDefinition {
+    pub server: String,
+    /// Absolute path of the server command
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    /// Directory the server starts in
+    pub working_dir: Option<PathBuf>,
+    pub log_dir: PathBuf,
+}
+
+impl ServiceDefinition {
+    /// `mcp-<server>` with anything unsafe in a unit or task name replaced
+    pub fn service_name(&self) -> String {
+        service_name(&self.server)
+    }
+
+    pub fn launchd_label(&self) -> String {
+        format!("{LAUNCHD_LABEL_PREFIX}{}", self.service_name())
+    }
+
+    fn log_file(&self, stream: &str) -> PathBuf {
+        self.log_dir
+            .join(format!("{}.{stream}.log", self.service_name()))
+    }
+}
+
+/// `mcp-<server>` with anything unsafe in a unit or task name replaced
+pub fn service_name(server: &str) -> String {
+    let sanitized: String = server
+        .trim_start_matches('@')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{SERVICE_PREFIX}{sanitized}")
+}
+
+/// A systemd user unit that restarts the server when it exits
+pub fn render_systemd_unit(definition: &ServiceDefinition) -> String {
+    let mut unit = format!(
+        "[Unit]\nDescription=MCP server {} (managed by mcp-helper)\nAfter=network-online.target\n\n[Service]\nType=simple\n",
+        definition.server
+    );
+
+    let exec: Vec<String> = std::iter::once(definition.program.to_string_lossy().to_string())
+        .chain(definition.args.iter().cloned())
+        .map(|arg| systemd_quote(&arg))
+        .collect();
+    unit.push_str(&format!("ExecStart={}\n", exec.join(" ")));
+    if let Some(dir) = &definition.working_dir {
+        unit.push_str(&format!(
+            "WorkingDirectory={}\n",
+            dir.to_string_lossy().replace('%', "%%")
+        ));
+    }
+    for (key, value) in &definition.env {
+        unit.push_str(&format!(
+            "Environment={}\n",
+            systemd_quote(&format!("{key}={value}"))
+        ));
+    }
+    unit.push_str(&format!(
+        "Restart=on-failure\nRestartSec={RESTART_DELAY_SECS}\nStandardOutput=append:{}\nStandardError=append:{}\n\n[Install]\nWantedBy=default.target\n",
+        definition.log_file("out").display(),
+        definition.log_file("err").display()
+    ));
+    unit
+}
+
+fn systemd_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    if escaped.is_empty() || escaped.contains(char::is_whitespace) || escaped != value {
+        format!("\"{escaped}\"")
+    } else {
+        escaped
+    }
+}
+
+/// A launchd agent that starts at login and is kept alive
+pub fn render_launchd_plist(definition: &ServiceDefinition) -> String {
+    let mut plist = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n<plist version=\"1.0\">\n<dict>\n",
+    );
+    plist.push_str(&format!(
+        "  <key>Label</key>\n  <string>{}</string>\n",
+        xml_escape(&definition.launchd_label())
+    ));
+
+    plist.push_str("  <key>ProgramArguments</key>\n  <array>\n");
+    let program = definition.program.to_string_lossy();
+    for arg in std::iter::once(program.as_ref()).chain(definition.args.iter().map(String::as_str)) {
+        plist.push_str(&format!("    <string>{}</string>\n", xml_escape(arg)));
+    }
+    plist.push_str("  </array>\n");
+
+    if let Some(dir) = &definition.working_dir {
+        plist.push_str(&format!(
+            "  <key>WorkingDirectory</key>\n  <string>{}</string>\n",
+            xml_escape(&dir.to_string_lossy())
+        ));
+    }
+
+    if !definition.env.is_empty() {
+        plist.push_str("  <key>EnvironmentVariables</key>\n  <dict>\n");
+        for (key, value) in &definition.env {
+            plist.push_str(&format!(
+                "    <key>{}</key>\n    <string>{}</string>\n",
+                xml_escape(key),
+                xml_escape(value)
+            ));
+        }
+        plist.push_str("  </dict>\n");
+    }
+
+    plist.push_str(&format!(
+        "  <key>RunAtLoad</key>\n  <true/>\n  <key>KeepAlive</key>\n  <dict>\n    <key>SuccessfulExit</key>\n    <false/>\n  </dict>\n  <key>ThrottleInterval</key>\n  <integer>{RESTART_DELAY_SECS}</integer>\n  <key>StandardOutPath</key>\n  <string>{}</string>\n  <key>StandardErrorPath</key>\n  <string>{}</string>\n</dict>\n</plist>\n",
+        xml_escape(&definition.log_file("out").to_string_lossy()),
+        xml_escape(&definition.log_file("err").to_string_lossy())
+    ));
+    plist
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// A batch wrapper for the Scheduled Task: sets the environment and restarts the
+/// server whenever it exits with an error.
+pub fn render_windows_wrapper(definition: &ServiceDefinition) -> String {
+    let mut script = format!(
+        "@echo off\r\nrem MCP server {} (managed by mcp-helper)\r\n",
+        batch_escape(&definition.server)
+    );
+    for (key, value) in &definition.env {
+        script.push_str(&format!("set \"{}={}\"\r\n", key, batch_escape(value)));
+    }
+    if let Some(dir) = &definition.working_dir {
+        script.push_str(&format!(
+            "cd /d \"{}\"\r\n",
+            batch_escape(&dir.to_string_lossy())
+        ));
+    }
+
+    let command: Vec<String> = std::iter::once(definition.program.to_string_lossy().to_string())
+        .chain(definition.args.iter().cloned())
+        .map(|arg| format!("\"{}\"", batch_escape(&arg).replace('"', "\"\"")))
+        .collect();
+    script.push_str(&format!(
+        ":run\r\ncall {} >> \"{}\" 2>> \"{}\"\r\nif errorlevel 1 (\r\n  timeout /t {RESTART_DELAY_SECS} /nobreak > nul\r\n  goto run\r\n)\r\n",
+        command.join(" "),
+        definition.log_file("out").display(),
+        definition.log_file("err").display()
+    ));
+    script
+}
+
+fn batch_escape(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+/// Install, inspect and remove background services for MCP servers
+pub struct ServiceCommand {
+    verbose: bool,
+    manager: ServiceManager,
+}
+
+impl ServiceCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            manager: ServiceManager::current(),
+        }
+    }
+
+    /// Register `server` (as configured in `client`, or the first client that has
+    /// it) to start at login.
+    pub fn install(&self, server: &str, client: Option<&str>) -> Result<(), McpError> {
+        let config = find_server_config(server, client)?;
+        let definition = build_definition(server, &config, &data_dir()?.join("logs"))?;
+        fs::create_dir_all(&definition.log_dir).map_err(|e| {
+            McpError::io_error(
+                "create service log directory",
+                Some(definition.log_dir.display().to_string()),
+                e,
+            )
+        })?;
+
+        let path = self.definition_path(&definition.service_name())?;
+        let content = match self.manager {
+            ServiceManager::Systemd => render_systemd_unit(&definition),
+            ServiceManager::Launchd => render_launchd_plist(&definition),
+            ServiceManager::ScheduledTask => render_windows_wrapper(&definition),
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                McpError::io_error(
+                    "create service directory",
+                    Some(parent.display().to_string()),
+                    e,
+                )
+            })?;
+        }
+        write_definition(&path, &content)?;
+        if self.verbose {
+            println!("  {} Wrote {}", "✓".green(), path.display());
+        }
+
+        let name = definition.service_name();
+        let path_str = path.to_string_lossy().to_string();
+        match self.manager {
+            ServiceManager::Systemd => {
+                run_tool("systemctl", &["--user", "daemon-reload"])?;
+                run_tool(
+                    "systemctl",
+                    &["--user", "enable", "--now", &format!("{name}.service")],
+                )?;
+            }
+            ServiceManager::Launchd => {
+                // Reload if it was installed before so the new definition is used
+                let _ = run_tool("launchctl", &["unload", &path_str]);
+                run_tool("launchctl", &["load", "-w", &path_str])?;
+            }
+            ServiceManager::ScheduledTask => {
+                let task = format!("\\mcp-helper\\{name}");
+                run_tool(
+                    "schtasks",
+                    &[
+                        "/Create",
+                        "/TN",
+                        &task,
+                        "/TR",
+                        &format!("\"{path_str}\""),
+                        "/SC",
+                        "ONLOGON",
+                        "/RL",
+                        "LIMITED",
+                        "/F",
+                    ],
+                )?;
+                run_tool("schtasks", &["/Run", "/TN", &task])?;
+            }
+        }
+
+        println!(
+            "{} {} runs as {} ({})",
+            "✅".green(),
+            server.cyan(),
+            name.bold(),
+            self.manager.name()
+        );
+        println!("  Logs: {}", definition.log_dir.display());
+        Ok(())
+    }
+
+    /// Show the state of one service, or of every service mcp-helper installed
+    pub fn status(&self, server: Option<&str>) -> Result<(), McpError> {
+        let names = match server {
+            Some(server) => vec![service_name(server)],
+            None => self.installed_services()?,
+        };
+
+        if names.is_empty() {
+            println!("{} No MCP server services installed", "ℹ".blue());
+            return Ok(());
+        }
+
+        for name in names {
+            let installed = self.definition_path(&name)?.exists();
+            let state = if installed {
+                self.query_state(&name)
+            } else {
+                "not installed".to_string()
+            };
+            let symbol = match state.as_str() {
+                "active" | "running" => "●".green(),
+                "not installed" => "○".dimmed(),
+                _ => "●".yellow(),
+            };
+            println!("{} {} {}", symbol, name.bold(), state.dimmed());
+        }
+        Ok(())
+    }
+
+    /// Stop a service and delete its definition
+    pub fn remove(&self, server: &str) -> Result<(), McpError> {
+        let name = service_name(server);
+        let path = self.definition_path(&name)?;
+        if !path.exists() {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "No service is installed for '{}'",
+                server
+            )));
+        }
+
+        let path_str = path.to_string_lossy().to_string();
+        match self.manager {
+            ServiceManager::Systemd => {
+                let _ = run_tool(
+                    "systemctl",
+                    &["--user", "disable", "--now", &format!("{name}.service")],
+                );
+            }
+            ServiceManager::Launchd => {
+                let _ = run_tool("launchctl", &["unload", "-w", &path_str]);
+            }
+            ServiceManager::ScheduledTask => {
+                let task = format!("\\mcp-helper\\{name}");
+                let _ = run_tool("schtasks", &["/End", "/TN", &task]);
+                run_tool("schtasks", &["/Delete", "/TN", &task, "/F"])?;
+            }
+        }
+
+        fs::remove_file(&path).map_err(|e| {
+            McpError::io_error("remove service definition", Some(path_str.clone()), e)
+        })?;
+        if self.manager == ServiceManager::Systemd {
+            let _ = run_tool("systemctl", &["--user", "daemon-reload"]);
+        }
+
+        println!("{} Removed service {}", "✅".green(), name.bold());
+        Ok(())
+    }
+
+    /// Where the definition for service `name` lives
+    fn definition_path(&self, name: &str) -> Result<PathBuf, McpError> {
+        let base = directories::BaseDirs::new().ok_or_else(|| {
+            McpError::Other(anyhow::anyhow!("Failed to determine home directory"))
+        })?;
+
+        Ok(match self.manager {
+            ServiceManager::Systemd => std::env::var("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| base.home_dir().join(".config"))
+                .join("systemd")
+                .join("user")
+                .join(format!("{name}.service")),
+            ServiceManager::Launchd => base
+                .home_dir()
+                .join("Library")
+                .join("LaunchAgents")
+                .join(format!("{LAUNCHD_LABEL_PREFIX}{name}.plist")),
+            ServiceManager::ScheduledTask => data_dir()?.join(format!("{name}.cmd")),
+        })
+    }
+
+    fn installed_services(&self) -> Result<Vec<String>, McpError> {
+        let probe = self.definition_path(&service_name("probe"))?;
+        let Some(dir) = probe.parent() else {
+            return Ok(Vec::new());
+        };
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(Vec::new());
+        };
+
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let stem = file_name
+                    .trim_start_matches(LAUNCHD_LABEL_PREFIX)
+                    .rsplit_once('.')
+                    .map(|(stem, _)| stem.to_string())?;
+                stem.starts_with(SERVICE_PREFIX).then_some(stem)
+            })
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    fn query_state(&self, name: &str) -> String {
+        let output = match self.manager {
+            ServiceManager::Systemd => Process::new("systemctl")
+                .args(["--user", "is-active", &format!("{name}.service")])
+                .output(),
+            ServiceManager::Launchd => Process::new("launchctl")
+                .args(["list", &format!("{LAUNCHD_LABEL_PREFIX}{name}")])
+                .output(),
+            ServiceManager::ScheduledTask => Process::new("schtasks")
+                .args([
+                    "/Query",
+                    "/TN",
+                    &format!("\\mcp-helper\\{name}"),
+                    "/FO",
+                    "LIST",
+                ])
+                .output(),
+        };
+        let Ok(output) = output else {
+            return "unknown".to_string();
+        };
+        let stdout = output.stdout_lossy();
+
+        match self.manager {
+            ServiceManager::Systemd => stdout.trim().to_string(),
+            ServiceManager::Launchd if !output.status.success() => "not loaded".to_string(),
+            ServiceManager::Launchd if stdout.contains("\"PID\"") => "running".to_string(),
+            ServiceManager::Launchd => "loaded, not running".to_string(),
+            ServiceManager::ScheduledTask if !output.status.success() => "task missing".to_string(),
+            ServiceManager::ScheduledTask => stdout
+                .lines()
+                .find_map(|line| line.strip_prefix("Status:"))
+                .map(|status| status.trim().to_lowercase())
+                .unwrap_or_else(|| "registered".to_string()),
+        }
+    }
+}
+
+/// Build a service definition from a client's server configuration
+pub fn build_definition(
+    server: &str,
+    config: &ServerConfig,
+    log_dir: &Path,
+) -> Result<ServiceDefinition, McpError> {
+    // Services don't inherit a login shell's PATH, so resolve the command now
+    let program = which::which(&config.command).map_err(|_| {
+        McpError::Other(anyhow::anyhow!(
+            "Command '{}' for server '{}' was not found on PATH",
+            config.command,
+            server
+        ))
+    })?;
+
+    Ok(ServiceDefinition {
+        server: server.to_string(),
+        program,
+        args: config.args.clone(),
+        env: config.env.clone().into_iter().collect(),
+        working_dir: config.cwd.as_ref().map(PathBuf::from),
+        log_dir: log_dir.to_path_buf(),
+    })
+}
+
+fn find_server_config(server: &str, client: Option<&str>) -> Result<ServerConfig, McpError> {
+    detect_clients()
+        .iter()
+        .filter(|c| c.is_installed())
+        .filter(|c| client.is_none_or(|name| c.name().eq_ignore_ascii_case(name)))
+        .find_map(|c| c.list_servers().ok()?.remove(server))
+        .ok_or_else(|| {
+            McpError::Other(anyhow::anyhow!(
+                "Server '{}' not found in {}",
+                server,
+                client.unwrap_or("any MCP client configuration")
+            ))
+        })
+}
+
+/// `<data dir>/mcp-helper/services`
+fn data_dir() -> Result<PathBuf, McpError> {
+    Ok(paths::data_dir().map_err(McpError::Other)?.join("services"))
+}
+
+fn run_tool(program: &str, args: &[&str]) -> Result<(), McpError> {
+    let output = Process::new(program).args(args).output()?;
+    if output.success() {
+        Ok(())
+    } else {
+        Err(McpError::Other(anyhow::anyhow!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            output.stderr_lossy().trim()
+        )))
+    }
+}
+
+/// Write a service definition readable only by the user: units, plists and
+/// wrappers carry the server's environment, API keys included. A definition
+/// left from an earlier install is replaced, so it loses any wider mode too
+fn write_definition(path: &Path, content: &str) -> Result<(), McpError> {
+    write_secure(path, content.as_bytes()).map_err(|e| {
+        McpError::Other(e.context(format!(
+            "Failed to write service definition {}",
+            path.display()
+        )))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definition() -> ServiceDefinition {
+        ServiceDefinition {
+            server: "@acme/weather".to_string(),
+            program: PathBuf::from("/usr/local/bin/npx"),
+            args: vec![
+                "-y".to_string(),
+                "@acme/weather".to_string(),
+                "--port=8080".to_string(),
+                "My Folder".to_string(),
+            ],
+            env: BTreeMap::from([("API_KEY".to_string(), "a&b 100%".to_string())]),
+            working_dir: Some(PathBuf::from("/srv/weather data")),
+            log_dir: PathBuf::from("/logs"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_definition_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("mcp-weather.service");
+        fs::write(&path, "old").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_definition(&path, &render_systemd_unit(&definition())).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert!(fs::read_to_string(&path).unwrap().contains("API_KEY"));
+    }
+
+    #[test]
+    fn test_service_name() {
+        assert_eq!(service_name("@acme/weather"), "mcp-acme-weather");
+        assert_eq!(service_name("fetch"), "mcp-fetch");
+    }
+
+    #[test]
+    fn test_render_systemd_unit() {
+        let unit = render_systemd_unit(&definition());
+        assert!(unit
+            .contains("ExecStart=/usr/local/bin/npx -y @acme/weather --port=8080 \"My Folder\"\n"));
+        assert!(unit.contains("Environment=\"API_KEY=a&b 100%%\"\n"));
+        assert!(unit.contains("WorkingDirectory=/srv/weather data\n"));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert!(unit.contains("StandardError=append:/logs/mcp-acme-weather.err.log\n"));
+        assert!(unit.contains("WantedBy=default.target"));
+    }
+
+    #[test]
+    fn test_render_launchd_plist() {
+        let plist = render_launchd_plist(&definition());
+        assert!(plist.contains("<string>com.mcp-helper.mcp-acme-weather</string>"));
+        assert!(plist.contains("<string>/usr/local/bin/npx</string>"));
+        assert!(plist.contains("<string>My Folder</string>"));
+        assert!(plist.contains("<key>API_KEY</key>\n    <string>a&amp;b 100%</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n  <true/>"));
+        assert!(plist.contains("<key>WorkingDirectory</key>\n  <string>/srv/weather data</string>"));
+    }
+
+    #[test]
+    fn test_render_windows_wrapper() {
+        let script = render_windows_wrapper(&definition());
+        assert!(script.contains("set \"API_KEY=a&b 100%%\"\r\n"));
+        assert!(script.contains(
+            "call \"/usr/local/bin/npx\" \"-y\" \"@acme/weather\" \"--port=8080\" \"My Folder\""
+        ));
+        assert!(script.contains("cd /d \"/srv/weather data\"\r\n"));
+        assert!(script.contains("goto run"));
+    }
+}
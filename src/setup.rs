@@ -7,10 +7,12 @@
 
 use anyhow::Result;
 use colored::Colorize;
-use std::process::Command;
 
-use crate::deps::{DependencyChecker, DockerChecker, InstallInstructions, NodeChecker};
+use crate::deps::probe::unresponsive_advice;
+use crate::deps::{check_responsive, DockerChecker, InstallInstructions, NodeChecker};
+use crate::error::catalog::tr;
 use crate::error::McpError;
+use crate::utils::process::Process;
 
 /// Environment setup and verification command
 pub struct SetupCommand {
@@ -58,8 +60,7 @@ impl SetupCommand {
         println!("{} Checking Node.js installation...", "→".green());
 
         let checker = NodeChecker::new();
-        let check = checker
-            .check()
+        let check = check_responsive(&checker)
             .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to check Node.js: {}", e)))?;
 
         match check.status {
@@ -86,15 +87,15 @@ impl SetupCommand {
                 println!();
                 println!(
                     "{}",
-                    "Node.js is required for npm-based MCP servers.".yellow()
+                    tr("Node.js is required for npm-based MCP servers.").yellow()
                 );
 
                 if let Some(ref instructions) = check.install_instructions {
-                    println!("To install Node.js:");
+                    println!("{}", tr("To install Node.js:"));
                     for method in instructions.for_platform() {
                         println!("  • {}: {}", method.name.green(), method.command.cyan());
                         if let Some(desc) = &method.description {
-                            println!("    {}", desc.dimmed());
+                            println!("    {}", tr(desc).dimmed());
                         }
                     }
                 }
@@ -124,6 +125,14 @@ impl SetupCommand {
                 println!("    Issue: {issue}");
                 println!("    Solution: {}", solution.cyan());
             }
+            crate::deps::DependencyStatus::Unresponsive { command, .. } => {
+                println!(
+                    "  {} Node.js: '{}' is not responding",
+                    "⚠".yellow(),
+                    command
+                );
+                println!("    {}", unresponsive_advice(&command).cyan());
+            }
         }
 
         Ok(())
@@ -133,8 +142,7 @@ impl SetupCommand {
         println!("{} Checking Docker installation...", "→".green());
 
         let checker = DockerChecker::new();
-        let check = checker
-            .check()
+        let check = check_responsive(&checker)
             .map_err(|e| McpError::Other(anyhow::anyhow!("Failed to check Docker: {}", e)))?;
 
         match check.status {
@@ -146,6 +154,10 @@ impl SetupCommand {
                 println!("  {} Docker: Not installed (optional)", "ℹ".blue());
                 println!("    Docker is only needed for container-based MCP servers");
             }
+            crate::deps::DependencyStatus::Unresponsive { command, .. } => {
+                println!("  {} Docker: '{}' is not responding", "⚠".yellow(), command);
+                println!("    {}", unresponsive_advice(&command).cyan());
+            }
             _ => {
                 println!("  {} Docker: May need configuration", "⚠".yellow());
             }
@@ -175,9 +187,9 @@ impl SetupCommand {
         };
 
         // Try to run npx --version
-        match Command::new(npx_cmd).arg("--version").output() {
-            Ok(output) if output.status.success() => {
-                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        match Process::new(npx_cmd).arg("--version").output() {
+            Ok(output) if output.success() => {
+                let version = output.stdout_lossy().trim().to_string();
                 println!(
                     "  {} npx command works correctly ({})",
                     "✓".green(),
@@ -209,10 +221,10 @@ impl SetupCommand {
     }
 
     fn get_command_version(&self, command: &str, args: &[&str]) -> Result<String> {
-        let output = Command::new(command).args(args).output()?;
+        let output = Process::new(command).args(args).output()?;
 
-        if output.status.success() {
-            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        if output.success() {
+            Ok(output.stdout_lossy().trim().to_string())
         } else {
             anyhow::bail!("Command failed")
         }
@@ -243,8 +255,8 @@ mod tests {
             assert!(result.is_ok() || result.is_err()); // May or may not work
         } else {
             let result = setup.get_command_version("echo", &["test"]);
-            if result.is_ok() {
-                assert_eq!(result.unwrap(), "test");
+            if let Ok(version) = result {
+                assert_eq!(version, "test");
             }
         }
     }
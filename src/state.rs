@@ -0,0 +1,232 @@
+//! `mcp state encrypt` / `mcp state decrypt`: switch mcp-helper's own state files
+//! between plaintext and encryption at rest.
+//!
+//! State files (dependency and metadata caches, meta-server membership, hook
+//! approvals, managed servers) can hold paths and tokens. `encrypt` creates the
+//! state key in the OS keychain, re-writes every existing file sealed with it and
+//! turns on `security.encrypt_state` so later saves stay encrypted. `decrypt`
+//! reverses this. Preferences are edited by hand and stay plaintext.
+
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+use crate::cache::CacheManager;
+use crate::config::hooks::HookApprovals;
+use crate::config::{ManagedServers, MetaMembership, Preferences};
+use crate::deps::install_log::InstallLog;
+use crate::error::McpError;
+use crate::utils::filesystem::RealFileSystem;
+use crate::utils::state_crypto::{self, StateKey};
+use crate::utils::state_file::{decode_state_with, is_encrypted, write_state_with};
+
+/// Every machine-written state file, whether or not it exists yet
+pub fn state_files() -> Vec<PathBuf> {
+    let mut files = CacheManager::state_files().unwrap_or_default();
+    files.extend(MetaMembership::new().map(|m| m.path().to_path_buf()));
+    files.extend(HookApprovals::new().map(|a| a.path().to_path_buf()));
+    files.extend(ManagedServers::new().map(|m| m.path().to_path_buf()));
+    files.extend(InstallLog::new().map(|l| l.path().to_path_buf()));
+    files
+}
+
+/// What happened to one file during a migration
+#[derive(Debug, Clone, PartialEq)]
+pub enum Migration {
+    Rewritten,
+    Unchanged,
+    Failed(String),
+}
+
+/// Re-write the state file at `path` sealed with `target`, or in plaintext when
+/// `target` is `None`. `current` opens files that are already encrypted.
+pub fn migrate_file(
+    path: &Path,
+    current: Option<&StateKey>,
+    target: Option<&StateKey>,
+) -> Migration {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => return Migration::Failed(e.to_string()),
+    };
+    let unchanged = match target {
+        Some(key) => sealed_key_id(&content).as_deref() == Some(key.id().as_str()),
+        None => !is_encrypted(&content),
+    };
+    if unchanged {
+        return Migration::Unchanged;
+    }
+
+    let value: serde_json::Value = match decode_state_with(&content, || {
+        current
+            .cloned()
+            .ok_or_else(|| "state encryption key is not available".to_string())
+    }) {
+        Ok(value) => value,
+        Err(corruption) => return Migration::Failed(corruption.to_string()),
+    };
+    match write_state_with(&RealFileSystem, path, &value, target) {
+        Ok(()) => Migration::Rewritten,
+        Err(e) => Migration::Failed(e.to_string()),
+    }
+}
+
+fn sealed_key_id(content: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    value.get("key_id")?.as_str().map(str::to_string)
+}
+
+/// Encrypt or decrypt mcp-helper's state files
+pub struct StateCommand {
+    verbose: bool,
+}
+
+impl StateCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self { verbose }
+    }
+
+    /// Create the key if needed, encrypt existing state and keep new state encrypted
+    pub fn encrypt(&self) -> Result<(), McpError> {
+        let key = state_crypto::load_or_create_key().map_err(McpError::Other)?;
+        println!(
+            "{} Using state key {} from the keychain",
+            "🔐".blue(),
+            key.id().cyan()
+        );
+
+        let failed = self.migrate_all(Some(&key), Some(&key));
+        self.set_encrypt_state(true)?;
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "{failed} state file(s) could not be encrypted"
+            )));
+        }
+        println!(
+            "{} State files are encrypted; new state will be saved encrypted",
+            "✓".green()
+        );
+        Ok(())
+    }
+
+    /// Decrypt existing state and save new state in plaintext
+    pub fn decrypt(&self) -> Result<(), McpError> {
+        let key = state_crypto::load_key().ok();
+        let failed = self.migrate_all(key.as_ref(), None);
+        self.set_encrypt_state(false)?;
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "{failed} state file(s) could not be decrypted"
+            )));
+        }
+        println!(
+            "{} State files are stored in plaintext; the key stays in the keychain under '{}'",
+            "✓".green(),
+            state_crypto::KEY_NAME
+        );
+        Ok(())
+    }
+
+    fn migrate_all(&self, current: Option<&StateKey>, target: Option<&StateKey>) -> usize {
+        let mut failed = 0;
+        for path in state_files().iter().filter(|path| path.exists()) {
+            match migrate_file(path, current, target) {
+                Migration::Rewritten => println!("  {} {}", "✓".green(), path.display()),
+                Migration::Unchanged => {
+                    if self.verbose {
+                        println!("  {} {} (already done)", "-".dimmed(), path.display());
+                    }
+                }
+                Migration::Failed(reason) => {
+                    failed += 1;
+                    eprintln!("  {} {}: {}", "✗".red(), path.display(), reason);
+                }
+            }
+        }
+        failed
+    }
+
+    fn set_encrypt_state(&self, enabled: bool) -> Result<(), McpError> {
+        let mut prefs = Preferences::load().map_err(McpError::Other)?;
+        if prefs.security.encrypt_state != enabled {
+            prefs.security.encrypt_state = enabled;
+            prefs.save().map_err(McpError::Other)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    fn read<T: serde::de::DeserializeOwned>(path: &Path, key: &StateKey) -> T {
+        let content = std::fs::read_to_string(path).unwrap();
+        decode_state_with(&content, || Ok(key.clone())).unwrap()
+    }
+
+    #[test]
+    fn test_migrate_file_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("managed-servers.json");
+        let value = BTreeMap::from([("token".to_string(), "npm-secret".to_string())]);
+        write_state_with(&RealFileSystem, &path, &value, None).unwrap();
+        let key = StateKey::from_bytes([3; 32]);
+
+        assert_eq!(migrate_file(&path, None, Some(&key)), Migration::Rewritten);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(is_encrypted(&content));
+        assert!(!content.contains("npm-secret"));
+        assert_eq!(read::<BTreeMap<String, String>>(&path, &key), value);
+
+        // Already sealed with this key
+        assert_eq!(
+            migrate_file(&path, Some(&key), Some(&key)),
+            Migration::Unchanged
+        );
+
+        assert_eq!(migrate_file(&path, Some(&key), None), Migration::Rewritten);
+        assert!(!is_encrypted(&std::fs::read_to_string(&path).unwrap()));
+        assert_eq!(migrate_file(&path, None, None), Migration::Unchanged);
+    }
+
+    #[test]
+    fn test_migrate_file_without_key_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("cache.json");
+        let key = StateKey::from_bytes([5; 32]);
+        write_state_with(&RealFileSystem, &path, &vec![1, 2, 3], Some(&key)).unwrap();
+
+        assert!(matches!(
+            migrate_file(&path, None, None),
+            Migration::Failed(reason) if reason.contains("encrypted")
+        ));
+        // The file is left as it was
+        assert!(is_encrypted(&std::fs::read_to_string(&path).unwrap()));
+    }
+
+    #[test]
+    fn test_rotating_to_a_new_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("hook-approvals.json");
+        let value = vec!["approved".to_string()];
+        let old = StateKey::from_bytes([1; 32]);
+        let new = StateKey::from_bytes([2; 32]);
+        write_state_with(&RealFileSystem, &path, &value, Some(&old)).unwrap();
+
+        // Opening needs the key the file was sealed with
+        assert!(matches!(
+            migrate_file(&path, Some(&new), Some(&new)),
+            Migration::Failed(reason) if reason.contains(&old.id())
+        ));
+
+        assert_eq!(
+            migrate_file(&path, Some(&old), Some(&new)),
+            Migration::Rewritten
+        );
+        assert_eq!(read::<Vec<String>>(&path, &new), value);
+    }
+}
@@ -0,0 +1,245 @@
+//! Status command showing run history for servers launched with `mcp run`.
+//!
+//! `mcp status --summary` prints one line such as `MCP: 7 ok, 1 warn, 0 err` for shell
+//! prompts and tmux status bars. It only reads the recorded run history, so it never
+//! starts processes or reads client configs.
+
+use chrono::{DateTime, Duration, Utc};
+use colored::Colorize;
+
+use crate::error::McpError;
+use crate::runner::{RunHistory, ServerRunSummary};
+
+/// Show recent runs and crashes of supervised servers
+pub struct StatusCommand {
+    verbose: bool,
+    window: Duration,
+}
+
+impl StatusCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            window: Duration::hours(1),
+        }
+    }
+
+    pub fn execute(&self) -> Result<(), McpError> {
+        let history = RunHistory::new().map_err(McpError::Other)?;
+        let now = Utc::now();
+        let summaries = history
+            .summaries(now, self.window)
+            .map_err(McpError::Other)?;
+
+        if summaries.is_empty() {
+            println!("{} No server runs recorded yet", "ℹ".blue());
+            println!("  Servers started with 'mcp run' appear here.");
+            return Ok(());
+        }
+
+        println!("{}", "Server status".bold());
+        println!();
+
+        for summary in &summaries {
+            self.print_summary(summary, now);
+        }
+
+        if self.verbose {
+            println!("History stored in {}", history.dir().display());
+        }
+
+        Ok(())
+    }
+
+    /// Print the one-line health summary and return its exit code (see [`HealthCounts`]).
+    pub fn summary_line(&self) -> i32 {
+        let summaries =
+            RunHistory::new().and_then(|history| history.summaries(Utc::now(), self.window));
+        match summaries {
+            Ok(summaries) => {
+                let counts = HealthCounts::from_summaries(&summaries);
+                println!("{}", counts.line());
+                counts.exit_code()
+            }
+            Err(e) => {
+                println!("MCP: unknown");
+                if self.verbose {
+                    eprintln!("{e:#}");
+                }
+                HealthCounts::UNKNOWN_EXIT_CODE
+            }
+        }
+    }
+
+    fn print_summary(&self, summary: &ServerRunSummary, now: DateTime<Utc>) {
+        let last = &summary.last_run;
+        let marker = if last.crashed() {
+            "✗".red()
+        } else {
+            "✓".green()
+        };
+
+        println!(
+            "{} {}  last run {} ({}), {} run(s) recorded",
+            marker,
+            summary.server.cyan(),
+            format_ago(now - last.ended_at),
+            describe_exit(last.exit_code),
+            summary.total_runs
+        );
+
+        if summary.recent_crashes > 0 {
+            println!(
+                "  {} crashed {} in the last hour",
+                "⚠".yellow(),
+                pluralize_times(summary.recent_crashes)
+            );
+        }
+
+        if let Some(report) = &summary.last_crash_report {
+            if summary.recent_crashes > 0 || self.verbose {
+                println!("  Crash report: {}", report.display().to_string().dimmed());
+            }
+        }
+        println!();
+    }
+}
+
+/// Servers per health state, from their run history.
+///
+/// A server is an error when its last run crashed, a warning when it crashed
+/// within the status window but has since run cleanly, and ok otherwise.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HealthCounts {
+    pub ok: usize,
+    pub warn: usize,
+    pub err: usize,
+}
+
+impl HealthCounts {
+    /// Exit code when the run history can't be read
+    pub const UNKNOWN_EXIT_CODE: i32 = 3;
+
+    pub fn from_summaries(summaries: &[ServerRunSummary]) -> Self {
+        let mut counts = Self::default();
+        for summary in summaries {
+            if summary.last_run.crashed() {
+                counts.err += 1;
+            } else if summary.recent_crashes > 0 {
+                counts.warn += 1;
+            } else {
+                counts.ok += 1;
+            }
+        }
+        counts
+    }
+
+    pub fn line(&self) -> String {
+        format!("MCP: {} ok, {} warn, {} err", self.ok, self.warn, self.err)
+    }
+
+    /// 0 when everything is ok, 1 with warnings, 2 with errors
+    pub fn exit_code(&self) -> i32 {
+        if self.err > 0 {
+            2
+        } else if self.warn > 0 {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+fn describe_exit(exit_code: Option<i32>) -> String {
+    match exit_code {
+        Some(0) => "exited cleanly".to_string(),
+        Some(code) => format!("exit code {code}"),
+        None => "terminated by signal".to_string(),
+    }
+}
+
+fn pluralize_times(count: usize) -> String {
+    match count {
+        1 => "once".to_string(),
+        n => format!("{n} times"),
+    }
+}
+
+/// Format an elapsed duration like "5m ago"
+pub fn format_ago(elapsed: Duration) -> String {
+    let seconds = elapsed.num_seconds().max(0);
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86_399 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_ago() {
+        assert_eq!(format_ago(Duration::seconds(10)), "just now");
+        assert_eq!(format_ago(Duration::minutes(5)), "5m ago");
+        assert_eq!(format_ago(Duration::hours(3)), "3h ago");
+        assert_eq!(format_ago(Duration::days(2)), "2d ago");
+        assert_eq!(format_ago(Duration::seconds(-5)), "just now");
+    }
+
+    fn summary(exit_code: Option<i32>, recent_crashes: usize) -> ServerRunSummary {
+        let now = Utc::now();
+        ServerRunSummary {
+            server: "server".to_string(),
+            total_runs: 3,
+            last_run: crate::runner::RunRecord {
+                server: "server".to_string(),
+                started_at: now,
+                ended_at: now,
+                exit_code,
+                crash_report: None,
+            },
+            recent_crashes,
+            last_crash_report: None,
+        }
+    }
+
+    #[test]
+    fn test_health_counts() {
+        let counts = HealthCounts::from_summaries(&[
+            summary(Some(0), 0),
+            summary(Some(0), 0),
+            summary(Some(0), 2),
+            summary(Some(1), 1),
+        ]);
+        assert_eq!(
+            counts,
+            HealthCounts {
+                ok: 2,
+                warn: 1,
+                err: 1
+            }
+        );
+        assert_eq!(counts.line(), "MCP: 2 ok, 1 warn, 1 err");
+        assert_eq!(counts.exit_code(), 2);
+
+        assert_eq!(HealthCounts::from_summaries(&[summary(None, 0)]).err, 1);
+        assert_eq!(
+            HealthCounts::from_summaries(&[summary(Some(0), 1)]).exit_code(),
+            1
+        );
+        assert_eq!(HealthCounts::default().line(), "MCP: 0 ok, 0 warn, 0 err");
+        assert_eq!(HealthCounts::default().exit_code(), 0);
+    }
+
+    #[test]
+    fn test_describe_exit() {
+        assert_eq!(describe_exit(Some(0)), "exited cleanly");
+        assert_eq!(describe_exit(Some(1)), "exit code 1");
+        assert_eq!(describe_exit(None), "terminated by signal");
+        assert_eq!(pluralize_times(3), "3 times");
+        assert_eq!(pluralize_times(1), "once");
+    }
+}
@@ -0,0 +1,345 @@
+//! Mirror one client's MCP servers into other clients.
+//!
+//! Each target client writes the entries in its own format through
+//! [`McpClient::add_server`], so copying from Claude Desktop to VS Code produces
+//! a proper `servers` entry rather than a verbatim copy of the JSON.
+
+use colored::Colorize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::client::{detect_clients, McpClient, ServerConfig};
+use crate::config::ConfigManager;
+use crate::error::McpError;
+
+/// What syncing one server into one target client does
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncAction {
+    /// The target has no server by this name
+    Add,
+    /// The target has a different definition and `--overwrite` was given
+    Update,
+    /// The target already has the same definition
+    Unchanged,
+    /// The target has a different definition and overwriting was not requested
+    Conflict,
+    /// The server's command is not available on this machine
+    MissingDependency(String),
+}
+
+impl SyncAction {
+    fn writes(&self) -> bool {
+        matches!(self, Self::Add | Self::Update)
+    }
+}
+
+/// Decide what to do with each source server for one target, sorted by server name.
+pub fn plan_sync(
+    source: &HashMap<String, ServerConfig>,
+    target: &HashMap<String, ServerConfig>,
+    overwrite: bool,
+    command_available: &dyn Fn(&str) -> bool,
+) -> Vec<(String, SyncAction)> {
+    let mut names: Vec<_> = source.keys().cloned().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let config = &source[&name];
+            let action = match target.get(&name) {
+                Some(existing) if existing == config => SyncAction::Unchanged,
+                Some(_) if !overwrite => SyncAction::Conflict,
+                _ if !command_available(&config.command) => {
+                    SyncAction::MissingDependency(config.command.clone())
+                }
+                Some(_) => SyncAction::Update,
+                None => SyncAction::Add,
+            };
+            (name, action)
+        })
+        .collect()
+}
+
+/// Whether a server command can be launched on this machine
+pub fn command_available(command: &str) -> bool {
+    let path = Path::new(command);
+    if path.is_absolute() {
+        return path.exists();
+    }
+    which::which(command).is_ok()
+}
+
+/// Copy every server from one client to other installed clients
+pub struct SyncClientsCommand {
+    verbose: bool,
+    dry_run: bool,
+    overwrite: bool,
+}
+
+impl SyncClientsCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            dry_run: false,
+            overwrite: false,
+        }
+    }
+
+    /// Only show what would change
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    /// Replace target entries that differ from the source
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+    }
+
+    /// Sync from the client named `from` into the clients named in `to`.
+    ///
+    /// `to` may contain `all` to target every other installed client.
+    pub fn execute(&self, from: &str, to: &[String]) -> Result<(), McpError> {
+        let clients = detect_clients();
+        let installed: Vec<&dyn McpClient> = clients
+            .iter()
+            .filter(|c| c.is_installed())
+            .map(|c| c.as_ref())
+            .collect();
+
+        let source = find_client(&installed, from)?;
+        let targets = select_targets(&installed, source, to)?;
+        if targets.is_empty() {
+            println!("{} No other installed clients to sync to", "ℹ".blue());
+            return Ok(());
+        }
+
+        let source_servers = source.list_servers().map_err(|e| {
+            McpError::Other(anyhow::anyhow!(
+                "Failed to read {} configuration: {}",
+                source.name(),
+                e
+            ))
+        })?;
+        if source_servers.is_empty() {
+            println!("{} {} has no servers configured", "ℹ".blue(), source.name());
+            return Ok(());
+        }
+
+        let title = if self.dry_run {
+            "🔄 Sync preview (dry run)"
+        } else {
+            "🔄 Syncing MCP servers"
+        };
+        println!("{}", title.blue().bold());
+        println!(
+            "  {} server(s) from {}",
+            source_servers.len(),
+            source.name().cyan()
+        );
+        println!();
+
+        let manager = if self.dry_run {
+            None
+        } else {
+            Some(ConfigManager::new().map_err(|e| {
+                McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+            })?)
+        };
+
+        let mut written = 0;
+        let mut skipped = 0;
+        let mut failed = 0;
+
+        for target in targets {
+            let target_servers = match target.list_servers() {
+                Ok(servers) => servers,
+                Err(e) => {
+                    println!(
+                        "{} {} - Error reading config: {}",
+                        "⚠".yellow(),
+                        target.name(),
+                        e.to_string().dimmed()
+                    );
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            println!("{}", target.name().cyan().bold());
+            let plan = plan_sync(
+                &source_servers,
+                &target_servers,
+                self.overwrite,
+                &command_available,
+            );
+
+            for (name, action) in plan {
+                if action.writes() {
+                    if let Some(manager) = &manager {
+                        if let Err(e) =
+                            manager.apply_config(target, &name, source_servers[&name].clone())
+                        {
+                            failed += 1;
+                            println!("  {} {} - {}", "✗".red(), name, e.to_string().dimmed());
+                            continue;
+                        }
+                    }
+                    written += 1;
+                } else if action != SyncAction::Unchanged {
+                    skipped += 1;
+                }
+                self.print_action(&name, &action);
+            }
+            println!();
+        }
+
+        let verb = if self.dry_run { "Would write" } else { "Wrote" };
+        println!(
+            "{} {} {} server entr{}, skipped {}{}",
+            "✓".green(),
+            verb,
+            written,
+            if written == 1 { "y" } else { "ies" },
+            skipped,
+            if failed > 0 {
+                format!(", {} failed", failed.to_string().red())
+            } else {
+                String::new()
+            }
+        );
+
+        if failed > 0 {
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Failed to sync {} server entr{}",
+                failed,
+                if failed == 1 { "y" } else { "ies" }
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn print_action(&self, name: &str, action: &SyncAction) {
+        match action {
+            SyncAction::Add => println!("  {} {}", "+".green(), name),
+            SyncAction::Update => println!("  {} {} (replaced)", "~".yellow(), name),
+            SyncAction::Unchanged => {
+                if self.verbose {
+                    println!("  {} {} (already in sync)", "=".dimmed(), name);
+                }
+            }
+            SyncAction::Conflict => println!(
+                "  {} {} {}",
+                "!".yellow(),
+                name,
+                "(differs from source; use --overwrite to replace)".dimmed()
+            ),
+            SyncAction::MissingDependency(command) => println!(
+                "  {} {} {}",
+                "-".red(),
+                name,
+                format!("(skipped: '{command}' is not installed)").dimmed()
+            ),
+        }
+    }
+}
+
+fn find_client<'a>(
+    clients: &[&'a dyn McpClient],
+    name: &str,
+) -> Result<&'a dyn McpClient, McpError> {
+    clients
+        .iter()
+        .copied()
+        .find(|c| c.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            McpError::client_not_found(
+                name,
+                clients.iter().map(|c| c.name().to_string()).collect(),
+                "Use one of the installed client names, e.g. --from \"Claude Desktop\"",
+            )
+        })
+}
+
+fn select_targets<'a>(
+    clients: &[&'a dyn McpClient],
+    source: &dyn McpClient,
+    to: &[String],
+) -> Result<Vec<&'a dyn McpClient>, McpError> {
+    if to.is_empty() || to.iter().any(|t| t.eq_ignore_ascii_case("all")) {
+        return Ok(clients
+            .iter()
+            .copied()
+            .filter(|c| c.name() != source.name())
+            .collect());
+    }
+
+    let mut targets = Vec::new();
+    for name in to {
+        let client = find_client(clients, name)?;
+        if client.name() == source.name() {
+            continue;
+        }
+        if !targets
+            .iter()
+            .any(|t: &&dyn McpClient| t.name() == client.name())
+        {
+            targets.push(client);
+        }
+    }
+    Ok(targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_sync() {
+        let source = HashMap::from([
+            ("fs".to_string(), server("npx", &["@org/fs"])),
+            ("github".to_string(), server("npx", &["@org/github"])),
+            ("db".to_string(), server("docker", &["run", "-i", "org/db"])),
+            ("local".to_string(), server("npx", &["@org/local"])),
+        ]);
+        let target = HashMap::from([
+            ("fs".to_string(), server("npx", &["@org/fs"])),
+            ("github".to_string(), server("npx", &["@org/github@1.0"])),
+        ]);
+        let available = |command: &str| command != "docker";
+
+        let plan = plan_sync(&source, &target, false, &available);
+        assert_eq!(
+            plan,
+            vec![
+                (
+                    "db".to_string(),
+                    SyncAction::MissingDependency("docker".to_string())
+                ),
+                ("fs".to_string(), SyncAction::Unchanged),
+                ("github".to_string(), SyncAction::Conflict),
+                ("local".to_string(), SyncAction::Add),
+            ]
+        );
+
+        let plan = plan_sync(&source, &target, true, &available);
+        assert_eq!(plan[2], ("github".to_string(), SyncAction::Update));
+    }
+
+    #[test]
+    fn test_command_available() {
+        assert!(!command_available("definitely-not-a-real-command-xyz"));
+        let exe = std::env::current_exe().unwrap();
+        assert!(command_available(exe.to_str().unwrap()));
+    }
+}
@@ -0,0 +1,232 @@
+//! `mcp test-server`: a minimal built-in MCP server for checking client setup.
+//!
+//! The server speaks newline-delimited JSON-RPC over stdio and exposes a single
+//! `echo` tool, so a client can be verified without any third-party package.
+//! `mcp install builtin:test` adds it to a client, pointing at this binary.
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+
+use crate::error::McpError;
+
+/// Protocol version answered when the client doesn't ask for one
+pub const DEFAULT_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// Name the test server reports and is configured under
+pub const TEST_SERVER_NAME: &str = "mcp-helper-test";
+
+/// Prefix of built-in server specs (`builtin:test`)
+pub const BUILTIN_PREFIX: &str = "builtin:";
+
+/// Built-in servers that `builtin:<name>` can refer to
+pub const BUILTIN_SERVERS: &[&str] = &["test"];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Whether `server` is a `builtin:` spec
+pub fn is_builtin(server: &str) -> bool {
+    server.starts_with(BUILTIN_PREFIX)
+}
+
+/// Command, args and server name for a `builtin:<name>` spec
+pub fn builtin_config(server: &str) -> Result<(String, Vec<String>, String), McpError> {
+    let name = server.strip_prefix(BUILTIN_PREFIX).unwrap_or(server);
+    match name {
+        "test" => {
+            let exe = std::env::current_exe()
+                .map_err(|e| McpError::io_error("locate mcp-helper binary", None, e))?;
+            Ok((
+                exe.to_string_lossy().to_string(),
+                vec!["test-server".to_string()],
+                TEST_SERVER_NAME.to_string(),
+            ))
+        }
+        _ => Err(McpError::Other(anyhow::anyhow!(
+            "Unknown built-in server '{}'. Available: {}",
+            name,
+            BUILTIN_SERVERS
+                .iter()
+                .map(|builtin| format!("{BUILTIN_PREFIX}{builtin}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Serve requests from `input` until it is closed
+pub fn serve(input: impl BufRead, mut output: impl Write) -> Result<()> {
+    for line in input.lines() {
+        let line = line.context("Failed to read from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = handle_line(&line) {
+            writeln!(output, "{reply}").context("Failed to write to stdout")?;
+            output.flush().context("Failed to write to stdout")?;
+        }
+    }
+    Ok(())
+}
+
+/// The reply to one line of input; notifications get none
+pub fn handle_line(line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(error(
+                Value::Null,
+                PARSE_ERROR,
+                &format!("Parse error: {e}"),
+            ))
+        }
+    };
+    let id = message.get("id").cloned()?;
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        return Some(error(id, INVALID_REQUEST, "Missing method"));
+    };
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = match method {
+        "initialize" => Ok(initialize(&params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": [echo_tool()] })),
+        "tools/call" => call_tool(&params),
+        _ => Err((METHOD_NOT_FOUND, format!("Method not found: {method}"))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error(id, code, &message),
+    })
+}
+
+fn initialize(params: &Value) -> Value {
+    let version = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .unwrap_or(DEFAULT_PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": {
+            "name": TEST_SERVER_NAME,
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+fn echo_tool() -> Value {
+    json!({
+        "name": "echo",
+        "description": "Return the given message unchanged",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "message": { "type": "string", "description": "Text to echo back" }
+            },
+            "required": ["message"],
+        },
+    })
+}
+
+fn call_tool(params: &Value) -> Result<Value, (i64, String)> {
+    let name = params.get("name").and_then(Value::as_str);
+    if name != Some("echo") {
+        return Err((
+            INVALID_PARAMS,
+            format!("Unknown tool: {}", name.unwrap_or("<missing>")),
+        ));
+    }
+    let message = params
+        .pointer("/arguments/message")
+        .and_then(Value::as_str)
+        .ok_or((INVALID_PARAMS, "'message' must be a string".to_string()))?;
+    Ok(json!({ "content": [{ "type": "text", "text": message }] }))
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn replies(input: &str) -> Vec<Value> {
+        let mut output = Vec::new();
+        serve(Cursor::new(input), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_handshake_and_echo() {
+        let replies = replies(concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"echo","arguments":{"message":"hello"}}}"#,
+            "\n",
+        ));
+
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0]["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(replies[0]["result"]["serverInfo"]["name"], TEST_SERVER_NAME);
+        assert_eq!(replies[1]["result"]["tools"][0]["name"], "echo");
+        assert_eq!(replies[2]["id"], 3);
+        assert_eq!(
+            replies[2]["result"]["content"],
+            json!([{ "type": "text", "text": "hello" }])
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            handle_line("not json").unwrap()["error"]["code"],
+            PARSE_ERROR
+        );
+        assert_eq!(
+            handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"resources/list"}"#).unwrap()["error"]
+                ["code"],
+            METHOD_NOT_FOUND
+        );
+        assert_eq!(
+            handle_line(
+                r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"add"}}"#
+            )
+            .unwrap()["error"]["code"],
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"echo","arguments":{"message":1}}}"#)
+                .unwrap()["error"]["code"],
+            INVALID_PARAMS
+        );
+    }
+
+    #[test]
+    fn test_builtin_config() {
+        let (command, args, name) = builtin_config("builtin:test").unwrap();
+        assert!(!command.is_empty());
+        assert_eq!(args, vec!["test-server"]);
+        assert_eq!(name, TEST_SERVER_NAME);
+
+        let err = builtin_config("builtin:nope").unwrap_err().to_string();
+        assert!(err.contains("builtin:test"), "{err}");
+    }
+}
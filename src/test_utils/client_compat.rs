@@ -0,0 +1,450 @@
+//! Golden-file compatibility harness for client config formats
+//!
+//! Every client keeps its servers in its own JSON layout, and small serialization
+//! changes can silently drop settings or entries. Each fixture is a sample config
+//! at `tests/fixtures/clients/<client>/<case>.json`, where `<client>` is one of
+//! [`FixtureClient::dir_name`]. Next to it, `<case>.expected.json` lists the stdio
+//! and remote servers mcp-helper should read from it.
+//!
+//! [`check_fixture`] installs the fixture into a temporary home and checks that:
+//!
+//! - the servers read from it match the golden file
+//! - adding a server keeps every existing server and the client's other settings
+//! - removing that server again gives back the same servers, and repeating the
+//!   add/remove cycle writes an identical file
+//!
+//! To cover a config from a bug report, drop it into the client's directory and
+//! run the tests with `MCP_HELPER_UPDATE_GOLDEN=1` to write its golden file, then
+//! check the generated servers by hand before committing both.
+//!
+//! A bundle written by `mcp debug snapshot` loads with [`load_snapshot`]. Its
+//! client configs have no golden files yet, so [`check_round_trip`] runs every
+//! check except the golden comparison; copy a config into the fixture directory
+//! once the bug it shows is understood.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use crate::client::{
+    ClaudeCodeClient, ClaudeDesktopClient, CursorClient, HomeDirectoryProvider, McpClient,
+    ServerConfig, VSCodeClient, WindsurfClient,
+};
+use crate::config::{ManagedServer, Preferences};
+use crate::debug_snapshot::{
+    SnapshotManifest, CLIENTS_DIR, MANAGED_SERVERS_FILE, MANIFEST_FILE, PREFERENCES_FILE,
+};
+
+/// Set to write golden files from the current behavior instead of checking them
+pub const UPDATE_GOLDEN_ENV: &str = "MCP_HELPER_UPDATE_GOLDEN";
+
+/// Name of the server added and removed by [`check_fixture`]
+pub const PROBE_SERVER: &str = "mcp-helper-compat-probe";
+
+/// Clients with a fixture directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureClient {
+    ClaudeDesktop,
+    ClaudeCode,
+    Cursor,
+    VsCode,
+    Windsurf,
+}
+
+impl FixtureClient {
+    pub const ALL: [Self; 5] = [
+        Self::ClaudeDesktop,
+        Self::ClaudeCode,
+        Self::Cursor,
+        Self::VsCode,
+        Self::Windsurf,
+    ];
+
+    /// Directory holding this client's fixtures
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            Self::ClaudeDesktop => "claude-desktop",
+            Self::ClaudeCode => "claude-code",
+            Self::Cursor => "cursor",
+            Self::VsCode => "vscode",
+            Self::Windsurf => "windsurf",
+        }
+    }
+
+    pub fn from_dir_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|client| client.dir_name() == name)
+    }
+
+    /// Top-level key holding the server entries
+    pub fn servers_key(self) -> &'static str {
+        match self {
+            Self::Cursor | Self::VsCode => "servers",
+            Self::ClaudeDesktop | Self::ClaudeCode | Self::Windsurf => "mcpServers",
+        }
+    }
+
+    /// The client, with its config file under `home`
+    pub fn client(self, home: &Path) -> Box<dyn McpClient> {
+        let provider = Box::new(FixtureHome(home.to_path_buf()));
+        match self {
+            Self::ClaudeDesktop => Box::new(ClaudeDesktopClient::with_config_path(
+                home.join("Claude").join("claude_desktop_config.json"),
+            )),
+            Self::ClaudeCode => Box::new(ClaudeCodeClient::new_with_provider(provider)),
+            Self::Cursor => Box::new(CursorClient::new_with_provider(provider)),
+            Self::VsCode => Box::new(VSCodeClient::new_with_provider(provider)),
+            Self::Windsurf => Box::new(WindsurfClient::new_with_provider(provider)),
+        }
+    }
+}
+
+struct FixtureHome(PathBuf);
+
+impl HomeDirectoryProvider for FixtureHome {
+    fn home_dir(&self) -> Option<PathBuf> {
+        Some(self.0.clone())
+    }
+}
+
+/// One sample config
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub client: FixtureClient,
+    pub path: PathBuf,
+}
+
+impl Fixture {
+    pub fn expected_path(&self) -> PathBuf {
+        self.path.with_extension("expected.json")
+    }
+}
+
+impl fmt::Display for Fixture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let case = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        write!(f, "{}/{}", self.client.dir_name(), case)
+    }
+}
+
+/// The servers a client reads from its config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReadServers {
+    #[serde(default)]
+    pub servers: BTreeMap<String, ServerConfig>,
+    #[serde(default)]
+    pub remote_servers: BTreeMap<String, RemoteEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteEntry {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl ReadServers {
+    pub fn from_client(client: &dyn McpClient) -> Result<Self> {
+        let servers = client.list_servers().context("Failed to list servers")?;
+        let remote_servers = client
+            .list_remote_servers()
+            .context("Failed to list remote servers")?
+            .into_iter()
+            .map(|(name, remote)| {
+                let entry = RemoteEntry {
+                    url: remote.url,
+                    headers: remote.headers.into_iter().collect(),
+                };
+                (name, entry)
+            })
+            .collect();
+        Ok(Self {
+            servers: servers.into_iter().collect(),
+            remote_servers,
+        })
+    }
+}
+
+/// The repository's fixture directory
+pub fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("clients")
+}
+
+/// All fixtures under `dir`, sorted by client and name
+pub fn discover(dir: &Path) -> Result<Vec<Fixture>> {
+    let mut fixtures = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let client_dir = entry?.path();
+        if !client_dir.is_dir() {
+            continue;
+        }
+        let dir_name = client_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let Some(client) = FixtureClient::from_dir_name(&dir_name) else {
+            bail!("Unknown client fixture directory '{dir_name}'");
+        };
+
+        for file in fs::read_dir(&client_dir)? {
+            let path = file?.path();
+            let name = path.to_string_lossy();
+            if name.ends_with(".json") && !name.ends_with(".expected.json") {
+                fixtures.push(Fixture { client, path });
+            }
+        }
+    }
+    fixtures.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(fixtures)
+}
+
+/// A bundle written by `mcp debug snapshot`
+#[derive(Debug, Clone)]
+pub struct LoadedSnapshot {
+    pub manifest: SnapshotManifest,
+    pub preferences: Preferences,
+    pub managed_servers: BTreeMap<String, ManagedServer>,
+    pub fixtures: Vec<Fixture>,
+}
+
+#[derive(Deserialize)]
+struct ManagedServersFile {
+    #[serde(default)]
+    servers: BTreeMap<String, ManagedServer>,
+}
+
+/// Load the bundle in `dir`
+pub fn load_snapshot(dir: &Path) -> Result<LoadedSnapshot> {
+    let manifest = serde_json::from_value(read_json(&dir.join(MANIFEST_FILE))?)
+        .context("Invalid snapshot manifest")?;
+    let preferences = Preferences::load_from(&dir.join(PREFERENCES_FILE))?;
+    let managed: ManagedServersFile =
+        serde_json::from_value(read_json(&dir.join(MANAGED_SERVERS_FILE))?)
+            .context("Invalid managed servers record")?;
+    let clients_dir = dir.join(CLIENTS_DIR);
+    let fixtures = if clients_dir.is_dir() {
+        discover(&clients_dir)?
+    } else {
+        Vec::new()
+    };
+    Ok(LoadedSnapshot {
+        manifest,
+        preferences,
+        managed_servers: managed.servers,
+        fixtures,
+    })
+}
+
+/// A stdio server unlike any in the fixtures
+pub fn probe_server() -> ServerConfig {
+    ServerConfig {
+        command: "node".to_string(),
+        args: vec!["probe-server.js".to_string(), "--stdio".to_string()],
+        env: HashMap::from([("PROBE_LEVEL".to_string(), "debug".to_string())]),
+        cwd: None,
+    }
+}
+
+/// Run every compatibility check against one fixture
+pub fn check_fixture(fixture: &Fixture) -> Result<()> {
+    run_checks(fixture, true)
+}
+
+/// Run every check except the golden comparison, for configs without one
+pub fn check_round_trip(fixture: &Fixture) -> Result<()> {
+    run_checks(fixture, false)
+}
+
+fn run_checks(fixture: &Fixture, golden: bool) -> Result<()> {
+    let original = read_json(&fixture.path)?;
+    let home = TempDir::new()?;
+    let client = fixture.client.client(home.path());
+    let config_path = client.config_path();
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(&fixture.path, &config_path)?;
+
+    let read = ReadServers::from_client(&*client).context("Reading the fixture failed")?;
+    if golden {
+        check_golden(fixture, &read)?;
+    }
+
+    // New servers merge without touching existing ones
+    client
+        .add_server(PROBE_SERVER, probe_server())
+        .context("Adding a server failed")?;
+    let mut merged = read.clone();
+    merged
+        .servers
+        .insert(PROBE_SERVER.to_string(), probe_server());
+    let after_add = ReadServers::from_client(&*client)?;
+    ensure!(
+        after_add == merged,
+        "Adding a server changed the configured servers:\nexpected {merged:#?}\ngot {after_add:#?}"
+    );
+    check_settings_kept(fixture.client, &original, &read_json(&config_path)?)?;
+
+    // Removing it again round-trips
+    ensure!(
+        client.remove_server(PROBE_SERVER)?,
+        "The added server could not be removed"
+    );
+    let after_remove = ReadServers::from_client(&*client)?;
+    ensure!(
+        after_remove == read,
+        "Adding and removing a server changed the configured servers:\nexpected {read:#?}\ngot {after_remove:#?}"
+    );
+    let written = read_json(&config_path)?;
+    check_settings_kept(fixture.client, &original, &written)?;
+
+    client.add_server(PROBE_SERVER, probe_server())?;
+    client.remove_server(PROBE_SERVER)?;
+    let rewritten = read_json(&config_path)?;
+    ensure!(
+        rewritten == written,
+        "Writing the config twice gave different files:\nfirst {written:#}\nsecond {rewritten:#}"
+    );
+    Ok(())
+}
+
+fn check_golden(fixture: &Fixture, read: &ReadServers) -> Result<()> {
+    let expected_path = fixture.expected_path();
+    if std::env::var_os(UPDATE_GOLDEN_ENV).is_some() {
+        let value = sort_keys(serde_json::to_value(read)?);
+        let json = serde_json::to_string_pretty(&value)? + "\n";
+        return fs::write(&expected_path, json)
+            .with_context(|| format!("Failed to write {}", expected_path.display()));
+    }
+
+    let content = fs::read_to_string(&expected_path).with_context(|| {
+        format!(
+            "Missing golden file {}; run with {UPDATE_GOLDEN_ENV}=1 to create it",
+            expected_path.display()
+        )
+    })?;
+    let expected: ReadServers = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse {}", expected_path.display()))?;
+    ensure!(
+        *read == expected,
+        "Servers read from the fixture differ from {}:\nexpected {expected:#?}\ngot {read:#?}",
+        expected_path.display()
+    );
+    Ok(())
+}
+
+/// Every top-level setting other than the server entries must survive a write
+fn check_settings_kept(client: FixtureClient, original: &Value, written: &Value) -> Result<()> {
+    let Some(original) = original.as_object() else {
+        return Ok(());
+    };
+    for (key, value) in original {
+        if key == client.servers_key() {
+            continue;
+        }
+        ensure!(
+            written.get(key) == Some(value),
+            "Setting '{key}' was not preserved: expected {value}, got {}",
+            written.get(key).unwrap_or(&Value::Null)
+        );
+    }
+    Ok(())
+}
+
+fn read_json(path: &Path) -> Result<Value> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Order object keys so generated golden files are stable
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let sorted: BTreeMap<String, Value> =
+                map.into_iter().map(|(k, v)| (k, sort_keys(v))).collect();
+            Value::Object(sorted.into_iter().collect())
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(sort_keys).collect()),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_and_check_minimal_fixture() {
+        let dir = TempDir::new().unwrap();
+        let client_dir = dir.path().join("windsurf");
+        fs::create_dir_all(&client_dir).unwrap();
+        fs::write(
+            client_dir.join("minimal.json"),
+            r#"{"mcpServers": {"fetch": {"command": "uvx", "args": ["mcp-server-fetch"]}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            client_dir.join("minimal.expected.json"),
+            r#"{"servers": {"fetch": {"command": "uvx", "args": ["mcp-server-fetch"], "env": {}}}}"#,
+        )
+        .unwrap();
+
+        let fixtures = discover(dir.path()).unwrap();
+        assert_eq!(fixtures.len(), 1);
+        assert_eq!(fixtures[0].client, FixtureClient::Windsurf);
+        assert_eq!(fixtures[0].to_string(), "windsurf/minimal.json");
+        check_fixture(&fixtures[0]).unwrap();
+    }
+
+    #[test]
+    fn test_golden_mismatch_is_reported() {
+        let dir = TempDir::new().unwrap();
+        let client_dir = dir.path().join("windsurf");
+        fs::create_dir_all(&client_dir).unwrap();
+        fs::write(
+            client_dir.join("minimal.json"),
+            r#"{"mcpServers": {"fetch": {"command": "uvx", "args": ["mcp-server-fetch"]}}}"#,
+        )
+        .unwrap();
+        fs::write(
+            client_dir.join("minimal.expected.json"),
+            r#"{"servers": {}}"#,
+        )
+        .unwrap();
+
+        let fixtures = discover(dir.path()).unwrap();
+        let error = check_fixture(&fixtures[0]).unwrap_err();
+        assert!(error.to_string().contains("differ from"));
+    }
+
+    #[test]
+    fn test_unknown_client_directory() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("notepad")).unwrap();
+        assert!(discover(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_settings_kept() {
+        let original = serde_json::json!({"theme": "dark", "mcpServers": {}});
+        let written = serde_json::json!({"theme": "dark", "mcpServers": {"a": {}}});
+        assert!(check_settings_kept(FixtureClient::ClaudeCode, &original, &written).is_ok());
+
+        let written = serde_json::json!({"mcpServers": {}});
+        assert!(check_settings_kept(FixtureClient::ClaudeCode, &original, &written).is_err());
+    }
+}
@@ -16,6 +16,7 @@ pub fn sample_server_config() -> ServerConfig {
             "MCP_ALLOWED_PATHS".to_string(),
             "/tmp,/home/user".to_string(),
         )]),
+        cwd: None,
     }
 }
 
@@ -25,6 +26,7 @@ pub fn minimal_server_config() -> ServerConfig {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     }
 }
 
@@ -76,6 +78,7 @@ pub fn multiple_server_configs() -> HashMap<String, ServerConfig> {
                 "MCP_ALLOWED_PATHS".to_string(),
                 "/home/user/documents".to_string(),
             )]),
+            cwd: None,
         },
     );
 
@@ -85,6 +88,7 @@ pub fn multiple_server_configs() -> HashMap<String, ServerConfig> {
             command: "npx".to_string(),
             args: vec!["@modelcontextprotocol/server-github".to_string()],
             env: HashMap::from([("GITHUB_TOKEN".to_string(), "ghp_test_token".to_string())]),
+            cwd: None,
         },
     );
 
@@ -94,6 +98,7 @@ pub fn multiple_server_configs() -> HashMap<String, ServerConfig> {
             command: "python".to_string(),
             args: vec!["-m".to_string(), "custom_server".to_string()],
             env: HashMap::new(),
+            cwd: None,
         },
     );
 
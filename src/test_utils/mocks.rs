@@ -6,9 +6,13 @@
 use crate::client::{McpClient, ServerConfig};
 use crate::deps::{Dependency, DependencyCheck, DependencyChecker, DependencyStatus};
 use crate::server::{McpServer, ServerMetadata, ServerType};
+use crate::utils::clock::Clock;
+use crate::utils::filesystem::FileSystem;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 /// Type alias for config validation function
 type ConfigValidator = Box<dyn Fn(&HashMap<String, String>) -> Result<()> + Send + Sync>;
@@ -68,6 +72,7 @@ impl MockServerBuilder {
                 description: self.description,
                 required_config: vec![],
                 optional_config: vec![],
+                args_template: vec![],
             },
             dependency: self.dependency,
             config_validator: self.config_validator,
@@ -257,6 +262,115 @@ impl McpClient for MockClient {
         let servers = self.servers.read().unwrap();
         Ok(servers.clone())
     }
+
+    fn remove_server(&self, name: &str) -> Result<bool> {
+        let mut servers = self.servers.write().unwrap();
+        Ok(servers.remove(name).is_some())
+    }
+}
+
+/// A clock that only moves when told to
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl MockClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Move the clock forward by `duration`
+    pub fn advance(&self, duration: std::time::Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += chrono::Duration::from_std(duration).expect("duration out of range");
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+}
+
+impl Default for MockClock {
+    /// Starts at 2024-01-01T00:00:00Z
+    fn default() -> Self {
+        Self::new(DateTime::from_timestamp(1_704_067_200, 0).unwrap())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// An in-memory file system; directories exist implicitly
+#[derive(Debug, Default)]
+pub struct MemoryFileSystem {
+    files: Mutex<HashMap<PathBuf, String>>,
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Contents of `path`, if it was written
+    pub fn file(&self, path: impl AsRef<Path>) -> Option<String> {
+        self.files.lock().unwrap().get(path.as_ref()).cloned()
+    }
+
+    /// Paths of every file, sorted
+    pub fn paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.files.lock().unwrap().keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    fn missing(path: &Path) -> anyhow::Error {
+        anyhow::anyhow!("{} does not exist", path.display())
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn exists(&self, path: &Path) -> bool {
+        let files = self.files.lock().unwrap();
+        files.contains_key(path) || files.keys().any(|file| file.starts_with(path))
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        self.file(path).ok_or_else(|| Self::missing(path))
+    }
+
+    fn write_secure(&self, path: &Path, contents: &str) -> Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), contents.to_string());
+        Ok(())
+    }
+
+    fn create_dir_all(&self, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .remove(path)
+            .map(|_| ())
+            .ok_or_else(|| Self::missing(path))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut files = self.files.lock().unwrap();
+        let contents = files.remove(from).ok_or_else(|| Self::missing(from))?;
+        files.insert(to.to_path_buf(), contents);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -313,6 +427,7 @@ mod tests {
                     command: "npx".to_string(),
                     args: vec!["test-server".to_string()],
                     env: HashMap::new(),
+                    cwd: None,
                 },
             )
             .build();
@@ -324,4 +439,32 @@ mod tests {
             PathBuf::from("/custom/path/config.json")
         );
     }
+
+    #[test]
+    fn test_mock_clock_advances() {
+        let clock = MockClock::default();
+        let start = clock.now();
+        clock.advance(std::time::Duration::from_secs(90));
+        assert_eq!((clock.now() - start).num_seconds(), 90);
+        assert_eq!(clock.unix_secs(), 1_704_067_290);
+    }
+
+    #[test]
+    fn test_memory_file_system() {
+        let fs = MemoryFileSystem::new();
+        let path = Path::new("/state/dir/file.json");
+        assert!(!fs.exists(path));
+        assert!(fs.read_to_string(path).is_err());
+
+        fs.write_secure(path, "{}").unwrap();
+        assert!(fs.exists(path));
+        assert!(fs.exists(Path::new("/state/dir")));
+        assert_eq!(fs.read_to_string(path).unwrap(), "{}");
+
+        let moved = Path::new("/state/dir/file.json.bak");
+        fs.rename(path, moved).unwrap();
+        assert_eq!(fs.paths(), vec![moved.to_path_buf()]);
+        fs.remove_file(moved).unwrap();
+        assert!(fs.paths().is_empty());
+    }
 }
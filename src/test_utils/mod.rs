@@ -11,3 +11,6 @@ pub mod fixtures;
 
 #[cfg(any(test, debug_assertions))]
 pub mod assertions;
+
+#[cfg(any(test, debug_assertions))]
+pub mod client_compat;
@@ -0,0 +1,81 @@
+//! `mcp uninstall`: the counterpart of `mcp install`.
+//!
+//! Removes a server's entries from the selected clients, each through the
+//! [`ConfigManager`](crate::config::ConfigManager) so it can be rolled back from
+//! the config history, and with `--cleanup` deletes what mcp-helper created for
+//! it: virtualenvs, binaries, cached downloads, keychain secrets, and the stopped
+//! containers and unused images of Docker servers. Artifacts that servers still
+//! configured elsewhere use are kept. `--dry-run` shows all of it without
+//! changing anything.
+
+use crate::config_commands::ConfigRemoveCommand;
+use crate::error::McpError;
+
+/// Remove a server from clients and clean up after it
+pub struct UninstallCommand {
+    verbose: bool,
+    all_clients: bool,
+    client: Option<String>,
+    cleanup: bool,
+    dry_run: bool,
+    yes: bool,
+}
+
+impl UninstallCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            all_clients: false,
+            client: None,
+            cleanup: false,
+            dry_run: false,
+            yes: false,
+        }
+    }
+
+    /// Remove the server from every client that has it, without asking which
+    pub fn with_all_clients(mut self, all_clients: bool) -> Self {
+        self.all_clients = all_clients;
+        self
+    }
+
+    /// Only remove the server from this client
+    pub fn with_client(mut self, client: Option<String>) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Also delete the server's files, cached downloads, secrets and Docker
+    /// containers and images
+    pub fn with_cleanup(mut self, cleanup: bool) -> Self {
+        self.cleanup = cleanup;
+        self
+    }
+
+    /// Only show what would be removed
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Remove without asking for confirmation
+    pub fn with_yes(mut self, yes: bool) -> Self {
+        self.yes = yes;
+        self
+    }
+
+    pub fn execute(&self, server_name: &str) -> Result<(), McpError> {
+        self.remove_command().execute(server_name)
+    }
+
+    fn remove_command(&self) -> ConfigRemoveCommand {
+        let mut remove = ConfigRemoveCommand::new(self.verbose);
+        remove.set_remove_all(self.all_clients);
+        remove.set_client(self.client.clone());
+        remove.set_purge(self.cleanup);
+        remove.set_prune_docker(self.cleanup);
+        remove.set_dry_run(self.dry_run);
+        remove.set_yes(self.yes);
+        remove
+    }
+}
@@ -0,0 +1,282 @@
+//! Updates of servers that aren't npm packages.
+//!
+//! - Docker images pinned to a digest (`--pin-digest`) move to the digest their
+//!   tag points at now. Tags are looked up on Docker Hub.
+//! - Binaries downloaded from GitHub Releases move to the asset of the latest
+//!   release, found by the same platform matching as the first install.
+//!
+//! The new image is pulled, or the new binary downloaded, before any config is
+//! written; the configs then only change the image reference or the command.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::Duration;
+
+use super::changelog;
+use crate::cache::CacheManager;
+use crate::client::ServerConfig;
+use crate::config::ServerKind;
+use crate::server::docker::{hub_tag_digest, image_digest, resolve_digest, with_digest};
+use crate::server::{BinaryServer, McpServer};
+use crate::utils::process::{Process, INSTALL_TIMEOUT};
+
+/// A newer release of a Docker image or binary than a config runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtifactUpdate {
+    Docker {
+        /// The image reference in the config, with its digest
+        image: String,
+        current: String,
+        latest: String,
+    },
+    Binary {
+        /// The binary in the config
+        command: String,
+        repo: String,
+        current: String,
+        latest: String,
+        /// Download URL of the latest release's asset for this platform
+        url: String,
+    },
+}
+
+/// What [`check`] found for a config
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArtifactCheck {
+    /// Not a pinned image or a binary from GitHub Releases
+    NotApplicable,
+    /// A Docker image referenced by tag, which only a pull can refresh
+    UnpinnedImage {
+        image: String,
+    },
+    UpToDate {
+        current: String,
+    },
+    Available(ArtifactUpdate),
+}
+
+/// Look up whether the image or binary `config` runs has a newer release
+pub fn check(config: &ServerConfig) -> Result<ArtifactCheck> {
+    if let ServerKind::Docker { image } = ServerKind::infer(config) {
+        let Some(current) = image_digest(&image) else {
+            return Ok(ArtifactCheck::UnpinnedImage { image });
+        };
+        let tagged = image.split('@').next().unwrap_or(&image);
+        let latest = hub_tag_digest(tagged)
+            .with_context(|| format!("Can't look up {tagged} on Docker Hub"))?;
+        if latest == current {
+            return Ok(ArtifactCheck::UpToDate {
+                current: current.to_string(),
+            });
+        }
+        return Ok(ArtifactCheck::Available(ArtifactUpdate::Docker {
+            current: current.to_string(),
+            image,
+            latest,
+        }));
+    }
+
+    let Some(source) = BinaryServer::installed_from(Path::new(&config.command)) else {
+        return Ok(ArtifactCheck::NotApplicable);
+    };
+    let Some((repo, current)) = BinaryServer::github_release_asset(&source) else {
+        anyhow::bail!("{source} is not a GitHub release, so there are no newer releases to find");
+    };
+    let latest = BinaryServer::from_github_repo(&repo, None)?;
+    let url = latest.url().to_string();
+    let (_, latest) = BinaryServer::github_release_asset(&url)
+        .with_context(|| format!("Unexpected release download URL {url}"))?;
+    let is_newer = match (
+        changelog::parse_tag_version(&current),
+        changelog::parse_tag_version(&latest),
+    ) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => current != latest,
+    };
+    if !is_newer {
+        return Ok(ArtifactCheck::UpToDate { current });
+    }
+    Ok(ArtifactCheck::Available(ArtifactUpdate::Binary {
+        command: config.command.clone(),
+        repo,
+        current,
+        latest,
+        url,
+    }))
+}
+
+impl ArtifactUpdate {
+    /// The image or repository the update comes from
+    pub fn source(&self) -> &str {
+        match self {
+            Self::Docker { image, .. } => image.split('@').next().unwrap_or(image),
+            Self::Binary { repo, .. } => repo,
+        }
+    }
+
+    pub fn current(&self) -> &str {
+        match self {
+            Self::Docker { current, .. } | Self::Binary { current, .. } => current,
+        }
+    }
+
+    pub fn latest(&self) -> &str {
+        match self {
+            Self::Docker { latest, .. } | Self::Binary { latest, .. } => latest,
+        }
+    }
+
+    /// Whether a binary's release tags cross a major version; image digests can't tell
+    pub fn is_breaking(&self) -> bool {
+        match self {
+            Self::Docker { .. } => false,
+            Self::Binary {
+                current, latest, ..
+            } => changelog::is_breaking_upgrade(current, latest),
+        }
+    }
+
+    /// Pull the new image or download the new binary, returning how configs change
+    pub fn fetch(&self, cache_manager: &CacheManager) -> Result<ConfigChange> {
+        match self {
+            Self::Docker { image, .. } => {
+                let tagged = image.split('@').next().unwrap_or(image);
+                let digest = resolve_digest(tagged)?;
+                Ok(ConfigChange::Image {
+                    from: image.clone(),
+                    to: with_digest(tagged, &digest),
+                })
+            }
+            Self::Binary { command, url, .. } => {
+                let mut server = BinaryServer::new(url, None);
+                server.download_and_install(Some(cache_manager))?;
+                let (to, _) = server.generate_command()?;
+                Ok(ConfigChange::Command {
+                    from: command.clone(),
+                    to,
+                })
+            }
+        }
+    }
+}
+
+/// How a fetched update changes the configs that run the old release
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    Image { from: String, to: String },
+    Command { from: String, to: String },
+}
+
+impl ConfigChange {
+    pub fn apply(&self, config: &ServerConfig) -> ServerConfig {
+        let mut updated = config.clone();
+        match self {
+            Self::Image { from, to } => {
+                for arg in updated.args.iter_mut().filter(|arg| *arg == from) {
+                    *arg = to.clone();
+                }
+            }
+            Self::Command { from, to } => {
+                if &updated.command == from {
+                    updated.command = to.clone();
+                }
+            }
+        }
+        updated
+    }
+}
+
+/// Pull `image` again, returning whether its tag now points at a different image
+pub fn pull_image(image: &str) -> Result<bool> {
+    let before = image_id(image);
+    let output = Process::new("docker")
+        .args(["pull", image])
+        .timeout(INSTALL_TIMEOUT)
+        .output()
+        .context("Failed to execute docker pull command")?;
+    if !output.success() {
+        anyhow::bail!(
+            "Failed to pull Docker image {}: {}",
+            image,
+            output.stderr_lossy()
+        );
+    }
+    Ok(image_id(image) != before)
+}
+
+/// The ID of the local image `image` refers to
+fn image_id(image: &str) -> Option<String> {
+    let output = Process::new("docker")
+        .args(["image", "inspect", "--format", "{{.Id}}", image])
+        .timeout(Duration::from_secs(30))
+        .output()
+        .ok()?;
+    output
+        .success()
+        .then(|| output.stdout_lossy().trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(command: &str, args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: command.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_unpinned_images_and_plain_commands() {
+        let docker = config("docker", &["run", "-i", "--rm", "mcp/postgres:13"]);
+        assert_eq!(
+            check(&docker).unwrap(),
+            ArtifactCheck::UnpinnedImage {
+                image: "mcp/postgres:13".to_string()
+            }
+        );
+        let binary = config("/usr/local/bin/some-server", &[]);
+        assert_eq!(check(&binary).unwrap(), ArtifactCheck::NotApplicable);
+    }
+
+    #[test]
+    fn test_config_change_apply() {
+        let docker = config("docker", &["run", "-i", "mcp/postgres:13@sha256:old"]);
+        let change = ConfigChange::Image {
+            from: "mcp/postgres:13@sha256:old".to_string(),
+            to: "mcp/postgres:13@sha256:new".to_string(),
+        };
+        assert_eq!(
+            change.apply(&docker).args,
+            vec!["run", "-i", "mcp/postgres:13@sha256:new"]
+        );
+
+        let binary = config("/home/me/.mcp/bin/server-v1/server", &["--stdio"]);
+        let change = ConfigChange::Command {
+            from: "/home/me/.mcp/bin/server-v1/server".to_string(),
+            to: "/home/me/.mcp/bin/server-v2/server".to_string(),
+        };
+        let updated = change.apply(&binary);
+        assert_eq!(updated.command, "/home/me/.mcp/bin/server-v2/server");
+        assert_eq!(updated.args, vec!["--stdio"]);
+    }
+
+    #[test]
+    fn test_binary_update_summary() {
+        let update = ArtifactUpdate::Binary {
+            command: "/bin/server".to_string(),
+            repo: "owner/server".to_string(),
+            current: "v1.4.0".to_string(),
+            latest: "v2.0.0".to_string(),
+            url: "https://github.com/owner/server/releases/download/v2.0.0/server".to_string(),
+        };
+        assert_eq!(update.source(), "owner/server");
+        assert_eq!(update.current(), "v1.4.0");
+        assert_eq!(update.latest(), "v2.0.0");
+        assert!(update.is_breaking());
+    }
+}
@@ -0,0 +1,512 @@
+//! `mcp update --all`: every configured server in one transaction.
+//!
+//! All updates are resolved before anything is written, and shown as a plan
+//! (`current → new` per server) in which entries can be deselected. Servers named
+//! with `--skip` or in the `update.skip` preference are left out. Each config write
+//! is snapshotted in the config history; if one fails, the writes already made are
+//! rolled back so no client is left half-updated.
+
+use colored::Colorize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::IsTerminal;
+
+use super::artifact::{self, ArtifactCheck, ArtifactUpdate};
+use super::ConfigSchema;
+use super::{changelog, find_npm_package_spec, with_package_version, UpdateCommand};
+use crate::cache::CacheManager;
+use crate::client::{detect_clients, McpClient, ServerConfig};
+use crate::config::{ConfigManager, ConfigSnapshot, ManagedServers, Preferences};
+use crate::error::McpError;
+use crate::logging::progress::{step_percent, Progress};
+use crate::utils::prompt::MultiSelect;
+
+/// A server update resolved before anything is written
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedUpdate {
+    pub server: String,
+    /// npm package, Docker image or GitHub repository
+    pub package: String,
+    pub current: String,
+    pub latest: String,
+    /// Whether the update crosses a major version
+    pub is_breaking: bool,
+    /// The image or binary to fetch, for servers that aren't npm packages
+    pub artifact: Option<ArtifactUpdate>,
+}
+
+impl PlannedUpdate {
+    /// One line of the plan: "filesystem  1.0.0 → 1.2.0 (major)"
+    pub fn summary(&self) -> String {
+        format!(
+            "{}  {} → {}{}",
+            self.server,
+            self.current,
+            self.latest,
+            if self.is_breaking { " (major)" } else { "" }
+        )
+    }
+}
+
+/// A config write of the transaction
+pub struct ClientChange<'a> {
+    pub client: &'a dyn McpClient,
+    pub server: String,
+    pub config: ServerConfig,
+}
+
+impl UpdateCommand {
+    /// Update every configured npm server, except skipped ones, in one transaction
+    pub fn execute_all(&self) -> Result<(), McpError> {
+        let progress = Progress::overall("update");
+        let result = self.update_all(&progress);
+        progress.finish(&result, "Update finished");
+        result
+    }
+
+    fn update_all(&self, progress: &Progress) -> Result<(), McpError> {
+        progress.step("check", 10, "Finding configured servers");
+        let clients = detect_clients();
+        let mut servers: BTreeMap<String, Vec<(&dyn McpClient, ServerConfig)>> = BTreeMap::new();
+        for client in clients.iter().filter(|client| client.is_installed()) {
+            let Ok(configs) = client.list_servers() else {
+                continue;
+            };
+            for (name, config) in configs {
+                servers
+                    .entry(name)
+                    .or_default()
+                    .push((client.as_ref(), config));
+            }
+        }
+
+        let skip = self.skip_list();
+        println!(
+            "{} Checking {} server(s) for updates",
+            "→".green(),
+            servers.len()
+        );
+        let mut plan = Vec::new();
+        for (index, (server, targets)) in servers.iter().enumerate() {
+            if skip.contains(server) {
+                println!("  {} {} (skipped)", "-".dimmed(), server.dimmed());
+                continue;
+            }
+            progress.step(
+                "registry",
+                step_percent(10, 30, index, servers.len()),
+                format!("Checking {server}"),
+            );
+            match self.plan_update(server, &targets[0].1) {
+                Ok(Some(planned)) => plan.push(planned),
+                Ok(None) => {}
+                Err(e) => println!("  {} {}: {}", "⚠".yellow(), server, e),
+            }
+        }
+
+        if plan.is_empty() {
+            println!("{} Everything is up to date", "✓".green());
+            return Ok(());
+        }
+
+        println!();
+        println!("{}", "Planned updates:".bold());
+        for planned in &plan {
+            println!(
+                "  {} {} → {}{}",
+                planned.server.cyan(),
+                planned.current,
+                planned.latest.green(),
+                if planned.is_breaking {
+                    format!(" {}", "(major)".red().bold())
+                } else {
+                    String::new()
+                }
+            );
+        }
+        println!();
+
+        let selected = self.select_updates(plan)?;
+        if selected.is_empty() {
+            println!("{} No updates selected", "ℹ".blue());
+            return Ok(());
+        }
+
+        // Every config is migrated, and new values collected, before any is written
+        progress.step("configure", 50, "Preparing new configs");
+        let mut changes = Vec::new();
+        let mut schemas = Vec::new();
+        let cache_manager = CacheManager::new().unwrap_or_default();
+        for planned in &selected {
+            let targets = &servers[&planned.server];
+            // Images are pulled and binaries downloaded before any config is written
+            if let Some(update) = &planned.artifact {
+                let change = update.fetch(&cache_manager).map_err(|e| {
+                    McpError::Other(e.context(format!("Failed to fetch {}", planned.server)))
+                })?;
+                for (client, config) in targets {
+                    changes.push(ClientChange {
+                        client: *client,
+                        server: planned.server.clone(),
+                        config: change.apply(config),
+                    });
+                }
+                continue;
+            }
+            let schema_change = self.schema_change(
+                &planned.server,
+                &planned.package,
+                (planned.current != "unpinned").then_some(planned.current.as_str()),
+                &planned.latest,
+            );
+            let mut values = HashMap::new();
+            for (client, config) in targets {
+                let Some(spec) = find_npm_package_spec(config) else {
+                    continue;
+                };
+                let mut updated = with_package_version(config, &spec, &planned.latest);
+                if let Some((_, delta)) = &schema_change {
+                    updated = self.reconfigure(delta, updated, &mut values)?;
+                }
+                changes.push(ClientChange {
+                    client: *client,
+                    server: planned.server.clone(),
+                    config: updated,
+                });
+            }
+            if let Some((schema, _)) = schema_change {
+                schemas.push((planned.server.clone(), schema));
+            }
+        }
+
+        progress.step("client", 70, format!("Writing {} config(s)", changes.len()));
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+        apply_all(&manager, &changes).map_err(McpError::Other)?;
+
+        record_schemas(schemas);
+        for planned in &selected {
+            println!(
+                "  {} {} {} → {}",
+                "✓".green(),
+                planned.server.cyan(),
+                planned.current,
+                planned.latest.green()
+            );
+        }
+        println!(
+            "{} Updated {} server(s) in {} client config(s)",
+            "✅".green(),
+            selected.len(),
+            changes.len()
+        );
+        Ok(())
+    }
+
+    /// Servers named with `--skip` and in the `update.skip` preference
+    fn skip_list(&self) -> Vec<String> {
+        let mut skip = self.skip.clone();
+        match Preferences::load() {
+            Ok(preferences) => skip.extend(preferences.update.skip),
+            Err(e) => tracing::debug!("Failed to load preferences: {}", e),
+        }
+        skip
+    }
+
+    /// The update `server` would get, or `None` when it's up to date or can't be
+    /// updated through its config
+    fn plan_update(
+        &self,
+        server: &str,
+        config: &ServerConfig,
+    ) -> Result<Option<PlannedUpdate>, McpError> {
+        let Some(spec) = find_npm_package_spec(config) else {
+            return self.plan_artifact_update(server, config);
+        };
+        // Unpinned packages already run `latest`; the beta channel pins them to a beta
+        let channel = self.resolve_channel(server)?;
+        if spec.version.is_none() && channel.is_stable() {
+            if self.verbose {
+                println!("  {} {} is not pinned", "-".dimmed(), server.dimmed());
+            }
+            return Ok(None);
+        }
+        let current = spec.version.unwrap_or_else(|| "unpinned".to_string());
+
+        let info = changelog::fetch_npm_package(&spec.package)
+            .map_err(McpError::Other)?
+            .on_channel(channel);
+        let is_newer = match (
+            changelog::parse_tag_version(&current),
+            changelog::parse_tag_version(&info.latest),
+        ) {
+            (Some(current), Some(latest)) => latest > current,
+            _ => current != info.latest,
+        };
+        if !is_newer {
+            if self.verbose {
+                println!("  {} {} is up to date ({})", "✓".green(), server, current);
+            }
+            return Ok(None);
+        }
+
+        Ok(Some(PlannedUpdate {
+            server: server.to_string(),
+            package: spec.package,
+            is_breaking: changelog::is_breaking_upgrade(&current, &info.latest),
+            current,
+            latest: info.latest,
+            artifact: None,
+        }))
+    }
+
+    /// [`Self::plan_update`] for a pinned Docker image or a GitHub release binary
+    fn plan_artifact_update(
+        &self,
+        server: &str,
+        config: &ServerConfig,
+    ) -> Result<Option<PlannedUpdate>, McpError> {
+        match artifact::check(config).map_err(McpError::Other)? {
+            ArtifactCheck::Available(update) => Ok(Some(PlannedUpdate {
+                server: server.to_string(),
+                package: update.source().to_string(),
+                current: update.current().to_string(),
+                latest: update.latest().to_string(),
+                is_breaking: update.is_breaking(),
+                artifact: Some(update),
+            })),
+            check => {
+                if self.verbose {
+                    let reason = match check {
+                        ArtifactCheck::UpToDate { current } => format!("is up to date ({current})"),
+                        ArtifactCheck::UnpinnedImage { .. } => "is not pinned".to_string(),
+                        _ => "can't be updated through its config".to_string(),
+                    };
+                    println!("  {} {} {}", "-".dimmed(), server.dimmed(), reason);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Let the user deselect entries of `plan`. Major upgrades start deselected
+    /// unless `--allow-major` is given; without a terminal the defaults are taken
+    fn select_updates(&self, plan: Vec<PlannedUpdate>) -> Result<Vec<PlannedUpdate>, McpError> {
+        let defaults: Vec<bool> = plan
+            .iter()
+            .map(|planned| self.allow_major || !planned.is_breaking)
+            .collect();
+
+        let chosen: Vec<usize> = if std::io::stdin().is_terminal() {
+            let items: Vec<String> = plan.iter().map(PlannedUpdate::summary).collect();
+            MultiSelect::new()
+                .with_prompt("Updates to apply (space to toggle, enter to confirm)")
+                .items(&items)
+                .defaults(&defaults)
+                .interact()
+                .map_err(|e| McpError::Other(anyhow::anyhow!("Selection failed: {}", e)))?
+        } else {
+            for planned in plan
+                .iter()
+                .filter(|planned| planned.is_breaking && !self.allow_major)
+            {
+                println!(
+                    "  {} Leaving out the major upgrade of {} (use --allow-major to include it)",
+                    "ℹ".blue(),
+                    planned.server.cyan()
+                );
+            }
+            (0..plan.len()).filter(|&index| defaults[index]).collect()
+        };
+
+        Ok(plan
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| chosen.contains(index))
+            .map(|(_, planned)| planned)
+            .collect())
+    }
+}
+
+/// Write every change, snapshotting each first. When a write fails, the ones
+/// already made are rolled back, newest first, and the error says so
+pub fn apply_all(manager: &ConfigManager, changes: &[ClientChange]) -> anyhow::Result<()> {
+    let mut applied: Vec<(&ClientChange, ConfigSnapshot)> = Vec::with_capacity(changes.len());
+    for change in changes {
+        match manager.apply_config(change.client, &change.server, change.config.clone()) {
+            Ok(snapshot) => applied.push((change, snapshot)),
+            Err(e) => {
+                let failed = format!(
+                    "Failed to update {} in {}: {}",
+                    change.server,
+                    change.client.name(),
+                    e
+                );
+                let mut unrestored = Vec::new();
+                for (done, snapshot) in applied.iter().rev() {
+                    if let Err(e) = manager.rollback(done.client, snapshot) {
+                        unrestored.push(format!(
+                            "{} in {} ({})",
+                            done.server,
+                            done.client.name(),
+                            e
+                        ));
+                    }
+                }
+                if unrestored.is_empty() {
+                    anyhow::bail!("{failed}; {} earlier change(s) rolled back", applied.len());
+                }
+                anyhow::bail!(
+                    "{failed}; could not roll back {}; their previous configs are in the config history",
+                    unrestored.join(", ")
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn record_schemas(schemas: Vec<(String, ConfigSchema)>) {
+    let Ok(managed) = ManagedServers::new() else {
+        return;
+    };
+    for (server, schema) in schemas {
+        let recorded = match managed.get(&server) {
+            Ok(Some(_)) => managed.set_schema(&server, schema),
+            Ok(None) => Ok(()),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = recorded {
+            tracing::warn!("Failed to record config schema of {}: {}", server, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::mocks::{MemoryFileSystem, MockClientBuilder, MockClock};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    /// A client whose config can't be written
+    struct ReadOnlyClient {
+        servers: HashMap<String, ServerConfig>,
+    }
+
+    impl McpClient for ReadOnlyClient {
+        fn name(&self) -> &str {
+            "Read Only"
+        }
+
+        fn config_path(&self) -> PathBuf {
+            PathBuf::from("/mock/read-only.json")
+        }
+
+        fn is_installed(&self) -> bool {
+            true
+        }
+
+        fn add_server(&self, _name: &str, _config: ServerConfig) -> anyhow::Result<()> {
+            anyhow::bail!("permission denied")
+        }
+
+        fn list_servers(&self) -> anyhow::Result<HashMap<String, ServerConfig>> {
+            Ok(self.servers.clone())
+        }
+    }
+
+    fn npx(package: &str) -> ServerConfig {
+        ServerConfig {
+            command: "npx".to_string(),
+            args: vec![package.to_string()],
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    fn manager() -> ConfigManager {
+        ConfigManager::open(
+            PathBuf::from("/history"),
+            Arc::new(MockClock::default()),
+            Arc::new(MemoryFileSystem::new()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_failed_write_rolls_back_earlier_ones() {
+        let manager = manager();
+        let cursor = MockClientBuilder::new("Cursor")
+            .with_server("fs", npx("fs-server@1.0.0"))
+            .with_server("git", npx("git-server@2.0.0"))
+            .build();
+        let read_only = ReadOnlyClient {
+            servers: HashMap::from([("fs".to_string(), npx("fs-server@1.0.0"))]),
+        };
+        let changes = vec![
+            ClientChange {
+                client: &cursor,
+                server: "fs".to_string(),
+                config: npx("fs-server@1.1.0"),
+            },
+            ClientChange {
+                client: &cursor,
+                server: "git".to_string(),
+                config: npx("git-server@2.1.0"),
+            },
+            ClientChange {
+                client: &read_only,
+                server: "fs".to_string(),
+                config: npx("fs-server@1.1.0"),
+            },
+        ];
+
+        let error = apply_all(&manager, &changes).unwrap_err().to_string();
+        assert!(error.contains("Failed to update fs in Read Only"));
+        assert!(error.contains("2 earlier change(s) rolled back"));
+
+        let servers = cursor.list_servers().unwrap();
+        assert_eq!(servers["fs"].args, vec!["fs-server@1.0.0"]);
+        assert_eq!(servers["git"].args, vec!["git-server@2.0.0"]);
+    }
+
+    #[test]
+    fn test_all_writes_applied() {
+        let manager = manager();
+        let cursor = MockClientBuilder::new("Cursor")
+            .with_server("fs", npx("fs-server@1.0.0"))
+            .build();
+        let changes = vec![ClientChange {
+            client: &cursor,
+            server: "fs".to_string(),
+            config: npx("fs-server@1.1.0"),
+        }];
+
+        apply_all(&manager, &changes).unwrap();
+        assert_eq!(
+            cursor.list_servers().unwrap()["fs"].args,
+            vec!["fs-server@1.1.0"]
+        );
+        assert_eq!(
+            manager
+                .get_history(Some("Cursor"), Some("fs"))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_planned_update_summary() {
+        let planned = PlannedUpdate {
+            server: "github".to_string(),
+            package: "@modelcontextprotocol/server-github".to_string(),
+            current: "1.4.0".to_string(),
+            latest: "2.0.0".to_string(),
+            is_breaking: true,
+            artifact: None,
+        };
+        assert_eq!(planned.summary(), "github  1.4.0 → 2.0.0 (major)");
+    }
+}
@@ -0,0 +1,366 @@
+//! Release notes lookup and breaking-change detection for server upgrades.
+//!
+//! Release notes come from GitHub Releases of the repository linked in the npm
+//! package metadata. Breaking changes are detected from the version numbers
+//! (a major bump, or a minor bump while still on `0.x`) and highlighted from
+//! lines in the notes that mention breaking changes. GitHub lookups are cached
+//! and rate limited (see [`crate::server::github`]).
+
+use anyhow::{Context, Result};
+use semver::Version;
+use serde::Deserialize;
+
+use super::UpdateChannel;
+use crate::server::{github, npm_registry};
+
+/// npm registry metadata relevant to upgrades
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackageInfo {
+    pub name: String,
+    /// Newest version on the channel being followed; `latest` until [`Self::on_channel`]
+    pub latest: String,
+    /// The `beta` dist-tag, if the package publishes one
+    pub beta: Option<String>,
+    pub repository: Option<String>,
+}
+
+impl PackageInfo {
+    /// Target `channel`. A beta tag older than `latest` is stale, so `latest` wins then.
+    pub fn on_channel(mut self, channel: UpdateChannel) -> Self {
+        if channel == UpdateChannel::Beta {
+            if let Some(beta) = self.beta.take() {
+                let newer = match (parse_tag_version(&beta), parse_tag_version(&self.latest)) {
+                    (Some(beta), Some(latest)) => beta > latest,
+                    _ => true,
+                };
+                if newer {
+                    self.latest = beta;
+                }
+            }
+        }
+        self
+    }
+}
+
+/// Release notes for a single published version
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseNote {
+    pub version: String,
+    pub body: String,
+}
+
+/// Summary shown to the user before an upgrade is applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpgradeNotice {
+    pub package: String,
+    pub current: String,
+    pub latest: String,
+    pub is_breaking: bool,
+    pub breaking_changes: Vec<String>,
+    pub releases: Vec<ReleaseNote>,
+    pub notes_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NpmPackument {
+    name: String,
+    #[serde(rename = "dist-tags")]
+    dist_tags: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    repository: Option<NpmRepository>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NpmRepository {
+    Url(String),
+    Object { url: String },
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// Fetch the latest version and repository of an npm package.
+pub fn fetch_npm_package(package: &str) -> Result<PackageInfo> {
+    let packument: NpmPackument = serde_json::from_value(npm_registry::packument(package)?)
+        .context("Failed to parse npm registry response")?;
+
+    let latest = packument
+        .dist_tags
+        .get("latest")
+        .cloned()
+        .with_context(|| format!("{package} has no 'latest' dist-tag"))?;
+
+    Ok(PackageInfo {
+        name: packument.name,
+        latest,
+        beta: packument.dist_tags.get("beta").cloned(),
+        repository: packument.repository.map(|repo| match repo {
+            NpmRepository::Url(url) | NpmRepository::Object { url } => url,
+        }),
+    })
+}
+
+/// Extract `owner/repo` from a repository URL such as `git+https://github.com/o/r.git`.
+pub fn parse_github_repo(repository: &str) -> Option<String> {
+    let rest = repository
+        .split_once("github.com")
+        .map(|(_, rest)| rest)
+        .or_else(|| repository.strip_prefix("github:"))?;
+
+    let mut parts = rest
+        .trim_start_matches([':', '/'])
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .split('/');
+
+    let owner = parts.next().filter(|s| !s.is_empty())?;
+    let repo = parts.next().filter(|s| !s.is_empty())?;
+    Some(format!("{owner}/{repo}"))
+}
+
+/// Fetch GitHub release notes for versions in `(current, latest]`, oldest first.
+pub fn fetch_release_notes(repo: &str, current: &str, latest: &str) -> Result<Vec<ReleaseNote>> {
+    let releases: Vec<GitHubRelease> = serde_json::from_value(github::releases(repo)?)
+        .context("Failed to parse GitHub releases response")?;
+
+    Ok(releases_between(
+        releases
+            .into_iter()
+            .map(|release| ReleaseNote {
+                version: release.tag_name,
+                body: release.body.unwrap_or_default(),
+            })
+            .collect(),
+        current,
+        latest,
+    ))
+}
+
+/// Parse a version from a tag like `v1.2.3` or `my-server@1.2.3`.
+pub fn parse_tag_version(tag: &str) -> Option<Version> {
+    let version = tag.rsplit('@').next().unwrap_or(tag);
+    Version::parse(version.trim_start_matches('v')).ok()
+}
+
+/// Keep releases newer than `current` and no newer than `latest`, sorted oldest first.
+pub fn releases_between(
+    releases: Vec<ReleaseNote>,
+    current: &str,
+    latest: &str,
+) -> Vec<ReleaseNote> {
+    let (Some(current), Some(latest)) = (parse_tag_version(current), parse_tag_version(latest))
+    else {
+        return Vec::new();
+    };
+
+    let mut selected: Vec<(Version, ReleaseNote)> = releases
+        .into_iter()
+        .filter_map(|release| {
+            let version = parse_tag_version(&release.version)?;
+            (version > current && version <= latest).then_some((version, release))
+        })
+        .collect();
+
+    selected.sort_by(|a, b| a.0.cmp(&b.0));
+    selected.into_iter().map(|(_, release)| release).collect()
+}
+
+/// Whether upgrading from `current` to `latest` crosses a semver compatibility boundary.
+///
+/// For `0.x` versions a minor bump is treated as breaking, matching npm's caret semantics.
+pub fn is_breaking_upgrade(current: &str, latest: &str) -> bool {
+    let (Some(current), Some(latest)) = (parse_tag_version(current), parse_tag_version(latest))
+    else {
+        return false;
+    };
+
+    if latest.major != current.major {
+        return latest.major > current.major;
+    }
+
+    current.major == 0 && latest.minor > current.minor
+}
+
+/// Extract lines that describe breaking changes from release notes.
+pub fn extract_breaking_changes(notes: &str) -> Vec<String> {
+    let mut changes = Vec::new();
+    let mut in_breaking_section = false;
+
+    for line in notes.lines() {
+        let trimmed = line.trim();
+        let lower = trimmed.to_lowercase();
+
+        if trimmed.starts_with('#') {
+            in_breaking_section = lower.contains("breaking");
+            continue;
+        }
+
+        let is_item = trimmed.starts_with(['-', '*']);
+        let text = trimmed.trim_start_matches(['-', '*', ' ']).trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if lower.contains("breaking") || (in_breaking_section && is_item) {
+            changes.push(text.to_string());
+        }
+    }
+
+    changes
+}
+
+impl UpgradeNotice {
+    /// Build a notice from already fetched release notes.
+    pub fn new(package: &str, current: &str, latest: &str, releases: Vec<ReleaseNote>) -> Self {
+        let breaking_changes = releases
+            .iter()
+            .flat_map(|release| {
+                extract_breaking_changes(&release.body)
+                    .into_iter()
+                    .map(move |change| format!("{}: {change}", release.version))
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            package: package.to_string(),
+            current: current.to_string(),
+            latest: latest.to_string(),
+            is_breaking: is_breaking_upgrade(current, latest),
+            breaking_changes,
+            releases,
+            notes_url: None,
+        }
+    }
+
+    /// Look up release notes for an npm package upgrade. Failures to fetch notes are
+    /// not fatal; the notice then only reflects the version numbers.
+    pub fn for_npm_package(info: &PackageInfo, current: &str) -> Self {
+        let repo = info.repository.as_deref().and_then(parse_github_repo);
+
+        let releases = repo
+            .as_deref()
+            .and_then(|repo| fetch_release_notes(repo, current, &info.latest).ok())
+            .unwrap_or_default();
+
+        let mut notice = Self::new(&info.name, current, &info.latest, releases);
+        notice.notes_url = Some(match repo {
+            Some(repo) => format!("https://github.com/{repo}/releases"),
+            None => format!(
+                "https://www.npmjs.com/package/{}?activeTab=versions",
+                info.name
+            ),
+        });
+        notice
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_info_on_channel() {
+        let info = |beta: Option<&str>| PackageInfo {
+            name: "@org/server".to_string(),
+            latest: "1.4.0".to_string(),
+            beta: beta.map(str::to_string),
+            repository: None,
+        };
+
+        assert_eq!(
+            info(Some("2.0.0-beta.1"))
+                .on_channel(UpdateChannel::Stable)
+                .latest,
+            "1.4.0"
+        );
+        assert_eq!(
+            info(Some("2.0.0-beta.1"))
+                .on_channel(UpdateChannel::Beta)
+                .latest,
+            "2.0.0-beta.1"
+        );
+        assert_eq!(
+            info(Some("1.3.0-beta.2"))
+                .on_channel(UpdateChannel::Beta)
+                .latest,
+            "1.4.0"
+        );
+        assert_eq!(info(None).on_channel(UpdateChannel::Beta).latest, "1.4.0");
+    }
+
+    #[test]
+    fn test_parse_github_repo() {
+        assert_eq!(
+            parse_github_repo("git+https://github.com/modelcontextprotocol/servers.git"),
+            Some("modelcontextprotocol/servers".to_string())
+        );
+        assert_eq!(
+            parse_github_repo("git@github.com:org/repo.git"),
+            Some("org/repo".to_string())
+        );
+        assert_eq!(
+            parse_github_repo("github:org/repo"),
+            Some("org/repo".to_string())
+        );
+        assert_eq!(parse_github_repo("https://gitlab.com/org/repo"), None);
+    }
+
+    #[test]
+    fn test_is_breaking_upgrade() {
+        assert!(is_breaking_upgrade("1.4.2", "2.0.0"));
+        assert!(is_breaking_upgrade("0.3.1", "0.4.0"));
+        assert!(!is_breaking_upgrade("1.4.2", "1.5.0"));
+        assert!(!is_breaking_upgrade("0.3.1", "0.3.9"));
+        assert!(!is_breaking_upgrade("2.0.0", "1.9.0"));
+        assert!(!is_breaking_upgrade("latest", "2.0.0"));
+    }
+
+    #[test]
+    fn test_releases_between() {
+        let releases = ["v2.1.0", "v2.0.0", "v1.5.0", "server@1.4.0", "nightly"]
+            .iter()
+            .map(|tag| ReleaseNote {
+                version: tag.to_string(),
+                body: String::new(),
+            })
+            .collect();
+
+        let selected: Vec<_> = releases_between(releases, "1.4.0", "2.0.0")
+            .into_iter()
+            .map(|r| r.version)
+            .collect();
+        assert_eq!(selected, vec!["v1.5.0", "v2.0.0"]);
+    }
+
+    #[test]
+    fn test_extract_breaking_changes() {
+        let notes = "## Features\n- Added search\n\n## ⚠ BREAKING CHANGES\n- Renamed `path` option to `root`\n* Dropped Node 16\n\n## Fixes\n- BREAKING: config file moved\n- Fixed crash";
+        assert_eq!(
+            extract_breaking_changes(notes),
+            vec![
+                "Renamed `path` option to `root`",
+                "Dropped Node 16",
+                "BREAKING: config file moved"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upgrade_notice() {
+        let releases = vec![ReleaseNote {
+            version: "v2.0.0".to_string(),
+            body: "- BREAKING: removed stdio flag".to_string(),
+        }];
+        let notice = UpgradeNotice::new("pkg", "1.0.0", "2.0.0", releases);
+        assert!(notice.is_breaking);
+        assert_eq!(
+            notice.breaking_changes,
+            vec!["v2.0.0: BREAKING: removed stdio flag"]
+        );
+    }
+}
@@ -0,0 +1,778 @@
+//! Update command for upgrading installed MCP servers.
+//!
+//! Servers are located in every installed client's configuration, the registry is
+//! queried for the newest release, and the configs are rewritten to the new version.
+//! Upgrades that cross a major version show release notes with breaking changes
+//! highlighted and need explicit confirmation. Servers installed as .NET tools are
+//! updated in place with `dotnet tool update`, leaving the configs untouched.
+//!
+//! Each managed server follows an [`UpdateChannel`]: `stable` tracks the `latest`
+//! dist-tag and `beta` the `beta` tag. The channel picked with `--channel` is kept in
+//! the managed servers record, so later updates stay on it.
+//!
+//! When the new version's config schema requires new fields or renames old ones
+//! (see [`schema`]), the configs are migrated and only the new values are asked
+//! for before anything is written.
+//!
+//! Docker images pinned to a digest and binaries from GitHub Releases are updated
+//! too (see [`artifact`]): the new image is pulled, or the new binary downloaded,
+//! and the configs point at it. Images referenced by tag are pulled again.
+//!
+//! `mcp update --all` updates every server in one transaction (see [`batch`]).
+
+pub mod artifact;
+pub mod batch;
+pub mod changelog;
+pub mod schema;
+
+use colored::Colorize;
+use dialoguer::{Input, Password};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+use crate::cache::CacheManager;
+use crate::client::{detect_clients, McpClient, ServerConfig};
+use crate::config::{ConfigManager, ManagedServers};
+use crate::error::McpError;
+use crate::logging::progress::{step_percent, Progress};
+use crate::server::dotnet::{managed_tool_for_command, DotnetToolServer};
+use crate::server::{parse_npm_package, ConfigField};
+use crate::utils::prompt::Confirm;
+use crate::utils::secrets::is_secret_name;
+
+pub use artifact::{ArtifactCheck, ArtifactUpdate, ConfigChange};
+pub use changelog::{PackageInfo, ReleaseNote, UpgradeNotice};
+pub use schema::{ConfigSchema, SchemaDelta};
+
+/// Maximum number of breaking-change lines printed before truncating
+const MAX_BREAKING_LINES: usize = 10;
+
+/// Which releases of a server updates move to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    /// The npm dist-tag the channel follows
+    pub fn dist_tag(self) -> &'static str {
+        match self {
+            Self::Stable => "latest",
+            Self::Beta => "beta",
+        }
+    }
+
+    pub fn is_stable(&self) -> bool {
+        *self == Self::Stable
+    }
+}
+
+impl FromStr for UpdateChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "stable" | "latest" => Ok(Self::Stable),
+            "beta" => Ok(Self::Beta),
+            other => Err(format!(
+                "Invalid channel '{other}'. Expected one of: stable, beta"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for UpdateChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stable => write!(f, "stable"),
+            Self::Beta => write!(f, "beta"),
+        }
+    }
+}
+
+/// Upgrade installed servers to newer versions
+pub struct UpdateCommand {
+    verbose: bool,
+    allow_major: bool,
+    channel: Option<UpdateChannel>,
+    /// Servers `--all` leaves alone, on top of the `update.skip` preference
+    skip: Vec<String>,
+}
+
+/// Location of the npm package spec inside a server's arguments
+#[derive(Debug, Clone, PartialEq)]
+pub struct NpmPackageSpec {
+    pub arg_index: usize,
+    pub package: String,
+    pub version: Option<String>,
+}
+
+impl UpdateCommand {
+    pub fn new(verbose: bool) -> Self {
+        Self {
+            verbose,
+            allow_major: false,
+            channel: None,
+            skip: Vec::new(),
+        }
+    }
+
+    /// Apply major (breaking) upgrades without asking for confirmation
+    pub fn with_allow_major(mut self, allow_major: bool) -> Self {
+        self.allow_major = allow_major;
+        self
+    }
+
+    /// Switch the server to `channel` and remember it; by default the recorded channel is used
+    pub fn with_channel(mut self, channel: Option<UpdateChannel>) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// Leave these servers out of `--all`
+    pub fn with_skip(mut self, skip: Vec<String>) -> Self {
+        self.skip = skip;
+        self
+    }
+
+    pub fn execute(&self, server_name: &str) -> Result<(), McpError> {
+        let progress = Progress::new("update", server_name);
+        let result = self.update(server_name, &progress);
+        progress.finish(&result, "Update finished");
+        result
+    }
+
+    fn update(&self, server_name: &str, progress: &Progress) -> Result<(), McpError> {
+        progress.step("check", 10, "Finding configured clients");
+        println!(
+            "{} Checking for updates: {}",
+            "→".green(),
+            server_name.cyan()
+        );
+
+        let clients = detect_clients();
+        let targets: Vec<(&dyn McpClient, ServerConfig)> = clients
+            .iter()
+            .filter(|client| client.is_installed())
+            .filter_map(|client| {
+                let config = client.list_servers().ok()?.remove(server_name)?;
+                Some((client.as_ref(), config))
+            })
+            .collect();
+
+        let Some((_, first_config)) = targets.first() else {
+            // `mcp update filesystem` covers every instance added with `--as`
+            let instances = ManagedServers::new()
+                .and_then(|managed| managed.instances_of(server_name))
+                .map_err(McpError::Other)?;
+            if !instances.is_empty() {
+                for instance in &instances {
+                    println!();
+                    self.update(instance, progress)?;
+                }
+                return Ok(());
+            }
+            return Err(McpError::Other(anyhow::anyhow!(
+                "Server '{}' not found in any MCP client configuration",
+                server_name
+            )));
+        };
+
+        let Some(spec) = find_npm_package_spec(first_config) else {
+            if let Some(tool) =
+                managed_tool_for_command(&first_config.command).map_err(McpError::Other)?
+            {
+                return self.update_dotnet_tool(&tool, progress);
+            }
+            return self.update_artifact(server_name, &targets, progress);
+        };
+
+        let channel = self.resolve_channel(server_name)?;
+        if self.verbose || !channel.is_stable() {
+            println!(
+                "  Channel: {} (dist-tag '{}')",
+                channel.to_string().cyan(),
+                channel.dist_tag()
+            );
+        }
+
+        // Unpinned packages already run `latest`; the beta channel pins them to a beta
+        if spec.version.is_none() && channel.is_stable() {
+            println!(
+                "{} {} is not pinned to a version, so npx already runs the latest release",
+                "ℹ".blue(),
+                spec.package.cyan()
+            );
+            return Ok(());
+        }
+
+        progress.step(
+            "registry",
+            30,
+            format!("Checking the registry for {}", spec.package),
+        );
+        let info = changelog::fetch_npm_package(&spec.package)
+            .map_err(McpError::Other)?
+            .on_channel(channel);
+
+        let current = match spec.version.clone() {
+            Some(current) => {
+                let is_newer = match (
+                    changelog::parse_tag_version(&current),
+                    changelog::parse_tag_version(&info.latest),
+                ) {
+                    (Some(current), Some(latest)) => latest > current,
+                    _ => current != info.latest,
+                };
+
+                if !is_newer {
+                    println!(
+                        "{} {} is up to date ({})",
+                        "✓".green(),
+                        spec.package.cyan(),
+                        current
+                    );
+                    return Ok(());
+                }
+
+                let notice = UpgradeNotice::for_npm_package(&info, &current);
+                self.print_notice(&notice);
+
+                if notice.is_breaking && !self.confirm_breaking_upgrade(&notice)? {
+                    println!("{} Update cancelled", "❌".red());
+                    return Ok(());
+                }
+                current
+            }
+            None => "unpinned".to_string(),
+        };
+
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+
+        // Every config is migrated, and new values collected, before any is written
+        let schema_change = self.schema_change(
+            server_name,
+            &spec.package,
+            spec.version.as_deref(),
+            &info.latest,
+        );
+        let mut values = HashMap::new();
+        let mut updates = Vec::with_capacity(targets.len());
+        for (client, config) in &targets {
+            let Some(client_spec) = find_npm_package_spec(config) else {
+                continue;
+            };
+            let mut updated = with_package_version(config, &client_spec, &info.latest);
+            if let Some((_, delta)) = &schema_change {
+                updated = self.reconfigure(delta, updated, &mut values)?;
+            }
+            updates.push((*client, updated));
+        }
+
+        let update_count = updates.len();
+        for (index, (client, updated)) in updates.into_iter().enumerate() {
+            progress.step(
+                "client",
+                step_percent(60, 40, index, update_count),
+                format!("Updating {} to {}", client.name(), info.latest),
+            );
+            manager
+                .apply_config(client, server_name, updated)
+                .map_err(|e| {
+                    McpError::Other(anyhow::anyhow!(
+                        "Failed to update {} in {}: {}",
+                        server_name,
+                        client.name(),
+                        e
+                    ))
+                })?;
+            println!(
+                "  {} Updated {} to {}",
+                "✓".green(),
+                client.name(),
+                info.latest.green()
+            );
+        }
+
+        if let Some((schema, _)) = schema_change {
+            let recorded =
+                ManagedServers::new().and_then(|managed| match managed.get(server_name)? {
+                    Some(_) => managed.set_schema(server_name, schema),
+                    None => Ok(()),
+                });
+            if let Err(e) = recorded {
+                tracing::warn!("Failed to record config schema of {}: {}", server_name, e);
+            }
+        }
+
+        println!(
+            "{} {} updated from {} to {}",
+            "✅".green(),
+            server_name.cyan(),
+            current,
+            info.latest.green()
+        );
+
+        Ok(())
+    }
+
+    /// Update a server running a Docker image or a binary downloaded by mcp-helper
+    fn update_artifact(
+        &self,
+        server_name: &str,
+        targets: &[(&dyn McpClient, ServerConfig)],
+        progress: &Progress,
+    ) -> Result<(), McpError> {
+        progress.step(
+            "registry",
+            30,
+            format!("Checking releases of {server_name}"),
+        );
+        let update = match artifact::check(&targets[0].1).map_err(McpError::Other)? {
+            ArtifactCheck::Available(update) => update,
+            ArtifactCheck::UpToDate { current } => {
+                println!(
+                    "{} {} is up to date ({})",
+                    "✓".green(),
+                    server_name.cyan(),
+                    current
+                );
+                return Ok(());
+            }
+            ArtifactCheck::UnpinnedImage { image } => {
+                progress.step("pull", 60, format!("Pulling {image}"));
+                let changed = artifact::pull_image(&image).map_err(McpError::Other)?;
+                if changed {
+                    println!("{} Pulled a newer {}", "✅".green(), image.cyan());
+                } else {
+                    println!("{} {} is up to date", "✓".green(), image.cyan());
+                }
+                return Ok(());
+            }
+            ArtifactCheck::NotApplicable => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Server '{}' is not an npm package, Docker image, GitHub release binary or .NET tool; only those can be updated",
+                    server_name
+                )));
+            }
+        };
+
+        println!(
+            "  {}  {} → {}",
+            update.source(),
+            update.current().red(),
+            update.latest().green()
+        );
+        if update.is_breaking() && !self.confirm_breaking_artifact(&update)? {
+            println!("{} Update cancelled", "❌".red());
+            return Ok(());
+        }
+
+        progress.step("download", 50, format!("Fetching {}", update.latest()));
+        let cache_manager = CacheManager::new().unwrap_or_default();
+        let change = update.fetch(&cache_manager).map_err(McpError::Other)?;
+
+        let manager = ConfigManager::new().map_err(|e| {
+            McpError::Other(anyhow::anyhow!("Failed to open config history: {}", e))
+        })?;
+        for (index, (client, config)) in targets.iter().enumerate() {
+            progress.step(
+                "client",
+                step_percent(70, 30, index, targets.len()),
+                format!("Updating {}", client.name()),
+            );
+            let updated = change.apply(config);
+            if &updated == config {
+                continue;
+            }
+            manager
+                .apply_config(*client, server_name, updated)
+                .map_err(|e| {
+                    McpError::Other(anyhow::anyhow!(
+                        "Failed to update {} in {}: {}",
+                        server_name,
+                        client.name(),
+                        e
+                    ))
+                })?;
+            println!("  {} Updated {}", "✓".green(), client.name());
+        }
+
+        println!(
+            "{} {} updated from {} to {}",
+            "✅".green(),
+            server_name.cyan(),
+            update.current(),
+            update.latest().green()
+        );
+        Ok(())
+    }
+
+    fn confirm_breaking_artifact(&self, update: &ArtifactUpdate) -> Result<bool, McpError> {
+        if self.allow_major {
+            return Ok(true);
+        }
+        Confirm::new()
+            .with_prompt(format!(
+                "{} {} → {} may break your setup. Apply this major upgrade?",
+                update.source(),
+                update.current(),
+                update.latest()
+            ))
+            .default(false)
+            .interact()
+            .map_err(|e| {
+                McpError::Other(anyhow::anyhow!(
+                    "Confirmation failed: {} (use --allow-major to upgrade non-interactively)",
+                    e
+                ))
+            })
+    }
+
+    /// The new version's config schema and how it differs from the recorded one,
+    /// or the old version's. `None` when the new schema can't be looked up
+    fn schema_change(
+        &self,
+        server_name: &str,
+        package: &str,
+        current: Option<&str>,
+        latest: &str,
+    ) -> Option<(ConfigSchema, SchemaDelta)> {
+        let new = match ConfigSchema::fetch(package, latest) {
+            Ok(schema) => schema,
+            Err(e) => {
+                tracing::debug!("No config schema for {}@{}: {:#}", package, latest, e);
+                return None;
+            }
+        };
+        let recorded = ManagedServers::new()
+            .and_then(|managed| managed.get(server_name))
+            .ok()
+            .flatten()
+            .and_then(|server| server.schema);
+        let old = recorded
+            .or_else(|| current.and_then(|version| ConfigSchema::fetch(package, version).ok()))
+            .unwrap_or_default();
+
+        let delta = SchemaDelta::between(&old, &new);
+        if !delta.is_empty() {
+            print_schema_delta(latest, &delta);
+        }
+        Some((new, delta))
+    }
+
+    /// `config` with renamed fields migrated and values for the fields the new
+    /// version needs. Each value is asked for once and reused for other clients
+    fn reconfigure(
+        &self,
+        delta: &SchemaDelta,
+        config: ServerConfig,
+        values: &mut HashMap<String, String>,
+    ) -> Result<ServerConfig, McpError> {
+        let mut config = delta.migrate(&config);
+        let missing: Vec<ConfigField> =
+            delta.missing_fields(&config).into_iter().cloned().collect();
+        for field in missing {
+            let value = match values.get(&field.name) {
+                Some(value) => value.clone(),
+                None => {
+                    let value = prompt_field(&field)?;
+                    values.insert(field.name.clone(), value.clone());
+                    value
+                }
+            };
+            config.env.insert(field.name, value);
+        }
+        Ok(config)
+    }
+
+    /// The channel to update on, recording `--channel` for later updates
+    fn resolve_channel(&self, server_name: &str) -> Result<UpdateChannel, McpError> {
+        let managed = ManagedServers::new().map_err(McpError::Other)?;
+        let recorded = managed
+            .get(server_name)
+            .map_err(McpError::Other)?
+            .map(|server| server.channel);
+
+        let Some(channel) = self.channel else {
+            return Ok(recorded.unwrap_or_default());
+        };
+        match recorded {
+            Some(recorded) if recorded == channel => {}
+            Some(recorded) => {
+                managed
+                    .set_channel(server_name, channel)
+                    .map_err(McpError::Other)?;
+                println!(
+                    "  Switched {} from the {} to the {} channel",
+                    server_name.cyan(),
+                    recorded,
+                    channel
+                );
+            }
+            None => {
+                return Err(McpError::Other(anyhow::anyhow!(
+                    "Server '{}' isn't managed by mcp-helper, so its channel can't be recorded; run 'mcp import' first",
+                    server_name
+                )))
+            }
+        }
+        Ok(channel)
+    }
+
+    /// Update a tool in the managed tool path; its executable path stays the same
+    fn update_dotnet_tool(
+        &self,
+        tool: &DotnetToolServer,
+        progress: &Progress,
+    ) -> Result<(), McpError> {
+        progress.step(
+            "registry",
+            30,
+            format!("Updating {} with dotnet tool", tool.package()),
+        );
+        let (before, after) = tool.update().map_err(McpError::Other)?;
+        if before == after {
+            println!(
+                "{} {} is up to date ({})",
+                "✓".green(),
+                tool.package().cyan(),
+                before
+            );
+        } else {
+            println!(
+                "{} {} updated from {} to {}",
+                "✅".green(),
+                tool.package().cyan(),
+                before,
+                after.green()
+            );
+        }
+        Ok(())
+    }
+
+    fn print_notice(&self, notice: &UpgradeNotice) {
+        println!();
+        println!(
+            "  {} {} → {}{}",
+            notice.package.cyan(),
+            notice.current,
+            notice.latest.green(),
+            if notice.is_breaking {
+                format!(" {}", "(major upgrade)".red().bold())
+            } else {
+                String::new()
+            }
+        );
+
+        if !notice.breaking_changes.is_empty() {
+            println!();
+            println!("  {}", "Breaking changes:".red().bold());
+            for change in notice.breaking_changes.iter().take(MAX_BREAKING_LINES) {
+                println!("    {} {}", "⚠".yellow(), change);
+            }
+            if notice.breaking_changes.len() > MAX_BREAKING_LINES {
+                println!(
+                    "    ... and {} more",
+                    notice.breaking_changes.len() - MAX_BREAKING_LINES
+                );
+            }
+        } else if notice.is_breaking {
+            println!();
+            println!(
+                "  {} No release notes found; a major version may contain breaking changes",
+                "⚠".yellow()
+            );
+        }
+
+        if self.verbose && !notice.releases.is_empty() {
+            println!();
+            println!("  Releases included:");
+            for release in &notice.releases {
+                println!("    • {}", release.version);
+            }
+        }
+
+        if let Some(url) = &notice.notes_url {
+            println!();
+            println!("  Release notes: {}", url.dimmed());
+        }
+        println!();
+    }
+
+    fn confirm_breaking_upgrade(&self, notice: &UpgradeNotice) -> Result<bool, McpError> {
+        if self.allow_major {
+            return Ok(true);
+        }
+
+        Confirm::new()
+            .with_prompt(format!(
+                "{} {} → {} may break your setup. Apply this major upgrade?",
+                notice.package, notice.current, notice.latest
+            ))
+            .default(false)
+            .interact()
+            .map_err(|e| {
+                McpError::Other(anyhow::anyhow!(
+                    "Confirmation failed: {} (use --allow-major to upgrade non-interactively)",
+                    e
+                ))
+            })
+    }
+}
+
+/// Find the npm package spec in an `npx` server configuration.
+pub fn find_npm_package_spec(config: &ServerConfig) -> Option<NpmPackageSpec> {
+    let command = config
+        .command
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(&config.command);
+    if !matches!(command, "npx" | "npx.cmd") {
+        return None;
+    }
+
+    let (arg_index, arg) = config
+        .args
+        .iter()
+        .enumerate()
+        .find(|(_, arg)| !arg.starts_with('-'))?;
+
+    let (package, version) = parse_npm_package(arg);
+    Some(NpmPackageSpec {
+        arg_index,
+        package,
+        version,
+    })
+}
+
+/// Return a copy of `config` with the package pinned to `version`.
+pub fn with_package_version(
+    config: &ServerConfig,
+    spec: &NpmPackageSpec,
+    version: &str,
+) -> ServerConfig {
+    let mut updated = config.clone();
+    updated.args[spec.arg_index] = format!("{}@{version}", spec.package);
+    updated
+}
+
+/// Summarize the config schema changes of `version`
+fn print_schema_delta(version: &str, delta: &SchemaDelta) {
+    println!("{} Config changes in {}:", "ℹ".blue(), version.cyan());
+    for migration in &delta.renamed {
+        println!("  • {} is now {}", migration.from, migration.to.cyan());
+    }
+    for field in &delta.newly_required {
+        println!("  • {} is now required", field.name.cyan());
+    }
+    for field in &delta.retyped {
+        println!("  • {} is now a {:?}", field.name.cyan(), field.field_type);
+    }
+    for name in &delta.removed {
+        println!("  • {} is no longer used", name.dimmed());
+    }
+}
+
+/// Ask for a value of `field`; without a terminal its default is used if it has one
+fn prompt_field(field: &ConfigField) -> Result<String, McpError> {
+    if !std::io::stdin().is_terminal() {
+        return field.default.clone().ok_or_else(|| {
+            McpError::Other(anyhow::anyhow!(
+                "The new version needs a value for {}; run 'mcp update' in a terminal to enter it",
+                field.name
+            ))
+        });
+    }
+
+    let prompt = match &field.description {
+        Some(description) => format!("{} ({})", field.name, description),
+        None => field.name.clone(),
+    };
+    let field_type = field.field_type.clone();
+    let value = if is_secret_name(&field.name) {
+        Password::new().with_prompt(prompt).interact()
+    } else {
+        let mut input =
+            Input::<String>::new()
+                .with_prompt(prompt)
+                .validate_with(move |value: &String| {
+                    if schema::fits_type(value, &field_type) {
+                        Ok(())
+                    } else {
+                        Err(format!("Expected a {field_type:?}"))
+                    }
+                });
+        if let Some(default) = &field.default {
+            input = input.default(default.clone());
+        }
+        input.interact()
+    };
+    value.map_err(|e| McpError::Other(anyhow::anyhow!("Input failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn npx_config(args: &[&str]) -> ServerConfig {
+        ServerConfig {
+            command: "npx".to_string(),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            env: HashMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn test_find_npm_package_spec() {
+        let config = npx_config(&["--yes", "@org/server@1.2.0", "--stdio"]);
+        let spec = find_npm_package_spec(&config).unwrap();
+        assert_eq!(spec.arg_index, 1);
+        assert_eq!(spec.package, "@org/server");
+        assert_eq!(spec.version, Some("1.2.0".to_string()));
+
+        let config = npx_config(&["server"]);
+        assert_eq!(find_npm_package_spec(&config).unwrap().version, None);
+
+        let mut config = npx_config(&["server"]);
+        config.command = "docker".to_string();
+        assert!(find_npm_package_spec(&config).is_none());
+    }
+
+    #[test]
+    fn test_with_package_version() {
+        let config = npx_config(&["-y", "@org/server@1.2.0", "/tmp"]);
+        let spec = find_npm_package_spec(&config).unwrap();
+        let updated = with_package_version(&config, &spec, "2.0.0");
+        assert_eq!(updated.args, vec!["-y", "@org/server@2.0.0", "/tmp"]);
+    }
+
+    #[test]
+    fn test_update_command_creation() {
+        let cmd = UpdateCommand::new(true);
+        assert!(cmd.verbose);
+        assert!(!cmd.allow_major);
+        assert!(cmd.with_allow_major(true).allow_major);
+    }
+
+    #[test]
+    fn test_update_channel_parsing() {
+        assert_eq!("beta".parse::<UpdateChannel>(), Ok(UpdateChannel::Beta));
+        assert_eq!("Stable".parse::<UpdateChannel>(), Ok(UpdateChannel::Stable));
+        assert_eq!("latest".parse::<UpdateChannel>(), Ok(UpdateChannel::Stable));
+        assert!("nightly".parse::<UpdateChannel>().is_err());
+        assert_eq!(UpdateChannel::Beta.to_string(), "beta");
+        assert_eq!(UpdateChannel::Beta.dist_tag(), "beta");
+        assert_eq!(UpdateChannel::default().dist_tag(), "latest");
+    }
+}
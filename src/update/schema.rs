@@ -0,0 +1,271 @@
+//! Config schema changes between server versions.
+//!
+//! npm servers describe their configuration in the `mcp` section of package.json:
+//! `required_config` and `optional_config` fields, which mcp-helper writes into the
+//! client's `env`. A new release can require a field the old one didn't, change a
+//! field's type, or rename one. Left alone, the updated server fails at the next
+//! client launch. [`SchemaDelta`] compares the schema recorded when the server was
+//! added (or the old version's, fetched from the registry) with the new one, so
+//! `mcp update` can rename fields and ask only for what's new.
+//!
+//! Renames come from migration hints in the new version's `mcp` section:
+//!
+//! ```json
+//! "mcp": {
+//!   "required_config": [{ "name": "WEATHER_TOKEN", "field_type": "String" }],
+//!   "migrations": [{ "from": "WEATHER_KEY", "to": "WEATHER_TOKEN" }]
+//! }
+//! ```
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::client::ServerConfig;
+use crate::server::{npm_registry, ConfigField, ConfigFieldType};
+
+/// A field renamed between versions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldMigration {
+    pub from: String,
+    pub to: String,
+}
+
+/// The config fields a server version declares
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSchema {
+    #[serde(default)]
+    pub required_config: Vec<ConfigField>,
+    #[serde(default)]
+    pub optional_config: Vec<ConfigField>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migrations: Vec<FieldMigration>,
+}
+
+impl ConfigSchema {
+    /// The schema in an `mcp` package.json section; empty when it has none
+    pub fn from_mcp(mcp: &Value) -> Self {
+        if mcp.is_null() {
+            return Self::default();
+        }
+        serde_json::from_value(mcp.clone()).unwrap_or_else(|e| {
+            tracing::debug!("Ignoring malformed mcp config schema: {}", e);
+            Self::default()
+        })
+    }
+
+    /// The schema `package` publishes at `version`
+    pub fn fetch(package: &str, version: &str) -> Result<Self> {
+        npm_registry::mcp_metadata(package, Some(version)).map(|mcp| Self::from_mcp(&mcp))
+    }
+
+    fn field(&self, name: &str) -> Option<&ConfigField> {
+        self.required_config
+            .iter()
+            .chain(&self.optional_config)
+            .find(|field| field.name == name)
+    }
+
+    fn is_required(&self, name: &str) -> bool {
+        self.required_config.iter().any(|field| field.name == name)
+    }
+}
+
+/// What changed in the config schema between two versions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDelta {
+    /// Fields the old version knew under another name
+    pub renamed: Vec<FieldMigration>,
+    /// Fields that are required now but weren't before
+    pub newly_required: Vec<ConfigField>,
+    /// Fields whose type changed
+    pub retyped: Vec<ConfigField>,
+    /// Fields the new version no longer declares
+    pub removed: Vec<String>,
+}
+
+impl SchemaDelta {
+    /// Compare the `old` schema with the `new` one
+    pub fn between(old: &ConfigSchema, new: &ConfigSchema) -> Self {
+        let renamed: Vec<FieldMigration> = new
+            .migrations
+            .iter()
+            .filter(|migration| old.field(&migration.from).is_some())
+            .cloned()
+            .collect();
+        let renamed_to = |name: &str| renamed.iter().any(|m| m.to == name);
+        let renamed_from = |name: &str| renamed.iter().any(|m| m.from == name);
+
+        let newly_required = new
+            .required_config
+            .iter()
+            .filter(|field| !old.is_required(&field.name) && !renamed_to(&field.name))
+            .cloned()
+            .collect();
+        let retyped = new
+            .required_config
+            .iter()
+            .chain(&new.optional_config)
+            .filter(|field| {
+                old.field(&field.name)
+                    .is_some_and(|old_field| old_field.field_type != field.field_type)
+            })
+            .cloned()
+            .collect();
+        let removed = old
+            .required_config
+            .iter()
+            .chain(&old.optional_config)
+            .filter(|field| new.field(&field.name).is_none() && !renamed_from(&field.name))
+            .map(|field| field.name.clone())
+            .collect();
+
+        Self {
+            renamed,
+            newly_required,
+            retyped,
+            removed,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renamed.is_empty()
+            && self.newly_required.is_empty()
+            && self.retyped.is_empty()
+            && self.removed.is_empty()
+    }
+
+    /// `config` with renamed fields moved to their new names. A value already set
+    /// under the new name wins over the old one
+    pub fn migrate(&self, config: &ServerConfig) -> ServerConfig {
+        let mut migrated = config.clone();
+        for migration in &self.renamed {
+            if let Some(value) = migrated.env.remove(&migration.from) {
+                migrated.env.entry(migration.to.clone()).or_insert(value);
+            }
+        }
+        migrated
+    }
+
+    /// Fields `config` needs a value for after migrating: new required fields it
+    /// doesn't set, and retyped fields whose value doesn't fit the new type
+    pub fn missing_fields<'a>(&'a self, config: &ServerConfig) -> Vec<&'a ConfigField> {
+        let unset = self
+            .newly_required
+            .iter()
+            .filter(|field| !config.env.contains_key(&field.name));
+        let mistyped = self.retyped.iter().filter(|field| {
+            config
+                .env
+                .get(&field.name)
+                .is_some_and(|value| !fits_type(value, &field.field_type))
+        });
+        unset.chain(mistyped).collect()
+    }
+}
+
+/// Whether `value` is valid for a field of type `field_type`
+pub(crate) fn fits_type(value: &str, field_type: &ConfigFieldType) -> bool {
+    match field_type {
+        ConfigFieldType::Number => value.parse::<f64>().is_ok(),
+        ConfigFieldType::Boolean => matches!(value, "true" | "false"),
+        ConfigFieldType::Url => url::Url::parse(value).is_ok(),
+        ConfigFieldType::String | ConfigFieldType::Path => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn config(env: &[(&str, &str)]) -> ServerConfig {
+        ServerConfig {
+            command: "npx".to_string(),
+            args: vec!["-y".to_string(), "weather-mcp@2.0.0".to_string()],
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>(),
+            cwd: None,
+        }
+    }
+
+    fn old_schema() -> ConfigSchema {
+        ConfigSchema::from_mcp(&json!({
+            "required_config": [{ "name": "WEATHER_KEY", "field_type": "String" }],
+            "optional_config": [
+                { "name": "TIMEOUT", "field_type": "String" },
+                { "name": "LEGACY_MODE", "field_type": "Boolean" }
+            ]
+        }))
+    }
+
+    fn new_schema() -> ConfigSchema {
+        ConfigSchema::from_mcp(&json!({
+            "required_config": [
+                { "name": "WEATHER_TOKEN", "field_type": "String" },
+                { "name": "REGION", "field_type": "String", "description": "Forecast region" }
+            ],
+            "optional_config": [{ "name": "TIMEOUT", "field_type": "Number", "default": "30" }],
+            "migrations": [
+                { "from": "WEATHER_KEY", "to": "WEATHER_TOKEN" },
+                { "from": "UNKNOWN", "to": "OTHER" }
+            ]
+        }))
+    }
+
+    #[test]
+    fn test_delta_between_schemas() {
+        let delta = SchemaDelta::between(&old_schema(), &new_schema());
+        assert_eq!(
+            delta.renamed,
+            [FieldMigration {
+                from: "WEATHER_KEY".to_string(),
+                to: "WEATHER_TOKEN".to_string()
+            }]
+        );
+        let names = |fields: &[ConfigField]| -> Vec<String> {
+            fields.iter().map(|field| field.name.clone()).collect()
+        };
+        assert_eq!(names(&delta.newly_required), ["REGION"]);
+        assert_eq!(names(&delta.retyped), ["TIMEOUT"]);
+        assert_eq!(delta.removed, ["LEGACY_MODE"]);
+
+        assert!(SchemaDelta::between(&new_schema(), &new_schema()).is_empty());
+    }
+
+    #[test]
+    fn test_migrate_and_missing_fields() {
+        let delta = SchemaDelta::between(&old_schema(), &new_schema());
+        let migrated = delta.migrate(&config(&[("WEATHER_KEY", "k-123"), ("TIMEOUT", "slow")]));
+        assert_eq!(
+            migrated.env.get("WEATHER_TOKEN").map(String::as_str),
+            Some("k-123")
+        );
+        assert!(!migrated.env.contains_key("WEATHER_KEY"));
+
+        let missing: Vec<&str> = delta
+            .missing_fields(&migrated)
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(missing, ["REGION", "TIMEOUT"]);
+
+        let complete = config(&[("WEATHER_TOKEN", "t"), ("REGION", "eu"), ("TIMEOUT", "10")]);
+        assert!(delta.missing_fields(&delta.migrate(&complete)).is_empty());
+    }
+
+    #[test]
+    fn test_schema_without_mcp_section() {
+        assert_eq!(
+            ConfigSchema::from_mcp(&Value::Null),
+            ConfigSchema::default()
+        );
+        assert_eq!(
+            ConfigSchema::from_mcp(&json!({ "required_config": "oops" })),
+            ConfigSchema::default()
+        );
+    }
+}
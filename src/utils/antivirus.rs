@@ -0,0 +1,232 @@
+//! Antivirus interference on Windows.
+//!
+//! Microsoft Defender's real-time scanning and SmartScreen inspect every new
+//! executable. Two failure modes show up in installs:
+//!
+//! - A freshly downloaded file stays locked while it is scanned, so copying or
+//!   reading it fails with a sharing violation
+//! - The first `npx` run takes tens of seconds while node and the package cache
+//!   are scanned, so probes time out
+//!
+//! File operations on fresh downloads go through [`retry_if_locked`], errors that
+//! still look like interference get a hint, and `mcp doctor --only antivirus`
+//! reports the likely cause with exclusion guidance.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::process::Process;
+
+/// Attempts made while a file is locked by a scanner
+pub const LOCK_RETRIES: u32 = 5;
+
+/// Pause between attempts on a locked file
+pub const LOCK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// `npx --version` taking longer than this suggests its files are being scanned
+pub const SLOW_NPX_THRESHOLD: Duration = Duration::from_secs(10);
+
+/// Hint attached to errors that look like antivirus interference
+pub const INTERFERENCE_HINT: &str =
+    "Antivirus real-time scanning may be holding the file. Run 'mcp doctor --only antivirus' for exclusion guidance";
+
+/// Windows error codes for a locked file or a file blocked as a threat
+pub fn is_interference_code(code: i32) -> bool {
+    // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION, ERROR_VIRUS_INFECTED, ERROR_VIRUS_DELETED
+    matches!(code, 32 | 33 | 225 | 226)
+}
+
+/// Whether `error` is a Windows lock or threat error
+pub fn is_interference(error: &io::Error) -> bool {
+    cfg!(target_os = "windows") && error.raw_os_error().is_some_and(is_interference_code)
+}
+
+/// Run `op`, retrying while the file it touches is locked by a scanner
+pub fn retry_if_locked<T>(op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    retry_with(op, LOCK_RETRIES, LOCK_RETRY_DELAY, is_interference)
+}
+
+fn retry_with<T>(
+    mut op: impl FnMut() -> io::Result<T>,
+    attempts: u32,
+    delay: Duration,
+    retryable: impl Fn(&io::Error) -> bool,
+) -> io::Result<T> {
+    let mut attempt = 1;
+    loop {
+        match op() {
+            Err(e) if attempt < attempts && retryable(&e) => {
+                tracing::debug!("File locked (attempt {}): {}", attempt, e);
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// `error` as an [`anyhow::Error`] about `path`, with a hint if it looks like interference
+pub fn explain(error: io::Error, action: &str, path: &Path) -> anyhow::Error {
+    let message = if is_interference(&error) {
+        format!("{action} {}. {INTERFERENCE_HINT}", path.display())
+    } else {
+        format!("{action} {}", path.display())
+    };
+    anyhow::Error::new(error).context(message)
+}
+
+/// Whether a first `npx` start of `elapsed` is slow enough to suspect scanning
+pub fn is_slow_start(elapsed: Duration) -> bool {
+    elapsed >= SLOW_NPX_THRESHOLD
+}
+
+/// Whether Defender real-time protection is on; `None` when it cannot be determined
+pub fn defender_realtime_enabled() -> Option<bool> {
+    if !cfg!(target_os = "windows") {
+        return None;
+    }
+    let output = Process::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "(Get-MpComputerStatus).RealTimeProtectionEnabled",
+        ])
+        .output()
+        .ok()?;
+    if !output.success() {
+        return None;
+    }
+    parse_powershell_bool(&output.stdout_lossy())
+}
+
+/// Parse `True` / `False` as printed by PowerShell
+pub fn parse_powershell_bool(output: &str) -> Option<bool> {
+    match output.trim().to_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Directories worth excluding from scanning: mcp-helper's own files and npm's caches
+pub fn exclusion_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(dirs) = directories::BaseDirs::new() {
+        paths.push(dirs.home_dir().join(".mcp"));
+    }
+    if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+        paths.push(PathBuf::from(local).join("npm-cache"));
+    }
+    if let Some(roaming) = std::env::var_os("APPDATA") {
+        paths.push(PathBuf::from(roaming).join("npm"));
+    }
+    paths
+}
+
+/// PowerShell command adding `paths` as Defender exclusions
+pub fn exclusion_command(paths: &[PathBuf]) -> String {
+    let quoted: Vec<String> = paths
+        .iter()
+        .map(|path| format!("'{}'", path.display().to_string().replace('\'', "''")))
+        .collect();
+    format!("Add-MpPreference -ExclusionPath {}", quoted.join(","))
+}
+
+/// Steps for excluding `paths` from Defender scanning
+pub fn exclusion_guidance(paths: &[PathBuf]) -> Vec<String> {
+    vec![
+        "Open PowerShell as Administrator".to_string(),
+        format!("Run: {}", exclusion_command(paths)),
+        "Or add the folders under Windows Security → Virus & threat protection → Manage settings → Exclusions".to_string(),
+        "Only exclude folders you trust; other antivirus products have similar exclusion settings".to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_interference_codes() {
+        assert!(is_interference_code(32));
+        assert!(is_interference_code(225));
+        assert!(!is_interference_code(2));
+        // Error numbers mean different things elsewhere (32 is EPIPE on Unix)
+        if !cfg!(target_os = "windows") {
+            assert!(!is_interference(&io::Error::from_raw_os_error(32)));
+        }
+    }
+
+    #[test]
+    fn test_retry_until_unlocked() {
+        let calls = Cell::new(0);
+        let result = retry_with(
+            || {
+                calls.set(calls.get() + 1);
+                if calls.get() < 3 {
+                    Err(io::Error::from_raw_os_error(32))
+                } else {
+                    Ok("done")
+                }
+            },
+            5,
+            Duration::ZERO,
+            |_| true,
+        );
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(calls.get(), 3);
+
+        calls.set(0);
+        let result: io::Result<()> = retry_with(
+            || {
+                calls.set(calls.get() + 1);
+                Err(io::Error::from_raw_os_error(32))
+            },
+            3,
+            Duration::ZERO,
+            |_| true,
+        );
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_other_errors_are_not_retried() {
+        let calls = Cell::new(0);
+        let result: io::Result<()> = retry_with(
+            || {
+                calls.set(calls.get() + 1);
+                Err(io::Error::new(io::ErrorKind::NotFound, "missing"))
+            },
+            5,
+            Duration::ZERO,
+            is_interference,
+        );
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_slow_start_and_powershell_bool() {
+        assert!(is_slow_start(Duration::from_secs(25)));
+        assert!(!is_slow_start(Duration::from_secs(1)));
+        assert_eq!(parse_powershell_bool("True\r\n"), Some(true));
+        assert_eq!(parse_powershell_bool("False"), Some(false));
+        assert_eq!(parse_powershell_bool(""), None);
+    }
+
+    #[test]
+    fn test_exclusion_command_quotes_paths() {
+        let command = exclusion_command(&[
+            PathBuf::from(r"C:\Users\o'neil\.mcp"),
+            PathBuf::from(r"C:\Users\me\AppData\Local\npm-cache"),
+        ]);
+        assert_eq!(
+            command,
+            r"Add-MpPreference -ExclusionPath 'C:\Users\o''neil\.mcp','C:\Users\me\AppData\Local\npm-cache'"
+        );
+    }
+}
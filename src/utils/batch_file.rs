@@ -0,0 +1,170 @@
+//! Parser for batch install files.
+//!
+//! A batch file lists servers in `[name]` sections, each followed by `key=value`
+//! configuration lines:
+//!
+//! ```text
+//! # MCP servers to install
+//! [@modelcontextprotocol/server-filesystem]
+//! allowedDirectories=/home/me/projects
+//!
+//! [docker:postgres:13]
+//! ```
+//!
+//! Blank lines and lines starting with `#` are ignored. Every mistake is reported as
+//! a [`ParseError`] pointing at the line and column where it was found; the parser
+//! never panics, whatever the input.
+
+use std::collections::HashMap;
+
+use super::parse_error::{column_of, ParseError};
+
+/// One `[server]` section
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchEntry {
+    pub server: String,
+    /// Line of the section header
+    pub line: usize,
+    pub config: HashMap<String, String>,
+}
+
+/// Parse a batch file; `file` names it in errors. Sections keep their file order.
+pub fn parse_batch_file(file: &str, content: &str) -> Result<Vec<BatchEntry>, ParseError> {
+    let mut entries: Vec<BatchEntry> = Vec::new();
+    let mut key_lines: HashMap<String, usize> = HashMap::new();
+
+    for (index, raw) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let indent = raw.len() - raw.trim_start().len();
+        let error = |offset: usize, message: String| {
+            ParseError::new(file, line_number, column_of(raw, indent + offset), message)
+        };
+
+        if let Some(header) = line.strip_prefix('[') {
+            let Some(name) = header.strip_suffix(']') else {
+                return Err(error(
+                    line.len(),
+                    "expected `]` to close the server name".to_string(),
+                ));
+            };
+            let server = name.trim();
+            if server.is_empty() {
+                return Err(error(1, "server name is empty".to_string()));
+            }
+            if let Some(first) = entries.iter().find(|entry| entry.server == server) {
+                return Err(error(
+                    1,
+                    format!("server `{server}` is already listed on line {}", first.line),
+                ));
+            }
+            entries.push(BatchEntry {
+                server: server.to_string(),
+                line: line_number,
+                config: HashMap::new(),
+            });
+            key_lines.clear();
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(error(
+                0,
+                format!("expected `key=value` or `[server-name]`, found `{line}`"),
+            ));
+        };
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(error(0, "missing key before `=`".to_string()));
+        }
+        let Some(entry) = entries.last_mut() else {
+            return Err(error(
+                0,
+                format!("`{key}` is set before any `[server-name]` section"),
+            ));
+        };
+        if let Some(first) = key_lines.insert(key.to_string(), line_number) {
+            return Err(error(
+                0,
+                format!(
+                    "`{key}` is already set for `{}` on line {first}",
+                    entry.server
+                ),
+            ));
+        }
+        entry
+            .config
+            .insert(key.to_string(), value.trim().to_string());
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn error(content: &str) -> ParseError {
+        parse_batch_file("servers.txt", content).unwrap_err()
+    }
+
+    #[test]
+    fn test_sections_keep_order() {
+        let entries =
+            parse_batch_file("servers.txt", "[b]\nport = 8080\n\n# c\n[a]\nkey=x=y\n").unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!((entries[0].server.as_str(), entries[0].line), ("b", 1));
+        assert_eq!(entries[0].config["port"], "8080");
+        assert_eq!(entries[1].config["key"], "x=y");
+    }
+
+    #[test]
+    fn test_errors_point_at_the_problem() {
+        let e = error("[server1]\nport=1\n  port 8080\n");
+        assert_eq!((e.line, e.column), (3, 3));
+        assert_eq!(
+            e.to_string(),
+            "servers.txt:3:3: expected `key=value` or `[server-name]`, found `port 8080`"
+        );
+
+        let e = error("[server1\n");
+        assert_eq!((e.line, e.column), (1, 9));
+
+        let e = error("port=1\n");
+        assert!(e.message.contains("before any"), "{}", e.message);
+
+        let e = error("[a]\n[ ]\n");
+        assert_eq!((e.line, e.column), (2, 2));
+
+        let e = error("[a]\n[b]\n[a]\n");
+        assert_eq!(e.line, 3);
+        assert!(e.message.contains("line 1"));
+
+        let e = error("[a]\nx=1\n =2\n");
+        assert_eq!((e.line, e.column), (3, 2));
+
+        let e = error("[a]\nx=1\nx=2\n");
+        assert!(e.message.contains("already set"));
+        // The same key in another section is fine
+        assert!(parse_batch_file("servers.txt", "[a]\nx=1\n[b]\nx=2\n").is_ok());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_never_panics(content in "\\PC*") {
+            let _ = parse_batch_file("servers.txt", &content);
+        }
+
+        #[test]
+        fn prop_errors_are_within_the_input(content in "[\\[\\]=# a-zé\\n]{0,64}") {
+            if let Err(e) = parse_batch_file("servers.txt", &content) {
+                let line = content.lines().nth(e.line - 1).unwrap_or_default();
+                prop_assert!(e.column >= 1 && e.column <= line.chars().count() + 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+//! Source of the current time.
+//!
+//! Code whose behaviour depends on the time (snapshot ordering, cache expiry) asks a
+//! [`Clock`] instead of calling `Utc::now()` directly, so tests can use
+//! [`MockClock`](crate::test_utils::mocks::MockClock) and move time forward without
+//! sleeping.
+
+use chrono::{DateTime, Utc};
+use std::fmt::Debug;
+
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Seconds since the Unix epoch
+    fn unix_secs(&self) -> u64 {
+        self.now().timestamp().max(0) as u64
+    }
+}
+
+/// The system's wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
@@ -0,0 +1,151 @@
+//! Reading and writing client config files without repeated parsing.
+//!
+//! A command often reads the same client config several times (listing stdio
+//! servers, then remote ones, then adding a server), and with dozens of servers
+//! parsing dominates. Parsed configs are cached per process, keyed by path and
+//! reused while the file's contents are unchanged; the file is still read every
+//! time, so edits made by the client application are always seen.
+//!
+//! Writes are skipped when the config is the same JSON as the file already
+//! holds, so no-op updates don't touch the file or its backups. Key order is
+//! ignored in that comparison because several clients keep servers and
+//! environment variables in hash maps.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use super::json_validator::parse_json_safe;
+use super::secure_file::write_json_secure;
+
+/// A config file as last read or written: its text and the parsed value
+struct CachedFile {
+    content: String,
+    value: Value,
+}
+
+fn cache() -> &'static Mutex<HashMap<PathBuf, CachedFile>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, CachedFile>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Run `f` on the parsed contents of `path`, parsing only if they changed since last time
+fn with_parsed<R>(path: &Path, f: impl FnOnce(&Value) -> R) -> Result<R> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config from {}", path.display()))?;
+
+    let mut cache = cache().lock().unwrap_or_else(|e| e.into_inner());
+    if cache
+        .get(path)
+        .is_none_or(|cached| cached.content != content)
+    {
+        let value = parse_json_safe(&content)?;
+        cache.insert(path.to_path_buf(), CachedFile { content, value });
+    }
+    Ok(f(&cache[path].value))
+}
+
+/// Parse the JSON config at `path`, reusing the previous parse if the file hasn't changed
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    with_parsed(path, |value| T::deserialize(value))?.context("Failed to deserialize JSON")
+}
+
+/// Whether the file at `path` already holds `config`
+pub fn is_current<T: Serialize>(path: &Path, config: &T) -> bool {
+    serde_json::to_value(config).is_ok_and(|value| holds(path, &value))
+}
+
+fn holds(path: &Path, value: &Value) -> bool {
+    path.exists() && with_parsed(path, |current| current == value).unwrap_or(false)
+}
+
+/// Write `config` to `path` as pretty JSON unless the file already holds it
+///
+/// Returns whether the file was written.
+pub fn write_json<T: Serialize>(path: &Path, config: &T) -> Result<bool> {
+    let value = serde_json::to_value(config).context("Failed to serialize config to JSON")?;
+    if holds(path, &value) {
+        tracing::debug!("{} is unchanged, not rewriting it", path.display());
+        return Ok(false);
+    }
+
+    let json =
+        serde_json::to_string_pretty(&value).context("Failed to serialize config to JSON")?;
+    write_json_secure(path, &json)?;
+    cache().lock().unwrap_or_else(|e| e.into_inner()).insert(
+        path.to_path_buf(),
+        CachedFile {
+            content: json,
+            value,
+        },
+    );
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Config {
+        servers: BTreeMap<String, String>,
+        #[serde(flatten)]
+        other: serde_json::Map<String, Value>,
+    }
+
+    #[test]
+    fn test_read_sees_external_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        fs::write(&path, r#"{"servers": {"a": "1"}, "theme": "dark"}"#).unwrap();
+
+        let config: Config = read_json(&path).unwrap();
+        assert_eq!(config.servers["a"], "1");
+        assert_eq!(config.other["theme"], "dark");
+        // Read again from the cache
+        assert_eq!(read_json::<Config>(&path).unwrap(), config);
+
+        fs::write(&path, r#"{"servers": {"a": "2"}}"#).unwrap();
+        let config: Config = read_json(&path).unwrap();
+        assert_eq!(config.servers["a"], "2");
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(read_json::<Config>(&path).is_err());
+    }
+
+    #[test]
+    fn test_write_skips_unchanged_configs() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let config = Config {
+            servers: BTreeMap::from([("a".to_string(), "1".to_string())]),
+            other: serde_json::Map::new(),
+        };
+
+        assert!(!is_current(&path, &config));
+        assert!(write_json(&path, &config).unwrap());
+        assert!(is_current(&path, &config));
+        assert!(!write_json(&path, &config).unwrap());
+
+        // The same JSON with another key order and layout is not rewritten
+        fs::write(&path, r#"{ "servers": { "a": "1" } }"#).unwrap();
+        assert!(!write_json(&path, &config).unwrap());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            r#"{ "servers": { "a": "1" } }"#
+        );
+
+        let mut changed = config;
+        changed.servers.insert("b".to_string(), "2".to_string());
+        assert!(write_json(&path, &changed).unwrap());
+        assert_eq!(read_json::<Config>(&path).unwrap(), changed);
+    }
+}
@@ -0,0 +1,116 @@
+//! Confirmation for destructive operations.
+//!
+//! Every command that deletes something asks through a [`Confirmer`]:
+//!
+//! - `--yes` skips the question, for scripts
+//! - Without a terminal and without `--yes` the operation is refused instead of
+//!   failing halfway through a prompt
+//! - Operations that are hard to undo ask the user to type a name, e.g. the server
+//!   being removed, rather than answer y/n
+
+use colored::Colorize;
+use dialoguer::Input;
+use std::io::IsTerminal;
+
+use crate::error::McpError;
+use crate::utils::prompt::Confirm;
+
+/// Asks before destructive operations
+#[derive(Debug, Clone, Copy)]
+pub struct Confirmer {
+    yes: bool,
+    interactive: bool,
+}
+
+impl Confirmer {
+    /// `yes` is the command's `--yes` flag
+    pub fn new(yes: bool) -> Self {
+        Self {
+            yes,
+            interactive: std::io::stdin().is_terminal(),
+        }
+    }
+
+    /// Override terminal detection
+    pub fn with_interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    /// Ask a y/n question, defaulting to no
+    pub fn confirm(&self, prompt: &str) -> Result<bool, McpError> {
+        if let Some(answer) = self.answer_without_asking(prompt)? {
+            return Ok(answer);
+        }
+        Confirm::new()
+            .with_prompt(prompt)
+            .default(false)
+            .interact()
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Confirmation failed: {}", e)))
+    }
+
+    /// Ask the user to type `expected` to go ahead
+    pub fn confirm_typed(&self, prompt: &str, expected: &str) -> Result<bool, McpError> {
+        if let Some(answer) = self.answer_without_asking(prompt)? {
+            return Ok(answer);
+        }
+        println!("{prompt}");
+        let typed: String = Input::new()
+            .with_prompt(format!("Type {} to confirm", expected.cyan()))
+            .allow_empty(true)
+            .interact_text()
+            .map_err(|e| McpError::Other(anyhow::anyhow!("Confirmation failed: {}", e)))?;
+
+        let confirmed = typed_matches(&typed, expected);
+        if !confirmed {
+            println!("{} Input did not match '{}'", "⚠".yellow(), expected);
+        }
+        Ok(confirmed)
+    }
+
+    /// `--yes` answers for the user; without a terminal nobody can
+    fn answer_without_asking(&self, prompt: &str) -> Result<Option<bool>, McpError> {
+        if self.yes {
+            Ok(Some(true))
+        } else if self.interactive {
+            Ok(None)
+        } else {
+            Err(McpError::Other(anyhow::anyhow!(
+                "Cannot ask \"{}\" without a terminal; pass --yes to confirm",
+                prompt
+            )))
+        }
+    }
+}
+
+/// Whether the typed confirmation matches, ignoring surrounding whitespace
+pub fn typed_matches(typed: &str, expected: &str) -> bool {
+    typed.trim() == expected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yes_skips_the_question() {
+        let confirmer = Confirmer::new(true).with_interactive(false);
+        assert!(confirmer.confirm("Remove?").unwrap());
+        assert!(confirmer.confirm_typed("Remove all?", "server").unwrap());
+    }
+
+    #[test]
+    fn test_refuses_without_terminal() {
+        let confirmer = Confirmer::new(false).with_interactive(false);
+        let error = confirmer.confirm("Remove this server?").unwrap_err();
+        assert!(error.to_string().contains("--yes"), "{error}");
+        assert!(confirmer.confirm_typed("Remove all?", "server").is_err());
+    }
+
+    #[test]
+    fn test_typed_matches() {
+        assert!(typed_matches(" my-server\n", "my-server"));
+        assert!(!typed_matches("my", "my-server"));
+        assert!(!typed_matches("My-Server", "my-server"));
+    }
+}
@@ -0,0 +1,200 @@
+//! Free disk space checks before large downloads.
+//!
+//! A binary download or `docker pull` that runs out of space fails halfway and
+//! leaves partial files or dangling layers behind. [`ensure_space`] compares the
+//! space left on the target volume with the expected size first: too little fails
+//! with a clear error, and a download that would leave the volume nearly full
+//! gets a warning. When the size isn't known only the warning applies.
+//! `mcp doctor --only disk` reports the volumes downloads go to.
+
+use anyhow::Result;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::network_fs::existing_ancestor;
+use super::process::Process;
+
+/// Below this much free space a volume is reported as low
+pub const LOW_SPACE_THRESHOLD: u64 = 1024 * 1024 * 1024;
+
+/// Room kept free on top of a download, for temporary files and unpacking
+pub const SAFETY_MARGIN: u64 = 64 * 1024 * 1024;
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Whether a volume has room for a download
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpaceVerdict {
+    Enough,
+    /// The download fits but leaves less than [`LOW_SPACE_THRESHOLD`]
+    Low,
+    /// The download does not fit
+    Insufficient,
+}
+
+/// Free space on a volume compared with what a download needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpaceCheck {
+    /// Where the download goes
+    pub path: PathBuf,
+    pub available: u64,
+    /// Expected size of the download, if known
+    pub required: Option<u64>,
+}
+
+impl SpaceCheck {
+    pub fn verdict(&self) -> SpaceVerdict {
+        let required = self.required.unwrap_or(0);
+        if self.required.is_some() && self.available < required.saturating_add(SAFETY_MARGIN) {
+            SpaceVerdict::Insufficient
+        } else if self.available - required < LOW_SPACE_THRESHOLD {
+            SpaceVerdict::Low
+        } else {
+            SpaceVerdict::Enough
+        }
+    }
+}
+
+/// Free bytes on the volume holding `path` (or its nearest existing parent)
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = existing_ancestor(path);
+    if cfg!(target_os = "windows") {
+        let script = format!(
+            "(Get-Item -LiteralPath '{}').PSDrive.Free",
+            path.display().to_string().replace('\'', "''")
+        );
+        let output = Process::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .timeout(QUERY_TIMEOUT)
+            .output()
+            .ok()?;
+        if !output.success() {
+            return None;
+        }
+        output.stdout_lossy().trim().parse().ok()
+    } else {
+        let output = Process::new("df")
+            .arg("-Pk")
+            .arg(&path)
+            .timeout(QUERY_TIMEOUT)
+            .output()
+            .ok()?;
+        if !output.success() {
+            return None;
+        }
+        parse_df_output(&output.stdout_lossy())
+    }
+}
+
+/// Available bytes from POSIX `df -Pk` output
+pub fn parse_df_output(output: &str) -> Option<u64> {
+    let fields: Vec<&str> = output.lines().nth(1)?.split_whitespace().collect();
+    // The device name or mount point may contain spaces, so find the fields by the capacity column
+    let capacity = fields.iter().position(|field| field.ends_with('%'))?;
+    let kilobytes: u64 = fields.get(capacity.checked_sub(1)?)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Check there is room in `dir` for `what`, which takes about `required` bytes.
+/// Fails when it does not fit, warns when it leaves the volume nearly full, and
+/// passes when free space can't be determined
+pub fn ensure_space(dir: &Path, required: Option<u64>, what: &str) -> Result<()> {
+    let Some(available) = available_space(dir) else {
+        tracing::debug!("Could not determine free space for {}", dir.display());
+        return Ok(());
+    };
+    let check = SpaceCheck {
+        path: dir.to_path_buf(),
+        available,
+        required,
+    };
+    match check.verdict() {
+        SpaceVerdict::Enough => Ok(()),
+        SpaceVerdict::Low => {
+            eprintln!(
+                "{} Low disk space: {} free on the volume holding {} before downloading {what}",
+                "⚠".yellow(),
+                format_bytes(available),
+                dir.display()
+            );
+            Ok(())
+        }
+        SpaceVerdict::Insufficient => anyhow::bail!(
+            "Not enough disk space for {what}: it needs about {} but only {} is free on the volume holding {}. Free up space and try again",
+            format_bytes(required.unwrap_or(0).saturating_add(SAFETY_MARGIN)),
+            format_bytes(available),
+            dir.display()
+        ),
+    }
+}
+
+/// `bytes` in binary units, e.g. `1.5 GiB`
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn check(available: u64, required: Option<u64>) -> SpaceVerdict {
+        SpaceCheck {
+            path: PathBuf::from("/tmp"),
+            available,
+            required,
+        }
+        .verdict()
+    }
+
+    #[test]
+    fn test_verdict() {
+        assert_eq!(
+            check(10 * 1024 * MIB, Some(500 * MIB)),
+            SpaceVerdict::Enough
+        );
+        assert_eq!(check(1200 * MIB, Some(500 * MIB)), SpaceVerdict::Low);
+        assert_eq!(
+            check(520 * MIB, Some(500 * MIB)),
+            SpaceVerdict::Insufficient
+        );
+        assert_eq!(check(100 * MIB, None), SpaceVerdict::Low);
+        assert_eq!(check(0, None), SpaceVerdict::Low);
+    }
+
+    #[test]
+    fn test_parse_df_output() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/sda1        102400000  50000000  52400000      49% /\n";
+        assert_eq!(parse_df_output(output), Some(52_400_000 * 1024));
+
+        let spaced = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      //server/my share 2000 1000 1000 50% /Volumes/my share\n";
+        assert_eq!(parse_df_output(spaced), Some(1000 * 1024));
+
+        assert_eq!(
+            parse_df_output("df: /nope: No such file or directory"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536 * MIB), "1.5 GiB");
+        assert_eq!(format_bytes(64 * MIB), "64.0 MiB");
+    }
+}
@@ -0,0 +1,59 @@
+//! File access for mcp-helper's own state.
+//!
+//! [`ConfigManager`](crate::config::ConfigManager) and
+//! [`CacheManager`](crate::cache::CacheManager) read and write through a
+//! [`FileSystem`], so tests can run them against
+//! [`MemoryFileSystem`](crate::test_utils::mocks::MemoryFileSystem) instead of real
+//! directories.
+
+use anyhow::{Context, Result};
+use std::fmt::Debug;
+use std::fs;
+use std::path::Path;
+
+pub trait FileSystem: Debug + Send + Sync {
+    fn exists(&self, path: &Path) -> bool;
+
+    fn read_to_string(&self, path: &Path) -> Result<String>;
+
+    /// Replace `path` with `contents`, readable by the owner only
+    fn write_secure(&self, path: &Path, contents: &str) -> Result<()>;
+
+    fn create_dir_all(&self, path: &Path) -> Result<()>;
+
+    fn remove_file(&self, path: &Path) -> Result<()>;
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<()>;
+}
+
+/// The real file system
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    fn write_secure(&self, path: &Path, contents: &str) -> Result<()> {
+        super::secure_file::write_json_secure(path, contents)
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        fs::create_dir_all(path)
+            .with_context(|| format!("Failed to create directory {}", path.display()))
+    }
+
+    fn remove_file(&self, path: &Path) -> Result<()> {
+        fs::remove_file(path).with_context(|| format!("Failed to remove {}", path.display()))
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        fs::rename(from, to)
+            .with_context(|| format!("Failed to move {} to {}", from.display(), to.display()))
+    }
+}
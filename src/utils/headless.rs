@@ -0,0 +1,188 @@
+//! Detection of containers and CI runners.
+//!
+//! MCP clients run on a desktop, so inside a devcontainer, Codespace or CI job
+//! there is usually no client config to write to. Commands use
+//! [`HeadlessEnvironment::detect`] to explain that instead of dead-ending, and
+//! point at `mcp add --manifest-out`, which writes the would-be client entry
+//! to a file that can be merged into a client on the host.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// CI systems, by the environment variable that identifies them
+const CI_VARIABLES: &[(&str, &str)] = &[
+    ("GITHUB_ACTIONS", "GitHub Actions"),
+    ("GITLAB_CI", "GitLab CI"),
+    ("CIRCLECI", "CircleCI"),
+    ("BUILDKITE", "Buildkite"),
+    ("JENKINS_URL", "Jenkins"),
+    ("TF_BUILD", "Azure Pipelines"),
+    ("TEAMCITY_VERSION", "TeamCity"),
+    ("CI", "CI"),
+];
+
+/// Container environments identified by an environment variable
+const CONTAINER_VARIABLES: &[(&str, &str)] = &[
+    ("CODESPACES", "GitHub Codespaces"),
+    ("REMOTE_CONTAINERS", "a devcontainer"),
+    ("DEVCONTAINER", "a devcontainer"),
+    ("GITPOD_WORKSPACE_ID", "Gitpod"),
+    ("KUBERNETES_SERVICE_HOST", "Kubernetes"),
+];
+
+/// Container environments identified by a marker file
+const CONTAINER_FILES: &[(&str, &str)] = &[
+    ("/.dockerenv", "a Docker container"),
+    ("/run/.containerenv", "a Podman container"),
+];
+
+/// Whether the environment is a container or a CI runner
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadlessKind {
+    Container,
+    Ci,
+}
+
+/// A container or CI environment mcp-helper is running in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadlessEnvironment {
+    pub kind: HeadlessKind,
+    /// e.g. "GitHub Codespaces", "a devcontainer", "GitLab CI"
+    pub name: String,
+}
+
+impl HeadlessEnvironment {
+    /// The environment this process runs in, if it is a container or CI runner
+    pub fn detect() -> Option<Self> {
+        Self::detect_with(
+            |name| std::env::var(name).ok(),
+            |path| path.exists(),
+            fs::read_to_string("/proc/1/cgroup").ok().as_deref(),
+        )
+    }
+
+    /// Detection from explicit sources, so tests don't depend on the machine
+    pub fn detect_with(
+        var: impl Fn(&str) -> Option<String>,
+        exists: impl Fn(&Path) -> bool,
+        init_cgroup: Option<&str>,
+    ) -> Option<Self> {
+        let is_set = |name: &str| {
+            var(name).is_some_and(|value| {
+                let value = value.trim().to_lowercase();
+                !value.is_empty() && value != "0" && value != "false"
+            })
+        };
+
+        // CI first: CI jobs often run in containers, and the job is what users recognise
+        let found = CI_VARIABLES
+            .iter()
+            .find(|(variable, _)| is_set(variable))
+            .map(|(_, name)| (HeadlessKind::Ci, *name))
+            .or_else(|| {
+                CONTAINER_VARIABLES
+                    .iter()
+                    .find(|(variable, _)| is_set(variable))
+                    .map(|(_, name)| (HeadlessKind::Container, *name))
+            })
+            .or_else(|| {
+                CONTAINER_FILES
+                    .iter()
+                    .find(|(file, _)| exists(Path::new(file)))
+                    .map(|(_, name)| (HeadlessKind::Container, *name))
+            })
+            .or_else(|| {
+                init_cgroup
+                    .filter(|cgroup| {
+                        ["docker", "kubepods", "containerd", "lxc"]
+                            .iter()
+                            .any(|marker| cgroup.contains(marker))
+                    })
+                    .map(|_| (HeadlessKind::Container, "a container"))
+            });
+
+        found.map(|(kind, name)| Self {
+            kind,
+            name: name.to_string(),
+        })
+    }
+
+    /// What to do when no client is installed here
+    pub fn guidance(&self) -> String {
+        let context = match self.kind {
+            HeadlessKind::Container => {
+                "MCP clients run on the host, so there is no client config here to update."
+            }
+            HeadlessKind::Ci => "CI runners have no MCP clients to configure.",
+        };
+        format!(
+            "{context} Use 'mcp add <server> --manifest-out mcp.json' to write the server entry to a file, then merge it into your client's config on the host (or mount it there)."
+        )
+    }
+}
+
+impl fmt::Display for HeadlessEnvironment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            HeadlessKind::Container => write!(f, "running inside {}", self.name),
+            HeadlessKind::Ci => write!(f, "running in {}", self.name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn detect(
+        vars: &[(&str, &str)],
+        files: &[&str],
+        cgroup: Option<&str>,
+    ) -> Option<HeadlessEnvironment> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        HeadlessEnvironment::detect_with(
+            |name| vars.get(name).cloned(),
+            |path| files.iter().any(|file| Path::new(file) == path),
+            cgroup,
+        )
+    }
+
+    #[test]
+    fn test_desktop_is_not_headless() {
+        assert_eq!(
+            detect(&[("CI", "false")], &[], Some("0::/user.slice")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ci_takes_precedence_over_container() {
+        let env = detect(
+            &[("GITHUB_ACTIONS", "true"), ("CI", "true")],
+            &["/.dockerenv"],
+            None,
+        )
+        .unwrap();
+        assert_eq!(env.kind, HeadlessKind::Ci);
+        assert_eq!(env.name, "GitHub Actions");
+    }
+
+    #[test]
+    fn test_container_markers() {
+        let env = detect(&[("REMOTE_CONTAINERS", "true")], &[], None).unwrap();
+        assert_eq!(env.kind, HeadlessKind::Container);
+        assert_eq!(env.to_string(), "running inside a devcontainer");
+
+        let env = detect(&[], &["/run/.containerenv"], None).unwrap();
+        assert_eq!(env.name, "a Podman container");
+
+        let env = detect(&[], &[], Some("12:pids:/kubepods/besteffort/pod1")).unwrap();
+        assert_eq!(env.kind, HeadlessKind::Container);
+        assert!(env.guidance().contains("--manifest-out"));
+    }
+}
@@ -31,19 +31,11 @@ const MAX_JSON_SIZE: usize = 10 * 1024 * 1024;
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
 pub fn validate_json_input(json_str: &str) -> Result<()> {
-    // Check size first (cheapest check)
-    if json_str.len() > MAX_JSON_SIZE {
-        anyhow::bail!(
-            "JSON input too large: {} bytes exceeds maximum of {} bytes",
-            json_str.len(),
-            MAX_JSON_SIZE
-        );
-    }
-
-    // Parse and validate depth
-    let value: Value = serde_json::from_str(json_str).context("Invalid JSON syntax")?;
+    parse_json_safe(json_str).map(|_| ())
+}
 
-    let depth = calculate_json_depth(&value);
+fn check_depth(value: &Value) -> Result<()> {
+    let depth = calculate_json_depth(value);
     if depth > MAX_JSON_DEPTH {
         anyhow::bail!(
             "JSON nesting too deep: {} levels exceeds maximum of {}",
@@ -51,7 +43,6 @@ pub fn validate_json_input(json_str: &str) -> Result<()> {
             MAX_JSON_DEPTH
         );
     }
-
     Ok(())
 }
 
@@ -75,8 +66,17 @@ pub fn validate_json_input(json_str: &str) -> Result<()> {
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
 pub fn parse_json_safe(json_str: &str) -> Result<Value> {
-    validate_json_input(json_str)?;
-    serde_json::from_str(json_str).context("Failed to parse JSON")
+    if json_str.len() > MAX_JSON_SIZE {
+        anyhow::bail!(
+            "JSON input too large: {} bytes exceeds maximum of {} bytes",
+            json_str.len(),
+            MAX_JSON_SIZE
+        );
+    }
+
+    let value: Value = serde_json::from_str(json_str).context("Invalid JSON syntax")?;
+    check_depth(&value)?;
+    Ok(value)
 }
 
 /// Safely deserialize JSON into a specific type.
@@ -107,8 +107,9 @@ pub fn deserialize_json_safe<T>(json_str: &str) -> Result<T>
 where
     T: serde::de::DeserializeOwned,
 {
-    validate_json_input(json_str)?;
-    serde_json::from_str(json_str).context("Failed to deserialize JSON")
+    // Validation already parses the input, so deserialize that value rather than the text
+    let value = parse_json_safe(json_str)?;
+    T::deserialize(value).context("Failed to deserialize JSON")
 }
 
 /// Calculate the maximum nesting depth of a JSON value.
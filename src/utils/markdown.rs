@@ -0,0 +1,245 @@
+//! Minimal Markdown rendering for terminal output.
+//!
+//! Handles the subset server READMEs actually use: headings, lists, block quotes,
+//! inline code and fenced code blocks. Code blocks get light syntax highlighting
+//! for JSON and shell snippets; everything else is printed as-is.
+
+use colored::Colorize;
+
+/// Render Markdown to colored terminal text, indenting every line by `indent` spaces.
+pub fn render_markdown(markdown: &str, indent: usize) -> String {
+    let pad = " ".repeat(indent);
+    let mut out = String::new();
+    let mut code_lang: Option<String> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some(fence) = trimmed.strip_prefix("```") {
+            code_lang = match code_lang {
+                Some(_) => None,
+                None => Some(fence.trim().to_lowercase()),
+            };
+            continue;
+        }
+
+        if let Some(lang) = &code_lang {
+            out.push_str(&format!("{pad}  {}\n", highlight_code_line(line, lang)));
+            continue;
+        }
+
+        // Skip HTML such as badges and <details> wrappers
+        if trimmed.starts_with('<') {
+            continue;
+        }
+
+        let rendered = if let Some(heading) = heading_text(trimmed) {
+            heading.bold().cyan().to_string()
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            let depth = (line.len() - trimmed.len()) / 2;
+            format!("{}• {}", "  ".repeat(depth), render_inline(item))
+        } else if let Some(quote) = trimmed.strip_prefix('>') {
+            format!("│ {}", render_inline(quote.trim_start()).dimmed())
+        } else {
+            render_inline(line)
+        };
+
+        if rendered.is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&format!("{pad}{rendered}\n"));
+        }
+    }
+
+    out
+}
+
+fn heading_text(line: &str) -> Option<&str> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    if (1..=6).contains(&hashes) && line[hashes..].starts_with(' ') {
+        Some(line[hashes..].trim())
+    } else {
+        None
+    }
+}
+
+/// Render inline code spans, bold text and links
+pub fn render_inline(text: &str) -> String {
+    let mut out = String::new();
+    for (index, part) in text.split('`').enumerate() {
+        if index % 2 == 1 {
+            out.push_str(&part.yellow().to_string());
+        } else {
+            out.push_str(&render_links(&part.replace("**", "")));
+        }
+    }
+    out
+}
+
+/// `[text](url)` becomes `text (url)`
+fn render_links(text: &str) -> String {
+    let mut out = String::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find("](").map(|i| open + i) else {
+            break;
+        };
+        let Some(end) = rest[close..].find(')').map(|i| close + i) else {
+            break;
+        };
+
+        out.push_str(&rest[..open]);
+        let label = &rest[open + 1..close];
+        let url = &rest[close + 2..end];
+        out.push_str(&format!("{label} ({})", url.underline()));
+        rest = &rest[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Highlight one line of a fenced code block
+pub fn highlight_code_line(line: &str, lang: &str) -> String {
+    match lang {
+        "json" | "jsonc" => highlight_json(line),
+        "sh" | "bash" | "shell" | "console" | "zsh" | "powershell" => highlight_shell(line),
+        _ => line.to_string(),
+    }
+}
+
+fn highlight_json(line: &str) -> String {
+    let mut out = String::new();
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '"' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != '"' {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            let literal: String = chars[start..i].iter().collect();
+
+            // A string followed by a colon is a key
+            let is_key = chars[i..]
+                .iter()
+                .find(|c| !c.is_whitespace())
+                .is_some_and(|c| *c == ':');
+            if is_key {
+                out.push_str(&literal.cyan().to_string());
+            } else {
+                out.push_str(&literal.green().to_string());
+            }
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = i;
+            i += 1;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit() || matches!(chars[i], '.' | 'e' | 'E' | '+' | '-'))
+            {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            out.push_str(&number.yellow().to_string());
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+
+    out
+}
+
+fn highlight_shell(line: &str) -> String {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    if trimmed.starts_with('#') {
+        return format!("{indent}{}", trimmed.dimmed());
+    }
+
+    let (prompt, command) = match trimmed.strip_prefix("$ ") {
+        Some(rest) => ("$ ", rest),
+        None => ("", trimmed),
+    };
+
+    let mut words = Vec::new();
+    for (index, word) in command.split(' ').enumerate() {
+        let rendered = if index == 0 {
+            word.bold().to_string()
+        } else if word.starts_with('-') {
+            word.yellow().to_string()
+        } else if word.starts_with('"') || word.starts_with('\'') {
+            word.green().to_string()
+        } else {
+            word.to_string()
+        };
+        words.push(rendered);
+    }
+
+    format!("{indent}{}{}", prompt.dimmed(), words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip_ansi(text: &str) -> String {
+        let mut out = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\u{1b}' {
+                for c in chars.by_ref() {
+                    if c == 'm' {
+                        break;
+                    }
+                }
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_render_markdown_structure() {
+        let markdown = "# Weather\n\n<img src=\"badge.svg\">\nGet the `forecast`.\n\n- one\n  - two\n\n```json\n{ \"key\": 1 }\n```\n";
+        let rendered = strip_ansi(&render_markdown(markdown, 2));
+
+        assert!(rendered.contains("  Weather\n"));
+        assert!(!rendered.contains("<img"));
+        assert!(rendered.contains("  Get the forecast."));
+        assert!(rendered.contains("  • one"));
+        assert!(rendered.contains("    • two"));
+        assert!(rendered.contains("    { \"key\": 1 }"));
+        assert!(!rendered.contains("```"));
+    }
+
+    #[test]
+    fn test_render_links() {
+        let rendered = strip_ansi(&render_inline("See [the docs](https://example.com) now"));
+        assert_eq!(rendered, "See the docs (https://example.com) now");
+    }
+
+    #[test]
+    fn test_highlighting_preserves_text() {
+        let json = r#"  "env": { "API_KEY": "abc", "PORT": -8080 }"#;
+        assert_eq!(strip_ansi(&highlight_code_line(json, "json")), json);
+
+        let shell = "$ npx -y @org/server \"arg\" # comment";
+        assert_eq!(strip_ansi(&highlight_code_line(shell, "bash")), shell);
+        assert_eq!(highlight_code_line("plain", "python"), "plain");
+    }
+}
@@ -1,2 +1,23 @@
+pub mod antivirus;
+pub mod batch_file;
+pub mod clock;
+pub mod config_file;
+pub mod confirm;
+pub mod disk_space;
+pub mod filesystem;
+pub mod headless;
 pub mod json_validator;
+pub mod markdown;
+pub mod network_fs;
+pub mod npm_cache;
+pub mod parse_error;
+pub mod paths;
+pub mod pattern;
+pub mod process;
+pub mod prompt;
+pub mod rate_limit;
+pub mod secrets;
 pub mod secure_file;
+pub mod state_crypto;
+pub mod state_file;
+pub mod yaml;
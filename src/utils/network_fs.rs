@@ -0,0 +1,407 @@
+//! Config files on network filesystems and in sync-client folders.
+//!
+//! Config writes normally go to a temporary file that is renamed over the
+//! target. That is atomic on a local disk, but not everywhere a home directory
+//! can live:
+//!
+//! - NFS and SMB shares may rename without replacing what other clients have
+//!   cached, and lock files are unreliable
+//! - OneDrive, Dropbox, iCloud Drive and Google Drive see the rename as a delete
+//!   plus a new file and can upload, restore or duplicate the wrong one
+//!
+//! [`WriteStrategy::for_path`] picks [`WriteStrategy::BackupThenWrite`] for
+//! those locations: the current file is copied aside, the new contents are
+//! written in place and read back, and the backup is restored if they do not
+//! match. `mcp doctor --only storage` reports configs in such locations and the
+//! conflict copies sync clients leave behind.
+
+use std::fmt;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Filesystem types where rename and locking cannot be trusted
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb",
+    "smb2",
+    "smb3",
+    "smbfs",
+    "afpfs",
+    "webdav",
+    "davfs",
+    "fuse.sshfs",
+    "9p",
+    "afs",
+    "ceph",
+    "glusterfs",
+    "lustre",
+];
+
+/// Where a file is stored, as far as writing it safely is concerned
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StorageKind {
+    Local,
+    /// A network filesystem such as `nfs4` or `cifs`
+    Network(String),
+    /// A folder kept in sync by a client such as OneDrive
+    Synced(String),
+}
+
+impl StorageKind {
+    /// Where `path` is stored; a path that does not exist yet is judged by its parents
+    pub fn detect(path: &Path) -> Self {
+        let path = existing_ancestor(path);
+        if let Some(provider) = sync_provider(&path) {
+            return Self::Synced(provider.to_string());
+        }
+        if is_unc(&path) {
+            return Self::Network("smb".to_string());
+        }
+        match filesystem_type(&path) {
+            Some(fs_type) if is_network_fs(&fs_type) => Self::Network(fs_type),
+            _ => Self::Local,
+        }
+    }
+
+    pub fn is_local(&self) -> bool {
+        matches!(self, Self::Local)
+    }
+}
+
+impl fmt::Display for StorageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local disk"),
+            Self::Network(fs_type) => write!(f, "network filesystem ({fs_type})"),
+            Self::Synced(provider) => write!(f, "{provider} folder"),
+        }
+    }
+}
+
+/// How a config file is replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteStrategy {
+    /// Write a temporary file and rename it over the target
+    Atomic,
+    /// Copy the target aside, write in place, read back and restore on mismatch
+    BackupThenWrite,
+}
+
+impl WriteStrategy {
+    pub fn for_path(path: &Path) -> Self {
+        if StorageKind::detect(path).is_local() {
+            Self::Atomic
+        } else {
+            Self::BackupThenWrite
+        }
+    }
+}
+
+/// The sync client whose folder contains `path`, judged by folder names
+pub fn sync_provider(path: &Path) -> Option<&'static str> {
+    let names: Vec<String> = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    names.iter().enumerate().find_map(|(i, name)| {
+        let next = names.get(i + 1).map(String::as_str);
+        match name.as_str() {
+            // "OneDrive" and "OneDrive - Contoso" for work accounts
+            n if n == "OneDrive" || n.starts_with("OneDrive - ") => Some("OneDrive"),
+            "Dropbox" => Some("Dropbox"),
+            "Google Drive" | "GoogleDrive" => Some("Google Drive"),
+            "iCloud Drive" => Some("iCloud Drive"),
+            "Mobile Documents" => Some("iCloud Drive"),
+            // macOS File Provider locations, ~/Library/CloudStorage/<Provider>-<account>
+            "CloudStorage" => next.and_then(cloud_storage_provider),
+            _ => None,
+        }
+    })
+}
+
+fn cloud_storage_provider(folder: &str) -> Option<&'static str> {
+    [
+        ("OneDrive", "OneDrive"),
+        ("Dropbox", "Dropbox"),
+        ("GoogleDrive", "Google Drive"),
+        ("Box", "Box"),
+    ]
+    .iter()
+    .find(|(prefix, _)| folder.starts_with(prefix))
+    .map(|(_, provider)| *provider)
+}
+
+/// Whether `fs_type`, as reported by the mount table, is a network filesystem
+pub fn is_network_fs(fs_type: &str) -> bool {
+    let fs_type = fs_type.to_lowercase();
+    NETWORK_FS_TYPES.contains(&fs_type.as_str())
+}
+
+/// A `\\server\share` path
+fn is_unc(path: &Path) -> bool {
+    let text = path.to_string_lossy();
+    (text.starts_with(r"\\") && !text.starts_with(r"\\?\") && !text.starts_with(r"\\.\"))
+        || text.starts_with(r"\\?\UNC\")
+}
+
+/// `path` or its nearest ancestor that exists
+pub(crate) fn existing_ancestor(path: &Path) -> PathBuf {
+    path.ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+/// A mounted filesystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub point: PathBuf,
+    pub fs_type: String,
+}
+
+/// Mounts in `/proc/self/mounts` format: `device point type options dump pass`,
+/// with spaces in the mount point escaped as `\040`
+pub fn parse_proc_mounts(contents: &str) -> Vec<Mount> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?.to_string();
+            Some(Mount {
+                point: PathBuf::from(point),
+                fs_type,
+            })
+        })
+        .collect()
+}
+
+/// Mounts in the format printed by `mount` on macOS and the BSDs:
+/// `server:/export on /Volumes/home (nfs, nodev, nosuid)`
+pub fn parse_mount_output(output: &str) -> Vec<Mount> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (point, options) = rest.rsplit_once(" (")?;
+            let fs_type = options.split([',', ')']).next()?.trim().to_string();
+            Some(Mount {
+                point: PathBuf::from(point),
+                fs_type,
+            })
+        })
+        .collect()
+}
+
+/// The filesystem type of the mount that contains `path`
+pub fn mount_for<'a>(path: &Path, mounts: &'a [Mount]) -> Option<&'a Mount> {
+    mounts
+        .iter()
+        .filter(|mount| path.starts_with(&mount.point))
+        .max_by_key(|mount| mount.point.components().count())
+}
+
+fn filesystem_type(path: &Path) -> Option<String> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mounts = if cfg!(target_os = "linux") {
+        parse_proc_mounts(&fs::read_to_string("/proc/self/mounts").ok()?)
+    } else if cfg!(target_os = "macos") {
+        let output = super::process::Process::new("mount").output().ok()?;
+        parse_mount_output(&output.stdout_lossy())
+    } else {
+        return None;
+    };
+    mount_for(&path, &mounts).map(|mount| mount.fs_type.clone())
+}
+
+/// Copies of `path` left by sync clients resolving an edit conflict, such as
+/// `config-LAPTOP.json` (OneDrive), `config (conflicted copy).json` (Dropbox)
+/// or `config 2.json` (iCloud Drive)
+pub fn conflict_copies(path: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (path.parent(), path.file_stem()) else {
+        return Vec::new();
+    };
+    let stem = stem.to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut copies: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|candidate| {
+            candidate
+                .file_name()
+                .map(|name| is_conflict_copy(&stem, &extension, &name.to_string_lossy()))
+                .unwrap_or(false)
+        })
+        .collect();
+    copies.sort();
+    copies
+}
+
+/// Whether `name` is a sync-conflict copy of `<stem><extension>`
+pub fn is_conflict_copy(stem: &str, extension: &str, name: &str) -> bool {
+    let Some(middle) = name
+        .strip_prefix(stem)
+        .and_then(|rest| rest.strip_suffix(extension))
+    else {
+        return false;
+    };
+    if middle.is_empty() {
+        return false;
+    }
+
+    let lower = middle.to_lowercase();
+    // Dropbox ("conflicted copy") and Google Drive ("conflict")
+    if lower.contains("conflict") {
+        return true;
+    }
+    // iCloud Drive and Finder duplicates: "config 2.json", Windows: "config (1).json"
+    let numbered = middle
+        .trim_start()
+        .trim_start_matches('(')
+        .trim_end_matches(')');
+    if middle.starts_with(' ')
+        && !numbered.is_empty()
+        && numbered.chars().all(|c| c.is_ascii_digit())
+    {
+        return true;
+    }
+    // OneDrive appends the machine name: "config-LAPTOP-4F2K.json"
+    middle.strip_prefix('-').is_some_and(|machine| {
+        !machine.is_empty()
+            && machine
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-')
+            && machine.chars().any(|c| c.is_ascii_uppercase())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sync_provider() {
+        let cases = [
+            ("/Users/me/OneDrive/AppData/config.json", Some("OneDrive")),
+            (
+                "C:/Users/me/OneDrive - Contoso/.cursor/mcp.json",
+                Some("OneDrive"),
+            ),
+            ("/home/me/Dropbox/dotfiles/config.json", Some("Dropbox")),
+            (
+                "/Users/me/Library/Mobile Documents/com~apple~CloudDocs/mcp.json",
+                Some("iCloud Drive"),
+            ),
+            (
+                "/Users/me/Library/CloudStorage/GoogleDrive-me@example.com/My Drive/mcp.json",
+                Some("Google Drive"),
+            ),
+            (
+                "/Users/me/Library/Application Support/Claude/claude_desktop_config.json",
+                None,
+            ),
+            ("/home/me/.config/OneDriveBackup/config.json", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(sync_provider(Path::new(path)), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_parse_proc_mounts_and_lookup() {
+        let mounts = parse_proc_mounts(
+            "/dev/sda1 / ext4 rw,relatime 0 0\n\
+             fileserver:/export/home /home nfs4 rw,vers=4.2 0 0\n\
+             //nas/share /mnt/My\\040Share cifs rw 0 0\n\
+             tmpfs /home/me/.cache tmpfs rw 0 0\n",
+        );
+        assert_eq!(mounts.len(), 4);
+        assert_eq!(mounts[2].point, PathBuf::from("/mnt/My Share"));
+
+        let fs_type = |path: &str| mount_for(Path::new(path), &mounts).map(|m| m.fs_type.as_str());
+        assert_eq!(fs_type("/home/me/.config/mcp.json"), Some("nfs4"));
+        assert_eq!(fs_type("/home/me/.cache/x"), Some("tmpfs"));
+        assert_eq!(fs_type("/etc/hosts"), Some("ext4"));
+        assert_eq!(fs_type("/mnt/My Share/a"), Some("cifs"));
+    }
+
+    #[test]
+    fn test_parse_mount_output() {
+        let mounts = parse_mount_output(
+            "/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)\n\
+             fileserver:/export/home on /Volumes/home dir (nfs, nodev, nosuid, mounted by me)\n\
+             //me@nas/share on /Volumes/share (smbfs, nodev, nosuid)\n",
+        );
+        assert_eq!(
+            mounts[1],
+            Mount {
+                point: PathBuf::from("/Volumes/home dir"),
+                fs_type: "nfs".to_string(),
+            }
+        );
+        assert!(is_network_fs(&mounts[2].fs_type));
+        assert!(!is_network_fs(&mounts[0].fs_type));
+    }
+
+    #[test]
+    fn test_unc_paths_are_network() {
+        assert!(is_unc(Path::new(r"\\fileserver\home\me\.cursor\mcp.json")));
+        assert!(is_unc(Path::new(r"\\?\UNC\fileserver\home\mcp.json")));
+        assert!(!is_unc(Path::new(r"\\?\C:\Users\me\mcp.json")));
+        assert!(!is_unc(Path::new("/home/me/mcp.json")));
+    }
+
+    #[test]
+    fn test_conflict_copy_names() {
+        let copy = |name| is_conflict_copy("claude_desktop_config", ".json", name);
+        assert!(copy("claude_desktop_config-DESKTOP-4F2K.json"));
+        assert!(copy(
+            "claude_desktop_config (conflicted copy 2024-05-01).json"
+        ));
+        assert!(copy("claude_desktop_config 2.json"));
+        assert!(copy("claude_desktop_config (1).json"));
+        assert!(!copy("claude_desktop_config.json"));
+        assert!(!copy("claude_desktop_config-backup.json"));
+        assert!(!copy("claude_desktop_config.json.bak"));
+    }
+
+    #[test]
+    fn test_conflict_copies_on_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = temp_dir.path().join("mcp.json");
+        for name in ["mcp.json", "mcp-LAPTOP.json", "mcp 2.json", "other.json"] {
+            fs::write(temp_dir.path().join(name), "{}").unwrap();
+        }
+        assert_eq!(
+            conflict_copies(&config),
+            vec![
+                temp_dir.path().join("mcp 2.json"),
+                temp_dir.path().join("mcp-LAPTOP.json")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_local_temp_dir_uses_atomic_writes() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("nested").join("config.json");
+        if StorageKind::detect(&path).is_local() {
+            assert_eq!(WriteStrategy::for_path(&path), WriteStrategy::Atomic);
+        }
+    }
+}
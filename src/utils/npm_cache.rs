@@ -0,0 +1,184 @@
+//! Corrupted npm caches.
+//!
+//! A truncated or half-written entry in npm's content-addressed cache makes every
+//! later `npx` start of the package fail with `EINTEGRITY`. MCP clients only show
+//! that in their own logs, so the server just appears to be missing. Failed
+//! launches are checked with [`is_corruption`] and get [`CORRUPTION_HINT`], and
+//! `mcp doctor --only npm` runs `npm cache verify` and offers to clean the cache.
+
+use std::time::Duration;
+
+use super::process::Process;
+use crate::error::McpError;
+
+/// How long `npm cache verify` may take; it reads every cached file
+pub const VERIFY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The command that clears the cache
+pub const CLEAN_COMMAND: &str = "npm cache clean --force";
+
+/// Hint attached to failures that look like a corrupted cache
+pub const CORRUPTION_HINT: &str =
+    "The npm cache looks corrupted. Run 'mcp doctor --only npm' to verify it and clean it";
+
+/// Lower-cased fragments npm and cacache print when cached content is damaged
+const CORRUPTION_SIGNATURES: &[&str] = &[
+    "eintegrity",
+    "integrity checksum failed",
+    "ebadsize",
+    "bad data size",
+    "unexpected end of json input while parsing",
+];
+
+/// Whether `output` shows an integrity failure from npm's cache
+pub fn is_corruption(output: &str) -> bool {
+    let output = output.to_lowercase();
+    CORRUPTION_SIGNATURES
+        .iter()
+        .any(|signature| output.contains(signature))
+}
+
+/// Whether any of a failed launch's stderr `lines` show a corrupted cache
+pub fn explains_failure(lines: &[String]) -> bool {
+    lines.iter().any(|line| is_corruption(line))
+}
+
+/// Outcome of `npm cache verify`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verification {
+    /// The cache is consistent; `removed` corrupted entries were dropped on the way
+    Healthy { removed: u64 },
+    /// Verification itself hit damaged content
+    Corrupted(String),
+    /// Verification failed for another reason, such as permissions
+    Failed(String),
+}
+
+impl Verification {
+    /// Interpret the exit status and combined output of `npm cache verify`
+    pub fn from_output(success: bool, output: &str) -> Self {
+        if is_corruption(output) {
+            Self::Corrupted(first_error_line(output))
+        } else if success {
+            Self::Healthy {
+                removed: removed_entries(output),
+            }
+        } else {
+            Self::Failed(first_error_line(output))
+        }
+    }
+}
+
+/// The npm executable for this platform
+pub fn npm_command() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "npm.cmd"
+    } else {
+        "npm"
+    }
+}
+
+/// Run `npm cache verify`
+pub fn verify() -> Result<Verification, McpError> {
+    let output = Process::new(npm_command())
+        .args(["cache", "verify"])
+        .timeout(VERIFY_TIMEOUT)
+        .output()?;
+    let combined = format!("{}\n{}", output.stdout_lossy(), output.stderr_lossy());
+    Ok(Verification::from_output(output.success(), &combined))
+}
+
+/// Run `npm cache clean --force`
+pub fn clean() -> Result<(), McpError> {
+    let output = Process::new(npm_command())
+        .args(["cache", "clean", "--force"])
+        .output()?;
+    if output.success() {
+        Ok(())
+    } else {
+        Err(McpError::Other(anyhow::anyhow!(
+            "{CLEAN_COMMAND} failed: {}",
+            output.stderr_lossy().trim()
+        )))
+    }
+}
+
+/// Count from the "Corrupted content removed: N" line of `npm cache verify`
+fn removed_entries(output: &str) -> u64 {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Corrupted content removed:"))
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// The most telling line of a failed npm run
+fn first_error_line(output: &str) -> String {
+    let lines: Vec<&str> = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines
+        .iter()
+        .find(|line| is_corruption(line))
+        .or_else(|| {
+            lines
+                .iter()
+                .find(|line| line.contains("ERR!") || line.starts_with("npm error"))
+        })
+        .or_else(|| lines.first())
+        .map(|line| line.to_string())
+        .unwrap_or_else(|| "npm cache verify failed".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_corruption() {
+        assert!(is_corruption("npm ERR! code EINTEGRITY"));
+        assert!(is_corruption(
+            "npm error sha512-abc integrity checksum failed when using sha512: wanted sha512-abc but got sha512-def"
+        ));
+        assert!(is_corruption(
+            "npm ERR! Unexpected end of JSON input while parsing near '...\"tarball\":\"ht'"
+        ));
+        assert!(!is_corruption("npm ERR! code E404"));
+        assert!(!is_corruption("Error: Cannot find module 'zod'"));
+
+        let lines = vec![
+            "Need to install the following packages:".to_string(),
+            "npm error code EINTEGRITY".to_string(),
+        ];
+        assert!(explains_failure(&lines));
+        assert!(!explains_failure(&lines[..1]));
+    }
+
+    #[test]
+    fn test_verification_from_output() {
+        let healthy = "Cache verified and compressed (~/.npm/_cacache)\n\
+                       Content verified: 2012 (130248394 bytes)\n\
+                       Corrupted content removed: 3\n\
+                       Index entries: 2012\n\
+                       Finished in 4.211s";
+        assert_eq!(
+            Verification::from_output(true, healthy),
+            Verification::Healthy { removed: 3 }
+        );
+
+        let corrupted = "npm ERR! code EINTEGRITY\n\
+                         npm ERR! sha512-abc integrity checksum failed when using sha512";
+        assert_eq!(
+            Verification::from_output(false, corrupted),
+            Verification::Corrupted("npm ERR! code EINTEGRITY".to_string())
+        );
+
+        let denied = "npm ERR! code EACCES\nnpm ERR! Your cache folder contains root-owned files";
+        assert_eq!(
+            Verification::from_output(false, denied),
+            Verification::Failed("npm ERR! code EACCES".to_string())
+        );
+    }
+}
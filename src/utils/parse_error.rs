@@ -0,0 +1,135 @@
+//! Parse errors that point at the offending line and column.
+//!
+//! Batch files, preferences and manifests are written by hand, so a parse error
+//! names the file, the 1-based line and column, and what was expected there:
+//!
+//! ```text
+//! servers.txt:4:1: expected `key=value` or `[server-name]`, found `port 8080`
+//! ```
+
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+/// A parse failure at a position in a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The file, or a description of the document when it has no path
+    pub file: String,
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, counted in characters
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(
+        file: impl Into<String>,
+        line: usize,
+        column: usize,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            file: file.into(),
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Name the file the document came from
+    pub fn with_file(mut self, file: impl Into<String>) -> Self {
+        self.file = file.into();
+        self
+    }
+
+    /// Locate a serde_json error in `file`
+    pub fn from_json(file: impl Into<String>, error: &serde_json::Error) -> Self {
+        // serde_json reports line 0 for errors that have no position (e.g. I/O)
+        let message = error.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(index) if error.line() > 0 => message[..index].to_string(),
+            _ => message,
+        };
+        Self::new(file, error.line().max(1), error.column().max(1), message)
+    }
+
+    /// Locate a serde_yaml error in `file`
+    pub fn from_yaml(file: impl Into<String>, error: &serde_yaml::Error) -> Self {
+        let message = error.to_string();
+        match error.location() {
+            Some(location) => {
+                let message = match message.rfind(" at line ") {
+                    Some(index) => message[..index].to_string(),
+                    None => message,
+                };
+                Self::new(file, location.line(), location.column(), message)
+            }
+            None => Self::new(file, 1, 1, message),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.file, self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Deserialize a JSON document, reporting syntax and schema errors with their position
+pub fn parse_json<T: DeserializeOwned>(file: &str, content: &str) -> Result<T, ParseError> {
+    if let Err(e) = super::json_validator::validate_json_input(content) {
+        // Syntax errors are located below; size and depth limits apply to the whole file
+        if serde_json::from_str::<serde_json::Value>(content).is_ok() {
+            return Err(ParseError::new(file, 1, 1, e.to_string()));
+        }
+    }
+    serde_json::from_str(content).map_err(|e| ParseError::from_json(file, &e))
+}
+
+/// 1-based column of byte offset `index` in `line`, counted in characters
+pub fn column_of(line: &str, index: usize) -> usize {
+    let index = index.min(line.len());
+    line.char_indices().take_while(|(i, _)| *i < index).count() + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Doc {
+        name: String,
+        port: u16,
+    }
+
+    #[test]
+    fn test_json_errors_are_located() {
+        let error = parse_json::<Doc>("doc.json", "{\n  \"name\": \"x\",\n  \"port\": \"high\"\n}")
+            .unwrap_err();
+        assert_eq!(error.file, "doc.json");
+        assert_eq!(error.line, 3);
+        assert!(error.message.contains("invalid type"), "{}", error.message);
+        assert!(error.to_string().starts_with("doc.json:3:"));
+
+        let error = parse_json::<Doc>("doc.json", "{\n  \"name\": \"x\",,\n}").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(error.column > 1);
+        assert!(!error.message.contains("at line"));
+    }
+
+    #[test]
+    fn test_column_counts_characters() {
+        assert_eq!(column_of("abc", 0), 1);
+        assert_eq!(column_of("é=x", 2), 2);
+        assert_eq!(column_of("abc", 99), 4);
+    }
+}
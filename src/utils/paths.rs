@@ -0,0 +1,52 @@
+//! Where mcp-helper keeps its own data, and the program a command path names.
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// `<data dir>/mcp-helper`, or `$XDG_DATA_HOME/mcp-helper` when that is set.
+///
+/// Every piece of machine-local state (run history, audit log, wrappers, managed
+/// servers and so on) lives under this directory.
+pub fn data_dir() -> Result<PathBuf> {
+    // Check if XDG_DATA_HOME is set (for testing)
+    if let Ok(xdg_data) = std::env::var("XDG_DATA_HOME") {
+        return Ok(PathBuf::from(xdg_data).join("mcp-helper"));
+    }
+
+    let base_dir = directories::ProjectDirs::from("com", "mcp", "mcp-helper")
+        .context("Failed to get project directories")?;
+    Ok(base_dir.data_dir().to_path_buf())
+}
+
+/// Lower-cased file name of a command without its directory or Windows executable
+/// extension, so `C:\tools\npx.cmd` and `/usr/bin/npx` both become `npx`.
+///
+/// Both separators are split on, since configs written on Windows are read everywhere.
+pub fn program_name(command: &str) -> String {
+    let file = command
+        .trim()
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    [".exe", ".cmd", ".bat"]
+        .iter()
+        .find_map(|ext| file.strip_suffix(ext))
+        .map(str::to_string)
+        .unwrap_or(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_program_name() {
+        assert_eq!(program_name("npx"), "npx");
+        assert_eq!(program_name("/usr/local/bin/npx"), "npx");
+        assert_eq!(program_name(r"C:\Program Files\nodejs\npx.cmd"), "npx");
+        assert_eq!(program_name(r"C:\Tools\Docker.EXE"), "docker");
+        assert_eq!(program_name(" python3.11 "), "python3.11");
+        assert_eq!(program_name(""), "");
+    }
+}
@@ -0,0 +1,105 @@
+//! Server name pattern matching shared by bulk operations and list filtering.
+//!
+//! Patterns are globs by default (`*` matches any run of characters, `?` matches
+//! a single character). A regular expression can be used instead by prefixing the
+//! pattern with `re:`, e.g. `re:^(github|gitlab)-`.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// Prefix selecting regular expression syntax instead of a glob
+pub const REGEX_PREFIX: &str = "re:";
+
+/// A compiled pattern for matching server names
+#[derive(Debug, Clone)]
+pub struct NamePattern {
+    source: String,
+    regex: Regex,
+}
+
+impl NamePattern {
+    /// Parse a pattern, treating it as a regex if it starts with `re:` and a glob otherwise.
+    pub fn parse(pattern: &str) -> Result<Self> {
+        match pattern.strip_prefix(REGEX_PREFIX) {
+            Some(expr) => Self::regex(expr),
+            None => Self::glob(pattern),
+        }
+    }
+
+    /// Compile a glob pattern. Globs always match the whole name.
+    pub fn glob(pattern: &str) -> Result<Self> {
+        let mut expr = String::from("^");
+        for ch in pattern.chars() {
+            match ch {
+                '*' => expr.push_str(".*"),
+                '?' => expr.push('.'),
+                _ => expr.push_str(&regex::escape(&ch.to_string())),
+            }
+        }
+        expr.push('$');
+
+        Ok(Self {
+            source: pattern.to_string(),
+            regex: Regex::new(&expr)
+                .with_context(|| format!("Invalid glob pattern '{pattern}'"))?,
+        })
+    }
+
+    /// Compile a regular expression. Unanchored expressions match anywhere in the name.
+    pub fn regex(expr: &str) -> Result<Self> {
+        Ok(Self {
+            source: format!("{REGEX_PREFIX}{expr}"),
+            regex: Regex::new(expr)
+                .with_context(|| format!("Invalid regular expression '{expr}'"))?,
+        })
+    }
+
+    /// Check whether a server name matches this pattern.
+    pub fn matches(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+    }
+
+    /// The pattern as written by the user
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matching() {
+        let pattern = NamePattern::parse("server-*").unwrap();
+        assert!(pattern.matches("server-github"));
+        assert!(pattern.matches("server-"));
+        assert!(!pattern.matches("my-server-github"));
+
+        let pattern = NamePattern::parse("db-?").unwrap();
+        assert!(pattern.matches("db-1"));
+        assert!(!pattern.matches("db-12"));
+    }
+
+    #[test]
+    fn test_glob_escapes_regex_characters() {
+        let pattern = NamePattern::parse("@scope/server.*").unwrap();
+        assert!(pattern.matches("@scope/server.fs"));
+        assert!(!pattern.matches("@scope/serverXfs"));
+    }
+
+    #[test]
+    fn test_regex_matching() {
+        let pattern = NamePattern::parse("re:^(github|gitlab)-").unwrap();
+        assert!(pattern.matches("github-issues"));
+        assert!(pattern.matches("gitlab-ci"));
+        assert!(!pattern.matches("bitbucket"));
+        assert_eq!(pattern.as_str(), "re:^(github|gitlab)-");
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        let err = NamePattern::parse("re:(unclosed").unwrap_err();
+        assert!(err.to_string().contains("Invalid regular expression"));
+    }
+}
@@ -0,0 +1,482 @@
+//! Running external programs.
+//!
+//! Every command mcp-helper starts (`docker`, `npx`, `dotnet`, `powershell`, ...)
+//! goes through [`Process`] so that none of them can hang the CLI or flood
+//! memory:
+//!
+//! - Each run has a deadline ([`DEFAULT_TIMEOUT`] unless the caller sets one);
+//!   when it passes the process is killed
+//! - Captured stdout and stderr are capped at [`DEFAULT_MAX_OUTPUT`] bytes each;
+//!   anything beyond that is read and discarded so the child never blocks
+//! - Runs that time out or fail to start for a transient reason can be retried
+//! - Failures surface as [`McpError::IoError`] naming the command, with
+//!   [`io::ErrorKind::TimedOut`] for timeouts (see [`is_timeout`])
+//!
+//! A non-zero exit status is not an error here; callers inspect
+//! [`ProcessOutput::status`] the way they would with [`std::process::Output`].
+//! Every run is recorded in the [`crate::audit_log`].
+//!
+//! Servers themselves are the exception: `mcp run` and the secret-resolving
+//! launcher in [`crate::audit`] hand the terminal to a long-lived server, which
+//! must be neither timed out nor captured, so they use [`Command`] directly.
+
+use std::ffi::{OsStr, OsString};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::audit_log::{self, AuditEvent};
+use crate::error::McpError;
+
+/// Deadline used when the caller does not set one
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Deadline for package installs, builds and image pulls
+pub const INSTALL_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Bytes of stdout and of stderr kept by default
+pub const DEFAULT_MAX_OUTPUT: usize = 1024 * 1024;
+
+/// Pause before retrying a failed attempt
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// How often a running child is polled for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// An external program to run, with its arguments, environment and limits
+#[derive(Debug, Clone)]
+pub struct Process {
+    program: OsString,
+    args: Vec<OsString>,
+    env: Vec<(OsString, Option<OsString>)>,
+    clear_env: bool,
+    current_dir: Option<PathBuf>,
+    stdin: Option<Vec<u8>>,
+    inherit_stdin: bool,
+    timeout: Option<Duration>,
+    retries: u32,
+    max_output: usize,
+}
+
+/// Output of a process that exited before its deadline
+#[derive(Debug, Clone)]
+pub struct ProcessOutput {
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// Whether stdout or stderr went over the output cap
+    pub truncated: bool,
+}
+
+impl ProcessOutput {
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    pub fn stdout_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stdout).into_owned()
+    }
+
+    pub fn stderr_lossy(&self) -> String {
+        String::from_utf8_lossy(&self.stderr).into_owned()
+    }
+}
+
+impl Process {
+    pub fn new(program: impl AsRef<OsStr>) -> Self {
+        Self {
+            program: program.as_ref().to_os_string(),
+            args: Vec::new(),
+            env: Vec::new(),
+            clear_env: false,
+            current_dir: None,
+            stdin: None,
+            inherit_stdin: false,
+            timeout: Some(DEFAULT_TIMEOUT),
+            retries: 0,
+            max_output: DEFAULT_MAX_OUTPUT,
+        }
+    }
+
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_os_string());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args
+            .extend(args.into_iter().map(|arg| arg.as_ref().to_os_string()));
+        self
+    }
+
+    pub fn env(mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> Self {
+        self.env.push((
+            key.as_ref().to_os_string(),
+            Some(value.as_ref().to_os_string()),
+        ));
+        self
+    }
+
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        for (key, value) in vars {
+            self = self.env(key, value);
+        }
+        self
+    }
+
+    pub fn env_remove(mut self, key: impl AsRef<OsStr>) -> Self {
+        self.env.push((key.as_ref().to_os_string(), None));
+        self
+    }
+
+    /// Start from an empty environment instead of inheriting ours
+    pub fn env_clear(mut self) -> Self {
+        self.clear_env = true;
+        self.env.clear();
+        self
+    }
+
+    pub fn current_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.current_dir = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Bytes written to the child's stdin; without this stdin is closed
+    pub fn stdin(mut self, input: impl Into<Vec<u8>>) -> Self {
+        self.stdin = Some(input.into());
+        self
+    }
+
+    /// Share our stdin with the child, for commands that prompt (such as `sudo`)
+    pub fn inherit_stdin(mut self) -> Self {
+        self.inherit_stdin = true;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Let the process run for as long as it takes, for interactive and
+    /// long-running commands
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Extra attempts after a timeout or a transient failure to start
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Bytes of stdout and of stderr to keep
+    pub fn max_output(mut self, bytes: usize) -> Self {
+        self.max_output = bytes;
+        self
+    }
+
+    /// The command line, for messages
+    pub fn display(&self) -> String {
+        std::iter::once(&self.program)
+            .chain(&self.args)
+            .map(|part| part.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Run with stdout and stderr captured
+    pub fn output(&self) -> Result<ProcessOutput, McpError> {
+        let result = self.with_retries(|| self.output_once());
+        self.record(result.as_ref().map(|output| output.status));
+        result
+    }
+
+    /// Run with stdout and stderr going to the terminal
+    pub fn status(&self) -> Result<ExitStatus, McpError> {
+        let result = self.with_retries(|| self.status_once());
+        self.record(result.as_ref().copied());
+        result
+    }
+
+    /// Add the run to the audit log
+    fn record(&self, result: Result<ExitStatus, &McpError>) {
+        let args: Vec<String> = self
+            .args
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        audit_log::record(AuditEvent::Command {
+            program: self.program.to_string_lossy().to_string(),
+            args: audit_log::redact_args(&args),
+            cwd: self
+                .current_dir
+                .as_ref()
+                .map(|dir| dir.display().to_string()),
+            exit_code: result.as_ref().ok().and_then(|status| status.code()),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+
+    fn with_retries<T>(&self, mut attempt: impl FnMut() -> io::Result<T>) -> Result<T, McpError> {
+        let mut remaining = self.retries;
+        loop {
+            match attempt() {
+                Err(e) if remaining > 0 && is_retryable(&e) => {
+                    tracing::debug!("Retrying '{}' after: {}", self.display(), e);
+                    remaining -= 1;
+                    std::thread::sleep(RETRY_DELAY);
+                }
+                result => {
+                    return result.map_err(|e| {
+                        McpError::io_error(format!("run '{}'", self.display()), None, e)
+                    })
+                }
+            }
+        }
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        if self.clear_env {
+            command.env_clear();
+        }
+        for (key, value) in &self.env {
+            match value {
+                Some(value) => command.env(key, value),
+                None => command.env_remove(key),
+            };
+        }
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+        command.stdin(if self.stdin.is_some() {
+            Stdio::piped()
+        } else if self.inherit_stdin {
+            Stdio::inherit()
+        } else {
+            Stdio::null()
+        });
+        command
+    }
+
+    fn output_once(&self) -> io::Result<ProcessOutput> {
+        let mut child = self
+            .command()
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        self.feed_stdin(&mut child);
+
+        // Drain both pipes on separate threads so a chatty child cannot fill one
+        // and stall before it exits
+        let stdout = drain(child.stdout.take(), self.max_output);
+        let stderr = drain(child.stderr.take(), self.max_output);
+
+        let status = self.wait(&mut child)?;
+        let (stdout, stdout_truncated) = stdout.join().unwrap_or_default();
+        let (stderr, stderr_truncated) = stderr.join().unwrap_or_default();
+        Ok(ProcessOutput {
+            status,
+            stdout,
+            stderr,
+            truncated: stdout_truncated || stderr_truncated,
+        })
+    }
+
+    fn status_once(&self) -> io::Result<ExitStatus> {
+        let mut child = self
+            .command()
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        self.feed_stdin(&mut child);
+        self.wait(&mut child)
+    }
+
+    fn feed_stdin(&self, child: &mut Child) {
+        if let (Some(input), Some(mut pipe)) = (self.stdin.clone(), child.stdin.take()) {
+            std::thread::spawn(move || {
+                let _ = pipe.write_all(&input);
+            });
+        }
+    }
+
+    fn wait(&self, child: &mut Child) -> io::Result<ExitStatus> {
+        let Some(timeout) = self.timeout else {
+            return child.wait();
+        };
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(status) = child.try_wait()? {
+                return Ok(status);
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("did not finish within {}s", timeout.as_secs_f32()),
+                ));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Read `pipe` to the end, keeping at most `limit` bytes
+fn drain<R: Read + Send + 'static>(pipe: Option<R>, limit: usize) -> JoinHandle<(Vec<u8>, bool)> {
+    std::thread::spawn(move || {
+        let mut kept = Vec::new();
+        let mut truncated = false;
+        let Some(mut pipe) = pipe else {
+            return (kept, truncated);
+        };
+        let mut chunk = [0u8; 8192];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => {
+                    let room = limit.saturating_sub(kept.len());
+                    kept.extend_from_slice(&chunk[..read.min(room)]);
+                    truncated |= read > room;
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        (kept, truncated)
+    })
+}
+
+/// Failures worth another attempt: timeouts and programs that were briefly
+/// unavailable, not a missing program or a denied permission
+fn is_retryable(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::TimedOut
+            | io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::ResourceBusy
+            | io::ErrorKind::ExecutableFileBusy
+    )
+}
+
+/// Whether `error` came from a process that was killed at its deadline
+pub fn is_timeout(error: &McpError) -> bool {
+    matches!(error, McpError::IoError { source, .. } if source.kind() == io::ErrorKind::TimedOut)
+}
+
+/// Whether `error` means the program itself could not be found
+pub fn is_not_found(error: &McpError) -> bool {
+    matches!(error, McpError::IoError { source, .. } if source.kind() == io::ErrorKind::NotFound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let process = Process::new("docker").args(["image", "inspect", "alpine"]);
+        assert_eq!(process.display(), "docker image inspect alpine");
+    }
+
+    #[test]
+    fn test_missing_program_is_io_error() {
+        let error = Process::new("mcp-helper-no-such-program")
+            .output()
+            .unwrap_err();
+        assert!(is_not_found(&error));
+        assert!(!is_timeout(&error));
+        assert!(error.to_string().contains("mcp-helper-no-such-program"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_and_exit_status() {
+        let output = Process::new("sh")
+            .args(["-c", "echo out; echo err >&2; exit 3"])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(output.stdout_lossy().trim(), "out");
+        assert_eq!(output.stderr_lossy().trim(), "err");
+        assert!(!output.truncated);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_times_out() {
+        let started = Instant::now();
+        let error = Process::new("sleep")
+            .arg("30")
+            .timeout(Duration::from_millis(200))
+            .output()
+            .unwrap_err();
+        assert!(is_timeout(&error));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_retries_after_timeout() {
+        let started = Instant::now();
+        let error = Process::new("sleep")
+            .arg("30")
+            .timeout(Duration::from_millis(100))
+            .retries(2)
+            .status()
+            .unwrap_err();
+        assert!(is_timeout(&error));
+        // Three attempts with two pauses in between
+        assert!(started.elapsed() >= Duration::from_millis(300) + RETRY_DELAY * 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_is_capped() {
+        let output = Process::new("sh")
+            .args(["-c", "head -c 100000 /dev/zero"])
+            .max_output(1000)
+            .output()
+            .unwrap();
+        assert!(output.success());
+        assert_eq!(output.stdout.len(), 1000);
+        assert!(output.truncated);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_environment_and_stdin() {
+        let output = Process::new("/bin/sh")
+            .args(["-c", "read line; printf '%s:%s' \"$GREETING\" \"$line\""])
+            .env_clear()
+            .env("GREETING", "hello")
+            .stdin("from stdin\n")
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout_lossy(), "hello:from stdin");
+
+        let output = Process::new("sh")
+            .args(["-c", "printf '%s' \"${MCP_PROCESS_TEST-unset}\""])
+            .env("MCP_PROCESS_TEST", "set")
+            .env_remove("MCP_PROCESS_TEST")
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout_lossy(), "unset");
+    }
+}
@@ -0,0 +1,458 @@
+//! Prompts that keep working without arrow-key support.
+//!
+//! dialoguer's `Select`, `MultiSelect` and `Confirm` read single key presses,
+//! which fails in dumb terminals, some Windows consoles and when input is piped.
+//! The builders here have the same API and fall back to numbered menus and typed
+//! y/n answers read line by line, with hints in the user's language.
+//!
+//! The style is picked once per process: numbered when `TERM` is `dumb` or unset,
+//! or when stdin or stderr is not a terminal. `MCP_HELPER_PROMPTS=numbered` or
+//! `=arrows` overrides the detection.
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::sync::OnceLock;
+
+use crate::error::catalog::tr;
+
+/// Environment variable that forces a prompt style
+pub const PROMPTS_ENV_VAR: &str = "MCP_HELPER_PROMPTS";
+
+/// How prompts are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptStyle {
+    /// dialoguer's arrow-key widgets
+    Arrows,
+    /// Numbered menus and typed answers
+    Numbered,
+}
+
+impl PromptStyle {
+    /// The style for this process, detected once
+    pub fn current() -> Self {
+        static STYLE: OnceLock<PromptStyle> = OnceLock::new();
+        *STYLE.get_or_init(|| {
+            Self::detect(
+                std::env::var(PROMPTS_ENV_VAR).ok().as_deref(),
+                std::env::var("TERM").ok().as_deref(),
+                io::stdin().is_terminal() && io::stderr().is_terminal(),
+            )
+        })
+    }
+
+    /// Pick a style from the override, `TERM` and whether we're attached to a terminal
+    pub fn detect(setting: Option<&str>, term: Option<&str>, is_terminal: bool) -> Self {
+        match setting.map(|s| s.trim().to_lowercase()).as_deref() {
+            Some("numbered" | "plain" | "numbers") => return Self::Numbered,
+            Some("arrows" | "interactive") => return Self::Arrows,
+            _ => {}
+        }
+        if !is_terminal {
+            return Self::Numbered;
+        }
+        // Windows consoles don't set TERM but do handle dialoguer's widgets
+        if cfg!(windows) && term.is_none() {
+            return Self::Arrows;
+        }
+        match term {
+            None | Some("") | Some("dumb") => Self::Numbered,
+            Some(_) => Self::Arrows,
+        }
+    }
+}
+
+/// A yes/no question
+#[derive(Debug, Clone, Default)]
+pub struct Confirm {
+    prompt: String,
+    default: Option<bool>,
+}
+
+impl Confirm {
+    pub fn new() -> Self {
+        Self {
+            prompt: String::new(),
+            default: None,
+        }
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    pub fn default(mut self, default: bool) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    pub fn interact(self) -> dialoguer::Result<bool> {
+        match PromptStyle::current() {
+            PromptStyle::Arrows => {
+                let mut confirm = dialoguer::Confirm::new().with_prompt(self.prompt);
+                if let Some(default) = self.default {
+                    confirm = confirm.default(default);
+                }
+                confirm.interact()
+            }
+            PromptStyle::Numbered => Ok(ask_yes_no(
+                &mut io::stdin().lock(),
+                &mut io::stderr(),
+                &self.prompt,
+                self.default,
+            )?),
+        }
+    }
+}
+
+/// Pick one item from a list
+#[derive(Debug, Clone, Default)]
+pub struct Select {
+    prompt: String,
+    items: Vec<String>,
+    default: usize,
+}
+
+impl Select {
+    pub fn new() -> Self {
+        Self {
+            prompt: String::new(),
+            items: Vec::new(),
+            default: 0,
+        }
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    pub fn items<T: ToString>(mut self, items: &[T]) -> Self {
+        self.items = items.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    pub fn default(mut self, default: usize) -> Self {
+        self.default = default;
+        self
+    }
+
+    pub fn interact(self) -> dialoguer::Result<usize> {
+        match PromptStyle::current() {
+            PromptStyle::Arrows => dialoguer::Select::new()
+                .with_prompt(self.prompt)
+                .items(&self.items)
+                .default(self.default)
+                .interact(),
+            PromptStyle::Numbered => Ok(ask_number(
+                &mut io::stdin().lock(),
+                &mut io::stderr(),
+                &self.prompt,
+                &self.items,
+                self.default,
+            )?),
+        }
+    }
+}
+
+/// Pick any number of items from a list
+#[derive(Debug, Clone, Default)]
+pub struct MultiSelect {
+    prompt: String,
+    items: Vec<String>,
+    defaults: Vec<bool>,
+}
+
+impl MultiSelect {
+    pub fn new() -> Self {
+        Self {
+            prompt: String::new(),
+            items: Vec::new(),
+            defaults: Vec::new(),
+        }
+    }
+
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
+    pub fn items<T: ToString>(mut self, items: &[T]) -> Self {
+        self.items = items.iter().map(ToString::to_string).collect();
+        self
+    }
+
+    pub fn defaults(mut self, defaults: &[bool]) -> Self {
+        self.defaults = defaults.to_vec();
+        self
+    }
+
+    pub fn interact(self) -> dialoguer::Result<Vec<usize>> {
+        match PromptStyle::current() {
+            PromptStyle::Arrows => {
+                let mut select = dialoguer::MultiSelect::new().items(&self.items);
+                if !self.prompt.is_empty() {
+                    select = select.with_prompt(self.prompt);
+                }
+                select.defaults(&self.defaults).interact()
+            }
+            PromptStyle::Numbered => Ok(ask_numbers(
+                &mut io::stdin().lock(),
+                &mut io::stderr(),
+                &self.prompt,
+                &self.items,
+                &self.defaults,
+            )?),
+        }
+    }
+}
+
+/// Read one answer line; end of input is an error since nobody is there to answer
+fn read_answer(input: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "no answer on standard input",
+        ));
+    }
+    Ok(line.trim().to_string())
+}
+
+/// Ask a y/n question as text; accepts English, Spanish, German and Japanese answers
+pub fn ask_yes_no(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    prompt: &str,
+    default: Option<bool>,
+) -> io::Result<bool> {
+    let choices = match default {
+        Some(true) => "[Y/n]",
+        Some(false) => "[y/N]",
+        None => "[y/n]",
+    };
+    loop {
+        write!(output, "{prompt} {choices}: ")?;
+        output.flush()?;
+        let answer = read_answer(input)?.to_lowercase();
+        match answer.as_str() {
+            "" if default.is_some() => return Ok(default.unwrap_or_default()),
+            "y" | "yes" | "s" | "si" | "sí" | "j" | "ja" | "はい" => return Ok(true),
+            "n" | "no" | "nein" | "いいえ" => return Ok(false),
+            _ => writeln!(output, "{}", tr("Please answer y or n"))?,
+        }
+    }
+}
+
+fn write_menu(
+    output: &mut impl Write,
+    prompt: &str,
+    items: &[String],
+    marks: Option<&[bool]>,
+) -> io::Result<()> {
+    if !prompt.is_empty() {
+        writeln!(output, "{prompt}")?;
+    }
+    for (index, item) in items.iter().enumerate() {
+        match marks {
+            Some(marks) => {
+                let mark = if marks.get(index).copied().unwrap_or(false) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                writeln!(output, "  {}) {mark} {item}", index + 1)?;
+            }
+            None => writeln!(output, "  {}) {item}", index + 1)?,
+        }
+    }
+    Ok(())
+}
+
+/// Show a numbered menu and read the number of one item
+pub fn ask_number(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    prompt: &str,
+    items: &[String],
+    default: usize,
+) -> io::Result<usize> {
+    write_menu(output, prompt, items, None)?;
+    loop {
+        write!(output, "{} [{}]: ", tr("Enter a number"), default + 1)?;
+        output.flush()?;
+        let answer = read_answer(input)?;
+        if answer.is_empty() && default < items.len() {
+            return Ok(default);
+        }
+        match answer.parse::<usize>() {
+            Ok(number) if (1..=items.len()).contains(&number) => return Ok(number - 1),
+            _ => writeln!(output, "{}", tr("Not a valid choice"))?,
+        }
+    }
+}
+
+/// Show a numbered menu with the defaults marked and read the chosen numbers
+pub fn ask_numbers(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    prompt: &str,
+    items: &[String],
+    defaults: &[bool],
+) -> io::Result<Vec<usize>> {
+    write_menu(output, prompt, items, Some(defaults))?;
+    loop {
+        write!(
+            output,
+            "{}: ",
+            tr("Enter numbers separated by commas, 'all' or 'none'; blank keeps the marked ones")
+        )?;
+        output.flush()?;
+        match parse_numbers(&read_answer(input)?, items.len(), defaults) {
+            Some(selection) => return Ok(selection),
+            None => writeln!(output, "{}", tr("Not a valid choice"))?,
+        }
+    }
+}
+
+/// Zero-based indices for an answer like `1, 3` or `2 4`
+fn parse_numbers(answer: &str, count: usize, defaults: &[bool]) -> Option<Vec<usize>> {
+    match answer.to_lowercase().as_str() {
+        "" => {
+            return Some(
+                (0..count)
+                    .filter(|&i| defaults.get(i).copied().unwrap_or(false))
+                    .collect(),
+            )
+        }
+        "all" | "*" => return Some((0..count).collect()),
+        "none" => return Some(Vec::new()),
+        _ => {}
+    }
+
+    let mut selection = Vec::new();
+    for part in answer.split([',', ' ']).filter(|part| !part.is_empty()) {
+        let number: usize = part.trim().parse().ok()?;
+        if !(1..=count).contains(&number) {
+            return None;
+        }
+        if !selection.contains(&(number - 1)) {
+            selection.push(number - 1);
+        }
+    }
+    selection.sort_unstable();
+    Some(selection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn items() -> Vec<String> {
+        vec![
+            "Claude Desktop".to_string(),
+            "VS Code".to_string(),
+            "Cursor".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_detect_style() {
+        assert_eq!(
+            PromptStyle::detect(None, Some("xterm-256color"), true),
+            PromptStyle::Arrows
+        );
+        assert_eq!(
+            PromptStyle::detect(None, Some("dumb"), true),
+            PromptStyle::Numbered
+        );
+        assert_eq!(
+            PromptStyle::detect(None, Some("xterm"), false),
+            PromptStyle::Numbered
+        );
+        assert_eq!(
+            PromptStyle::detect(Some("numbered"), Some("xterm"), true),
+            PromptStyle::Numbered
+        );
+        assert_eq!(
+            PromptStyle::detect(Some("arrows"), Some("dumb"), false),
+            PromptStyle::Arrows
+        );
+    }
+
+    #[test]
+    fn test_ask_number_retries_until_valid() {
+        let mut output = Vec::new();
+        let choice = ask_number(
+            &mut Cursor::new("7\nx\n2\n"),
+            &mut output,
+            "Pick",
+            &items(),
+            0,
+        )
+        .unwrap();
+        assert_eq!(choice, 1);
+        let shown = String::from_utf8(output).unwrap();
+        assert!(shown.contains("  2) VS Code"));
+        assert_eq!(shown.matches("Not a valid choice").count(), 2);
+
+        let choice =
+            ask_number(&mut Cursor::new("\n"), &mut Vec::new(), "Pick", &items(), 2).unwrap();
+        assert_eq!(choice, 2);
+    }
+
+    #[test]
+    fn test_ask_numbers() {
+        let defaults = [true, false, true];
+        let mut output = Vec::new();
+        let chosen = ask_numbers(
+            &mut Cursor::new("3, 1 3\n"),
+            &mut output,
+            "Pick",
+            &items(),
+            &defaults,
+        )
+        .unwrap();
+        assert_eq!(chosen, vec![0, 2]);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("  2) [ ] VS Code"));
+
+        let blank = ask_numbers(
+            &mut Cursor::new("\n"),
+            &mut Vec::new(),
+            "",
+            &items(),
+            &defaults,
+        );
+        assert_eq!(blank.unwrap(), vec![0, 2]);
+        let none = ask_numbers(
+            &mut Cursor::new("none\n"),
+            &mut Vec::new(),
+            "",
+            &items(),
+            &defaults,
+        );
+        assert_eq!(none.unwrap(), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_ask_yes_no() {
+        let ask = |answers: &str, default| {
+            ask_yes_no(
+                &mut Cursor::new(answers.to_string()),
+                &mut Vec::new(),
+                "Go?",
+                default,
+            )
+        };
+        assert!(ask("\n", Some(true)).unwrap());
+        assert!(!ask("\n", Some(false)).unwrap());
+        assert!(ask("maybe\nja\n", None).unwrap());
+        assert!(!ask("No\n", Some(true)).unwrap());
+        assert_eq!(
+            ask("", Some(true)).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+}
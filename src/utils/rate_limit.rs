@@ -0,0 +1,347 @@
+//! Rate limits and backoff for the npm registry and the GitHub API.
+//!
+//! Unauthenticated GitHub API calls are limited to 60 an hour per address, which
+//! a few binary installs and release-note lookups use up quickly. The
+//! `x-ratelimit-*` headers of every response are recorded in `rate_limits.json`
+//! in the cache directory, so once a limit is exhausted later calls, from this
+//! or any other mcp-helper process, fail straight away with the time it resets
+//! instead of spending another request. `429` and `5xx` responses are retried
+//! with exponential backoff when the wait is short; longer waits become a
+//! [`RateLimited`] error saying when to retry.
+
+use anyhow::{Context, Result};
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::cache::CacheManager;
+use crate::server::github;
+use crate::utils::clock::{Clock, SystemClock};
+use crate::utils::state_file::{read_state, write_state};
+
+/// Retries of a throttled or failed request before giving up
+const MAX_RETRIES: u32 = 3;
+/// Longest wait that is sat out rather than reported
+const MAX_WAIT: Duration = Duration::from_secs(10);
+/// First backoff delay, doubled on every retry
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+
+/// An API whose limits are tracked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Api {
+    GitHub,
+    NpmRegistry,
+}
+
+impl Api {
+    fn key(self) -> &'static str {
+        match self {
+            Self::GitHub => "github",
+            Self::NpmRegistry => "npm",
+        }
+    }
+}
+
+impl fmt::Display for Api {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GitHub => write!(f, "GitHub API"),
+            Self::NpmRegistry => write!(f, "npm registry"),
+        }
+    }
+}
+
+/// A request refused until the API's limit resets
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimited {
+    pub api: Api,
+    pub retry_after: Duration,
+    /// Whether the request carried a token; unauthenticated GitHub limits are far lower
+    pub authenticated: bool,
+}
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} rate limit reached; retry after {}",
+            self.api,
+            format_wait(self.retry_after)
+        )?;
+        if self.api == Api::GitHub && !self.authenticated {
+            write!(f, " (set GITHUB_TOKEN to raise the limit)")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// What an API last reported about its limit
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct LimitWindow {
+    remaining: u64,
+    /// Unix time the window resets
+    reset_at: u64,
+}
+
+/// Limit windows of every tracked API
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateLimits {
+    #[serde(default)]
+    windows: HashMap<String, LimitWindow>,
+}
+
+impl RateLimits {
+    const FILE_NAME: &'static str = "rate_limits.json";
+
+    fn path() -> Result<PathBuf> {
+        Ok(CacheManager::default_cache_dir()?.join(Self::FILE_NAME))
+    }
+
+    fn load() -> Self {
+        Self::path()
+            .and_then(|path| read_state(&path, Self::default))
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let saved = Self::path().and_then(|path| write_state(&path, self));
+        if let Err(e) = saved {
+            tracing::debug!("Failed to save rate limits: {e}");
+        }
+    }
+
+    /// How long `api` refuses requests, if its limit is exhausted
+    pub fn blocked(&self, api: Api, now: u64) -> Option<Duration> {
+        self.windows
+            .get(api.key())
+            .filter(|window| window.remaining == 0 && window.reset_at > now)
+            .map(|window| Duration::from_secs(window.reset_at - now))
+    }
+
+    /// Remember the limit `headers` report; `retry_after` also blocks until then
+    pub fn record(
+        &mut self,
+        api: Api,
+        headers: &HeaderMap,
+        retry_after: Option<Duration>,
+        now: u64,
+    ) {
+        let remaining = header_u64(headers, "x-ratelimit-remaining");
+        let reset_at = header_u64(headers, "x-ratelimit-reset");
+        let window = match (remaining, reset_at, retry_after) {
+            (_, _, Some(wait)) => LimitWindow {
+                remaining: 0,
+                reset_at: now + wait.as_secs().max(1),
+            },
+            (Some(remaining), Some(reset_at), None) => LimitWindow {
+                remaining,
+                reset_at,
+            },
+            _ => return,
+        };
+        self.windows.insert(api.key().to_string(), window);
+    }
+}
+
+/// Send the request `build` makes, retrying short waits with backoff and
+/// recording the limits each response reports
+pub fn send(api: Api, build: impl Fn() -> RequestBuilder) -> Result<Response> {
+    let clock = SystemClock;
+    let authenticated = api == Api::GitHub && github::token().is_some();
+    let mut limits = RateLimits::load();
+    if let Some(retry_after) = limits.blocked(api, clock.unix_secs()) {
+        return Err(RateLimited {
+            api,
+            retry_after,
+            authenticated,
+        }
+        .into());
+    }
+
+    let mut attempt = 0;
+    loop {
+        let response = build()
+            .send()
+            .with_context(|| format!("Failed to reach the {api}"))?;
+        let now = clock.unix_secs();
+        let status = response.status();
+        let limited = is_rate_limited(status, response.headers());
+        let wait = wait_for(status, response.headers(), now, attempt);
+
+        limits.record(api, response.headers(), wait.filter(|_| limited), now);
+        limits.save();
+
+        match wait {
+            Some(wait) if wait <= MAX_WAIT && attempt < MAX_RETRIES => {
+                tracing::debug!("{api} returned {status}; retrying in {wait:?}");
+                std::thread::sleep(wait);
+                attempt += 1;
+            }
+            Some(retry_after) if limited => {
+                return Err(RateLimited {
+                    api,
+                    retry_after,
+                    authenticated,
+                }
+                .into());
+            }
+            _ => return Ok(response),
+        }
+    }
+}
+
+/// Whether `status` and `headers` say the request was refused for exceeding a limit
+fn is_rate_limited(status: StatusCode, headers: &HeaderMap) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN
+            && (header_u64(headers, "x-ratelimit-remaining") == Some(0)
+                || headers.contains_key(RETRY_AFTER)))
+}
+
+/// How long to wait before retrying a response, or `None` if it shouldn't be
+/// retried. Rate limits say when they reset; server errors back off exponentially
+fn wait_for(status: StatusCode, headers: &HeaderMap, now: u64, attempt: u32) -> Option<Duration> {
+    let backoff = BASE_BACKOFF * 2u32.pow(attempt);
+    if is_rate_limited(status, headers) {
+        let wait = header_u64(headers, RETRY_AFTER.as_str())
+            .or_else(|| {
+                header_u64(headers, "x-ratelimit-reset").map(|reset| reset.saturating_sub(now))
+            })
+            .map(Duration::from_secs)
+            .unwrap_or(backoff);
+        return Some(wait);
+    }
+    status.is_server_error().then_some(backoff)
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// `wait` rounded for people: "45s", "12m 5s", "1h 3m"
+fn format_wait(wait: Duration) -> String {
+    let secs = wait.as_secs().max(1);
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, 0) => format!("{m}m"),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, m, _) => format!("{h}h {m}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_wait_for_rate_limits_and_server_errors() {
+        let exhausted = headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1000600"),
+        ]);
+        assert_eq!(
+            wait_for(StatusCode::FORBIDDEN, &exhausted, 1_000_000, 0),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            wait_for(
+                StatusCode::TOO_MANY_REQUESTS,
+                &headers(&[("retry-after", "3")]),
+                0,
+                0
+            ),
+            Some(Duration::from_secs(3))
+        );
+        assert_eq!(
+            wait_for(StatusCode::BAD_GATEWAY, &HeaderMap::new(), 0, 2),
+            Some(Duration::from_secs(4))
+        );
+        // A 403 without limit headers is a permission problem, not a limit
+        assert_eq!(
+            wait_for(StatusCode::FORBIDDEN, &HeaderMap::new(), 0, 0),
+            None
+        );
+        assert_eq!(wait_for(StatusCode::OK, &exhausted, 0, 0), None);
+    }
+
+    #[test]
+    fn test_limits_block_until_reset() {
+        let mut limits = RateLimits::default();
+        limits.record(
+            Api::GitHub,
+            &headers(&[
+                ("x-ratelimit-remaining", "0"),
+                ("x-ratelimit-reset", "1000"),
+            ]),
+            None,
+            900,
+        );
+        assert_eq!(
+            limits.blocked(Api::GitHub, 900),
+            Some(Duration::from_secs(100))
+        );
+        assert_eq!(limits.blocked(Api::GitHub, 1000), None);
+        assert_eq!(limits.blocked(Api::NpmRegistry, 900), None);
+
+        limits.record(
+            Api::GitHub,
+            &headers(&[
+                ("x-ratelimit-remaining", "59"),
+                ("x-ratelimit-reset", "4600"),
+            ]),
+            None,
+            1000,
+        );
+        assert_eq!(limits.blocked(Api::GitHub, 1000), None);
+
+        limits.record(
+            Api::NpmRegistry,
+            &HeaderMap::new(),
+            Some(Duration::from_secs(30)),
+            1000,
+        );
+        assert_eq!(
+            limits.blocked(Api::NpmRegistry, 1010),
+            Some(Duration::from_secs(20))
+        );
+    }
+
+    #[test]
+    fn test_rate_limited_message() {
+        let error = RateLimited {
+            api: Api::GitHub,
+            retry_after: Duration::from_secs(725),
+            authenticated: false,
+        };
+        assert_eq!(
+            error.to_string(),
+            "GitHub API rate limit reached; retry after 12m 5s (set GITHUB_TOKEN to raise the limit)"
+        );
+
+        let error = RateLimited {
+            authenticated: true,
+            retry_after: Duration::from_secs(3900),
+            ..error
+        };
+        assert_eq!(
+            error.to_string(),
+            "GitHub API rate limit reached; retry after 1h 5m"
+        );
+    }
+}
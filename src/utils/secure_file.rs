@@ -2,9 +2,13 @@
 
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
+use super::network_fs::{StorageKind, WriteStrategy};
+use crate::audit_log;
+
 /// Write data to a file with secure permissions (0600 on Unix).
 ///
 /// This function ensures that configuration files are written with
@@ -21,7 +25,28 @@ use tempfile::NamedTempFile;
 /// secure_file::write_secure("/path/to/config.json", b"{ \"key\": \"value\" }")?;
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
+/// On network filesystems and in sync-client folders the file is replaced with
+/// [`write_verified`] instead of a rename (see [`super::network_fs`]). Changes are
+/// recorded in the [`crate::audit_log`].
+#[tracing::instrument(level = "debug", skip(contents), fields(path = %path.display(), bytes = contents.len()))]
 pub fn write_secure(path: &Path, contents: &[u8]) -> Result<()> {
+    let before = audit_log::file_hash(path);
+    match WriteStrategy::for_path(path) {
+        WriteStrategy::Atomic => write_atomic(path, contents)?,
+        WriteStrategy::BackupThenWrite => {
+            tracing::debug!(
+                "{} is on a {}, writing in place",
+                path.display(),
+                StorageKind::detect(path)
+            );
+            write_verified(path, contents)?
+        }
+    }
+    audit_log::record_file_write(path, before);
+    Ok(())
+}
+
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
     // Create a temporary file in the same directory
     let parent = path.parent().unwrap_or_else(|| Path::new("."));
     let temp_file = NamedTempFile::new_in(parent).context("Failed to create temporary file")?;
@@ -46,6 +71,86 @@ pub fn write_secure(path: &Path, contents: &[u8]) -> Result<()> {
     Ok(())
 }
 
+/// Replace `path` in place with secure permissions, keeping a copy of the old
+/// contents until the new ones have been read back intact.
+///
+/// Used where renaming a temporary file over the target is not reliable: the
+/// old file is restored if writing or verifying fails.
+pub fn write_verified(path: &Path, contents: &[u8]) -> Result<()> {
+    let backup = backup_path(path);
+    let had_original = path.exists();
+    if had_original {
+        fs::copy(path, &backup).with_context(|| format!("Failed to back up {}", path.display()))?;
+    }
+
+    match write_in_place(path, contents).and_then(|()| verify_contents(path, contents)) {
+        Ok(()) => {
+            if had_original {
+                let _ = fs::remove_file(&backup);
+            }
+            Ok(())
+        }
+        Err(e) if had_original => match fs::copy(&backup, path) {
+            Ok(_) => {
+                let _ = fs::remove_file(&backup);
+                Err(e.context(format!("Restored the previous {}", path.display())))
+            }
+            Err(_) => Err(e.context(format!(
+                "The previous contents are saved in {}",
+                backup.display()
+            ))),
+        },
+        Err(e) => {
+            let _ = fs::remove_file(path);
+            Err(e)
+        }
+    }
+}
+
+/// `<file>.mcp-backup` next to `path`
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".mcp-backup");
+    path.with_file_name(name)
+}
+
+fn write_in_place(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to open {} for writing", path.display()))?;
+    file.write_all(contents)
+        .and_then(|()| file.sync_all())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+    // The mode above only applies to new files
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .context("Failed to set file permissions")?;
+    }
+    Ok(())
+}
+
+fn verify_contents(path: &Path, contents: &[u8]) -> Result<()> {
+    let written =
+        fs::read(path).with_context(|| format!("Failed to read back {}", path.display()))?;
+    if written != contents {
+        anyhow::bail!(
+            "{} did not contain what was written; a sync client may have replaced it",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
 /// Write a JSON string to a file with secure permissions.
 ///
 /// This is a convenience wrapper around `write_secure` for JSON data.
@@ -95,6 +200,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_write_verified_replaces_in_place() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("config.json");
+
+        write_verified(&file_path, b"{}").unwrap();
+        write_verified(&file_path, br#"{"mcpServers": {}}"#).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            r#"{"mcpServers": {}}"#
+        );
+        assert!(!backup_path(&file_path).exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&file_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_write_verified_keeps_original_on_failure() {
+        let temp_dir = TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("config.json");
+        fs::write(&file_path, "original").unwrap();
+
+        // A directory in place of the backup makes the copy fail before anything is written
+        fs::create_dir(backup_path(&file_path)).unwrap();
+        assert!(write_verified(&file_path, b"new").is_err());
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "original");
+    }
+
     #[test]
     fn test_write_json_secure() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,243 @@
+//! Encryption at rest for mcp-helper's own state files.
+//!
+//! With `"security": { "encrypt_state": true }` in preferences, state files are
+//! written with their data sealed under a 256-bit key kept in the OS keychain
+//! (see [`crate::remote::keychain`]) instead of in plaintext:
+//!
+//! ```json
+//! { "format_version": 2, "key_id": "1f3a...", "nonce": "...", "ciphertext": "...", "mac": "..." }
+//! ```
+//!
+//! The data is sealed with XChaCha20-Poly1305 under a random 192-bit nonce, with
+//! the key id as associated data; `mac` is the Poly1305 tag.
+//! `mcp state encrypt` creates the key and re-writes existing state.
+
+use anyhow::{Context, Result};
+use chacha20poly1305::aead::{AeadInPlace, KeyInit};
+use chacha20poly1305::{Tag, XChaCha20Poly1305, XNonce};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::OnceLock;
+
+use crate::remote::keychain;
+
+/// Keychain entry holding the hex-encoded state key
+pub const KEY_NAME: &str = "state-encryption-key";
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+
+/// Key the state files are sealed with
+#[derive(Clone, PartialEq, Eq)]
+pub struct StateKey([u8; KEY_LEN]);
+
+impl std::fmt::Debug for StateKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "StateKey({})", self.id())
+    }
+}
+
+impl StateKey {
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// A new key from the operating system's random source
+    pub fn generate() -> Result<Self> {
+        let mut key = [0u8; KEY_LEN];
+        fill_random(&mut key)?;
+        Ok(Self(key))
+    }
+
+    /// A key stretched from `secret` and `salt` with PBKDF2-HMAC-SHA256
+    pub fn derive_from(secret: &[u8], salt: &[u8], rounds: u32) -> Self {
+        Self(pbkdf2::pbkdf2_hmac_array::<Sha256, KEY_LEN>(
+            secret, salt, rounds,
+        ))
+    }
+
+    pub fn from_hex(encoded: &str) -> Result<Self> {
+        let bytes = hex::decode(encoded.trim()).context("State key is not valid hex")?;
+        let key: [u8; KEY_LEN] = bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("State key must be {KEY_LEN} bytes"))?;
+        Ok(Self(key))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Short public fingerprint recorded in each sealed file
+    pub fn id(&self) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.0)
+            .expect("HMAC accepts keys of any length");
+        mac.update(b"mcp-helper state key id");
+        hex::encode(&mac.finalize().into_bytes()[..8])
+    }
+
+    fn cipher(&self) -> XChaCha20Poly1305 {
+        XChaCha20Poly1305::new(&self.0.into())
+    }
+}
+
+/// Sealed contents of an encrypted state file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sealed {
+    pub key_id: String,
+    pub nonce: String,
+    pub ciphertext: String,
+    pub mac: String,
+}
+
+/// Why sealed data could not be opened
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpenError {
+    /// Sealed with a different key
+    WrongKey(String),
+    /// Fields are not valid hex
+    Malformed,
+    /// Authentication failed: the file was modified
+    Tampered,
+}
+
+/// Encrypt and authenticate `plaintext` under `key`
+pub fn seal(key: &StateKey, plaintext: &[u8]) -> Result<Sealed> {
+    let mut nonce = [0u8; NONCE_LEN];
+    fill_random(&mut nonce)?;
+    let key_id = key.id();
+    let mut ciphertext = plaintext.to_vec();
+    let tag = key
+        .cipher()
+        .encrypt_in_place_detached(
+            XNonce::from_slice(&nonce),
+            key_id.as_bytes(),
+            &mut ciphertext,
+        )
+        .map_err(|_| anyhow::anyhow!("State data is too large to encrypt"))?;
+    Ok(Sealed {
+        key_id,
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+        mac: hex::encode(tag),
+    })
+}
+
+/// Verify and decrypt `sealed` with `key`
+pub fn open(key: &StateKey, sealed: &Sealed) -> Result<Vec<u8>, OpenError> {
+    if sealed.key_id != key.id() {
+        return Err(OpenError::WrongKey(sealed.key_id.clone()));
+    }
+    let nonce = hex::decode(&sealed.nonce).map_err(|_| OpenError::Malformed)?;
+    let mut data = hex::decode(&sealed.ciphertext).map_err(|_| OpenError::Malformed)?;
+    let tag = hex::decode(&sealed.mac).map_err(|_| OpenError::Malformed)?;
+    if nonce.len() != NONCE_LEN {
+        return Err(OpenError::Malformed);
+    }
+    if tag.len() != Tag::default().len() {
+        return Err(OpenError::Tampered);
+    }
+
+    key.cipher()
+        .decrypt_in_place_detached(
+            XNonce::from_slice(&nonce),
+            sealed.key_id.as_bytes(),
+            &mut data,
+            Tag::from_slice(&tag),
+        )
+        .map_err(|_| OpenError::Tampered)?;
+    Ok(data)
+}
+
+static CACHED_KEY: OnceLock<StateKey> = OnceLock::new();
+
+/// The state key from the keychain, looked up once per process
+pub fn load_key() -> Result<StateKey> {
+    if let Some(key) = CACHED_KEY.get() {
+        return Ok(key.clone());
+    }
+    let encoded = keychain::lookup(KEY_NAME)
+        .context("State encryption key is not available; run 'mcp state encrypt' to create it")?;
+    let key = StateKey::from_hex(&encoded)?;
+    Ok(CACHED_KEY.get_or_init(|| key).clone())
+}
+
+/// The existing state key, or a new one stored in the keychain
+pub fn load_or_create_key() -> Result<StateKey> {
+    if let Ok(key) = load_key() {
+        return Ok(key);
+    }
+    let key = StateKey::generate()?;
+    keychain::store(KEY_NAME, &key.to_hex())
+        .context("Failed to store the state encryption key in the keychain")?;
+    Ok(CACHED_KEY.get_or_init(|| key).clone())
+}
+
+/// Fill `bytes` from the operating system's random source
+pub(crate) fn fill_random(bytes: &mut [u8]) -> Result<()> {
+    getrandom::getrandom(bytes).map_err(|e| anyhow::anyhow!("Failed to read random bytes: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> StateKey {
+        StateKey::from_bytes([byte; KEY_LEN])
+    }
+
+    #[test]
+    fn test_seal_and_open() {
+        let plaintext = br#"{"token":"registry-secret","paths":["/home/me/projects"]}"#;
+        let sealed = seal(&key(7), plaintext).unwrap();
+
+        assert!(!sealed.ciphertext.contains(&hex::encode("registry-secret")));
+        assert_eq!(open(&key(7), &sealed).unwrap(), plaintext);
+
+        // Every write uses a fresh nonce
+        let again = seal(&key(7), plaintext).unwrap();
+        assert_ne!(again.nonce, sealed.nonce);
+        assert_ne!(again.ciphertext, sealed.ciphertext);
+    }
+
+    #[test]
+    fn test_open_rejects_wrong_key_and_tampering() {
+        let sealed = seal(&key(1), b"state").unwrap();
+        assert_eq!(
+            open(&key(2), &sealed),
+            Err(OpenError::WrongKey(key(1).id()))
+        );
+
+        let mut tampered = sealed.clone();
+        let mut bytes = hex::decode(&tampered.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        tampered.ciphertext = hex::encode(bytes);
+        assert_eq!(open(&key(1), &tampered), Err(OpenError::Tampered));
+
+        let mut malformed = sealed;
+        malformed.nonce = "zz".to_string();
+        assert_eq!(open(&key(1), &malformed), Err(OpenError::Malformed));
+    }
+
+    #[test]
+    fn test_derive_from_matches_pbkdf2_vectors() {
+        assert_eq!(
+            StateKey::derive_from(b"password", b"salt", 1).to_hex(),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"
+        );
+        assert_eq!(
+            StateKey::derive_from(b"password", b"salt", 2).to_hex(),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"
+        );
+    }
+
+    #[test]
+    fn test_key_hex_roundtrip() {
+        let key = StateKey::generate().unwrap();
+        assert_eq!(StateKey::from_hex(&key.to_hex()).unwrap(), key);
+        assert_eq!(key.id().len(), 16);
+        assert!(StateKey::from_hex("abcd").is_err());
+        assert!(!format!("{key:?}").contains(&key.to_hex()));
+    }
+}
@@ -0,0 +1,448 @@
+//! Integrity checks for mcp-helper's own state files.
+//!
+//! Machine-written state (caches, meta-server membership, hook approvals) is saved in
+//! a versioned envelope with a SHA-256 checksum of its contents:
+//!
+//! ```json
+//! { "format_version": 1, "checksum": "9f86d08...", "data": { ... } }
+//! ```
+//!
+//! Files written before the envelope existed are still read and are upgraded on the
+//! next save. A file that fails to parse, fails its checksum or comes from a newer
+//! format version is moved aside to `<name>.corrupt-<timestamp>`, the caller falls
+//! back to defaults, and the incident is recorded for `mcp doctor` to report.
+//!
+//! When `security.encrypt_state` is set in preferences, files are sealed with a
+//! keychain-held key instead (format version 2, see [`super::state_crypto`]). An
+//! encrypted file that cannot be opened because the key is missing is left in place
+//! and reported as an error rather than quarantined.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::filesystem::{FileSystem, RealFileSystem};
+use super::paths;
+use super::state_crypto::{self, OpenError, Sealed, StateKey};
+use crate::config::preferences::Preferences;
+
+/// Current envelope format
+pub const STATE_FORMAT_VERSION: u32 = 1;
+
+/// Format of envelopes whose data is encrypted
+pub const ENCRYPTED_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    format_version: u32,
+    checksum: String,
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedEnvelope {
+    format_version: u32,
+    #[serde(flatten)]
+    sealed: Sealed,
+}
+
+/// Why a state file was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum Corruption {
+    Unparseable(String),
+    ChecksumMismatch,
+    UnsupportedVersion(u32),
+    /// Encrypted and the key to open it is not available
+    Locked(String),
+}
+
+impl std::fmt::Display for Corruption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unparseable(error) => write!(f, "could not be parsed ({error})"),
+            Self::ChecksumMismatch => write!(f, "failed its checksum"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "uses format version {version}, newer than this mcp-helper supports ({ENCRYPTED_FORMAT_VERSION})"
+            ),
+            Self::Locked(reason) => write!(f, "is encrypted and cannot be opened ({reason})"),
+        }
+    }
+}
+
+fn checksum(data: &serde_json::Value) -> String {
+    let bytes = serde_json::to_vec(data).unwrap_or_default();
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Whether preferences ask for state files to be encrypted
+pub fn encryption_enabled() -> bool {
+    Preferences::load()
+        .map(|prefs| prefs.security.encrypt_state)
+        .unwrap_or(false)
+}
+
+/// Save `value` to `path` inside a checksummed envelope, encrypted if enabled.
+pub fn write_state<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    write_state_in(&RealFileSystem, path, value)
+}
+
+/// [`write_state`] through `fs`
+pub fn write_state_in<T: Serialize>(fs: &dyn FileSystem, path: &Path, value: &T) -> Result<()> {
+    let key = if encryption_enabled() {
+        Some(state_crypto::load_key()?)
+    } else {
+        None
+    };
+    write_state_with(fs, path, value, key.as_ref())
+}
+
+/// Save `value` to `path`, sealed with `key` when given and in plaintext otherwise
+pub fn write_state_with<T: Serialize>(
+    fs: &dyn FileSystem,
+    path: &Path,
+    value: &T,
+    key: Option<&StateKey>,
+) -> Result<()> {
+    let data = serde_json::to_value(value).context("Failed to serialize state")?;
+    let json = match key {
+        Some(key) => {
+            let plaintext = serde_json::to_vec(&data).context("Failed to serialize state")?;
+            let envelope = EncryptedEnvelope {
+                format_version: ENCRYPTED_FORMAT_VERSION,
+                sealed: state_crypto::seal(key, &plaintext)?,
+            };
+            serde_json::to_string_pretty(&envelope)
+        }
+        None => {
+            let envelope = Envelope {
+                format_version: STATE_FORMAT_VERSION,
+                checksum: checksum(&data),
+                data,
+            };
+            serde_json::to_string_pretty(&envelope)
+        }
+    }
+    .context("Failed to serialize state")?;
+
+    if let Some(parent) = path.parent() {
+        fs.create_dir_all(parent)?;
+    }
+    fs.write_secure(path, &json)
+}
+
+/// Whether `content` is an encrypted state file
+pub fn is_encrypted(content: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(content)
+        .map(|value| value.get("ciphertext").is_some())
+        .unwrap_or(false)
+}
+
+/// Decode the contents of a state file, enveloped, encrypted or legacy.
+pub fn decode_state<T: DeserializeOwned>(content: &str) -> Result<T, Corruption> {
+    decode_state_with(content, || {
+        state_crypto::load_key().map_err(|e| e.to_string())
+    })
+}
+
+/// [`decode_state`] taking the key for encrypted files from `key`
+pub fn decode_state_with<T: DeserializeOwned>(
+    content: &str,
+    key: impl FnOnce() -> Result<StateKey, String>,
+) -> Result<T, Corruption> {
+    let value: serde_json::Value = super::json_validator::deserialize_json_safe(content)
+        .map_err(|e| Corruption::Unparseable(e.to_string()))?;
+
+    let data = if value.get("format_version").is_some() && value.get("ciphertext").is_some() {
+        let envelope: EncryptedEnvelope =
+            serde_json::from_value(value).map_err(|e| Corruption::Unparseable(e.to_string()))?;
+        if envelope.format_version > ENCRYPTED_FORMAT_VERSION {
+            return Err(Corruption::UnsupportedVersion(envelope.format_version));
+        }
+        let key = key().map_err(Corruption::Locked)?;
+        let plaintext = state_crypto::open(&key, &envelope.sealed).map_err(|e| match e {
+            OpenError::WrongKey(id) => {
+                Corruption::Locked(format!("sealed with key {id}, not the keychain key"))
+            }
+            OpenError::Malformed => Corruption::Unparseable("invalid encrypted data".to_string()),
+            OpenError::Tampered => Corruption::ChecksumMismatch,
+        })?;
+        serde_json::from_slice(&plaintext).map_err(|e| Corruption::Unparseable(e.to_string()))?
+    } else if value.get("format_version").is_some() && value.get("data").is_some() {
+        let envelope: Envelope =
+            serde_json::from_value(value).map_err(|e| Corruption::Unparseable(e.to_string()))?;
+        if envelope.format_version > STATE_FORMAT_VERSION {
+            return Err(Corruption::UnsupportedVersion(envelope.format_version));
+        }
+        if checksum(&envelope.data) != envelope.checksum {
+            return Err(Corruption::ChecksumMismatch);
+        }
+        envelope.data
+    } else {
+        value
+    };
+
+    serde_json::from_value(data).map_err(|e| Corruption::Unparseable(e.to_string()))
+}
+
+/// Check a state file without loading it into a type
+pub fn verify_state_file(path: &Path) -> Result<(), Corruption> {
+    match fs::read_to_string(path) {
+        Ok(content) => decode_state::<serde_json::Value>(&content).map(|_| ()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(Corruption::Unparseable(e.to_string())),
+    }
+}
+
+/// Load a state file, repairing it if it is corrupt.
+///
+/// A missing file yields `default()`. A corrupt one is quarantined, recorded as an
+/// incident and also yields `default()`.
+pub fn read_state<T: DeserializeOwned>(path: &Path, default: impl FnOnce() -> T) -> Result<T> {
+    read_state_in(&RealFileSystem, path, default)
+}
+
+/// [`read_state`] through `fs`
+pub fn read_state_in<T: DeserializeOwned>(
+    fs: &dyn FileSystem,
+    path: &Path,
+    default: impl FnOnce() -> T,
+) -> Result<T> {
+    if !fs.exists(path) {
+        return Ok(default());
+    }
+
+    let content = fs
+        .read_to_string(path)
+        .with_context(|| format!("Failed to read state file {}", path.display()))?;
+
+    match decode_state(&content) {
+        Ok(value) => Ok(value),
+        Err(corruption @ Corruption::Locked(_)) => {
+            anyhow::bail!("State file {} {}", path.display(), corruption)
+        }
+        Err(corruption) => {
+            quarantine_in(fs, path, &corruption.to_string())?;
+            Ok(default())
+        }
+    }
+}
+
+/// Move a bad file aside and record the incident; returns the new location.
+pub fn quarantine(path: &Path, reason: &str) -> Result<PathBuf> {
+    quarantine_in(&RealFileSystem, path, reason)
+}
+
+fn quarantine_in(fs: &dyn FileSystem, path: &Path, reason: &str) -> Result<PathBuf> {
+    match IncidentLog::new() {
+        Ok(log) => log.quarantine_in(fs, path, reason),
+        Err(e) => {
+            tracing::warn!("Failed to open state incident log: {}", e);
+            move_aside(fs, path)
+        }
+    }
+}
+
+fn move_aside(fs: &dyn FileSystem, path: &Path) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "state".to_string());
+    let quarantined = path.with_file_name(format!(
+        "{file_name}.corrupt-{}",
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ));
+
+    fs.rename(path, &quarantined)
+        .with_context(|| format!("Failed to quarantine {}", path.display()))?;
+    Ok(quarantined)
+}
+
+/// A corrupt state file that was moved aside
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateIncident {
+    pub file: PathBuf,
+    pub quarantined_to: PathBuf,
+    pub reason: String,
+    pub detected_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Incidents reported by `mcp doctor`
+#[derive(Debug, Clone)]
+pub struct IncidentLog {
+    path: PathBuf,
+}
+
+impl IncidentLog {
+    pub fn new() -> Result<Self> {
+        Ok(Self::with_path(Self::default_path()?))
+    }
+
+    pub fn with_path(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn default_path() -> Result<PathBuf> {
+        Ok(paths::data_dir()?.join("state-incidents.json"))
+    }
+
+    /// All recorded incidents; an unreadable log is treated as empty
+    pub fn list(&self) -> Vec<StateIncident> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Incidents whose quarantined file is still on disk
+    pub fn open_incidents(&self) -> Vec<StateIncident> {
+        self.list()
+            .into_iter()
+            .filter(|incident| incident.quarantined_to.exists())
+            .collect()
+    }
+
+    /// Move a bad file aside and record the incident here
+    pub fn quarantine(&self, path: &Path, reason: &str) -> Result<PathBuf> {
+        self.quarantine_in(&RealFileSystem, path, reason)
+    }
+
+    fn quarantine_in(&self, fs: &dyn FileSystem, path: &Path, reason: &str) -> Result<PathBuf> {
+        let quarantined = move_aside(fs, path)?;
+        tracing::warn!(
+            file = %path.display(),
+            quarantined = %quarantined.display(),
+            reason,
+            "Quarantined corrupt state file"
+        );
+
+        let incident = StateIncident {
+            file: path.to_path_buf(),
+            quarantined_to: quarantined.clone(),
+            reason: reason.to_string(),
+            detected_at: chrono::Utc::now(),
+        };
+        if let Err(e) = self.record(incident) {
+            tracing::warn!("Failed to record state incident: {}", e);
+        }
+        Ok(quarantined)
+    }
+
+    pub fn record(&self, incident: StateIncident) -> Result<()> {
+        let mut incidents = self.open_incidents();
+        incidents.push(incident);
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&incidents)
+            .context("Failed to serialize state incidents")?;
+        fs::write(&self.path, json).context("Failed to write state incidents")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.json");
+        let value = BTreeMap::from([("a".to_string(), 1), ("b".to_string(), 2)]);
+
+        write_state(&path, &value).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"format_version\": 1"));
+
+        let loaded: BTreeMap<String, i32> = read_state(&path, BTreeMap::new).unwrap();
+        assert_eq!(loaded, value);
+        assert!(verify_state_file(&path).is_ok());
+    }
+
+    #[test]
+    fn test_legacy_files_are_accepted() {
+        let decoded: BTreeMap<String, i32> = decode_state(r#"{ "a": 1 }"#).unwrap();
+        assert_eq!(decoded["a"], 1);
+    }
+
+    #[test]
+    fn test_detects_corruption() {
+        let tampered = r#"{ "format_version": 1, "checksum": "00", "data": { "a": 1 } }"#;
+        assert_eq!(
+            decode_state::<serde_json::Value>(tampered).unwrap_err(),
+            Corruption::ChecksumMismatch
+        );
+
+        let future = r#"{ "format_version": 99, "checksum": "00", "data": {} }"#;
+        assert_eq!(
+            decode_state::<serde_json::Value>(future).unwrap_err(),
+            Corruption::UnsupportedVersion(99)
+        );
+
+        assert!(matches!(
+            decode_state::<serde_json::Value>("{ truncated"),
+            Err(Corruption::Unparseable(_))
+        ));
+    }
+
+    #[test]
+    fn test_encrypted_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.json");
+        let key = StateKey::from_bytes([9; 32]);
+        let value = BTreeMap::from([("registry_token".to_string(), 42)]);
+
+        write_state_with(&RealFileSystem, &path, &value, Some(&key)).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"format_version\": 2"));
+        assert!(!content.contains("registry_token"));
+        assert!(is_encrypted(&content));
+
+        let loaded: BTreeMap<String, i32> =
+            decode_state_with(&content, || Ok(key.clone())).unwrap();
+        assert_eq!(loaded, value);
+
+        let other = StateKey::from_bytes([8; 32]);
+        assert!(matches!(
+            decode_state_with::<serde_json::Value>(&content, || Ok(other)),
+            Err(Corruption::Locked(_))
+        ));
+        assert!(matches!(
+            decode_state_with::<serde_json::Value>(&content, || Err("no key".to_string())),
+            Err(Corruption::Locked(_))
+        ));
+
+        let tampered = content.replacen("\"mac\": \"", "\"mac\": \"00", 1);
+        assert_eq!(
+            decode_state_with::<serde_json::Value>(&tampered, || Ok(key.clone())).unwrap_err(),
+            Corruption::ChecksumMismatch
+        );
+    }
+
+    #[test]
+    fn test_quarantine_records_incident() {
+        let temp_dir = TempDir::new().unwrap();
+        let log = IncidentLog::with_path(temp_dir.path().join("state-incidents.json"));
+        let path = temp_dir.path().join("cache.json");
+        fs::write(&path, "{ truncated").unwrap();
+
+        let quarantined = log.quarantine(&path, "could not be parsed").unwrap();
+        assert!(!path.exists());
+        assert!(quarantined.exists());
+
+        let incidents = log.open_incidents();
+        assert_eq!(incidents.len(), 1);
+        assert_eq!(incidents[0].file, path);
+        assert_eq!(incidents[0].quarantined_to, quarantined);
+
+        // Deleting the quarantined copy resolves the incident
+        fs::remove_file(&quarantined).unwrap();
+        assert!(log.open_incidents().is_empty());
+    }
+}
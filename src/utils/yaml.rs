@@ -0,0 +1,118 @@
+//! YAML input for hand-written mcp-helper files such as `mcp apply` state files.
+//!
+//! Documents are read with `serde_yaml`; syntax and schema errors become a
+//! [`ParseError`] at the line and column where they were found:
+//!
+//! ```text
+//! state.yaml:5:9: clients.Cursor.servers.x: unknown field `comand`, expected one of ...
+//! ```
+//!
+//! An empty document deserializes as null, so callers read into an `Option` when
+//! an empty file is allowed.
+
+use serde::de::DeserializeOwned;
+
+use super::parse_error::ParseError;
+
+/// Deserialize a YAML document, reporting syntax and schema errors with their position
+pub fn parse_yaml<T: DeserializeOwned>(file: &str, content: &str) -> Result<T, ParseError> {
+    serde_yaml::from_str(content).map_err(|e| ParseError::from_yaml(file, &e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_nested_mappings_and_lists() {
+        let content = "\
+# desired servers
+clients:
+  Claude Desktop:
+    servers:
+      fetch:
+        command: uvx   # from PyPI
+        args: [mcp-server-fetch, \"--ignore-robots-txt\"]
+      files:
+        package: '@modelcontextprotocol/server-filesystem@0.6.2'
+        args:
+          - /Users/me/projects
+          - \"/Users/me/My Notes\"
+        env: {}
+  Cursor:
+    servers:
+";
+        let value: Value = parse_yaml("state.yaml", content).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "clients": {
+                    "Claude Desktop": {
+                        "servers": {
+                            "fetch": {
+                                "command": "uvx",
+                                "args": ["mcp-server-fetch", "--ignore-robots-txt"]
+                            },
+                            "files": {
+                                "package": "@modelcontextprotocol/server-filesystem@0.6.2",
+                                "args": ["/Users/me/projects", "/Users/me/My Notes"],
+                                "env": {}
+                            }
+                        }
+                    },
+                    "Cursor": { "servers": null }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_scalars_read_into_strings() {
+        let env: HashMap<String, String> = parse_yaml(
+            "a.yaml",
+            "PORT: 8080\nDEBUG: true\nURL: http://localhost:3000/#top\nQUOTE: 'it''s'\n",
+        )
+        .unwrap();
+        assert_eq!(env["PORT"], "8080");
+        assert_eq!(env["DEBUG"], "true");
+        assert_eq!(env["URL"], "http://localhost:3000/#top");
+        assert_eq!(env["QUOTE"], "it's");
+    }
+
+    #[test]
+    fn test_errors_are_located() {
+        let error = parse_yaml::<Value>("a.yaml", "a: 1\n  b: 2\n").unwrap_err();
+        assert_eq!(error.file, "a.yaml");
+        assert_eq!(error.line, 2);
+        assert!(!error.message.contains(" at line "), "{}", error.message);
+
+        let error = parse_yaml::<Value>("a.yaml", "a: &x 1\nb: *y\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(
+            error.message.contains("unknown anchor"),
+            "{}",
+            error.message
+        );
+
+        let error = parse_yaml::<Value>("a.yaml", "a: \"open\n").unwrap_err();
+        assert!(error.line > 1, "{error}");
+
+        let error =
+            parse_yaml::<HashMap<String, Vec<String>>>("a.yaml", "a: [x]\nb: text\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 4));
+    }
+
+    #[test]
+    fn test_empty_document() {
+        assert_eq!(
+            parse_yaml::<Option<Value>>("a.yaml", "# nothing\n\n").unwrap(),
+            None
+        );
+        assert_eq!(
+            parse_yaml::<Value>("a.yaml", "---\na: b\n").unwrap(),
+            json!({"a": "b"})
+        );
+    }
+}
@@ -22,6 +22,7 @@ fn test_claude_desktop_client_error_paths() {
             command: "test".to_string(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         },
     );
 
@@ -66,6 +67,7 @@ fn test_claude_desktop_backup_creation() {
         command: "npx".to_string(),
         args: vec!["test-server".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // The actual test would need to mock the file system paths
@@ -86,6 +88,7 @@ fn test_claude_desktop_validate_config_edge_cases() {
             command: "".to_string(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         },
     );
 
@@ -108,6 +111,7 @@ fn test_claude_desktop_env_var_validation() {
         command: "test".to_string(),
         args: vec![],
         env: env.clone(),
+        cwd: None,
     };
 
     // This should be valid
@@ -122,6 +126,7 @@ fn test_claude_desktop_env_var_validation() {
         command: "test".to_string(),
         args: vec![],
         env: bad_env,
+        cwd: None,
     };
 
     let bad_result = client.add_server("test-bad-env", bad_config);
@@ -161,6 +166,7 @@ fn test_claude_desktop_special_characters_in_config() {
         command: "test".to_string(),
         args: vec!["--option=\"quoted value\"".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let result = client.add_server("server-with-special-chars-🚀", config);
@@ -182,6 +188,7 @@ fn test_claude_desktop_large_config() {
         command: "test".to_string(),
         args: vec!["arg1".to_string(); 50], // Many args
         env: large_env,
+        cwd: None,
     };
 
     let result = client.add_server("large-config-server", config);
@@ -221,6 +228,7 @@ fn test_claude_desktop_atomic_write_simulation() {
             command: format!("server-{i}"),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         };
         let _ = client.add_server(&format!("test-{i}"), config);
     }
@@ -178,6 +178,7 @@ fn test_server_config_validation() {
         command: String::new(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
     let _result = client.add_server("test", config);
     // In real implementation, this should fail validation
@@ -188,6 +189,7 @@ fn test_server_config_validation() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
     assert!(client.add_server("valid-server", config).is_ok());
 }
@@ -206,6 +208,7 @@ fn test_add_and_list_servers_empty_config() {
         command: "npx".to_string(),
         args: vec!["@modelcontextprotocol/server-filesystem".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
     client.add_server("filesystem", config.clone()).unwrap();
 
@@ -243,6 +246,7 @@ fn test_add_server_to_existing_config() {
         command: "node".to_string(),
         args: vec!["new-server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
     client.add_server("new-server", new_config.clone()).unwrap();
 
@@ -271,6 +275,7 @@ fn test_server_with_environment_variables() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: env_vars.clone(),
+        cwd: None,
     };
 
     client.add_server("env-test", config).unwrap();
@@ -293,6 +298,7 @@ fn test_overwrite_existing_server() {
         command: "python".to_string(),
         args: vec!["old-server.py".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
     client.add_server("test-server", config1).unwrap();
 
@@ -301,6 +307,7 @@ fn test_overwrite_existing_server() {
         command: "node".to_string(),
         args: vec!["new-server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
     client.add_server("test-server", config2.clone()).unwrap();
 
@@ -328,6 +335,7 @@ fn test_complex_server_configurations() {
                     "/Users/testuser/Documents".to_string(),
                 ],
                 env: HashMap::new(),
+                cwd: None,
             },
         ),
         (
@@ -343,6 +351,7 @@ fn test_complex_server_configurations() {
                     env.insert("GITHUB_TOKEN".to_string(), "ghp_testtoken123".to_string());
                     env
                 },
+                cwd: None,
             },
         ),
         (
@@ -366,6 +375,7 @@ fn test_complex_server_configurations() {
                     );
                     env
                 },
+                cwd: None,
             },
         ),
     ];
@@ -427,6 +437,7 @@ fn test_config_with_unicode_and_special_chars() {
             "--message=Hello, 世界!".to_string(),
         ],
         env: env_vars,
+        cwd: None,
     };
 
     client.add_server("unicode-test", config.clone()).unwrap();
@@ -450,6 +461,7 @@ fn test_empty_args_and_env() {
         command: "standalone-server".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     client.add_server("minimal", config.clone()).unwrap();
@@ -481,6 +493,7 @@ fn test_concurrent_server_additions() {
                     command: format!("server{i}"),
                     args: vec![format!("arg{i}")],
                     env: HashMap::new(),
+                    cwd: None,
                 };
                 // Serialize access to prevent concurrent writes
                 let client = client.lock().unwrap();
@@ -512,6 +525,7 @@ fn test_server_name_edge_cases() {
         command: "test".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // Test various server names
@@ -90,6 +90,7 @@ fn test_add_server_validates_empty_command() {
         command: "".to_string(), // Empty command
         args: vec!["arg".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let result = client.add_server("test-server", server_config);
@@ -110,6 +111,7 @@ fn test_add_server_validates_empty_env_var_name() {
         command: "node".to_string(),
         args: vec![],
         env,
+        cwd: None,
     };
 
     let result = client.add_server("test-server", server_config);
@@ -132,6 +134,7 @@ fn test_add_server_validates_env_var_with_equals() {
         command: "node".to_string(),
         args: vec![],
         env,
+        cwd: None,
     };
 
     let result = client.add_server("test-server", server_config);
@@ -159,6 +162,7 @@ fn test_add_server_creates_parent_directory() {
         command: "npx".to_string(),
         args: vec!["server".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // This will try to create directories and write config
@@ -232,6 +236,7 @@ fn test_backup_creation() {
         command: "test".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // This would create a backup if the config exists at the actual path
@@ -265,6 +270,7 @@ fn test_complex_server_config() {
             "localhost".to_string(),
         ],
         env,
+        cwd: None,
     };
 
     // Test adding a complex server
@@ -284,6 +290,7 @@ fn test_unicode_in_server_names_and_values() {
         command: "node".to_string(),
         args: vec!["server.js".to_string(), "--name=测试服务器".to_string()],
         env,
+        cwd: None,
     };
 
     // Test with unicode server name
@@ -299,6 +306,7 @@ fn test_empty_args_and_env() {
         command: "simple-server".to_string(),
         args: vec![],        // Empty args
         env: HashMap::new(), // Empty env
+        cwd: None,
     };
 
     let result = client.add_server("minimal-server", server_config);
@@ -313,6 +321,7 @@ fn test_special_characters_in_paths() {
         command: "/path/with spaces/and-special#chars/server".to_string(),
         args: vec!["--config=/path/with\"quotes\"/config.json".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let result = client.add_server("special-path-server", server_config);
@@ -331,6 +340,7 @@ fn test_very_long_values() {
         command: "server".to_string(),
         args: vec![format!("--data={}", long_string)],
         env,
+        cwd: None,
     };
 
     let result = client.add_server("long-value-server", server_config);
@@ -55,6 +55,7 @@ fn test_add_server_to_empty_config() {
         command: "npx".to_string(),
         args: vec!["@modelcontextprotocol/server-filesystem".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // Test that the method exists and returns a Result
@@ -74,6 +75,7 @@ fn test_add_server_with_environment_variables() {
         command: "python".to_string(),
         args: vec!["server.py".to_string()],
         env,
+        cwd: None,
     };
 
     // Test that the method handles env vars
@@ -105,6 +107,7 @@ fn test_validate_empty_command() {
         command: "".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let result = client.add_server("invalid", server_config);
@@ -122,6 +125,7 @@ fn test_validate_invalid_env_var_name() {
         command: "node".to_string(),
         args: vec![],
         env,
+        cwd: None,
     };
 
     let result = client.add_server("invalid-env", server_config);
@@ -139,6 +143,7 @@ fn test_validate_env_var_with_equals() {
         command: "node".to_string(),
         args: vec![],
         env,
+        cwd: None,
     };
 
     let result = client.add_server("invalid-env-equals", server_config);
@@ -418,3 +418,14 @@ fn test_cli_error_messages() {
             || stderr.contains("help")
     );
 }
+
+#[test]
+fn test_playground_calls_builtin_test_server() {
+    let mut cmd = Command::cargo_bin("mcp").unwrap();
+    cmd.args(["playground", "builtin:test"])
+        .write_stdin("tools\ncall echo {\"message\": \"from the playground\"}\nquit\n")
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Connected to mcp-helper-test"))
+        .stdout(predicates::str::contains("from the playground"));
+}
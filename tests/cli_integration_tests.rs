@@ -94,6 +94,32 @@ fn test_run_command_with_args() {
         .stdout(contains_text("Running MCP server: test-server"));
 }
 
+#[test]
+fn test_run_command_explain_does_not_run() {
+    let temp = tempfile::TempDir::new().unwrap();
+    test_cmd()
+        .args(["run", "nonexistent-server", "--explain"])
+        .env("HOME", temp.path())
+        .env("XDG_DATA_HOME", temp.path())
+        .assert()
+        .success()
+        .stdout(contains_text(
+            "Resolution of 'nonexistent-server' for mcp run",
+        ))
+        .stdout(contains_text("npm package nonexistent-server"))
+        .stdout(contains_text("Nothing was run"))
+        .stdout(contains_text("Running MCP server").not());
+}
+
+#[test]
+fn test_run_safe_conflicts_with_explain() {
+    test_cmd()
+        .args(["run", "nonexistent-server", "--safe", "--explain"])
+        .assert()
+        .failure()
+        .stderr(contains_text("cannot be used with"));
+}
+
 #[test]
 fn test_install_command_help() {
     test_cmd()
@@ -147,6 +173,33 @@ fn test_install_command_with_dry_run() {
         .stdout(contains_text("Installing MCP server: test-server"));
 }
 
+#[test]
+fn test_install_dry_run_leaves_client_config_untouched() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let cursor_config = temp_dir.path().join(".cursor").join("mcp.json");
+    std::fs::create_dir_all(cursor_config.parent().unwrap()).unwrap();
+    let original = r#"{"mcpServers": {"existing": {"command": "node", "args": []}}}"#;
+    std::fs::write(&cursor_config, original).unwrap();
+
+    test_cmd()
+        .args([
+            "install",
+            "docker:example/server",
+            "--dry-run",
+            "--client",
+            "Cursor",
+        ])
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .write_stdin("n\n")
+        .assert()
+        .success()
+        .stdout(contains_text("Would add server to Cursor"));
+
+    assert_eq!(std::fs::read_to_string(&cursor_config).unwrap(), original);
+}
+
 #[test]
 fn test_install_command_with_config() {
     test_cmd()
@@ -262,12 +315,102 @@ fn test_config_remove_missing_server() {
 
 #[test]
 fn test_doctor_command() {
+    let output = test_cmd()
+        .args(["doctor", "--only", "path"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("MCP Doctor - System Diagnostics"));
+    assert!(stdout.contains("Diagnostic Results:"));
+    // 0 when healthy, 2 for warnings and 3 for errors; 1 only if doctor itself fails
+    assert!([Some(0), Some(2), Some(3)].contains(&output.status.code()));
+}
+
+#[test]
+fn test_doctor_fix_without_clients() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
     test_cmd()
-        .arg("doctor")
+        .args(["doctor", "--fix", "--only", "path", "--fail-on", "never"])
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
         .assert()
         .success()
-        .stdout(contains_text("Running MCP diagnostics"))
-        .stdout(contains_text("Doctor command not yet implemented"));
+        .stdout(contains_text("Nothing to fix automatically"));
+}
+
+#[test]
+fn test_doctor_json_output() {
+    let output = test_cmd()
+        .args([
+            "doctor",
+            "--output",
+            "json",
+            "--only",
+            "path",
+            "--fail-on",
+            "error",
+        ])
+        .output()
+        .unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert!(report["results"].is_array());
+    assert!(report["summary"]["ok"].is_number());
+    for result in report["results"].as_array().unwrap() {
+        assert_eq!(result["category"], "PATH");
+    }
+}
+
+#[test]
+fn test_doctor_json_output_keeps_logs_off_stdout() {
+    let output = test_cmd()
+        .args(["--verbose", "doctor", "--output", "json", "--only", "path"])
+        .args(["--fail-on", "never"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Verbose logging enabled"));
+}
+
+#[test]
+fn test_doctor_rejects_unknown_category() {
+    test_cmd()
+        .args(["doctor", "--only", "bogus"])
+        .assert()
+        .failure()
+        .stderr(contains_text("Unknown check category"));
+}
+
+#[test]
+fn test_remove_glob_conflicts_with_server_name() {
+    test_cmd()
+        .args(["remove", "my-server", "--glob", "server-*"])
+        .assert()
+        .failure()
+        .stderr(contains_text("cannot be used with"));
+}
+
+#[test]
+fn test_list_rejects_invalid_filter_regex() {
+    test_cmd()
+        .args(["list", "--filter", "re:(unclosed"])
+        .assert()
+        .failure()
+        .stderr(contains_text("Invalid regular expression"));
+}
+
+#[test]
+fn test_doctor_rejects_invalid_severity() {
+    test_cmd()
+        .args(["doctor", "--fail-on", "fatal"])
+        .assert()
+        .failure()
+        .stderr(contains_text("Invalid severity"));
 }
 
 #[test]
@@ -474,3 +617,801 @@ fn test_platform_detection_linux() {
         .failure()
         .stderr(contains_text("Detected platform: Linux"));
 }
+
+#[test]
+fn test_update_requires_server_name() {
+    test_cmd()
+        .arg("update")
+        .assert()
+        .failure()
+        .stderr(contains_text("required"));
+}
+
+#[test]
+fn test_update_skip_requires_all() {
+    test_cmd()
+        .args(["update", "--skip", "github"])
+        .assert()
+        .failure()
+        .stderr(contains_text("--all"));
+}
+
+#[test]
+fn test_update_all_without_servers() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    test_cmd()
+        .args(["update", "--all"])
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .assert()
+        .success()
+        .stdout(contains_text("Everything is up to date"));
+}
+
+#[test]
+fn test_outdated_without_servers() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    test_cmd()
+        .arg("outdated")
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .assert()
+        .success()
+        .stdout(contains_text("Every pinned server runs the latest release"));
+}
+
+#[test]
+fn test_inventory_json_without_clients() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let output = test_cmd()
+        .args(["inventory", "--output", "json"])
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let inventory: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(inventory["schema_version"], 1);
+    assert_eq!(inventory["policy"]["compliant"], true);
+    assert!(inventory["host"]["hostname"].is_string());
+}
+
+#[test]
+fn test_inventory_writes_file() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("inventory.json");
+    test_cmd()
+        .args(["inventory", "--file"])
+        .arg(&path)
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .assert()
+        .success()
+        .stdout(contains_text("Wrote the inventory"));
+
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written["schema_version"], 1);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_list_json_aggregates_servers() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let claude_dir = temp_dir.path().join("config").join("Claude");
+    std::fs::create_dir_all(&claude_dir).unwrap();
+    std::fs::write(
+        claude_dir.join("claude_desktop_config.json"),
+        r#"{"mcpServers": {"postgres": {"command": "docker", "args": ["run", "-i", "--rm", "mcp/postgres:13"]}}}"#,
+    )
+    .unwrap();
+
+    let output = test_cmd()
+        .args(["list", "--json"])
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let servers: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        servers,
+        serde_json::json!([{
+            "name": "postgres",
+            "kind": "docker",
+            "source": "mcp/postgres:13",
+            "version": "13",
+            "clients": ["Claude Desktop"],
+        }])
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_add_to_named_client() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_path = temp_dir
+        .path()
+        .join("config")
+        .join("Claude")
+        .join("claude_desktop_config.json");
+    std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+    std::fs::write(&config_path, r#"{"mcpServers": {}}"#).unwrap();
+    let add = |client: &str| {
+        let mut cmd = test_cmd();
+        cmd.args([
+            "add",
+            "echo-server",
+            "--command",
+            "echo",
+            "--non-interactive",
+        ])
+        .args(["--client", client])
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"));
+        cmd
+    };
+
+    add("Windsurf")
+        .assert()
+        .failure()
+        .stderr(contains_text("Client 'Windsurf' is not installed"))
+        .stderr(contains_text("Claude Desktop"));
+
+    add("claude desktop").assert().success();
+    let config: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(config["mcpServers"]["echo-server"]["command"], "echo");
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_audit_show_and_export() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let log_dir = temp_dir.path().join("data").join("mcp-helper");
+    std::fs::create_dir_all(&log_dir).unwrap();
+    std::fs::write(
+        log_dir.join("audit.jsonl"),
+        concat!(
+            r#"{"timestamp":"2026-01-02T10:00:00Z","invocation":"mcp install fetch","kind":"command","program":"uvx","args":["mcp-server-fetch"],"exit_code":0}"#,
+            "\n",
+            r#"{"timestamp":"2026-03-04T10:00:00Z","invocation":"mcp install fetch","kind":"security_override","subject":"fetch","warnings":["Uses HTTP"]}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    test_cmd()
+        .args(["audit", "show", "--kind", "override"])
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .assert()
+        .success()
+        .stdout(contains_text("Uses HTTP"))
+        .stdout(contains_text("mcp-server-fetch").not());
+
+    let output = test_cmd()
+        .args(["audit", "export", "--since", "2026-02-01"])
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["kind"], "security_override");
+}
+
+#[test]
+fn test_debug_snapshot_writes_bundle() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let bundle = temp_dir.path().join("bundle");
+    test_cmd()
+        .args(["debug", "snapshot", "--anonymize", "--output"])
+        .arg(&bundle)
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .assert()
+        .success()
+        .stdout(contains_text("Wrote a snapshot"));
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(bundle.join("snapshot.json")).unwrap())
+            .unwrap();
+    assert_eq!(manifest["anonymized"], true);
+    assert!(bundle.join("preferences.json").exists());
+    assert!(bundle.join("managed-servers.json").exists());
+}
+
+#[test]
+fn test_status_without_history() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    test_cmd()
+        .arg("status")
+        .env("XDG_DATA_HOME", temp_dir.path())
+        .assert()
+        .success()
+        .stdout(contains_text("No server runs recorded yet"));
+}
+
+#[test]
+fn test_status_summary_without_history() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    test_cmd()
+        .args(["status", "--summary"])
+        .env("XDG_DATA_HOME", temp_dir.path())
+        .assert()
+        .code(0)
+        .stdout("MCP: 0 ok, 0 warn, 0 err\n");
+}
+
+#[test]
+fn test_config_lint_help() {
+    test_cmd()
+        .args(["config", "lint", "--help"])
+        .assert()
+        .success()
+        .stdout(contains_text("--fix"));
+}
+
+#[test]
+fn test_trace_flag_writes_chrome_trace() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let trace_path = temp_dir.path().join("trace.json");
+
+    test_cmd()
+        .args(["doctor", "--only", "platform", "--trace"])
+        .arg(&trace_path)
+        .output()
+        .unwrap();
+
+    let contents = std::fs::read_to_string(&trace_path).unwrap();
+    let events: Vec<serde_json::Value> = serde_json::from_str(&contents).unwrap();
+    assert!(events
+        .iter()
+        .any(|event| event["name"] == "doctor" && event["ph"] == "B"));
+}
+
+#[test]
+fn test_sync_clients_requires_from() {
+    test_cmd()
+        .args(["sync-clients", "--to", "all"])
+        .assert()
+        .failure()
+        .stderr(contains_text("--from"));
+}
+
+#[test]
+fn test_cache_stats() {
+    let temp = tempfile::TempDir::new().unwrap();
+    test_cmd()
+        .args(["cache", "stats"])
+        .env("XDG_CACHE_HOME", temp.path())
+        .env("HOME", temp.path())
+        .assert()
+        .success()
+        .stdout(contains_text("npm registry metadata: 0 packages"));
+}
+
+#[test]
+fn test_help_server_builtin_registry() {
+    test_cmd()
+        .args(["help-server", "@modelcontextprotocol/server-filesystem"])
+        .assert()
+        .success()
+        .stdout(contains_text("Filesystem Server"));
+}
+
+#[test]
+fn test_info_offline_builtin_registry() {
+    test_cmd()
+        .args([
+            "info",
+            "@modelcontextprotocol/server-filesystem",
+            "--offline",
+        ])
+        .assert()
+        .success()
+        .stdout(contains_text("npm package"));
+}
+
+#[test]
+fn test_test_server_echoes_over_stdio() {
+    test_cmd()
+        .arg("test-server")
+        .write_stdin(concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"echo","arguments":{"message":"ping from test"}}}"#,
+            "\n",
+        ))
+        .assert()
+        .success()
+        .stdout(contains_text("mcp-helper-test"))
+        .stdout(contains_text("ping from test"));
+}
+
+#[test]
+fn test_install_security_report_sarif() {
+    test_cmd()
+        .args([
+            "install",
+            "http://unknown.example/mcp-server",
+            "--dry-run",
+            "--security-report",
+            "sarif",
+        ])
+        .assert()
+        .success()
+        .stdout(contains_text("\"version\": \"2.1.0\""))
+        .stdout(contains_text("insecure-transport"));
+}
+
+#[test]
+fn test_install_security_report_strict_fails_on_findings() {
+    test_cmd()
+        .args([
+            "install",
+            "http://unknown.example/mcp-server",
+            "--dry-run",
+            "--security-report",
+            "json",
+            "--strict",
+        ])
+        .assert()
+        .code(20)
+        .stdout(contains_text("insecure-transport"));
+}
+
+#[test]
+fn test_strict_mode_blocks_insecure_source() {
+    test_cmd()
+        .args([
+            "add",
+            "http://unknown.example/mcp-server",
+            "--non-interactive",
+            "--strict",
+        ])
+        .assert()
+        .code(20)
+        .stderr(contains_text("Strict mode blocked"));
+}
+
+#[test]
+fn test_import_normalize_flags_conflict() {
+    test_cmd()
+        .args(["import", "--normalize", "--no-normalize"])
+        .assert()
+        .failure()
+        .stderr(contains_text("cannot be used with"));
+}
+
+#[test]
+fn test_service_remove_unknown_server() {
+    test_cmd()
+        .args(["service", "remove", "definitely-not-installed-server"])
+        .assert()
+        .failure()
+        .stderr(contains_text("No service is installed"));
+}
+
+#[test]
+fn test_check_requires_a_check() {
+    test_cmd()
+        .arg("check")
+        .assert()
+        .failure()
+        .stderr(contains_text("mcp check --sync"));
+
+    test_cmd()
+        .args(["check", "--update-lockfile"])
+        .assert()
+        .failure()
+        .stderr(contains_text("--sync"));
+}
+
+#[test]
+fn test_add_help_mentions_quiet() {
+    test_cmd()
+        .args(["add", "--help"])
+        .assert()
+        .success()
+        .stdout(contains_text("--quiet"))
+        .stdout(contains_text("next steps"));
+}
+
+#[test]
+fn test_add_rejects_invalid_instance_name() {
+    test_cmd()
+        .args([
+            "add",
+            "@modelcontextprotocol/server-filesystem",
+            "--as",
+            "docs/fs",
+            "--non-interactive",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains_text("Instance name can only contain"));
+}
+
+#[test]
+fn test_add_rejects_relative_working_directory() {
+    test_cmd()
+        .args([
+            "add",
+            "@modelcontextprotocol/server-filesystem",
+            "--cwd",
+            "relative/dir",
+            "--non-interactive",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains_text("must be an absolute path"));
+}
+
+#[test]
+fn test_add_manifest_out_writes_client_entry() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let manifest = temp.path().join("mcp.json");
+    test_cmd()
+        .args(["add", "demo", "--command", "node", "--args", "server.js"])
+        .arg("--manifest-out")
+        .arg(&manifest)
+        .arg("--non-interactive")
+        .assert()
+        .success();
+
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&manifest).unwrap()).unwrap();
+    assert_eq!(written["mcpServers"]["demo"]["command"], "node");
+    assert_eq!(written["mcpServers"]["demo"]["args"][0], "server.js");
+}
+
+#[test]
+fn test_add_rejects_plain_http_remote_url() {
+    test_cmd()
+        .args([
+            "add",
+            "remote",
+            "--url",
+            "http://api.example.com/mcp",
+            "--non-interactive",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains_text("plain HTTP"));
+}
+
+#[test]
+fn test_bridge_rejects_malformed_header() {
+    test_cmd()
+        .args([
+            "bridge",
+            "--url",
+            "https://api.example.com/mcp",
+            "--header",
+            "Authorization",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains_text("expected Name=Value"));
+}
+
+#[test]
+fn test_bridge_rejects_unknown_transport() {
+    test_cmd()
+        .args([
+            "bridge",
+            "--url",
+            "https://api.example.com/mcp",
+            "--transport",
+            "websocket",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains_text("Invalid transport"));
+}
+
+#[test]
+fn test_graph_rejects_unknown_format() {
+    test_cmd()
+        .args(["graph", "--format", "svg"])
+        .assert()
+        .failure()
+        .stderr(contains_text("Invalid graph format"));
+}
+
+#[test]
+fn test_graph_dot_output() {
+    test_cmd()
+        .args(["graph", "--format", "dot"])
+        .assert()
+        .success()
+        .stdout(contains_text("digraph mcp {"));
+}
+
+#[test]
+fn test_exec_requires_command() {
+    test_cmd().arg("exec").assert().failure();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exec_resolves_env_references() {
+    test_cmd()
+        .args(["exec", "--", "sh", "-c", "test \"$TOKEN\" = s3cret"])
+        .env("TOKEN", "${env:MCP_TEST_SECRET}")
+        .env("MCP_TEST_SECRET", "s3cret")
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_docker_prune_help() {
+    test_cmd()
+        .args(["docker", "prune", "--help"])
+        .assert()
+        .success()
+        .stdout(contains_text("--dry-run"))
+        .stdout(contains_text("--yes"));
+}
+
+#[test]
+fn test_state_help() {
+    test_cmd()
+        .args(["state", "--help"])
+        .assert()
+        .success()
+        .stdout(contains_text("encrypt"))
+        .stdout(contains_text("decrypt"));
+}
+
+#[test]
+fn test_config_repair_help() {
+    test_cmd()
+        .args(["config", "repair", "--help"])
+        .assert()
+        .success()
+        .stdout(contains_text("--client"))
+        .stdout(contains_text("--yes"));
+}
+
+#[test]
+fn test_export_script_rejects_unknown_shell() {
+    test_cmd()
+        .args(["export-script", "--shell", "fish"])
+        .assert()
+        .failure()
+        .stderr(contains_text("Invalid shell"));
+}
+
+#[test]
+fn test_debug_env_help() {
+    test_cmd()
+        .args(["debug-env", "--help"])
+        .assert()
+        .success()
+        .stdout(contains_text("--client"));
+}
+
+#[test]
+fn test_remove_help_lists_yes() {
+    test_cmd()
+        .args(["remove", "--help"])
+        .assert()
+        .success()
+        .stdout(contains_text("--yes"))
+        .stdout(contains_text("--prune-docker"));
+}
+
+#[test]
+fn test_uninstall_unknown_server() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    test_cmd()
+        .args([
+            "uninstall",
+            "definitely-not-installed-server",
+            "--all-clients",
+            "--cleanup",
+            "--dry-run",
+        ])
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .assert()
+        .failure()
+        .stderr(contains_text("not found in any MCP client configuration"));
+}
+
+#[test]
+fn test_uninstall_all_clients_conflicts_with_client() {
+    test_cmd()
+        .args([
+            "uninstall",
+            "my-server",
+            "--all-clients",
+            "--client",
+            "Cursor",
+        ])
+        .assert()
+        .failure()
+        .stderr(contains_text("cannot be used with"));
+}
+
+#[test]
+fn test_progress_stream_reports_errors() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let stream = temp_dir.path().join("progress.jsonl");
+
+    test_cmd()
+        .args([
+            "update",
+            "definitely-not-installed-server",
+            "--progress-stream",
+        ])
+        .arg(format!("file:{}", stream.display()))
+        .assert()
+        .failure();
+
+    let contents = std::fs::read_to_string(&stream).unwrap();
+    let events: Vec<serde_json::Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(events.first().unwrap()["event"], "step");
+    let last = events.last().unwrap();
+    assert_eq!(last["event"], "error");
+    assert_eq!(last["operation"], "update");
+    assert!(last["error"].as_str().unwrap().contains("not found"));
+}
+
+/// `mcp install --batch` for `batch` in an empty home, with nothing on stdin
+fn batch_install(temp_dir: &tempfile::TempDir, batch: &str) -> Command {
+    let file = temp_dir.path().join("servers.txt");
+    std::fs::write(&file, batch).unwrap();
+
+    let mut cmd = test_cmd();
+    cmd.arg("install")
+        .arg("--batch")
+        .arg(&file)
+        .env("HOME", temp_dir.path())
+        .env("XDG_CONFIG_HOME", temp_dir.path().join("config"))
+        .env("XDG_DATA_HOME", temp_dir.path().join("data"))
+        .write_stdin("");
+    cmd
+}
+
+#[test]
+fn test_install_batch_prefetches_downloads() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    // Without docker on PATH the early pulls fail fast and each server then fails on its own
+    batch_install(&temp_dir, "[docker:postgres:13]\n\n[docker:redis:7]\n")
+        .env("PATH", temp_dir.path())
+        .assert()
+        .failure()
+        .stdout(contains_text("Found 2 server(s) to install"))
+        .stdout(contains_text("Downloading 2 server(s)"))
+        .stdout(contains_text(
+            "Could not download postgres:13 ahead of time",
+        ))
+        .stdout(contains_text("Batch installation complete"))
+        .stderr(contains_text("not yet supported").not());
+}
+
+#[test]
+fn test_install_batch_checks_shared_dependency_once() {
+    if which::which("node").is_err() {
+        return;
+    }
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let output = batch_install(
+        &temp_dir,
+        "[@modelcontextprotocol/server-memory]\n\n[@modelcontextprotocol/server-everything]\n",
+    )
+    .arg("--dry-run")
+    .output()
+    .unwrap();
+
+    // Both servers get past the dependency check, but Node.js is only checked once
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.matches("Checking dependencies").count(),
+        1,
+        "{stdout}"
+    );
+    assert_eq!(
+        stdout.matches("Node.js is installed").count(),
+        1,
+        "{stdout}"
+    );
+    assert_eq!(
+        stdout
+            .matches("→ Installing @modelcontextprotocol/")
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn test_install_batch_streams_progress() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let stream = temp_dir.path().join("progress.jsonl");
+
+    batch_install(&temp_dir, "[./first.jar]\n\n[./second.jar]\n")
+        .arg("--progress-stream")
+        .arg(format!("file:{}", stream.display()))
+        .assert()
+        .failure();
+
+    let contents = std::fs::read_to_string(&stream).unwrap();
+    let events: Vec<serde_json::Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let batch_steps: Vec<_> = events
+        .iter()
+        .filter(|event| event["operation"] == "batch" && event["step"] == "server")
+        .collect();
+    assert_eq!(batch_steps.len(), 2);
+    assert_eq!(batch_steps[0]["percent"], 0);
+    assert!(batch_steps[1]["message"]
+        .as_str()
+        .unwrap()
+        .contains("./second.jar (2/2)"));
+    assert!(events
+        .iter()
+        .any(|event| event["operation"] == "install" && event["server"] == "./first.jar"));
+
+    let last = events.last().unwrap();
+    assert_eq!(last["event"], "error");
+    assert_eq!(last["operation"], "batch");
+    assert!(last["error"]
+        .as_str()
+        .unwrap()
+        .contains("2 out of 2 installations failed"));
+}
+
+#[test]
+fn test_doctor_accepts_comma_separated_categories() {
+    let output = test_cmd()
+        .args(["doctor", "--category", "path,platform", "--output", "json"])
+        .output()
+        .unwrap();
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    for result in report["results"].as_array().unwrap() {
+        assert_ne!(result["category"], "Node.js");
+    }
+}
+
+#[test]
+fn test_doctor_accepts_nodejs_category() {
+    let output = test_cmd()
+        .args(["doctor", "--category", "nodejs,path", "--output", "json"])
+        .args(["--fail-on", "never"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let categories: Vec<_> = report["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["category"].as_str().unwrap().to_string())
+        .collect();
+    assert!(categories.iter().any(|c| c == "Node.js"), "{categories:?}");
+    assert!(categories.iter().any(|c| c == "PATH"), "{categories:?}");
+    assert!(!categories.iter().any(|c| c == "Docker"));
+}
@@ -0,0 +1,33 @@
+//! Golden-file compatibility tests for client config formats
+//!
+//! Fixtures live in `tests/fixtures/clients/<client>/`; see
+//! `mcp_helper::test_utils::client_compat` for the checks and how to add one.
+
+use mcp_helper::test_utils::client_compat::{check_fixture, discover, fixtures_dir, FixtureClient};
+
+#[test]
+fn test_every_client_has_fixtures() {
+    let fixtures = discover(&fixtures_dir()).unwrap();
+    for client in FixtureClient::ALL {
+        assert!(
+            fixtures.iter().any(|fixture| fixture.client == client),
+            "No fixtures for {}",
+            client.dir_name()
+        );
+    }
+}
+
+#[test]
+fn test_client_config_fixtures() {
+    let failures: Vec<String> = discover(&fixtures_dir())
+        .unwrap()
+        .iter()
+        .filter_map(|fixture| {
+            check_fixture(fixture)
+                .err()
+                .map(|e| format!("{fixture}: {e:#}"))
+        })
+        .collect();
+
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
@@ -20,6 +20,7 @@ fn create_test_server_config(name: &str) -> ServerConfig {
             );
             env
         },
+        cwd: None,
     }
 }
 
@@ -122,6 +123,7 @@ fn test_server_config_equality_and_cloning() {
             env.insert("API_KEY".to_string(), "secret123".to_string());
             env
         },
+        cwd: None,
     };
 
     // Test clone
@@ -167,6 +169,7 @@ fn test_server_config_json_serialization() {
             env.insert("COMPOSE_PROJECT_NAME".to_string(), "mcp-test".to_string());
             env
         },
+        cwd: None,
     };
 
     // Serialize to JSON
@@ -189,6 +192,7 @@ fn test_server_config_edge_cases() {
         command: String::new(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
     assert!(config.command.is_empty());
 
@@ -197,6 +201,7 @@ fn test_server_config_edge_cases() {
         command: "C:\\Program Files\\Node\\node.exe".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
     assert!(config.command.contains(' '));
 
@@ -208,6 +213,7 @@ fn test_server_config_edge_cases() {
             "echo 'Hello, World!' && exit 0".to_string(),
         ],
         env: HashMap::new(),
+        cwd: None,
     };
     assert!(config.args[1].contains('\''));
     assert!(config.args[1].contains('&'));
@@ -223,6 +229,7 @@ fn test_server_config_edge_cases() {
         command: "test".to_string(),
         args: vec![],
         env,
+        cwd: None,
     };
 
     assert_eq!(config.env["EMPTY"], "");
@@ -337,6 +344,7 @@ fn test_server_config_with_different_command_types() {
             "/path/to/files".to_string(),
         ],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // Python server
@@ -353,6 +361,7 @@ fn test_server_config_with_different_command_types() {
             env.insert("PYTHONPATH".to_string(), "/custom/python/path".to_string());
             env
         },
+        cwd: None,
     };
 
     // Docker server
@@ -368,6 +377,7 @@ fn test_server_config_with_different_command_types() {
             "mcp/server:latest".to_string(),
         ],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // Binary server
@@ -384,6 +394,7 @@ fn test_server_config_with_different_command_types() {
             env.insert("LOG_LEVEL".to_string(), "debug".to_string());
             env
         },
+        cwd: None,
     };
 
     // Verify each config is valid and different
@@ -484,6 +495,7 @@ fn test_environment_variable_validation() {
             command: "test".to_string(),
             args: vec![],
             env: env.clone(),
+            cwd: None,
         };
 
         if should_be_valid {
@@ -15,6 +15,7 @@ fn test_server_config_creation() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env,
+        cwd: None,
     };
 
     assert_eq!(config.command, "node");
@@ -38,6 +39,7 @@ fn test_server_config_with_complex_env() {
             "production.json".to_string(),
         ],
         env: env.clone(),
+        cwd: None,
     };
 
     assert_eq!(config.command, "node");
@@ -52,6 +54,7 @@ fn test_server_config_empty() {
         command: "echo".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     assert_eq!(config.command, "echo");
@@ -65,6 +68,7 @@ fn test_server_config_clone() {
         command: "python".to_string(),
         args: vec!["app.py".to_string(), "--port=8080".to_string()],
         env: HashMap::from([("PYTHONPATH".to_string(), "/usr/lib/python".to_string())]),
+        cwd: None,
     };
 
     let cloned = config.clone();
@@ -192,6 +196,7 @@ fn test_server_config_with_many_args() {
             "mcp-server:latest".to_string(),
         ],
         env: HashMap::new(),
+        cwd: None,
     };
 
     assert_eq!(config.command, "docker");
@@ -215,6 +220,7 @@ fn test_server_config_with_special_chars_in_env() {
         command: "test".to_string(),
         args: vec![],
         env: env.clone(),
+        cwd: None,
     };
 
     assert_eq!(
@@ -318,18 +324,21 @@ fn test_server_config_equality() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::from([("PORT".to_string(), "3000".to_string())]),
+        cwd: None,
     };
 
     let config2 = ServerConfig {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::from([("PORT".to_string(), "3000".to_string())]),
+        cwd: None,
     };
 
     let config3 = ServerConfig {
         command: "deno".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::from([("PORT".to_string(), "3000".to_string())]),
+        cwd: None,
     };
 
     // Same configs should be equal
@@ -1,4 +1,4 @@
-use mcp_helper::client::{ClientRegistry, McpClient, ServerConfig};
+use mcp_helper::client::{ClientRegistry, ClientTarget, McpClient, ServerConfig};
 use mcp_helper::test_utils::mocks::MockClientBuilder;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -12,6 +12,7 @@ fn test_server_config_creation() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env,
+        cwd: None,
     };
 
     assert_eq!(config.command, "node");
@@ -76,6 +77,44 @@ fn test_client_registry_get_by_name() {
     assert!(registry.get_by_name("Unknown").is_none());
 }
 
+#[test]
+fn test_client_registry_get_installed_by_names() {
+    let mut registry = ClientRegistry::new();
+    registry.register(Box::new(MockClientBuilder::new("Claude Desktop").build()));
+    registry.register(Box::new(MockClientBuilder::new("Cursor").build()));
+    registry.register(Box::new(
+        MockClientBuilder::new("Windsurf").not_installed().build(),
+    ));
+
+    let names = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    let selected = registry
+        .get_installed_by_names(&names(&["cursor", "CLAUDE DESKTOP", "Cursor"]))
+        .unwrap();
+    let selected: Vec<_> = selected.iter().map(|client| client.name()).collect();
+    assert_eq!(selected, vec!["Cursor", "Claude Desktop"]);
+
+    let Err(error) = registry.get_installed_by_names(&names(&["windsurf"])) else {
+        panic!("Windsurf is not installed");
+    };
+    assert_eq!(
+        error.to_string(),
+        "Client 'windsurf' is not installed. Detected clients: Claude Desktop, Cursor"
+    );
+}
+
+#[test]
+fn test_client_target_from_flags() {
+    assert_eq!(
+        ClientTarget::from_flags(vec![], false),
+        ClientTarget::Prompt
+    );
+    assert_eq!(ClientTarget::from_flags(vec![], true), ClientTarget::All);
+    assert_eq!(
+        ClientTarget::from_flags(vec!["cursor".to_string()], false),
+        ClientTarget::Named(vec!["cursor".to_string()])
+    );
+}
+
 #[test]
 fn test_client_registry_default() {
     let registry = ClientRegistry::default();
@@ -88,6 +127,7 @@ fn test_mock_client_trait_methods() {
         command: "cmd".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let client = MockClientBuilder::new("test")
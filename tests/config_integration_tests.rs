@@ -54,6 +54,7 @@ fn test_config_snapshot_creation() {
             command: "npx".to_string(),
             args: vec!["test-server".to_string()],
             env: HashMap::new(),
+            cwd: None,
         },
         previous_config: None,
         description: "Test snapshot".to_string(),
@@ -72,6 +73,7 @@ fn test_config_diff_no_changes() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::from([("PORT".to_string(), "3000".to_string())]),
+        cwd: None,
     };
 
     let diff = manager.diff_configs(&config, &config);
@@ -86,12 +88,14 @@ fn test_config_diff_command_change() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
         command: "deno".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let diff = manager.diff_configs(&old_config, &new_config);
@@ -110,12 +114,14 @@ fn test_config_diff_args_change() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
         command: "node".to_string(),
         args: vec!["server.js".to_string(), "--port=3000".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let diff = manager.diff_configs(&old_config, &new_config);
@@ -134,6 +140,7 @@ fn test_config_diff_env_changes() {
             ("PORT".to_string(), "3000".to_string()),
             ("HOST".to_string(), "localhost".to_string()),
         ]),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
@@ -144,6 +151,7 @@ fn test_config_diff_env_changes() {
             ("DEBUG".to_string(), "true".to_string()), // Added
                                                       // HOST removed
         ]),
+        cwd: None,
     };
 
     let diff = manager.diff_configs(&old_config, &new_config);
@@ -251,12 +259,14 @@ fn test_snapshot_with_previous_config() {
         command: "old-command".to_string(),
         args: vec!["old.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let current = ServerConfig {
         command: "new-command".to_string(),
         args: vec!["new.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let snapshot = ConfigSnapshot {
@@ -286,6 +296,7 @@ fn test_complex_env_diff() {
             ("VAR4".to_string(), "value4".to_string()),
             ("VAR5".to_string(), "value5".to_string()),
         ]),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
@@ -300,6 +311,7 @@ fn test_complex_env_diff() {
             ("VAR6".to_string(), "value6".to_string()),    // Added
             ("VAR7".to_string(), "value7".to_string()),    // Added
         ]),
+        cwd: None,
     };
 
     let diff = manager.diff_configs(&old_config, &new_config);
@@ -335,6 +347,7 @@ fn test_timestamp_ordering() {
             command: "cmd1".to_string(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         },
         previous_config: None,
         description: "First".to_string(),
@@ -351,6 +364,7 @@ fn test_timestamp_ordering() {
             command: "cmd2".to_string(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         },
         previous_config: None,
         description: "Second".to_string(),
@@ -367,6 +381,7 @@ fn test_empty_diff() {
         command: "cmd".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let diff = manager.diff_configs(&empty_config, &empty_config);
@@ -381,12 +396,14 @@ fn test_args_diff_with_order() {
         command: "cmd".to_string(),
         args: vec!["arg1".to_string(), "arg2".to_string(), "arg3".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let config2 = ServerConfig {
         command: "cmd".to_string(),
         args: vec!["arg3".to_string(), "arg2".to_string(), "arg1".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let diff = manager.diff_configs(&config1, &config2);
@@ -407,6 +424,7 @@ fn test_config_with_special_characters() {
             ("QUOTES".to_string(), "value with \"quotes\"".to_string()),
             ("NEWLINE".to_string(), "value\nwith\nnewlines".to_string()),
         ]),
+        cwd: None,
     };
 
     assert_eq!(config.env.get("SPECIAL"), Some(&"!@#$%^&*()".to_string()));
@@ -32,6 +32,7 @@ fn test_config_snapshot_creation() {
             command: "npx".to_string(),
             args: vec!["test-server".to_string()],
             env: HashMap::new(),
+            cwd: None,
         },
         previous_config: None,
         description: "Test snapshot".to_string(),
@@ -54,6 +55,7 @@ fn test_diff_configs_no_changes() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::from([("NODE_ENV".to_string(), "production".to_string())]),
+        cwd: None,
     };
 
     let differences = manager.diff_configs(&config, &config);
@@ -73,12 +75,14 @@ fn test_diff_configs_command_change() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
         command: "deno".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let differences = manager.diff_configs(&old_config, &new_config);
@@ -101,6 +105,7 @@ fn test_diff_configs_args_change() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
@@ -111,6 +116,7 @@ fn test_diff_configs_args_change() {
             "3000".to_string(),
         ],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let differences = manager.diff_configs(&old_config, &new_config);
@@ -131,6 +137,7 @@ fn test_diff_configs_env_additions() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
@@ -140,6 +147,7 @@ fn test_diff_configs_env_additions() {
             ("NODE_ENV".to_string(), "production".to_string()),
             ("PORT".to_string(), "3000".to_string()),
         ]),
+        cwd: None,
     };
 
     let differences = manager.diff_configs(&old_config, &new_config);
@@ -164,12 +172,14 @@ fn test_diff_configs_env_removals() {
             ("NODE_ENV".to_string(), "production".to_string()),
             ("PORT".to_string(), "3000".to_string()),
         ]),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let differences = manager.diff_configs(&old_config, &new_config);
@@ -198,6 +208,7 @@ fn test_diff_configs_env_modifications() {
             ("NODE_ENV".to_string(), "development".to_string()),
             ("PORT".to_string(), "3000".to_string()),
         ]),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
@@ -207,6 +218,7 @@ fn test_diff_configs_env_modifications() {
             ("NODE_ENV".to_string(), "production".to_string()),
             ("PORT".to_string(), "8080".to_string()),
         ]),
+        cwd: None,
     };
 
     let differences = manager.diff_configs(&old_config, &new_config);
@@ -235,6 +247,7 @@ fn test_diff_configs_comprehensive_changes() {
             ("OLD_VAR".to_string(), "old_value".to_string()),
             ("SHARED_VAR".to_string(), "old_shared".to_string()),
         ]),
+        cwd: None,
     };
 
     let new_config = ServerConfig {
@@ -244,6 +257,7 @@ fn test_diff_configs_comprehensive_changes() {
             ("NEW_VAR".to_string(), "new_value".to_string()),
             ("SHARED_VAR".to_string(), "new_shared".to_string()),
         ]),
+        cwd: None,
     };
 
     let differences = manager.diff_configs(&old_config, &new_config);
@@ -270,6 +284,7 @@ fn test_config_snapshot_with_previous() {
         command: "old-command".to_string(),
         args: vec!["old-arg".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let snapshot = ConfigSnapshot {
@@ -280,6 +295,7 @@ fn test_config_snapshot_with_previous() {
             command: "new-command".to_string(),
             args: vec!["new-arg".to_string()],
             env: HashMap::new(),
+            cwd: None,
         },
         previous_config: Some(previous.clone()),
         description: "Update with previous".to_string(),
@@ -368,6 +384,7 @@ fn test_config_snapshot_timestamp() {
             command: "cmd".to_string(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         },
         previous_config: None,
         description: "test".to_string(),
@@ -391,6 +408,7 @@ fn test_diff_configs_empty_to_populated() {
         command: "cmd".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let populated_config = ServerConfig {
@@ -400,6 +418,7 @@ fn test_diff_configs_empty_to_populated() {
             ("VAR1".to_string(), "value1".to_string()),
             ("VAR2".to_string(), "value2".to_string()),
         ]),
+        cwd: None,
     };
 
     let differences = manager.diff_configs(&empty_config, &populated_config);
@@ -436,6 +455,7 @@ fn test_snapshot_description_formatting() {
             command: "npx".to_string(),
             args: vec!["@modelcontextprotocol/server-filesystem".to_string()],
             env: HashMap::new(),
+            cwd: None,
         },
         previous_config: None,
         description: "Configuration update for @modelcontextprotocol/server-filesystem".to_string(),
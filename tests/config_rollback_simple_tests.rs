@@ -17,6 +17,7 @@ fn test_simple_config_snapshot_creation() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // Apply configuration and create snapshot
@@ -51,6 +52,7 @@ fn test_simple_config_rollback() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     manager
@@ -101,6 +103,7 @@ fn test_simple_config_history() {
             command: "node".to_string(),
             args: vec![format!("server-{}.js", i)],
             env: HashMap::new(),
+            cwd: None,
         };
 
         manager
@@ -159,6 +162,7 @@ fn test_simple_config_diff() {
             env.insert("DEBUG".to_string(), "false".to_string());
             env
         },
+        cwd: None,
     };
 
     let new_config = ServerConfig {
@@ -170,6 +174,7 @@ fn test_simple_config_diff() {
             env.insert("PRODUCTION".to_string(), "true".to_string());
             env
         },
+        cwd: None,
     };
 
     let diffs = manager.diff_configs(&old_config, &new_config);
@@ -205,6 +210,7 @@ fn test_simple_latest_snapshot() {
             command: "node".to_string(),
             args: vec![format!("version-{}.js", i)],
             env: HashMap::new(),
+            cwd: None,
         };
 
         manager
@@ -22,6 +22,7 @@ fn test_config_snapshot_creation() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // Apply configuration and create snapshot
@@ -58,6 +59,7 @@ fn test_config_rollback_with_previous_config() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     manager
@@ -102,6 +104,7 @@ fn test_config_rollback_without_previous_config() {
         command: "deno".to_string(),
         args: ["run", "server.ts"].iter().map(|s| s.to_string()).collect(),
         env: HashMap::new(),
+        cwd: None,
     };
 
     let snapshot = manager
@@ -136,6 +139,7 @@ fn test_config_history_tracking() {
             command: "node".to_string(),
             args: vec![format!("server-{}.js", i)],
             env: HashMap::new(),
+            cwd: None,
         };
 
         manager
@@ -181,6 +185,7 @@ fn test_config_diff_functionality() {
             env.insert("DEBUG".to_string(), "false".to_string());
             env
         },
+        cwd: None,
     };
 
     let new_config = ServerConfig {
@@ -192,6 +197,7 @@ fn test_config_diff_functionality() {
             env.insert("PRODUCTION".to_string(), "true".to_string());
             env
         },
+        cwd: None,
     };
 
     let diffs = manager.diff_configs(&old_config, &new_config);
@@ -229,6 +235,7 @@ fn test_config_history_cleanup() {
             command: "node".to_string(),
             args: vec![format!("server-{}.js", i)],
             env: HashMap::new(),
+            cwd: None,
         };
 
         manager
@@ -270,6 +277,7 @@ fn test_latest_snapshot_retrieval() {
             command: "node".to_string(),
             args: vec![format!("version-{}.js", i)],
             env: HashMap::new(),
+            cwd: None,
         };
 
         manager
@@ -288,3 +296,42 @@ fn test_latest_snapshot_retrieval() {
     assert_eq!(snapshot.server_name, "latest-server");
     assert_eq!(snapshot.client_name, "latest-client");
 }
+
+#[test]
+fn test_remove_config_can_be_rolled_back() {
+    let temp_dir = TempDir::new().unwrap();
+    let unique_path = temp_dir
+        .path()
+        .join(format!("remove_test_{}", std::process::id()));
+    std::fs::create_dir_all(&unique_path).unwrap();
+    std::env::set_var("XDG_DATA_HOME", &unique_path);
+
+    let manager = ConfigManager::new().unwrap();
+
+    let client = MockClientBuilder::new("remove-client").build();
+
+    let config = ServerConfig {
+        command: "node".to_string(),
+        args: vec!["server.js".to_string()],
+        env: HashMap::new(),
+        cwd: None,
+    };
+    client.add_server("test-server", config.clone()).unwrap();
+
+    // Removing a server that is not configured is a no-op
+    assert!(manager
+        .remove_config(&client, "missing-server")
+        .unwrap()
+        .is_none());
+
+    let snapshot = manager
+        .remove_config(&client, "test-server")
+        .unwrap()
+        .expect("server should have been removed");
+    assert_eq!(snapshot.previous_config, Some(config.clone()));
+    assert!(!client.list_servers().unwrap().contains_key("test-server"));
+
+    // Rolling back restores the removed server
+    manager.rollback(&client, &snapshot).unwrap();
+    assert_eq!(client.list_servers().unwrap()["test-server"], config);
+}
@@ -35,7 +35,8 @@ fn test_node_checker_check() {
             DependencyStatus::Installed { .. }
             | DependencyStatus::Missing
             | DependencyStatus::VersionMismatch { .. }
-            | DependencyStatus::ConfigurationRequired { .. } => {}
+            | DependencyStatus::ConfigurationRequired { .. }
+            | DependencyStatus::Unresponsive { .. } => {}
         }
     }
 }
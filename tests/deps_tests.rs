@@ -46,6 +46,15 @@ fn test_dependency_status_display() {
         format!("{mismatch}"),
         "Version mismatch (installed: 16.0.0, required: 18.0.0)"
     );
+
+    let unresponsive = DependencyStatus::Unresponsive {
+        command: "docker info".to_string(),
+        timeout_secs: 10,
+    };
+    assert_eq!(
+        format!("{unresponsive}"),
+        "Unresponsive ('docker info' did not answer within 10s)"
+    );
 }
 
 #[test]
@@ -219,6 +219,9 @@ fn test_status_determination() {
                 DependencyStatus::ConfigurationRequired { .. } => {
                     // Docker installed but not running
                 }
+                DependencyStatus::Unresponsive { .. } => {
+                    // Docker daemon did not answer in time
+                }
             }
         }
         Err(_) => {
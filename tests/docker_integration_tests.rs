@@ -242,8 +242,8 @@ fn test_docker_image_pull_simulation() {
 
     // This will likely fail in CI without Docker, but shouldn't panic
     // The error should be informative
-    if result.is_err() {
-        let error_msg = result.unwrap_err().to_string();
+    if let Err(e) = result {
+        let error_msg = e.to_string();
         assert!(
             error_msg.contains("docker")
                 || error_msg.contains("Docker")
@@ -343,6 +343,7 @@ fn test_docker_server_integration_with_client() {
             env.insert("ports".to_string(), "8080:80".to_string());
             env
         },
+        cwd: None,
     };
 
     // Test adding Docker server to client
@@ -67,6 +67,7 @@ fn test_end_to_end_npm_server_installation() {
             env.insert("API_KEY".to_string(), "test123".to_string());
             env
         },
+        cwd: None,
     };
 
     // Test server installation
@@ -111,6 +112,7 @@ fn test_end_to_end_docker_server_installation() {
             );
             env
         },
+        cwd: None,
     };
 
     let result = client.add_server("nginx-server", config);
@@ -148,6 +150,7 @@ fn test_end_to_end_configuration_management() {
             env.insert("PORT".to_string(), "3000".to_string());
             env
         },
+        cwd: None,
     };
 
     // Apply initial configuration
@@ -169,6 +172,7 @@ fn test_end_to_end_configuration_management() {
             env.insert("DEBUG".to_string(), "true".to_string());
             env
         },
+        cwd: None,
     };
 
     let snapshot2 = config_manager
@@ -406,6 +410,7 @@ fn test_end_to_end_history_and_rollback() {
                 env.insert("VERSION".to_string(), version.to_string());
                 env
             },
+            cwd: None,
         };
 
         let snapshot = config_manager
@@ -476,6 +481,7 @@ fn test_end_to_end_comprehensive_workflow() {
             command: command.to_string(),
             args: args.iter().map(|s| s.to_string()).collect(),
             env: HashMap::new(),
+            cwd: None,
         };
 
         let result = client.add_server(name, config);
@@ -504,6 +510,7 @@ fn test_end_to_end_comprehensive_workflow() {
             env.insert("DEBUG".to_string(), "true".to_string());
             env
         },
+        cwd: None,
     };
 
     let snapshot = config_manager
@@ -31,9 +31,13 @@ fn error_no_clients_installed() {
             );
         }
         Err(McpError::Other(err)) => {
-            // In CI/non-interactive environments, dialog operations fail
+            // In CI/non-interactive environments, dialog operations fail; without a
+            // terminal the numbered menu reads standard input and finds it empty
             let error_msg = err.to_string();
-            if error_msg.contains("Dialog error") && error_msg.contains("not a terminal") {
+            if error_msg.contains("Dialog error")
+                && (error_msg.contains("not a terminal")
+                    || error_msg.contains("no answer on standard input"))
+            {
                 // This is acceptable in non-interactive environments
                 println!("Got expected dialog error in non-TTY environment");
             } else {
@@ -275,7 +275,9 @@ invalid line without equals sign
     assert!(result.is_err());
     match result {
         Err(McpError::Other(e)) => {
-            assert!(e.to_string().contains("Invalid line"));
+            let message = e.to_string();
+            assert!(message.contains("invalid.txt:4:"), "{message}");
+            assert!(message.contains("expected `key=value`"), "{message}");
         }
         _ => panic!("Expected Other error for invalid line"),
     }
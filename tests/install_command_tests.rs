@@ -242,6 +242,7 @@ mod integration_helpers {
             command: "npx".to_string(),
             args: vec!["--yes".to_string(), "test-server".to_string()],
             env: HashMap::new(),
+            cwd: None,
         }
     }
 }
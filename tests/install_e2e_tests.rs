@@ -30,6 +30,7 @@ fn test_server_metadata_creation() {
         },
         required_config: vec![],
         optional_config: vec![],
+        args_template: vec![],
     };
 
     assert_eq!(metadata.name, "test-server");
@@ -88,6 +89,7 @@ fn test_server_config_creation() {
         command: "npx".to_string(),
         args: vec!["test-server".to_string()],
         env: HashMap::from([("NODE_ENV".to_string(), "production".to_string())]),
+        cwd: None,
     };
 
     assert_eq!(config.command, "npx");
@@ -215,6 +217,7 @@ fn test_extended_server_metadata() {
             windows: true,
             macos: true,
             linux: true,
+            cpu: Vec::new(),
             min_node_version: None,
             min_python_version: None,
         },
@@ -242,6 +245,7 @@ fn test_registry_entry() {
         popularity_score: 4.5,
         last_updated: "2024-01-01".to_string(),
         verified: true,
+        platform_support: None,
     };
 
     assert_eq!(entry.name, "registry-server");
@@ -306,6 +310,7 @@ fn test_config_with_environment_variables() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: env_vars.clone(),
+        cwd: None,
     };
 
     assert_eq!(config.env.len(), 4);
@@ -337,6 +342,7 @@ fn test_required_vs_optional_config() {
             description: Some("Request timeout".to_string()),
             default: Some("30".to_string()),
         }],
+        args_template: vec![],
     };
 
     // Required fields should have no default
@@ -356,6 +362,7 @@ fn test_platform_support() {
         windows: true,
         macos: true,
         linux: true,
+        cpu: Vec::new(),
         min_node_version: None,
         min_python_version: None,
     };
@@ -364,6 +371,7 @@ fn test_platform_support() {
         windows: false,
         macos: true,
         linux: true,
+        cpu: Vec::new(),
         min_node_version: Some("18.0.0".to_string()),
         min_python_version: Some("3.8".to_string()),
     };
@@ -27,6 +27,7 @@ impl DependencyTestServer {
                 },
                 required_config: vec![],
                 optional_config: vec![],
+                args_template: vec![],
             },
             dep_status: DependencyStatus::Missing,
             has_instructions: true,
@@ -44,6 +45,7 @@ impl DependencyTestServer {
                 },
                 required_config: vec![],
                 optional_config: vec![],
+                args_template: vec![],
             },
             dep_status: DependencyStatus::VersionMismatch {
                 installed: "16.0.0".to_string(),
@@ -307,6 +309,7 @@ fn test_server_metadata_variations() {
         },
         required_config: vec![],
         optional_config: vec![],
+        args_template: vec![],
     };
 
     assert!(metadata.description.is_none());
@@ -113,6 +113,18 @@ fn test_server_type_detection_coverage() {
             ServerType::Docker { .. } => {
                 assert!(server.starts_with("docker:"));
             }
+            ServerType::Cargo { .. } => {
+                assert!(server.starts_with("cargo:"));
+            }
+            ServerType::Deno { .. } => {
+                assert!(server.starts_with("jsr:") || server.ends_with(".ts"));
+            }
+            ServerType::Jar { .. } => {
+                assert!(server.starts_with("jar:") || server.ends_with(".jar"));
+            }
+            ServerType::Dotnet { .. } => {
+                assert!(server.starts_with("dotnet:"));
+            }
         }
     }
 }
@@ -226,6 +238,7 @@ impl MockServer {
                 },
                 required_config: vec![],
                 optional_config: vec![],
+                args_template: vec![],
             },
         }
     }
@@ -66,6 +66,7 @@ impl ConfigurableServer {
                 },
                 required_config: required,
                 optional_config: optional,
+                args_template: vec![],
             },
         }
     }
@@ -310,6 +311,7 @@ fn test_install_to_client_error_handling() {
             command: "test".to_string(),
             args: vec![],
             env: config,
+            cwd: None,
         },
     );
 
@@ -99,6 +99,7 @@ fn test_server_metadata_required_config() {
             description: Some("Request timeout in seconds".to_string()),
             default: Some("30".to_string()),
         }],
+        args_template: vec![],
     };
 
     assert_eq!(metadata.required_config.len(), 1);
@@ -157,6 +158,7 @@ fn test_mock_client_add_server() {
         command: "npx".to_string(),
         args: vec!["test-server".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let client = MockClientBuilder::new("test-client")
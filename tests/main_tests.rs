@@ -135,12 +135,12 @@ fn test_config_remove_command() {
 #[test]
 fn test_doctor_command() {
     let mut cmd = get_command();
-    cmd.arg("doctor")
+    cmd.args(["doctor", "--fail-on", "never"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("Running MCP diagnostics..."))
+        .stdout(predicate::str::contains("MCP Doctor - System Diagnostics"))
         .stdout(predicate::str::contains(
-            "Doctor command not yet implemented",
+            "Running comprehensive system checks...",
         ));
 }
 
@@ -116,6 +116,7 @@ fn test_mcp_client_add_and_list_servers() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
     client.add_server("test-server", config.clone()).unwrap();
 
@@ -134,6 +135,7 @@ fn test_mcp_client_error_handling() {
         command: "test".to_string(),
         args: vec![],
         env: HashMap::new(),
+        cwd: None,
     };
 
     let result = client.add_server("test", config);
@@ -163,12 +165,14 @@ fn test_server_config_creation_and_equality() {
         command: "npx".to_string(),
         args: vec!["@modelcontextprotocol/server-filesystem".to_string()],
         env: env1.clone(),
+        cwd: None,
     };
 
     let config2 = ServerConfig {
         command: "npx".to_string(),
         args: vec!["@modelcontextprotocol/server-filesystem".to_string()],
         env: env1,
+        cwd: None,
     };
 
     // Test equality
@@ -179,6 +183,7 @@ fn test_server_config_creation_and_equality() {
         command: "python".to_string(),
         args: vec!["server.py".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
     assert_ne!(config1, config3);
 }
@@ -196,6 +201,7 @@ fn test_server_config_serialization() {
             "mcp-server".to_string(),
         ],
         env,
+        cwd: None,
     };
 
     // Test serialization
@@ -355,6 +361,7 @@ fn test_mcp_client_trait_send_sync() {
                     command: format!("cmd{i}"),
                     args: vec![],
                     env: HashMap::new(),
+                    cwd: None,
                 };
                 let _ = client.add_server(&format!("server{i}"), config);
             })
@@ -383,6 +390,7 @@ fn test_server_config_with_complex_environment() {
             "3000".to_string(),
         ],
         env: env.clone(),
+        cwd: None,
     };
 
     assert_eq!(config.env.len(), 4);
@@ -406,6 +414,7 @@ fn test_client_with_multiple_servers() {
                 command: "npx".to_string(),
                 args: vec!["@modelcontextprotocol/server-filesystem".to_string()],
                 env: HashMap::new(),
+                cwd: None,
             },
         ),
         (
@@ -418,6 +427,7 @@ fn test_client_with_multiple_servers() {
                     env.insert("GITHUB_TOKEN".to_string(), "ghp_xxx".to_string());
                     env
                 },
+                cwd: None,
             },
         ),
         (
@@ -426,6 +436,7 @@ fn test_client_with_multiple_servers() {
                 command: "python".to_string(),
                 args: vec!["-m".to_string(), "mcp_server".to_string()],
                 env: HashMap::new(),
+                cwd: None,
             },
         ),
     ];
@@ -456,6 +467,7 @@ fn test_registry_with_mixed_client_states() {
             command: "test".to_string(),
             args: vec![],
             env: HashMap::new(),
+            cwd: None,
         },
     );
 
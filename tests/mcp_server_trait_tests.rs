@@ -29,6 +29,7 @@ impl TestMcpServer {
             },
             required_config: vec![],
             optional_config: vec![],
+            args_template: vec![],
         };
 
         Self {
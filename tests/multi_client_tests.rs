@@ -79,6 +79,7 @@ fn test_multiple_client_installation() {
         command: "node".to_string(),
         args: vec!["server.js".to_string()],
         env: HashMap::new(),
+        cwd: None,
     };
 
     // Install to all clients
@@ -192,6 +193,7 @@ fn test_client_config_formats() {
                 command: "cmd".to_string(),
                 args: vec!["arg".to_string()],
                 env: HashMap::new(),
+                cwd: None,
             },
         )
         .unwrap();
@@ -208,6 +210,7 @@ fn test_client_config_formats() {
                 command: "cmd".to_string(),
                 args: vec!["arg".to_string()],
                 env: HashMap::new(),
+                cwd: None,
             },
         )
         .unwrap();
@@ -229,6 +232,7 @@ fn test_client_config_formats() {
                 command: "cmd".to_string(),
                 args: vec!["arg".to_string()],
                 env: HashMap::new(),
+                cwd: None,
             },
         )
         .unwrap();
@@ -357,6 +361,7 @@ fn test_client_with_env_vars() {
         command: "npx".to_string(),
         args: vec!["server".to_string()],
         env: env.clone(),
+        cwd: None,
     };
 
     // Test all clients handle env vars
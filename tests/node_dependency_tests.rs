@@ -226,6 +226,7 @@ fn test_dependency_status_all_variants() {
             DependencyStatus::Missing => {}
             DependencyStatus::VersionMismatch { .. } => {}
             DependencyStatus::ConfigurationRequired { .. } => {}
+            DependencyStatus::Unresponsive { .. } => {}
         }
     }
 }
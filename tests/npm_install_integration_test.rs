@@ -56,6 +56,10 @@ fn test_npm_server_install_flow() {
                     // Configuration required is also a valid scenario
                     assert!(check.install_instructions.is_none());
                 }
+                DependencyStatus::Unresponsive { .. } => {
+                    // A probe that timed out carries no install instructions
+                    assert!(check.install_instructions.is_none());
+                }
             }
         }
         Err(_) => {
@@ -219,6 +223,7 @@ fn test_server_config_generation() {
             "--stdio".to_string(),
         ],
         env: HashMap::new(),
+        cwd: None,
     };
 
     assert_eq!(server_config.command, "npx");
@@ -233,6 +238,7 @@ fn test_server_config_generation() {
         command: server_config.command,
         args: server_config.args,
         env,
+        cwd: None,
     };
 
     assert_eq!(server_config_with_env.env.len(), 2);
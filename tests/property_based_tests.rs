@@ -43,7 +43,9 @@ proptest! {
         // Every string should be classified as some server type
         match server_type {
             ServerType::Npm { .. } | ServerType::Python { .. } |
-            ServerType::Binary { .. } | ServerType::Docker { .. } => (),
+            ServerType::Binary { .. } | ServerType::Docker { .. } |
+            ServerType::Cargo { .. } | ServerType::Deno { .. } |
+            ServerType::Jar { .. } | ServerType::Dotnet { .. } => (),
         }
     }
 }
@@ -341,6 +343,11 @@ fn prop_server_detection_categories() {
         // Python scripts
         ("script.py", true),
         ("./path/to/script.py", true),
+        // JAR files
+        ("./connector-1.2.0.jar", true),
+        ("jar:/opt/mcp/server", true),
+        // .NET tools
+        ("dotnet:Contoso.Mcp.Server@1.2.0", true),
         // URLs
         ("https://example.com/binary", true),
         ("http://localhost:8080/download", true),
@@ -371,6 +378,22 @@ fn prop_server_detection_categories() {
                 // Docker images
                 spec.starts_with("docker:")
             }
+            ServerType::Cargo { .. } => {
+                // Rust crates
+                spec.starts_with("cargo:")
+            }
+            ServerType::Deno { .. } => {
+                // Deno scripts and jsr packages
+                spec.starts_with("deno:") || spec.starts_with("jsr:") || spec.ends_with(".ts")
+            }
+            ServerType::Jar { .. } => {
+                // Local JAR files
+                spec.starts_with("jar:") || spec.to_lowercase().ends_with(".jar")
+            }
+            ServerType::Dotnet { .. } => {
+                // .NET tools
+                spec.starts_with("dotnet:")
+            }
         };
 
         assert_eq!(valid, expected, "Failed for spec: {spec}");
@@ -0,0 +1,63 @@
+// Working directory wrappers live under XDG_DATA_HOME, which this test points at a
+// temporary directory. It is the only test in this binary so nothing else races it.
+
+use mcp_helper::client::working_dir::wrapper_path;
+use mcp_helper::client::{
+    CursorClient, HomeDirectoryProvider, McpClient, ServerConfig, VSCodeClient,
+};
+use mcp_helper::remote::RemoteServerConfig;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+struct TestHomeProvider {
+    home_path: PathBuf,
+}
+
+impl HomeDirectoryProvider for TestHomeProvider {
+    fn home_dir(&self) -> Option<PathBuf> {
+        Some(self.home_path.clone())
+    }
+}
+
+fn home(temp_dir: &TempDir) -> Box<TestHomeProvider> {
+    Box::new(TestHomeProvider {
+        home_path: temp_dir.path().to_path_buf(),
+    })
+}
+
+#[test]
+fn test_remote_servers_remove_stale_wrappers() {
+    let temp_dir = TempDir::new().unwrap();
+    std::env::set_var("XDG_DATA_HOME", temp_dir.path().join("data"));
+    let remote = RemoteServerConfig::new("https://api.example.com/mcp").unwrap();
+    let project = temp_dir.path().join("project");
+    fs::create_dir(&project).unwrap();
+
+    // Cursor has no working directory setting, so a stdio entry with one is wrapped
+    let cursor = CursorClient::new_with_provider(home(&temp_dir));
+    let config = ServerConfig {
+        command: "node".to_string(),
+        args: vec!["server.js".to_string()],
+        env: HashMap::new(),
+        cwd: Some(project.to_string_lossy().to_string()),
+    };
+    cursor.add_server("api", config).unwrap();
+    let wrapper = wrapper_path(cursor.name(), "api").unwrap();
+    assert!(wrapper.exists());
+
+    cursor.add_remote_server("api", &remote).unwrap();
+    assert!(!wrapper.exists());
+    assert!(cursor.list_remote_servers().unwrap().contains_key("api"));
+
+    // VS Code writes `cwd` natively, but a wrapper from an earlier entry is still removed
+    let vscode = VSCodeClient::new_with_provider(home(&temp_dir));
+    let wrapper = wrapper_path(vscode.name(), "api").unwrap();
+    fs::create_dir_all(wrapper.parent().unwrap()).unwrap();
+    fs::write(&wrapper, "#!/bin/sh\n").unwrap();
+
+    vscode.add_remote_server("api", &remote).unwrap();
+    assert!(!wrapper.exists());
+    assert!(vscode.list_remote_servers().unwrap().contains_key("api"));
+}
@@ -311,8 +311,7 @@ fn test_unix_specific_behavior() {
     // Unix-specific test
     let result = runner.get_unix_command(&PathBuf::from("test-server"), &[]);
 
-    if result.is_ok() {
-        let (cmd, _) = result.unwrap();
+    if let Ok((cmd, _)) = result {
         assert_eq!(cmd, "npx");
     }
 }
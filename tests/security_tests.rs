@@ -504,6 +504,18 @@ fn test_malicious_server_detection() {
                     println!("  WARNING: Docker image contains potential command injection!");
                 }
             }
+            ServerType::Cargo { crate_name, .. } => {
+                println!("  Detected as Cargo: {crate_name}");
+            }
+            ServerType::Deno { specifier } => {
+                println!("  Detected as Deno: {specifier}");
+            }
+            ServerType::Jar { path } => {
+                println!("  Detected as JAR: {path}");
+            }
+            ServerType::Dotnet { package, .. } => {
+                println!("  Detected as .NET tool: {package}");
+            }
         }
     }
 }
@@ -144,6 +144,108 @@ fn test_detect_docker_without_tag() {
     }
 }
 
+#[test]
+fn test_detect_cargo_crates() {
+    let cases = vec![
+        ("cargo:mcp-server-foo", "mcp-server-foo", None),
+        (
+            "cargo:mcp-server-foo@0.3.1",
+            "mcp-server-foo",
+            Some("0.3.1"),
+        ),
+    ];
+
+    for (input, expected_crate, expected_version) in cases {
+        match detect_server_type(input) {
+            ServerType::Cargo {
+                crate_name,
+                version,
+            } => {
+                assert_eq!(crate_name, expected_crate);
+                assert_eq!(version.as_deref(), expected_version);
+            }
+            other => panic!("Expected Cargo type for {input}, got: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_detect_deno_specifiers() {
+    let cases = vec![
+        ("jsr:@scope/notes", "jsr:@scope/notes"),
+        ("deno:npm:mcp-server", "npm:mcp-server"),
+        ("./servers/weather.ts", "./servers/weather.ts"),
+        (
+            "https://deno.land/x/mcp_server/main.ts",
+            "https://deno.land/x/mcp_server/main.ts",
+        ),
+    ];
+
+    for (input, expected_specifier) in cases {
+        match detect_server_type(input) {
+            ServerType::Deno { specifier } => assert_eq!(specifier, expected_specifier),
+            other => panic!("Expected Deno type for {input}, got: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_detect_dotnet_tools() {
+    let cases = vec![
+        (
+            "dotnet:Contoso.Mcp.Server@1.2.0",
+            "Contoso.Mcp.Server",
+            Some("1.2.0"),
+        ),
+        ("dotnet:mcp-weather", "mcp-weather", None),
+    ];
+
+    for (input, expected_package, expected_version) in cases {
+        match detect_server_type(input) {
+            ServerType::Dotnet { package, version } => {
+                assert_eq!(package, expected_package);
+                assert_eq!(version.as_deref(), expected_version);
+            }
+            other => panic!("Expected Dotnet type for {input}, got: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_detect_jar_files() {
+    let cases = vec![
+        (
+            "./connectors/jira-mcp-1.0.0.jar",
+            "./connectors/jira-mcp-1.0.0.jar",
+        ),
+        ("C:\\mcp\\Confluence.JAR", "C:\\mcp\\Confluence.JAR"),
+        ("jar:/opt/mcp/server-all", "/opt/mcp/server-all"),
+    ];
+
+    for (input, expected_path) in cases {
+        match detect_server_type(input) {
+            ServerType::Jar { path } => assert_eq!(path, expected_path),
+            other => panic!("Expected Jar type for {input}, got: {other:?}"),
+        }
+    }
+
+    // Remote JARs are downloads, not local files
+    assert!(matches!(
+        detect_server_type("https://example.com/server.jar"),
+        ServerType::Binary { .. }
+    ));
+}
+
+#[test]
+fn test_bare_script_names_stay_npm() {
+    for input in ["a.ts", "server.mts", "@scope/tools.tsx", "server.jar"] {
+        assert!(
+            matches!(detect_server_type(input), ServerType::Npm { .. }),
+            "{input} should be an npm package"
+        );
+    }
+}
+
 #[test]
 fn test_detect_binary_urls() {
     let cases = vec![
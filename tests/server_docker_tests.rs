@@ -290,9 +290,12 @@ fn test_config_field_types() {
     for field in &metadata.optional_config {
         match field.name.as_str() {
             "volumes" | "environment" | "ports" | "network" | "entrypoint" | "user"
-            | "restart_policy" | "memory_limit" | "cpu_limit" => {
+            | "restart_policy" | "memory_limit" => {
                 assert_eq!(field.field_type, ConfigFieldType::String);
             }
+            "cpu_limit" => {
+                assert_eq!(field.field_type, ConfigFieldType::Number);
+            }
             "working_dir" => {
                 assert_eq!(field.field_type, ConfigFieldType::Path);
             }
@@ -54,6 +54,7 @@ fn test_platform_support_custom() {
         windows: true,
         macos: true,
         linux: true,
+        cpu: Vec::new(),
         min_node_version: Some("18.0.0".to_string()),
         min_python_version: None,
     };
@@ -70,6 +71,8 @@ fn test_usage_example_creation() {
     let example = UsageExample {
         title: "Basic Usage".to_string(),
         description: Some("How to use the server with basic configuration".to_string()),
+        prompt: None,
+        tool: None,
         config: HashMap::from([
             ("api_key".to_string(), "your-api-key".to_string()),
             (
@@ -103,6 +106,7 @@ fn test_registry_entry_creation() {
         popularity_score: 95.0,
         last_updated: "2024-01-15".to_string(),
         verified: true,
+        platform_support: None,
     };
 
     assert_eq!(entry.name, "Filesystem Server");
@@ -187,6 +191,7 @@ fn test_metadata_with_full_config() {
             windows: true,
             macos: true,
             linux: true,
+            cpu: Vec::new(),
             min_node_version: Some("16.0.0".to_string()),
             min_python_version: None,
         },
@@ -194,6 +199,8 @@ fn test_metadata_with_full_config() {
             UsageExample {
                 title: "Production Setup".to_string(),
                 description: Some("Recommended production configuration".to_string()),
+                prompt: None,
+                tool: None,
                 config: HashMap::from([
                     ("api_key".to_string(), "prod-key-123".to_string()),
                     (
@@ -206,6 +213,8 @@ fn test_metadata_with_full_config() {
             UsageExample {
                 title: "Development Setup".to_string(),
                 description: Some("Configuration for development".to_string()),
+                prompt: None,
+                tool: None,
                 config: HashMap::from([
                     ("api_key".to_string(), "dev-key-456".to_string()),
                     (
@@ -255,6 +264,7 @@ fn test_registry_entry_categories() {
             popularity_score: 50.0,
             last_updated: "2024-01-01".to_string(),
             verified: false,
+            platform_support: None,
         };
 
         assert_eq!(entry.category, category);
@@ -279,6 +289,7 @@ fn test_registry_entry_popularity_scores() {
             popularity_score: score,
             last_updated: "2024-01-01".to_string(),
             verified: false,
+            platform_support: None,
         };
 
         assert_eq!(entry.popularity_score, score);
@@ -304,6 +315,20 @@ fn test_server_type_variations() {
             image: "docker-server".to_string(),
             tag: Some("latest".to_string()),
         },
+        ServerType::Cargo {
+            crate_name: "cargo-server".to_string(),
+            version: Some("0.1.0".to_string()),
+        },
+        ServerType::Deno {
+            specifier: "jsr:@scope/deno-server".to_string(),
+        },
+        ServerType::Jar {
+            path: "./jar-server.jar".to_string(),
+        },
+        ServerType::Dotnet {
+            package: "Dotnet.Server".to_string(),
+            version: None,
+        },
     ];
 
     for server_type in server_types {
@@ -330,6 +355,10 @@ fn test_server_type_variations() {
             ServerType::Binary { .. } => assert!(matches!(server_type, ServerType::Binary { .. })),
             ServerType::Python { .. } => assert!(matches!(server_type, ServerType::Python { .. })),
             ServerType::Docker { .. } => assert!(matches!(server_type, ServerType::Docker { .. })),
+            ServerType::Cargo { .. } => assert!(matches!(server_type, ServerType::Cargo { .. })),
+            ServerType::Deno { .. } => assert!(matches!(server_type, ServerType::Deno { .. })),
+            ServerType::Jar { .. } => assert!(matches!(server_type, ServerType::Jar { .. })),
+            ServerType::Dotnet { .. } => assert!(matches!(server_type, ServerType::Dotnet { .. })),
         }
     }
 }
@@ -339,6 +368,8 @@ fn test_usage_example_without_description() {
     let example = UsageExample {
         title: "Minimal Example".to_string(),
         description: None,
+        prompt: None,
+        tool: None,
         config: HashMap::new(),
     };
 
@@ -368,6 +399,7 @@ fn test_registry_entry_tags() {
         popularity_score: 80.0,
         last_updated: "2024-03-01".to_string(),
         verified: true,
+        platform_support: None,
     };
 
     assert_eq!(entry.tags.len(), 5);
@@ -394,6 +426,7 @@ fn test_platform_support_partial() {
         windows: false,
         macos: true,
         linux: true,
+        cpu: Vec::new(),
         min_node_version: Some("20.0.0".to_string()),
         min_python_version: Some("3.11".to_string()),
     };
@@ -451,6 +484,7 @@ fn test_registry_entry_dates() {
             popularity_score: 50.0,
             last_updated: date.to_string(),
             verified: false,
+            platform_support: None,
         };
 
         assert_eq!(entry.last_updated, date);
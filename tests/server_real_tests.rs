@@ -16,6 +16,7 @@ fn test_server_metadata_creation() {
         },
         required_config: vec![],
         optional_config: vec![],
+        args_template: vec![],
     };
 
     assert_eq!(metadata.name, "test-server");
@@ -48,6 +49,7 @@ fn test_server_metadata_with_config_fields() {
         },
         required_config: vec![required_field.clone()],
         optional_config: vec![optional_field.clone()],
+        args_template: vec![],
     };
 
     assert_eq!(metadata.required_config.len(), 1);
@@ -178,6 +180,7 @@ fn test_server_metadata_default() {
         },
         required_config: vec![],
         optional_config: vec![],
+        args_template: vec![],
     };
 
     assert!(metadata.description.is_none());
@@ -238,6 +241,7 @@ fn test_multiple_required_fields() {
         },
         required_config: fields,
         optional_config: vec![],
+        args_template: vec![],
     };
 
     assert_eq!(metadata.required_config.len(), 3);
@@ -337,6 +341,7 @@ fn test_server_metadata_mixed_config() {
                 default: Some("100".to_string()),
             },
         ],
+        args_template: vec![],
     };
 
     assert_eq!(metadata.required_config.len(), 1);
@@ -460,6 +465,7 @@ fn test_server_metadata_empty_configs() {
         },
         required_config: vec![],
         optional_config: vec![],
+        args_template: vec![],
     };
 
     assert!(metadata.required_config.is_empty());
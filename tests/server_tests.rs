@@ -101,6 +101,7 @@ fn test_server_metadata_creation() {
             default: None,
         }],
         optional_config: vec![],
+        args_template: vec![],
     };
 
     assert_eq!(metadata.name, "test-server");
@@ -111,7 +112,7 @@ fn test_server_metadata_creation() {
 
 #[test]
 fn test_config_field_types() {
-    let fields = vec![
+    let fields = [
         ConfigField {
             name: "string_field".to_string(),
             field_type: ConfigFieldType::String,